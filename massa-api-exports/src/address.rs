@@ -1,6 +1,6 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
-use massa_models::address::ExecutionAddressCycleInfo;
+use massa_models::address::{ExecutionAddressCycleInfo, IncomingAsyncMessage};
 use massa_models::endorsement::EndorsementId;
 use massa_models::operation::OperationId;
 use massa_models::slot::{IndexedSlot, Slot};
@@ -48,6 +48,10 @@ pub struct AddressInfo {
 
     /// cycle information
     pub cycle_infos: Vec<ExecutionAddressCycleInfo>,
+
+    /// asynchronous messages of the final pool targeting the address, from the highest priority
+    /// to the lowest, at most `MAX_INCOMING_ASYNC_MESSAGES_PER_ADDRESS`
+    pub incoming_async_messages: Vec<IncomingAsyncMessage>,
 }
 
 impl std::fmt::Display for AddressInfo {
@@ -94,6 +98,20 @@ impl std::fmt::Display for AddressInfo {
                 },
             )?;
         }
+        if !self.incoming_async_messages.is_empty() {
+            writeln!(f, "\tIncoming async messages:")?;
+            for message in &self.incoming_async_messages {
+                writeln!(
+                    f,
+                    "\t\tFrom {} calling {} with {} coins, valid from slot {} to slot {}",
+                    message.sender,
+                    message.function,
+                    message.coins,
+                    message.validity_start,
+                    message.validity_end,
+                )?;
+            }
+        }
         //writeln!(f, "\tProduced blocks: {}", self.created_blocks.iter().map(|id| id.to_string()).intersperse(", ".into()).collect())?;
         //writeln!(f, "\tProduced operations: {}", self.created_operations.iter().map(|id| id.to_string()).intersperse(", ".into()).collect())?;
         //writeln!(f, "\tProduced endorsements: {}", self.created_endorsements.iter().map(|id| id.to_string()).intersperse(", ".into()).collect())?;
@@ -1,6 +1,13 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
-use massa_models::{address::Address, block::Block, block_id::BlockId, slot::Slot};
+use massa_hash::Hash;
+use massa_models::{
+    address::Address,
+    block::{Block, BlockFinalityProof, BlockGraphFormat, SecureShareBlock},
+    block_header::SecuredHeader,
+    block_id::BlockId,
+    slot::Slot,
+};
 
 use serde::{Deserialize, Serialize};
 
@@ -88,3 +95,118 @@ impl std::fmt::Display for BlockSummary {
         Ok(())
     }
 }
+
+/// The blockclique (or final) block of a slot
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SlotBlock {
+    /// the slot of the block
+    pub slot: Slot,
+    /// block id
+    pub id: BlockId,
+    /// the block header, if requested
+    pub header: Option<SecuredHeader>,
+}
+
+impl std::fmt::Display for SlotBlock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Slot: {}", self.slot)?;
+        writeln!(f, "Block ID: {}", self.id)?;
+        if let Some(header) = &self.header {
+            writeln!(f, "Header: {}", header.content)?;
+        }
+        Ok(())
+    }
+}
+
+/// Part of the block graph to render, and the format of the rendering
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct BlockGraphExportRequest {
+    /// optional start slot (included)
+    pub start_slot: Option<Slot>,
+    /// optional end slot (excluded)
+    pub end_slot: Option<Slot>,
+    /// format of the rendering
+    pub format: BlockGraphFormat,
+}
+
+/// Data allowing an external verifier, like a bridge relayer, to check that a block is final
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FinalityProof {
+    /// block id
+    pub block_id: BlockId,
+    /// header and endorsements of the block, and headers of its descendants
+    pub graph_proof: BlockFinalityProof,
+    /// last final slot executed by the node, at or after the slot of the block
+    pub final_cursor: Slot,
+    /// fingerprint of the final state after the execution of `final_cursor`
+    pub final_state_fingerprint: Hash,
+}
+
+impl std::fmt::Display for FinalityProof {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let proof = &self.graph_proof;
+        writeln!(
+            f,
+            "Block {} at slot {} is final",
+            self.block_id, proof.header.content.slot
+        )?;
+        writeln!(f, "\tEndorsements: {}", proof.endorsements.len())?;
+        writeln!(
+            f,
+            "\tDescendants: {} of fitness {} (threshold {})",
+            proof.descendants.len(),
+            proof.descendants_fitness,
+            proof.fitness_threshold
+        )?;
+        for header in proof.descendants.iter() {
+            writeln!(f, "\t\t{} at slot {}", header.id, header.content.slot)?;
+        }
+        writeln!(
+            f,
+            "\tFinal state fingerprint at slot {}: {}",
+            self.final_cursor, self.final_state_fingerprint
+        )?;
+        Ok(())
+    }
+}
+
+/// filter used when subscribing to new blocks
+#[derive(Debug, Default, Deserialize, Clone, Serialize)]
+pub struct NewBlocksFilter {
+    /// optional block ids
+    pub block_ids: Option<Vec<BlockId>>,
+    /// optional block creator addresses
+    pub addresses: Option<Vec<Address>>,
+    /// optional start slot (included)
+    pub start_slot: Option<Slot>,
+    /// optional end slot (excluded)
+    pub end_slot: Option<Slot>,
+}
+
+impl NewBlocksFilter {
+    /// true if the block matches all the criteria of the filter
+    pub fn matches(&self, block: &SecureShareBlock) -> bool {
+        if let Some(block_ids) = &self.block_ids {
+            if !block_ids.contains(&block.id) {
+                return false;
+            }
+        }
+        if let Some(addresses) = &self.addresses {
+            if !addresses.contains(&block.content_creator_address) {
+                return false;
+            }
+        }
+        let slot = block.content.header.content.slot;
+        if let Some(start_slot) = self.start_slot {
+            if slot < start_slot {
+                return false;
+            }
+        }
+        if let Some(end_slot) = self.end_slot {
+            if slot >= end_slot {
+                return false;
+            }
+        }
+        true
+    }
+}
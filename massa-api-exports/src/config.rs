@@ -5,6 +5,8 @@ use massa_signature::KeyPair;
 use massa_time::MassaTime;
 use std::net::SocketAddr;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 
 use serde::Deserialize;
 
@@ -20,8 +22,8 @@ pub struct APIConfig {
     pub bind_public: SocketAddr,
     /// bind for the Massa API
     pub bind_api: SocketAddr,
-    /// max argument count
-    pub max_arguments: u64,
+    /// limits that can be changed while the node is running, shared by the clones of the config
+    pub limits: ApiLimits,
     /// openrpc specification path
     pub openrpc_spec_path: PathBuf,
     /// bootstrap whitelist path
@@ -40,8 +42,22 @@ pub struct APIConfig {
     pub max_log_length: u32,
     /// host filtering.
     pub allow_hosts: Vec<String>,
+    /// CORS policy of the public API.
+    pub cors_public: CorsConfig,
+    /// CORS policy of the private API.
+    pub cors_private: CorsConfig,
+    /// CORS policy of the Massa API (V2).
+    pub cors_api: CorsConfig,
+    /// IPC endpoint of the public API, if any.
+    pub ipc_public: Option<IpcConfig>,
+    /// IPC endpoint of the private API, if any.
+    pub ipc_private: Option<IpcConfig>,
+    /// IPC endpoint of the Massa API (V2), if any.
+    pub ipc_api: Option<IpcConfig>,
     /// batch request limit. 0 means disabled.
     pub batch_request_limit: u32,
+    /// max number of calls of a batch request executed concurrently (HTTP only). 1 executes them one by one.
+    pub batch_request_concurrency: usize,
     /// the interval at which `Ping` frames are submitted.
     pub ping_interval: MassaTime,
     /// whether to enable HTTP.
@@ -62,6 +78,8 @@ pub struct APIConfig {
     pub max_parameter_size: u32,
     /// max gas in a block
     pub max_gas_per_block: u64,
+    /// max number of operations in a block
+    pub max_operations_per_block: u32,
     /// base gas used by any operation
     pub base_operation_gas_cost: u64,
     /// Amount required for a SinglePass compilation (ExecuteSC operation)
@@ -82,4 +100,60 @@ pub struct APIConfig {
     pub chain_id: u64,
     /// minimal fees to include an operation in a block
     pub minimal_fees: Amount,
+    /// number of periods of history kept by the node in pruned mode,
+    /// none when it keeps its whole history
+    pub pruned_history_periods: Option<u64>,
+}
+
+/// Limits of the API that can be changed while the node is running
+#[derive(Debug, Deserialize, Clone)]
+#[serde(from = "u64")]
+pub struct ApiLimits {
+    /// max argument count
+    max_arguments: Arc<AtomicU64>,
+}
+
+impl ApiLimits {
+    /// Creates the limits with the given max argument count
+    pub fn new(max_arguments: u64) -> Self {
+        ApiLimits {
+            max_arguments: Arc::new(AtomicU64::new(max_arguments)),
+        }
+    }
+
+    /// Returns the max argument count
+    pub fn max_arguments(&self) -> u64 {
+        self.max_arguments.load(Ordering::Relaxed)
+    }
+
+    /// Changes the max argument count of the API servers sharing these limits
+    pub fn set_max_arguments(&self, max_arguments: u64) {
+        self.max_arguments.store(max_arguments, Ordering::Relaxed);
+    }
+}
+
+impl From<u64> for ApiLimits {
+    fn from(max_arguments: u64) -> Self {
+        ApiLimits::new(max_arguments)
+    }
+}
+
+/// CORS policy of an HTTP API
+#[derive(Debug, Deserialize, Clone)]
+pub struct CorsConfig {
+    /// origins allowed to make cross-origin requests. `*` allows any origin, an empty list none.
+    pub allowed_origins: Vec<String>,
+    /// methods allowed in cross-origin requests. `*` allows any method.
+    pub allowed_methods: Vec<String>,
+    /// headers allowed in cross-origin requests. `*` allows any header.
+    pub allowed_headers: Vec<String>,
+}
+
+/// Local IPC endpoint of an API: a Unix socket, or a named pipe on Windows
+#[derive(Debug, Deserialize, Clone)]
+pub struct IpcConfig {
+    /// path of the Unix socket, or name of the named pipe on Windows (ex: `\\.\pipe\massa`)
+    pub path: PathBuf,
+    /// file permissions of the Unix socket (ignored on Windows)
+    pub permissions: u32,
 }
@@ -47,6 +47,8 @@ pub enum ApiError {
     InternalServerError(String),
     /// Versioning Factory error: {0}
     FactoryError(#[from] FactoryError),
+    /// Pool error: {0}
+    PoolError(String),
 }
 
 impl From<ApiError> for ErrorObjectOwned {
@@ -70,6 +72,7 @@ impl From<ApiError> for ErrorObjectOwned {
             ApiError::MissingConfig(_) => -32018,
             ApiError::WrongAPI => -32019,
             ApiError::FactoryError(_) => -32020,
+            ApiError::PoolError(_) => -32021,
         };
 
         ErrorObject::owned(code, err.to_string(), None::<()>)
@@ -87,6 +87,30 @@ pub struct ReadOnlyCall {
     pub coins: Option<Amount>,
     /// fee
     pub fee: Option<Amount>,
+    /// temporary overrides of the state applied before the call, optional
+    pub state_overrides: Option<Vec<StateOverride>>,
+}
+
+/// temporary override of the state of an address, only applied for a read-only call
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub struct StateOverride {
+    /// overridden address, created if it does not exist
+    pub address: Address,
+    /// balance, optional
+    pub balance: Option<Amount>,
+    /// bytecode, optional
+    pub bytecode: Option<Vec<u8>>,
+    /// datastore entries, optional
+    pub datastore: Option<Vec<DatastoreEntryOverride>>,
+}
+
+/// override of a datastore entry
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub struct DatastoreEntryOverride {
+    /// datastore key
+    pub key: Vec<u8>,
+    /// value to set, the entry is deleted if absent
+    pub value: Option<Vec<u8>>,
 }
 
 /// Context of the transfer
@@ -121,3 +145,80 @@ pub struct Transfer {
     /// Context
     pub context: TransferContext,
 }
+
+/// Execution trace of an operation
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct OperationTrace {
+    /// The operation id
+    pub op_id: OperationId,
+    /// The slot at which the operation was executed
+    pub slot: Slot,
+    /// The block including the operation
+    pub block_id: BlockId,
+    /// If the execution succeed or not
+    pub succeed: bool,
+    /// The gas consumed by the smart contract execution of the operation
+    pub gas_used: u64,
+    /// The ABI calls made by the operation, in order
+    pub abi_calls: Vec<AbiCall>,
+    /// The datastore accesses made by the operation, in order
+    pub storage_accesses: Vec<StorageAccess>,
+    /// The transfers made by the operation
+    pub transfers: Vec<Transfer>,
+}
+
+/// ABI call made during an execution
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AbiCall {
+    /// The ABI name
+    pub name: String,
+    /// The parameters of the call
+    pub parameters: Vec<serde_json::Value>,
+    /// The returned value
+    pub return_value: serde_json::Value,
+    /// The ABI calls made during this call, in order
+    pub sub_calls: Vec<AbiCall>,
+}
+
+/// Access to a datastore entry made during an execution
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct StorageAccess {
+    /// The address owning the datastore
+    pub address: Address,
+    /// The datastore key
+    pub key: Vec<u8>,
+    /// The type of access
+    pub access_type: StorageAccessType,
+}
+
+/// Type of access to a datastore entry
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageAccessType {
+    /// The entry was read or checked for existence
+    Read,
+    /// The entry was set or appended to
+    Write,
+    /// The entry was deleted
+    Delete,
+}
+
+/// Gas charged for the ABI calls of executed slots
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GasProfile {
+    /// The number of executed slots of the range kept in the execution trace history
+    pub slot_count: u64,
+    /// The gas usage of each called ABI, from the most expensive one
+    pub abis: Vec<AbiGasUsage>,
+}
+
+/// Gas charged for the calls to an ABI
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AbiGasUsage {
+    /// The ABI name
+    pub name: String,
+    /// The number of calls
+    pub call_count: u64,
+    /// The gas charged for the calls, according to the ABI gas costs
+    pub gas: u64,
+}
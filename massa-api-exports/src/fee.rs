@@ -0,0 +1,47 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use massa_models::amount::Amount;
+use serde::{Deserialize, Serialize};
+
+/// Operation fees and fullness of the final blocks of a period
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PeriodFeeStats {
+    /// period
+    pub period: u64,
+    /// number of final blocks of the period still kept by the node
+    pub block_count: usize,
+    /// number of operations included in these blocks
+    pub operation_count: usize,
+    /// lowest operation fee, `None` if no operation was included
+    pub min_fee: Option<Amount>,
+    /// median operation fee, `None` if no operation was included
+    pub median_fee: Option<Amount>,
+    /// highest operation fee, `None` if no operation was included
+    pub max_fee: Option<Amount>,
+    /// average fullness of the blocks, between 0 and 1.
+    /// The fullness of a block is the highest of its operation count and gas usage ratios.
+    pub block_fullness: f64,
+}
+
+impl std::fmt::Display for PeriodFeeStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Period {}: {} block(s), {} operation(s), fullness {:.2}%",
+            self.period,
+            self.block_count,
+            self.operation_count,
+            self.block_fullness * 100.0
+        )?;
+        if let (Some(min_fee), Some(median_fee), Some(max_fee)) =
+            (self.min_fee, self.median_fee, self.max_fee)
+        {
+            writeln!(
+                f,
+                "\tFees: min {}, median {}, max {}",
+                min_fee, median_fee, max_fee
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -23,10 +23,14 @@ pub mod endorsement;
 pub mod error;
 /// execution
 pub mod execution;
+/// fee statistics
+pub mod fee;
 /// ledger structures
 pub mod ledger;
 /// node related structure
 pub mod node;
+/// node configuration inspection and reload
+pub mod node_config;
 /// operations
 pub mod operation;
 /// page
@@ -35,6 +39,8 @@ pub mod page;
 pub mod rolls;
 /// slots
 pub mod slot;
+/// disk usage of the node storage
+pub mod storage;
 
 /// Dumb utils function to display nicely boolean value
 fn display_if_true(value: bool, text: &str) -> String {
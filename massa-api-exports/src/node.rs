@@ -7,7 +7,7 @@ use massa_models::{config::CompactConfig, slot::Slot, version::Version};
 use massa_time::MassaTime;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
-use std::net::IpAddr;
+use std::net::{IpAddr, SocketAddr};
 
 /// node status
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -46,6 +46,9 @@ pub struct NodeStatus {
     pub chain_id: u64,
     /// minimal fees to include an operation in a block
     pub minimal_fees: Amount,
+    /// bounds of the history of final operations and events available on the node
+    #[serde(default)]
+    pub history: HistoryAvailability,
 }
 
 impl std::fmt::Display for NodeStatus {
@@ -81,6 +84,8 @@ impl std::fmt::Display for NodeStatus {
 
         writeln!(f, "{}", self.execution_stats)?;
 
+        writeln!(f, "{}", self.history)?;
+
         writeln!(f, "Connected nodes:")?;
         for (node_id, (ip_addr, is_outgoing)) in &self.connected_nodes {
             writeln!(
@@ -94,3 +99,123 @@ impl std::fmt::Display for NodeStatus {
         Ok(())
     }
 }
+
+/// history of final operations and events available on the node
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize)]
+pub struct HistoryAvailability {
+    /// whether the node runs in pruned mode, keeping only the final state and its recent history
+    pub pruned: bool,
+    /// first period whose final operations and events are still available,
+    /// none when the node keeps its whole history
+    pub first_available_period: Option<u64>,
+}
+
+impl std::fmt::Display for HistoryAvailability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.first_available_period {
+            Some(period) => writeln!(f, "History: pruned, available from period {}", period),
+            None => writeln!(f, "History: full"),
+        }
+    }
+}
+
+/// peer known by the node, with its reputation
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PeerInfo {
+    /// node id of the peer
+    pub node_id: NodeId,
+    /// ip address of the peer and whether the connection is outgoing, if it is connected
+    pub connection: Option<(IpAddr, bool)>,
+    /// addresses of the listeners announced by the peer, of both IP families
+    pub listeners: Vec<SocketAddr>,
+    /// current score of the peer, lowered by its misbehaviors and raised when it is useful
+    pub score: f64,
+    /// whether the peer is throttled because of its low score:
+    /// it is neither connected to nor advertised to the other peers
+    pub is_throttled: bool,
+    /// whether the peer is banned
+    pub is_banned: bool,
+    /// number of invalid messages received from the peer
+    pub invalid_message_count: u64,
+    /// number of our requests the peer did not answer in time
+    pub slow_response_count: u64,
+    /// number of unrequested messages received from the peer
+    pub spam_count: u64,
+    /// number of block headers first received from the peer
+    pub new_block_count: u64,
+    /// number of batches of new operations first received from the peer
+    pub new_operations_count: u64,
+}
+
+impl std::fmt::Display for PeerInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Node's ID: {}", self.node_id)?;
+        match self.connection {
+            Some((ip_addr, is_outgoing)) => writeln!(
+                f,
+                "\tIP address: {} / {} connection",
+                ip_addr,
+                if is_outgoing { "Out" } else { "In" }
+            )?,
+            None => writeln!(f, "\tNot connected")?,
+        }
+        if !self.listeners.is_empty() {
+            let listeners: Vec<String> = self.listeners.iter().map(|l| l.to_string()).collect();
+            writeln!(f, "\tListeners: {}", listeners.join(", "))?;
+        }
+        writeln!(f, "\tScore: {:.2}", self.score)?;
+        if self.is_banned {
+            writeln!(f, "\tBanned")?;
+        } else if self.is_throttled {
+            writeln!(f, "\tThrottled")?;
+        }
+        writeln!(
+            f,
+            "\tInvalid messages: {} / Slow responses: {} / Spam: {}",
+            self.invalid_message_count, self.slow_response_count, self.spam_count
+        )?;
+        writeln!(
+            f,
+            "\tNew blocks: {} / New operation batches: {}",
+            self.new_block_count, self.new_operations_count
+        )
+    }
+}
+
+/// bandwidth limits of the node, in bytes per second. A limit of 0 disables it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct BandwidthLimits {
+    /// max upload rate to each peer
+    pub peer_upload: u64,
+    /// max download rate from each peer
+    pub peer_download: u64,
+    /// max upload rate to all the peers
+    pub global_upload: u64,
+    /// max download rate from all the peers
+    pub global_download: u64,
+}
+
+impl std::fmt::Display for BandwidthLimits {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let display_limit = |limit: u64| {
+            if limit == 0 {
+                "unlimited".to_string()
+            } else {
+                format!("{} bytes/s", limit)
+            }
+        };
+        writeln!(f, "Bandwidth limits:")?;
+        writeln!(
+            f,
+            "\tPer peer: upload {} / download {}",
+            display_limit(self.peer_upload),
+            display_limit(self.peer_download)
+        )?;
+        writeln!(
+            f,
+            "\tGlobal: upload {} / download {}",
+            display_limit(self.global_upload),
+            display_limit(self.global_download)
+        )
+    }
+}
@@ -0,0 +1,56 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::error::ApiError;
+use serde::{Deserialize, Serialize};
+
+/// Outcome of a node configuration reload
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct NodeConfigReload {
+    /// changed settings that were applied to the running node
+    pub applied: Vec<String>,
+    /// changed settings that need a node restart to be taken into account
+    pub requires_restart: Vec<String>,
+}
+
+impl std::fmt::Display for NodeConfigReload {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.applied.is_empty() && self.requires_restart.is_empty() {
+            return writeln!(f, "No configuration change");
+        }
+        if !self.applied.is_empty() {
+            writeln!(f, "Applied settings:")?;
+            for setting in &self.applied {
+                writeln!(f, "\t{}", setting)?;
+            }
+        }
+        if !self.requires_restart.is_empty() {
+            writeln!(f, "Settings requiring a restart:")?;
+            for setting in &self.requires_restart {
+                writeln!(f, "\t{}", setting)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Access to the configuration of the running node
+pub trait NodeConfigController: Send + Sync {
+    /// Returns the effective node configuration, merged from all the configuration sources
+    fn get_node_config(&self) -> Result<serde_json::Value, ApiError>;
+
+    /// Re-reads the configuration sources and applies the settings that can be changed
+    /// without restarting the node (log level, pool sizes)
+    fn reload_node_config(&self) -> Result<NodeConfigReload, ApiError>;
+
+    /// Returns a boxed clone of self.
+    /// Useful to allow cloning `Box<dyn NodeConfigController>`.
+    fn clone_box(&self) -> Box<dyn NodeConfigController>;
+}
+
+/// Allow cloning `Box<dyn NodeConfigController>`
+/// Uses `NodeConfigController::clone_box` internally
+impl Clone for Box<dyn NodeConfigController> {
+    fn clone(&self) -> Box<dyn NodeConfigController> {
+        self.clone_box()
+    }
+}
@@ -1,12 +1,21 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use massa_final_state::StateChanges;
 use massa_models::{
+    address::Address,
+    amount::Amount,
     block_id::BlockId,
-    operation::{OperationId, SecureShareOperation},
+    operation::{
+        OperationId, OperationKind, OperationType, PoolOperationSummary, SecureShareOperation,
+    },
+    output_event::SCOutputEvent,
+    slot::Slot,
+    stats::OperationPoolStats,
 };
 
 use massa_signature::{PublicKey, Signature};
 use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
 
 use crate::{display_if_true, display_option_bool};
 
@@ -65,6 +74,179 @@ impl std::fmt::Display for OperationInfo {
     }
 }
 
+/// Final operation in which an address has a given role
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AddressOperation {
+    /// id
+    pub id: OperationId,
+    /// slot of the final block including the operation
+    pub slot: Slot,
+}
+
+impl std::fmt::Display for AddressOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Operation {} (slot {})", self.id, self.slot)
+    }
+}
+
+/// page of the operations of the pool, with the occupancy of the pool
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PoolOperations {
+    /// summaries of the operations of the page
+    pub operations: Vec<PoolOperationSummary>,
+    /// occupancy of the operation pool
+    pub stats: OperationPoolStats,
+}
+
+impl std::fmt::Display for PoolOperations {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.stats)?;
+        for operation in self.operations.iter() {
+            write!(f, "{}", operation)?;
+        }
+        Ok(())
+    }
+}
+
+/// operation to simulate
+#[derive(Serialize, Deserialize, Debug)]
+pub struct OperationSimulationInput {
+    /// The public key of the creator of the operation
+    pub creator_public_key: PublicKey,
+    /// The signature of the operation, verified if provided
+    pub signature: Option<Signature>,
+    /// The serialized version of the content `base58` encoded
+    pub serialized_content: Vec<u8>,
+}
+
+/// Status an operation would have if it was included in the next block of its thread
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub enum OperationSimulationStatus {
+    /// the operation would be executed successfully
+    Success,
+    /// the fee of the operation would be paid but its execution would fail
+    Failure,
+    /// the operation could not be included in a block, for the given reason
+    Rejected(String),
+}
+
+/// Result of the simulation of an operation
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct OperationSimulation {
+    /// id of the operation
+    pub id: OperationId,
+    /// slot at which the operation was simulated
+    pub executed_at: Slot,
+    /// status the operation would have
+    pub status: OperationSimulationStatus,
+    /// gas the operation would use in its block
+    pub gas_usage: u64,
+    /// events emitted by the operation
+    pub output_events: VecDeque<SCOutputEvent>,
+    /// state changes caused by the operation, including the fee payment
+    pub state_changes: StateChanges,
+}
+
+impl std::fmt::Display for OperationSimulation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Operation {} at slot {}", self.id, self.executed_at)?;
+        match &self.status {
+            OperationSimulationStatus::Success => writeln!(f, "Status: success")?,
+            OperationSimulationStatus::Failure => writeln!(f, "Status: execution failure")?,
+            OperationSimulationStatus::Rejected(reason) => {
+                writeln!(f, "Status: rejected ({})", reason)?
+            }
+        }
+        writeln!(f, "Gas usage: {}", self.gas_usage)?;
+        if !self.output_events.is_empty() {
+            writeln!(f, "Generated events:")?;
+            for event in self.output_events.iter() {
+                writeln!(f, "{}", event)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// filter used when subscribing to new operations
+#[derive(Debug, Default, Deserialize, Clone, Serialize)]
+pub struct NewOperationsFilter {
+    /// optional operation ids
+    pub operation_ids: Option<Vec<OperationId>>,
+    /// optional operation creator addresses
+    pub addresses: Option<Vec<Address>>,
+    /// optional operation kinds
+    pub operation_types: Option<Vec<OperationKind>>,
+    /// optional addresses receiving the coins or the call
+    pub recipient_addresses: Option<Vec<Address>>,
+    /// optional minimum transferred amount (included)
+    ///
+    /// operations that do not transfer coins never match an amount range
+    pub min_amount: Option<Amount>,
+    /// optional maximum transferred amount (included)
+    pub max_amount: Option<Amount>,
+    /// optional minimum fee (included)
+    pub min_fee: Option<Amount>,
+    /// optional maximum fee (included)
+    pub max_fee: Option<Amount>,
+}
+
+impl NewOperationsFilter {
+    /// true if the operation matches all the criteria of the filter
+    pub fn matches(&self, operation: &SecureShareOperation) -> bool {
+        if let Some(operation_ids) = &self.operation_ids {
+            if !operation_ids.contains(&operation.id) {
+                return false;
+            }
+        }
+        if let Some(addresses) = &self.addresses {
+            if !addresses.contains(&operation.content_creator_address) {
+                return false;
+            }
+        }
+        let op = &operation.content.op;
+        if let Some(operation_types) = &self.operation_types {
+            if !operation_types.contains(&OperationKind::from(op)) {
+                return false;
+            }
+        }
+        if let Some(recipient_addresses) = &self.recipient_addresses {
+            let recipient = match op {
+                OperationType::Transaction {
+                    recipient_address, ..
+                } => recipient_address,
+                OperationType::CallSC { target_addr, .. } => target_addr,
+                _ => return false,
+            };
+            if !recipient_addresses.contains(recipient) {
+                return false;
+            }
+        }
+        if self.min_amount.is_some() || self.max_amount.is_some() {
+            let amount = match op {
+                OperationType::Transaction { amount, .. } => *amount,
+                OperationType::CallSC { coins, .. } => *coins,
+                _ => return false,
+            };
+            if self.min_amount.map_or(false, |min| amount < min)
+                || self.max_amount.map_or(false, |max| amount > max)
+            {
+                return false;
+            }
+        }
+        if self
+            .min_fee
+            .map_or(false, |min| operation.content.fee < min)
+            || self
+                .max_fee
+                .map_or(false, |max| operation.content.fee > max)
+        {
+            return false;
+        }
+        true
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use jsonrpsee::core::__reexports::serde_json::{self, Value};
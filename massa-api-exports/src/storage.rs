@@ -0,0 +1,63 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use massa_time::MassaTime;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Disk usage of a subsystem of the node
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SubsystemStorageInfo {
+    /// name of the subsystem
+    pub name: String,
+    /// storage path of the subsystem
+    pub path: PathBuf,
+    /// disk usage in bytes
+    pub size: u64,
+}
+
+/// Disk usage of the node storage
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct StorageInfo {
+    /// disk usage of each subsystem storing data on the disk
+    pub subsystems: Vec<SubsystemStorageInfo>,
+    /// total disk usage in bytes
+    pub total_size: u64,
+    /// total disk usage above which warnings are logged (0 if disabled)
+    pub warning_threshold: u64,
+    /// total disk usage above which the writes to the operation and event indexes
+    /// are suspended (0 if disabled)
+    pub max_size: u64,
+    /// whether the writes to the operation and event indexes are suspended
+    pub index_writes_suspended: bool,
+    /// time of the last measure, None before the first one
+    pub last_check: Option<MassaTime>,
+}
+
+impl std::fmt::Display for StorageInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Some(last_check) = self.last_check else {
+            return writeln!(f, "Disk usage not measured yet");
+        };
+        writeln!(f, "Disk usage measured at {}:", last_check.format_instant())?;
+        for subsystem in &self.subsystems {
+            writeln!(
+                f,
+                "\t{}: {} bytes ({})",
+                subsystem.name,
+                subsystem.size,
+                subsystem.path.display()
+            )?;
+        }
+        writeln!(f, "Total: {} bytes", self.total_size)?;
+        if self.warning_threshold > 0 {
+            writeln!(f, "Warning threshold: {} bytes", self.warning_threshold)?;
+        }
+        if self.max_size > 0 {
+            writeln!(f, "Max size: {} bytes", self.max_size)?;
+        }
+        if self.index_writes_suspended {
+            writeln!(f, "Writes to the operation and event indexes suspended")?;
+        }
+        Ok(())
+    }
+}
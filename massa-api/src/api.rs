@@ -9,14 +9,17 @@ use futures::future::{self, Either};
 use futures::StreamExt;
 use jsonrpsee::core::{Error as JsonRpseeError, RpcResult, SubscriptionResult};
 use jsonrpsee::{PendingSubscriptionSink, SubscriptionMessage};
+use massa_api_exports::block::NewBlocksFilter;
 use massa_api_exports::config::APIConfig;
 use massa_api_exports::error::ApiError;
+use massa_api_exports::operation::NewOperationsFilter;
 use massa_api_exports::page::{PageRequest, PagedVec, PagedVecV2};
 use massa_api_exports::ApiRequest;
 use massa_consensus_exports::{ConsensusBroadcasts, ConsensusController};
-use massa_execution_exports::ExecutionController;
+use massa_execution_exports::{ExecutionChannels, ExecutionController, SlotExecutionOutput};
 use massa_models::address::Address;
 use massa_models::block_id::BlockId;
+use massa_models::execution::EventFilter;
 use massa_models::slot::Slot;
 use massa_models::timeslots::get_latest_block_slot_at_timestamp;
 use massa_models::version::Version;
@@ -31,6 +34,7 @@ impl API<ApiV2> {
         consensus_controller: Box<dyn ConsensusController>,
         consensus_broadcasts: ConsensusBroadcasts,
         execution_controller: Box<dyn ExecutionController>,
+        execution_channels: ExecutionChannels,
         pool_broadcasts: PoolBroadcasts,
         api_settings: APIConfig,
         version: Version,
@@ -39,6 +43,7 @@ impl API<ApiV2> {
             consensus_controller,
             consensus_broadcasts,
             execution_controller,
+            execution_channels,
             pool_broadcasts,
             api_settings,
             version,
@@ -53,7 +58,15 @@ impl ApiServer for API<ApiV2> {
         url: &SocketAddr,
         api_config: &APIConfig,
     ) -> Result<StopHandle, JsonRpseeError> {
-        crate::serve(self.into_rpc(), url, api_config).await
+        crate::serve(
+            self.into_rpc(),
+            crate::versioning::API_V2_NAMESPACE,
+            url,
+            api_config,
+            &api_config.cors_api,
+            api_config.ipc_api.as_ref(),
+        )
+        .await
     }
 }
 
@@ -117,8 +130,31 @@ impl MassaApiServer for API<ApiV2> {
         Ok(self.0.version)
     }
 
-    async fn subscribe_new_blocks(&self, pending: PendingSubscriptionSink) -> SubscriptionResult {
-        broadcast_via_ws(self.0.consensus_broadcasts.block_sender.clone(), pending).await
+    async fn subscribe_new_blocks(
+        &self,
+        pending: PendingSubscriptionSink,
+        filter: Option<NewBlocksFilter>,
+    ) -> SubscriptionResult {
+        let filter = filter.unwrap_or_default();
+        let api_settings = &self.0.api_settings;
+        let check = check_filter_len("block_ids", &filter.block_ids, api_settings)
+            .and(check_filter_len(
+                "addresses",
+                &filter.addresses,
+                api_settings,
+            ))
+            .and(check_slot_range(filter.start_slot, filter.end_slot));
+        if let Err(e) = check {
+            pending.reject(e).await;
+            return Ok(());
+        }
+
+        broadcast_via_ws(
+            self.0.consensus_broadcasts.block_sender.clone(),
+            pending,
+            move |block| filter.matches(&block).then_some(block),
+        )
+        .await
     }
 
     async fn subscribe_new_blocks_headers(
@@ -128,6 +164,7 @@ impl MassaApiServer for API<ApiV2> {
         broadcast_via_ws(
             self.0.consensus_broadcasts.block_header_sender.clone(),
             pending,
+            Some,
         )
         .await
     }
@@ -139,6 +176,7 @@ impl MassaApiServer for API<ApiV2> {
         broadcast_via_ws(
             self.0.consensus_broadcasts.filled_block_sender.clone(),
             pending,
+            Some,
         )
         .await
     }
@@ -146,16 +184,116 @@ impl MassaApiServer for API<ApiV2> {
     async fn subscribe_new_operations(
         &self,
         pending: PendingSubscriptionSink,
+        filter: Option<NewOperationsFilter>,
     ) -> SubscriptionResult {
-        broadcast_via_ws(self.0.pool_broadcasts.operation_sender.clone(), pending).await
+        let filter = filter.unwrap_or_default();
+        let api_settings = &self.0.api_settings;
+        let check = check_filter_len("operation_ids", &filter.operation_ids, api_settings)
+            .and(check_filter_len(
+                "addresses",
+                &filter.addresses,
+                api_settings,
+            ))
+            .and(check_filter_len(
+                "operation_types",
+                &filter.operation_types,
+                api_settings,
+            ))
+            .and(check_filter_len(
+                "recipient_addresses",
+                &filter.recipient_addresses,
+                api_settings,
+            ));
+        if let Err(e) = check {
+            pending.reject(e).await;
+            return Ok(());
+        }
+
+        broadcast_via_ws(
+            self.0.pool_broadcasts.operation_sender.clone(),
+            pending,
+            move |operation| filter.matches(&operation).then_some(operation),
+        )
+        .await
+    }
+
+    async fn subscribe_sc_events(
+        &self,
+        pending: PendingSubscriptionSink,
+        filter: Option<EventFilter>,
+    ) -> SubscriptionResult {
+        let filter = filter.unwrap_or_default();
+        if let Err(e) = check_slot_range(filter.start, filter.end) {
+            pending.reject(e).await;
+            return Ok(());
+        }
+
+        broadcast_via_ws(
+            self.0
+                .execution_channels
+                .slot_execution_output_sender
+                .clone(),
+            pending,
+            move |output| {
+                let events = match output {
+                    SlotExecutionOutput::ExecutedSlot(output) => output.events,
+                    SlotExecutionOutput::FinalizedSlot(output) => {
+                        // finalized outputs are broadcast before their events are marked as final
+                        let mut events = output.events;
+                        events.finalize();
+                        events
+                    }
+                };
+                events.get_filtered_sc_output_events(&filter)
+            },
+        )
+        .await
+    }
+}
+
+// Check that a subscription filter list does not exceed the max number of arguments
+fn check_filter_len<T>(
+    name: &str,
+    list: &Option<Vec<T>>,
+    api_settings: &APIConfig,
+) -> Result<(), ApiError> {
+    match list {
+        Some(list) if list.len() as u64 > api_settings.limits.max_arguments() => {
+            Err(ApiError::BadRequest(format!(
+                "too many {} in filter. Only a maximum of {} are accepted",
+                name,
+                api_settings.limits.max_arguments()
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+// Check that a subscription filter slot range is not empty
+fn check_slot_range(start: Option<Slot>, end: Option<Slot>) -> Result<(), ApiError> {
+    match (start, end) {
+        (Some(start), Some(end)) if start >= end => Err(ApiError::BadRequest(format!(
+            "invalid slot range: start slot {} is not lower than end slot {}",
+            start, end
+        ))),
+        _ => Ok(()),
     }
 }
 
-// Brodcast the stream(sender) content via a WebSocket
-async fn broadcast_via_ws<T: Serialize + Send + Clone + 'static>(
+// Brodcast the stream(sender) content via a WebSocket.
+// Each item is passed to `filter_map` and the resulting items are sent, if any.
+async fn broadcast_via_ws<T, I, F>(
     sender: tokio::sync::broadcast::Sender<T>,
     pending: PendingSubscriptionSink,
-) -> SubscriptionResult {
+    filter_map: F,
+) -> SubscriptionResult
+where
+    T: Send + Clone + 'static,
+    I: IntoIterator,
+    I::IntoIter: Send,
+    I::Item: Serialize + Send,
+    F: Fn(T) -> I + Send,
+{
     let sink = pending.accept().await?;
     let closed = sink.closed();
     let stream = BroadcastStream::new(sender.subscribe());
@@ -168,10 +306,12 @@ async fn broadcast_via_ws<T: Serialize + Send + Clone + 'static>(
 
             // received new item from the stream.
             Either::Right((Some(Ok(item)), c)) => {
-                let notif = SubscriptionMessage::from_json(&item)?;
+                for item in filter_map(item) {
+                    let notif = SubscriptionMessage::from_json(&item)?;
 
-                if sink.send(notif).await.is_err() {
-                    break Ok(());
+                    if sink.send(notif).await.is_err() {
+                        return Ok(());
+                    }
                 }
 
                 closed = c;
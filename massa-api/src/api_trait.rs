@@ -2,10 +2,13 @@
 //! Json RPC API for a massa-node
 use jsonrpsee::core::{RpcResult, SubscriptionResult};
 use jsonrpsee::proc_macros::rpc;
+use massa_api_exports::block::NewBlocksFilter;
+use massa_api_exports::operation::NewOperationsFilter;
 use massa_api_exports::page::PagedVecV2;
 use massa_api_exports::ApiRequest;
 use massa_models::address::Address;
 use massa_models::block_id::BlockId;
+use massa_models::execution::EventFilter;
 use massa_models::version::Version;
 
 /// Exposed API methods
@@ -26,13 +29,13 @@ pub trait MassaApi {
     #[method(name = "get_version")]
     async fn get_version(&self) -> RpcResult<Version>;
 
-    /// New produced block.
+    /// New produced block, optionally filtered.
     #[subscription(
 		name = "subscribe_new_blocks" => "new_blocks",
 		unsubscribe = "unsubscribe_new_blocks",
 		item = Block
 	)]
-    async fn subscribe_new_blocks(&self) -> SubscriptionResult;
+    async fn subscribe_new_blocks(&self, filter: Option<NewBlocksFilter>) -> SubscriptionResult;
 
     /// New produced blocks headers.
     #[subscription(
@@ -50,11 +53,22 @@ pub trait MassaApi {
 	)]
     async fn subscribe_new_filled_blocks(&self) -> SubscriptionResult;
 
-    /// New produced operations.
+    /// New produced operations, optionally filtered.
     #[subscription(
 		name = "subscribe_new_operations" => "new_operations",
 		unsubscribe = "unsubscribe_new_operations",
 		item = Operation
 	)]
-    async fn subscribe_new_operations(&self) -> SubscriptionResult;
+    async fn subscribe_new_operations(
+        &self,
+        filter: Option<NewOperationsFilter>,
+    ) -> SubscriptionResult;
+
+    /// New smart contract output events of the executed and finalized slots, optionally filtered.
+    #[subscription(
+		name = "subscribe_sc_events" => "sc_events",
+		unsubscribe = "unsubscribe_sc_events",
+		item = SCOutputEvent
+	)]
+    async fn subscribe_sc_events(&self, filter: Option<EventFilter>) -> SubscriptionResult;
 }
@@ -0,0 +1,229 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+//! Concurrent execution of the JSON-RPC batch requests
+use futures::future::BoxFuture;
+use futures::StreamExt;
+use hyper::body::HttpBody;
+use hyper::header::{HeaderValue, CONTENT_LENGTH, CONTENT_TYPE};
+use hyper::http::request::Parts;
+use hyper::{Body, Method, Request, Response, StatusCode};
+use massa_api_exports::config::APIConfig;
+use serde_json::Value;
+use std::task::{Context, Poll};
+use tower::{Layer, Service, ServiceExt};
+
+/// JSON-RPC error code of a response larger than the max response size (same as jsonrpsee)
+const OVERSIZED_RESPONSE_CODE: i32 = -32008;
+
+/// Limits applied to the batch requests
+#[derive(Debug, Clone, Copy)]
+struct BatchLimits {
+    /// max number of calls of a batch executed at the same time
+    concurrency: usize,
+    /// max number of calls in a batch (0 means that batches are disabled)
+    max_batch_size: usize,
+    /// max size in bytes of a request
+    max_request_body_size: usize,
+    /// max size in bytes of a response
+    max_response_body_size: usize,
+}
+
+/// Layer executing concurrently the calls of the JSON-RPC batch requests received over HTTP.
+///
+/// Each call of a batch is dispatched to the inner service as a single request, on its own task,
+/// with at most `batch_request_concurrency` calls of the batch running at the same time.
+/// The responses are returned in the order of the calls.
+/// The batches that are invalid or too large are forwarded as is to be rejected by the server,
+/// and the batches received over WebSocket are executed by the server.
+#[derive(Clone)]
+pub struct BatchConcurrencyLayer {
+    limits: BatchLimits,
+}
+
+impl BatchConcurrencyLayer {
+    /// Creates a new layer with the batch limits of the API configuration
+    pub fn new(api_config: &APIConfig) -> Self {
+        BatchConcurrencyLayer {
+            limits: BatchLimits {
+                concurrency: api_config.batch_request_concurrency,
+                max_batch_size: api_config.batch_request_limit as usize,
+                max_request_body_size: api_config.max_request_body_size as usize,
+                max_response_body_size: api_config.max_response_body_size as usize,
+            },
+        }
+    }
+}
+
+impl<S> Layer<S> for BatchConcurrencyLayer {
+    type Service = BatchConcurrencyService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        BatchConcurrencyService {
+            inner,
+            limits: self.limits,
+        }
+    }
+}
+
+/// Service dispatching the calls of the batch requests
+#[derive(Clone)]
+pub struct BatchConcurrencyService<S> {
+    inner: S,
+    limits: BatchLimits,
+}
+
+impl<S> Service<Request<Body>> for BatchConcurrencyService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    S::Error: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        // take the service that was driven to readiness and leave a clone in its place
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let limits = self.limits;
+
+        // only the requests with a known length within the limit are buffered
+        let within_size_limit = request
+            .body()
+            .size_hint()
+            .upper()
+            .map_or(false, |len| len <= limits.max_request_body_size as u64);
+        if limits.concurrency <= 1
+            || limits.max_batch_size == 0
+            || request.method() != Method::POST
+            || !within_size_limit
+        {
+            return Box::pin(inner.call(request));
+        }
+
+        Box::pin(async move {
+            let (parts, body) = request.into_parts();
+            let Ok(body) = hyper::body::to_bytes(body).await else {
+                return Ok(empty_response(StatusCode::BAD_REQUEST));
+            };
+            let Some(calls) = parse_batch(&body, limits.max_batch_size) else {
+                return inner
+                    .call(Request::from_parts(parts, Body::from(body)))
+                    .await;
+            };
+
+            let responses = futures::stream::iter(calls)
+                .map(|call| {
+                    let request = single_call_request(&parts, &call);
+                    tokio::spawn(inner.clone().oneshot(request))
+                })
+                .buffered(limits.concurrency)
+                .collect::<Vec<_>>()
+                .await;
+
+            let mut call_responses = Vec::with_capacity(responses.len());
+            // opening and closing brackets
+            let mut len = 2;
+            for response in responses {
+                let response = match response {
+                    Ok(Ok(response)) => response,
+                    Ok(Err(err)) => return Err(err),
+                    // the task panicked or was cancelled
+                    Err(_) => return Ok(empty_response(StatusCode::INTERNAL_SERVER_ERROR)),
+                };
+                if !response.status().is_success() {
+                    return Ok(response);
+                }
+                let Ok(body) = hyper::body::to_bytes(response.into_body()).await else {
+                    return Ok(empty_response(StatusCode::INTERNAL_SERVER_ERROR));
+                };
+                // notifications have no response
+                if body.is_empty() {
+                    continue;
+                }
+                // responses and separating comma
+                len += body.len() + 1;
+                if len > limits.max_response_body_size {
+                    return Ok(json_response(
+                        serde_json::json!({
+                            "jsonrpc": "2.0",
+                            "error": {
+                                "code": OVERSIZED_RESPONSE_CODE,
+                                "message": format!(
+                                    "Exceeded max limit of {}",
+                                    limits.max_response_body_size
+                                ),
+                            },
+                            "id": null,
+                        })
+                        .to_string()
+                        .into_bytes(),
+                    ));
+                }
+                call_responses.push(body);
+            }
+
+            // a batch of notifications has no response
+            if call_responses.is_empty() {
+                return Ok(empty_response(StatusCode::OK));
+            }
+            let mut batch_response = Vec::with_capacity(len);
+            batch_response.push(b'[');
+            for (index, call_response) in call_responses.iter().enumerate() {
+                if index > 0 {
+                    batch_response.push(b',');
+                }
+                batch_response.extend_from_slice(call_response);
+            }
+            batch_response.push(b']');
+            Ok(json_response(batch_response))
+        })
+    }
+}
+
+/// Returns the calls of the request if it is a batch to be dispatched.
+/// Malformed, empty and too large batches are left to the server to be rejected.
+fn parse_batch(body: &[u8], max_batch_size: usize) -> Option<Vec<Value>> {
+    let first = body.iter().find(|byte| !byte.is_ascii_whitespace())?;
+    if *first != b'[' {
+        return None;
+    }
+    match serde_json::from_slice::<Vec<Value>>(body) {
+        Ok(calls) if !calls.is_empty() && calls.len() <= max_batch_size => Some(calls),
+        _ => None,
+    }
+}
+
+/// Builds the request of a single call of a batch, with the headers of the batch request
+fn single_call_request(parts: &Parts, call: &Value) -> Request<Body> {
+    let body = call.to_string();
+    let len = body.len();
+    let mut request = Request::new(Body::from(body));
+    *request.method_mut() = parts.method.clone();
+    *request.uri_mut() = parts.uri.clone();
+    *request.version_mut() = parts.version;
+    *request.headers_mut() = parts.headers.clone();
+    request
+        .headers_mut()
+        .insert(CONTENT_LENGTH, HeaderValue::from(len));
+    request
+}
+
+fn json_response(body: Vec<u8>) -> Response<Body> {
+    let mut response = Response::new(Body::from(body));
+    response.headers_mut().insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static("application/json; charset=utf-8"),
+    );
+    response
+}
+
+pub(crate) fn empty_response(status: StatusCode) -> Response<Body> {
+    let mut response = Response::new(Body::empty());
+    *response.status_mut() = status;
+    response
+}
@@ -0,0 +1,143 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+//! Identification of the client of the JSON-RPC calls, to which their read-only executions are accounted
+use futures::future::BoxFuture;
+use hyper::{Body, Request, Response};
+use jsonrpsee::server::logger::{HttpRequest, Logger, MethodKind, TransportProtocol};
+use jsonrpsee::types::Params;
+use parking_lot::Mutex;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+
+/// Id shared by the calls whose client IP is not known, like the ones received over WebSocket
+const UNKNOWN_CALLER_ID: &str = "unknown";
+
+/// Address of the client of an HTTP request, reported by the server to `RemoteAddrLogger`
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RemoteAddrSlot(Arc<Mutex<Option<SocketAddr>>>);
+
+/// Transport of the JSON-RPC call being handled
+#[derive(Debug, Clone)]
+pub(crate) enum CallOrigin {
+    /// call received over HTTP
+    Http(RemoteAddrSlot),
+    /// call received over the local IPC transport
+    Ipc,
+}
+
+tokio::task_local! {
+    /// transport of the JSON-RPC call handled by the current task
+    pub(crate) static CALL_ORIGIN: CallOrigin;
+}
+
+/// Get the id to which the read-only executions of the current call are accounted:
+/// the IP of the client, or `local_id` for the calls received over the local IPC transport.
+/// The calls whose client IP is not known share a single id.
+pub(crate) fn get_caller_id(local_id: impl FnOnce() -> String) -> String {
+    let remote_ip = match CALL_ORIGIN.try_with(|origin| origin.clone()) {
+        Ok(CallOrigin::Ipc) => return local_id(),
+        Ok(CallOrigin::Http(slot)) => *slot.0.lock(),
+        Err(_) => None,
+    };
+    match remote_ip.map(|remote_addr| remote_addr.ip()) {
+        // the IPv4 clients of a dual-stack server have an IPv4-mapped address
+        Some(IpAddr::V6(ip)) => match ip.to_ipv4_mapped() {
+            Some(ip) => ip.to_string(),
+            None => ip.to_string(),
+        },
+        Some(ip) => ip.to_string(),
+        None => UNKNOWN_CALLER_ID.to_string(),
+    }
+}
+
+/// Layer handling each HTTP request with its `CALL_ORIGIN`.
+///
+/// The address of the client is only known by the server, that reports it to `RemoteAddrLogger`
+/// when it receives the request: this layer must be the innermost one, so that each request
+/// reaches the server with its own `RemoteAddrSlot`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CallOriginLayer;
+
+impl<S> Layer<S> for CallOriginLayer {
+    type Service = CallOriginService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        CallOriginService { inner }
+    }
+}
+
+/// Service setting the `CALL_ORIGIN` of the HTTP requests
+#[derive(Debug, Clone)]
+pub struct CallOriginService<S> {
+    inner: S,
+}
+
+impl<S> Service<Request<Body>> for CallOriginService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>>,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<Body>) -> Self::Future {
+        let slot = RemoteAddrSlot::default();
+        request.extensions_mut().insert(slot.clone());
+        Box::pin(CALL_ORIGIN.scope(CallOrigin::Http(slot), self.inner.call(request)))
+    }
+}
+
+/// Logger of the server filling the `RemoteAddrSlot` of the requests with the address of their client
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RemoteAddrLogger;
+
+impl Logger for RemoteAddrLogger {
+    type Instant = ();
+
+    fn on_connect(
+        &self,
+        remote_addr: SocketAddr,
+        request: &HttpRequest,
+        _transport: TransportProtocol,
+    ) {
+        if let Some(slot) = request.extensions().get::<RemoteAddrSlot>() {
+            *slot.0.lock() = Some(remote_addr);
+        }
+    }
+
+    fn on_request(&self, _transport: TransportProtocol) -> Self::Instant {}
+
+    fn on_call(
+        &self,
+        _method_name: &str,
+        _params: Params,
+        _kind: MethodKind,
+        _transport: TransportProtocol,
+    ) {
+    }
+
+    fn on_result(
+        &self,
+        _method_name: &str,
+        _success: bool,
+        _started_at: Self::Instant,
+        _transport: TransportProtocol,
+    ) {
+    }
+
+    fn on_response(
+        &self,
+        _result: &str,
+        _started_at: Self::Instant,
+        _transport: TransportProtocol,
+    ) {
+    }
+
+    fn on_disconnect(&self, _remote_addr: SocketAddr, _transport: TransportProtocol) {}
+}
@@ -0,0 +1,414 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+//! JSON-RPC over a local IPC transport: a Unix socket, or a named pipe on Windows.
+//!
+//! Requests and responses are JSON texts delimited by newlines.
+//! The notifications of a subscription are sent on the connection it was made on.
+use crate::caller::{CallOrigin, CALL_ORIGIN};
+use crate::versioning::{annotate_response, deprecated_calls, DEPRECATED_METHODS};
+use jsonrpsee::core::Error as JsonRpseeError;
+use jsonrpsee::Methods;
+use massa_api_exports::config::{APIConfig, IpcConfig};
+use serde_json::Value;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::{mpsc, watch, Semaphore};
+use tracing::{info, warn};
+
+/// JSON-RPC error codes (same as jsonrpsee)
+const PARSE_ERROR_CODE: i32 = -32700;
+const INVALID_REQUEST_CODE: i32 = -32600;
+const OVERSIZED_REQUEST_CODE: i32 = -32007;
+const BATCHES_NOT_SUPPORTED_CODE: i32 = -32005;
+const TOO_BIG_BATCH_REQUEST_CODE: i32 = -32010;
+
+/// Max number of messages waiting to be written on a connection
+const CONNECTION_BUFFER_SIZE: usize = 1024;
+
+/// Limits applied to the IPC connections
+#[derive(Debug, Clone, Copy)]
+struct IpcLimits {
+    /// max size in bytes of a request
+    max_request_body_size: usize,
+    /// max number of calls in a batch (0 means that batches are disabled)
+    max_batch_size: usize,
+}
+
+/// Starts serving the methods on the IPC endpoint, until `stop` is notified
+pub(crate) fn start_ipc_server(
+    methods: Methods,
+    ipc_config: &IpcConfig,
+    api_config: &APIConfig,
+    stop: watch::Receiver<bool>,
+) -> Result<(), JsonRpseeError> {
+    let limits = IpcLimits {
+        max_request_body_size: api_config.max_request_body_size as usize,
+        max_batch_size: api_config.batch_request_limit as usize,
+    };
+    let connection_permits = Arc::new(Semaphore::new(api_config.max_connections as usize));
+
+    #[cfg(unix)]
+    {
+        let path = ipc_config.path.clone();
+        let listener = bind_unix_socket(&path, ipc_config.permissions)?;
+        info!("API | listening on IPC socket: {}", path.display());
+        let mut stop_listener = stop.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    accepted = listener.accept() => match accepted {
+                        Ok((stream, _)) => spawn_connection(
+                            stream,
+                            methods.clone(),
+                            limits,
+                            &connection_permits,
+                            stop.clone(),
+                        ),
+                        Err(e) => warn!("failed to accept IPC connection: {}", e),
+                    },
+                    _ = stop_listener.changed() => break,
+                }
+            }
+            let _ = std::fs::remove_file(&path);
+        });
+    }
+
+    #[cfg(windows)]
+    {
+        use tokio::net::windows::named_pipe::ServerOptions;
+
+        let name = ipc_config.path.clone();
+        let mut server = ServerOptions::new()
+            .first_pipe_instance(true)
+            .create(&name)
+            .map_err(|e| {
+                JsonRpseeError::Custom(format!(
+                    "failed to create named pipe {}: {}",
+                    name.display(),
+                    e
+                ))
+            })?;
+        info!("API | listening on IPC named pipe: {}", name.display());
+        let mut stop_listener = stop.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    connected = server.connect() => {
+                        if let Err(e) = connected {
+                            warn!("failed to accept IPC connection: {}", e);
+                        }
+                        // a new pipe instance waits for the next client
+                        let next_server = match ServerOptions::new().create(&name) {
+                            Ok(next_server) => next_server,
+                            Err(e) => {
+                                warn!("failed to create named pipe {}: {}", name.display(), e);
+                                break;
+                            }
+                        };
+                        let stream = std::mem::replace(&mut server, next_server);
+                        spawn_connection(
+                            stream,
+                            methods.clone(),
+                            limits,
+                            &connection_permits,
+                            stop.clone(),
+                        );
+                    },
+                    _ = stop_listener.changed() => break,
+                }
+            }
+        });
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = (methods, ipc_config, limits, connection_permits, stop);
+        warn!("API IPC transport is only available on Unix and Windows systems, ignoring it");
+    }
+
+    Ok(())
+}
+
+// Bind a Unix socket listener at `path`, restricting its access with the given file permissions
+#[cfg(unix)]
+fn bind_unix_socket(
+    path: &std::path::Path,
+    permissions: u32,
+) -> Result<tokio::net::UnixListener, JsonRpseeError> {
+    use std::os::unix::fs::PermissionsExt;
+
+    // remove the socket left by a previous run
+    if path.exists() {
+        std::fs::remove_file(path).map_err(|e| {
+            JsonRpseeError::Custom(format!(
+                "failed to remove existing IPC socket {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+    }
+    let listener = tokio::net::UnixListener::bind(path).map_err(|e| {
+        JsonRpseeError::Custom(format!(
+            "failed to bind IPC socket {}: {}",
+            path.display(),
+            e
+        ))
+    })?;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(permissions)).map_err(|e| {
+        JsonRpseeError::Custom(format!(
+            "failed to set permissions of IPC socket {}: {}",
+            path.display(),
+            e
+        ))
+    })?;
+    Ok(listener)
+}
+
+/// Serves a new connection on its own task, if the max number of connections is not reached
+fn spawn_connection<S>(
+    stream: S,
+    methods: Methods,
+    limits: IpcLimits,
+    connection_permits: &Arc<Semaphore>,
+    stop: watch::Receiver<bool>,
+) where
+    S: AsyncRead + AsyncWrite + Send + 'static,
+{
+    let Ok(permit) = connection_permits.clone().try_acquire_owned() else {
+        warn!("too many IPC connections, closing the new one");
+        return;
+    };
+    tokio::spawn(async move {
+        serve_connection(stream, methods, limits, stop).await;
+        drop(permit);
+    });
+}
+
+/// Reads the requests of a connection and writes back their responses,
+/// until the connection is closed or the server stopped
+async fn serve_connection<S>(
+    stream: S,
+    methods: Methods,
+    limits: IpcLimits,
+    mut stop: watch::Receiver<bool>,
+) where
+    S: AsyncRead + AsyncWrite + Send + 'static,
+{
+    let (reader, mut writer) = tokio::io::split(stream);
+    let (sender, mut receiver) = mpsc::channel::<String>(CONNECTION_BUFFER_SIZE);
+    // closes the subscriptions of the connection when dropped
+    let (closed_sender, closed) = watch::channel(false);
+
+    // write the responses and notifications in the order they are produced
+    let write_task = tokio::spawn(async move {
+        while let Some(message) = receiver.recv().await {
+            if writer.write_all(message.as_bytes()).await.is_err()
+                || writer.write_all(b"\n").await.is_err()
+                || writer.flush().await.is_err()
+            {
+                break;
+            }
+        }
+    });
+
+    let mut reader = BufReader::new(reader);
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        // read one more byte than the limit to detect the oversized requests
+        let mut limited_reader = (&mut reader).take(limits.max_request_body_size as u64 + 1);
+        let read = tokio::select! {
+            read = limited_reader.read_until(b'\n', &mut line) => read,
+            _ = stop.changed() => break,
+        };
+        match read {
+            // connection closed
+            Ok(0) | Err(_) => break,
+            Ok(_) => {}
+        }
+        if line.len() > limits.max_request_body_size && line.last() != Some(&b'\n') {
+            let _ = sender
+                .send(error_response(
+                    OVERSIZED_REQUEST_CODE,
+                    &format!(
+                        "Request is too big, max size is {} bytes",
+                        limits.max_request_body_size
+                    ),
+                ))
+                .await;
+            break;
+        }
+        let Ok(request) = std::str::from_utf8(&line) else {
+            if sender
+                .send(error_response(PARSE_ERROR_CODE, "Parse error"))
+                .await
+                .is_err()
+            {
+                break;
+            }
+            continue;
+        };
+        let request = request.trim();
+        if request.is_empty() {
+            continue;
+        }
+
+        let (response, subscriptions) = process_request(&methods, request, limits).await;
+        if let Some(response) = response {
+            if sender.send(response).await.is_err() {
+                break;
+            }
+        }
+        // forwarded once the response is sent, for the notifications to follow it
+        for notifications in subscriptions {
+            tokio::spawn(forward_notifications(
+                notifications,
+                sender.clone(),
+                closed.clone(),
+            ));
+        }
+    }
+
+    // let the pending responses be written before closing the connection
+    drop(closed_sender);
+    drop(sender);
+    let _ = write_task.await;
+}
+
+/// Processes a single request or a batch.
+/// Returns the response, if any, and the notification receivers of the calls.
+async fn process_request(
+    methods: &Methods,
+    request: &str,
+    limits: IpcLimits,
+) -> (Option<String>, Vec<mpsc::Receiver<String>>) {
+    let calls = match serde_json::from_str::<Value>(request) {
+        Ok(Value::Array(calls)) => calls,
+        Ok(call) => {
+            let (response, notifications) = process_call(methods, call).await;
+            return (response, notifications.into_iter().collect());
+        }
+        Err(_) => {
+            return (
+                Some(error_response(PARSE_ERROR_CODE, "Parse error")),
+                vec![],
+            )
+        }
+    };
+
+    if limits.max_batch_size == 0 {
+        return (
+            Some(error_response(
+                BATCHES_NOT_SUPPORTED_CODE,
+                "Batched requests are not supported by this server",
+            )),
+            vec![],
+        );
+    }
+    if calls.is_empty() {
+        return (
+            Some(error_response(INVALID_REQUEST_CODE, "Invalid request")),
+            vec![],
+        );
+    }
+    if calls.len() > limits.max_batch_size {
+        return (
+            Some(error_response(
+                TOO_BIG_BATCH_REQUEST_CODE,
+                &format!(
+                    "The batch request was too large, max size is {}",
+                    limits.max_batch_size
+                ),
+            )),
+            vec![],
+        );
+    }
+
+    let mut responses = Vec::with_capacity(calls.len());
+    let mut subscriptions = Vec::new();
+    for call in calls {
+        let (response, notifications) = process_call(methods, call).await;
+        responses.extend(response);
+        subscriptions.extend(notifications);
+    }
+    // a batch of notifications has no response
+    let response = if responses.is_empty() {
+        None
+    } else {
+        Some(format!("[{}]", responses.join(",")))
+    };
+    (response, subscriptions)
+}
+
+/// Processes a single call.
+/// Returns the response, if any, and the receiver of the notifications of the call.
+async fn process_call(
+    methods: &Methods,
+    call: Value,
+) -> (Option<String>, Option<mpsc::Receiver<String>>) {
+    // JSON-RPC notifications have no response
+    let is_notification = call
+        .as_object()
+        .map_or(false, |call| !call.contains_key("id"));
+    let result = CALL_ORIGIN
+        .scope(
+            CallOrigin::Ipc,
+            methods.raw_json_request(&call.to_string(), CONNECTION_BUFFER_SIZE),
+        )
+        .await;
+    match result {
+        Ok((_, notifications)) if is_notification => (None, Some(notifications)),
+        Ok((response, notifications)) => {
+            let deprecated_calls = deprecated_calls(DEPRECATED_METHODS, &call);
+            if deprecated_calls.is_empty() {
+                return (Some(response.result), Some(notifications));
+            }
+            let response = match serde_json::from_str::<Value>(&response.result) {
+                Ok(mut response) => {
+                    annotate_response(&mut response, &deprecated_calls);
+                    response.to_string()
+                }
+                Err(_) => response.result,
+            };
+            (Some(response), Some(notifications))
+        }
+        Err(_) if is_notification => (None, None),
+        Err(_) => (
+            Some(error_response(INVALID_REQUEST_CODE, "Invalid request")),
+            None,
+        ),
+    }
+}
+
+/// Sends the notifications of a subscription on the connection, until it is closed
+async fn forward_notifications(
+    mut notifications: mpsc::Receiver<String>,
+    sender: mpsc::Sender<String>,
+    mut closed: watch::Receiver<bool>,
+) {
+    loop {
+        tokio::select! {
+            notification = notifications.recv() => match notification {
+                Some(notification) => {
+                    if sender.send(notification).await.is_err() {
+                        break;
+                    }
+                }
+                // the subscription ended
+                None => break,
+            },
+            _ = closed.changed() => break,
+        }
+    }
+}
+
+fn error_response(code: i32, message: &str) -> String {
+    serde_json::json!({
+        "jsonrpc": "2.0",
+        "error": {
+            "code": code,
+            "message": message,
+        },
+        "id": null,
+    })
+    .to_string()
+}
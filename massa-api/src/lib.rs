@@ -5,55 +5,78 @@
 #![warn(unused_crate_dependencies)]
 
 use api_trait::MassaApiServer;
+use batch::BatchConcurrencyLayer;
+use caller::{CallOriginLayer, RemoteAddrLogger};
+use hyper::header::{HeaderName, HeaderValue};
 use hyper::Method;
+use ipc::start_ipc_server;
 use jsonrpsee::core::{Error as JsonRpseeError, RpcResult};
 use jsonrpsee::proc_macros::rpc;
 use jsonrpsee::server::middleware::HostFilterLayer;
 use jsonrpsee::server::{BatchRequestConfig, ServerBuilder, ServerHandle};
-use jsonrpsee::RpcModule;
-use massa_api_exports::execution::Transfer;
+use jsonrpsee::{Methods, RpcModule};
+use massa_api_exports::execution::{GasProfile, OperationTrace, Transfer};
 use massa_api_exports::{
     address::{AddressFilter, AddressInfo},
-    block::{BlockInfo, BlockSummary},
-    config::APIConfig,
+    block::{BlockGraphExportRequest, BlockInfo, BlockSummary, FinalityProof, SlotBlock},
+    config::{APIConfig, CorsConfig, IpcConfig},
     datastore::{DatastoreEntryInput, DatastoreEntryOutput},
     endorsement::EndorsementInfo,
     error::ApiError::WrongAPI,
     execution::{ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall},
-    node::NodeStatus,
-    operation::{OperationInfo, OperationInput},
+    fee::PeriodFeeStats,
+    node::{BandwidthLimits, NodeStatus, PeerInfo},
+    node_config::{NodeConfigController, NodeConfigReload},
+    operation::{
+        AddressOperation, OperationInfo, OperationInput, OperationSimulation,
+        OperationSimulationInput, PoolOperations,
+    },
     page::{PageRequest, PagedVec},
+    storage::StorageInfo,
     TimeInterval,
 };
 use massa_consensus_exports::{ConsensusBroadcasts, ConsensusController};
-use massa_execution_exports::ExecutionController;
-use massa_models::clique::Clique;
+use massa_execution_exports::{ExecutionChannels, ExecutionController};
+use massa_models::clique::{Clique, CliqueDetails};
 use massa_models::composite::PubkeySig;
 use massa_models::node::NodeId;
-use massa_models::operation::OperationId;
+use massa_models::operation::{OperationAddressRole, OperationId, PoolOperationSort};
 use massa_models::output_event::SCOutputEvent;
 use massa_models::prehash::PreHashSet;
+use massa_models::stats::{CycleStaleBlockStats, PoolStats};
 use massa_models::{
-    address::Address, block::Block, block_id::BlockId, endorsement::EndorsementId,
-    execution::EventFilter, slot::Slot, version::Version,
+    address::Address,
+    block::{Block, DiscardedBlock},
+    block_id::BlockId,
+    endorsement::EndorsementId,
+    execution::{AsyncMessageFeeSuggestion, AsyncMessageFilter, EventFilter, PendingAsyncMessage},
+    slot::Slot,
+    version::Version,
 };
 use massa_pool_exports::{PoolBroadcasts, PoolController};
 use massa_pos_exports::SelectorController;
-use massa_protocol_exports::{ProtocolConfig, ProtocolController};
+use massa_protocol_exports::{IpCidr, ProtocolConfig, ProtocolController};
 use massa_storage::Storage;
+use massa_time::MassaTime;
 use massa_versioning::keypair_factory::KeyPairFactory;
 use massa_wallet::Wallet;
 use parking_lot::RwLock;
 use serde_json::Value;
 use std::net::{IpAddr, SocketAddr};
+use std::str::FromStr;
 use std::sync::{Arc, Condvar, Mutex};
 use tower_http::cors::{Any, CorsLayer};
 use tracing::{info, warn};
+use versioning::{register_namespace, DeprecationLayer, DEPRECATED_METHODS};
 
 mod api;
 mod api_trait;
+mod batch;
+mod caller;
+mod ipc;
 mod private;
 mod public;
+mod versioning;
 
 #[cfg(test)]
 mod tests;
@@ -97,6 +120,15 @@ pub struct Private {
     pub stop_cv: Arc<(Mutex<bool>, Condvar)>,
     /// User wallet
     pub node_wallet: Arc<RwLock<Wallet>>,
+    /// link to the node configuration
+    pub node_config_controller: Box<dyn NodeConfigController>,
+    /// disk usage of the node storage, measured periodically
+    pub storage_info: Arc<RwLock<StorageInfo>>,
+    /// link to the consensus component
+    pub consensus_controller: Box<dyn ConsensusController>,
+    /// time given to the in-flight requests to finish when the node stops,
+    /// overridden by the `shutdown` method
+    pub shutdown_grace_period: Arc<RwLock<MassaTime>>,
 }
 
 /// API v2 content
@@ -107,6 +139,8 @@ pub struct ApiV2 {
     pub consensus_broadcasts: ConsensusBroadcasts,
     /// link to the execution component
     pub execution_controller: Box<dyn ExecutionController>,
+    /// channels with informations broadcasted by the execution
+    pub execution_channels: ExecutionChannels,
     /// channels with informations broadcasted by the pool
     pub pool_broadcasts: PoolBroadcasts,
     /// API settings
@@ -141,10 +175,15 @@ pub trait ApiServer: MassaApiServer {
 }
 
 async fn serve<T>(
-    api: RpcModule<T>,
+    mut api: RpcModule<T>,
+    namespace: &str,
     url: &SocketAddr,
     api_config: &APIConfig,
+    cors_config: &CorsConfig,
+    ipc_config: Option<&IpcConfig>,
 ) -> Result<StopHandle, JsonRpseeError> {
+    register_namespace(&mut api, namespace)?;
+
     let mut server_builder = ServerBuilder::new()
         .max_request_body_size(api_config.max_request_body_size)
         .max_response_body_size(api_config.max_response_body_size)
@@ -164,12 +203,7 @@ async fn serve<T>(
         panic!("wrong server configuration, you can't disable both http and ws");
     }
 
-    let cors = CorsLayer::new()
-        // Allow `POST` and `OPTIONS` when accessing the resource
-        .allow_methods([Method::POST, Method::OPTIONS])
-        // Allow requests from any origin
-        .allow_origin(Any)
-        .allow_headers([hyper::header::CONTENT_TYPE]);
+    let cors = cors_layer(cors_config)?;
 
     let hosts = if api_config.allow_hosts.is_empty() {
         vec!["*:*"]
@@ -185,28 +219,112 @@ async fn serve<T>(
 
     let middleware = tower::ServiceBuilder::new()
         .layer(cors)
-        .layer(allowed_hosts);
+        .layer(allowed_hosts)
+        .layer(BatchConcurrencyLayer::new(api_config))
+        .layer(DeprecationLayer::new(DEPRECATED_METHODS, api_config))
+        .layer(CallOriginLayer);
 
     let server = server_builder
+        .set_logger(RemoteAddrLogger)
         .set_middleware(middleware)
         .build(url)
         .await
         .expect("failed to build server");
 
+    let (ipc_stop, ipc_stop_receiver) = tokio::sync::watch::channel(false);
+    if let Some(ipc_config) = ipc_config {
+        start_ipc_server(
+            Methods::clone(&api),
+            ipc_config,
+            api_config,
+            ipc_stop_receiver,
+        )?;
+    }
+
     let server_handler = server.start(api);
-    let stop_handler = StopHandle { server_handler };
+    let stop_handler = StopHandle {
+        server_handler,
+        ipc_stop,
+    };
 
     Ok(stop_handler)
 }
 
+/// Builds the layer applying the CORS policy of an API
+pub(crate) fn cors_layer(cors_config: &CorsConfig) -> Result<CorsLayer, JsonRpseeError> {
+    let mut cors = CorsLayer::new();
+
+    if cors_config
+        .allowed_origins
+        .iter()
+        .any(|origin| origin == "*")
+    {
+        cors = cors.allow_origin(Any);
+    } else if !cors_config.allowed_origins.is_empty() {
+        let origins = cors_config
+            .allowed_origins
+            .iter()
+            .map(|origin| {
+                HeaderValue::from_str(origin).map_err(|_| {
+                    JsonRpseeError::Custom(format!("invalid CORS allowed origin: {}", origin))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        cors = cors.allow_origin(origins);
+    }
+
+    if cors_config
+        .allowed_methods
+        .iter()
+        .any(|method| method == "*")
+    {
+        cors = cors.allow_methods(Any);
+    } else {
+        let methods = cors_config
+            .allowed_methods
+            .iter()
+            .map(|method| {
+                Method::from_bytes(method.as_bytes()).map_err(|_| {
+                    JsonRpseeError::Custom(format!("invalid CORS allowed method: {}", method))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        cors = cors.allow_methods(methods);
+    }
+
+    if cors_config
+        .allowed_headers
+        .iter()
+        .any(|header| header == "*")
+    {
+        cors = cors.allow_headers(Any);
+    } else {
+        let headers = cors_config
+            .allowed_headers
+            .iter()
+            .map(|header| {
+                HeaderName::from_str(header).map_err(|_| {
+                    JsonRpseeError::Custom(format!("invalid CORS allowed header: {}", header))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        cors = cors.allow_headers(headers);
+    }
+
+    Ok(cors)
+}
+
 /// Used to be able to stop the API
 pub struct StopHandle {
     server_handler: ServerHandle,
+    /// stops the IPC endpoint, if any
+    ipc_stop: tokio::sync::watch::Sender<bool>,
 }
 
 impl StopHandle {
     /// stop the API gracefully
     pub async fn stop(self) {
+        let _ = self.ipc_stop.send(true);
         match self.server_handler.stop() {
             Ok(_) => {
                 info!("API stop signal sent successfully");
@@ -224,6 +342,11 @@ pub trait MassaRpc {
     #[method(name = "stop_node")]
     fn stop_node(&self) -> RpcResult<()>;
 
+    /// Gracefully stop the node, giving the in-flight API requests and streams `grace_period`
+    /// to finish instead of the configured one.
+    #[method(name = "shutdown")]
+    fn shutdown(&self, grace_period: Option<MassaTime>) -> RpcResult<()>;
+
     /// Sign message with node's key.
     /// Returns the public key that signed the message and the signature.
     #[method(name = "node_sign_message")]
@@ -267,21 +390,20 @@ pub trait MassaRpc {
     #[method(name = "node_ban_by_id")]
     async fn node_ban_by_id(&self, arg: Vec<NodeId>) -> RpcResult<()>;
 
-    /// Returns node peers whitelist IP address(es).
+    /// Returns node peers whitelist IP ranges.
+    /// When it is not empty, the node only connects to the peers in these ranges.
     #[method(name = "node_peers_whitelist")]
-    async fn node_peers_whitelist(&self) -> RpcResult<Vec<IpAddr>>;
+    async fn node_peers_whitelist(&self) -> RpcResult<Vec<IpCidr>>;
 
-    /// Add IP address(es) to node peers whitelist.
+    /// Add IP address(es) or ranges (CIDR notation) to node peers whitelist.
     /// No confirmation to expect.
-    /// Note: If the ip was unknown it adds it to the known peers, otherwise it updates the peer type
     #[method(name = "node_add_to_peers_whitelist")]
-    async fn node_add_to_peers_whitelist(&self, arg: Vec<IpAddr>) -> RpcResult<()>;
+    async fn node_add_to_peers_whitelist(&self, arg: Vec<IpCidr>) -> RpcResult<()>;
 
-    /// Remove from peers whitelist given IP address(es).
-    /// keep it as standard
+    /// Remove from peers whitelist given IP address(es) or ranges (CIDR notation).
     /// No confirmation to expect.
     #[method(name = "node_remove_from_peers_whitelist")]
-    async fn node_remove_from_peers_whitelist(&self, arg: Vec<IpAddr>) -> RpcResult<()>;
+    async fn node_remove_from_peers_whitelist(&self, arg: Vec<IpCidr>) -> RpcResult<()>;
 
     /// Returns node bootstrap whitelist IP address(es).
     #[method(name = "node_bootstrap_whitelist")]
@@ -322,6 +444,37 @@ pub trait MassaRpc {
     #[method(name = "node_unban_by_id")]
     async fn node_unban_by_id(&self, arg: Vec<NodeId>) -> RpcResult<()>;
 
+    /// Returns the effective node configuration, merged from all the configuration sources.
+    #[method(name = "get_node_config")]
+    async fn get_node_config(&self) -> RpcResult<Value>;
+
+    /// Re-reads the node configuration and applies the settings that can be changed without a restart
+    /// (log level, pool sizes). Returns the changed settings.
+    #[method(name = "reload_node_config")]
+    async fn reload_node_config(&self) -> RpcResult<NodeConfigReload>;
+
+    /// Returns the disk usage of the node subsystems, as last measured.
+    #[method(name = "get_storage_info")]
+    async fn get_storage_info(&self) -> RpcResult<StorageInfo>;
+
+    /// Returns the peers known by the node, with their reputation score.
+    #[method(name = "get_peers")]
+    async fn get_peers(&self) -> RpcResult<Vec<PeerInfo>>;
+
+    /// Returns the bandwidth limits of the node, in bytes per second (0 meaning unlimited).
+    #[method(name = "get_bandwidth_limits")]
+    async fn get_bandwidth_limits(&self) -> RpcResult<BandwidthLimits>;
+
+    /// Changes the bandwidth limits of the node, in bytes per second (0 meaning unlimited).
+    /// The limits are reset to the configured ones when the node restarts.
+    #[method(name = "set_bandwidth_limits")]
+    async fn set_bandwidth_limits(&self, arg: BandwidthLimits) -> RpcResult<()>;
+
+    /// Renders the block graph over a slot range in DOT or GraphML, for visualization tools.
+    /// Each block is linked to its parents, and annotated with its finality status and cliques.
+    #[method(name = "node_export_block_graph")]
+    async fn node_export_block_graph(&self, arg: BlockGraphExportRequest) -> RpcResult<String>;
+
     /// Summary of the current state: time, last final blocks (hash, thread, slot, timestamp), clique count, connected nodes count.
     #[method(name = "get_status")]
     async fn get_status(&self) -> RpcResult<NodeStatus>;
@@ -330,6 +483,26 @@ pub trait MassaRpc {
     #[method(name = "get_cliques")]
     async fn get_cliques(&self) -> RpcResult<Vec<Clique>>;
 
+    /// Get the cliques with their blocks and fitness, and why each one is the blockclique or not.
+    #[method(name = "get_cliques_details")]
+    async fn get_cliques_details(&self) -> RpcResult<Vec<CliqueDetails>>;
+
+    /// Get the stale blocks of the stats history with their creators and slots, grouped by cycle.
+    #[method(name = "get_stale_block_stats")]
+    async fn get_stale_block_stats(&self) -> RpcResult<Vec<CycleStaleBlockStats>>;
+
+    /// Get the discarded blocks retained by the node, with the reason of their discard.
+    #[method(name = "get_discarded_blocks")]
+    async fn get_discarded_blocks(
+        &self,
+        page_request: Option<PageRequest>,
+    ) -> RpcResult<PagedVec<DiscardedBlock>>;
+
+    /// Get the data proving to an external verifier that a block is final: its endorsements,
+    /// the headers of its descendants, and the fingerprint of the final state that includes it.
+    #[method(name = "get_finality_proof")]
+    async fn get_finality_proof(&self, block_id: BlockId) -> RpcResult<FinalityProof>;
+
     /// Returns the active stakers and their active roll counts for the current cycle.
     #[method(name = "get_stakers")]
     async fn get_stakers(
@@ -341,6 +514,30 @@ pub trait MassaRpc {
     #[method(name = "get_operations")]
     async fn get_operations(&self, arg: Vec<OperationId>) -> RpcResult<Vec<OperationInfo>>;
 
+    /// Returns the final operations in which an address is the emitter or the recipient,
+    /// from the most recent one. Requires the operation index to be enabled on the node.
+    #[method(name = "get_operations_by_address")]
+    async fn get_operations_by_address(
+        &self,
+        address: Address,
+        role: OperationAddressRole,
+        page_request: Option<PageRequest>,
+    ) -> RpcResult<Vec<AddressOperation>>;
+
+    /// Returns a page of summaries of the operations of the pool, sorted by decreasing fee
+    /// or by decreasing age, with the occupancy of the pool.
+    #[method(name = "get_pool_operations")]
+    async fn get_pool_operations(
+        &self,
+        sort: PoolOperationSort,
+        page_request: Option<PageRequest>,
+    ) -> RpcResult<PoolOperations>;
+
+    /// Returns the number of items of the pools, the age of their oldest endorsement
+    /// and denunciation, and the endorsement indexes collected at each slot.
+    #[method(name = "get_pool_stats")]
+    async fn get_pool_stats(&self) -> RpcResult<PoolStats>;
+
     /// Returns endorsement(s) information associated to a given list of endorsement(s) ID(s)
     #[method(name = "get_endorsements")]
     async fn get_endorsements(&self, arg: Vec<EndorsementId>) -> RpcResult<Vec<EndorsementInfo>>;
@@ -354,6 +551,16 @@ pub trait MassaRpc {
     #[method(name = "get_blockclique_block_by_slot")]
     async fn get_blockclique_block_by_slot(&self, arg: Slot) -> RpcResult<Option<Block>>;
 
+    /// Get the blockclique (or final) blocks of the slots from `<start>` (included) to `<end>` (excluded).
+    /// Only the slots having a block are returned. The block headers are returned if `<with_headers>` is true.
+    #[method(name = "get_blocks_by_slot_range")]
+    async fn get_blocks_by_slot_range(
+        &self,
+        start: Slot,
+        end: Slot,
+        with_headers: Option<bool>,
+    ) -> RpcResult<Vec<SlotBlock>>;
+
     /// Get the block graph within the specified time interval.
     /// Optional parameters: from `<time_start>` (included) and to `<time_end>` (excluded) millisecond timestamp
     #[method(name = "get_graph_interval")]
@@ -378,10 +585,37 @@ pub trait MassaRpc {
     #[method(name = "get_slots_transfers")]
     async fn get_slots_transfers(&self, arg: Vec<Slot>) -> RpcResult<Vec<Vec<Transfer>>>;
 
+    /// Get the execution trace of a recently executed operation: its ABI calls,
+    /// the gas it consumed, its datastore accesses and its transfers.
+    /// Requires the node to be built with the `execution-trace` feature.
+    #[method(name = "get_operation_trace")]
+    async fn get_operation_trace(&self, arg: OperationId) -> RpcResult<Option<OperationTrace>>;
+
+    /// Get the gas charged for the calls to each ABI in the executed slots
+    /// from `<start>` (included) to `<end>` (excluded),
+    /// among the recently executed slots kept in the execution trace history.
+    /// Requires the node to be built with the `execution-trace` feature.
+    #[method(name = "get_gas_profile")]
+    async fn get_gas_profile(&self, start: Slot, end: Slot) -> RpcResult<GasProfile>;
+
+    /// Get the operation fees and the block fullness of the last `last_n_periods` final periods,
+    /// from the oldest to the most recent one.
+    #[method(name = "get_fee_history")]
+    async fn get_fee_history(&self, last_n_periods: u64) -> RpcResult<Vec<PeriodFeeStats>>;
+
     /// Adds operations to pool. Returns operations that were ok and sent to pool.
     #[method(name = "send_operations")]
     async fn send_operations(&self, arg: Vec<OperationInput>) -> RpcResult<Vec<OperationId>>;
 
+    /// Simulates an operation against the current speculative state, as if it was included
+    /// in the next block of its thread, without adding it to the pool nor propagating it.
+    /// The signature of the operation is verified only if provided.
+    #[method(name = "simulate_operation")]
+    async fn simulate_operation(
+        &self,
+        arg: OperationSimulationInput,
+    ) -> RpcResult<OperationSimulation>;
+
     /// Get events optionally filtered by:
     /// * start slot
     /// * end slot
@@ -392,6 +626,25 @@ pub trait MassaRpc {
     async fn get_filtered_sc_output_event(&self, arg: EventFilter)
         -> RpcResult<Vec<SCOutputEvent>>;
 
+    /// Get the asynchronous messages of the final pool, from the highest priority to the lowest,
+    /// optionally filtered by:
+    /// * sender address
+    /// * destination address
+    /// * execution status
+    #[method(name = "get_async_messages")]
+    async fn get_async_messages(
+        &self,
+        arg: AsyncMessageFilter,
+    ) -> RpcResult<Vec<PendingAsyncMessage>>;
+
+    /// Get the occupancy of the asynchronous pool and its eviction threshold,
+    /// with the minimum fee for a new message using the given max gas to be retained in it
+    #[method(name = "get_async_message_fee_suggestion")]
+    async fn get_async_message_fee_suggestion(
+        &self,
+        arg: u64,
+    ) -> RpcResult<AsyncMessageFeeSuggestion>;
+
     /// Get OpenRPC specification.
     #[method(name = "rpc.discover")]
     async fn get_openrpc_spec(&self) -> RpcResult<Value>;
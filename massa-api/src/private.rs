@@ -6,26 +6,47 @@ use async_trait::async_trait;
 use jsonrpsee::core::{Error as JsonRpseeError, RpcResult};
 use massa_api_exports::{
     address::{AddressFilter, AddressInfo},
-    block::{BlockInfo, BlockSummary},
+    block::{BlockGraphExportRequest, BlockInfo, BlockSummary, FinalityProof, SlotBlock},
     config::APIConfig,
     datastore::{DatastoreEntryInput, DatastoreEntryOutput},
     endorsement::EndorsementInfo,
     error::ApiError,
-    execution::{ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall, Transfer},
-    node::NodeStatus,
-    operation::{OperationInfo, OperationInput},
+    execution::{
+        ExecuteReadOnlyResponse, GasProfile, OperationTrace, ReadOnlyBytecodeExecution,
+        ReadOnlyCall, Transfer,
+    },
+    fee::PeriodFeeStats,
+    node::{BandwidthLimits, NodeStatus, PeerInfo},
+    node_config::{NodeConfigController, NodeConfigReload},
+    operation::{
+        AddressOperation, OperationInfo, OperationInput, OperationSimulation,
+        OperationSimulationInput, PoolOperations,
+    },
     page::{PageRequest, PagedVec},
+    storage::StorageInfo,
     ListType, ScrudOperation, TimeInterval,
 };
+use massa_consensus_exports::ConsensusController;
 use massa_execution_exports::ExecutionController;
 use massa_hash::Hash;
 use massa_models::{
-    address::Address, block::Block, block_id::BlockId, clique::Clique, composite::PubkeySig,
-    endorsement::EndorsementId, execution::EventFilter, node::NodeId, operation::OperationId,
-    output_event::SCOutputEvent, prehash::PreHashSet, slot::Slot,
+    address::Address,
+    block::{Block, DiscardedBlock},
+    block_id::BlockId,
+    clique::{Clique, CliqueDetails},
+    composite::PubkeySig,
+    endorsement::EndorsementId,
+    execution::{AsyncMessageFeeSuggestion, AsyncMessageFilter, EventFilter, PendingAsyncMessage},
+    node::NodeId,
+    operation::{OperationAddressRole, OperationId, PoolOperationSort},
+    output_event::SCOutputEvent,
+    prehash::PreHashSet,
+    slot::Slot,
+    stats::{CycleStaleBlockStats, PoolStats},
 };
-use massa_protocol_exports::{PeerId, ProtocolController};
+use massa_protocol_exports::{IpCidr, PeerConnectionType, PeerId, ProtocolController};
 use massa_signature::KeyPair;
+use massa_time::MassaTime;
 use massa_wallet::Wallet;
 use parking_lot::RwLock;
 use std::net::{IpAddr, SocketAddr};
@@ -40,12 +61,17 @@ use std::{
 
 impl API<Private> {
     /// generate a new private API
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         protocol_controller: Box<dyn ProtocolController>,
         execution_controller: Box<dyn ExecutionController>,
         api_settings: APIConfig,
         stop_cv: Arc<(Mutex<bool>, Condvar)>,
         node_wallet: Arc<RwLock<Wallet>>,
+        node_config_controller: Box<dyn NodeConfigController>,
+        storage_info: Arc<RwLock<StorageInfo>>,
+        consensus_controller: Box<dyn ConsensusController>,
+        shutdown_grace_period: Arc<RwLock<MassaTime>>,
     ) -> Self {
         API(Private {
             protocol_controller,
@@ -53,6 +79,10 @@ impl API<Private> {
             api_settings,
             stop_cv,
             node_wallet,
+            node_config_controller,
+            storage_info,
+            consensus_controller,
+            shutdown_grace_period,
         })
     }
 }
@@ -64,7 +94,15 @@ impl RpcServer for API<Private> {
         url: &SocketAddr,
         settings: &APIConfig,
     ) -> Result<StopHandle, JsonRpseeError> {
-        crate::serve(self.into_rpc(), url, settings).await
+        crate::serve(
+            self.into_rpc(),
+            crate::versioning::API_V1_NAMESPACE,
+            url,
+            settings,
+            &settings.cors_private,
+            settings.ipc_private.as_ref(),
+        )
+        .await
     }
 }
 
@@ -77,6 +115,13 @@ impl MassaRpcServer for API<Private> {
         Ok(())
     }
 
+    fn shutdown(&self, grace_period: Option<MassaTime>) -> RpcResult<()> {
+        if let Some(grace_period) = grace_period {
+            *self.0.shutdown_grace_period.write() = grace_period;
+        }
+        self.stop_node()
+    }
+
     async fn node_sign_message(&self, message: Vec<u8>) -> RpcResult<PubkeySig> {
         let signature = match self
             .0
@@ -183,6 +228,89 @@ impl MassaRpcServer for API<Private> {
             .map_err(|e| ApiError::ProtocolError(e.to_string()).into())
     }
 
+    async fn get_node_config(&self) -> RpcResult<Value> {
+        self.0
+            .node_config_controller
+            .get_node_config()
+            .map_err(|e| e.into())
+    }
+
+    async fn reload_node_config(&self) -> RpcResult<NodeConfigReload> {
+        self.0
+            .node_config_controller
+            .reload_node_config()
+            .map_err(|e| e.into())
+    }
+
+    async fn get_storage_info(&self) -> RpcResult<StorageInfo> {
+        Ok(self.0.storage_info.read().clone())
+    }
+
+    async fn get_peers(&self) -> RpcResult<Vec<PeerInfo>> {
+        let protocol_controller = self.0.protocol_controller.clone();
+        let peers = protocol_controller
+            .get_peers()
+            .map_err(|e| ApiError::ProtocolError(e.to_string()))?;
+        let (_, connected_peers) = protocol_controller
+            .get_stats()
+            .map_err(|e| ApiError::ProtocolError(e.to_string()))?;
+        let mut peers: Vec<PeerInfo> = peers
+            .into_iter()
+            .map(|(peer_id, reputation)| PeerInfo {
+                //TODO: Change when unify node id and peer id
+                node_id: NodeId::new(peer_id.get_public_key()),
+                connection: connected_peers
+                    .get(&peer_id)
+                    .map(|(addr, kind)| (addr.ip(), matches!(kind, PeerConnectionType::OUT))),
+                listeners: reputation.listeners,
+                score: reputation.current_score,
+                is_throttled: reputation.throttled,
+                is_banned: reputation.banned,
+                invalid_message_count: reputation.score.invalid_message_count,
+                slow_response_count: reputation.score.slow_response_count,
+                spam_count: reputation.score.spam_count,
+                new_block_count: reputation.score.new_block_count,
+                new_operations_count: reputation.score.new_operations_count,
+            })
+            .collect();
+        // best peers first
+        peers.sort_by(|a, b| b.score.total_cmp(&a.score));
+        Ok(peers)
+    }
+
+    async fn get_bandwidth_limits(&self) -> RpcResult<BandwidthLimits> {
+        let limits = self
+            .0
+            .protocol_controller
+            .get_bandwidth_limits()
+            .map_err(|e| ApiError::ProtocolError(e.to_string()))?;
+        Ok(BandwidthLimits {
+            peer_upload: limits.peer_upload,
+            peer_download: limits.peer_download,
+            global_upload: limits.global_upload,
+            global_download: limits.global_download,
+        })
+    }
+
+    async fn set_bandwidth_limits(&self, limits: BandwidthLimits) -> RpcResult<()> {
+        self.0
+            .protocol_controller
+            .set_bandwidth_limits(massa_protocol_exports::BandwidthLimits {
+                peer_upload: limits.peer_upload,
+                peer_download: limits.peer_download,
+                global_upload: limits.global_upload,
+                global_download: limits.global_download,
+            })
+            .map_err(|e| ApiError::ProtocolError(e.to_string()).into())
+    }
+
+    async fn node_export_block_graph(&self, request: BlockGraphExportRequest) -> RpcResult<String> {
+        self.0
+            .consensus_controller
+            .export_block_graph(request.start_slot, request.end_slot, request.format)
+            .map_err(|e| ApiError::ConsensusError(e.to_string()).into())
+    }
+
     async fn node_unban_by_ip(&self, _ips: Vec<IpAddr>) -> RpcResult<()> {
         //TODO: Reinvoke
         // let network_command_sender = self.0.network_command_sender.clone();
@@ -199,6 +327,18 @@ impl MassaRpcServer for API<Private> {
         crate::wrong_api::<Vec<Vec<Transfer>>>()
     }
 
+    async fn get_operation_trace(&self, _: OperationId) -> RpcResult<Option<OperationTrace>> {
+        crate::wrong_api::<Option<OperationTrace>>()
+    }
+
+    async fn get_gas_profile(&self, _: Slot, _: Slot) -> RpcResult<GasProfile> {
+        crate::wrong_api::<GasProfile>()
+    }
+
+    async fn get_fee_history(&self, _: u64) -> RpcResult<Vec<PeriodFeeStats>> {
+        crate::wrong_api::<Vec<PeriodFeeStats>>()
+    }
+
     async fn get_status(&self) -> RpcResult<NodeStatus> {
         crate::wrong_api::<NodeStatus>()
     }
@@ -207,6 +347,25 @@ impl MassaRpcServer for API<Private> {
         crate::wrong_api::<Vec<Clique>>()
     }
 
+    async fn get_cliques_details(&self) -> RpcResult<Vec<CliqueDetails>> {
+        crate::wrong_api::<Vec<CliqueDetails>>()
+    }
+
+    async fn get_stale_block_stats(&self) -> RpcResult<Vec<CycleStaleBlockStats>> {
+        crate::wrong_api::<Vec<CycleStaleBlockStats>>()
+    }
+
+    async fn get_discarded_blocks(
+        &self,
+        _: Option<PageRequest>,
+    ) -> RpcResult<PagedVec<DiscardedBlock>> {
+        crate::wrong_api::<PagedVec<DiscardedBlock>>()
+    }
+
+    async fn get_finality_proof(&self, _: BlockId) -> RpcResult<FinalityProof> {
+        crate::wrong_api::<FinalityProof>()
+    }
+
     async fn get_stakers(&self, _: Option<PageRequest>) -> RpcResult<PagedVec<(Address, u64)>> {
         crate::wrong_api::<PagedVec<(Address, u64)>>()
     }
@@ -215,6 +374,27 @@ impl MassaRpcServer for API<Private> {
         crate::wrong_api::<Vec<OperationInfo>>()
     }
 
+    async fn get_operations_by_address(
+        &self,
+        _: Address,
+        _: OperationAddressRole,
+        _: Option<PageRequest>,
+    ) -> RpcResult<Vec<AddressOperation>> {
+        crate::wrong_api::<Vec<AddressOperation>>()
+    }
+
+    async fn get_pool_operations(
+        &self,
+        _: PoolOperationSort,
+        _: Option<PageRequest>,
+    ) -> RpcResult<PoolOperations> {
+        crate::wrong_api::<PoolOperations>()
+    }
+
+    async fn get_pool_stats(&self) -> RpcResult<PoolStats> {
+        crate::wrong_api::<PoolStats>()
+    }
+
     async fn get_endorsements(&self, _: Vec<EndorsementId>) -> RpcResult<Vec<EndorsementInfo>> {
         crate::wrong_api::<Vec<EndorsementInfo>>()
     }
@@ -227,6 +407,15 @@ impl MassaRpcServer for API<Private> {
         crate::wrong_api::<Option<Block>>()
     }
 
+    async fn get_blocks_by_slot_range(
+        &self,
+        _: Slot,
+        _: Slot,
+        _: Option<bool>,
+    ) -> RpcResult<Vec<SlotBlock>> {
+        crate::wrong_api::<Vec<SlotBlock>>()
+    }
+
     async fn get_graph_interval(&self, _: TimeInterval) -> RpcResult<Vec<BlockSummary>> {
         crate::wrong_api::<Vec<BlockSummary>>()
     }
@@ -250,45 +439,50 @@ impl MassaRpcServer for API<Private> {
         crate::wrong_api::<Vec<OperationId>>()
     }
 
+    async fn simulate_operation(
+        &self,
+        _: OperationSimulationInput,
+    ) -> RpcResult<OperationSimulation> {
+        crate::wrong_api::<OperationSimulation>()
+    }
+
     async fn get_filtered_sc_output_event(&self, _: EventFilter) -> RpcResult<Vec<SCOutputEvent>> {
         crate::wrong_api::<Vec<SCOutputEvent>>()
     }
 
-    async fn node_peers_whitelist(&self) -> RpcResult<Vec<IpAddr>> {
-        //TODO: Reinvoke
-        // let network_command_sender = self.0.network_command_sender.clone();
-        // match network_command_sender.get_peers().await {
-        //     Ok(peers) => Ok(peers.peers.into_keys().sorted().collect::<Vec<IpAddr>>()),
-        //     Err(e) => Err(ApiError::NetworkError(e).into()),
-        // }
-        return Err(
-            ApiError::BadRequest("This request is currently not available".to_string()).into(),
-        );
+    async fn get_async_messages(
+        &self,
+        _: AsyncMessageFilter,
+    ) -> RpcResult<Vec<PendingAsyncMessage>> {
+        crate::wrong_api::<Vec<PendingAsyncMessage>>()
     }
 
-    async fn node_add_to_peers_whitelist(&self, _ips: Vec<IpAddr>) -> RpcResult<()> {
-        //TODO: Readd in network refactoring
-        // let network_command_sender = self.0.network_command_sender.clone();
-        // network_command_sender
-        //     .add_to_whitelist(ips)
-        //     .await
-        //     .map_err(|e| ApiError::NetworkError(e).into())
-        return Err(
-            ApiError::BadRequest("This request is currently not available".to_string()).into(),
-        );
+    async fn get_async_message_fee_suggestion(
+        &self,
+        _: u64,
+    ) -> RpcResult<AsyncMessageFeeSuggestion> {
+        crate::wrong_api::<AsyncMessageFeeSuggestion>()
     }
 
-    async fn node_remove_from_peers_whitelist(&self, _ips: Vec<IpAddr>) -> RpcResult<()> {
-        //TODO: Reinvoke
-        //TODO: Readd in network refactoring
-        // let network_command_sender = self.0.network_command_sender.clone();
-        // network_command_sender
-        //     .remove_from_whitelist(ips)
-        //     .await
-        //     .map_err(|e| ApiError::NetworkError(e).into())
-        return Err(
-            ApiError::BadRequest("This request is currently not available".to_string()).into(),
-        );
+    async fn node_peers_whitelist(&self) -> RpcResult<Vec<IpCidr>> {
+        self.0
+            .protocol_controller
+            .get_allowed_ips()
+            .map_err(|e| ApiError::ProtocolError(e.to_string()).into())
+    }
+
+    async fn node_add_to_peers_whitelist(&self, ranges: Vec<IpCidr>) -> RpcResult<()> {
+        self.0
+            .protocol_controller
+            .allow_ips(ranges)
+            .map_err(|e| ApiError::ProtocolError(e.to_string()).into())
+    }
+
+    async fn node_remove_from_peers_whitelist(&self, ranges: Vec<IpCidr>) -> RpcResult<()> {
+        self.0
+            .protocol_controller
+            .disallow_ips(ranges)
+            .map_err(|e| ApiError::ProtocolError(e.to_string()).into())
     }
 
     async fn node_bootstrap_whitelist(&self) -> RpcResult<Vec<IpAddr>> {
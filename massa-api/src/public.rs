@@ -1,24 +1,35 @@
 //! Copyright (c) 2022 MASSA LABS <info@massa.net>
 #![allow(clippy::too_many_arguments)]
 
+use crate::caller::get_caller_id;
 use crate::{MassaRpcServer, Public, RpcServer, StopHandle, Value, API};
 use async_trait::async_trait;
 use itertools::{izip, Itertools};
 use jsonrpsee::core::{Error as JsonRpseeError, RpcResult};
 use massa_api_exports::{
     address::{AddressFilter, AddressInfo},
-    block::{BlockInfo, BlockInfoContent, BlockSummary},
+    block::{
+        BlockGraphExportRequest, BlockInfo, BlockInfoContent, BlockSummary, FinalityProof,
+        SlotBlock,
+    },
     config::APIConfig,
     datastore::{DatastoreEntryInput, DatastoreEntryOutput},
     endorsement::EndorsementInfo,
     error::ApiError,
     execution::{
-        ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall, ReadOnlyResult, Transfer,
+        ExecuteReadOnlyResponse, GasProfile, OperationTrace, ReadOnlyBytecodeExecution,
+        ReadOnlyCall, ReadOnlyResult, Transfer,
+    },
+    fee::PeriodFeeStats,
+    node::{BandwidthLimits, HistoryAvailability, NodeStatus, PeerInfo},
+    node_config::NodeConfigReload,
+    operation::{
+        AddressOperation, OperationInfo, OperationInput, OperationSimulation,
+        OperationSimulationInput, OperationSimulationStatus, PoolOperations,
     },
-    node::NodeStatus,
-    operation::{OperationInfo, OperationInput},
     page::{PageRequest, PagedVec},
     slot::SlotAmount,
+    storage::StorageInfo,
     TimeInterval,
 };
 use massa_consensus_exports::block_status::DiscardReason;
@@ -26,46 +37,56 @@ use massa_consensus_exports::ConsensusController;
 use massa_execution_exports::{
     ExecutionController, ExecutionQueryRequest, ExecutionQueryRequestItem,
     ExecutionQueryResponseItem, ExecutionStackElement, ReadOnlyExecutionRequest,
-    ReadOnlyExecutionTarget,
+    ReadOnlyExecutionTarget, ReadOnlyPriority, ReadOnlyStateOverride,
 };
 use massa_models::{
     address::Address,
     amount::Amount,
-    block::{Block, BlockGraphStatus},
+    block::{Block, BlockGraphStatus, DiscardedBlock},
     block_id::BlockId,
-    clique::Clique,
+    bytecode::Bytecode,
+    clique::{Clique, CliqueDetails},
     composite::PubkeySig,
     config::CompactConfig,
     datastore::DatastoreDeserializer,
     endorsement::EndorsementId,
     endorsement::SecureShareEndorsement,
     error::ModelsError,
-    execution::EventFilter,
+    execution::{AsyncMessageFeeSuggestion, AsyncMessageFilter, EventFilter, PendingAsyncMessage},
     node::NodeId,
     operation::OperationDeserializer,
     operation::OperationId,
-    operation::{OperationType, SecureShareOperation},
+    operation::{OperationAddressRole, OperationType, PoolOperationSort, SecureShareOperation},
     output_event::SCOutputEvent,
     prehash::{PreHashMap, PreHashSet},
     secure_share::SecureShareDeserializer,
     slot::{IndexedSlot, Slot},
+    stats::{CycleStaleBlockStats, PoolStats},
     timeslots,
     timeslots::{get_latest_block_slot_at_timestamp, time_range_to_slot_range},
     version::Version,
 };
 use massa_pool_exports::PoolController;
 use massa_pos_exports::SelectorController;
-use massa_protocol_exports::{PeerConnectionType, ProtocolConfig, ProtocolController};
+use massa_protocol_exports::{IpCidr, PeerConnectionType, ProtocolConfig, ProtocolController};
 use massa_serialization::{DeserializeError, Deserializer};
+use massa_signature::Signature;
 use massa_storage::Storage;
 use massa_time::MassaTime;
 use massa_versioning::versioning_factory::FactoryStrategy;
 use massa_versioning::{
-    keypair_factory::KeyPairFactory, versioning::MipStore, versioning_factory::VersioningFactory,
+    keypair_factory::KeyPairFactory,
+    versioning::{MipComponent, MipStore},
+    versioning_factory::VersioningFactory,
 };
 use std::collections::BTreeMap;
 use std::net::{IpAddr, SocketAddr};
 
+#[cfg(feature = "execution-trace")]
+use massa_api_exports::execution::AbiCall;
+#[cfg(feature = "execution-trace")]
+use massa_execution_exports::AbiTrace;
+
 impl API<Public> {
     /// generate a new public API
     pub fn new(
@@ -104,7 +125,15 @@ impl RpcServer for API<Public> {
         url: &SocketAddr,
         api_config: &APIConfig,
     ) -> Result<StopHandle, JsonRpseeError> {
-        crate::serve(self.into_rpc(), url, api_config).await
+        crate::serve(
+            self.into_rpc(),
+            crate::versioning::API_V1_NAMESPACE,
+            url,
+            api_config,
+            &api_config.cors_public,
+            api_config.ipc_public.as_ref(),
+        )
+        .await
     }
 }
 
@@ -115,6 +144,10 @@ impl MassaRpcServer for API<Public> {
         crate::wrong_api::<()>()
     }
 
+    fn shutdown(&self, _: Option<MassaTime>) -> RpcResult<()> {
+        crate::wrong_api::<()>()
+    }
+
     async fn node_sign_message(&self, _: Vec<u8>) -> RpcResult<PubkeySig> {
         crate::wrong_api::<PubkeySig>()
     }
@@ -217,11 +250,127 @@ impl MassaRpcServer for API<Public> {
         RpcResult::Err(ApiError::BadRequest("feature execution-trace is not enabled".into()).into())
     }
 
+    #[cfg(feature = "execution-trace")]
+    async fn get_operation_trace(&self, op_id: OperationId) -> RpcResult<Option<OperationTrace>> {
+        use massa_api_exports::execution::{StorageAccess, StorageAccessType, TransferContext};
+        use std::str::FromStr;
+
+        let Some(trace) = self.0.execution_controller.get_operation_trace(&op_id) else {
+            return Ok(None);
+        };
+
+        // transfers of the operation itself
+        let mut transfers: Vec<Transfer> = trace
+            .transfers
+            .iter()
+            .map(|t| Transfer {
+                from: t.from,
+                to: t.to,
+                amount: t.amount,
+                effective_amount_received: t.effective_received_amount,
+                context: TransferContext::Operation(t.op_id),
+                succeed: t.succeed,
+                fee: t.fee,
+                block_id: trace.block_id,
+            })
+            .collect();
+
+        // transfers made by the ABI calls of the operation
+        let transfer_abi_names = vec![
+            "assembly_script_transfer_coins".to_string(),
+            "assembly_script_transfer_coins_for".to_string(),
+            "abi_transfer_coins".to_string(),
+        ];
+        for abi_trace in trace.abi_calls.iter() {
+            for transfer in abi_trace.flatten_filter(&transfer_abi_names) {
+                let (t_from, t_to, t_amount) = transfer.parse_transfer();
+                let (Ok(from), Ok(to)) = (Address::from_str(&t_from), Address::from_str(&t_to))
+                else {
+                    continue;
+                };
+                transfers.push(Transfer {
+                    from,
+                    to,
+                    amount: Amount::from_raw(t_amount),
+                    effective_amount_received: Amount::from_raw(t_amount),
+                    context: TransferContext::Operation(op_id),
+                    succeed: true,
+                    fee: Amount::from_raw(0),
+                    block_id: trace.block_id,
+                });
+            }
+        }
+
+        Ok(Some(OperationTrace {
+            op_id: trace.op_id,
+            slot: trace.slot,
+            block_id: trace.block_id,
+            succeed: trace.succeed,
+            gas_used: trace.gas_used,
+            abi_calls: trace.abi_calls.iter().map(into_abi_call).collect(),
+            storage_accesses: trace
+                .storage_accesses
+                .into_iter()
+                .map(|access| StorageAccess {
+                    address: access.address,
+                    key: access.key,
+                    access_type: match access.access_type {
+                        massa_execution_exports::StorageAccessType::Read => StorageAccessType::Read,
+                        massa_execution_exports::StorageAccessType::Write => {
+                            StorageAccessType::Write
+                        }
+                        massa_execution_exports::StorageAccessType::Delete => {
+                            StorageAccessType::Delete
+                        }
+                    },
+                })
+                .collect(),
+            transfers,
+        }))
+    }
+
+    #[cfg(not(feature = "execution-trace"))]
+    async fn get_operation_trace(&self, _: OperationId) -> RpcResult<Option<OperationTrace>> {
+        RpcResult::Err(ApiError::BadRequest("feature execution-trace is not enabled".into()).into())
+    }
+
+    #[cfg(feature = "execution-trace")]
+    async fn get_gas_profile(&self, start: Slot, end: Slot) -> RpcResult<GasProfile> {
+        use massa_api_exports::execution::AbiGasUsage;
+
+        if end <= start {
+            return Err(ApiError::BadRequest("start must be before end".to_string()).into());
+        }
+
+        let profile = self.0.execution_controller.get_gas_profile(start, end);
+        let mut abis: Vec<AbiGasUsage> = profile
+            .abi_usage
+            .into_iter()
+            .map(|(name, usage)| AbiGasUsage {
+                name,
+                call_count: usage.call_count,
+                gas: usage.gas,
+            })
+            .collect();
+        // the most expensive ABIs first
+        abis.sort_by(|a, b| b.gas.cmp(&a.gas).then_with(|| a.name.cmp(&b.name)));
+
+        Ok(GasProfile {
+            slot_count: profile.slot_count,
+            abis,
+        })
+    }
+
+    #[cfg(not(feature = "execution-trace"))]
+    async fn get_gas_profile(&self, _: Slot, _: Slot) -> RpcResult<GasProfile> {
+        RpcResult::Err(ApiError::BadRequest("feature execution-trace is not enabled".into()).into())
+    }
+
     async fn execute_read_only_bytecode(
         &self,
         reqs: Vec<ReadOnlyBytecodeExecution>,
     ) -> RpcResult<Vec<ExecuteReadOnlyResponse>> {
-        if reqs.len() as u64 > self.0.api_settings.max_arguments {
+        if reqs.len() as u64 > self.0.api_settings.limits.max_arguments() {
             return Err(ApiError::BadRequest("too many arguments".into()).into());
         }
 
@@ -279,6 +428,9 @@ impl MassaRpcServer for API<Public> {
                 }],
                 coins: None,
                 fee,
+                priority: ReadOnlyPriority::Normal,
+                caller_id: Some(get_caller_id(|| address.to_string())),
+                state_overrides: Vec::new(),
             };
 
             // check if fee is enough
@@ -330,7 +482,7 @@ impl MassaRpcServer for API<Public> {
         &self,
         reqs: Vec<ReadOnlyCall>,
     ) -> RpcResult<Vec<ExecuteReadOnlyResponse>> {
-        if reqs.len() as u64 > self.0.api_settings.max_arguments {
+        if reqs.len() as u64 > self.0.api_settings.limits.max_arguments() {
             return Err(ApiError::BadRequest("too many arguments".into()).into());
         }
 
@@ -343,6 +495,7 @@ impl MassaRpcServer for API<Public> {
             caller_address,
             coins,
             fee,
+            state_overrides,
         } in reqs
         {
             let caller_address = if let Some(addr) = caller_address {
@@ -381,6 +534,23 @@ impl MassaRpcServer for API<Public> {
                 ],
                 coins,
                 fee,
+                priority: ReadOnlyPriority::Normal,
+                caller_id: Some(get_caller_id(|| caller_address.to_string())),
+                state_overrides: state_overrides
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|state_override| ReadOnlyStateOverride {
+                        address: state_override.address,
+                        balance: state_override.balance,
+                        bytecode: state_override.bytecode.map(Bytecode),
+                        datastore: state_override
+                            .datastore
+                            .unwrap_or_default()
+                            .into_iter()
+                            .map(|entry| (entry.key, entry.value))
+                            .collect(),
+                    })
+                    .collect(),
             };
 
             if let Some(fee) = fee {
@@ -450,6 +620,34 @@ impl MassaRpcServer for API<Public> {
         crate::wrong_api::<()>()
     }
 
+    async fn get_node_config(&self) -> RpcResult<Value> {
+        crate::wrong_api::<Value>()
+    }
+
+    async fn reload_node_config(&self) -> RpcResult<NodeConfigReload> {
+        crate::wrong_api::<NodeConfigReload>()
+    }
+
+    async fn get_storage_info(&self) -> RpcResult<StorageInfo> {
+        crate::wrong_api::<StorageInfo>()
+    }
+
+    async fn get_peers(&self) -> RpcResult<Vec<PeerInfo>> {
+        crate::wrong_api::<Vec<PeerInfo>>()
+    }
+
+    async fn get_bandwidth_limits(&self) -> RpcResult<BandwidthLimits> {
+        crate::wrong_api::<BandwidthLimits>()
+    }
+
+    async fn set_bandwidth_limits(&self, _: BandwidthLimits) -> RpcResult<()> {
+        crate::wrong_api::<()>()
+    }
+
+    async fn node_export_block_graph(&self, _: BlockGraphExportRequest) -> RpcResult<String> {
+        crate::wrong_api::<String>()
+    }
+
     /// get status
     async fn get_status(&self) -> RpcResult<NodeStatus> {
         let version = self.0.version;
@@ -538,6 +736,13 @@ impl MassaRpcServer for API<Public> {
             Err(e) => return Err(ApiError::TimeError(e).into()),
         };
 
+        let pruned_history_periods = self.0.api_settings.pruned_history_periods;
+        let history = HistoryAvailability {
+            pruned: pruned_history_periods.is_some(),
+            first_available_period: pruned_history_periods
+                .map(|periods| execution_stats.final_cursor.period.saturating_sub(periods)),
+        };
+
         Ok(NodeStatus {
             node_id,
             node_ip: protocol_config.routable_ip,
@@ -556,6 +761,7 @@ impl MassaRpcServer for API<Public> {
             current_cycle,
             chain_id: self.0.api_settings.chain_id,
             minimal_fees: self.0.api_settings.minimal_fees,
+            history,
         })
     }
 
@@ -564,6 +770,54 @@ impl MassaRpcServer for API<Public> {
         Ok(self.0.consensus_controller.get_cliques())
     }
 
+    /// get cliques details
+    async fn get_cliques_details(&self) -> RpcResult<Vec<CliqueDetails>> {
+        self.0
+            .consensus_controller
+            .get_cliques_details()
+            .map_err(|e| ApiError::ConsensusError(e.to_string()).into())
+    }
+
+    /// get stale block stats
+    async fn get_stale_block_stats(&self) -> RpcResult<Vec<CycleStaleBlockStats>> {
+        Ok(self.0.consensus_controller.get_stale_block_stats())
+    }
+
+    /// get discarded blocks
+    async fn get_discarded_blocks(
+        &self,
+        page_request: Option<PageRequest>,
+    ) -> RpcResult<PagedVec<DiscardedBlock>> {
+        let discarded_blocks = self.0.consensus_controller.get_discarded_blocks();
+        Ok(PagedVec::new(discarded_blocks, page_request))
+    }
+
+    /// get finality proof
+    async fn get_finality_proof(&self, block_id: BlockId) -> RpcResult<FinalityProof> {
+        let graph_proof = self
+            .0
+            .consensus_controller
+            .get_finality_proof(block_id)
+            .map_err(|e| ApiError::ConsensusError(e.to_string()))?;
+        let execution_state = self
+            .0
+            .execution_controller
+            .query_state(ExecutionQueryRequest { requests: vec![] });
+        if execution_state.final_cursor < graph_proof.header.content.slot {
+            return Err(ApiError::ExecutionError(format!(
+                "block {} is not yet executed in the final state",
+                block_id
+            ))
+            .into());
+        }
+        Ok(FinalityProof {
+            block_id,
+            graph_proof,
+            final_cursor: execution_state.final_cursor,
+            final_state_fingerprint: execution_state.final_state_fingerprint,
+        })
+    }
+
     /// get stakers
     async fn get_stakers(
         &self,
@@ -639,7 +893,7 @@ impl MassaRpcServer for API<Public> {
         let ops: Vec<OperationId> = storage_info.iter().map(|(op, _)| op.id).collect();
 
         let api_cfg = self.0.api_settings.clone();
-        if ops.len() as u64 > api_cfg.max_arguments {
+        if ops.len() as u64 > api_cfg.limits.max_arguments() {
             return Err(ApiError::BadRequest("too many arguments".into()).into());
         }
 
@@ -715,12 +969,78 @@ impl MassaRpcServer for API<Public> {
         Ok(res)
     }
 
+    /// gets the final operations of an address from the operation index
+    async fn get_operations_by_address(
+        &self,
+        address: Address,
+        role: OperationAddressRole,
+        page_request: Option<PageRequest>,
+    ) -> RpcResult<Vec<AddressOperation>> {
+        let max_arguments = self.0.api_settings.limits.max_arguments() as usize;
+        let (limit, offset) = match page_request {
+            Some(PageRequest { limit, offset }) => (limit, offset),
+            None => (max_arguments, 0),
+        };
+        if limit == 0 || limit > max_arguments {
+            return Err(ApiError::BadRequest(format!(
+                "the page limit must be between 1 and {}",
+                max_arguments
+            ))
+            .into());
+        }
+
+        // the offset is a page index
+        let operations = self
+            .0
+            .execution_controller
+            .get_operations_by_address(&address, role, offset.saturating_mul(limit), limit)
+            .map_err(|e| ApiError::ExecutionError(e.to_string()))?;
+        let res = operations
+            .into_iter()
+            .map(|(slot, id)| AddressOperation { id, slot })
+            .collect();
+        Ok(res)
+    }
+
+    /// gets a page of the operations of the pool
+    async fn get_pool_operations(
+        &self,
+        sort: PoolOperationSort,
+        page_request: Option<PageRequest>,
+    ) -> RpcResult<PoolOperations> {
+        let max_arguments = self.0.api_settings.limits.max_arguments() as usize;
+        let (limit, offset) = match page_request {
+            Some(PageRequest { limit, offset }) => (limit, offset),
+            None => (max_arguments, 0),
+        };
+        if limit == 0 || limit > max_arguments {
+            return Err(ApiError::BadRequest(format!(
+                "the page limit must be between 1 and {}",
+                max_arguments
+            ))
+            .into());
+        }
+
+        // the offset is a page index
+        let (operations, stats) = self.0.pool_command_sender.get_pool_operations(
+            sort,
+            offset.saturating_mul(limit),
+            limit,
+        );
+        Ok(PoolOperations { operations, stats })
+    }
+
+    /// gets the stats of the endorsement and denunciation pools
+    async fn get_pool_stats(&self) -> RpcResult<PoolStats> {
+        Ok(self.0.pool_command_sender.get_pool_stats())
+    }
+
     /// get endorsements
     async fn get_endorsements(
         &self,
         mut endorsement_ids: Vec<EndorsementId>,
     ) -> RpcResult<Vec<EndorsementInfo>> {
-        if endorsement_ids.len() as u64 > self.0.api_settings.max_arguments {
+        if endorsement_ids.len() as u64 > self.0.api_settings.limits.max_arguments() {
             return Err(ApiError::BadRequest("too many arguments".into()).into());
         }
 
@@ -863,6 +1183,54 @@ impl MassaRpcServer for API<Public> {
         Ok(res)
     }
 
+    /// gets the blockclique (or final) blocks of a slot range
+    async fn get_blocks_by_slot_range(
+        &self,
+        start: Slot,
+        end: Slot,
+        with_headers: Option<bool>,
+    ) -> RpcResult<Vec<SlotBlock>> {
+        let api_settings = &self.0.api_settings;
+        let slot_count = end
+            .slots_since(&start, api_settings.thread_count)
+            .map_err(|_| ApiError::BadRequest("start must be before end".to_string()))?;
+        if slot_count == 0 || slot_count > api_settings.limits.max_arguments() {
+            return Err(ApiError::BadRequest(format!(
+                "the slot range must contain between 1 and {} slots",
+                api_settings.limits.max_arguments()
+            ))
+            .into());
+        }
+
+        let blocks = self
+            .0
+            .consensus_controller
+            .get_blockclique_blocks_in_slot_range(start, end);
+
+        if !with_headers.unwrap_or(false) {
+            return Ok(blocks
+                .into_iter()
+                .map(|(slot, id)| SlotBlock {
+                    slot,
+                    id,
+                    header: None,
+                })
+                .collect());
+        }
+        let block_storage_lock = self.0.storage.read_blocks();
+        let res = blocks
+            .into_iter()
+            .map(|(slot, id)| SlotBlock {
+                slot,
+                id,
+                header: block_storage_lock
+                    .get(&id)
+                    .map(|block| block.content.header.clone()),
+            })
+            .collect();
+        Ok(res)
+    }
+
     /// gets an interval of the block graph from consensus, with time filtering
     /// time filtering is done consensus-side to prevent communication overhead
     async fn get_graph_interval(&self, time: TimeInterval) -> RpcResult<Vec<BlockSummary>> {
@@ -924,6 +1292,96 @@ impl MassaRpcServer for API<Public> {
         Ok(res)
     }
 
+    /// gets the operation fees and the fullness of the final blocks of the last final periods
+    async fn get_fee_history(&self, last_n_periods: u64) -> RpcResult<Vec<PeriodFeeStats>> {
+        let api_settings = &self.0.api_settings;
+        if last_n_periods == 0 || last_n_periods > api_settings.limits.max_arguments() {
+            return Err(ApiError::BadRequest(format!(
+                "last_n_periods must be between 1 and {}",
+                api_settings.limits.max_arguments()
+            ))
+            .into());
+        }
+
+        let graph = self
+            .0
+            .consensus_controller
+            .get_block_graph_status(None, None)
+            .map_err(|e| ApiError::ConsensusError(e.to_string()))?;
+
+        // last period that is final in every thread
+        let Some(last_final_period) = graph
+            .latest_final_blocks_periods
+            .iter()
+            .map(|(_, period)| *period)
+            .min()
+        else {
+            return Ok(Vec::new());
+        };
+        let first_period = last_final_period.saturating_sub(last_n_periods - 1);
+
+        // final blocks of each period
+        let mut period_blocks: BTreeMap<u64, Vec<BlockId>> = (first_period..=last_final_period)
+            .map(|period| (period, Vec::new()))
+            .collect();
+        for (id, exported_block) in graph.active_blocks.iter() {
+            if !exported_block.is_final {
+                continue;
+            }
+            if let Some(block_ids) =
+                period_blocks.get_mut(&exported_block.header.content.slot.period)
+            {
+                block_ids.push(*id);
+            }
+        }
+
+        let block_storage_lock = self.0.storage.read_blocks();
+        let operation_storage_lock = self.0.storage.read_operations();
+        let res = period_blocks
+            .into_iter()
+            .map(|(period, block_ids)| {
+                let mut fees = Vec::new();
+                let mut block_count = 0;
+                let mut fullness_sum = 0.0;
+                for block_id in block_ids {
+                    let Some(block) = block_storage_lock.get(&block_id) else {
+                        continue;
+                    };
+                    block_count += 1;
+                    let mut gas_usage: u64 = 0;
+                    for operation_id in block.content.operations.iter() {
+                        if let Some(operation) = operation_storage_lock.get(operation_id) {
+                            fees.push(operation.content.fee);
+                            gas_usage = gas_usage.saturating_add(operation.get_gas_usage(
+                                api_settings.base_operation_gas_cost,
+                                api_settings.sp_compilation_cost,
+                            ));
+                        }
+                    }
+                    let operation_ratio = block.content.operations.len() as f64
+                        / api_settings.max_operations_per_block as f64;
+                    let gas_ratio = gas_usage as f64 / api_settings.max_gas_per_block as f64;
+                    fullness_sum += operation_ratio.max(gas_ratio).min(1.0);
+                }
+                fees.sort_unstable();
+                PeriodFeeStats {
+                    period,
+                    block_count,
+                    operation_count: fees.len(),
+                    min_fee: fees.first().copied(),
+                    median_fee: fees.get(fees.len() / 2).copied(),
+                    max_fee: fees.last().copied(),
+                    block_fullness: if block_count > 0 {
+                        fullness_sum / block_count as f64
+                    } else {
+                        0.0
+                    },
+                }
+            })
+            .collect();
+        Ok(res)
+    }
+
     /// get datastore entries
     async fn get_datastore_entries(
         &self,
@@ -1095,6 +1553,9 @@ impl MassaRpcServer for API<Public> {
 
                 // cycle infos
                 cycle_infos: execution_infos.cycle_infos,
+
+                // async pool info
+                incoming_async_messages: execution_infos.incoming_async_messages,
             });
         }
 
@@ -1118,8 +1579,8 @@ impl MassaRpcServer for API<Public> {
             return Err(ApiError::BadRequest("no arguments specified".to_string()).into());
         }
 
-        if queries.len() as u64 > self.0.api_settings.max_arguments {
-            return Err(ApiError::BadRequest(format!("too many arguments received. Only a maximum of {} arguments are accepted per request", self.0.api_settings.max_arguments)).into());
+        if queries.len() as u64 > self.0.api_settings.limits.max_arguments() {
+            return Err(ApiError::BadRequest(format!("too many arguments received. Only a maximum of {} arguments are accepted per request", self.0.api_settings.limits.max_arguments())).into());
         }
 
         let responses = self
@@ -1151,7 +1612,7 @@ impl MassaRpcServer for API<Public> {
         let api_cfg = &self.0.api_settings;
         let mut to_send = self.0.storage.clone_without_refs();
 
-        if ops.len() as u64 > api_cfg.max_arguments {
+        if ops.len() as u64 > api_cfg.limits.max_arguments() {
             return Err(ApiError::BadRequest("too many arguments".into()).into());
         }
         let now = MassaTime::now();
@@ -1162,6 +1623,11 @@ impl MassaRpcServer for API<Public> {
             now,
         )
         .map_err(ApiError::ModelsError)?;
+        let execution_version = self
+            .0
+            .keypair_factory
+            .mip_store
+            .get_latest_component_version_at(&MipComponent::Execution, now);
 
         let verified_ops = ops
             .into_iter()
@@ -1181,6 +1647,13 @@ impl MassaRpcServer for API<Public> {
                         .into());
                     }
 
+                    if operation.content.op.required_execution_version() > execution_version {
+                        return Err(ApiError::BadRequest(
+                            "this type of operation is not active yet".into(),
+                        )
+                        .into());
+                    }
+
                     let _verify_signature = match operation.verify_signature() {
                         Ok(()) => (),
                         Err(e) => return Err(ApiError::ModelsError(e).into()),
@@ -1191,9 +1664,12 @@ impl MassaRpcServer for API<Public> {
             })
             .collect::<RpcResult<Vec<SecureShareOperation>>>()?;
 
+        cmd_sender
+            .check_sender_limits(&verified_ops)
+            .map_err(|err| ApiError::PoolError(err.to_string()))?;
         to_send.store_operations(verified_ops.clone());
         let ids: Vec<OperationId> = verified_ops.iter().map(|op| op.id).collect();
-        cmd_sender.add_operations(to_send.clone());
+        cmd_sender.add_local_operations(to_send.clone());
 
         tokio::task::spawn_blocking(move || protocol_sender.propagate_operations(to_send))
             .await
@@ -1204,6 +1680,62 @@ impl MassaRpcServer for API<Public> {
         Ok(ids)
     }
 
+    /// simulates an operation, without adding it to the pool nor propagating it
+    async fn simulate_operation(
+        &self,
+        input: OperationSimulationInput,
+    ) -> RpcResult<OperationSimulation> {
+        let is_signed = input.signature.is_some();
+        // the unsigned operations are given a well-formed signature (version 0, zero bytes),
+        // which is not verified
+        let signature = match input.signature {
+            Some(signature) => signature,
+            None => Signature::from_bytes(&[0u8; 65])
+                .map_err(|err| ApiError::InternalServerError(err.to_string()))?,
+        };
+        let op_input = OperationInput {
+            creator_public_key: input.creator_public_key,
+            signature,
+            serialized_content: input.serialized_content,
+        };
+        // an expired operation is reported as rejected by the simulation
+        let operation = check_input_operation(op_input, &self.0.api_settings, None)?;
+        if is_signed {
+            operation
+                .verify_signature()
+                .map_err(ApiError::ModelsError)?;
+        }
+
+        let output = self
+            .0
+            .execution_controller
+            .simulate_operation(operation.clone())
+            .map_err(|e| ApiError::ExecutionError(e.to_string()))?;
+        let status = match output.status {
+            massa_execution_exports::OperationSimulationStatus::Success => {
+                OperationSimulationStatus::Success
+            }
+            massa_execution_exports::OperationSimulationStatus::Failure => {
+                OperationSimulationStatus::Failure
+            }
+            massa_execution_exports::OperationSimulationStatus::Rejected(reason) => {
+                OperationSimulationStatus::Rejected(reason)
+            }
+        };
+        let mut output_events = output.out.events.0;
+        if let Some(event_overflow) = output.out.event_overflow {
+            output_events.extend(event_overflow.spilled_events.0);
+        }
+        Ok(OperationSimulation {
+            id: operation.id,
+            executed_at: output.out.slot,
+            status,
+            gas_usage: output.gas_usage,
+            output_events,
+            state_changes: output.out.state_changes,
+        })
+    }
+
     /// Get events optionally filtered by:
     /// * start slot
     /// * end slot
@@ -1223,15 +1755,32 @@ impl MassaRpcServer for API<Public> {
         Ok(events)
     }
 
-    async fn node_peers_whitelist(&self) -> RpcResult<Vec<IpAddr>> {
-        crate::wrong_api::<Vec<IpAddr>>()
+    async fn get_async_messages(
+        &self,
+        filter: AsyncMessageFilter,
+    ) -> RpcResult<Vec<PendingAsyncMessage>> {
+        Ok(self.0.execution_controller.get_async_messages(filter))
     }
 
-    async fn node_add_to_peers_whitelist(&self, _: Vec<IpAddr>) -> RpcResult<()> {
+    async fn get_async_message_fee_suggestion(
+        &self,
+        max_gas: u64,
+    ) -> RpcResult<AsyncMessageFeeSuggestion> {
+        Ok(self
+            .0
+            .execution_controller
+            .get_async_message_fee_suggestion(max_gas))
+    }
+
+    async fn node_peers_whitelist(&self) -> RpcResult<Vec<IpCidr>> {
+        crate::wrong_api::<Vec<IpCidr>>()
+    }
+
+    async fn node_add_to_peers_whitelist(&self, _: Vec<IpCidr>) -> RpcResult<()> {
         crate::wrong_api::<()>()
     }
 
-    async fn node_remove_from_peers_whitelist(&self, _: Vec<IpAddr>) -> RpcResult<()> {
+    async fn node_remove_from_peers_whitelist(&self, _: Vec<IpCidr>) -> RpcResult<()> {
         crate::wrong_api::<()>()
     }
 
@@ -1288,6 +1837,26 @@ impl MassaRpcServer for API<Public> {
     }
 }
 
+#[cfg(feature = "execution-trace")]
+/// recursive function to convert an ABI trace and its sub calls
+fn into_abi_call(abi_trace: &AbiTrace) -> AbiCall {
+    AbiCall {
+        name: abi_trace.name.clone(),
+        parameters: abi_trace
+            .parameters
+            .iter()
+            .map(|p| serde_json::to_value(p).unwrap_or_default())
+            .collect(),
+        return_value: serde_json::to_value(&abi_trace.return_value).unwrap_or_default(),
+        sub_calls: abi_trace
+            .sub_calls
+            .iter()
+            .flatten()
+            .map(into_abi_call)
+            .collect(),
+    }
+}
+
 /// Checks the validity of an input operation.
 ///
 /// This function takes an `OperationInput`, an `APIConfig`, and an optional `Slot` as input parameters.
@@ -1311,6 +1880,7 @@ fn check_input_operation(
 ) -> RpcResult<SecureShareOperation> {
     let operation_deserializer = SecureShareDeserializer::new(
         OperationDeserializer::new(
+            api_cfg.thread_count,
             api_cfg.max_datastore_value_length,
             api_cfg.max_function_name_length,
             api_cfg.max_parameter_size,
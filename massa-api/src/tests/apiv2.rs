@@ -1,22 +1,35 @@
-use std::{collections::BTreeMap, net::SocketAddr, str::FromStr, time::Duration};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    net::SocketAddr,
+    str::FromStr,
+    time::Duration,
+};
 
 use jsonrpsee::{
     async_client::ClientBuilder,
     client_transport::ws::{Url, WsTransportClientBuilder},
     core::client::{ClientT, Subscription, SubscriptionClientT},
+    core::params::BatchRequestBuilder,
+    http_client::HttpClientBuilder,
     rpc_params,
     ws_client::WsClientBuilder,
 };
+use massa_api_exports::block::NewBlocksFilter;
 use massa_consensus_exports::MockConsensusController;
-use massa_execution_exports::MockExecutionController;
+use massa_execution_exports::{
+    EventStore, ExecutionOutput, MockExecutionController, SlotExecutionOutput,
+};
 use massa_models::{
     address::Address,
     block::{FilledBlock, SecureShareBlock},
     block_header::BlockHeader,
     block_id::BlockId,
     config::VERSION,
+    execution::EventFilter,
     operation::SecureShareOperation,
+    output_event::{EventExecutionContext, SCOutputEvent},
     secure_share::SecureShare,
+    slot::Slot,
 };
 use massa_protocol_exports::test_exports::tools::{
     create_block, create_operation_with_expire_period,
@@ -142,6 +155,78 @@ async fn get_largest_stakers() {
     api_handle.stop().await;
 }
 
+#[tokio::test]
+async fn batch_request() {
+    let addr: SocketAddr = "[::]:5052".parse().unwrap();
+    let (mut api_server, api_config) = get_apiv2_server(&addr);
+
+    let mut consensus_ctrl = MockConsensusController::new();
+    consensus_ctrl.expect_get_best_parents().returning(|| {
+        vec![(
+            massa_models::block_id::BlockId::from_str(
+                "B12oYMQEAX35HPeDVgGdW2fYRtDs4UJTpeXqW75QPYCdEdPUZ9oV",
+            )
+            .unwrap(),
+            100,
+        )]
+    });
+
+    api_server.0.consensus_controller = Box::new(consensus_ctrl);
+
+    let api_handle = api_server
+        .serve(&addr, &api_config)
+        .await
+        .expect("failed to start MASSA API V2");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+
+    // calls executed concurrently
+    let mut batch = BatchRequestBuilder::new();
+    for _ in 0..api_config.batch_request_limit {
+        batch
+            .insert("get_next_block_best_parents", rpc_params![])
+            .unwrap();
+    }
+    let response = client
+        .batch_request::<Vec<(BlockId, u64)>>(batch)
+        .await
+        .unwrap();
+    assert_eq!(
+        response.num_successful_calls(),
+        api_config.batch_request_limit as usize
+    );
+    for parents in response.into_iter() {
+        assert_eq!(parents.unwrap()[0].1, 100);
+    }
+
+    // errors are returned in place of the failed calls
+    let mut batch = BatchRequestBuilder::new();
+    batch.insert("get_version", rpc_params![]).unwrap();
+    batch.insert("unknown_method", rpc_params![]).unwrap();
+    batch.insert("get_version", rpc_params![]).unwrap();
+    let response = client.batch_request::<String>(batch).await.unwrap();
+    assert_eq!(response.num_successful_calls(), 2);
+    assert_eq!(response.num_failed_calls(), 1);
+    let responses = response.into_iter().collect::<Vec<_>>();
+    assert_eq!(responses[0].as_ref().unwrap(), &VERSION.to_string());
+    assert!(responses[1].is_err());
+    assert_eq!(responses[2].as_ref().unwrap(), &VERSION.to_string());
+
+    // batches larger than the limit are rejected
+    let mut batch = BatchRequestBuilder::new();
+    for _ in 0..=api_config.batch_request_limit {
+        batch.insert("get_version", rpc_params![]).unwrap();
+    }
+    assert!(client.batch_request::<String>(batch).await.is_err());
+
+    api_handle.stop().await;
+}
+
 #[tokio::test]
 async fn subscribe_new_blocks() {
     let addr: SocketAddr = "[::]:5033".parse().unwrap();
@@ -333,3 +418,162 @@ async fn subscribe_new_operations() {
 
     api_handle.stop().await;
 }
+
+#[tokio::test]
+async fn subscribe_new_blocks_with_filter() {
+    let addr: SocketAddr = "[::]:5050".parse().unwrap();
+    let (mut api_server, api_config) = get_apiv2_server(&addr);
+
+    let (tx, _rx) = tokio::sync::broadcast::channel::<SecureShareBlock>(10);
+
+    api_server.0.consensus_broadcasts.block_sender = tx.clone();
+
+    let block_1 = create_block(&KeyPair::generate(0).unwrap());
+    let block_2 = create_block(&KeyPair::generate(0).unwrap());
+
+    let api_handle = api_server
+        .serve(&addr, &api_config)
+        .await
+        .expect("failed to start MASSA API V2");
+
+    let uri = Url::parse(&format!(
+        "ws://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .unwrap();
+
+    let client1 = WsClientBuilder::default().build(&uri).await.unwrap();
+
+    // empty slot range
+    let filter = NewBlocksFilter {
+        start_slot: Some(Slot::new(2, 0)),
+        end_slot: Some(Slot::new(1, 0)),
+        ..Default::default()
+    };
+    let result: Result<Subscription<Value>, _> = client1
+        .subscribe(
+            "subscribe_new_blocks",
+            rpc_params![filter],
+            "unsubscribe_new_blocks",
+        )
+        .await;
+    assert!(result.is_err());
+
+    let filter = NewBlocksFilter {
+        addresses: Some(vec![block_2.content_creator_address]),
+        ..Default::default()
+    };
+    let mut sub1: Subscription<Value> = client1
+        .subscribe(
+            "subscribe_new_blocks",
+            rpc_params![filter],
+            "unsubscribe_new_blocks",
+        )
+        .await
+        .unwrap();
+
+    let to_send = vec![block_1.clone(), block_2.clone()];
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        for block in to_send {
+            let _ = tx.send(block).unwrap();
+        }
+    });
+
+    let result = tokio::time::timeout(Duration::from_secs(4), sub1.next())
+        .await
+        .unwrap();
+
+    // the block of the other creator is skipped
+    let value = result.unwrap().unwrap();
+    assert_eq!(value["id"].as_str().unwrap(), &block_2.id.to_string());
+
+    api_handle.stop().await;
+}
+
+#[tokio::test]
+async fn subscribe_sc_events() {
+    let addr: SocketAddr = "[::]:5051".parse().unwrap();
+    let (mut api_server, api_config) = get_apiv2_server(&addr);
+
+    let (tx, _rx) = tokio::sync::broadcast::channel::<SlotExecutionOutput>(10);
+
+    api_server.0.execution_channels.slot_execution_output_sender = tx.clone();
+
+    let api_handle = api_server
+        .serve(&addr, &api_config)
+        .await
+        .expect("failed to start MASSA API V2");
+
+    let uri = Url::parse(&format!(
+        "ws://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .unwrap();
+
+    let emitter_1 = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+    let emitter_2 = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+    let slot = Slot::new(1, 0);
+    let events = [emitter_1, emitter_2]
+        .into_iter()
+        .enumerate()
+        .map(|(index, emitter)| SCOutputEvent {
+            context: EventExecutionContext {
+                slot,
+                block: None,
+                read_only: false,
+                index_in_slot: index as u64,
+                call_stack: VecDeque::from(vec![emitter]),
+                origin_operation_id: None,
+                is_final: false,
+                is_error: false,
+            },
+            data: format!("event {}", index),
+        })
+        .collect::<VecDeque<_>>();
+    let output = ExecutionOutput {
+        slot,
+        block_info: None,
+        state_changes: massa_final_state::StateChanges::default(),
+        events: EventStore(events),
+        event_overflow: None,
+        stats: Default::default(),
+        final_ledger_reads: Default::default(),
+        #[cfg(feature = "execution-trace")]
+        slot_trace: None,
+    };
+
+    let client1 = WsClientBuilder::default().build(&uri).await.unwrap();
+    let filter = EventFilter {
+        emitter_address: Some(emitter_2),
+        is_final: Some(true),
+        ..Default::default()
+    };
+    let mut sub1: Subscription<Value> = client1
+        .subscribe(
+            "subscribe_sc_events",
+            rpc_params![filter],
+            "unsubscribe_sc_events",
+        )
+        .await
+        .unwrap();
+
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let _ = tx
+            .send(SlotExecutionOutput::ExecutedSlot(output.clone()))
+            .unwrap();
+        let _ = tx.send(SlotExecutionOutput::FinalizedSlot(output)).unwrap();
+    });
+
+    let result = tokio::time::timeout(Duration::from_secs(4), sub1.next())
+        .await
+        .unwrap();
+
+    // only the final event of the second emitter is received
+    let value = result.unwrap().unwrap();
+    assert_eq!(value["data"].as_str().unwrap(), "event 1");
+    assert!(value["context"]["is_final"].as_bool().unwrap());
+
+    api_handle.stop().await;
+}
@@ -4,15 +4,15 @@
 
 use std::{collections::HashMap, net::SocketAddr};
 
-use massa_api_exports::config::APIConfig;
+use massa_api_exports::config::{APIConfig, ApiLimits, CorsConfig};
 use massa_consensus_exports::{ConsensusBroadcasts, MockConsensusController};
-use massa_execution_exports::{GasCosts, MockExecutionController};
+use massa_execution_exports::{ExecutionChannels, GasCosts, MockExecutionController};
 use massa_models::amount::Amount;
 use massa_models::config::CHAINID;
 use massa_models::{
     config::{
         BASE_OPERATION_GAS_COST, ENDORSEMENT_COUNT, GENESIS_TIMESTAMP, MAX_DATASTORE_VALUE_LENGTH,
-        MAX_FUNCTION_NAME_LENGTH, MAX_GAS_PER_BLOCK, MAX_MESSAGE_SIZE,
+        MAX_FUNCTION_NAME_LENGTH, MAX_GAS_PER_BLOCK, MAX_MESSAGE_SIZE, MAX_OPERATIONS_PER_BLOCK,
         MAX_OPERATION_DATASTORE_ENTRY_COUNT, MAX_OPERATION_DATASTORE_KEY_LENGTH,
         MAX_OPERATION_DATASTORE_VALUE_LENGTH, MAX_PARAMETERS_SIZE,
         MIP_STORE_STATS_BLOCK_CONSIDERED, PERIODS_PER_CYCLE, T0, THREAD_COUNT, VERSION,
@@ -21,7 +21,9 @@ use massa_models::{
 };
 use massa_pool_exports::{MockPoolController, PoolBroadcasts};
 use massa_pos_exports::MockSelectorController;
-use massa_protocol_exports::{MockProtocolController, PeerCategoryInfo, ProtocolConfig};
+use massa_protocol_exports::{
+    BandwidthLimits, MockProtocolController, PeerCategoryInfo, ProtocolConfig,
+};
 use massa_signature::KeyPair;
 use massa_time::MassaTime;
 use massa_versioning::versioning::{MipStatsConfig, MipStore};
@@ -31,6 +33,15 @@ use tokio::sync::broadcast;
 
 use crate::{ApiV2, Public, API};
 
+/// CORS policy of the APIs in the default configuration
+pub(crate) fn default_cors_config() -> CorsConfig {
+    CorsConfig {
+        allowed_origins: vec!["*".to_string()],
+        allowed_methods: vec!["POST".to_string(), "OPTIONS".to_string()],
+        allowed_headers: vec!["content-type".to_string()],
+    }
+}
+
 pub(crate) fn get_apiv2_server(addr: &SocketAddr) -> (API<ApiV2>, APIConfig) {
     let keypair = KeyPair::generate(0).unwrap();
     let api_config: APIConfig = APIConfig {
@@ -38,7 +49,7 @@ pub(crate) fn get_apiv2_server(addr: &SocketAddr) -> (API<ApiV2>, APIConfig) {
         bind_public: "[::]:0".parse().unwrap(),
         bind_api: *addr,
         draw_lookahead_period_count: 10,
-        max_arguments: 128,
+        limits: ApiLimits::new(128),
         openrpc_spec_path: "base_config/openrpc.json".parse().unwrap(),
         bootstrap_whitelist_path: "base_config/bootstrap_whitelist.json".parse().unwrap(),
         bootstrap_blacklist_path: "base_config/bootstrap_blacklist.json".parse().unwrap(),
@@ -48,7 +59,14 @@ pub(crate) fn get_apiv2_server(addr: &SocketAddr) -> (API<ApiV2>, APIConfig) {
         max_subscriptions_per_connection: 1024,
         max_log_length: 4096,
         allow_hosts: vec![],
+        cors_public: default_cors_config(),
+        cors_private: default_cors_config(),
+        cors_api: default_cors_config(),
+        ipc_public: None,
+        ipc_private: None,
+        ipc_api: None,
         batch_request_limit: 16,
+        batch_request_concurrency: 4,
         ping_interval: MassaTime::from_millis(60000),
         enable_http: true,
         enable_ws: true,
@@ -57,6 +75,7 @@ pub(crate) fn get_apiv2_server(addr: &SocketAddr) -> (API<ApiV2>, APIConfig) {
         max_op_datastore_key_length: MAX_OPERATION_DATASTORE_KEY_LENGTH,
         max_op_datastore_value_length: MAX_OPERATION_DATASTORE_VALUE_LENGTH,
         max_gas_per_block: MAX_GAS_PER_BLOCK,
+        max_operations_per_block: MAX_OPERATIONS_PER_BLOCK,
         base_operation_gas_cost: BASE_OPERATION_GAS_COST,
         sp_compilation_cost: GasCosts::default().sp_compilation_cost,
         max_function_name_length: MAX_FUNCTION_NAME_LENGTH,
@@ -69,6 +88,7 @@ pub(crate) fn get_apiv2_server(addr: &SocketAddr) -> (API<ApiV2>, APIConfig) {
         last_start_period: 0,
         chain_id: *CHAINID,
         minimal_fees: Amount::zero(),
+        pruned_history_periods: None,
     };
 
     // let shared_storage: massa_storage::Storage = massa_storage::Storage::create_root();
@@ -92,12 +112,21 @@ pub(crate) fn get_apiv2_server(addr: &SocketAddr) -> (API<ApiV2>, APIConfig) {
         block_header_sender: broadcast::channel(100).0,
         block_sender: broadcast::channel(100).0,
         filled_block_sender: broadcast::channel(100).0,
+        reorg_sender: broadcast::channel(100).0,
+    };
+
+    let execution_channels = ExecutionChannels {
+        slot_execution_output_sender: broadcast::channel(100).0,
+        slot_execution_serialized_output_sender: broadcast::channel(100).0,
+        #[cfg(feature = "execution-trace")]
+        slot_execution_traces_sender: broadcast::channel(100).0,
     };
 
     let api = API::<ApiV2>::new(
         Box::new(consensus_ctrl),
         consensus_broadcasts,
         Box::new(exec_ctrl),
+        execution_channels,
         pool_broadcasts,
         api_config.clone(),
         *VERSION,
@@ -113,7 +142,7 @@ pub(crate) fn start_public_api(addr: SocketAddr) -> (API<Public>, APIConfig) {
         bind_public: addr,
         bind_api: "[::]:0".parse().unwrap(),
         draw_lookahead_period_count: 10,
-        max_arguments: 128,
+        limits: ApiLimits::new(128),
         openrpc_spec_path: "base_config/openrpc.json".parse().unwrap(),
         bootstrap_whitelist_path: "base_config/bootstrap_whitelist.json".parse().unwrap(),
         bootstrap_blacklist_path: "base_config/bootstrap_blacklist.json".parse().unwrap(),
@@ -123,7 +152,14 @@ pub(crate) fn start_public_api(addr: SocketAddr) -> (API<Public>, APIConfig) {
         max_subscriptions_per_connection: 1024,
         max_log_length: 4096,
         allow_hosts: vec![],
+        cors_public: default_cors_config(),
+        cors_private: default_cors_config(),
+        cors_api: default_cors_config(),
+        ipc_public: None,
+        ipc_private: None,
+        ipc_api: None,
         batch_request_limit: 16,
+        batch_request_concurrency: 4,
         ping_interval: MassaTime::from_millis(60000),
         enable_http: true,
         enable_ws: true,
@@ -132,6 +168,7 @@ pub(crate) fn start_public_api(addr: SocketAddr) -> (API<Public>, APIConfig) {
         max_op_datastore_key_length: MAX_OPERATION_DATASTORE_KEY_LENGTH,
         max_op_datastore_value_length: MAX_OPERATION_DATASTORE_VALUE_LENGTH,
         max_gas_per_block: MAX_GAS_PER_BLOCK,
+        max_operations_per_block: MAX_OPERATIONS_PER_BLOCK,
         base_operation_gas_cost: BASE_OPERATION_GAS_COST,
         sp_compilation_cost: GasCosts::default().sp_compilation_cost,
         max_function_name_length: MAX_FUNCTION_NAME_LENGTH,
@@ -144,6 +181,7 @@ pub(crate) fn start_public_api(addr: SocketAddr) -> (API<Public>, APIConfig) {
         last_start_period: 0,
         chain_id: *CHAINID,
         minimal_fees: Amount::zero(),
+        pruned_history_periods: None,
     };
 
     let shared_storage: massa_storage::Storage = massa_storage::Storage::create_root();
@@ -207,6 +245,11 @@ pub(crate) fn start_public_api(addr: SocketAddr) -> (API<Public>, APIConfig) {
                 .expect("cannot create temp file")
                 .path()
                 .to_path_buf(),
+            ip_ban_list_file: NamedTempFile::new()
+                .expect("cannot create temp file")
+                .path()
+                .to_path_buf(),
+            asn_database_file: None,
             listeners: HashMap::default(),
             thread_tester_count: 2,
             max_size_channel_commands_connectivity: 1000,
@@ -241,7 +284,11 @@ pub(crate) fn start_public_api(addr: SocketAddr) -> (API<Public>, APIConfig) {
             timeout_connection: MassaTime::from_millis(1000),
             try_connection_timer: MassaTime::from_millis(5000),
             unban_everyone_timer: MassaTime::from_millis(3600000),
+            peer_score_half_life: MassaTime::from_millis(3600000),
+            peer_score_throttle_threshold: -20.0,
+            peer_score_ban_threshold: -50.0,
             routable_ip: None,
+            additional_routable_ips: Vec::new(),
             max_in_connections: 10,
             debug: true,
             peers_categories: HashMap::default(),
@@ -255,6 +302,14 @@ pub(crate) fn start_public_api(addr: SocketAddr) -> (API<Public>, APIConfig) {
             try_connection_timer_same_peer: MassaTime::from_millis(1000),
             test_oldest_peer_cooldown: MassaTime::from_millis(720000),
             rate_limit: 1024 * 1024 * 2,
+            bandwidth_limits: BandwidthLimits::default(),
+            port_mapping: false,
+            port_mapping_lease_duration: MassaTime::from_millis(3600000),
+            reachability_timeout: MassaTime::from_millis(600000),
+            dns_seeds: Vec::new(),
+            dns_seed_refresh_interval: MassaTime::from_millis(3600000),
+            compact_block_relay: false,
+            proxy: None,
             chain_id: *CHAINID,
         },
         *VERSION,
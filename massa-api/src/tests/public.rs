@@ -14,44 +14,62 @@ use jsonrpsee::{
 };
 use massa_api_exports::{
     address::{AddressFilter, AddressInfo},
-    block::{BlockInfo, BlockSummary},
+    block::{BlockInfo, BlockSummary, FinalityProof, SlotBlock},
     datastore::{DatastoreEntryInput, DatastoreEntryOutput},
     endorsement::EndorsementInfo,
-    execution::{ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall},
-    operation::{OperationInfo, OperationInput},
+    execution::{
+        DatastoreEntryOverride, ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall,
+        StateOverride,
+    },
+    fee::PeriodFeeStats,
+    operation::{
+        AddressOperation, OperationInfo, OperationInput, OperationSimulation,
+        OperationSimulationInput, OperationSimulationStatus, PoolOperations,
+    },
+    page::PageRequest,
     TimeInterval,
 };
 use massa_consensus_exports::{
-    block_graph_export::BlockGraphExport, block_status::ExportCompiledBlock,
+    block_graph_export::BlockGraphExport, block_status::ExportCompiledBlock, error::ConsensusError,
     MockConsensusController,
 };
-use massa_pool_exports::MockPoolController;
+use massa_pool_exports::{MockPoolController, PoolError};
 use massa_pos_exports::MockSelectorController;
 
 use crate::{tests::mock::start_public_api, RpcServer};
 use massa_execution_exports::{
-    ExecutionAddressInfo, ExecutionQueryResponse, ExecutionQueryResponseItem,
-    MockExecutionController, ReadOnlyExecutionOutput,
+    ExecutionAddressInfo, ExecutionError, ExecutionQueryResponse, ExecutionQueryResponseItem,
+    MockExecutionController, OperationSimulationOutput, ReadOnlyExecutionOutput,
 };
 use massa_models::{
     address::Address,
     amount::Amount,
-    block::{Block, BlockGraphStatus},
+    block::{Block, BlockFinalityProof, BlockGraphStatus, DiscardedBlock, DiscardedBlockReason},
+    block_id::BlockId,
     bytecode::Bytecode,
-    clique::Clique,
+    clique::{BlockcliqueChoice, Clique, CliqueDetails},
+    config::CHAINID,
     endorsement::EndorsementId,
-    execution::EventFilter,
+    execution::{AsyncMessageFeeSuggestion, AsyncMessageFilter, EventFilter, PendingAsyncMessage},
     node::NodeId,
-    operation::OperationId,
+    operation::{
+        Operation, OperationAddressRole, OperationId, OperationKind, OperationSerializer,
+        OperationType, PoolOperationSort, PoolOperationSummary, SecureShareOperation,
+    },
     output_event::SCOutputEvent,
     prehash::{CapacityAllocator, PreHashMap},
+    secure_share::SecureShareContent,
     slot::Slot,
-    stats::{ConsensusStats, ExecutionStats, NetworkStats},
+    stats::{
+        ConsensusStats, CycleStaleBlockStats, ExecutionStats, NetworkStats, OperationPoolStats,
+        PoolStats, Reachability, SlotEndorsementCoverage, StaleBlock, StaleBlockCreator,
+    },
 };
 use massa_protocol_exports::{
     test_exports::tools::{
-        create_block, create_call_sc_op_with_too_much_gas, create_endorsement,
-        create_execute_sc_op_with_too_much_gas, create_operation_with_expire_period,
+        create_block, create_block_with_operations, create_call_sc_op_with_too_much_gas,
+        create_endorsement, create_execute_sc_op_with_too_much_gas,
+        create_operation_with_expire_period,
     },
     MockProtocolController,
 };
@@ -96,6 +114,8 @@ async fn get_status() {
                 known_peer_count: 6,
                 banned_peer_count: 0,
                 active_node_count: 15,
+                reachability: Reachability::Unknown,
+                mapped_address: None,
             },
             HashMap::new(),
         ))
@@ -127,6 +147,7 @@ async fn get_status() {
 
     assert_eq!(response.network_stats.in_connection_count, 10);
     assert_eq!(response.network_stats.out_connection_count, 5);
+    assert_eq!(response.network_stats.reachability, Reachability::Unknown);
     assert_eq!(response.config.thread_count, 32);
     // Chain id == 77 for Node in sandbox mode otherwise it is always greater
     assert!(response.chain_id > 77);
@@ -166,6 +187,241 @@ async fn get_cliques() {
     api_public_handle.stop().await;
 }
 
+#[tokio::test]
+async fn get_cliques_details() {
+    let addr: SocketAddr = "[::]:5061".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+
+    let mut consensus_ctrl = MockConsensusController::new();
+    consensus_ctrl.expect_get_cliques_details().returning(|| {
+        Ok(vec![
+            CliqueDetails {
+                blocks: vec![],
+                fitness: 10,
+                fitness_gap: 0,
+                is_blockclique: true,
+                choice: BlockcliqueChoice::WonTieBreak,
+            },
+            CliqueDetails {
+                blocks: vec![],
+                fitness: 10,
+                fitness_gap: 0,
+                is_blockclique: false,
+                choice: BlockcliqueChoice::LostTieBreak,
+            },
+        ])
+    });
+
+    api_public.0.consensus_controller = Box::new(consensus_ctrl);
+
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+    let response: Vec<CliqueDetails> = client
+        .request("get_cliques_details", rpc_params![])
+        .await
+        .unwrap();
+
+    assert_eq!(response.len(), 2);
+    assert!(response[0].is_blockclique);
+    assert_eq!(response[0].choice, BlockcliqueChoice::WonTieBreak);
+    assert_eq!(response[1].choice, BlockcliqueChoice::LostTieBreak);
+
+    api_public_handle.stop().await;
+}
+
+#[tokio::test]
+async fn get_stale_block_stats() {
+    let addr: SocketAddr = "[::]:5062".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+
+    let creator =
+        Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x").unwrap();
+    let block_id = BlockId::generate_from_hash(massa_hash::Hash::compute_from(b"stale"));
+    let mut consensus_ctrl = MockConsensusController::new();
+    consensus_ctrl
+        .expect_get_stale_block_stats()
+        .returning(move || {
+            vec![CycleStaleBlockStats {
+                cycle: 2,
+                stale_block_count: 1,
+                creators: vec![StaleBlockCreator {
+                    address: creator,
+                    stale_block_count: 1,
+                }],
+                blocks: vec![StaleBlock {
+                    block_id,
+                    slot: Slot::new(300, 4),
+                    creator,
+                }],
+            }]
+        });
+
+    api_public.0.consensus_controller = Box::new(consensus_ctrl);
+
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+    let response: Vec<CycleStaleBlockStats> = client
+        .request("get_stale_block_stats", rpc_params![])
+        .await
+        .unwrap();
+
+    assert_eq!(response.len(), 1);
+    assert_eq!(response[0].cycle, 2);
+    assert_eq!(response[0].creators[0].address, creator);
+    assert_eq!(response[0].blocks[0].slot, Slot::new(300, 4));
+
+    api_public_handle.stop().await;
+}
+
+#[tokio::test]
+async fn get_discarded_blocks() {
+    let addr: SocketAddr = "[::]:5063".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+
+    let creator =
+        Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x").unwrap();
+    let mut consensus_ctrl = MockConsensusController::new();
+    consensus_ctrl
+        .expect_get_discarded_blocks()
+        .returning(move || {
+            (1..=3)
+                .map(|period| DiscardedBlock {
+                    block_id: BlockId::generate_from_hash(massa_hash::Hash::compute_from(&[
+                        period,
+                    ])),
+                    slot: Slot::new(period as u64, 0),
+                    creator,
+                    parents: vec![],
+                    reason: DiscardedBlockReason::Invalid(format!("invalid block {}", period)),
+                })
+                .collect()
+        });
+
+    api_public.0.consensus_controller = Box::new(consensus_ctrl);
+
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+    let response: Vec<DiscardedBlock> = client
+        .request("get_discarded_blocks", rpc_params![])
+        .await
+        .unwrap();
+    assert_eq!(response.len(), 3);
+    assert_eq!(
+        response[0].reason,
+        DiscardedBlockReason::Invalid("invalid block 1".to_string())
+    );
+
+    let response: Vec<DiscardedBlock> = client
+        .request(
+            "get_discarded_blocks",
+            rpc_params![PageRequest {
+                limit: 2,
+                offset: 1
+            }],
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.len(), 1);
+    assert_eq!(response[0].slot, Slot::new(3, 0));
+
+    api_public_handle.stop().await;
+}
+
+#[tokio::test]
+async fn get_finality_proof() {
+    let addr: SocketAddr = "[::]:5064".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+
+    let keypair = KeyPair::generate(0).unwrap();
+    let final_block = create_block(&keypair);
+    let final_block_id = final_block.id;
+    let mut consensus_ctrl = MockConsensusController::new();
+    consensus_ctrl
+        .expect_get_finality_proof()
+        .returning(move |block_id| {
+            if block_id != final_block_id {
+                return Err(ConsensusError::BlockNotFinal(block_id.to_string()));
+            }
+            Ok(BlockFinalityProof {
+                header: final_block.content.header.clone(),
+                endorsements: vec![],
+                descendants: vec![],
+                descendants_fitness: 0,
+                fitness_threshold: 0,
+            })
+        });
+    let mut exec_ctrl = MockExecutionController::new();
+    exec_ctrl
+        .expect_query_state()
+        .returning(|_| ExecutionQueryResponse {
+            responses: vec![],
+            candidate_cursor: Slot::new(3, 0),
+            final_cursor: Slot::new(2, 0),
+            final_state_fingerprint: massa_hash::Hash::compute_from(b"final state"),
+        });
+
+    api_public.0.consensus_controller = Box::new(consensus_ctrl);
+    api_public.0.execution_controller = Box::new(exec_ctrl);
+
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+    let response: FinalityProof = client
+        .request("get_finality_proof", rpc_params![final_block_id])
+        .await
+        .unwrap();
+    assert_eq!(response.block_id, final_block_id);
+    assert_eq!(response.graph_proof.header.id, final_block_id);
+    assert_eq!(response.final_cursor, Slot::new(2, 0));
+    assert_eq!(
+        response.final_state_fingerprint,
+        massa_hash::Hash::compute_from(b"final state")
+    );
+
+    let other_block_id = create_block(&KeyPair::generate(0).unwrap()).id;
+    let response: Result<FinalityProof, Error> = client
+        .request("get_finality_proof", rpc_params![other_block_id])
+        .await;
+    assert!(response.is_err());
+
+    api_public_handle.stop().await;
+}
+
 #[tokio::test]
 async fn get_operations() {
     let addr: SocketAddr = "[::]:5003".parse().unwrap();
@@ -210,6 +466,243 @@ async fn get_operations() {
     api_public_handle.stop().await;
 }
 
+#[tokio::test]
+async fn get_operations_by_address() {
+    let addr: SocketAddr = "[::]:5057".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+
+    let op_id =
+        OperationId::from_str("O1q4CBcuYo8YANEV34W4JRWVHrzcYns19VJfyAB7jT4qfitAnMC").unwrap();
+    let mut exec_ctrl = MockExecutionController::new();
+    exec_ctrl
+        .expect_get_operations_by_address()
+        .returning(move |_address, role, offset, limit| match role {
+            OperationAddressRole::Emitter => Ok(vec![(Slot::new(3, 0), op_id)]
+                .into_iter()
+                .skip(offset)
+                .take(limit)
+                .collect()),
+            OperationAddressRole::Recipient => Err(ExecutionError::OperationIndexError(
+                "the operation index is disabled".to_string(),
+            )),
+        });
+    api_public.0.execution_controller = Box::new(exec_ctrl);
+
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+
+    let address =
+        Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x").unwrap();
+    let response: Vec<AddressOperation> = client
+        .request(
+            "get_operations_by_address",
+            rpc_params![address, OperationAddressRole::Emitter],
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.len(), 1);
+    assert_eq!(response[0].id, op_id);
+    assert_eq!(response[0].slot, Slot::new(3, 0));
+
+    // second page
+    let response: Vec<AddressOperation> = client
+        .request(
+            "get_operations_by_address",
+            rpc_params![
+                address,
+                OperationAddressRole::Emitter,
+                PageRequest {
+                    limit: 1,
+                    offset: 1
+                }
+            ],
+        )
+        .await
+        .unwrap();
+    assert!(response.is_empty());
+
+    // page too large
+    let response: Result<Vec<AddressOperation>, Error> = client
+        .request(
+            "get_operations_by_address",
+            rpc_params![
+                address,
+                OperationAddressRole::Emitter,
+                PageRequest {
+                    limit: config.limits.max_arguments() as usize + 1,
+                    offset: 0
+                }
+            ],
+        )
+        .await;
+    assert!(response.unwrap_err().to_string().contains("page limit"));
+
+    let response: Result<Vec<AddressOperation>, Error> = client
+        .request(
+            "get_operations_by_address",
+            rpc_params![address, OperationAddressRole::Recipient],
+        )
+        .await;
+    assert!(response
+        .unwrap_err()
+        .to_string()
+        .contains("operation index is disabled"));
+
+    api_public_handle.stop().await;
+}
+
+#[tokio::test]
+async fn get_pool_operations() {
+    let addr: SocketAddr = "[::]:5065".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+
+    let op_id =
+        OperationId::from_str("O1q4CBcuYo8YANEV34W4JRWVHrzcYns19VJfyAB7jT4qfitAnMC").unwrap();
+    let sender =
+        Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x").unwrap();
+    let mut pool_ctrl = MockPoolController::new();
+    pool_ctrl
+        .expect_get_pool_operations()
+        .returning(move |sort, offset, limit| {
+            let summary = PoolOperationSummary {
+                id: op_id,
+                sender,
+                fee: Amount::from_raw(1000),
+                kind: OperationKind::Transaction,
+                expire_period: 10,
+                added_at: MassaTime::from_millis(0),
+            };
+            let operations = match sort {
+                PoolOperationSort::Fee => vec![summary],
+                PoolOperationSort::Age => Vec::new(),
+            };
+            let stats = OperationPoolStats {
+                operation_count: 1,
+                max_operation_count: 1000,
+                total_size: 100,
+                sender_count: 1,
+            };
+            (
+                operations.into_iter().skip(offset).take(limit).collect(),
+                stats,
+            )
+        });
+    api_public.0.pool_command_sender = Box::new(pool_ctrl);
+
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+
+    let response: PoolOperations = client
+        .request("get_pool_operations", rpc_params![PoolOperationSort::Fee])
+        .await
+        .unwrap();
+    assert_eq!(response.operations.len(), 1);
+    assert_eq!(response.operations[0].id, op_id);
+    assert_eq!(response.operations[0].kind, OperationKind::Transaction);
+    assert_eq!(response.stats.operation_count, 1);
+
+    // second page
+    let response: PoolOperations = client
+        .request(
+            "get_pool_operations",
+            rpc_params![
+                PoolOperationSort::Fee,
+                PageRequest {
+                    limit: 1,
+                    offset: 1
+                }
+            ],
+        )
+        .await
+        .unwrap();
+    assert!(response.operations.is_empty());
+    assert_eq!(response.stats.operation_count, 1);
+
+    // page too large
+    let response: Result<PoolOperations, Error> = client
+        .request(
+            "get_pool_operations",
+            rpc_params![
+                PoolOperationSort::Age,
+                PageRequest {
+                    limit: config.limits.max_arguments() as usize + 1,
+                    offset: 0
+                }
+            ],
+        )
+        .await;
+    assert!(response.unwrap_err().to_string().contains("page limit"));
+
+    api_public_handle.stop().await;
+}
+
+#[tokio::test]
+async fn get_pool_stats() {
+    let addr: SocketAddr = "[::]:5067".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+
+    let mut pool_ctrl = MockPoolController::new();
+    pool_ctrl.expect_get_pool_stats().returning(|| PoolStats {
+        operation_count: 1,
+        endorsement_count: 3,
+        denunciation_count: 0,
+        oldest_endorsement_age: Some(MassaTime::from_millis(2000)),
+        oldest_denunciation_age: None,
+        max_block_endorsement_count: 16,
+        endorsement_slots: vec![SlotEndorsementCoverage {
+            slot: Slot::new(1, 2),
+            endorsement_count: 3,
+            indexes: vec![0, 1],
+        }],
+    });
+    api_public.0.pool_command_sender = Box::new(pool_ctrl);
+
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+
+    let response: PoolStats = client
+        .request("get_pool_stats", rpc_params![])
+        .await
+        .unwrap();
+    assert_eq!(response.endorsement_count, 3);
+    assert_eq!(
+        response.oldest_endorsement_age,
+        Some(MassaTime::from_millis(2000))
+    );
+    assert!(response.oldest_denunciation_age.is_none());
+    assert_eq!(response.endorsement_slots.len(), 1);
+    assert_eq!(response.endorsement_slots[0].indexes, vec![0, 1]);
+
+    api_public_handle.stop().await;
+}
+
 #[tokio::test]
 async fn get_endorsements() {
     let addr: SocketAddr = "[::]:5005".parse().unwrap();
@@ -362,6 +855,82 @@ async fn get_blockclique_block_by_slot() {
     api_public_handle.stop().await;
 }
 
+#[tokio::test]
+async fn get_blocks_by_slot_range() {
+    let addr: SocketAddr = "[::]:5056".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+
+    let block =
+        create_block_with_operations(&KeyPair::generate(0).unwrap(), Slot::new(1, 1), vec![]);
+    let id = block.id;
+
+    api_public.0.storage.store_block(block.clone());
+
+    let mut consensus_ctrl = MockConsensusController::new();
+    consensus_ctrl
+        .expect_get_blockclique_blocks_in_slot_range()
+        .returning(move |_start, _end| vec![(Slot::new(1, 1), id)]);
+
+    api_public.0.consensus_controller = Box::new(consensus_ctrl);
+
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+
+    // end slot before start slot
+    let response: Result<Vec<SlotBlock>, Error> = client
+        .request(
+            "get_blocks_by_slot_range",
+            rpc_params![Slot::new(2, 0), Slot::new(1, 0)],
+        )
+        .await;
+    assert!(response
+        .unwrap_err()
+        .to_string()
+        .contains("start must be before end"));
+
+    // too many slots
+    let response: Result<Vec<SlotBlock>, Error> = client
+        .request(
+            "get_blocks_by_slot_range",
+            rpc_params![Slot::new(0, 0), Slot::new(config.limits.max_arguments(), 0)],
+        )
+        .await;
+    assert!(response.unwrap_err().to_string().contains("slot range"));
+
+    let response: Vec<SlotBlock> = client
+        .request(
+            "get_blocks_by_slot_range",
+            rpc_params![Slot::new(1, 0), Slot::new(2, 0)],
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.len(), 1);
+    assert_eq!(response[0].id, id);
+    assert_eq!(response[0].slot, Slot::new(1, 1));
+    assert!(response[0].header.is_none());
+
+    let response: Vec<SlotBlock> = client
+        .request(
+            "get_blocks_by_slot_range",
+            rpc_params![Slot::new(1, 0), Slot::new(2, 0), true],
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.len(), 1);
+    assert_eq!(response[0].header.as_ref().unwrap().id, id);
+
+    api_public_handle.stop().await;
+}
+
 #[tokio::test]
 async fn get_graph_interval() {
     let addr: SocketAddr = "[::]:5008".parse().unwrap();
@@ -449,6 +1018,100 @@ async fn get_graph_interval() {
     api_public_handle.stop().await;
 }
 
+#[tokio::test]
+async fn get_fee_history() {
+    let addr: SocketAddr = "[::]:5053".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+
+    let keypair = KeyPair::generate(0).unwrap();
+    let operations: Vec<SecureShareOperation> = [3, 1, 2]
+        .into_iter()
+        .map(|fee| {
+            Operation::new_verifiable(
+                Operation {
+                    fee: Amount::from_str(&fee.to_string()).unwrap(),
+                    op: OperationType::Transaction {
+                        recipient_address: Address::from_public_key(&keypair.get_public_key()),
+                        amount: Amount::zero(),
+                    },
+                    expire_period: 10,
+                },
+                OperationSerializer::new(),
+                &keypair,
+                *CHAINID,
+            )
+            .unwrap()
+        })
+        .collect();
+    let block = create_block_with_operations(&keypair, Slot::new(1, 0), operations.clone());
+    let block_id = block.id;
+
+    api_public.0.storage.store_operations(operations);
+    api_public.0.storage.store_block(block.clone());
+
+    let mut consensus_ctrl = MockConsensusController::new();
+    consensus_ctrl
+        .expect_get_block_graph_status()
+        .returning(move |_start, _end| {
+            let mut active = PreHashMap::with_capacity(1);
+            active.insert(
+                block_id,
+                ExportCompiledBlock {
+                    header: block.content.header.clone(),
+                    children: vec![],
+                    is_final: true,
+                },
+            );
+            Ok(BlockGraphExport {
+                genesis_blocks: vec![],
+                active_blocks: active,
+                discarded_blocks: PreHashMap::with_capacity(0),
+                best_parents: vec![],
+                latest_final_blocks_periods: vec![(block_id, 1), (block_id, 2)],
+                gi_head: PreHashMap::with_capacity(0),
+                max_cliques: vec![Clique::default()],
+            })
+        });
+
+    api_public.0.consensus_controller = Box::new(consensus_ctrl);
+
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+
+    let response: Result<Vec<PeriodFeeStats>, Error> =
+        client.request("get_fee_history", rpc_params![0]).await;
+    assert!(response.unwrap_err().to_string().contains("last_n_periods"));
+
+    let response: Vec<PeriodFeeStats> = client
+        .request("get_fee_history", rpc_params![3])
+        .await
+        .unwrap();
+
+    // periods 0 and 1, the last period final in every thread being 1
+    assert_eq!(response.len(), 2);
+    assert_eq!(response[0].period, 0);
+    assert_eq!(response[0].block_count, 0);
+    assert_eq!(response[0].min_fee, None);
+    assert_eq!(response[1].period, 1);
+    assert_eq!(response[1].block_count, 1);
+    assert_eq!(response[1].operation_count, 3);
+    assert_eq!(response[1].min_fee, Some(Amount::from_str("1").unwrap()));
+    assert_eq!(response[1].median_fee, Some(Amount::from_str("2").unwrap()));
+    assert_eq!(response[1].max_fee, Some(Amount::from_str("3").unwrap()));
+    assert!(response[1].block_fullness > 0.0);
+
+    api_public_handle.stop().await;
+}
+
 #[tokio::test]
 async fn send_operations_low_fee() {
     let addr: SocketAddr = "[::]:5049".parse().unwrap();
@@ -460,7 +1123,10 @@ async fn send_operations_low_fee() {
     let mut pool_ctrl = MockPoolController::new();
     pool_ctrl.expect_clone_box().returning(|| {
         let mut pool_ctrl = MockPoolController::new();
-        pool_ctrl.expect_add_operations().returning(|_a| ());
+        pool_ctrl.expect_add_local_operations().returning(|_a| ());
+        pool_ctrl
+            .expect_check_sender_limits()
+            .returning(|_a| Ok(()));
         Box::new(pool_ctrl)
     });
 
@@ -520,7 +1186,10 @@ async fn send_operations() {
     let mut pool_ctrl = MockPoolController::new();
     pool_ctrl.expect_clone_box().returning(|| {
         let mut pool_ctrl = MockPoolController::new();
-        pool_ctrl.expect_add_operations().returning(|_a| ());
+        pool_ctrl.expect_add_local_operations().returning(|_a| ());
+        pool_ctrl
+            .expect_check_sender_limits()
+            .returning(|_a| Ok(()));
         Box::new(pool_ctrl)
     });
 
@@ -607,6 +1276,134 @@ async fn send_operations() {
     api_public_handle.stop().await;
 }
 
+#[tokio::test]
+async fn send_operations_sender_limit() {
+    let addr: SocketAddr = "[::]:5066".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+
+    let mut pool_ctrl = MockPoolController::new();
+    pool_ctrl.expect_clone_box().returning(|| {
+        let mut pool_ctrl = MockPoolController::new();
+        pool_ctrl.expect_check_sender_limits().returning(|ops| {
+            Err(PoolError::SenderOperationCountLimit(
+                ops[0].content_creator_address,
+                1000,
+            ))
+        });
+        Box::new(pool_ctrl)
+    });
+    api_public.0.pool_command_sender = Box::new(pool_ctrl);
+
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+    let keypair = KeyPair::generate(0).unwrap();
+
+    let operation = create_operation_with_expire_period(&keypair, u64::MAX);
+    let input: OperationInput = OperationInput {
+        creator_public_key: keypair.get_public_key(),
+        signature: operation.signature,
+        serialized_content: operation.serialized_data,
+    };
+
+    // the operation is neither added to the pool nor propagated
+    let response: Result<Vec<OperationId>, Error> = client
+        .request("send_operations", rpc_params![vec![input]])
+        .await;
+    assert!(response
+        .unwrap_err()
+        .to_string()
+        .contains("would exceed the limit of 1000 operations"));
+
+    api_public_handle.stop().await;
+}
+
+#[tokio::test]
+async fn simulate_operation() {
+    let addr: SocketAddr = "[::]:5058".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+
+    let mut exec_ctrl = MockExecutionController::new();
+    exec_ctrl.expect_simulate_operation().returning(|_| {
+        Ok(OperationSimulationOutput {
+            out: massa_execution_exports::ExecutionOutput {
+                slot: Slot::new(1, 5),
+                block_info: None,
+                state_changes: massa_final_state::StateChanges::default(),
+                events: massa_execution_exports::EventStore::default(),
+                event_overflow: None,
+                stats: Default::default(),
+                final_ledger_reads: Default::default(),
+            },
+            gas_usage: 1_000,
+            status: massa_execution_exports::OperationSimulationStatus::Failure,
+        })
+    });
+    api_public.0.execution_controller = Box::new(exec_ctrl);
+
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+    let keypair = KeyPair::generate(0).unwrap();
+    let operation = create_operation_with_expire_period(&keypair, u64::MAX);
+
+    // signed operation
+    let input = OperationSimulationInput {
+        creator_public_key: keypair.get_public_key(),
+        signature: Some(operation.signature),
+        serialized_content: operation.serialized_data.clone(),
+    };
+    let response: OperationSimulation = client
+        .request("simulate_operation", rpc_params![input])
+        .await
+        .unwrap();
+    assert_eq!(response.id, operation.id);
+    assert_eq!(response.executed_at, Slot::new(1, 5));
+    assert_eq!(response.status, OperationSimulationStatus::Failure);
+
+    // unsigned operation
+    let input = OperationSimulationInput {
+        creator_public_key: keypair.get_public_key(),
+        signature: None,
+        serialized_content: operation.serialized_data.clone(),
+    };
+    let response: OperationSimulation = client
+        .request("simulate_operation", rpc_params![input])
+        .await
+        .unwrap();
+    assert_eq!(response.id, operation.id);
+
+    // the provided signatures are verified
+    let other_operation = create_operation_with_expire_period(&keypair, 1);
+    let input = OperationSimulationInput {
+        creator_public_key: keypair.get_public_key(),
+        signature: Some(other_operation.signature),
+        serialized_content: operation.serialized_data,
+    };
+    let response: Result<OperationSimulation, Error> = client
+        .request("simulate_operation", rpc_params![input])
+        .await;
+    assert!(response.is_err());
+
+    api_public_handle.stop().await;
+}
+
 #[tokio::test]
 async fn get_filtered_sc_output_event() {
     let addr: SocketAddr = "[::]:5013".parse().unwrap();
@@ -676,6 +1473,107 @@ async fn get_filtered_sc_output_event() {
     api_public_handle.stop().await;
 }
 
+#[tokio::test]
+async fn get_async_messages() {
+    let addr: SocketAddr = "[::]:5059".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+    let sender =
+        Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x").unwrap();
+    let destination =
+        Address::from_str("AS12fZLkHnLED3okr8Lduyty7dz9ZKkd24xMCc2JJWPcdmfn2eUEx").unwrap();
+
+    let mut exec_ctrl = MockExecutionController::new();
+    exec_ctrl
+        .expect_get_async_messages()
+        .returning(move |filter| {
+            assert_eq!(filter.sender, Some(sender));
+            assert_eq!(filter.destination, None);
+            vec![PendingAsyncMessage {
+                emission_slot: Slot::new(1, 0),
+                emission_index: 0,
+                sender,
+                destination,
+                function: "receive".to_string(),
+                max_gas: 1_000_000,
+                fee: Amount::from_str("1").unwrap(),
+                coins: Amount::from_str("10").unwrap(),
+                validity_start: Slot::new(2, 0),
+                validity_end: Slot::new(10, 0),
+                can_be_executed: true,
+                priority: 1000.0,
+            }]
+        });
+
+    api_public.0.execution_controller = Box::new(exec_ctrl);
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+
+    let response: Vec<PendingAsyncMessage> = client
+        .request(
+            "get_async_messages",
+            rpc_params![AsyncMessageFilter {
+                sender: Some(sender),
+                ..Default::default()
+            }],
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.len(), 1);
+    assert_eq!(response[0].destination, destination);
+    assert_eq!(response[0].function, "receive");
+    api_public_handle.stop().await;
+}
+
+#[tokio::test]
+async fn get_async_message_fee_suggestion() {
+    let addr: SocketAddr = "[::]:5060".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+
+    let mut exec_ctrl = MockExecutionController::new();
+    exec_ctrl
+        .expect_get_async_message_fee_suggestion()
+        .returning(|max_gas| AsyncMessageFeeSuggestion {
+            pool_length: 1000,
+            max_pool_length: 1000,
+            eviction_threshold: Some(0.5),
+            max_gas,
+            min_fee: Amount::from_raw(max_gas / 2 + 1),
+        });
+
+    api_public.0.execution_controller = Box::new(exec_ctrl);
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+
+    let response: AsyncMessageFeeSuggestion = client
+        .request("get_async_message_fee_suggestion", rpc_params![1_000_000])
+        .await
+        .unwrap();
+
+    assert_eq!(response.max_gas, 1_000_000);
+    assert_eq!(response.eviction_threshold, Some(0.5));
+    assert_eq!(response.min_fee, Amount::from_raw(500_001));
+    api_public_handle.stop().await;
+}
+
 #[tokio::test]
 async fn execute_read_only_bytecode() {
     let addr: SocketAddr = "[::]:5012".parse().unwrap();
@@ -694,6 +1592,9 @@ async fn execute_read_only_bytecode() {
                     block_info: None,
                     state_changes: massa_final_state::StateChanges::default(),
                     events: massa_execution_exports::EventStore::default(),
+                    event_overflow: None,
+                    stats: Default::default(),
+                    final_ledger_reads: Default::default(),
                 },
                 gas_cost: 100,
                 call_result: "toto".as_bytes().to_vec(),
@@ -764,7 +1665,10 @@ async fn execute_read_only_call() {
     let mut exec_ctrl = MockExecutionController::new();
     exec_ctrl
         .expect_execute_readonly_request()
-        .returning(|_req| {
+        .returning(|req| {
+            // the request is accounted to the IP of the client, not to the caller address
+            let caller_ip: IpAddr = req.caller_id.as_deref().unwrap().parse().unwrap();
+            assert!(caller_ip.is_loopback());
             Ok(ReadOnlyExecutionOutput {
                 out: massa_execution_exports::ExecutionOutput {
                     slot: Slot {
@@ -774,6 +1678,9 @@ async fn execute_read_only_call() {
                     block_info: None,
                     state_changes: massa_final_state::StateChanges::default(),
                     events: massa_execution_exports::EventStore::default(),
+                    event_overflow: None,
+                    stats: Default::default(),
+                    final_ledger_reads: Default::default(),
                 },
                 gas_cost: 100,
                 call_result: "toto".as_bytes().to_vec(),
@@ -809,6 +1716,35 @@ async fn execute_read_only_call() {
         caller_address: None,
         fee: None,
         coins: None,
+        state_overrides: None,
+    }]];
+    let response: Vec<ExecuteReadOnlyResponse> = client
+        .request("execute_read_only_call", params.clone())
+        .await
+        .unwrap();
+
+    assert_eq!(response.len(), 1);
+
+    // with state overrides
+    let params = rpc_params![vec![ReadOnlyCall {
+        max_gas: 1000000,
+        target_address: Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x")
+            .unwrap(),
+        target_function: "hello".to_string(),
+        parameter: vec![],
+        caller_address: None,
+        fee: None,
+        coins: None,
+        state_overrides: Some(vec![StateOverride {
+            address: Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x")
+                .unwrap(),
+            balance: Some(Amount::from_str("1000").unwrap()),
+            bytecode: None,
+            datastore: Some(vec![DatastoreEntryOverride {
+                key: "key".as_bytes().to_vec(),
+                value: Some("value".as_bytes().to_vec()),
+            }]),
+        }]),
     }]];
     let response: Vec<ExecuteReadOnlyResponse> = client
         .request("execute_read_only_call", params.clone())
@@ -836,6 +1772,18 @@ async fn get_addresses() {
                 candidate_datastore_keys: std::collections::BTreeSet::new(),
                 future_deferred_credits: BTreeMap::new(),
                 cycle_infos: vec![],
+                incoming_async_messages: vec![massa_models::address::IncomingAsyncMessage {
+                    emission_slot: Slot::new(1, 0),
+                    emission_index: 0,
+                    sender: Address::from_str(
+                        "AS12fZLkHnLED3okr8Lduyty7dz9ZKkd24xMCc2JJWPcdmfn2eUEx",
+                    )
+                    .unwrap(),
+                    function: "receive".to_string(),
+                    coins: Amount::from_str("10").unwrap(),
+                    validity_start: Slot::new(2, 0),
+                    validity_end: Slot::new(10, 0),
+                }],
             })
             .collect()
     });
@@ -875,6 +1823,8 @@ async fn get_addresses() {
         .unwrap();
 
     assert!(response.len() == 1);
+    assert_eq!(response[0].incoming_async_messages.len(), 1);
+    assert_eq!(response[0].incoming_async_messages[0].function, "receive");
 
     api_public_handle.stop().await;
 }
@@ -1007,6 +1957,12 @@ async fn wrong_api() {
         .to_string()
         .contains("The wrong API (either Public or Private) was called"));
 
+    let response: Result<(), Error> = client.request("shutdown", rpc_params![Some(1000)]).await;
+    assert!(response
+        .unwrap_err()
+        .to_string()
+        .contains("The wrong API (either Public or Private) was called"));
+
     let response: Result<(), Error> = client
         .request("node_sign_message", rpc_params![Vec::<u8>::new()])
         .await;
@@ -1,13 +1,26 @@
-use std::{net::SocketAddr, str::FromStr};
+use std::{convert::Infallible, net::SocketAddr, str::FromStr};
 
+use hyper::{
+    header::{ACCESS_CONTROL_ALLOW_ORIGIN, ACCESS_CONTROL_REQUEST_METHOD, ORIGIN},
+    Body, Method, Request, Response,
+};
 use jsonrpsee::{
     client_transport::ws::Url, core::client::ClientT, http_client::HttpClientBuilder, rpc_params,
     ws_client::WsClientBuilder,
 };
-use massa_api_exports::operation::OperationInfo;
+use massa_api_exports::{
+    config::{CorsConfig, IpcConfig},
+    operation::OperationInfo,
+};
 use massa_models::operation::OperationId;
+use tower::{Layer, ServiceExt};
 
-use crate::{tests::mock::get_apiv2_server, ApiServer, RpcServer};
+use crate::{
+    cors_layer,
+    tests::mock::get_apiv2_server,
+    versioning::{DeprecatedMethod, DeprecationLayer},
+    ApiServer, RpcServer,
+};
 
 #[tokio::test]
 async fn max_conn() {
@@ -217,3 +230,187 @@ async fn host_allowed() {
     api_handle.stop().await;
     api_handle2.stop().await;
 }
+
+#[tokio::test]
+async fn cors_allowed_origins() {
+    let cors_config = CorsConfig {
+        allowed_origins: vec!["https://allowed.example".to_string()],
+        allowed_methods: vec!["POST".to_string()],
+        allowed_headers: vec!["content-type".to_string()],
+    };
+    let service = cors_layer(&cors_config).unwrap().layer(tower::service_fn(
+        |_request: Request<Body>| async { Ok::<_, Infallible>(Response::new(Body::empty())) },
+    ));
+
+    let preflight = |origin: &str| {
+        Request::builder()
+            .method(Method::OPTIONS)
+            .header(ORIGIN, origin)
+            .header(ACCESS_CONTROL_REQUEST_METHOD, "POST")
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let response = service
+        .clone()
+        .oneshot(preflight("https://allowed.example"))
+        .await
+        .unwrap();
+    assert_eq!(
+        response.headers().get(ACCESS_CONTROL_ALLOW_ORIGIN).unwrap(),
+        "https://allowed.example"
+    );
+
+    let response = service
+        .oneshot(preflight("https://other.example"))
+        .await
+        .unwrap();
+    assert!(response
+        .headers()
+        .get(ACCESS_CONTROL_ALLOW_ORIGIN)
+        .is_none());
+
+    // invalid values are rejected
+    let cors_config = CorsConfig {
+        allowed_methods: vec!["NOT A METHOD".to_string()],
+        ..cors_config
+    };
+    assert!(cors_layer(&cors_config).is_err());
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn ipc_transport() {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    let addr: SocketAddr = "[::]:5054".parse().unwrap();
+    let (mut api_server, mut api_config) = crate::tests::mock::start_public_api(addr);
+
+    let socket_dir = tempfile::tempdir().unwrap();
+    let ipc_config = IpcConfig {
+        path: socket_dir.path().join("api_public.sock"),
+        permissions: 0o600,
+    };
+    api_server.0.api_settings.ipc_public = Some(ipc_config.clone());
+    api_config.ipc_public = Some(ipc_config.clone());
+
+    let api_handle = api_server
+        .serve(&addr, &api_config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let stream = tokio::net::UnixStream::connect(&ipc_config.path)
+        .await
+        .unwrap();
+    let (reader, mut writer) = stream.into_split();
+    let mut responses = BufReader::new(reader).lines();
+
+    // single call
+    writer
+        .write_all(b"{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"stop_node\",\"params\":[]}\n")
+        .await
+        .unwrap();
+    let response: serde_json::Value =
+        serde_json::from_str(&responses.next_line().await.unwrap().unwrap()).unwrap();
+    assert_eq!(response["id"], 1);
+    assert!(response["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("The wrong API (either Public or Private) was called"));
+
+    // batch
+    writer
+        .write_all(
+            b"[{\"jsonrpc\":\"2.0\",\"id\":2,\"method\":\"stop_node\",\"params\":[]},\
+            {\"jsonrpc\":\"2.0\",\"id\":3,\"method\":\"unknown_method\",\"params\":[]}]\n",
+        )
+        .await
+        .unwrap();
+    let response: serde_json::Value =
+        serde_json::from_str(&responses.next_line().await.unwrap().unwrap()).unwrap();
+    let response = response.as_array().unwrap();
+    assert_eq!(response.len(), 2);
+    assert_eq!(response[0]["id"], 2);
+    assert_eq!(response[1]["id"], 3);
+    assert_eq!(response[1]["error"]["code"], -32601);
+
+    // invalid JSON
+    writer.write_all(b"not json\n").await.unwrap();
+    let response: serde_json::Value =
+        serde_json::from_str(&responses.next_line().await.unwrap().unwrap()).unwrap();
+    assert_eq!(response["error"]["code"], -32700);
+
+    api_handle.stop().await;
+}
+
+#[tokio::test]
+async fn versioned_namespace() {
+    let addr: SocketAddr = "[::]:5055".parse().unwrap();
+    let (api_server, api_config) = crate::tests::mock::start_public_api(addr);
+
+    let api_handle = api_server
+        .serve(&addr, &api_config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+
+    // the method is served under its plain and namespaced names
+    for method in ["stop_node", "massa_v1_stop_node"] {
+        let response: Result<(), jsonrpsee::core::Error> =
+            client.request(method, rpc_params![]).await;
+        assert!(response
+            .unwrap_err()
+            .to_string()
+            .contains("The wrong API (either Public or Private) was called"));
+    }
+
+    let response: Result<(), jsonrpsee::core::Error> =
+        client.request("massa_v2_stop_node", rpc_params![]).await;
+    assert!(response
+        .unwrap_err()
+        .to_string()
+        .contains("Method not found"));
+
+    api_handle.stop().await;
+}
+
+#[tokio::test]
+async fn deprecation_field() {
+    static DEPRECATIONS: &[DeprecatedMethod] = &[DeprecatedMethod {
+        method: "get_old",
+        since: "28.3.0",
+        removal: None,
+        replacement: Some("massa_v2_get_new"),
+    }];
+    let (_, api_config) = get_apiv2_server(&"[::]:0".parse().unwrap());
+    let service = DeprecationLayer::new(DEPRECATIONS, &api_config).layer(tower::service_fn(
+        |_request: Request<Body>| async {
+            Ok::<_, Infallible>(Response::new(Body::from(
+                r#"[{"jsonrpc":"2.0","result":1,"id":1},{"jsonrpc":"2.0","result":2,"id":2}]"#,
+            )))
+        },
+    ));
+
+    let request = Request::builder()
+        .method(Method::POST)
+        .body(Body::from(
+            r#"[{"jsonrpc":"2.0","method":"massa_v1_get_old","id":1},{"jsonrpc":"2.0","method":"get_new","id":2}]"#,
+        ))
+        .unwrap();
+    let response = service.oneshot(request).await.unwrap();
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    let response: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(response[0]["deprecation"]["method"], "get_old");
+    assert_eq!(
+        response[0]["deprecation"]["replacement"],
+        "massa_v2_get_new"
+    );
+    assert!(response[1].get("deprecation").is_none());
+}
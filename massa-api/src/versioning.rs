@@ -0,0 +1,226 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+//! Versioned method namespaces and deprecation of the API methods
+//!
+//! Each method is served under its plain name and under the namespace of its API version
+//! (ex: `massa_v2_get_largest_stakers`), so that a breaking change can be introduced in a new
+//! namespace while the previous one keeps being served.
+//! The responses to the calls of a deprecated method carry a `deprecation` field
+//! describing the deprecation (over HTTP and IPC).
+use crate::batch::empty_response;
+use futures::future::BoxFuture;
+use hyper::body::HttpBody;
+use hyper::header::CONTENT_LENGTH;
+use hyper::{Body, Method, Request, Response, StatusCode};
+use jsonrpsee::core::Error as JsonRpseeError;
+use jsonrpsee::RpcModule;
+use massa_api_exports::config::APIConfig;
+use serde::Serialize;
+use serde_json::Value;
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+
+/// Namespace of the methods of the public and private APIs
+pub(crate) const API_V1_NAMESPACE: &str = "massa_v1";
+/// Namespace of the methods of the Massa API (V2)
+pub(crate) const API_V2_NAMESPACE: &str = "massa_v2";
+
+/// Deprecated methods, still served until their removal.
+/// The methods whose deprecation is announced must be added here.
+pub(crate) const DEPRECATED_METHODS: &[DeprecatedMethod] = &[];
+
+/// Deprecation of a method
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct DeprecatedMethod {
+    /// plain name of the method, its namespaced names being deprecated as well
+    pub method: &'static str,
+    /// node version since which the method is deprecated
+    pub since: &'static str,
+    /// node version from which the method will not be served anymore, if planned
+    pub removal: Option<&'static str>,
+    /// method to use instead, if any
+    pub replacement: Option<&'static str>,
+}
+
+/// Registers the methods of the module under the given namespace as well.
+pub(crate) fn register_namespace<T>(
+    module: &mut RpcModule<T>,
+    namespace: &str,
+) -> Result<(), JsonRpseeError> {
+    let method_names: Vec<&'static str> = module
+        .method_names()
+        // the methods of the JSON-RPC protocol itself (ex: `rpc.discover`) are not versioned
+        .filter(|name| !name.starts_with("rpc."))
+        .collect();
+    for method_name in method_names {
+        // the aliases are registered once per server, for the lifetime of the node
+        let alias: &'static str =
+            Box::leak(format!("{}_{}", namespace, method_name).into_boxed_str());
+        module.register_alias(alias, method_name)?;
+    }
+    Ok(())
+}
+
+/// Returns the deprecation of the called method, whatever the namespace it was called in
+fn method_deprecation(
+    deprecations: &'static [DeprecatedMethod],
+    method: &str,
+) -> Option<&'static DeprecatedMethod> {
+    let method = [API_V1_NAMESPACE, API_V2_NAMESPACE]
+        .iter()
+        .find_map(|namespace| {
+            method
+                .strip_prefix(namespace)
+                .and_then(|name| name.strip_prefix('_'))
+        })
+        .unwrap_or(method);
+    deprecations
+        .iter()
+        .find(|deprecation| deprecation.method == method)
+}
+
+/// Returns the ids of the calls of a request (single call or batch) to deprecated methods,
+/// with their deprecation. The notifications are ignored as they have no response.
+pub(crate) fn deprecated_calls(
+    deprecations: &'static [DeprecatedMethod],
+    request: &Value,
+) -> Vec<(Value, &'static DeprecatedMethod)> {
+    let calls: Vec<&Value> = match request {
+        Value::Array(calls) => calls.iter().collect(),
+        call => vec![call],
+    };
+    calls
+        .into_iter()
+        .filter_map(|call| {
+            let id = call.get("id")?;
+            let method = call.get("method")?.as_str()?;
+            method_deprecation(deprecations, method).map(|deprecation| (id.clone(), deprecation))
+        })
+        .collect()
+}
+
+/// Adds the `deprecation` field to the responses (single response or batch)
+/// of the calls to deprecated methods
+pub(crate) fn annotate_response(
+    response: &mut Value,
+    deprecated_calls: &[(Value, &'static DeprecatedMethod)],
+) {
+    let responses: Vec<&mut Value> = match response {
+        Value::Array(responses) => responses.iter_mut().collect(),
+        response => vec![response],
+    };
+    for response in responses {
+        let Some(deprecation) = response.get("id").and_then(|id| {
+            deprecated_calls
+                .iter()
+                .find(|(call_id, _)| call_id == id)
+                .map(|(_, deprecation)| *deprecation)
+        }) else {
+            continue;
+        };
+        if let (Some(response), Ok(deprecation)) =
+            (response.as_object_mut(), serde_json::to_value(deprecation))
+        {
+            response.insert("deprecation".to_string(), deprecation);
+        }
+    }
+}
+
+/// Layer adding the `deprecation` field to the HTTP responses to the calls of deprecated methods.
+#[derive(Clone)]
+pub struct DeprecationLayer {
+    deprecations: &'static [DeprecatedMethod],
+    max_request_body_size: usize,
+}
+
+impl DeprecationLayer {
+    /// Creates a new layer signaling the given deprecations
+    pub fn new(deprecations: &'static [DeprecatedMethod], api_config: &APIConfig) -> Self {
+        DeprecationLayer {
+            deprecations,
+            max_request_body_size: api_config.max_request_body_size as usize,
+        }
+    }
+}
+
+impl<S> Layer<S> for DeprecationLayer {
+    type Service = DeprecationService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        DeprecationService {
+            inner,
+            deprecations: self.deprecations,
+            max_request_body_size: self.max_request_body_size,
+        }
+    }
+}
+
+/// Service adding the `deprecation` field to the responses
+#[derive(Clone)]
+pub struct DeprecationService<S> {
+    inner: S,
+    deprecations: &'static [DeprecatedMethod],
+    max_request_body_size: usize,
+}
+
+impl<S> Service<Request<Body>> for DeprecationService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    S::Error: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        // take the service that was driven to readiness and leave a clone in its place
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let deprecations = self.deprecations;
+
+        // only the requests with a known length within the limit are inspected
+        let within_size_limit = request
+            .body()
+            .size_hint()
+            .upper()
+            .map_or(false, |len| len <= self.max_request_body_size as u64);
+        if deprecations.is_empty() || request.method() != Method::POST || !within_size_limit {
+            return Box::pin(inner.call(request));
+        }
+
+        Box::pin(async move {
+            let (parts, body) = request.into_parts();
+            let Ok(body) = hyper::body::to_bytes(body).await else {
+                return Ok(empty_response(StatusCode::BAD_REQUEST));
+            };
+            let deprecated = serde_json::from_slice::<Value>(&body)
+                .map(|request| deprecated_calls(deprecations, &request))
+                .unwrap_or_default();
+            let response = inner
+                .call(Request::from_parts(parts, Body::from(body)))
+                .await?;
+            if deprecated.is_empty() || !response.status().is_success() {
+                return Ok(response);
+            }
+
+            let (mut parts, body) = response.into_parts();
+            let Ok(body) = hyper::body::to_bytes(body).await else {
+                return Ok(empty_response(StatusCode::INTERNAL_SERVER_ERROR));
+            };
+            let Ok(mut response) = serde_json::from_slice::<Value>(&body) else {
+                return Ok(Response::from_parts(parts, Body::from(body)));
+            };
+            annotate_response(&mut response, &deprecated);
+            // the length of the body changed
+            parts.headers.remove(CONTENT_LENGTH);
+            Ok(Response::from_parts(
+                parts,
+                Body::from(response.to_string()),
+            ))
+        })
+    }
+}
@@ -14,7 +14,9 @@ use massa_db_exports::{
     MESSAGE_ID_DESER_ERROR, MESSAGE_ID_SER_ERROR, MESSAGE_SER_ERROR, STATE_CF,
 };
 use massa_ledger_exports::{Applicable, SetOrKeep, SetUpdateOrDelete};
-use massa_models::address::Address;
+use massa_models::{
+    address::Address, execution::AsyncMessageFilter, prehash::PreHashMap, slot::Slot,
+};
 use massa_serialization::{
     DeserializeError, Deserializer, SerializeError, Serializer, U64VarIntDeserializer,
     U64VarIntSerializer,
@@ -25,7 +27,7 @@ use nom::{
     sequence::tuple,
     IResult, Parser,
 };
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::ops::Bound::Included;
 
 const EMISSION_SLOT_IDENT: u8 = 0u8;
@@ -198,6 +200,10 @@ pub struct AsyncPool {
     pub config: AsyncPoolConfig,
     pub db: ShareableMassaDBController,
     pub message_info_cache: BTreeMap<AsyncMessageId, AsyncMessageInfo>,
+    /// ids of the messages of the pool, indexed by emission slot and index
+    message_id_cache: HashMap<(Slot, u64), AsyncMessageId>,
+    /// ids of the messages of the pool, indexed by destination
+    destination_cache: PreHashMap<Address, BTreeSet<AsyncMessageId>>,
     message_id_serializer: AsyncMessageIdSerializer,
     message_serializer: AsyncMessageSerializer,
     message_id_deserializer: AsyncMessageIdDeserializer,
@@ -211,6 +217,8 @@ impl AsyncPool {
             config: config.clone(),
             db,
             message_info_cache: Default::default(),
+            message_id_cache: Default::default(),
+            destination_cache: Default::default(),
             message_id_serializer: AsyncMessageIdSerializer::new(),
             message_serializer: AsyncMessageSerializer::new(true),
             message_id_deserializer: AsyncMessageIdDeserializer::new(config.thread_count),
@@ -227,6 +235,8 @@ impl AsyncPool {
     /// Recomputes the local message_info_cache after bootstrap or loading the state from disk
     pub fn recompute_message_info_cache(&mut self) {
         self.message_info_cache.clear();
+        self.message_id_cache.clear();
+        self.destination_cache.clear();
 
         let db = self.db.read();
 
@@ -254,7 +264,13 @@ impl AsyncPool {
                 .expect(MESSAGE_ID_DESER_ERROR);
 
             if let Some(message) = self.fetch_message(&message_id) {
+                self.destination_cache
+                    .entry(message.destination)
+                    .or_default()
+                    .insert(message_id);
                 self.message_info_cache.insert(message_id, message.into());
+                self.message_id_cache
+                    .insert((message_id.1, message_id.2), message_id);
             }
 
             last_id = Some(
@@ -284,28 +300,68 @@ impl AsyncPool {
             match change {
                 (id, SetUpdateOrDelete::Set(message)) => {
                     self.put_entry(id, message.clone(), batch);
-                    self.message_info_cache
-                        .insert(*id, AsyncMessageInfo::from(message.clone()));
+                    if let Some(message_info) = self
+                        .message_info_cache
+                        .insert(*id, AsyncMessageInfo::from(message.clone()))
+                    {
+                        self.remove_from_destination_cache(id, &message_info.destination);
+                    }
+                    self.destination_cache
+                        .entry(message.destination)
+                        .or_default()
+                        .insert(*id);
+                    self.message_id_cache.insert((id.1, id.2), *id);
                 }
 
                 (id, SetUpdateOrDelete::Update(message_update)) => {
                     self.update_entry(id, message_update.clone(), batch);
 
-                    self.message_info_cache
-                        .entry(*id)
-                        .and_modify(|message_info| {
-                            message_info.apply(message_update.clone());
-                        });
+                    if let Some(message_info) = self.message_info_cache.get_mut(id) {
+                        let previous_destination = message_info.destination;
+                        message_info.apply(message_update.clone());
+                        let destination = message_info.destination;
+                        if destination != previous_destination {
+                            self.remove_from_destination_cache(id, &previous_destination);
+                            self.destination_cache
+                                .entry(destination)
+                                .or_default()
+                                .insert(*id);
+                        }
+                    }
                 }
 
                 (id, SetUpdateOrDelete::Delete) => {
                     self.delete_entry(id, batch);
-                    self.message_info_cache.remove(id);
+                    if let Some(message_info) = self.message_info_cache.remove(id) {
+                        self.remove_from_destination_cache(id, &message_info.destination);
+                    }
+                    self.message_id_cache.remove(&(id.1, id.2));
                 }
             }
         }
     }
 
+    /// Removes `id` from the ids of the messages sent to `destination`
+    fn remove_from_destination_cache(&mut self, id: &AsyncMessageId, destination: &Address) {
+        if let Some(message_ids) = self.destination_cache.get_mut(destination) {
+            message_ids.remove(id);
+            if message_ids.is_empty() {
+                self.destination_cache.remove(destination);
+            }
+        }
+    }
+
+    /// Get the id of the message of the pool emitted at `emission_slot` with `emission_index`
+    pub fn get_message_id(
+        &self,
+        emission_slot: Slot,
+        emission_index: u64,
+    ) -> Option<AsyncMessageId> {
+        self.message_id_cache
+            .get(&(emission_slot, emission_index))
+            .copied()
+    }
+
     /// Query a message from the database.
     ///
     /// This should only be called when we know we want to execute the message.
@@ -356,6 +412,62 @@ impl AsyncPool {
         fetched_messages
     }
 
+    /// Query at most `max_count` messages of the pool sent to `destination`,
+    /// in priority order (highest first).
+    ///
+    /// The messages are found in the index of the destinations,
+    /// and only these messages are read from the database.
+    pub fn fetch_messages_by_destination(
+        &self,
+        destination: &Address,
+        max_count: usize,
+    ) -> Vec<AsyncMessage> {
+        let message_ids = self
+            .destination_cache
+            .get(destination)
+            .map(|message_ids| message_ids.iter().take(max_count).collect())
+            .unwrap_or_default();
+
+        self.fetch_messages(message_ids)
+            .into_iter()
+            .filter_map(|(_, message)| message)
+            .collect()
+    }
+
+    /// Query the messages of the pool matching `filter`, in priority order (highest first).
+    ///
+    /// The messages of the destination are found in the index of the destinations,
+    /// and the execution statuses are looked up in the `message_info_cache`:
+    /// only the matching messages are read from the database.
+    pub fn fetch_filtered_messages(&self, filter: &AsyncMessageFilter) -> Vec<AsyncMessage> {
+        let candidate_ids: Box<dyn Iterator<Item = &AsyncMessageId>> = match filter.destination {
+            Some(destination) => match self.destination_cache.get(&destination) {
+                Some(message_ids) => Box::new(message_ids.iter()),
+                None => Box::new(std::iter::empty()),
+            },
+            None => Box::new(self.message_info_cache.keys()),
+        };
+        let message_ids = candidate_ids
+            .filter(|message_id| {
+                filter.can_be_executed.map_or(true, |status| {
+                    self.message_info_cache
+                        .get(*message_id)
+                        .map_or(false, |message_info| message_info.can_be_executed == status)
+                })
+            })
+            .collect();
+
+        self.fetch_messages(message_ids)
+            .into_iter()
+            .filter_map(|(_, message)| message)
+            .filter(|message| {
+                filter
+                    .sender
+                    .map_or(true, |sender| message.sender == sender)
+            })
+            .collect()
+    }
+
     /// Deserializes the key and value, useful after bootstrap
     pub fn is_key_value_valid(&self, serialized_key: &[u8], serialized_value: &[u8]) -> bool {
         if !serialized_key.starts_with(ASYNC_POOL_PREFIX.as_bytes()) {
@@ -1412,4 +1524,92 @@ mod tests {
 
         assert_eq!(pool2.message_info_cache, message_info_cache1);
     }
+
+    #[test]
+    fn test_message_id_cache() {
+        let config = AsyncPoolConfig::default();
+        let temp_dir = tempdir().expect("Unable to create a temp folder");
+        let db_config = MassaDBConfig {
+            path: temp_dir.path().to_path_buf(),
+            max_history_length: 100,
+            max_final_state_elements_size: 100,
+            max_versioning_elements_size: 100,
+            thread_count: THREAD_COUNT,
+        };
+        let db: ShareableMassaDBController = Arc::new(RwLock::new(
+            Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>,
+        ));
+        let mut pool = AsyncPool::new(config, db);
+
+        let message = create_message();
+        let message_id = message.compute_id();
+        assert_eq!(pool.get_message_id(Slot::new(1, 0), 0), None);
+
+        // the id of an added message is found from its emission slot and index
+        let mut changes = AsyncPoolChanges::default();
+        changes.push_add(message_id, message);
+        pool.apply_changes_to_batch(&changes, &mut DBBatch::new());
+        assert_eq!(pool.get_message_id(Slot::new(1, 0), 0), Some(message_id));
+        assert_eq!(pool.get_message_id(Slot::new(1, 0), 1), None);
+
+        // the id of a deleted message is not found anymore
+        let mut changes = AsyncPoolChanges::default();
+        changes.push_delete(message_id);
+        pool.apply_changes_to_batch(&changes, &mut DBBatch::new());
+        assert_eq!(pool.get_message_id(Slot::new(1, 0), 0), None);
+    }
+
+    #[test]
+    fn test_destination_cache() {
+        let config = AsyncPoolConfig::default();
+        let temp_dir = tempdir().expect("Unable to create a temp folder");
+        let db_config = MassaDBConfig {
+            path: temp_dir.path().to_path_buf(),
+            max_history_length: 100,
+            max_final_state_elements_size: 100,
+            max_versioning_elements_size: 100,
+            thread_count: THREAD_COUNT,
+        };
+        let db: ShareableMassaDBController = Arc::new(RwLock::new(
+            Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>,
+        ));
+        let mut pool = AsyncPool::new(config, db);
+
+        let message = create_message();
+        let destination = message.destination;
+        let new_destination =
+            Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x").unwrap();
+        let message_id = message.compute_id();
+
+        // an added message is indexed by its destination
+        let mut changes = AsyncPoolChanges::default();
+        changes.push_add(message_id, message);
+        pool.apply_changes_to_batch(&changes, &mut DBBatch::new());
+        assert_eq!(
+            pool.destination_cache.get(&destination),
+            Some(&BTreeSet::from([message_id]))
+        );
+
+        // a message whose destination is updated is indexed by its new destination only
+        let mut changes = AsyncPoolChanges::default();
+        changes.0.insert(
+            message_id,
+            SetUpdateOrDelete::Update(AsyncMessageUpdate {
+                destination: SetOrKeep::Set(new_destination),
+                ..Default::default()
+            }),
+        );
+        pool.apply_changes_to_batch(&changes, &mut DBBatch::new());
+        assert_eq!(pool.destination_cache.get(&destination), None);
+        assert_eq!(
+            pool.destination_cache.get(&new_destination),
+            Some(&BTreeSet::from([message_id]))
+        );
+
+        // a deleted message is not indexed anymore
+        let mut changes = AsyncPoolChanges::default();
+        changes.push_delete(message_id);
+        pool.apply_changes_to_batch(&changes, &mut DBBatch::new());
+        assert!(pool.destination_cache.is_empty());
+    }
 }
@@ -8,6 +8,7 @@ use crate::messages::{
 };
 use crate::settings::BootstrapClientConfig;
 use massa_hash::Hash;
+use massa_metrics::MassaMetrics;
 use massa_models::config::{
     MAX_BOOTSTRAP_MESSAGE_SIZE, MAX_BOOTSTRAP_MESSAGE_SIZE_BYTES, SIGNATURE_DESER_SIZE,
 };
@@ -27,6 +28,7 @@ pub struct BootstrapClientBinder {
     prev_message: Option<Hash>,
     version_serializer: VersionSerializer,
     cfg: BootstrapClientConfig,
+    massa_metrics: Option<MassaMetrics>,
 }
 
 const KNOWN_PREFIX_LEN: usize = SIGNATURE_DESER_SIZE + MAX_BOOTSTRAP_MESSAGE_SIZE_BYTES;
@@ -58,9 +60,21 @@ impl BootstrapClientBinder {
             prev_message: None,
             version_serializer: VersionSerializer::new(),
             cfg,
+            massa_metrics: None,
         }
     }
 
+    /// Record the messages exchanged with the server in the network metrics
+    pub fn with_massa_metrics(mut self, massa_metrics: MassaMetrics) -> Self {
+        self.massa_metrics = Some(massa_metrics);
+        self
+    }
+
+    /// Metrics in which the messages exchanged with the server are recorded, if any
+    pub fn massa_metrics(&self) -> Option<&MassaMetrics> {
+        self.massa_metrics.as_ref()
+    }
+
     /// Performs a handshake. Should be called after connection
     /// NOT cancel-safe
     pub fn handshake(&mut self, version: Version) -> Result<(), BootstrapError> {
@@ -147,6 +161,11 @@ impl BootstrapClientBinder {
                 msg
             }
         };
+        if let Some(massa_metrics) = &self.massa_metrics {
+            let message_size = (KNOWN_PREFIX_LEN + msg_len as usize) as u64;
+            massa_metrics.record_network_message_received(message.metrics_name(), message_size);
+            massa_metrics.inc_bootstrap_bytes_received(message_size);
+        }
         Ok(message)
     }
 
@@ -194,6 +213,9 @@ impl BootstrapClientBinder {
         // And send it off
         self.write_all_timeout(&write_buf, deadline)
             .map_err(|(e, _)| e)?;
+        if let Some(massa_metrics) = &self.massa_metrics {
+            massa_metrics.record_network_message_sent(msg.metrics_name(), write_buf.len() as u64);
+        }
         Ok(())
     }
 
@@ -6,9 +6,11 @@ use crate::messages::{
     BootstrapClientMessage, BootstrapClientMessageDeserializer, BootstrapServerMessage,
     BootstrapServerMessageSerializer,
 };
+use crate::sessions::BandwidthShare;
 use crate::settings::BootstrapSrvBindCfg;
 use massa_hash::Hash;
 use massa_hash::HASH_SIZE_BYTES;
+use massa_metrics::MassaMetrics;
 use massa_models::config::{MAX_BOOTSTRAP_MESSAGE_SIZE, MAX_BOOTSTRAP_MESSAGE_SIZE_BYTES};
 use massa_models::serialization::{DeserializeMinBEInt, SerializeMinBEInt};
 use massa_models::version::{Version, VersionDeserializer, VersionSerializer};
@@ -48,6 +50,8 @@ pub struct BootstrapServerBinder {
     version_serializer: VersionSerializer,
     version_deserializer: VersionDeserializer,
     write_error_timeout: MassaTime,
+    massa_metrics: Option<MassaMetrics>,
+    bandwidth_share: Option<BandwidthShare>,
 }
 
 impl BootstrapServerBinder {
@@ -88,8 +92,22 @@ impl BootstrapServerBinder {
             version_serializer: VersionSerializer::new(),
             version_deserializer: VersionDeserializer::new(),
             write_error_timeout,
+            massa_metrics: None,
+            bandwidth_share: None,
         }
     }
+
+    /// Record the messages exchanged with the client in the network metrics
+    pub fn with_massa_metrics(mut self, massa_metrics: MassaMetrics) -> Self {
+        self.massa_metrics = Some(massa_metrics);
+        self
+    }
+
+    /// Throttle the messages sent to the client to the share of the upload rate of its session
+    pub(crate) fn with_bandwidth_share(mut self, bandwidth_share: BandwidthShare) -> Self {
+        self.bandwidth_share = Some(bandwidth_share);
+        self
+    }
     /// Performs a handshake. Should be called after connection
     /// MUST always be followed by a send of the `BootstrapMessage::BootstrapTime`
     pub fn handshake_timeout(
@@ -194,7 +212,6 @@ impl BootstrapServerBinder {
         msg: BootstrapServerMessage,
         duration: Option<Duration>,
     ) -> Result<(), BootstrapError> {
-        let deadline = duration.map(|d| Instant::now() + d);
         // serialize the message to bytes
         let mut msg_bytes = Vec::new();
         BootstrapServerMessageSerializer::new().serialize(&msg, &mut msg_bytes)?;
@@ -202,6 +219,12 @@ impl BootstrapServerBinder {
             BootstrapError::GeneralError(format!("bootstrap message too large to encode: {}", e))
         })?;
 
+        // wait for the turn of the session to send, outside of the timeout
+        if let Some(bandwidth_share) = &mut self.bandwidth_share {
+            bandwidth_share.consume(msg_bytes.len());
+        }
+        let deadline = duration.map(|d| Instant::now() + d);
+
         // compute signature, and extract the bytes
         let sig = {
             if let Some(prev_message) = self.prev_message {
@@ -226,6 +249,9 @@ impl BootstrapServerBinder {
         // send the data
         self.write_all_timeout(&stream_data, deadline)
             .map_err(|(e, _)| e)?;
+        if let Some(massa_metrics) = &self.massa_metrics {
+            massa_metrics.record_network_message_sent(msg.metrics_name(), stream_data.len() as u64);
+        }
 
         // update prev sig
         self.prev_message = Some(Hash::compute_from(&sig.to_bytes()));
@@ -284,6 +310,12 @@ impl BootstrapServerBinder {
         )
         .deserialize::<DeserializeError>(&msg_bytes)
         .map_err(|err| BootstrapError::GeneralError(format!("{}", err)))?;
+        if let Some(massa_metrics) = &self.massa_metrics {
+            massa_metrics.record_network_message_received(
+                msg.metrics_name(),
+                (KNOWN_PREFIX_LEN + msg_bytes.len()) as u64,
+            );
+        }
 
         Ok(msg)
     }
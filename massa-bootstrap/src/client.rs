@@ -1,9 +1,14 @@
 use humantime::format_duration;
-use massa_db_exports::DBBatch;
+use massa_db_exports::{
+    DBBatch, ShareableMassaDBController, StateRange, StreamBatch, CHANGE_ID_DESER_ERROR,
+    LEDGER_PREFIX,
+};
 use massa_final_state::{FinalStateController, FinalStateError};
 use massa_logging::massa_trace;
 use massa_metrics::MassaMetrics;
 use massa_models::{node::NodeId, slot::Slot, streaming_step::StreamingStep, version::Version};
+use massa_protocol_exports::Socks5Proxy;
+use massa_serialization::{DeserializeError, Deserializer, Serializer};
 use massa_signature::PublicKey;
 use massa_time::MassaTime;
 use massa_versioning::versioning::{ComponentStateTypeId, MipInfo, MipState, StateAtError};
@@ -17,15 +22,22 @@ use std::{
     collections::HashSet,
     io,
     net::{SocketAddr, TcpStream},
-    sync::{Arc, Condvar, Mutex},
-    time::Duration,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Condvar, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
 };
 use tracing::{debug, info, warn};
 
 use crate::{
     bindings::BootstrapClientBinder,
     error::BootstrapError,
-    messages::{BootstrapClientMessage, BootstrapServerMessage},
+    messages::{
+        BootstrapClientMessage, BootstrapClientMessageDeserializer,
+        BootstrapClientMessageSerializer, BootstrapServerMessage,
+    },
     settings::IpType,
     BootstrapConfig, GlobalBootstrapState,
 };
@@ -43,8 +55,11 @@ pub trait BSConnector {
 }
 
 /// Initiates a connection with given timeout in milliseconds
-#[derive(Debug)]
-pub struct DefaultConnector;
+#[derive(Debug, Default)]
+pub struct DefaultConnector {
+    /// SOCKS5 proxy through which the connections are made, if any
+    pub proxy: Option<Socks5Proxy>,
+}
 
 impl BSConnector for DefaultConnector {
     /// Tries to connect to address
@@ -56,12 +71,69 @@ impl BSConnector for DefaultConnector {
         addr: SocketAddr,
         duration: Option<MassaTime>,
     ) -> io::Result<TcpStream> {
+        if let Some(proxy) = &self.proxy {
+            return proxy.connect(addr, duration.map(|duration| duration.to_duration()));
+        }
         let Some(duration) = duration else {
             return TcpStream::connect(addr);
         };
         TcpStream::connect_timeout(&addr, duration.to_duration())
     }
 }
+
+/// Phases of the bootstrap of our node, as reported by the `bootstrap_phase` metric
+#[derive(Clone, Copy)]
+enum BootstrapPhase {
+    Connecting = 1,
+    FinalState = 2,
+    Consensus = 3,
+    Peers = 4,
+    Finished = 5,
+}
+
+fn set_bootstrap_phase(client: &BootstrapClientBinder, phase: BootstrapPhase) {
+    if let Some(massa_metrics) = client.massa_metrics() {
+        massa_metrics.set_bootstrap_phase(phase as i64);
+    }
+}
+
+/// Estimates the share of the final state preceding `last_state_step`, assuming that the state is
+/// mostly made of the ledger, whose user addresses are spread uniformly on the first bytes of their
+/// hash (see `state_ranges`)
+pub(crate) fn estimate_state_progress(last_state_step: &StreamingStep<Vec<u8>>) -> f64 {
+    let ledger_start = LEDGER_PREFIX.as_bytes();
+    match last_state_step {
+        StreamingStep::Started => 0.0,
+        StreamingStep::Finished(_) => 1.0,
+        StreamingStep::Ongoing(last_key) if last_key.as_slice() < ledger_start => 0.0,
+        StreamingStep::Ongoing(last_key) if !last_key.starts_with(ledger_start) => 1.0,
+        StreamingStep::Ongoing(last_key) => {
+            // key version, address type and address version, then the hash
+            let address = &last_key[ledger_start.len()..];
+            match address {
+                [_, 0, _, high, low, ..] => u16::from_be_bytes([*high, *low]) as f64 / 65536.0,
+                [_, 0, _, high] => *high as f64 / 256.0,
+                [_, 0, ..] => 0.0,
+                // the smart contract addresses come after the user ones, and are far fewer
+                _ => 1.0,
+            }
+        }
+    }
+}
+
+/// Estimates the time left to download the final state, from the progress made during `elapsed`
+pub(crate) fn estimate_state_eta(
+    elapsed: Duration,
+    initial_progress: f64,
+    progress: f64,
+) -> Option<Duration> {
+    if progress <= initial_progress {
+        return None;
+    }
+    let ratio = (1.0 - progress) / (progress - initial_progress);
+    Duration::try_from_secs_f64(elapsed.as_secs_f64() * ratio).ok()
+}
+
 /// This function will send the starting point to receive a stream of the ledger and will receive and process each part until receive a `BootstrapServerMessage::FinalStateFinished` message from the server.
 /// `next_bootstrap_message` passed as parameter must be `BootstrapClientMessage::AskFinalStatePart` enum variant.
 /// `next_bootstrap_message` will be updated after receiving each part so that in case of connection lost we can restart from the last message we processed.
@@ -71,11 +143,17 @@ fn stream_final_state_and_consensus(
     next_bootstrap_message: &mut BootstrapClientMessage,
     global_bootstrap_state: &mut GlobalBootstrapState,
 ) -> Result<(), BootstrapError> {
-    if let BootstrapClientMessage::AskBootstrapPart { .. } = &next_bootstrap_message {
+    if let BootstrapClientMessage::AskBootstrapPart {
+        last_state_step, ..
+    } = &next_bootstrap_message
+    {
+        let session_start = Instant::now();
+        let initial_progress = estimate_state_progress(last_state_step);
         client.send_timeout(
             next_bootstrap_message,
             Some(cfg.write_timeout.to_duration()),
         )?;
+        set_bootstrap_phase(client, BootstrapPhase::FinalState);
 
         loop {
             match client.next_timeout(Some(cfg.read_timeout.to_duration()))? {
@@ -133,6 +211,18 @@ fn stream_final_state_and_consensus(
                             .collect(),
                     );
 
+                    if let Some(massa_metrics) = client.massa_metrics() {
+                        let progress = estimate_state_progress(&last_state_step);
+                        let elapsed = session_start.elapsed();
+                        massa_metrics.on_bootstrap_part(
+                            progress,
+                            estimate_state_eta(elapsed, initial_progress, progress),
+                        );
+                    }
+                    if last_state_step.finished() && last_versioning_step.finished() {
+                        set_bootstrap_phase(client, BootstrapPhase::Consensus);
+                    }
+
                     // Set new message in case of disconnection
                     *next_bootstrap_message = BootstrapClientMessage::AskBootstrapPart {
                         last_slot: Some(slot),
@@ -141,6 +231,8 @@ fn stream_final_state_and_consensus(
                         last_consensus_step,
                         send_last_start_period: false,
                     };
+                    // Save it in case of restart
+                    save_bootstrap_cursor(write_final_state.get_database(), next_bootstrap_message);
 
                     // Logs for an easier diagnostic if needed
                     debug!(
@@ -156,6 +248,7 @@ fn stream_final_state_and_consensus(
                     // Update MIP store by reading from the disk
                     let mut guard = global_bootstrap_state.final_state.write();
                     let db = guard.get_database().clone();
+                    db.read().set_bootstrap_cursor(None);
                     let (updated, added) = guard
                         .get_mip_store_mut()
                         .extend_from_db(db)
@@ -167,15 +260,7 @@ fn stream_final_state_and_consensus(
                 }
                 BootstrapServerMessage::SlotTooOld => {
                     info!("Slot is too old retry bootstrap from scratch");
-                    *next_bootstrap_message = BootstrapClientMessage::AskBootstrapPart {
-                        last_slot: None,
-                        last_state_step: StreamingStep::Started,
-                        last_versioning_step: StreamingStep::Started,
-                        last_consensus_step: StreamingStep::Started,
-                        send_last_start_period: true,
-                    };
-                    let mut write_final_state = global_bootstrap_state.final_state.write();
-                    write_final_state.reset();
+                    restart_bootstrap_from_scratch(next_bootstrap_message, global_bootstrap_state);
                     return Err(BootstrapError::GeneralError(String::from("Slot too old")));
                 }
                 // At this point, we have successfully received the next message from the server, and it's an error-message String
@@ -197,6 +282,131 @@ fn stream_final_state_and_consensus(
     }
 }
 
+/// Saves the final state cursor of `next_bootstrap_message` in the database, so that an interrupted
+/// bootstrap resumes from it after a restart of the node. The consensus blocks are kept in memory
+/// only: they are asked again from the start when resuming.
+fn save_bootstrap_cursor(
+    db: &ShareableMassaDBController,
+    next_bootstrap_message: &BootstrapClientMessage,
+) {
+    let BootstrapClientMessage::AskBootstrapPart {
+        last_slot,
+        last_state_step,
+        last_versioning_step,
+        ..
+    } = next_bootstrap_message
+    else {
+        return;
+    };
+    let cursor = BootstrapClientMessage::AskBootstrapPart {
+        last_slot: *last_slot,
+        last_state_step: last_state_step.clone(),
+        last_versioning_step: last_versioning_step.clone(),
+        last_consensus_step: StreamingStep::Started,
+        send_last_start_period: true,
+    };
+    let mut cursor_bytes = Vec::new();
+    match BootstrapClientMessageSerializer::new().serialize(&cursor, &mut cursor_bytes) {
+        Ok(()) => db.read().set_bootstrap_cursor(Some(&cursor_bytes)),
+        Err(e) => warn!("Could not save the bootstrap cursor: {}", e),
+    }
+}
+
+/// Loads the cursor of an interrupted bootstrap from the database, if any
+fn load_bootstrap_cursor(
+    cfg: &BootstrapConfig,
+    db: &ShareableMassaDBController,
+) -> Option<BootstrapClientMessage> {
+    let cursor_bytes = db.read().get_bootstrap_cursor()?;
+    let deserializer = BootstrapClientMessageDeserializer::new(
+        cfg.thread_count,
+        cfg.max_datastore_key_length,
+        cfg.max_bootstrap_blocks_length.into(),
+    );
+    match deserializer.deserialize::<DeserializeError>(&cursor_bytes) {
+        Ok((_, cursor @ BootstrapClientMessage::AskBootstrapPart { .. })) => Some(cursor),
+        _ => {
+            warn!("Ignoring the invalid bootstrap cursor found in the database");
+            None
+        }
+    }
+}
+
+/// Downloads a range of the final state from a bootstrap server, until the range is verified
+/// against the hash sent by the server along with the last part.
+/// `last_slot` and `last_state_step` are updated after each part: asking the range again then only
+/// downloads the changes since the last part.
+fn stream_state_range(
+    cfg: &BootstrapConfig,
+    client: &mut BootstrapClientBinder,
+    final_state: &Arc<RwLock<dyn FinalStateController>>,
+    range: &StateRange,
+    last_slot: &mut Option<Slot>,
+    last_state_step: &mut StreamingStep<Vec<u8>>,
+) -> Result<(), BootstrapError> {
+    client.send_timeout(
+        &BootstrapClientMessage::AskStateRange {
+            range: range.clone(),
+            last_slot: *last_slot,
+            last_state_step: last_state_step.clone(),
+        },
+        Some(cfg.write_timeout.to_duration()),
+    )?;
+
+    loop {
+        match client.next_timeout(Some(cfg.read_timeout.to_duration()))? {
+            BootstrapServerMessage::StateRangePart {
+                state_part,
+                range_hash,
+            } => {
+                let final_state_read = final_state.read();
+                let mut db = final_state_read.get_database().write();
+
+                // The ranges are downloaded at different slots, while the change id of the
+                // database has to increase: it is set to the slot of the whole state afterwards.
+                let slot = state_part.change_id;
+                let change_id =
+                    std::cmp::max(slot, db.get_change_id().expect(CHANGE_ID_DESER_ERROR));
+                let versioning_part = StreamBatch {
+                    new_elements: BTreeMap::new(),
+                    updates_on_previous_elements: BTreeMap::new(),
+                    change_id,
+                };
+                let (new_state_step, _) = db
+                    .write_batch_bootstrap_client(
+                        StreamBatch {
+                            change_id,
+                            ..state_part
+                        },
+                        versioning_part,
+                    )
+                    .map_err(|e| {
+                        BootstrapError::GeneralError(format!(
+                            "Cannot write received stream batch to disk: {}",
+                            e
+                        ))
+                    })?;
+                *last_slot = Some(slot);
+                *last_state_step = new_state_step;
+
+                if let Some(range_hash) = range_hash {
+                    if db.get_range_xof_hash(range) != range_hash {
+                        return Err(BootstrapError::GeneralError(format!(
+                            "state range {:?} does not match the hash sent by the server",
+                            range
+                        )));
+                    }
+                    return Ok(());
+                }
+            }
+            BootstrapServerMessage::BootstrapError { error } => {
+                return Err(BootstrapError::ReceivedError(error))
+            }
+            other => return Err(BootstrapError::UnexpectedServerMessage(other)),
+        }
+    }
+}
+
 /// Gets the state from a bootstrap server (internal private function)
 /// needs to be CANCELLABLE
 pub(crate) fn bootstrap_from_server(
@@ -207,22 +417,92 @@ pub(crate) fn bootstrap_from_server(
     our_version: Version,
 ) -> Result<(), BootstrapError> {
     massa_trace!("bootstrap.lib.bootstrap_from_server", {});
+    let differential_bootstrap = start_session(cfg, client, our_version)?;
+    if cfg.differential_bootstrap
+        && !differential_bootstrap
+        && asks_state_changes_only(next_bootstrap_message)
+    {
+        info!("The server does not send the changes of the final state only, bootstrapping the whole state");
+        restart_bootstrap_from_scratch(next_bootstrap_message, global_bootstrap_state);
+    }
+    run_session(cfg, client, next_bootstrap_message, global_bootstrap_state)
+}
 
-    // read error (if sent by the server)
-    // client.next() is not cancel-safe but we drop the whole client object if cancelled => it's OK
-    match client.next_timeout(Some(cfg.read_error_timeout.to_duration())) {
-        Err(BootstrapError::TimedOut(_)) => {
-            massa_trace!(
-                "bootstrap.lib.bootstrap_from_server: No error sent at connection",
-                {}
-            );
-        }
-        Err(e) => return Err(e),
-        Ok(BootstrapServerMessage::BootstrapError { error: err }) => {
-            return Err(BootstrapError::ReceivedError(err))
+/// Whether `message` only asks the changes of the final state and of the versioning since a slot
+fn asks_state_changes_only(message: &BootstrapClientMessage) -> bool {
+    matches!(
+        message,
+        BootstrapClientMessage::AskBootstrapPart {
+            last_slot: Some(_),
+            last_state_step: StreamingStep::Finished(_),
+            last_versioning_step: StreamingStep::Finished(_),
+            ..
         }
-        Ok(msg) => return Err(BootstrapError::UnexpectedServerMessage(msg)),
+    )
+}
+
+/// Resets the final state, so that it is bootstrapped again from the start
+fn restart_bootstrap_from_scratch(
+    next_bootstrap_message: &mut BootstrapClientMessage,
+    global_bootstrap_state: &mut GlobalBootstrapState,
+) {
+    *next_bootstrap_message = BootstrapClientMessage::AskBootstrapPart {
+        last_slot: None,
+        last_state_step: StreamingStep::Started,
+        last_versioning_step: StreamingStep::Started,
+        last_consensus_step: StreamingStep::Started,
+        send_last_start_period: true,
     };
+    let mut write_final_state = global_bootstrap_state.final_state.write();
+    write_final_state.reset();
+    write_final_state
+        .get_database()
+        .read()
+        .set_bootstrap_cursor(None);
+}
+
+/// Reads the error sent by the server at connection if any, then makes the handshake and checks
+/// the ping and the clocks.
+///
+/// Returns whether the server sends the changes of the final state since the slot of a client
+/// that kept its final state.
+fn start_session(
+    cfg: &BootstrapConfig,
+    client: &mut BootstrapClientBinder,
+    our_version: Version,
+) -> Result<bool, BootstrapError> {
+    // read error (if sent by the server), or our position in its queue until our turn
+    // client.next() is not cancel-safe but we drop the whole client object if cancelled => it's OK
+    let mut queued = false;
+    loop {
+        let timeout = if queued {
+            cfg.read_timeout.to_duration()
+        } else {
+            cfg.read_error_timeout.to_duration()
+        };
+        match client.next_timeout(Some(timeout)) {
+            Err(BootstrapError::TimedOut(_)) if !queued => {
+                massa_trace!(
+                    "bootstrap.lib.bootstrap_from_server: No error sent at connection",
+                    {}
+                );
+                break;
+            }
+            Err(e) => return Err(e),
+            Ok(BootstrapServerMessage::BootstrapError { error: err }) => {
+                return Err(BootstrapError::ReceivedError(err))
+            }
+            Ok(BootstrapServerMessage::BootstrapQueued { position: 0 }) => break,
+            Ok(BootstrapServerMessage::BootstrapQueued { position }) => {
+                info!(
+                    "Waiting for a free slot on the bootstrap server, position in the queue: {}",
+                    position
+                );
+                queued = true;
+            }
+            Ok(msg) => return Err(BootstrapError::UnexpectedServerMessage(msg)),
+        }
+    }
 
     // handshake
     let send_time_uncompensated = MassaTime::now();
@@ -239,25 +519,27 @@ pub(crate) fn bootstrap_from_server(
 
     // First, clock and version.
     // client.next() is not cancel-safe but we drop the whole client object if cancelled => it's OK
-    let server_time = match client.next_timeout(Some(cfg.read_timeout.into())) {
-        Err(e) => return Err(e),
-        Ok(BootstrapServerMessage::BootstrapTime {
-            server_time,
-            version,
-        }) => {
-            if !our_version.is_compatible(&version) {
-                return Err(BootstrapError::IncompatibleVersionError(format!(
-                    "remote is running incompatible version: {} (local node version: {})",
-                    version, our_version
-                )));
+    let (server_time, differential_bootstrap) =
+        match client.next_timeout(Some(cfg.read_timeout.into())) {
+            Err(e) => return Err(e),
+            Ok(BootstrapServerMessage::BootstrapTime {
+                server_time,
+                version,
+                differential_bootstrap,
+            }) => {
+                if !our_version.is_compatible(&version) {
+                    return Err(BootstrapError::IncompatibleVersionError(format!(
+                        "remote is running incompatible version: {} (local node version: {})",
+                        version, our_version
+                    )));
+                }
+                (server_time, differential_bootstrap)
             }
-            server_time
-        }
-        Ok(BootstrapServerMessage::BootstrapError { error }) => {
-            return Err(BootstrapError::ReceivedError(error))
-        }
-        Ok(msg) => return Err(BootstrapError::UnexpectedServerMessage(msg)),
-    };
+            Ok(BootstrapServerMessage::BootstrapError { error }) => {
+                return Err(BootstrapError::ReceivedError(error))
+            }
+            Ok(msg) => return Err(BootstrapError::UnexpectedServerMessage(msg)),
+        };
 
     // get the time of reception
     let recv_time = MassaTime::now();
@@ -285,7 +567,16 @@ pub(crate) fn bootstrap_from_server(
         );
         return Err(BootstrapError::ClockError(message));
     }
+    Ok(differential_bootstrap)
+}
 
+/// Asks data to the server, starting from `next_bootstrap_message`, until the bootstrap succeeds
+fn run_session(
+    cfg: &BootstrapConfig,
+    client: &mut BootstrapClientBinder,
+    next_bootstrap_message: &mut BootstrapClientMessage,
+    global_bootstrap_state: &mut GlobalBootstrapState,
+) -> Result<(), BootstrapError> {
     let write_timeout: std::time::Duration = cfg.write_timeout.into();
     // Loop to ask data to the server depending on the last message we sent
     loop {
@@ -299,6 +590,7 @@ pub(crate) fn bootstrap_from_server(
                 )?;
             }
             BootstrapClientMessage::AskBootstrapPeers => {
+                set_bootstrap_phase(client, BootstrapPhase::Peers);
                 let peers = match send_client_message(
                     next_bootstrap_message,
                     client,
@@ -317,11 +609,15 @@ pub(crate) fn bootstrap_from_server(
             }
             BootstrapClientMessage::BootstrapSuccess => {
                 client.send_timeout(next_bootstrap_message, Some(write_timeout))?;
+                set_bootstrap_phase(client, BootstrapPhase::Finished);
                 break;
             }
             BootstrapClientMessage::BootstrapError { error: _ } => {
                 panic!("The next message to send shouldn't be BootstrapError");
             }
+            BootstrapClientMessage::AskStateRange { .. } => {
+                panic!("The next message to send shouldn't be AskStateRange");
+            }
         };
     }
     info!("Successful bootstrap");
@@ -348,7 +644,7 @@ fn send_client_message(
 }
 
 pub(crate) fn connect_to_server(
-    connector: &mut impl BSConnector,
+    connector: &impl BSConnector,
     bootstrap_config: &BootstrapConfig,
     addr: &SocketAddr,
     pub_key: &PublicKey,
@@ -364,6 +660,234 @@ pub(crate) fn connect_to_server(
     ))
 }
 
+/// Splits the final state into `count` ranges (at least 2) to download in parallel: the first one
+/// holds everything before the ledger (PoS state, async pool, executed operations...), and the
+/// others split the ledger on the first byte of the hash of the addresses. This only balances the
+/// ranges, which cover the whole state whatever the distribution of the keys.
+pub(crate) fn state_ranges(count: usize) -> Vec<StateRange> {
+    let ledger_start = LEDGER_PREFIX.as_bytes().to_vec();
+    let ledger_range_count = count.saturating_sub(1).max(1);
+    // first key of the user addresses whose hash starts after the given share of the hash space:
+    // key version, address type and address version, then the first byte of the hash
+    let boundary = |index: usize| {
+        let mut key = ledger_start.clone();
+        key.extend([0, 0, 0, (index * 256 / ledger_range_count) as u8]);
+        key
+    };
+
+    let mut ranges = vec![StateRange {
+        start: Vec::new(),
+        end: Some(ledger_start.clone()),
+    }];
+    for index in 0..ledger_range_count {
+        ranges.push(StateRange {
+            start: if index == 0 {
+                ledger_start.clone()
+            } else {
+                boundary(index)
+            },
+            end: (index + 1 < ledger_range_count).then(|| boundary(index + 1)),
+        });
+    }
+    ranges
+}
+
+/// Settings of a parallel bootstrap, and progress shared by the threads downloading the ranges
+struct ParallelBootstrap<'a, C> {
+    cfg: &'a BootstrapConfig,
+    connector: &'a C,
+    final_state: &'a Arc<RwLock<dyn FinalStateController>>,
+    our_version: Version,
+    massa_metrics: &'a MassaMetrics,
+    /// number of ranges that are not downloaded yet
+    remaining_ranges: AtomicUsize,
+    /// set when the download of a range fails, to stop the other ones
+    failed: AtomicBool,
+}
+
+/// Downloads a range of the final state from a server, then keeps it up to date until all the
+/// ranges are downloaded, so that their slots stay close to each other and within the change
+/// history of the servers. Returns the connection to the server, and the slot of the range.
+fn bootstrap_state_range(
+    bootstrap: &ParallelBootstrap<impl BSConnector>,
+    (addr, node_id): &(SocketAddr, NodeId),
+    range: &StateRange,
+) -> Result<(BootstrapClientBinder, Slot), BootstrapError> {
+    let cfg = bootstrap.cfg;
+    info!(
+        "Start bootstrapping the state range {:?} from {}",
+        range, addr
+    );
+    let client = connect_to_server(
+        bootstrap.connector,
+        cfg,
+        addr,
+        &node_id.get_public_key(),
+        Some(cfg.rate_limit),
+    )?;
+    let mut client = client.with_massa_metrics(bootstrap.massa_metrics.clone());
+    bootstrap.massa_metrics.inc_bootstrap_counter();
+
+    // the servers end the sessions whose client stays silent for longer than their read timeout,
+    // assumed to be the same as ours
+    let refresh_interval = cfg.read_timeout.to_duration() / 2;
+    let mut last_slot = None;
+    let mut last_state_step = StreamingStep::Started;
+    let streamed = start_session(cfg, &mut client, bootstrap.our_version).and_then(|_| loop {
+        let all_downloaded = bootstrap.remaining_ranges.load(Ordering::SeqCst) == 0;
+        let downloaded = last_state_step.finished();
+        stream_state_range(
+            cfg,
+            &mut client,
+            bootstrap.final_state,
+            range,
+            &mut last_slot,
+            &mut last_state_step,
+        )?;
+        if !downloaded {
+            bootstrap.remaining_ranges.fetch_sub(1, Ordering::SeqCst);
+        }
+        if all_downloaded {
+            break Ok(());
+        }
+        if bootstrap.failed.load(Ordering::SeqCst) {
+            break Err(BootstrapError::GeneralError(
+                "another state range failed".to_string(),
+            ));
+        }
+        thread::sleep(refresh_interval);
+    });
+    match streamed {
+        Ok(()) => Ok((
+            client,
+            last_slot.expect("state range downloaded without any part"),
+        )),
+        Err(e) => {
+            bootstrap.failed.store(true, Ordering::SeqCst);
+            // the connection is dropped anyway, so a failure to send the error does not matter
+            let _ = client.send_timeout(
+                &BootstrapClientMessage::BootstrapError {
+                    error: e.to_string(),
+                },
+                Some(cfg.write_error_timeout.into()),
+            );
+            Err(e)
+        }
+    }
+}
+
+/// Checks each downloaded range against another server than the one it was downloaded from: the
+/// range is asked again to this server from the slot of the range, so that only the changes since
+/// then are downloaded, along with the hash of the range as this server sees it.
+///
+/// A server sending a corrupted range would need all the other servers to agree with it.
+/// The slots of the ranges are updated to the slots at which they were checked.
+fn cross_check_state_ranges(
+    cfg: &BootstrapConfig,
+    final_state: &Arc<RwLock<dyn FinalStateController>>,
+    ranges: &[StateRange],
+    sessions: &mut [(BootstrapClientBinder, Slot)],
+) -> Result<(), BootstrapError> {
+    // the slots of the servers, as of the last part they sent
+    let mut server_slots: Vec<Slot> = sessions.iter().map(|(_, slot)| *slot).collect();
+    for (index, range) in ranges.iter().enumerate() {
+        // a server can only send the changes up to its own slot, so the most advanced one is used
+        let checker = (0..sessions.len())
+            .filter(|other| *other != index)
+            .max_by_key(|other| server_slots[*other])
+            .expect("a parallel bootstrap needs at least two servers");
+        let mut last_slot = Some(sessions[index].1);
+        stream_state_range(
+            cfg,
+            &mut sessions[checker].0,
+            final_state,
+            range,
+            &mut last_slot,
+            &mut StreamingStep::Finished(None),
+        )?;
+        let slot = last_slot.expect("state range checked without any part");
+        sessions[index].1 = slot;
+        server_slots[checker] = slot;
+    }
+    Ok(())
+}
+
+/// Downloads the final state from several servers in parallel, one range of the state per server,
+/// then cross-checks the ranges between the servers.
+///
+/// Returns the connection to the first server, along with the slot of the oldest range: the
+/// bootstrap goes on with this server from this slot, so that the changes of all the ranges are
+/// caught up to the same slot.
+pub(crate) fn parallel_state_bootstrap(
+    cfg: &BootstrapConfig,
+    connector: &(impl BSConnector + Sync),
+    final_state: &Arc<RwLock<dyn FinalStateController>>,
+    servers: &[(SocketAddr, NodeId)],
+    our_version: Version,
+    massa_metrics: &MassaMetrics,
+) -> Result<(BootstrapClientBinder, Slot), BootstrapError> {
+    let ranges = state_ranges(std::cmp::min(cfg.parallel_bootstrap_servers, servers.len()));
+    massa_metrics.set_bootstrap_phase(BootstrapPhase::Connecting as i64);
+    massa_metrics.set_bootstrap_servers(
+        &servers
+            .iter()
+            .take(ranges.len())
+            .map(|(addr, _)| addr.to_string())
+            .collect::<Vec<_>>(),
+    );
+    let bootstrap = ParallelBootstrap {
+        cfg,
+        connector,
+        final_state,
+        our_version,
+        massa_metrics,
+        remaining_ranges: AtomicUsize::new(ranges.len()),
+        failed: AtomicBool::new(false),
+    };
+    let results: Vec<_> = thread::scope(|scope| {
+        let handles: Vec<_> = ranges
+            .iter()
+            .zip(servers)
+            .map(|(range, server)| {
+                let bootstrap = &bootstrap;
+                scope.spawn(move || bootstrap_state_range(bootstrap, server, range))
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("state range thread panicked"))
+            .collect()
+    });
+
+    let mut sessions = results.into_iter().collect::<Result<Vec<_>, _>>()?;
+    if let Err(e) = cross_check_state_ranges(cfg, final_state, &ranges, &mut sessions) {
+        // the connections are dropped anyway, so a failure to send the error does not matter
+        for (client, _) in sessions.iter_mut() {
+            let _ = client.send_timeout(
+                &BootstrapClientMessage::BootstrapError {
+                    error: e.to_string(),
+                },
+                Some(cfg.write_error_timeout.into()),
+            );
+        }
+        return Err(e);
+    }
+    let min_slot = sessions
+        .iter()
+        .map(|(_, slot)| *slot)
+        .min()
+        .expect("no state range");
+    // end the sessions with the other servers
+    for (client, _) in sessions.iter_mut().skip(1) {
+        let _ = client.send_timeout(
+            &BootstrapClientMessage::BootstrapSuccess,
+            Some(cfg.write_timeout.into()),
+        );
+    }
+    let (client, _) = sessions.swap_remove(0);
+    Ok((client, min_slot))
+}
+
 fn filter_bootstrap_list(
     bootstrap_list: Vec<(SocketAddr, NodeId)>,
     ip_type: IpType,
@@ -397,7 +921,7 @@ fn filter_bootstrap_list(
 pub fn get_state(
     bootstrap_config: &BootstrapConfig,
     final_state: Arc<RwLock<dyn FinalStateController>>,
-    mut connector: impl BSConnector,
+    connector: impl BSConnector + Sync,
     version: Version,
     genesis_timestamp: MassaTime,
     end_timestamp: Option<MassaTime>,
@@ -486,8 +1010,84 @@ pub fn get_state(
             last_consensus_step: StreamingStep::Started,
             send_last_start_period: true,
         };
+    let saved_cursor = load_bootstrap_cursor(bootstrap_config, final_state.read().get_database());
     let mut global_bootstrap_state = GlobalBootstrapState::new(final_state);
 
+    if let Some(cursor) = saved_cursor {
+        info!("Resuming the interrupted bootstrap");
+        next_bootstrap_message = cursor;
+    } else if bootstrap_config.differential_bootstrap {
+        let slot = global_bootstrap_state
+            .final_state
+            .read()
+            .get_database()
+            .read()
+            .get_change_id()
+            .map_err(|e| BootstrapError::GeneralError(e.to_string()))?;
+        // the server answers SlotTooOld if the changes since our slot have left its history,
+        // in which case the whole state is bootstrapped
+        info!(
+            "Bootstrapping the changes of the final state since slot {}",
+            slot
+        );
+        next_bootstrap_message = BootstrapClientMessage::AskBootstrapPart {
+            last_slot: Some(slot),
+            last_state_step: StreamingStep::Finished(None),
+            last_versioning_step: StreamingStep::Finished(None),
+            last_consensus_step: StreamingStep::Started,
+            send_last_start_period: true,
+        };
+    } else if bootstrap_config.parallel_bootstrap_servers > 1 && filtered_bootstrap_list.len() > 1 {
+        let parallel_bootstrap = parallel_state_bootstrap(
+            bootstrap_config,
+            &connector,
+            &global_bootstrap_state.final_state,
+            &filtered_bootstrap_list,
+            version,
+            &massa_metrics,
+        );
+        match parallel_bootstrap {
+            Ok((mut client, slot)) => {
+                info!(
+                    "State ranges downloaded, catching up their changes since slot {}",
+                    slot
+                );
+                // the rest of the bootstrap resumes from this message, whatever the server
+                next_bootstrap_message = BootstrapClientMessage::AskBootstrapPart {
+                    last_slot: Some(slot),
+                    last_state_step: StreamingStep::Finished(None),
+                    last_versioning_step: StreamingStep::Started,
+                    last_consensus_step: StreamingStep::Started,
+                    send_last_start_period: true,
+                };
+                match run_session(
+                    bootstrap_config,
+                    &mut client,
+                    &mut next_bootstrap_message,
+                    &mut global_bootstrap_state,
+                ) {
+                    Ok(()) => return Ok(global_bootstrap_state),
+                    Err(e) => {
+                        warn!("Error while bootstrapping: {}", &e);
+                        let _ = client.send_timeout(
+                            &BootstrapClientMessage::BootstrapError {
+                                error: e.to_string(),
+                            },
+                            Some(bootstrap_config.write_error_timeout.into()),
+                        );
+                    }
+                }
+            }
+            Err(e) => {
+                warn!(
+                    "Parallel bootstrap failed, bootstrapping from a single server: {}",
+                    e
+                );
+                global_bootstrap_state.final_state.write().reset();
+            }
+        }
+    }
+
     let limit = bootstrap_config.rate_limit;
     loop {
         // check for interuption
@@ -503,15 +1103,18 @@ pub fn get_state(
                 }
             }
             info!("Start bootstrapping from {}", addr);
+            massa_metrics.set_bootstrap_phase(BootstrapPhase::Connecting as i64);
+            massa_metrics.set_bootstrap_servers(&[addr.to_string()]);
             let conn = connect_to_server(
-                &mut connector,
+                &connector,
                 bootstrap_config,
                 addr,
                 &node_id.get_public_key(),
                 Some(limit),
             );
             match conn {
-                Ok(mut client) => {
+                Ok(client) => {
+                    let mut client = client.with_massa_metrics(massa_metrics.clone());
                     massa_metrics.inc_bootstrap_counter();
                     let bs = bootstrap_from_server(
                         bootstrap_config,
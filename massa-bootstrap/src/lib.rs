@@ -26,6 +26,7 @@ pub use error::BootstrapError;
 mod listener;
 mod messages;
 mod server;
+mod sessions;
 mod settings;
 mod tools;
 /// white/black list
@@ -38,6 +39,7 @@ pub use messages::{
     BootstrapServerMessage, BootstrapServerMessageDeserializer, BootstrapServerMessageSerializer,
 };
 pub use server::{start_bootstrap_server, BootstrapManager};
+pub use sessions::BootstrapRateLimits;
 pub use settings::IpType;
 pub use settings::{BootstrapConfig, BootstrapServerMessageDeserializerArgs};
 
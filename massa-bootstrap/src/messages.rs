@@ -5,7 +5,8 @@ use massa_consensus_exports::bootstrapable_graph::{
     BootstrapableGraph, BootstrapableGraphDeserializer, BootstrapableGraphSerializer,
 };
 
-use massa_db_exports::StreamBatch;
+use massa_db_exports::{StateRange, StreamBatch};
+use massa_hash::{HashXof, HashXofDeserializer, HashXofSerializer, HASH_XOF_SIZE_BYTES};
 
 use massa_models::block_id::{BlockId, BlockIdDeserializer, BlockIdSerializer};
 
@@ -28,6 +29,7 @@ use massa_serialization::{
 };
 
 use massa_time::{MassaTime, MassaTimeDeserializer, MassaTimeSerializer};
+use nom::combinator::opt;
 use nom::error::context;
 use nom::multi::{length_data, length_value, many0};
 use nom::sequence::tuple;
@@ -50,6 +52,9 @@ pub enum BootstrapServerMessage {
         server_time: MassaTime,
         /// The version of the bootstrap server.
         version: Version,
+        /// Whether the server sends the changes of the final state since the slot of a client
+        /// that kept its final state (false for the servers that do not send this flag)
+        differential_bootstrap: bool,
     },
     /// Bootstrap peers
     BootstrapPeers {
@@ -73,6 +78,13 @@ pub enum BootstrapServerMessage {
         /// Last Slot before downtime for network restart management
         last_slot_before_downtime: Option<Option<Slot>>,
     },
+    /// Part of a range of the final state
+    StateRangePart {
+        /// Part of the range, and its changes since the last part
+        state_part: StreamBatch<Slot>,
+        /// Hash of the entries of the range, sent along with its last part
+        range_hash: Option<HashXof<HASH_XOF_SIZE_BYTES>>,
+    },
     /// Message sent when the final state and consensus bootstrap are finished
     BootstrapFinished,
     /// Slot sent to get state changes is too old
@@ -82,6 +94,12 @@ pub enum BootstrapServerMessage {
         /// Error message
         error: String,
     },
+    /// Position in the queue of the sessions waiting for a free slot of the server, sent before
+    /// the handshake. The session starts once the position 0 is sent.
+    BootstrapQueued {
+        /// Position in the queue, starting at 1
+        position: u32,
+    },
 }
 
 impl ToString for BootstrapServerMessage {
@@ -90,11 +108,31 @@ impl ToString for BootstrapServerMessage {
             BootstrapServerMessage::BootstrapTime { .. } => "BootstrapTime".to_string(),
             BootstrapServerMessage::BootstrapPeers { .. } => "BootstrapPeers".to_string(),
             BootstrapServerMessage::BootstrapPart { .. } => "BootstrapPart".to_string(),
+            BootstrapServerMessage::StateRangePart { .. } => "StateRangePart".to_string(),
             BootstrapServerMessage::BootstrapFinished => "BootstrapFinished".to_string(),
             BootstrapServerMessage::SlotTooOld => "SlotTooOld".to_string(),
             BootstrapServerMessage::BootstrapError { error } => {
                 format!("BootstrapError {{ error: {} }}", error)
             }
+            BootstrapServerMessage::BootstrapQueued { position } => {
+                format!("BootstrapQueued {{ position: {} }}", position)
+            }
+        }
+    }
+}
+
+impl BootstrapServerMessage {
+    /// Name of the message type in the network metrics
+    pub fn metrics_name(&self) -> &'static str {
+        match self {
+            BootstrapServerMessage::BootstrapTime { .. } => "bootstrap_time",
+            BootstrapServerMessage::BootstrapPeers { .. } => "bootstrap_peers",
+            BootstrapServerMessage::BootstrapPart { .. } => "bootstrap_part",
+            BootstrapServerMessage::StateRangePart { .. } => "bootstrap_state_range_part",
+            BootstrapServerMessage::BootstrapFinished => "bootstrap_finished",
+            BootstrapServerMessage::SlotTooOld => "bootstrap_slot_too_old",
+            BootstrapServerMessage::BootstrapError { .. } => "bootstrap_server_error",
+            BootstrapServerMessage::BootstrapQueued { .. } => "bootstrap_queued",
         }
     }
 }
@@ -108,6 +146,8 @@ enum MessageServerTypeId {
     FinalStateFinished = 3u32,
     SlotTooOld = 4u32,
     BootstrapError = 5u32,
+    StateRangePart = 6u32,
+    BootstrapQueued = 7u32,
 }
 
 /// Serializer for `BootstrapServerMessage`
@@ -125,6 +165,8 @@ pub struct BootstrapServerMessageSerializer {
     opt_last_start_period_serializer: OptionSerializer<u64, U64VarIntSerializer>,
     opt_last_slot_before_downtime_serializer:
         OptionSerializer<Option<Slot>, OptionSerializer<Slot, SlotSerializer>>,
+    opt_range_hash_serializer: OptionSerializer<HashXof<HASH_XOF_SIZE_BYTES>, HashXofSerializer>,
+    bool_serializer: BoolSerializer,
 }
 
 impl Default for BootstrapServerMessageSerializer {
@@ -151,8 +193,51 @@ impl BootstrapServerMessageSerializer {
             opt_last_slot_before_downtime_serializer: OptionSerializer::new(OptionSerializer::new(
                 SlotSerializer::new(),
             )),
+            opt_range_hash_serializer: OptionSerializer::new(HashXofSerializer::new()),
+            bool_serializer: BoolSerializer::new(),
         }
     }
+
+    /// Serializes the new elements, then the updates, then the change id of a stream batch
+    fn serialize_stream_batch(
+        &self,
+        stream_batch: &StreamBatch<Slot>,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), SerializeError> {
+        // new_elements
+        let mut new_element_buffer: Vec<u8> = Vec::new();
+        for (key, value) in stream_batch.new_elements.iter() {
+            self.vec_u8_serializer
+                .serialize(key, &mut new_element_buffer)?;
+            self.vec_u8_serializer
+                .serialize(value, &mut new_element_buffer)?;
+        }
+        self.u64_serializer.serialize(
+            &new_element_buffer
+                .len()
+                .try_into()
+                .expect("Overflow of new_elements len"),
+            buffer,
+        )?;
+        buffer.extend(new_element_buffer);
+        // updates
+        let mut updates_buffer: Vec<u8> = Vec::new();
+        for (key, value) in stream_batch.updates_on_previous_elements.iter() {
+            self.vec_u8_serializer.serialize(key, &mut updates_buffer)?;
+            self.opt_vec_u8_serializer
+                .serialize(value, &mut updates_buffer)?;
+        }
+        self.u64_serializer.serialize(
+            &updates_buffer
+                .len()
+                .try_into()
+                .expect("Overflow of updates len"),
+            buffer,
+        )?;
+        buffer.extend(updates_buffer);
+        self.slot_serializer
+            .serialize(&stream_batch.change_id, buffer)
+    }
 }
 
 impl Serializer<BootstrapServerMessage> for BootstrapServerMessageSerializer {
@@ -168,6 +253,7 @@ impl Serializer<BootstrapServerMessage> for BootstrapServerMessageSerializer {
     /// let bootstrap_server_message = BootstrapServerMessage::BootstrapTime {
     ///    server_time: MassaTime::from_millis(0),
     ///    version: Version::from_str("TEST.1.10").unwrap(),
+    ///    differential_bootstrap: true,
     /// };
     /// let mut message_serialized = Vec::new();
     /// message_serializer.serialize(&bootstrap_server_message, &mut message_serialized).unwrap();
@@ -181,11 +267,14 @@ impl Serializer<BootstrapServerMessage> for BootstrapServerMessageSerializer {
             BootstrapServerMessage::BootstrapTime {
                 server_time,
                 version,
+                differential_bootstrap,
             } => {
                 self.u32_serializer
                     .serialize(&u32::from(MessageServerTypeId::BootstrapTime), buffer)?;
                 self.time_serializer.serialize(server_time, buffer)?;
                 self.version_serializer.serialize(version, buffer)?;
+                self.bool_serializer
+                    .serialize(differential_bootstrap, buffer)?;
             }
             BootstrapServerMessage::BootstrapPeers { peers } => {
                 self.u32_serializer
@@ -206,74 +295,10 @@ impl Serializer<BootstrapServerMessage> for BootstrapServerMessageSerializer {
                     .serialize(&u32::from(MessageServerTypeId::FinalStatePart), buffer)?;
                 // slot
                 self.slot_serializer.serialize(slot, buffer)?;
-                // state new_elements
-                let mut state_new_element_buffer: Vec<u8> = Vec::new();
-                for (key, value) in state_part.new_elements.iter() {
-                    self.vec_u8_serializer
-                        .serialize(key, &mut state_new_element_buffer)?;
-                    self.vec_u8_serializer
-                        .serialize(value, &mut state_new_element_buffer)?;
-                }
-                self.u64_serializer.serialize(
-                    &state_new_element_buffer
-                        .len()
-                        .try_into()
-                        .expect("Overflow of state new_elements len"),
-                    buffer,
-                )?;
-                buffer.extend(state_new_element_buffer);
-                // state updates
-                let mut state_updates_buffer: Vec<u8> = Vec::new();
-                for (key, value) in state_part.updates_on_previous_elements.iter() {
-                    self.vec_u8_serializer
-                        .serialize(key, &mut state_updates_buffer)?;
-                    self.opt_vec_u8_serializer
-                        .serialize(value, &mut state_updates_buffer)?;
-                }
-                self.u64_serializer.serialize(
-                    &state_updates_buffer
-                        .len()
-                        .try_into()
-                        .expect("Overflow of state updates len"),
-                    buffer,
-                )?;
-                buffer.extend(state_updates_buffer);
-                self.slot_serializer
-                    .serialize(&state_part.change_id, buffer)?;
-                // versioning new_elements
-                let mut versioning_new_element_buffer: Vec<u8> = Vec::new();
-                for (key, value) in versioning_part.new_elements.iter() {
-                    self.vec_u8_serializer
-                        .serialize(key, &mut versioning_new_element_buffer)?;
-                    self.vec_u8_serializer
-                        .serialize(value, &mut versioning_new_element_buffer)?;
-                }
-                self.u64_serializer.serialize(
-                    &versioning_new_element_buffer
-                        .len()
-                        .try_into()
-                        .expect("Overflow of versioning new_elements len"),
-                    buffer,
-                )?;
-                buffer.extend(versioning_new_element_buffer);
-                // versioning updates
-                let mut versioning_updates_buffer: Vec<u8> = Vec::new();
-                for (key, value) in versioning_part.updates_on_previous_elements.iter() {
-                    self.vec_u8_serializer
-                        .serialize(key, &mut versioning_updates_buffer)?;
-                    self.opt_vec_u8_serializer
-                        .serialize(value, &mut versioning_updates_buffer)?;
-                }
-                self.u64_serializer.serialize(
-                    &versioning_updates_buffer
-                        .len()
-                        .try_into()
-                        .expect("Overflow of versioning updates len"),
-                    buffer,
-                )?;
-                buffer.extend(versioning_updates_buffer);
-                self.slot_serializer
-                    .serialize(&versioning_part.change_id, buffer)?;
+                // state
+                self.serialize_stream_batch(state_part, buffer)?;
+                // versioning
+                self.serialize_stream_batch(versioning_part, buffer)?;
                 // consensus graph
                 self.bootstrapable_graph_serializer
                     .serialize(consensus_part, buffer)?;
@@ -287,6 +312,16 @@ impl Serializer<BootstrapServerMessage> for BootstrapServerMessageSerializer {
                 self.opt_last_slot_before_downtime_serializer
                     .serialize(last_slot_before_downtime, buffer)?;
             }
+            BootstrapServerMessage::StateRangePart {
+                state_part,
+                range_hash,
+            } => {
+                self.u32_serializer
+                    .serialize(&u32::from(MessageServerTypeId::StateRangePart), buffer)?;
+                self.serialize_stream_batch(state_part, buffer)?;
+                self.opt_range_hash_serializer
+                    .serialize(range_hash, buffer)?;
+            }
             BootstrapServerMessage::BootstrapFinished => {
                 self.u32_serializer
                     .serialize(&u32::from(MessageServerTypeId::FinalStateFinished), buffer)?;
@@ -306,6 +341,11 @@ impl Serializer<BootstrapServerMessage> for BootstrapServerMessageSerializer {
                 )?;
                 buffer.extend(error.as_bytes())
             }
+            BootstrapServerMessage::BootstrapQueued { position } => {
+                self.u32_serializer
+                    .serialize(&u32::from(MessageServerTypeId::BootstrapQueued), buffer)?;
+                self.u32_serializer.serialize(position, buffer)?;
+            }
         }
         Ok(())
     }
@@ -330,6 +370,10 @@ pub struct BootstrapServerMessageDeserializer {
     opt_last_start_period_deserializer: OptionDeserializer<u64, U64VarIntDeserializer>,
     opt_last_slot_before_downtime_deserializer:
         OptionDeserializer<Option<Slot>, OptionDeserializer<Slot, SlotDeserializer>>,
+    opt_range_hash_deserializer:
+        OptionDeserializer<HashXof<HASH_XOF_SIZE_BYTES>, HashXofDeserializer>,
+    queue_position_deserializer: U32VarIntDeserializer,
+    bool_deserializer: BoolDeserializer,
 }
 
 impl BootstrapServerMessageDeserializer {
@@ -396,8 +440,59 @@ impl BootstrapServerMessageDeserializer {
                     (Included(0), Excluded(args.thread_count)),
                 )),
             ),
+            opt_range_hash_deserializer: OptionDeserializer::new(HashXofDeserializer::new()),
+            queue_position_deserializer: U32VarIntDeserializer::new(
+                Included(0),
+                Included(u32::MAX),
+            ),
+            bool_deserializer: BoolDeserializer::new(),
         }
     }
+
+    /// Deserializes a stream batch, whose new elements are bounded by
+    /// `new_elements_length_deserializer`
+    fn deserialize_stream_batch<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        new_elements_length_deserializer: &U64VarIntDeserializer,
+        input: &'a [u8],
+    ) -> IResult<&'a [u8], StreamBatch<Slot>, E> {
+        tuple((
+            context(
+                "Failed new_elements deserialization",
+                length_value(
+                    context("Failed length deserialization", |input| {
+                        new_elements_length_deserializer.deserialize(input)
+                    }),
+                    many0(tuple((
+                        |input| self.datastore_key_deserializer.deserialize(input),
+                        |input| self.datastore_val_deserializer.deserialize(input),
+                    ))),
+                ),
+            ),
+            context(
+                "Failed updates deserialization",
+                length_value(
+                    context("Failed length deserialization", |input| {
+                        self.stream_batch_updates_length_deserializer
+                            .deserialize(input)
+                    }),
+                    many0(tuple((
+                        |input| self.datastore_key_deserializer.deserialize(input),
+                        |input| self.opt_vec_u8_deserializer.deserialize(input),
+                    ))),
+                ),
+            ),
+            context("Failed slot deserialization", |input| {
+                self.slot_deserializer.deserialize(input)
+            }),
+        ))
+        .map(|(new_elements, updates, change_id)| StreamBatch {
+            new_elements: new_elements.into_iter().collect(),
+            updates_on_previous_elements: updates.into_iter().collect(),
+            change_id,
+        })
+        .parse(input)
+    }
 }
 
 impl Deserializer<BootstrapServerMessage> for BootstrapServerMessageDeserializer {
@@ -431,6 +526,7 @@ impl Deserializer<BootstrapServerMessage> for BootstrapServerMessageDeserializer
     /// let bootstrap_server_message = BootstrapServerMessage::BootstrapTime {
     ///    server_time: MassaTime::from_millis(0),
     ///    version: Version::from_str("TEST.1.10").unwrap(),
+    ///    differential_bootstrap: true,
     /// };
     /// let mut message_serialized = Vec::new();
     /// message_serializer.serialize(&bootstrap_server_message, &mut message_serialized).unwrap();
@@ -439,13 +535,23 @@ impl Deserializer<BootstrapServerMessage> for BootstrapServerMessageDeserializer
     ///     BootstrapServerMessage::BootstrapTime {
     ///        server_time,
     ///        version,
+    ///        differential_bootstrap,
     ///    } => {
     ///     assert_eq!(server_time, MassaTime::from_millis(0));
     ///     assert_eq!(version, Version::from_str("TEST.1.10").unwrap());
+    ///     assert!(differential_bootstrap);
     ///   }
     ///   _ => panic!("Unexpected message"),
     /// }
     /// assert_eq!(rest.len(), 0);
+    ///
+    /// // the servers that do not send the flag do not support the differential bootstrap
+    /// message_serialized.pop();
+    /// let (_, message_deserialized) = message_deserializer.deserialize::<DeserializeError>(&message_serialized).unwrap();
+    /// assert!(matches!(
+    ///     message_deserialized,
+    ///     BootstrapServerMessage::BootstrapTime { differential_bootstrap: false, .. }
+    /// ));
     /// ```
     fn deserialize<'a, E: nom::error::ParseError<&'a [u8]> + nom::error::ContextError<&'a [u8]>>(
         &self,
@@ -472,13 +578,19 @@ impl Deserializer<BootstrapServerMessage> for BootstrapServerMessageDeserializer
                     context("Failed version deserialization", |input| {
                         self.version_deserializer.deserialize(input)
                     }),
+                    // absent from the messages of the servers that do not support it
+                    opt(context(
+                        "Failed differential_bootstrap deserialization",
+                        |input| self.bool_deserializer.deserialize(input),
+                    )),
                 ))
-                .map(
-                    |(server_time, version)| BootstrapServerMessage::BootstrapTime {
+                .map(|(server_time, version, differential_bootstrap)| {
+                    BootstrapServerMessage::BootstrapTime {
                         server_time,
                         version,
-                    },
-                )
+                        differential_bootstrap: differential_bootstrap.unwrap_or(false),
+                    }
+                })
                 .parse(input),
                 MessageServerTypeId::Peers => context("Failed peers deserialization", |input| {
                     self.peers_deserializer.deserialize(input)
@@ -489,74 +601,18 @@ impl Deserializer<BootstrapServerMessage> for BootstrapServerMessageDeserializer
                     context("Failed slot deserialization", |input| {
                         self.slot_deserializer.deserialize(input)
                     }),
-                    context(
-                        "Failed state_part deserialization",
-                        tuple((
-                            context(
-                                "Failed new_elements deserialization",
-                                length_value(
-                                    context("Failed length deserialization", |input| {
-                                        self.state_new_elements_length_deserializer
-                                            .deserialize(input)
-                                    }),
-                                    many0(tuple((
-                                        |input| self.datastore_key_deserializer.deserialize(input),
-                                        |input| self.datastore_val_deserializer.deserialize(input),
-                                    ))),
-                                ),
-                            ),
-                            context(
-                                "Failed updates deserialization",
-                                length_value(
-                                    context("Failed length deserialization", |input| {
-                                        self.stream_batch_updates_length_deserializer
-                                            .deserialize(input)
-                                    }),
-                                    many0(tuple((
-                                        |input| self.datastore_key_deserializer.deserialize(input),
-                                        |input| self.opt_vec_u8_deserializer.deserialize(input),
-                                    ))),
-                                ),
-                            ),
-                            context("Failed slot deserialization", |input| {
-                                self.slot_deserializer.deserialize(input)
-                            }),
-                        )),
-                    ),
-                    context(
-                        "Failed versioning_part deserialization",
-                        tuple((
-                            context(
-                                "Failed new_elements deserialization",
-                                length_value(
-                                    context("Failed length deserialization", |input| {
-                                        self.versioning_part_new_elements_length_deserializer
-                                            .deserialize(input)
-                                    }),
-                                    many0(tuple((
-                                        |input| self.datastore_key_deserializer.deserialize(input),
-                                        |input| self.datastore_val_deserializer.deserialize(input),
-                                    ))),
-                                ),
-                            ),
-                            context(
-                                "Failed updates deserialization",
-                                length_value(
-                                    context("Failed length deserialization", |input| {
-                                        self.stream_batch_updates_length_deserializer
-                                            .deserialize(input)
-                                    }),
-                                    many0(tuple((
-                                        |input| self.datastore_key_deserializer.deserialize(input),
-                                        |input| self.opt_vec_u8_deserializer.deserialize(input),
-                                    ))),
-                                ),
-                            ),
-                            context("Failed slot deserialization", |input| {
-                                self.slot_deserializer.deserialize(input)
-                            }),
-                        )),
-                    ),
+                    context("Failed state_part deserialization", |input| {
+                        self.deserialize_stream_batch(
+                            &self.state_new_elements_length_deserializer,
+                            input,
+                        )
+                    }),
+                    context("Failed versioning_part deserialization", |input| {
+                        self.deserialize_stream_batch(
+                            &self.versioning_part_new_elements_length_deserializer,
+                            input,
+                        )
+                    }),
                     context("Failed consensus_part deserialization", |input| {
                         self.bootstrapable_graph_deserializer.deserialize(input)
                     }),
@@ -577,39 +633,38 @@ impl Deserializer<BootstrapServerMessage> for BootstrapServerMessageDeserializer
                 .map(
                     |(
                         slot,
-                        (state_part_new_elems, state_part_updates, state_part_change_id),
-                        (
-                            versioning_part_new_elems,
-                            versioning_part_updates,
-                            versioning_part_change_id,
-                        ),
+                        state_part,
+                        versioning_part,
+                        consensus_part,
+                        consensus_outdated_ids,
+                        last_start_period,
+                        last_slot_before_downtime,
+                    )| BootstrapServerMessage::BootstrapPart {
+                        slot,
+                        state_part,
+                        versioning_part,
                         consensus_part,
                         consensus_outdated_ids,
                         last_start_period,
                         last_slot_before_downtime,
-                    )| {
-                        let state_part = StreamBatch::<Slot> {
-                            new_elements: state_part_new_elems.into_iter().collect(),
-                            updates_on_previous_elements: state_part_updates.into_iter().collect(),
-                            change_id: state_part_change_id,
-                        };
-                        let versioning_part = StreamBatch::<Slot> {
-                            new_elements: versioning_part_new_elems.into_iter().collect(),
-                            updates_on_previous_elements: versioning_part_updates
-                                .into_iter()
-                                .collect(),
-                            change_id: versioning_part_change_id,
-                        };
-
-                        BootstrapServerMessage::BootstrapPart {
-                            slot,
-                            state_part,
-                            versioning_part,
-                            consensus_part,
-                            consensus_outdated_ids,
-                            last_start_period,
-                            last_slot_before_downtime,
-                        }
+                    },
+                )
+                .parse(input),
+                MessageServerTypeId::StateRangePart => tuple((
+                    context("Failed state_part deserialization", |input| {
+                        self.deserialize_stream_batch(
+                            &self.state_new_elements_length_deserializer,
+                            input,
+                        )
+                    }),
+                    context("Failed range_hash deserialization", |input| {
+                        self.opt_range_hash_deserializer.deserialize(input)
+                    }),
+                ))
+                .map(
+                    |(state_part, range_hash)| BootstrapServerMessage::StateRangePart {
+                        state_part,
+                        range_hash,
                     },
                 )
                 .parse(input),
@@ -627,6 +682,13 @@ impl Deserializer<BootstrapServerMessage> for BootstrapServerMessageDeserializer
                     error: String::from_utf8_lossy(error).into_owned(),
                 })
                 .parse(input),
+                MessageServerTypeId::BootstrapQueued => {
+                    context("Failed position deserialization", |input| {
+                        self.queue_position_deserializer.deserialize(input)
+                    })
+                    .map(|position| BootstrapServerMessage::BootstrapQueued { position })
+                    .parse(input)
+                }
             }
         })
         .parse(buffer)
@@ -652,6 +714,15 @@ pub enum BootstrapClientMessage {
         /// Should be true only for the first part, false later
         send_last_start_period: bool,
     },
+    /// Ask for a part of a range of the final state
+    AskStateRange {
+        /// Range of the final state keys to stream
+        range: StateRange,
+        /// Slot we are attached to for changes
+        last_slot: Option<Slot>,
+        /// Last received state key
+        last_state_step: StreamingStep<Vec<u8>>,
+    },
     /// Bootstrap error
     BootstrapError {
         /// Error message
@@ -661,6 +732,19 @@ pub enum BootstrapClientMessage {
     BootstrapSuccess,
 }
 
+impl BootstrapClientMessage {
+    /// Name of the message type in the network metrics
+    pub fn metrics_name(&self) -> &'static str {
+        match self {
+            BootstrapClientMessage::AskBootstrapPeers => "bootstrap_ask_peers",
+            BootstrapClientMessage::AskBootstrapPart { .. } => "bootstrap_ask_part",
+            BootstrapClientMessage::AskStateRange { .. } => "bootstrap_ask_state_range",
+            BootstrapClientMessage::BootstrapError { .. } => "bootstrap_client_error",
+            BootstrapClientMessage::BootstrapSuccess => "bootstrap_success",
+        }
+    }
+}
+
 #[derive(IntoPrimitive, Debug, Eq, PartialEq, TryFromPrimitive)]
 #[repr(u32)]
 enum MessageClientTypeId {
@@ -668,6 +752,7 @@ enum MessageClientTypeId {
     AskFinalStatePart = 1u32,
     BootstrapError = 2u32,
     BootstrapSuccess = 3u32,
+    AskStateRange = 4u32,
 }
 
 /// Serializer for `BootstrapClientMessage`
@@ -680,6 +765,9 @@ pub struct BootstrapClientMessageSerializer {
         PreHashSetSerializer<BlockId, BlockIdSerializer>,
     >,
     bool_serializer: BoolSerializer,
+    vec_u8_serializer: VecU8Serializer,
+    opt_vec_u8_serializer: OptionSerializer<Vec<u8>, VecU8Serializer>,
+    opt_slot_serializer: OptionSerializer<Slot, SlotSerializer>,
 }
 
 impl BootstrapClientMessageSerializer {
@@ -693,6 +781,9 @@ impl BootstrapClientMessageSerializer {
                 BlockIdSerializer::new(),
             )),
             bool_serializer: BoolSerializer::new(),
+            vec_u8_serializer: VecU8Serializer::new(),
+            opt_vec_u8_serializer: OptionSerializer::new(VecU8Serializer::new()),
+            opt_slot_serializer: OptionSerializer::new(SlotSerializer::new()),
         }
     }
 }
@@ -748,6 +839,19 @@ impl Serializer<BootstrapClientMessage> for BootstrapClientMessageSerializer {
                         .serialize(send_last_start_period, buffer)?;
                 }
             }
+            BootstrapClientMessage::AskStateRange {
+                range,
+                last_slot,
+                last_state_step,
+            } => {
+                self.u32_serializer
+                    .serialize(&u32::from(MessageClientTypeId::AskStateRange), buffer)?;
+                self.vec_u8_serializer.serialize(&range.start, buffer)?;
+                self.opt_vec_u8_serializer.serialize(&range.end, buffer)?;
+                self.opt_slot_serializer.serialize(last_slot, buffer)?;
+                self.state_step_serializer
+                    .serialize(last_state_step, buffer)?;
+            }
             BootstrapClientMessage::BootstrapError { error } => {
                 self.u32_serializer
                     .serialize(&u32::from(MessageClientTypeId::BootstrapError), buffer)?;
@@ -779,6 +883,9 @@ pub struct BootstrapClientMessageDeserializer {
         PreHashSetDeserializer<BlockId, BlockIdDeserializer>,
     >,
     bool_deserializer: BoolDeserializer,
+    key_deserializer: VecU8Deserializer,
+    opt_key_deserializer: OptionDeserializer<Vec<u8>, VecU8Deserializer>,
+    opt_slot_deserializer: OptionDeserializer<Slot, SlotDeserializer>,
 }
 
 impl BootstrapClientMessageDeserializer {
@@ -807,6 +914,18 @@ impl BootstrapClientMessageDeserializer {
                 ),
             ),
             bool_deserializer: BoolDeserializer::new(),
+            key_deserializer: VecU8Deserializer::new(
+                Included(0),
+                Included(max_datastore_key_length.into()),
+            ),
+            opt_key_deserializer: OptionDeserializer::new(VecU8Deserializer::new(
+                Included(0),
+                Included(max_datastore_key_length.into()),
+            )),
+            opt_slot_deserializer: OptionDeserializer::new(SlotDeserializer::new(
+                (Included(0), Included(u64::MAX)),
+                (Included(0), Excluded(thread_count)),
+            )),
         }
     }
 }
@@ -903,6 +1022,28 @@ impl Deserializer<BootstrapClientMessage> for BootstrapClientMessageDeserializer
                         .parse(input)
                     }
                 }
+                MessageClientTypeId::AskStateRange => tuple((
+                    context("Failed range start deserialization", |input| {
+                        self.key_deserializer.deserialize(input)
+                    }),
+                    context("Failed range end deserialization", |input| {
+                        self.opt_key_deserializer.deserialize(input)
+                    }),
+                    context("Failed last_slot deserialization", |input| {
+                        self.opt_slot_deserializer.deserialize(input)
+                    }),
+                    context("Failed last_state_step deserialization", |input| {
+                        self.state_step_deserializer.deserialize(input)
+                    }),
+                ))
+                .map(|(start, end, last_slot, last_state_step)| {
+                    BootstrapClientMessage::AskStateRange {
+                        range: StateRange { start, end },
+                        last_slot,
+                        last_state_step,
+                    }
+                })
+                .parse(input),
                 MessageClientTypeId::BootstrapError => context(
                     "Failed BootstrapError deserialization",
                     length_data(context("Failed length deserialization", |input| {
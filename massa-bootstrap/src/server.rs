@@ -20,15 +20,18 @@
 //!
 //! 1. Checks if the stopper has been invoked.
 //! 2. Checks if the client is permited under the white/black list rules
-//! 3. Checks if there are not too many active sessions already
+//! 3. Registers the session, refusing it if the client has too many sessions already,
+//!    or if all the slots are taken and the queue is full
 //! 4. Checks if the client has attempted too recently
 //! 5. All checks have passed: spawn a thread on which to run the bootstrap session
 //!    This thread creates a new tokio runtime, and runs it with `block_on`
+//!
+//! A queued session is told its position in the queue until a slot is free, see [`crate::sessions`]
 
 use crossbeam::channel::tick;
 use humantime::format_duration;
 use massa_consensus_exports::{bootstrapable_graph::BootstrapableGraph, ConsensusController};
-use massa_db_exports::CHANGE_ID_DESER_ERROR;
+use massa_db_exports::{MassaDBError, StateRange, CHANGE_ID_DESER_ERROR};
 use massa_final_state::FinalStateController;
 use massa_logging::massa_trace;
 use massa_metrics::MassaMetrics;
@@ -60,6 +63,7 @@ use crate::{
     error::BootstrapError,
     listener::{BootstrapListenerStopHandle, PollEvent},
     messages::{BootstrapClientMessage, BootstrapServerMessage},
+    sessions::{BootstrapRateLimits, BootstrapSessions, Session},
     white_black_list::SharedWhiteBlackList,
     BootstrapConfig,
 };
@@ -81,6 +85,8 @@ pub struct BootstrapManager {
     update_stopper_tx: crossbeam::channel::Sender<()>,
     /// shared white/black list
     pub white_black_list: SharedWhiteBlackList<'static>,
+    /// rate limits of the server, which can be changed while it runs
+    pub rate_limits: Arc<BootstrapRateLimits>,
 }
 
 impl BootstrapManager {
@@ -92,6 +98,7 @@ impl BootstrapManager {
         update_stopper_tx: crossbeam::channel::Sender<()>,
         listener_stopper: BootstrapListenerStopHandle,
         white_black_list: SharedWhiteBlackList<'static>,
+        rate_limits: Arc<BootstrapRateLimits>,
     ) -> Self {
         Self {
             update_handle,
@@ -99,6 +106,7 @@ impl BootstrapManager {
             update_stopper_tx,
             listener_stopper,
             white_black_list,
+            rate_limits,
         }
     }
 
@@ -152,6 +160,17 @@ pub fn start_bootstrap_server(
             "Fail to convert u32 to usize".to_string(),
         ));
     };
+    let rate_limits = Arc::new(BootstrapRateLimits::new(
+        config.server_rate_limit,
+        config.rate_limit,
+    ));
+    let sessions = Arc::new(BootstrapSessions::new(
+        max_bootstraps,
+        config.max_simultaneous_bootstraps_per_ip,
+        config.max_queued_bootstraps,
+        rate_limits.clone(),
+        massa_metrics.clone(),
+    ));
 
     let white_black_list = SharedWhiteBlackList::new(
         config.bootstrap_whitelist_path.clone(),
@@ -191,7 +210,7 @@ pub fn start_bootstrap_server(
                 bootstrap_config: config,
                 massa_metrics,
             }
-            .event_loop(max_bootstraps)
+            .event_loop(sessions)
         })
         .expect("in `start_bootstrap_server`, OS failed to spawn main-loop thread");
     // Give the runtime to the bootstrap manager, otherwise it will be dropped, forcibly aborting the spawned tasks.
@@ -202,6 +221,7 @@ pub fn start_bootstrap_server(
         update_stopper_tx,
         listener_stopper,
         white_black_list,
+        rate_limits,
     ))
 }
 
@@ -239,12 +259,8 @@ impl BootstrapServer<'_> {
         }
     }
 
-    fn event_loop(mut self, max_bootstraps: usize) -> Result<(), BootstrapError> {
-        // Use the strong-count of this variable to track the session count
-        let bootstrap_sessions_counter: Arc<()> = Arc::new(());
+    fn event_loop(mut self, sessions: Arc<BootstrapSessions>) -> Result<(), BootstrapError> {
         let per_ip_min_interval = self.bootstrap_config.per_ip_min_interval.to_duration();
-        // TODO: Work out how to integration-test this
-        let limit = self.bootstrap_config.rate_limit;
         loop {
             // block until we have a connection to work with, or break out of main-loop
             let connections = match self.ev_poller.poll() {
@@ -265,105 +281,103 @@ impl BootstrapServer<'_> {
                     dplx,
                     self.keypair.clone(),
                     (&self.bootstrap_config).into(),
-                    Some(limit),
-                );
-
-                // the `- 1` is to account for the top-level Arc that is created at the top
-                // of this method. subsequent counts correspond to each `clone` that is passed
-                // into a thread
-                // TODO: If we don't find a way to handle the counting automagically, make
-                //       a dedicated wrapper-type with doc-comments, manual drop impl that
-                //       integrates logging, etc...
-                if Arc::strong_count(&bootstrap_sessions_counter) - 1 < max_bootstraps {
-                    let bootstrap_count_token = bootstrap_sessions_counter.clone();
-                    // check whether incoming peer IP is allowed.
-                    if let Err(error_msg) = self.white_black_list.is_ip_allowed(&remote_addr) {
-                        server_binding.close_and_send_error(
-                            error_msg.to_string(),
-                            remote_addr,
-                            move || {},
-                        );
-                        self.massa_metrics.inc_bootstrap_peers_failed();
-                        continue;
-                    };
-                    massa_trace!("bootstrap.lib.run.select.accept", {
-                        "remote_addr": remote_addr
-                    });
-                    let now = Instant::now();
+                    Some(sessions.rate_limits().connection_rate_limit()),
+                )
+                .with_massa_metrics(self.massa_metrics.clone());
 
-                    // clear IP history if necessary
-                    if self.ip_hist_map.len() > self.bootstrap_config.ip_list_max_size {
-                        self.ip_hist_map
-                            .retain(|_k, v| now.duration_since(*v) <= per_ip_min_interval);
-                        if self.ip_hist_map.len() > self.bootstrap_config.ip_list_max_size {
-                            // too many IPs are spamming us: clear cache
-                            warn!("high bootstrap load: at least {} different IPs attempted bootstrap in the last {}", self.ip_hist_map.len(),format_duration(self.bootstrap_config.per_ip_min_interval.to_duration()).to_string());
-                            self.ip_hist_map.clear();
-                        }
-                    }
-
-                    // check IP's bootstrap attempt history
-                    if let Err(msg) = BootstrapServer::greedy_client_check(
-                        &mut self.ip_hist_map,
+                // check whether incoming peer IP is allowed.
+                if let Err(error_msg) = self.white_black_list.is_ip_allowed(&remote_addr) {
+                    server_binding.close_and_send_error(
+                        error_msg.to_string(),
                         remote_addr,
-                        now,
-                        per_ip_min_interval,
-                    ) {
-                        // Client has been too greedy: send out the bad-news :(
-                        let msg = format!(
-                            "Your last bootstrap on this server was {} ago and you have to wait {} before retrying.",
-                            format_duration(msg),
-                            format_duration(per_ip_min_interval.saturating_sub(msg))
-                        );
-                        let tracer = move || {
-                            massa_trace!("bootstrap.lib.run.select.accept.refuse_limit", {
-                                "remote_addr": remote_addr
-                            })
-                        };
-                        server_binding.close_and_send_error(msg, remote_addr, tracer);
+                        move || {},
+                    );
+                    self.massa_metrics.inc_bootstrap_peers_failed();
+                    continue;
+                };
+
+                // claim a slot in the bootstrap sessions, or a place in the queue
+                let session = match sessions.register(remote_addr.ip()) {
+                    Ok(session) => session,
+                    Err(msg) => {
+                        server_binding.close_and_send_error(msg, remote_addr, move || {
+                            debug!("did not bootstrap {}: session refused", remote_addr)
+                        });
                         self.massa_metrics.inc_bootstrap_peers_failed();
                         continue;
-                    };
-
-                    // Clients Option<last-attempt> is good, and has been updated
-                    massa_trace!("bootstrap.lib.run.select.accept.cache_available", {});
-
-                    // launch bootstrap
-                    let version = self.version;
-                    let data_execution = self.final_state.clone();
-                    let consensus_command_sender = self.consensus_controller.clone();
-                    let protocol_controller = self.protocol_controller.clone();
-                    let config = self.bootstrap_config.clone();
-
-                    let massa_metrics = self.massa_metrics.clone();
-
-                    let _ = thread::Builder::new()
-                        .name(format!("bootstrap thread, peer: {}", remote_addr))
-                        .spawn(move || {
-                            run_bootstrap_session(
-                                server_binding,
-                                bootstrap_count_token,
-                                config,
-                                remote_addr,
-                                data_execution,
-                                version,
-                                consensus_command_sender,
-                                protocol_controller,
-                                massa_metrics,
-                            )
-                        });
+                    }
+                };
+                massa_trace!("bootstrap.lib.run.select.accept", {
+                    "remote_addr": remote_addr
+                });
+                let now = Instant::now();
+
+                // clear IP history if necessary
+                if self.ip_hist_map.len() > self.bootstrap_config.ip_list_max_size {
+                    self.ip_hist_map
+                        .retain(|_k, v| now.duration_since(*v) <= per_ip_min_interval);
+                    if self.ip_hist_map.len() > self.bootstrap_config.ip_list_max_size {
+                        // too many IPs are spamming us: clear cache
+                        warn!("high bootstrap load: at least {} different IPs attempted bootstrap in the last {}", self.ip_hist_map.len(),format_duration(self.bootstrap_config.per_ip_min_interval.to_duration()).to_string());
+                        self.ip_hist_map.clear();
+                    }
+                }
 
-                    massa_trace!("bootstrap.session.started", {
-                        "active_count": Arc::strong_count(&bootstrap_sessions_counter) - 1
-                    });
-                } else {
-                    server_binding.close_and_send_error(
-                        "Bootstrap failed because the bootstrap server currently has no slots available.".to_string(),
-                        remote_addr,
-                        move || debug!("did not bootstrap {}: no available slots", remote_addr),
+                // check IP's bootstrap attempt history
+                if let Err(msg) = BootstrapServer::greedy_client_check(
+                    &mut self.ip_hist_map,
+                    remote_addr,
+                    now,
+                    per_ip_min_interval,
+                ) {
+                    // Client has been too greedy: send out the bad-news :(
+                    let msg = format!(
+                        "Your last bootstrap on this server was {} ago and you have to wait {} before retrying.",
+                        format_duration(msg),
+                        format_duration(per_ip_min_interval.saturating_sub(msg))
                     );
+                    let tracer = move || {
+                        massa_trace!("bootstrap.lib.run.select.accept.refuse_limit", {
+                            "remote_addr": remote_addr
+                        })
+                    };
+                    server_binding.close_and_send_error(msg, remote_addr, tracer);
                     self.massa_metrics.inc_bootstrap_peers_failed();
-                }
+                    continue;
+                };
+
+                // Clients Option<last-attempt> is good, and has been updated
+                massa_trace!("bootstrap.lib.run.select.accept.cache_available", {});
+
+                // launch bootstrap
+                let server_binding = server_binding.with_bandwidth_share(session.bandwidth_share());
+                let version = self.version;
+                let data_execution = self.final_state.clone();
+                let consensus_command_sender = self.consensus_controller.clone();
+                let protocol_controller = self.protocol_controller.clone();
+                let config = self.bootstrap_config.clone();
+
+                let massa_metrics = self.massa_metrics.clone();
+
+                let _ = thread::Builder::new()
+                    .name(format!("bootstrap thread, peer: {}", remote_addr))
+                    .spawn(move || {
+                        run_bootstrap_session(
+                            server_binding,
+                            session,
+                            config,
+                            remote_addr,
+                            data_execution,
+                            version,
+                            consensus_command_sender,
+                            protocol_controller,
+                            massa_metrics,
+                        )
+                    });
+
+                massa_trace!("bootstrap.session.started", {
+                    "active_count": sessions.active_count()
+                });
             }
         }
     }
@@ -402,12 +416,12 @@ impl BootstrapServer<'_> {
 /// a multi-thread-aware tokio runtime (the bs-main-loop runtime, to be exact). When this
 /// function blocks in the `block_on`, it should thread-block, and switch to another session
 ///
-/// The arc_counter variable is used as a proxy to keep track the number of active bootstrap
-/// sessions.
+/// A queued session first waits for a free slot, telling the client its position in the queue.
+/// The session is dropped once served, freeing its slot.
 #[allow(clippy::too_many_arguments)]
 fn run_bootstrap_session(
     mut server: BootstrapServerBinder,
-    arc_counter: Arc<()>,
+    mut session: Session,
     config: BootstrapConfig,
     remote_addr: SocketAddr,
     data_execution: Arc<RwLock<dyn FinalStateController>>,
@@ -416,6 +430,19 @@ fn run_bootstrap_session(
     protocol_controller: Box<dyn ProtocolController>,
     massa_metrics: MassaMetrics,
 ) {
+    let queue_refresh_interval = config.read_timeout.to_duration() / 2;
+    let waited = session.wait_turn(queue_refresh_interval, |position| {
+        server.send_msg(
+            config.write_timeout.to_duration(),
+            BootstrapServerMessage::BootstrapQueued { position },
+        )
+    });
+    if let Err(err) = waited {
+        debug!("bootstrap queue error for peer {}: {}", remote_addr, err);
+        massa_metrics.inc_bootstrap_peers_failed();
+        return;
+    }
+
     debug!("running bootstrap for peer {}", remote_addr);
     let deadline = Instant::now() + config.bootstrap_timeout.to_duration();
     // TODO: reinstate prevention of bootstrap slot camping. Deadline cancellation is one option
@@ -430,11 +457,8 @@ fn run_bootstrap_session(
     );
 
     // This drop allows the server to accept new connections before having to complete the error notifications
-    // account for this session being finished, as well as the root-instance
-    massa_trace!("bootstrap.session.finished", {
-        "sessions_remaining": Arc::strong_count(&arc_counter) - 2
-    });
-    drop(arc_counter);
+    drop(session);
+    massa_trace!("bootstrap.session.finished", {});
     match res {
         Err(BootstrapError::TimedOut(_)) => {
             debug!("bootstrap timeout for peer {}", remote_addr);
@@ -487,12 +511,16 @@ pub fn stream_bootstrap_information(
         let last_start_period;
         let last_slot_before_downtime;
 
-        let slot_too_old = false;
-
         // Scope of the final state read
         {
             let final_state_read = final_state.read();
 
+            let db_slot = final_state_read
+                .get_database()
+                .read()
+                .get_change_id()
+                .expect(CHANGE_ID_DESER_ERROR);
+
             last_start_period = if send_last_start_period {
                 Some(final_state_read.get_last_start_period())
             } else {
@@ -504,13 +532,25 @@ pub fn stream_bootstrap_information(
                 None
             };
 
-            state_part = final_state_read
+            let state_part_result = final_state_read
                 .get_database()
                 .read()
-                .get_batch_to_stream(&last_state_step, last_slot)
-                .map_err(|e| {
-                    BootstrapError::GeneralError(format!("Error get_batch_to_stream: {}", e))
-                })?;
+                .get_batch_to_stream(&last_state_step, last_slot);
+            state_part = match state_part_result {
+                Ok(state_part) => state_part,
+                // The changes since the slot of the client have left our history, typically when
+                // it resumes an interrupted bootstrap: it has to start over
+                Err(MassaDBError::CacheMissError(_)) if last_slot < Some(db_slot) => {
+                    drop(final_state_read);
+                    return server.send_msg(write_timeout, BootstrapServerMessage::SlotTooOld);
+                }
+                Err(e) => {
+                    return Err(BootstrapError::GeneralError(format!(
+                        "Error get_batch_to_stream: {}",
+                        e
+                    )))
+                }
+            };
 
             let new_state_step = match (&last_state_step, state_part.is_empty()) {
                 // We already finished streaming the state
@@ -591,12 +631,6 @@ pub fn stream_bootstrap_information(
                 }
             };
 
-            let db_slot = final_state_read
-                .get_database()
-                .read()
-                .get_change_id()
-                .expect(CHANGE_ID_DESER_ERROR);
-
             if let Some(slot) = last_slot {
                 if slot > db_slot {
                     return Err(BootstrapError::GeneralError(
@@ -613,10 +647,6 @@ pub fn stream_bootstrap_information(
             send_last_start_period = false;
         }
 
-        if slot_too_old {
-            return server.send_msg(write_timeout, BootstrapServerMessage::SlotTooOld);
-        }
-
         // Setup final state global cursor
         let final_state_global_step =
             if last_state_step.finished() && last_versioning_step.finished() {
@@ -681,6 +711,75 @@ pub fn stream_bootstrap_information(
     Ok(())
 }
 
+/// Streams a range of the final state, along with its changes, until the client holds the range as
+/// it is at the slot of the last part. The last part carries the hash of the range at this slot,
+/// so that the client can verify it.
+pub fn stream_state_range(
+    server: &mut BootstrapServerBinder,
+    final_state: Arc<RwLock<dyn FinalStateController>>,
+    range: StateRange,
+    mut last_slot: Option<Slot>,
+    mut last_state_step: StreamingStep<Vec<u8>>,
+    bs_deadline: &Instant,
+    write_timeout: Duration,
+) -> Result<(), BootstrapError> {
+    loop {
+        let state_part;
+        let range_hash;
+
+        // Scope of the final state read
+        {
+            let final_state_read = final_state.read();
+            let db = final_state_read.get_database().read();
+
+            state_part = db
+                .get_range_batch_to_stream(&range, &last_state_step, last_slot)
+                .map_err(|e| {
+                    BootstrapError::GeneralError(format!("Error get_range_batch_to_stream: {}", e))
+                })?;
+
+            let db_slot = db.get_change_id().expect(CHANGE_ID_DESER_ERROR);
+            if let Some(slot) = last_slot {
+                if slot > db_slot {
+                    return Err(BootstrapError::GeneralError(
+                        "Bootstrap cursor set to future slot".to_string(),
+                    ));
+                }
+            }
+
+            // The range is complete once there are no new elements left: its hash is then
+            // computed under the same lock, so that it matches the streamed state
+            range_hash = match state_part.new_elements.last_key_value() {
+                Some((new_last_key, _)) => {
+                    last_state_step = StreamingStep::Ongoing(new_last_key.clone());
+                    None
+                }
+                None => {
+                    last_state_step = StreamingStep::Finished(None);
+                    Some(db.get_range_xof_hash(&range))
+                }
+            };
+            last_slot = Some(db_slot);
+        }
+
+        let Some(write_timeout) = step_timeout_duration(bs_deadline, &write_timeout) else {
+            return Err(BootstrapError::Interrupted(
+                "insufficient time left to provide next state range part".to_string(),
+            ));
+        };
+        server.send_msg(
+            write_timeout,
+            BootstrapServerMessage::StateRangePart {
+                state_part,
+                range_hash,
+            },
+        )?;
+        if last_state_step.finished() {
+            return Ok(());
+        }
+    }
+}
+
 // derives the duration allowed for a step in the bootstrap process.
 // Returns None if the deadline for the entire bs-process has been reached
 fn step_timeout_duration(bs_deadline: &Instant, step_timeout: &Duration) -> Option<Duration> {
@@ -743,6 +842,8 @@ pub(crate) fn manage_bootstrap(
         BootstrapServerMessage::BootstrapTime {
             server_time: MassaTime::now(),
             version,
+            // the changes since any slot of the history of the final state can be sent
+            differential_bootstrap: true,
         },
     )?;
 
@@ -795,6 +896,21 @@ pub(crate) fn manage_bootstrap(
                         bootstrap_config.write_timeout.to_duration(),
                     )?;
                 }
+                BootstrapClientMessage::AskStateRange {
+                    range,
+                    last_slot,
+                    last_state_step,
+                } => {
+                    stream_state_range(
+                        server,
+                        final_state.clone(),
+                        range,
+                        last_slot,
+                        last_state_step,
+                        &deadline,
+                        bootstrap_config.write_timeout.to_duration(),
+                    )?;
+                }
                 BootstrapClientMessage::BootstrapSuccess => break Ok(()),
                 BootstrapClientMessage::BootstrapError { error } => {
                     break Err(BootstrapError::ReceivedError(error));
@@ -0,0 +1,281 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Sessions of the bootstrap server.
+//!
+//! At most `max_simultaneous_bootstraps` sessions are active at once, and at most
+//! `max_simultaneous_bootstraps_per_ip` sessions, active or queued, come from the same IP address.
+//! The sessions arriving while all the slots are taken wait for a free one in a queue of at most
+//! `max_queued_bootstraps` sessions, in their order of arrival.
+//! The upload rate of the server, if limited, is shared equally among the active sessions.
+//! The rate limits can be changed while the server runs.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    net::IpAddr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Condvar, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use massa_metrics::MassaMetrics;
+
+const LOCK_ERROR: &str = "bootstrap sessions lock poisoned";
+
+/// Rate limits of the bootstrap server, which can be changed while it runs
+#[derive(Debug, Default)]
+pub struct BootstrapRateLimits {
+    /// upload rate of the server in bytes per second, shared among the active sessions,
+    /// 0 if not limited
+    server_rate_limit: AtomicU64,
+    /// read-write rate of each connection in bytes per second
+    connection_rate_limit: AtomicU64,
+}
+
+impl BootstrapRateLimits {
+    pub(crate) fn new(server_rate_limit: u64, connection_rate_limit: u64) -> Self {
+        BootstrapRateLimits {
+            server_rate_limit: AtomicU64::new(server_rate_limit),
+            connection_rate_limit: AtomicU64::new(connection_rate_limit),
+        }
+    }
+
+    /// Change the rate limits. The upload rate of the server applies at once to the active
+    /// sessions, the rate of each connection to the new connections.
+    pub fn set(&self, server_rate_limit: u64, connection_rate_limit: u64) {
+        self.server_rate_limit
+            .store(server_rate_limit, Ordering::Relaxed);
+        self.connection_rate_limit
+            .store(connection_rate_limit, Ordering::Relaxed);
+    }
+
+    /// Upload rate of the server in bytes per second, 0 if not limited
+    pub fn server_rate_limit(&self) -> u64 {
+        self.server_rate_limit.load(Ordering::Relaxed)
+    }
+
+    /// Read-write rate of each connection in bytes per second
+    pub fn connection_rate_limit(&self) -> u64 {
+        self.connection_rate_limit.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Default)]
+struct SessionsState {
+    /// number of active sessions
+    active: usize,
+    /// number of active or queued sessions, by IP address
+    per_ip: HashMap<IpAddr, usize>,
+    /// identifiers of the queued sessions, in their order of arrival
+    queue: VecDeque<u64>,
+    /// identifier of the next session
+    next_id: u64,
+    /// incremented each time a session ends, which may free a slot or move the queue
+    ended: u64,
+}
+
+/// Registry of the active and queued sessions of the bootstrap server
+pub(crate) struct BootstrapSessions {
+    max_sessions: usize,
+    max_sessions_per_ip: usize,
+    max_queued: usize,
+    rate_limits: Arc<BootstrapRateLimits>,
+    state: Mutex<SessionsState>,
+    /// notified when a session ends
+    session_ended: Condvar,
+    massa_metrics: MassaMetrics,
+}
+
+impl BootstrapSessions {
+    pub(crate) fn new(
+        max_sessions: usize,
+        max_sessions_per_ip: usize,
+        max_queued: usize,
+        rate_limits: Arc<BootstrapRateLimits>,
+        massa_metrics: MassaMetrics,
+    ) -> Self {
+        BootstrapSessions {
+            max_sessions,
+            max_sessions_per_ip,
+            max_queued,
+            rate_limits,
+            state: Default::default(),
+            session_ended: Condvar::new(),
+            massa_metrics,
+        }
+    }
+
+    /// Register a session of a client at `ip`. The session is active at once if a slot is free
+    /// and no other session waits for one, and queued otherwise.
+    ///
+    /// # Error
+    /// The reason why the session is refused, to send to the client
+    pub(crate) fn register(self: &Arc<Self>, ip: IpAddr) -> Result<Session, String> {
+        let mut state = self.state.lock().expect(LOCK_ERROR);
+        let ip_sessions = state.per_ip.get(&ip).copied().unwrap_or_default();
+        if ip_sessions >= self.max_sessions_per_ip {
+            return Err(format!(
+                "Bootstrap failed because the bootstrap server already has {} sessions from your IP address.",
+                ip_sessions
+            ));
+        }
+        let active = state.active < self.max_sessions && state.queue.is_empty();
+        if !active && state.queue.len() >= self.max_queued {
+            return Err(
+                "Bootstrap failed because the bootstrap server currently has no slots available."
+                    .to_string(),
+            );
+        }
+
+        let id = state.next_id;
+        state.next_id += 1;
+        if active {
+            state.active += 1;
+        } else {
+            state.queue.push_back(id);
+        }
+        *state.per_ip.entry(ip).or_default() += 1;
+        self.update_metrics(&state);
+        Ok(Session {
+            sessions: self.clone(),
+            id,
+            ip,
+            active,
+        })
+    }
+
+    /// Rate limits of the server
+    pub(crate) fn rate_limits(&self) -> &BootstrapRateLimits {
+        &self.rate_limits
+    }
+
+    /// Number of active sessions
+    pub(crate) fn active_count(&self) -> usize {
+        self.state.lock().expect(LOCK_ERROR).active
+    }
+
+    /// Share of the upload rate of the server of each active session, in bytes per second,
+    /// if the upload rate of the server is limited
+    pub(crate) fn session_rate_limit(&self) -> Option<u64> {
+        let rate_limit = self.rate_limits.server_rate_limit();
+        if rate_limit == 0 {
+            return None;
+        }
+        let active = self.active_count().max(1) as u64;
+        Some((rate_limit / active).max(1))
+    }
+
+    fn update_metrics(&self, state: &SessionsState) {
+        self.massa_metrics
+            .set_bootstrap_server_sessions(state.active, state.queue.len());
+    }
+}
+
+/// Session registered in [`BootstrapSessions`], which ends when dropped
+pub(crate) struct Session {
+    sessions: Arc<BootstrapSessions>,
+    id: u64,
+    ip: IpAddr,
+    active: bool,
+}
+
+impl Session {
+    /// Wait for the session to become active if it is queued.
+    ///
+    /// `on_position` is called with the position of the session in the queue each time a session
+    /// ends, and at least every `refresh_interval`, then with 0 once the session is active.
+    /// The session stays queued if `on_position` fails.
+    pub(crate) fn wait_turn<E>(
+        &mut self,
+        refresh_interval: Duration,
+        mut on_position: impl FnMut(u32) -> Result<(), E>,
+    ) -> Result<(), E> {
+        if self.active {
+            return Ok(());
+        }
+        let sessions = self.sessions.clone();
+        loop {
+            let mut state = sessions.state.lock().expect(LOCK_ERROR);
+            let index = state
+                .queue
+                .iter()
+                .position(|id| *id == self.id)
+                .expect("queued bootstrap session not found");
+            if index == 0 && state.active < sessions.max_sessions {
+                state.queue.pop_front();
+                state.active += 1;
+                self.active = true;
+                sessions.update_metrics(&state);
+                drop(state);
+                return on_position(0);
+            }
+            let ended = state.ended;
+            drop(state);
+
+            on_position((index + 1) as u32)?;
+
+            let state = sessions.state.lock().expect(LOCK_ERROR);
+            // a session may have ended while the position was sent
+            if state.ended == ended {
+                let _ = sessions
+                    .session_ended
+                    .wait_timeout(state, refresh_interval)
+                    .expect(LOCK_ERROR);
+            }
+        }
+    }
+
+    /// Bandwidth share of the session, to throttle the messages sent to the client
+    pub(crate) fn bandwidth_share(&self) -> BandwidthShare {
+        BandwidthShare {
+            sessions: self.sessions.clone(),
+            next_send: Instant::now(),
+        }
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        let mut state = self.sessions.state.lock().expect(LOCK_ERROR);
+        if self.active {
+            state.active -= 1;
+        } else {
+            state.queue.retain(|id| *id != self.id);
+        }
+        if let Some(ip_sessions) = state.per_ip.get_mut(&self.ip) {
+            *ip_sessions -= 1;
+            if *ip_sessions == 0 {
+                state.per_ip.remove(&self.ip);
+            }
+        }
+        state.ended += 1;
+        self.sessions.update_metrics(&state);
+        drop(state);
+        self.sessions.session_ended.notify_all();
+    }
+}
+
+/// Share of the upload rate of the server of a session
+pub(crate) struct BandwidthShare {
+    sessions: Arc<BootstrapSessions>,
+    /// earliest time of the next send
+    next_send: Instant,
+}
+
+impl BandwidthShare {
+    /// Wait until the session can send again without exceeding its share of the upload rate,
+    /// then account for the `bytes` it is about to send
+    pub(crate) fn consume(&mut self, bytes: usize) {
+        let Some(rate_limit) = self.sessions.session_rate_limit() else {
+            return;
+        };
+        let now = Instant::now();
+        if self.next_send > now {
+            thread::sleep(self.next_send - now);
+        }
+        self.next_send = std::cmp::max(self.next_send, now)
+            + Duration::from_secs_f64(bytes as f64 / rate_limit as f64);
+    }
+}
@@ -57,12 +57,23 @@ pub struct BootstrapConfig {
     pub keep_ledger: bool,
     /// Max simultaneous bootstraps
     pub max_simultaneous_bootstraps: u32,
+    /// Max simultaneous bootstraps, active or queued, from a given IP
+    pub max_simultaneous_bootstraps_per_ip: usize,
+    /// Max number of bootstraps waiting for a free slot
+    pub max_queued_bootstraps: usize,
+    /// Upload limit of the server in bytes per seconds, shared among its sessions (0 to disable)
+    pub server_rate_limit: u64,
     /// Minimum interval between two bootstrap attempts from a given IP
     pub per_ip_min_interval: MassaTime,
     /// Max size of the IP list
     pub ip_list_max_size: usize,
     /// Read-Write limitation for a connection in bytes per seconds
     pub rate_limit: u64,
+    /// Number of servers from which the final state is downloaded in parallel (1 to disable)
+    pub parallel_bootstrap_servers: usize,
+    /// Bootstrap only the changes of the final state since its slot, the node having been offline
+    /// for a short time
+    pub differential_bootstrap: bool,
     /// thread count
     pub thread_count: u8,
     /// period per cycle
@@ -46,9 +46,14 @@ impl Default for BootstrapConfig {
             max_clock_delta: MassaTime::from_millis(1000),
             cache_duration: MassaTime::from_millis(10000),
             max_simultaneous_bootstraps: 2,
+            max_simultaneous_bootstraps_per_ip: 2,
+            max_queued_bootstraps: 0,
+            server_rate_limit: 0,
             ip_list_max_size: 10,
             per_ip_min_interval: MassaTime::from_millis(10000),
             rate_limit: std::u64::MAX,
+            parallel_bootstrap_servers: 1,
+            differential_bootstrap: false,
             max_datastore_key_length: MAX_DATASTORE_KEY_LENGTH,
             randomness_size_bytes: BOOTSTRAP_RANDOMNESS_SIZE_BYTES,
             thread_count: THREAD_COUNT,
@@ -3,7 +3,9 @@
 mod binders;
 mod config;
 mod messages;
+mod progress;
 mod scenarios;
+mod sessions;
 pub(crate) mod tools;
 mod universe_client;
 mod universe_server;
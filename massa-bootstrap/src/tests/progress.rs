@@ -0,0 +1,61 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use std::time::Duration;
+
+use massa_db_exports::{ASYNC_POOL_PREFIX, LEDGER_PREFIX, MIP_STORE_PREFIX};
+use massa_models::streaming_step::StreamingStep;
+
+use crate::client::{estimate_state_eta, estimate_state_progress, state_ranges};
+
+fn ledger_key(address: &[u8]) -> StreamingStep<Vec<u8>> {
+    let mut key = LEDGER_PREFIX.as_bytes().to_vec();
+    key.extend_from_slice(address);
+    StreamingStep::Ongoing(key)
+}
+
+#[test]
+fn test_state_progress() {
+    assert_eq!(estimate_state_progress(&StreamingStep::Started), 0.0);
+    assert_eq!(estimate_state_progress(&StreamingStep::Finished(None)), 1.0);
+    // the state before the ledger is negligible, and so is the one after it
+    let async_pool_key = StreamingStep::Ongoing(ASYNC_POOL_PREFIX.as_bytes().to_vec());
+    assert_eq!(estimate_state_progress(&async_pool_key), 0.0);
+    let versioning_key = StreamingStep::Ongoing(MIP_STORE_PREFIX.as_bytes().to_vec());
+    assert_eq!(estimate_state_progress(&versioning_key), 1.0);
+
+    // user addresses: key version, address type and address version, then the hash
+    assert_eq!(estimate_state_progress(&ledger_key(&[0, 0])), 0.0);
+    assert_eq!(
+        estimate_state_progress(&ledger_key(&[0, 0, 0, 0x80, 0x00, 0xff, 0xff])),
+        0.5
+    );
+    assert_eq!(estimate_state_progress(&ledger_key(&[0, 0, 0, 0xc0])), 0.75);
+    // smart contract addresses
+    assert_eq!(estimate_state_progress(&ledger_key(&[0, 1, 0, 0x10])), 1.0);
+
+    // the ledger ranges downloaded in parallel are of the same size
+    let ranges = state_ranges(5);
+    for (index, range) in ranges.iter().enumerate().skip(2) {
+        let progress = estimate_state_progress(&StreamingStep::Ongoing(range.start.clone()));
+        assert_eq!(progress, (index - 1) as f64 / 4.0);
+    }
+}
+
+#[test]
+fn test_state_eta() {
+    // no progress
+    assert_eq!(estimate_state_eta(Duration::from_secs(10), 0.2, 0.2), None);
+    // as long to download the rest as what was downloaded during the session
+    assert_eq!(
+        estimate_state_eta(Duration::from_secs(10), 0.25, 0.625),
+        Some(Duration::from_secs(10))
+    );
+    assert_eq!(
+        estimate_state_eta(Duration::from_secs(10), 0.0, 0.5),
+        Some(Duration::from_secs(10))
+    );
+    assert_eq!(
+        estimate_state_eta(Duration::from_secs(10), 0.0, 1.0),
+        Some(Duration::ZERO)
+    );
+}
@@ -2,11 +2,14 @@
 
 use super::universe_client::{BootstrapClientForeignControllers, BootstrapClientTestUniverse};
 use super::universe_server::BootstrapServerTestUniverseBuilder;
+use crate::BootstrapClientMessage;
 use crate::BootstrapConfig;
 use crate::BootstrapError;
 use massa_models::amount::Amount;
 use massa_models::bytecode::Bytecode;
 use massa_models::datastore::Datastore;
+use massa_models::slot::Slot;
+use massa_models::streaming_step::StreamingStep;
 use massa_models::{address::Address, node::NodeId};
 use massa_signature::KeyPair;
 use massa_test_framework::TestUniverse;
@@ -78,3 +81,150 @@ fn test_bootstrap_accept_err() {
         .build();
     drop(server_universe);
 }
+
+#[test]
+#[serial]
+fn test_parallel_bootstrap_rejects_bad_range() {
+    let (port_1, port_2) = (8072, 8073);
+    let server_keypair_1 = KeyPair::generate(0).unwrap();
+    let server_keypair_2 = KeyPair::generate(0).unwrap();
+    let address = Address::from_public_key(&server_keypair_1.get_public_key());
+    let server_universe_1 = BootstrapServerTestUniverseBuilder::new()
+        .set_port(port_1)
+        .set_keypair(&server_keypair_1)
+        .set_address_balance(&address, Amount::from_mantissa_scale(100, 0).unwrap())
+        .build();
+    // the second server sends a ledger in which the address has another balance
+    let server_universe_2 = BootstrapServerTestUniverseBuilder::new()
+        .set_port(port_2)
+        .set_keypair(&server_keypair_2)
+        .set_address_balance(&address, Amount::from_mantissa_scale(1000, 0).unwrap())
+        .build();
+    let mut client_universe = BootstrapClientTestUniverse::new(
+        BootstrapClientForeignControllers::new_with_mocks(),
+        BootstrapConfig {
+            parallel_bootstrap_servers: 2,
+            ..Default::default()
+        },
+    );
+    // the ledger is downloaded from the second server, and checked against the first one
+    match client_universe.launch_parallel_bootstrap(&[
+        (port_1, NodeId::new(server_keypair_1.get_public_key())),
+        (port_2, NodeId::new(server_keypair_2.get_public_key())),
+    ]) {
+        Ok(_) => panic!("Bootstrap should have failed"),
+        Err(BootstrapError::GeneralError(err)) => {
+            assert!(err.contains("does not match the hash sent by the server"))
+        }
+        Err(err) => panic!("Unexpected error: {:?}", err),
+    }
+    drop(server_universe_1);
+    drop(server_universe_2);
+}
+
+/// Message of a client that kept its final state at `slot`, asking only the changes since then
+fn ask_state_changes_since(slot: Slot) -> BootstrapClientMessage {
+    BootstrapClientMessage::AskBootstrapPart {
+        last_slot: Some(slot),
+        last_state_step: StreamingStep::Finished(None),
+        last_versioning_step: StreamingStep::Finished(None),
+        last_consensus_step: StreamingStep::Started,
+        send_last_start_period: true,
+    }
+}
+
+#[test]
+#[serial]
+fn test_differential_bootstrap() {
+    let port = 8074;
+    let server_keypair = KeyPair::generate(0).unwrap();
+    let address_1 = Address::from_public_key(&server_keypair.get_public_key());
+    let address_2 = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+    // the server applied changes at the genesis slot, where the client left, then at a later slot
+    let server_universe = BootstrapServerTestUniverseBuilder::new()
+        .set_port(port)
+        .set_keypair(&server_keypair)
+        .set_address_balance(&address_1, Amount::from_mantissa_scale(100, 0).unwrap())
+        .set_slot(Slot::new(1, 0))
+        .set_address_balance(&address_2, Amount::from_mantissa_scale(200, 0).unwrap())
+        .build();
+    // the final state is not reset, as only its changes are bootstrapped
+    let mut client_universe = BootstrapClientTestUniverse::new(
+        BootstrapClientForeignControllers::new_with_mocks(),
+        BootstrapConfig {
+            differential_bootstrap: true,
+            ..Default::default()
+        },
+    );
+    client_universe
+        .launch_bootstrap_from(
+            port,
+            NodeId::new(server_keypair.get_public_key()),
+            &mut ask_state_changes_since(Slot::new(0, 0)),
+        )
+        .unwrap();
+    client_universe.compare_database(server_universe.database.clone());
+}
+
+#[test]
+#[serial]
+fn test_differential_bootstrap_slot_too_old() {
+    let (port_1, port_2) = (8075, 8076);
+    let server_keypair = KeyPair::generate(0).unwrap();
+    let address = Address::from_public_key(&server_keypair.get_public_key());
+    // the history of the servers starts after the slot of the client
+    let build_server = |port| {
+        BootstrapServerTestUniverseBuilder::new()
+            .set_port(port)
+            .set_keypair(&server_keypair)
+            .set_slot(Slot::new(1, 0))
+            .set_address_balance(&address, Amount::from_mantissa_scale(100, 0).unwrap())
+            .build()
+    };
+    let server_universe_1 = build_server(port_1);
+    let controllers = BootstrapClientForeignControllers::new_with_mocks();
+    controllers
+        .final_state_controller
+        .write()
+        .expect_reset()
+        .times(1)
+        .return_const(());
+    let mut client_universe = BootstrapClientTestUniverse::new(
+        controllers,
+        BootstrapConfig {
+            differential_bootstrap: true,
+            ..Default::default()
+        },
+    );
+    let mut next_bootstrap_message = ask_state_changes_since(Slot::new(0, 0));
+    match client_universe.launch_bootstrap_from(
+        port_1,
+        NodeId::new(server_keypair.get_public_key()),
+        &mut next_bootstrap_message,
+    ) {
+        Ok(()) => panic!("Bootstrap should have failed"),
+        Err(BootstrapError::GeneralError(err)) => assert_eq!(err, "Slot too old"),
+        Err(err) => panic!("Unexpected error: {:?}", err),
+    }
+    drop(server_universe_1);
+
+    // the client falls back to bootstrapping the whole state
+    assert!(matches!(
+        next_bootstrap_message,
+        BootstrapClientMessage::AskBootstrapPart {
+            last_slot: None,
+            last_state_step: StreamingStep::Started,
+            last_versioning_step: StreamingStep::Started,
+            ..
+        }
+    ));
+    let server_universe_2 = build_server(port_2);
+    client_universe
+        .launch_bootstrap_from(
+            port_2,
+            NodeId::new(server_keypair.get_public_key()),
+            &mut next_bootstrap_message,
+        )
+        .unwrap();
+    client_universe.compare_database(server_universe_2.database.clone());
+}
@@ -0,0 +1,93 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use massa_metrics::MassaMetrics;
+use massa_models::config::THREAD_COUNT;
+
+use crate::sessions::{BootstrapRateLimits, BootstrapSessions};
+
+fn new_sessions(
+    max_sessions: usize,
+    max_per_ip: usize,
+    max_queued: usize,
+) -> Arc<BootstrapSessions> {
+    let massa_metrics = MassaMetrics::new(
+        false,
+        "0.0.0.0:31248".parse().unwrap(),
+        THREAD_COUNT,
+        Duration::from_secs(5),
+    )
+    .0;
+    Arc::new(BootstrapSessions::new(
+        max_sessions,
+        max_per_ip,
+        max_queued,
+        Arc::new(BootstrapRateLimits::default()),
+        massa_metrics,
+    ))
+}
+
+#[test]
+fn test_sessions_limits() {
+    let sessions = new_sessions(1, 1, 1);
+    let ip_a: IpAddr = "1.2.3.4".parse().unwrap();
+    let ip_b: IpAddr = "5.6.7.8".parse().unwrap();
+    let ip_c: IpAddr = "9.10.11.12".parse().unwrap();
+
+    let active = sessions.register(ip_a).unwrap();
+    // a single session per IP
+    assert!(sessions.register(ip_a).is_err());
+    // the slot is taken, the session is queued
+    let queued = sessions.register(ip_b).unwrap();
+    // the queue is full
+    assert!(sessions.register(ip_c).is_err());
+    assert_eq!(sessions.active_count(), 1);
+
+    // the queued session leaving frees its place in the queue
+    drop(queued);
+    let _queued = sessions.register(ip_c).unwrap();
+    drop(active);
+    assert_eq!(sessions.active_count(), 0);
+    assert!(sessions.register(ip_a).is_ok());
+}
+
+#[test]
+fn test_sessions_queue() {
+    let sessions = new_sessions(1, 1, 2);
+    let active = sessions.register("1.2.3.4".parse().unwrap()).unwrap();
+    let mut first = sessions.register("5.6.7.8".parse().unwrap()).unwrap();
+    let mut second = sessions.register("9.10.11.12".parse().unwrap()).unwrap();
+
+    // the position is told again at each refresh until the session is active
+    let mut positions = Vec::new();
+    let waited = second.wait_turn(Duration::from_millis(10), |position| {
+        positions.push(position);
+        if positions.len() < 3 {
+            Ok(())
+        } else {
+            Err(())
+        }
+    });
+    assert_eq!(waited, Err(()));
+    assert_eq!(positions, vec![2, 2, 2]);
+
+    let handle = std::thread::spawn(move || {
+        let mut positions = Vec::new();
+        first
+            .wait_turn(Duration::from_secs(10), |position| {
+                positions.push(position);
+                Ok::<(), ()>(())
+            })
+            .unwrap();
+        (first, positions)
+    });
+    // the end of the active session wakes up the first queued one
+    std::thread::sleep(Duration::from_millis(100));
+    drop(active);
+    let (_first, positions) = handle.join().unwrap();
+    assert_eq!(positions, vec![1, 0]);
+    assert_eq!(sessions.active_count(), 1);
+}
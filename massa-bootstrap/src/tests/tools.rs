@@ -8,14 +8,14 @@ use massa_async_pool::{test_exports::get_random_message, AsyncPool};
 use massa_consensus_exports::{
     bootstrapable_graph::BootstrapableGraph, export_active_block::ExportActiveBlock,
 };
-use massa_db_exports::{DBBatch, ShareableMassaDBController, StreamBatch};
+use massa_db_exports::{DBBatch, ShareableMassaDBController, StateRange, StreamBatch};
 use massa_executed_ops::{
     ExecutedDenunciations, ExecutedDenunciationsChanges, ExecutedDenunciationsConfig, ExecutedOps,
     ExecutedOpsConfig,
 };
 use massa_final_state::test_exports::create_final_state;
 use massa_final_state::{FinalState, FinalStateConfig, FinalStateController};
-use massa_hash::{Hash, HASH_SIZE_BYTES};
+use massa_hash::{Hash, HashXof, HASH_SIZE_BYTES};
 use massa_ledger_exports::{LedgerEntry, SetUpdateOrDelete};
 use massa_ledger_worker::test_exports::create_final_ledger;
 use massa_models::bytecode::Bytecode;
@@ -351,9 +351,14 @@ pub fn get_bootstrap_config(bootstrap_public_key: NodeId) -> BootstrapConfig {
         max_clock_delta: MassaTime::from_millis(1000),
         cache_duration: MassaTime::from_millis(10000),
         max_simultaneous_bootstraps: 2,
+        max_simultaneous_bootstraps_per_ip: 2,
+        max_queued_bootstraps: 0,
+        server_rate_limit: 0,
         ip_list_max_size: 10,
         per_ip_min_interval: MassaTime::from_millis(10000),
         rate_limit: std::u64::MAX,
+        parallel_bootstrap_servers: 1,
+        differential_bootstrap: false,
         max_datastore_key_length: MAX_DATASTORE_KEY_LENGTH,
         randomness_size_bytes: BOOTSTRAP_RANDOMNESS_SIZE_BYTES,
         thread_count: THREAD_COUNT,
@@ -610,7 +615,7 @@ impl BootstrapServerMessageFaultyPart {
 
 impl BootstrapServerMessage {
     pub fn generate<R: Rng>(rng: &mut R) -> Self {
-        let variant = rng.gen_range(0..6);
+        let variant = rng.gen_range(0..8);
         match variant {
             0 => {
                 let t: u64 = rng.gen();
@@ -626,6 +631,7 @@ impl BootstrapServerMessage {
                 BootstrapServerMessage::BootstrapTime {
                     server_time,
                     version,
+                    differential_bootstrap: rng.gen(),
                 }
             }
             1 => {
@@ -680,6 +686,23 @@ impl BootstrapServerMessage {
             5 => BootstrapServerMessage::BootstrapError {
                 error: gen_random_string(MAX_BOOTSTRAP_ERROR_LENGTH as usize, rng),
             },
+            6 => {
+                let slot = gen_random_slot(rng);
+                let state_part =
+                    gen_random_stream_batch(MAX_BOOTSTRAP_FINAL_STATE_PARTS_SIZE, slot, rng);
+                let range_hash = if rng.gen_bool(0.5) {
+                    Some(HashXof::compute_from(&gen_random_vector(10, rng)))
+                } else {
+                    None
+                };
+                BootstrapServerMessage::StateRangePart {
+                    state_part,
+                    range_hash,
+                }
+            }
+            7 => BootstrapServerMessage::BootstrapQueued {
+                position: rng.gen(),
+            },
             _ => unreachable!(),
         }
     }
@@ -989,12 +1012,14 @@ impl BootstrapServerMessage {
                 BootstrapServerMessage::BootstrapTime {
                     server_time: t1,
                     version: v1,
+                    differential_bootstrap: d1,
                 },
                 BootstrapServerMessage::BootstrapTime {
                     server_time: t2,
                     version: v2,
+                    differential_bootstrap: d2,
                 },
-            ) => (t1 == t2) && (v1 == v2),
+            ) => (t1 == t2) && (v1 == v2) && (d1 == d2),
             (
                 BootstrapServerMessage::BootstrapPeers { peers: p1 },
                 BootstrapServerMessage::BootstrapPeers { peers: p2 },
@@ -1040,6 +1065,16 @@ impl BootstrapServerMessage {
                     && (lp1 == lp2)
                     && (ls1 == ls2)
             }
+            (
+                BootstrapServerMessage::StateRangePart {
+                    state_part: state1,
+                    range_hash: h1,
+                },
+                BootstrapServerMessage::StateRangePart {
+                    state_part: state2,
+                    range_hash: h2,
+                },
+            ) => stream_batch_equal(state1, state2) && (h1 == h2),
             (
                 BootstrapServerMessage::BootstrapFinished,
                 BootstrapServerMessage::BootstrapFinished,
@@ -1049,6 +1084,10 @@ impl BootstrapServerMessage {
                 BootstrapServerMessage::BootstrapError { error: e1 },
                 BootstrapServerMessage::BootstrapError { error: e2 },
             ) => e1 == e2,
+            (
+                BootstrapServerMessage::BootstrapQueued { position: p1 },
+                BootstrapServerMessage::BootstrapQueued { position: p2 },
+            ) => p1 == p2,
             _ => false,
         }
     }
@@ -1105,6 +1144,18 @@ impl BootstrapClientMessage {
                     && (lcs1 == lcs2)
                     && (slp1 == slp2)
             }
+            (
+                BootstrapClientMessage::AskStateRange {
+                    range: r1,
+                    last_slot: ls1,
+                    last_state_step: lstate1,
+                },
+                BootstrapClientMessage::AskStateRange {
+                    range: r2,
+                    last_slot: ls2,
+                    last_state_step: lstate2,
+                },
+            ) => (r1 == r2) && (ls1 == ls2) && (lstate1 == lstate2),
             (
                 BootstrapClientMessage::BootstrapError { error: e1 },
                 BootstrapClientMessage::BootstrapError { error: e2 },
@@ -1120,7 +1171,7 @@ impl BootstrapClientMessage {
     // Generates a message filled with random data of random size based on the limit given in
     // constants. Used for parametric testing
     pub fn generate<R: Rng>(rng: &mut R) -> Self {
-        let variant = rng.gen_range(0..4);
+        let variant = rng.gen_range(0..5);
         match variant {
             0 => BootstrapClientMessage::AskBootstrapPeers,
             1 => {
@@ -1171,6 +1222,27 @@ impl BootstrapClientMessage {
                 error: gen_random_string(MAX_BOOTSTRAP_ERROR_LENGTH as usize, rng),
             },
             3 => BootstrapClientMessage::BootstrapSuccess,
+            4 => {
+                let range = StateRange {
+                    start: gen_random_vector(10, rng),
+                    end: if rng.gen_bool(0.5) {
+                        Some(gen_random_vector(10, rng))
+                    } else {
+                        None
+                    },
+                };
+                let last_slot = if rng.gen_bool(0.5) {
+                    Some(gen_random_slot(rng))
+                } else {
+                    None
+                };
+                let data = gen_random_vector(10, rng);
+                BootstrapClientMessage::AskStateRange {
+                    range,
+                    last_slot,
+                    last_state_step: gen_random_streaming_step(rng, data),
+                }
+            }
             _ => unreachable!(),
         }
     }
@@ -1,16 +1,19 @@
 use std::{
     net::{IpAddr, Ipv4Addr, SocketAddr},
     sync::Arc,
+    time::Duration,
 };
 
 use massa_db_exports::{MassaDBConfig, MassaDBController, ShareableMassaDBController};
 use massa_db_worker::MassaDB;
 use massa_final_state::MockFinalStateController;
+use massa_metrics::MassaMetrics;
 use massa_models::{
     config::{
         MAX_BOOTSTRAP_FINAL_STATE_PARTS_SIZE, MAX_BOOTSTRAP_VERSIONING_ELEMENTS_SIZE, THREAD_COUNT,
     },
     node::NodeId,
+    slot::Slot,
     streaming_step::StreamingStep,
 };
 use massa_test_framework::TestUniverse;
@@ -20,7 +23,7 @@ use parking_lot::RwLock;
 use tempfile::TempDir;
 
 use crate::{
-    client::{bootstrap_from_server, connect_to_server, MockBSConnector},
+    client::{bootstrap_from_server, connect_to_server, parallel_state_bootstrap, MockBSConnector},
     BootstrapClientMessage, BootstrapConfig, BootstrapError, GlobalBootstrapState,
 };
 
@@ -106,14 +109,6 @@ impl BootstrapClientTestUniverse {
         remote_port: u16,
         remote_node_id: NodeId,
     ) -> Result<(), BootstrapError> {
-        let remote_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), remote_port);
-        self.controllers
-            .bs_connector
-            .expect_connect_timeout()
-            .times(1)
-            .returning(move |_, _| Ok(std::net::TcpStream::connect(remote_addr).unwrap()));
-        //TODO: Maybe move it out of this
-        let version = "BOOT.1.0".parse().unwrap();
         let mut next_bootstrap_message: BootstrapClientMessage =
             BootstrapClientMessage::AskBootstrapPart {
                 last_slot: None,
@@ -122,9 +117,28 @@ impl BootstrapClientTestUniverse {
                 last_consensus_step: StreamingStep::Started,
                 send_last_start_period: true,
             };
+        self.launch_bootstrap_from(remote_port, remote_node_id, &mut next_bootstrap_message)
+    }
+
+    /// Bootstraps from the server listening on the given port, starting with
+    /// `next_bootstrap_message`, which is updated as the bootstrap goes
+    pub fn launch_bootstrap_from(
+        &mut self,
+        remote_port: u16,
+        remote_node_id: NodeId,
+        next_bootstrap_message: &mut BootstrapClientMessage,
+    ) -> Result<(), BootstrapError> {
+        let remote_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), remote_port);
+        self.controllers
+            .bs_connector
+            .expect_connect_timeout()
+            .times(1)
+            .returning(move |_, _| Ok(std::net::TcpStream::connect(remote_addr).unwrap()));
+        //TODO: Maybe move it out of this
+        let version = "BOOT.1.0".parse().unwrap();
 
         let mut conn = connect_to_server(
-            &mut self.controllers.bs_connector,
+            &self.controllers.bs_connector,
             &self.config,
             &remote_addr,
             &remote_node_id.get_public_key(),
@@ -134,12 +148,55 @@ impl BootstrapClientTestUniverse {
         bootstrap_from_server(
             &self.config,
             &mut conn,
-            &mut next_bootstrap_message,
+            next_bootstrap_message,
             &mut self.global_bootstrap_state,
             version,
         )
     }
 
+    /// Downloads the final state in parallel from the servers listening on the given ports
+    pub fn launch_parallel_bootstrap(
+        &mut self,
+        remote_servers: &[(u16, NodeId)],
+    ) -> Result<Slot, BootstrapError> {
+        self.controllers
+            .bs_connector
+            .expect_connect_timeout()
+            .times(remote_servers.len())
+            .returning(move |addr, _| Ok(std::net::TcpStream::connect(addr).unwrap()));
+        let servers: Vec<(SocketAddr, NodeId)> = remote_servers
+            .iter()
+            .map(|(port, node_id)| {
+                (
+                    SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), *port),
+                    *node_id,
+                )
+            })
+            .collect();
+        let massa_metrics = MassaMetrics::new(
+            false,
+            "0.0.0.0:31248".parse().unwrap(),
+            THREAD_COUNT,
+            Duration::from_secs(5),
+        )
+        .0;
+        let (mut client, slot) = parallel_state_bootstrap(
+            &self.config,
+            &self.controllers.bs_connector,
+            &self.global_bootstrap_state.final_state,
+            &servers,
+            "BOOT.1.0".parse().unwrap(),
+            &massa_metrics,
+        )?;
+        client
+            .send_timeout(
+                &BootstrapClientMessage::BootstrapSuccess,
+                Some(self.config.write_timeout.into()),
+            )
+            .unwrap();
+        Ok(slot)
+    }
+
     //TODO: Add consensus blocks and peers
     pub fn compare_database(&self, other_database: ShareableMassaDBController) {
         assert_eq!(
@@ -23,6 +23,7 @@ use massa_models::{
     },
     datastore::Datastore,
     prehash::PreHashSet,
+    slot::Slot,
     streaming_step::StreamingStep,
 };
 use massa_protocol_exports::{BootstrapPeers, MockProtocolControllerWrapper};
@@ -121,6 +122,7 @@ pub struct BootstrapServerTestUniverseBuilder {
     final_ledger: FinalLedger,
     socket_addr: SocketAddr,
     accept_error: bool,
+    slot: Option<Slot>,
 }
 
 impl Default for BootstrapServerTestUniverseBuilder {
@@ -186,6 +188,7 @@ impl Default for BootstrapServerTestUniverseBuilder {
             final_ledger,
             socket_addr: SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8069),
             accept_error: false,
+            slot: None,
         }
     }
 }
@@ -205,7 +208,7 @@ impl BootstrapServerTestUniverseBuilder {
         self.controllers
             .database
             .write()
-            .write_batch(batch, versioning_batch, None);
+            .write_batch(batch, versioning_batch, self.slot);
         self
     }
 
@@ -219,7 +222,7 @@ impl BootstrapServerTestUniverseBuilder {
         self.controllers
             .database
             .write()
-            .write_batch(batch, versioning_batch, None);
+            .write_batch(batch, versioning_batch, self.slot);
         self
     }
 
@@ -235,7 +238,13 @@ impl BootstrapServerTestUniverseBuilder {
         self.controllers
             .database
             .write()
-            .write_batch(batch, versioning_batch, None);
+            .write_batch(batch, versioning_batch, self.slot);
+        self
+    }
+
+    /// Writes the following changes as the changes of `slot`, which become the slot of the final state
+    pub fn set_slot(mut self, slot: Slot) -> Self {
+        self.slot = Some(slot);
         self
     }
 
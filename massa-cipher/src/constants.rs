@@ -6,17 +6,32 @@
 
 use pbkdf2::Params;
 
+use crate::kdf::Argon2Params;
+
 /// AES-GCM-SIV nonce size.
 ///
 /// A nonce is a single-use value which enables securely encrypting multiple messages under the same key.
 /// Nonces need not be random: a counter can be used so long as the values are never repeated under the same key.
 pub const NONCE_SIZE: usize = 12;
 
-/// `PBKDF2` salt size.
+/// Key derivation salt size.
 pub const SALT_SIZE: usize = 16;
 
+/// AES-256 key size.
+pub const KEY_SIZE: usize = 32;
+
 /// `PBKDF2` hash parameters.
 pub const HASH_PARAMS: Params = Params {
     rounds: 600_000,
     output_length: 32,
 };
+
+/// `Argon2id` parameters of the new encryptions.
+///
+/// 19 MiB of memory and 2 iterations, as recommended by OWASP: loading a wallet derives the key of
+/// each of its files, which has to stay fast enough with many files.
+pub const ARGON2_PARAMS: Argon2Params = Argon2Params {
+    memory_cost: 19 * 1024,
+    time_cost: 2,
+    parallelism: 1,
+};
@@ -0,0 +1,79 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! massa-cipher key derivation module.
+//!
+//! Read `lib.rs` module documentation for more information.
+
+use argon2::{Algorithm, Argon2, Version};
+use pbkdf2::{
+    password_hash::{PasswordHasher, Salt, SaltString},
+    Pbkdf2,
+};
+
+use crate::constants::{ARGON2_PARAMS, HASH_PARAMS, KEY_SIZE, SALT_SIZE};
+
+/// `Argon2id` cost parameters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Argon2Params {
+    /// memory size, in KiB
+    pub memory_cost: u32,
+    /// number of iterations
+    pub time_cost: u32,
+    /// degree of parallelism
+    pub parallelism: u32,
+}
+
+/// Key derivation function hashing the password into the cipher key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kdf {
+    /// `PBKDF2`-HMAC-SHA256 with `HASH_PARAMS`, used by the data encrypted before `Argon2id`
+    Pbkdf2,
+    /// `Argon2id` with the given parameters
+    Argon2id(Argon2Params),
+}
+
+impl Default for Kdf {
+    /// `Argon2id` with the current recommended parameters
+    fn default() -> Self {
+        Kdf::Argon2id(ARGON2_PARAMS)
+    }
+}
+
+/// Derive the cipher key from the password and the salt.
+pub(crate) fn derive_key(
+    password: &str,
+    salt: &[u8; SALT_SIZE],
+    kdf: Kdf,
+) -> Result<[u8; KEY_SIZE], String> {
+    let mut key = [0u8; KEY_SIZE];
+    match kdf {
+        Kdf::Pbkdf2 => {
+            let salt = SaltString::encode_b64(salt).map_err(|e| e.to_string())?;
+            let password_hash = Pbkdf2
+                .hash_password_customized(
+                    password.as_bytes(),
+                    None,
+                    None,
+                    HASH_PARAMS,
+                    Salt::from(&salt),
+                )
+                .map_err(|e| e.to_string())?
+                .hash
+                .expect("content is missing after a successful hash");
+            key.copy_from_slice(password_hash.as_bytes());
+        }
+        Kdf::Argon2id(params) => {
+            let params = argon2::Params::new(
+                params.memory_cost,
+                params.time_cost,
+                params.parallelism,
+                Some(KEY_SIZE),
+            )
+            .map_err(|e| e.to_string())?;
+            Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+                .hash_password_into(password.as_bytes(), salt, &mut key)
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(key)
+}
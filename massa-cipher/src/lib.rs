@@ -7,8 +7,11 @@
 //! AES-GCM is a state-of-the-art high-performance Authenticated Encryption with Associated Data (AEAD)
 //! that provides confidentiality and authenticity.
 //!
-//! To hash the password before using it as a cipher key, we use the `PBKDF2` key derivation function
-//! as specified in [RFC 2898](https://datatracker.ietf.org/doc/html/rfc2898).
+//! To hash the password before using it as a cipher key, we use the `Argon2id` key derivation
+//! function as specified in [RFC 9106](https://datatracker.ietf.org/doc/html/rfc9106), whose cost
+//! parameters are recorded with the encrypted data so that they can be raised over time.
+//! The data encrypted before uses the `PBKDF2` key derivation function as specified in
+//! [RFC 2898](https://datatracker.ietf.org/doc/html/rfc2898), which is still supported.
 //!
 //! The AES-GCM crate we use has received one security audit by NCC Group, with no significant findings.
 
@@ -16,11 +19,15 @@ mod constants;
 mod decrypt;
 mod encrypt;
 mod error;
+mod kdf;
 mod tests;
 
+pub use constants::ARGON2_PARAMS;
 pub use decrypt::decrypt;
 pub use encrypt::encrypt;
+pub use encrypt::encrypt_with_kdf;
 pub use encrypt::CipherData;
 pub use error::CipherError;
+pub use kdf::{Argon2Params, Kdf};
 
 pub type Salt = [u8; constants::SALT_SIZE];
@@ -1,9 +1,11 @@
 #[cfg(test)]
-use crate::constants::{HASH_PARAMS, NONCE_SIZE, SALT_SIZE};
+use crate::constants::{ARGON2_PARAMS, HASH_PARAMS, NONCE_SIZE, SALT_SIZE};
 #[cfg(test)]
 use crate::decrypt::decrypt;
 #[cfg(test)]
-use crate::encrypt::encrypt;
+use crate::encrypt::{encrypt, encrypt_with_kdf};
+#[cfg(test)]
+use crate::kdf::{Argon2Params, Kdf};
 
 #[test]
 fn test_encrypt() {
@@ -42,3 +44,22 @@ fn test_encrypt_decrypt_bad_password() {
     let cipher_data = encrypt("password", data.as_bytes()).unwrap();
     decrypt("wrong", cipher_data).expect_err("Wrong password should failed");
 }
+
+#[test]
+fn test_encrypt_decrypt_kdfs() {
+    let data = "data";
+
+    // data encrypted with PBKDF2 can still be decrypted
+    let cipher_data = encrypt_with_kdf("password", data.as_bytes(), Kdf::Pbkdf2).unwrap();
+    let decrypted_data = decrypt("password", cipher_data).unwrap();
+    assert_eq!(decrypted_data, data.as_bytes());
+
+    // the key depends on the parameters of the key derivation
+    let mut cipher_data = encrypt("password", data.as_bytes()).unwrap();
+    assert_eq!(cipher_data.kdf, Kdf::Argon2id(ARGON2_PARAMS));
+    cipher_data.kdf = Kdf::Argon2id(Argon2Params {
+        time_cost: ARGON2_PARAMS.time_cost + 1,
+        ..ARGON2_PARAMS
+    });
+    decrypt("password", cipher_data).expect_err("Other parameters should failed");
+}
@@ -0,0 +1,207 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Sending of a batch of transactions described in a CSV or JSON file.
+//!
+//! The transactions are sent in rounds of at most one block worth of operations, one period apart,
+//! so that each round can be included in a block of the thread of the sender. The operations of a
+//! round share the same expire period, except for the identical transfers: the pool considers an
+//! operation with the same creator, content and expire period as a pending one as a replacement of
+//! it, so their expire periods are spread over the validity period of the operations.
+
+use crate::cmds::get_expire_period;
+use crate::rpc_error;
+use anyhow::{anyhow, bail, Result};
+use massa_api_exports::operation::OperationInput;
+use massa_models::config::constants::MAX_OPERATIONS_PER_BLOCK;
+use massa_models::{
+    address::Address,
+    amount::Amount,
+    operation::{Operation, OperationId, OperationType},
+};
+use massa_sdk::Client;
+use massa_wallet::Wallet;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Number of operations sent per request, the default limit of arguments of the API
+const OPERATIONS_PER_REQUEST: usize = 128;
+
+/// Transaction of a batch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct BatchTransfer {
+    pub recipient_address: Address,
+    pub amount: Amount,
+    pub fee: Amount,
+}
+
+/// Result of a transaction of a batch
+#[derive(Debug, Serialize)]
+pub(crate) struct BatchReport {
+    #[serde(flatten)]
+    pub transfer: BatchTransfer,
+    /// id of the operation, if the node accepted it
+    pub operation_id: Option<OperationId>,
+    /// reason why the operation was not sent or was refused by the node
+    pub error: Option<String>,
+}
+
+/// Parse the transactions of a batch file.
+/// A `.json` file holds an array of transactions, any other file is a CSV file whose lines are
+/// `recipient_address,amount,fee`, with an optional header line.
+pub(crate) fn parse_batch(path: &Path, content: &str) -> Result<Vec<BatchTransfer>> {
+    if path
+        .extension()
+        .map_or(false, |extension| extension == "json")
+    {
+        return Ok(serde_json::from_str(content)?);
+    }
+    let mut transfers = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || (index == 0 && line.starts_with("recipient")) {
+            continue;
+        }
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        let [recipient_address, amount, fee] = fields[..] else {
+            bail!("line {}: expected recipient_address,amount,fee", index + 1);
+        };
+        transfers.push(BatchTransfer {
+            recipient_address: recipient_address
+                .parse()
+                .map_err(|e| anyhow!("line {}: {}", index + 1, e))?,
+            amount: amount
+                .parse()
+                .map_err(|e| anyhow!("line {}: {}", index + 1, e))?,
+            fee: fee
+                .parse()
+                .map_err(|e| anyhow!("line {}: {}", index + 1, e))?,
+        });
+    }
+    Ok(transfers)
+}
+
+/// Sign the transactions with the key of `addr` and send them, printing the progress
+pub(crate) async fn send_batch(
+    client: &Client,
+    wallet: &Wallet,
+    addr: Address,
+    transfers: Vec<BatchTransfer>,
+    json: bool,
+) -> Result<Vec<BatchReport>> {
+    let status = match client.public.get_status().await {
+        Ok(status) => status,
+        Err(e) => rpc_error!(e),
+    };
+    let total = transfers.len();
+    let mut reports: Vec<BatchReport> = transfers
+        .into_iter()
+        .map(|transfer| BatchReport {
+            transfer,
+            operation_id: None,
+            error: None,
+        })
+        .collect();
+
+    let mut sent = 0;
+    for (round, round_reports) in reports
+        .chunks_mut(MAX_OPERATIONS_PER_BLOCK as usize)
+        .enumerate()
+    {
+        if round > 0 {
+            tokio::time::sleep(status.config.t0.to_duration()).await;
+        }
+        let fees = round_reports.iter().map(|report| report.transfer.fee);
+        let Some(min_fee) = fees.min() else {
+            continue;
+        };
+        let expire_period = get_expire_period(client, min_fee, addr).await?;
+
+        let mut occurrences: HashMap<(Address, u64), u64> = HashMap::new();
+        let mut indices = Vec::with_capacity(round_reports.len());
+        let mut inputs = Vec::with_capacity(round_reports.len());
+        for (index, report) in round_reports.iter_mut().enumerate() {
+            let transfer = &report.transfer;
+            let occurrence = occurrences
+                .entry((transfer.recipient_address, transfer.amount.to_raw()))
+                .or_default();
+            if *occurrence >= status.config.operation_validity_periods {
+                report.error = Some("too many identical transactions in a round".to_string());
+                continue;
+            }
+            let op = wallet.create_operation(
+                Operation {
+                    fee: transfer.fee,
+                    expire_period: expire_period - *occurrence,
+                    op: OperationType::Transaction {
+                        recipient_address: transfer.recipient_address,
+                        amount: transfer.amount,
+                    },
+                },
+                addr,
+            )?;
+            *occurrence += 1;
+            indices.push(index);
+            inputs.push(OperationInput {
+                creator_public_key: op.content_creator_pub_key,
+                signature: op.signature,
+                serialized_content: op.serialized_data,
+            });
+        }
+
+        let mut inputs = inputs.into_iter();
+        for request_indices in indices.chunks(OPERATIONS_PER_REQUEST) {
+            let request = inputs.by_ref().take(request_indices.len()).collect();
+            match client.public.send_operations(request).await {
+                Ok(operation_ids) => {
+                    for (index, operation_id) in request_indices.iter().zip(operation_ids) {
+                        round_reports[*index].operation_id = Some(operation_id);
+                    }
+                    sent += request_indices.len();
+                }
+                Err(e) => {
+                    for index in request_indices {
+                        round_reports[*index].error = Some(e.to_string());
+                    }
+                }
+            }
+            if !json {
+                println!("Sent {}/{} operations", sent, total);
+            }
+        }
+    }
+    Ok(reports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_signature::KeyPair;
+
+    #[test]
+    fn test_parse_batch() {
+        let recipient = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        let expected = vec![
+            BatchTransfer {
+                recipient_address: recipient,
+                amount: Amount::from_raw(1_500_000_000),
+                fee: Amount::from_raw(10_000_000),
+            };
+            2
+        ];
+
+        let csv = format!(
+            "recipient_address,amount,fee\n{0},1.5,0.01\n\n {0} , 1.5 , 0.01\n",
+            recipient
+        );
+        let transfers = parse_batch(Path::new("batch.csv"), &csv).unwrap();
+        assert_eq!(transfers, expected);
+
+        let json = serde_json::to_string(&expected).unwrap();
+        let transfers = parse_batch(Path::new("batch.json"), &json).unwrap();
+        assert_eq!(transfers, expected);
+
+        let csv = format!("{},1.5\n", recipient);
+        assert!(parse_batch(Path::new("batch.csv"), &csv).is_err());
+    }
+}
@@ -1,14 +1,21 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use crate::batch::{parse_batch, send_batch};
+use crate::deploy::{deploy, read_datastore, Deployment, INIT_ARGS_KEY};
 use crate::display::Output;
+use crate::settings::SETTINGS;
 use crate::{client_warning, rpc_error};
 use anyhow::{anyhow, bail, Result};
 use console::style;
+use dialoguer::Password;
 use massa_api_exports::{
     address::{AddressInfo, CompactAddressInfo},
+    block::BlockGraphExportRequest,
     datastore::DatastoreEntryInput,
     execution::{ReadOnlyBytecodeExecution, ReadOnlyCall},
     operation::OperationInput,
+    page::PageRequest,
+    slot::SlotAmount,
 };
 use massa_models::node::NodeId;
 use massa_models::prehash::PreHashMap;
@@ -18,17 +25,25 @@ use massa_models::{
     amount::Amount,
     block_id::BlockId,
     endorsement::EndorsementId,
-    execution::EventFilter,
-    operation::{Operation, OperationId, OperationType},
+    execution::{AsyncMessageFilter, EventFilter},
+    operation::{
+        Operation, OperationId, OperationSerializer, OperationType, PoolOperationSort,
+        SecureShareOperation,
+    },
+    secure_share::SecureShareContent,
     slot::Slot,
 };
+use massa_sdk::multisig::{self, MultisigProposal};
 use massa_sdk::Client;
-use massa_signature::KeyPair;
-use massa_wallet::Wallet;
+use massa_signature::{KeyPair, PublicKey};
+use massa_time::MassaTime;
+#[cfg(feature = "ledger")]
+use massa_wallet::LedgerDevice;
+use massa_wallet::{Mnemonic, Wallet, MNEMONIC_WORD_COUNT};
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashMap, HashSet};
-use std::fmt::Write as _;
+use std::env;
 use std::fmt::{Debug, Display};
 use std::net::IpAddr;
 use std::path::PathBuf;
@@ -58,6 +73,13 @@ pub enum Command {
     )]
     exit,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(pwd_not_needed = "true"),
+        message = "show the profiles of the configuration file, selected with --profile"
+    )]
+    profile_list,
+
     #[strum(
         ascii_case_insensitive,
         props(args = "IpAddr1 IpAddr2 ...", pwd_not_needed = "true"),
@@ -93,6 +115,68 @@ pub enum Command {
     )]
     node_stop,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "[GracePeriod]", pwd_not_needed = "true"),
+        message = "stops the node, giving the in-flight API requests GracePeriod milliseconds to finish instead of the configured period"
+    )]
+    node_shutdown,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(pwd_not_needed = "true"),
+        message = "show the effective node configuration"
+    )]
+    node_get_config,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(pwd_not_needed = "true"),
+        message = "reload the node configuration, applying the settings that can change without a restart"
+    )]
+    node_reload_config,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(pwd_not_needed = "true"),
+        message = "show the disk usage of the node storage"
+    )]
+    node_get_storage_info,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(pwd_not_needed = "true"),
+        message = "show the peers known by the node, with their reputation score"
+    )]
+    node_get_peers,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(pwd_not_needed = "true"),
+        message = "show the bandwidth limits of the node"
+    )]
+    node_get_bandwidth_limits,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(
+            args = "peer_upload=bytes_per_second peer_download=bytes_per_second global_upload=bytes_per_second global_download=bytes_per_second",
+            pwd_not_needed = "true"
+        ),
+        message = "change the bandwidth limits of the node until its restart (0 meaning unlimited), the omitted ones being unchanged"
+    )]
+    node_set_bandwidth_limits,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(
+            args = "format=dot|graphml start=slot_period,slot_thread end=slot_period,slot_thread",
+            pwd_not_needed = "true"
+        ),
+        message = "export the block graph with the finality status and cliques of the blocks, for visualization"
+    )]
+    node_export_block_graph,
+
     #[strum(
         ascii_case_insensitive,
         props(pwd_not_needed = "true"),
@@ -137,8 +221,8 @@ pub enum Command {
 
     #[strum(
         ascii_case_insensitive,
-        props(args = "(add or remove) [IpAddr]", pwd_not_needed = "true"),
-        message = "Manage peers whitelist IP address(es). No args returns the peers whitelist"
+        props(args = "(add or remove) [IpAddr|IpCidr]", pwd_not_needed = "true"),
+        message = "Manage peers whitelist IP address(es) and ranges. No args returns the peers whitelist"
     )]
     node_peers_whitelist,
 
@@ -149,6 +233,40 @@ pub enum Command {
     )]
     get_status,
 
+    #[strum(
+        ascii_case_insensitive,
+        message = "show a dashboard of the node (sync status, peers, staking slots, wallet balances, recent blocks) refreshed at each slot, until Ctrl+C is pressed"
+    )]
+    monitor,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(pwd_not_needed = "true"),
+        message = "show the cliques of the block graph and why each of them is the blockclique or not"
+    )]
+    get_cliques_details,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(pwd_not_needed = "true"),
+        message = "show the recent stale blocks by cycle, with their creators and slots"
+    )]
+    get_stale_block_stats,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(pwd_not_needed = "true"),
+        message = "show the discarded blocks retained by the node, with the reason of their discard"
+    )]
+    get_discarded_blocks,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "BlockId", pwd_not_needed = "true"),
+        message = "show the proof that a block is final (endorsements, descendants, final state fingerprint)"
+    )]
+    get_finality_proof,
+
     #[strum(
         ascii_case_insensitive,
         props(args = "Address1 Address2 ...", pwd_not_needed = "true"),
@@ -184,6 +302,20 @@ pub enum Command {
     )]
     get_operations,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "fee|age [Limit] [Page]", pwd_not_needed = "true"),
+        message = "show a page of the operations of the pool, sorted by fee or by age, with the occupancy of the pool"
+    )]
+    get_pool_operations,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(pwd_not_needed = "true"),
+        message = "show the item counts of the pools, and the endorsements collected at each slot"
+    )]
+    get_pool_stats,
+
     #[strum(
         ascii_case_insensitive,
         props(
@@ -194,6 +326,23 @@ pub enum Command {
     )]
     get_filtered_sc_output_event,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(
+            args = "sender=Address destination=Address can_be_executed=bool",
+            pwd_not_needed = "true"
+        ),
+        message = "show the asynchronous messages waiting in the pool with various filters"
+    )]
+    get_async_messages,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "MaxGas", pwd_not_needed = "true"),
+        message = "show the eviction threshold of the asynchronous pool and the min fee for a message to be retained in it"
+    )]
+    get_async_message_fee_suggestion,
+
     #[strum(
         ascii_case_insensitive,
         props(args = "show-all-keys"),
@@ -228,6 +377,27 @@ pub enum Command {
     )]
     wallet_add_secret_keys,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "PublicKey1 PublicKey2 ..."),
+        message = "add a list of public keys to the wallet, to follow their addresses without their secret keys"
+    )]
+    wallet_add_watch_only,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "[AccountCount]"),
+        message = "generate a 24-word mnemonic phrase and add the keys of its first accounts (1 by default) into the wallet"
+    )]
+    wallet_generate_mnemonic,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "AccountCount Word1 Word2 ... Word24"),
+        message = "add the keys of the first accounts of a 24-word mnemonic phrase to the wallet"
+    )]
+    wallet_restore_mnemonic,
+
     #[strum(
         ascii_case_insensitive,
         props(args = "Address1 Address2 ..."),
@@ -235,6 +405,18 @@ pub enum Command {
     )]
     wallet_remove_addresses,
 
+    #[strum(
+        ascii_case_insensitive,
+        message = "re-encrypt the wallet with a new password (read from MASSA_CLIENT_NEW_PASSWORD or prompted), keeping the previous wallet as a backup"
+    )]
+    wallet_change_password,
+
+    #[strum(
+        ascii_case_insensitive,
+        message = "write the wallet files of a previous format in the current format, keeping the previous wallet as a backup"
+    )]
+    wallet_migrate,
+
     #[strum(
         ascii_case_insensitive,
         props(args = "Address string"),
@@ -263,6 +445,13 @@ pub enum Command {
     )]
     send_transaction,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "SenderAddress PathToBatch PathToReport"),
+        message = "send coins from a wallet address to the recipients of a CSV (recipient_address,amount,fee lines) or JSON file, and write the result of each transaction to a JSON report"
+    )]
+    send_batch,
+
     #[strum(
         ascii_case_insensitive,
         props(args = "SenderAddress PathToBytecode MaxGas MaxCoins Fee"),
@@ -270,6 +459,13 @@ pub enum Command {
     )]
     execute_smart_contract,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "SenderAddress PathToBytecode Coins Fee InitArgs PathToDatastore"),
+        message = "deploy smart contracts by executing their deployment byte code, with gas estimated by the node and coins for their storage on top of Coins. InitArgs (the constructor arguments) and PathToDatastore (a JSON object of text or {\"file\": path} values) are optional, and are given to the byte code in the operation datastore"
+    )]
+    deploy_smart_contract,
+
     #[strum(
         ascii_case_insensitive,
         props(args = "SenderAddress TargetAddress FunctionName Parameter MaxGas Coins Fee"),
@@ -277,6 +473,86 @@ pub enum Command {
     )]
     call_smart_contract,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "SenderAddress EmissionSlot EmissionIndex Fee"),
+        message = "cancel an async message emitted by a wallet address and not executed yet, EmissionSlot being period,thread"
+    )]
+    cancel_async_message,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "PathToOperationDescription PathToSignedOperation"),
+        message = "sign an operation described in a JSON file (chain_id, creator, fee, expire_period, op) without contacting the node, and write the signed operation to a file to broadcast from an online machine"
+    )]
+    sign_offline,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "PathToSignedOperation", pwd_not_needed = "true"),
+        message = "send an operation signed with sign_offline"
+    )]
+    broadcast,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(
+            args = "VaultAddress Nonce TargetAddress FunctionName Parameter Coins PathToProposal",
+            pwd_not_needed = "true"
+        ),
+        message = "write to a file a proposal of call to be made by a multisig vault, for its owners to sign it with multisig_sign"
+    )]
+    multisig_propose,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "PathToProposal Address"),
+        message = "sign a multisig proposal with a wallet address owning the vault, and add the signature to the file"
+    )]
+    multisig_sign,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "PathToProposal SenderAddress MaxGas Fee"),
+        message = "send a multisig proposal signed by enough owners to its vault, for the vault to make the call"
+    )]
+    multisig_submit,
+
+    #[cfg(feature = "ledger")]
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "AccountIndex", pwd_not_needed = "true"),
+        message = "show the address of an account of the Ledger device, and display it on the device to check it"
+    )]
+    ledger_get_address,
+
+    #[cfg(feature = "ledger")]
+    #[strum(
+        ascii_case_insensitive,
+        props(
+            args = "AccountIndex ReceiverAddress Amount Fee",
+            pwd_not_needed = "true"
+        ),
+        message = "send coins from an account of the Ledger device"
+    )]
+    ledger_send_transaction,
+
+    #[cfg(feature = "ledger")]
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "AccountIndex RollCount Fee", pwd_not_needed = "true"),
+        message = "buy rolls with an account of the Ledger device"
+    )]
+    ledger_buy_rolls,
+
+    #[cfg(feature = "ledger")]
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "AccountIndex RollCount Fee", pwd_not_needed = "true"),
+        message = "sell rolls with an account of the Ledger device"
+    )]
+    ledger_sell_rolls,
+
     #[strum(
         ascii_case_insensitive,
         props(args = "PathToBytecode MaxGas Address Fee", pwd_not_needed = "true"),
@@ -326,14 +602,45 @@ pub enum ListOperation {
     Remove,
 }
 
+/// Operation to sign with `sign_offline`, with the chain parameters usually read from the node
+#[derive(Debug, Deserialize)]
+struct OfflineOperation {
+    /// id of the chain on which the operation is valid
+    chain_id: u64,
+    /// address of the creator, whose key must be in the wallet
+    creator: Address,
+    fee: Amount,
+    /// last period at which the operation can be included in a block
+    expire_period: u64,
+    op: OperationType,
+}
+
+/// Description of a command
+#[derive(Debug, Serialize)]
+pub(crate) struct CommandHelp {
+    pub name: String,
+    /// arguments of the command, if it takes any
+    pub args: Option<String>,
+    pub description: String,
+    /// whether the command needs to unlock the wallet
+    pub password_needed: bool,
+}
+
 /// Used to have a shinny json output
 /// TODO re-factor me
 #[derive(Debug, Serialize)]
 pub(crate) struct ExtendedWalletEntry {
-    /// the keypair
-    pub keypair: KeyPair,
+    /// the keypair, missing for the watch-only addresses
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keypair: Option<KeyPair>,
+    /// the public key
+    pub public_key: PublicKey,
+    /// whether the address is watched without its secret key
+    pub watch_only: bool,
     /// address and balance information
     pub address_info: CompactAddressInfo,
+    /// coins to be unlocked, by slot
+    pub deferred_credits: Vec<SlotAmount>,
     /// whether to display the public/secret keys or just the address info
     pub show_keys: bool,
 }
@@ -341,8 +648,10 @@ pub(crate) struct ExtendedWalletEntry {
 impl Display for ExtendedWalletEntry {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         if self.show_keys {
-            writeln!(f, "Secret key: {}", self.keypair)?;
-            writeln!(f, "Public key: {}", self.keypair.get_public_key())?;
+            if let Some(keypair) = &self.keypair {
+                writeln!(f, "Secret key: {}", keypair)?;
+            }
+            writeln!(f, "Public key: {}", self.public_key)?;
         }
         writeln!(f, "{}", self.address_info)?;
         writeln!(f, "\n=====\n")?;
@@ -362,15 +671,18 @@ impl ExtendedWallet {
             addresses_info
                 .iter()
                 .map(|x| {
-                    let keypair = wallet
-                        .keys
-                        .get(&x.address)
+                    let public_key = wallet
+                        .find_associated_public_key(&x.address)
                         .ok_or_else(|| anyhow!("missing key"))?;
+                    let keypair = wallet.keys.get(&x.address).cloned();
                     Ok((
                         x.address,
                         ExtendedWalletEntry {
-                            keypair: keypair.clone(),
+                            watch_only: keypair.is_none(),
+                            keypair,
+                            public_key,
                             address_info: x.compact(),
+                            deferred_credits: x.deferred_credits.clone(),
                             show_keys,
                         },
                     ))
@@ -414,6 +726,16 @@ impl Command {
         )
     }
 
+    /// Description of the command, as listed by `help` in JSON mode
+    pub(crate) fn help_entry(&self) -> CommandHelp {
+        CommandHelp {
+            name: self.to_string(),
+            args: self.get_str("args").map(str::to_string),
+            description: self.get_message().unwrap_or_default().to_string(),
+            password_needed: self.is_pwd_needed(),
+        }
+    }
+
     /// Returns true if the command needs wallet access
     pub(crate) fn is_pwd_needed(&self) -> bool {
         !(self.get_str("pwd_not_needed").is_some()
@@ -443,7 +765,17 @@ impl Command {
 
         match self {
             Command::help => {
-                if !json {
+                if json {
+                    if parameters.is_empty() {
+                        let commands: Vec<CommandHelp> =
+                            Command::iter().map(|c| c.help_entry()).collect();
+                        return Ok(Box::new(commands));
+                    }
+                    let Ok(c) = parameters[0].parse::<Command>() else {
+                        bail!("command {} not found", parameters[0]);
+                    };
+                    return Ok(Box::new(vec![c.help_entry()]));
+                } else {
                     if !parameters.is_empty() {
                         if let Ok(c) = parameters[0].parse::<Command>() {
                             c.help();
@@ -524,6 +856,115 @@ impl Command {
                 Ok(Box::new(()))
             }
 
+            Command::node_shutdown => {
+                if parameters.len() > 1 {
+                    bail!("wrong number of parameters");
+                }
+                let grace_period = match parameters.first() {
+                    Some(grace_period) => Some(MassaTime::from_millis(grace_period.parse()?)),
+                    None => None,
+                };
+                match client.private.shutdown(grace_period).await {
+                    Ok(()) => {
+                        if !json {
+                            println!("Request of shutting down the Node successfully sent")
+                        }
+                    }
+                    Err(e) => rpc_error!(e),
+                };
+                Ok(Box::new(()))
+            }
+
+            Command::node_get_config => match client.private.get_node_config().await {
+                Ok(config) => Ok(Box::new(config)),
+                Err(e) => rpc_error!(e),
+            },
+
+            Command::node_reload_config => match client.private.reload_node_config().await {
+                Ok(reload) => Ok(Box::new(reload)),
+                Err(e) => rpc_error!(e),
+            },
+
+            Command::node_get_storage_info => match client.private.get_storage_info().await {
+                Ok(storage_info) => Ok(Box::new(storage_info)),
+                Err(e) => rpc_error!(e),
+            },
+
+            Command::node_get_peers => match client.private.get_peers().await {
+                Ok(peers) => Ok(Box::new(peers)),
+                Err(e) => rpc_error!(e),
+            },
+
+            Command::node_get_bandwidth_limits => {
+                match client.private.get_bandwidth_limits().await {
+                    Ok(limits) => Ok(Box::new(limits)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
+            Command::node_set_bandwidth_limits => {
+                let p_list: [&str; 4] = [
+                    "peer_upload",
+                    "peer_download",
+                    "global_upload",
+                    "global_download",
+                ];
+                let mut p: HashMap<&str, &str> = HashMap::new();
+                for v in parameters {
+                    let s: Vec<&str> = v.split('=').collect();
+                    if s.len() == 2 && p_list.contains(&s[0]) {
+                        p.insert(s[0], s[1]);
+                    } else {
+                        bail!("invalid parameter: {}, type \"help node_set_bandwidth_limits\" to get the list of valid parameters", v);
+                    }
+                }
+                let mut limits = match client.private.get_bandwidth_limits().await {
+                    Ok(limits) => limits,
+                    Err(e) => rpc_error!(e),
+                };
+                if let Some(limit) = parse_key_value(&p, p_list[0])? {
+                    limits.peer_upload = limit;
+                }
+                if let Some(limit) = parse_key_value(&p, p_list[1])? {
+                    limits.peer_download = limit;
+                }
+                if let Some(limit) = parse_key_value(&p, p_list[2])? {
+                    limits.global_upload = limit;
+                }
+                if let Some(limit) = parse_key_value(&p, p_list[3])? {
+                    limits.global_download = limit;
+                }
+                match client.private.set_bandwidth_limits(limits).await {
+                    Ok(()) => Ok(Box::new(limits)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
+            Command::node_export_block_graph => {
+                let p_list: [&str; 3] = ["format", "start", "end"];
+                let mut p: HashMap<&str, &str> = HashMap::new();
+                for v in parameters {
+                    let s: Vec<&str> = v.split('=').collect();
+                    if s.len() == 2 && p_list.contains(&s[0]) {
+                        p.insert(s[0], s[1]);
+                    } else {
+                        bail!("invalid parameter: {}, type \"help node_export_block_graph\" to get the list of valid parameters", v);
+                    }
+                }
+                let Some(format) = parse_key_value(&p, p_list[0])? else {
+                    bail!("missing parameter: format");
+                };
+                let request = BlockGraphExportRequest {
+                    start_slot: parse_key_value(&p, p_list[1])?,
+                    end_slot: parse_key_value(&p, p_list[2])?,
+                    format,
+                };
+                match client.private.node_export_block_graph(request).await {
+                    Ok(graph) => Ok(Box::new(graph)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
             Command::node_get_staking_addresses => {
                 match client.private.get_staking_addresses().await {
                     Ok(staking_addresses) => Ok(Box::new(staking_addresses)),
@@ -569,6 +1010,40 @@ impl Command {
                 Err(e) => rpc_error!(e),
             },
 
+            Command::monitor => {
+                if json {
+                    bail!("the monitor cannot be displayed in JSON mode");
+                }
+                crate::monitor::run(client, wallet_opt.as_ref().unwrap()).await?;
+                Ok(Box::new(()))
+            }
+
+            Command::get_cliques_details => match client.public.get_cliques_details().await {
+                Ok(cliques) => Ok(Box::new(cliques)),
+                Err(e) => rpc_error!(e),
+            },
+
+            Command::get_stale_block_stats => match client.public.get_stale_block_stats().await {
+                Ok(stale_block_stats) => Ok(Box::new(stale_block_stats)),
+                Err(e) => rpc_error!(e),
+            },
+
+            Command::get_discarded_blocks => match client.public.get_discarded_blocks().await {
+                Ok(discarded_blocks) => Ok(Box::new(discarded_blocks)),
+                Err(e) => rpc_error!(e),
+            },
+
+            Command::get_finality_proof => {
+                if parameters.len() != 1 {
+                    bail!("wrong number of parameters");
+                }
+                let block_id = BlockId::from_str(&parameters[0])?;
+                match client.public.get_finality_proof(block_id).await {
+                    Ok(finality_proof) => Ok(Box::new(finality_proof)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
             Command::get_addresses => {
                 let addresses = parse_vec::<Address>(parameters)?;
                 match client.public.get_addresses(addresses).await {
@@ -620,6 +1095,36 @@ impl Command {
                 }
             }
 
+            Command::get_pool_operations => {
+                if parameters.is_empty() || parameters.len() > 3 {
+                    bail!("wrong number of parameters");
+                }
+                let sort = match parameters[0].as_str() {
+                    "fee" => PoolOperationSort::Fee,
+                    "age" => PoolOperationSort::Age,
+                    v => bail!("invalid sort order: {}, expected fee or age", v),
+                };
+                let page_request = match parameters.get(1) {
+                    Some(limit) => Some(PageRequest {
+                        limit: limit.parse::<usize>()?,
+                        offset: match parameters.get(2) {
+                            Some(page) => page.parse::<usize>()?,
+                            None => 0,
+                        },
+                    }),
+                    None => None,
+                };
+                match client.public.get_pool_operations(sort, page_request).await {
+                    Ok(pool_operations) => Ok(Box::new(pool_operations)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
+            Command::get_pool_stats => match client.public.get_pool_stats().await {
+                Ok(pool_stats) => Ok(Box::new(pool_stats)),
+                Err(e) => rpc_error!(e),
+            },
+
             Command::get_filtered_sc_output_event => {
                 let p_list: [&str; 7] = [
                     "start",
@@ -654,6 +1159,43 @@ impl Command {
                 }
             }
 
+            Command::get_async_messages => {
+                let p_list: [&str; 3] = ["sender", "destination", "can_be_executed"];
+                let mut p: HashMap<&str, &str> = HashMap::new();
+                for v in parameters {
+                    let s: Vec<&str> = v.split('=').collect();
+                    if s.len() == 2 && p_list.contains(&s[0]) {
+                        p.insert(s[0], s[1]);
+                    } else {
+                        bail!("invalid parameter: {}, type \"help get_async_messages\" to get the list of valid parameters", v);
+                    }
+                }
+                let filter = AsyncMessageFilter {
+                    sender: parse_key_value(&p, p_list[0])?,
+                    destination: parse_key_value(&p, p_list[1])?,
+                    can_be_executed: parse_key_value(&p, p_list[2])?,
+                };
+                match client.public.get_async_messages(filter).await {
+                    Ok(messages) => Ok(Box::new(messages)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
+            Command::get_async_message_fee_suggestion => {
+                if parameters.len() != 1 {
+                    bail!("wrong number of parameters");
+                }
+                let max_gas = parameters[0].parse::<u64>()?;
+                match client
+                    .public
+                    .get_async_message_fee_suggestion(max_gas)
+                    .await
+                {
+                    Ok(suggestion) => Ok(Box::new(suggestion)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
             Command::wallet_info => {
                 let show_keys = parameters.len() == 1 && parameters[0] == "show-all-keys";
 
@@ -662,11 +1204,12 @@ impl Command {
                 if !json && show_keys {
                     client_warning!("do not share your secret key");
                 }
-                match client
-                    .public
-                    .get_addresses(wallet.get_full_wallet().keys().copied().collect())
-                    .await
-                {
+                let addresses = wallet
+                    .get_wallet_address_list()
+                    .union(&wallet.get_watch_only_address_list())
+                    .copied()
+                    .collect();
+                match client.public.get_addresses(addresses).await {
                     Ok(addresses_info) => Ok(Box::new(ExtendedWallet::new(
                         wallet,
                         &addresses_info,
@@ -689,15 +1232,9 @@ impl Command {
 
                 let hashset: HashSet<_> = addresses.into_iter().collect();
 
-                let keypair: Vec<(&Address, Option<&KeyPair>)> = hashset
-                    .iter()
-                    .map(|addr| (addr, wallet.get_full_wallet().get(addr)))
-                    .filter(|kp| kp.1.is_some())
-                    .collect();
-
-                let addr_public_keys: Vec<_> = keypair
+                let addr_public_keys: Vec<_> = hashset
                     .iter()
-                    .map(|kp| (*kp.0, kp.1.unwrap().get_public_key()))
+                    .filter_map(|addr| Some((*addr, wallet.find_associated_public_key(addr)?)))
                     .collect();
 
                 Ok(Box::new(addr_public_keys))
@@ -787,35 +1324,88 @@ impl Command {
                 let keypair_version: u64 = 0;
                 let key = KeyPair::generate(keypair_version).expect("Unable to generate key pair");
 
-                let ad = wallet.add_keypairs(vec![key])?[0];
+                let ad = wallet.add_keypairs(vec![key])?[0];
+                if json {
+                    Ok(Box::new(ad.to_string()))
+                } else {
+                    println!("Generated {} address and added it to the wallet", ad);
+                    println!(
+                        "Type `wallet_info` to show wallet info (keys, addresses, balances ...) and/or `node_start_staking <your address>` to start staking.\n"
+                    );
+                    Ok(Box::new(()))
+                }
+            }
+
+            Command::wallet_add_secret_keys => {
+                if parameters.is_empty() {
+                    bail!("wrong number of parameters");
+                }
+                let wallet = wallet_opt.as_mut().unwrap();
+
+                let keypairs = parse_vec::<KeyPair>(parameters)?;
+                let addresses = wallet.add_keypairs(keypairs)?;
+                if json {
+                    return Ok(Box::new(addresses));
+                } else {
+                    for address in addresses {
+                        println!("Derived and added address {} to the wallet.", address);
+                    }
+                    println!("Type `node_start_staking <address>` to start staking with the corresponding key.\n");
+                }
+                Ok(Box::new(()))
+            }
+
+            Command::wallet_add_watch_only => {
+                if parameters.is_empty() {
+                    bail!("wrong number of parameters");
+                }
+                let wallet = wallet_opt.as_mut().unwrap();
+
+                let public_keys = parse_vec::<PublicKey>(parameters)?;
+                let addresses = wallet.add_watch_only(public_keys)?;
+                if !json {
+                    println!("Watching the addresses:");
+                }
+                Ok(Box::new(addresses))
+            }
+
+            Command::wallet_generate_mnemonic => {
+                if parameters.len() > 1 {
+                    bail!("wrong number of parameters");
+                }
+                let account_count = match parameters.first() {
+                    Some(account_count) => account_count.parse::<u32>()?,
+                    None => 1,
+                };
+                let wallet = wallet_opt.as_mut().unwrap();
+
+                let mnemonic = Mnemonic::generate();
+                let addresses = add_mnemonic_accounts(wallet, &mnemonic, account_count)?;
                 if json {
-                    Ok(Box::new(ad.to_string()))
-                } else {
-                    println!("Generated {} address and added it to the wallet", ad);
-                    println!(
-                        "Type `wallet_info` to show wallet info (keys, addresses, balances ...) and/or `node_start_staking <your address>` to start staking.\n"
-                    );
-                    Ok(Box::new(()))
+                    return Ok(Box::new(serde_json::json!({
+                        "mnemonic": mnemonic.phrase(),
+                        "addresses": addresses,
+                    })));
                 }
+                println!("Mnemonic phrase: {}", mnemonic.phrase());
+                client_warning!("write this phrase down and keep it secret: it restores the keys of all its accounts");
+                println!("Derived and added the addresses:");
+                Ok(Box::new(addresses))
             }
 
-            Command::wallet_add_secret_keys => {
-                if parameters.is_empty() {
+            Command::wallet_restore_mnemonic => {
+                if parameters.len() != MNEMONIC_WORD_COUNT + 1 {
                     bail!("wrong number of parameters");
                 }
+                let account_count = parameters[0].parse::<u32>()?;
                 let wallet = wallet_opt.as_mut().unwrap();
 
-                let keypairs = parse_vec::<KeyPair>(parameters)?;
-                let addresses = wallet.add_keypairs(keypairs)?;
-                if json {
-                    return Ok(Box::new(addresses));
-                } else {
-                    for address in addresses {
-                        println!("Derived and added address {} to the wallet.", address);
-                    }
-                    println!("Type `node_start_staking <address>` to start staking with the corresponding key.\n");
+                let mnemonic = Mnemonic::from_phrase(&parameters[1..].join(" "))?;
+                let addresses = add_mnemonic_accounts(wallet, &mnemonic, account_count)?;
+                if !json {
+                    println!("Derived and added the addresses:");
                 }
-                Ok(Box::new(()))
+                Ok(Box::new(addresses))
             }
 
             Command::wallet_remove_addresses => {
@@ -824,25 +1414,69 @@ impl Command {
                 }
                 let wallet = wallet_opt.as_mut().unwrap();
 
-                let mut res = "".to_string();
                 let addresses = parse_vec::<Address>(parameters)?;
                 match wallet.remove_addresses(&addresses) {
                     Ok(changed) => {
                         if changed {
                             wallet.save()?;
                         }
-                        let _ = writeln!(res, "Addresses removed from the wallet");
-                    }
-                    Err(_) => {
-                        let _ = writeln!(res, "Wallet error while removing addresses");
                     }
+                    Err(e) => bail!("Wallet error while removing addresses: {}", e),
                 }
                 if !json {
-                    println!("{}", res);
+                    println!("Addresses removed from the wallet");
+                }
+                Ok(Box::new(()))
+            }
+
+            Command::wallet_change_password => {
+                if !parameters.is_empty() {
+                    bail!("wrong number of parameters");
+                }
+                let wallet = wallet_opt.as_mut().unwrap();
+
+                let new_password = match env::var("MASSA_CLIENT_NEW_PASSWORD") {
+                    Ok(password) => password,
+                    Err(_) => Password::new()
+                        .with_prompt("Enter new password for wallet")
+                        .with_confirmation("Confirm password", "Passwords mismatching")
+                        .interact()?,
+                };
+                let backup_path = wallet.change_password(new_password)?;
+                if json {
+                    return Ok(Box::new(backup_path.display().to_string()));
                 }
+                println!(
+                    "Wallet password changed, the previous wallet is kept in {}",
+                    backup_path.display()
+                );
                 Ok(Box::new(()))
             }
 
+            Command::wallet_migrate => {
+                if !parameters.is_empty() {
+                    bail!("wrong number of parameters");
+                }
+                let wallet = wallet_opt.as_mut().unwrap();
+
+                let outdated = wallet.get_outdated_address_list();
+                if outdated.is_empty() {
+                    if !json {
+                        println!("The wallet files are in the current format");
+                    }
+                    return Ok(Box::new(Vec::<Address>::new()));
+                }
+                let backup_path = wallet.migrate()?;
+                if !json {
+                    println!(
+                        "Wallet migrated, the previous wallet is kept in {}",
+                        backup_path.display()
+                    );
+                    println!("Migrated the files of the addresses:");
+                }
+                Ok(Box::new(outdated.into_iter().collect::<Vec<_>>()))
+            }
+
             Command::buy_rolls => {
                 let wallet = wallet_opt.as_mut().unwrap();
 
@@ -974,6 +1608,62 @@ impl Command {
                 )
                 .await
             }
+            Command::send_batch => {
+                let wallet = wallet_opt.as_mut().unwrap();
+
+                if parameters.len() != 3 {
+                    bail!("wrong number of parameters");
+                }
+                let addr = parameters[0].parse::<Address>()?;
+                let batch_path = parameters[1].parse::<PathBuf>()?;
+                let report_path = parameters[2].parse::<PathBuf>()?;
+                let transfers =
+                    parse_batch(&batch_path, &tokio::fs::read_to_string(&batch_path).await?)?;
+
+                if !json {
+                    let total = transfers
+                        .iter()
+                        .try_fold(Amount::zero(), |total, transfer| {
+                            total
+                                .checked_add(transfer.amount)?
+                                .checked_add(transfer.fee)
+                        });
+                    if let Ok(addresses_info) = client.public.get_addresses(vec![addr]).await {
+                        match (addresses_info.get(0), total) {
+                            (Some(info), Some(total)) => {
+                                if info.candidate_balance < total {
+                                    client_warning!("this batch may be partially rejected due to insufficient balance");
+                                }
+                            }
+                            (Some(_), None) => {
+                                client_warning!("the total amount hit the limit overflow, the batch will be partially rejected");
+                            }
+                            (None, _) => {
+                                client_warning!(format!("address {} not found", addr))
+                            }
+                        }
+                    }
+                }
+
+                let reports = send_batch(client, wallet, addr, transfers, json).await?;
+                tokio::fs::write(&report_path, serde_json::to_vec_pretty(&reports)?).await?;
+                let failed = reports
+                    .iter()
+                    .filter(|report| report.error.is_some())
+                    .count();
+                if !json {
+                    if failed > 0 {
+                        client_warning!(format!("{} transactions were not sent", failed));
+                    }
+                    println!("Report written to {}", report_path.display());
+                    println!("Sent operation IDs:");
+                }
+                let operation_ids: Vec<OperationId> = reports
+                    .iter()
+                    .filter_map(|report| report.operation_id)
+                    .collect();
+                Ok(Box::new(operation_ids))
+            }
             Command::when_moon => {
                 let res = "At night 🌔.";
                 if !json {
@@ -1033,6 +1723,35 @@ impl Command {
                 )
                 .await
             }
+            Command::deploy_smart_contract => {
+                let wallet = wallet_opt.as_mut().unwrap();
+
+                if parameters.len() < 4 || parameters.len() > 6 {
+                    bail!("wrong number of parameters");
+                }
+                let addr = parameters[0].parse::<Address>()?;
+                let path = parameters[1].parse::<PathBuf>()?;
+                let coins = parameters[2].parse::<Amount>()?;
+                let fee = parameters[3].parse::<Amount>()?;
+                let mut datastore = match parameters.get(5) {
+                    Some(datastore_path) => {
+                        read_datastore(&datastore_path.parse::<PathBuf>()?).await?
+                    }
+                    None => BTreeMap::new(),
+                };
+                if let Some(init_args) = parameters.get(4) {
+                    datastore.insert(INIT_ARGS_KEY.to_vec(), init_args.clone().into_bytes());
+                }
+                let deployment = Deployment {
+                    bytecode: get_file_as_byte_vec(&path).await?,
+                    datastore,
+                    coins,
+                    fee,
+                };
+                Ok(Box::new(
+                    deploy(client, wallet, addr, deployment, json).await?,
+                ))
+            }
             Command::call_smart_contract => {
                 let wallet = wallet_opt.as_mut().unwrap();
 
@@ -1088,6 +1807,227 @@ impl Command {
                 )
                 .await
             }
+            Command::cancel_async_message => {
+                let wallet = wallet_opt.as_mut().unwrap();
+
+                if parameters.len() != 4 {
+                    bail!("wrong number of parameters");
+                }
+                let addr = parameters[0].parse::<Address>()?;
+                let emission_slot = parameters[1].parse::<Slot>()?;
+                let emission_index = parameters[2].parse::<u64>()?;
+                let fee = parameters[3].parse::<Amount>()?;
+                send_operation(
+                    client,
+                    wallet,
+                    OperationType::CancelAsyncMessage {
+                        emission_slot,
+                        emission_index,
+                    },
+                    fee,
+                    addr,
+                    json,
+                )
+                .await
+            }
+            Command::sign_offline => {
+                let wallet = wallet_opt.as_mut().unwrap();
+
+                if parameters.len() != 2 {
+                    bail!("wrong number of parameters");
+                }
+                let description_path = parameters[0].parse::<PathBuf>()?;
+                let signed_path = parameters[1].parse::<PathBuf>()?;
+                let description: OfflineOperation =
+                    serde_json::from_slice(&get_file_as_byte_vec(&description_path).await?)?;
+                let Some(keypair) = wallet.keys.get(&description.creator) else {
+                    bail!("address {} not found in the wallet", description.creator);
+                };
+                let op: SecureShareOperation = Operation::new_verifiable(
+                    Operation {
+                        fee: description.fee,
+                        expire_period: description.expire_period,
+                        op: description.op,
+                    },
+                    OperationSerializer::new(),
+                    keypair,
+                    description.chain_id,
+                )?;
+                let signed = OperationInput {
+                    creator_public_key: op.content_creator_pub_key,
+                    signature: op.signature,
+                    serialized_content: op.serialized_data,
+                };
+                tokio::fs::write(&signed_path, serde_json::to_vec_pretty(&signed)?).await?;
+                if !json {
+                    println!("Signed operation written to {}", signed_path.display());
+                }
+                Ok(Box::new(vec![op.id]))
+            }
+            Command::broadcast => {
+                if parameters.len() != 1 {
+                    bail!("wrong number of parameters");
+                }
+                let path = parameters[0].parse::<PathBuf>()?;
+                let signed: OperationInput =
+                    serde_json::from_slice(&get_file_as_byte_vec(&path).await?)?;
+                match client.public.send_operations(vec![signed]).await {
+                    Ok(operation_ids) => {
+                        if !json {
+                            println!("Sent operation IDs:");
+                        }
+                        Ok(Box::new(operation_ids))
+                    }
+                    Err(e) => rpc_error!(e),
+                }
+            }
+            Command::multisig_propose => {
+                if parameters.len() != 7 {
+                    bail!("wrong number of parameters");
+                }
+                let proposal = MultisigProposal {
+                    chain_id: client.chain_id,
+                    vault: parameters[0].parse::<Address>()?,
+                    nonce: parameters[1].parse::<u64>()?,
+                    target_address: parameters[2].parse::<Address>()?,
+                    target_function: parameters[3].clone(),
+                    parameter: parameters[4].clone().into_bytes(),
+                    coins: parameters[5].parse::<Amount>()?,
+                    signatures: Vec::new(),
+                };
+                let path = parameters[6].parse::<PathBuf>()?;
+                tokio::fs::write(&path, serde_json::to_vec_pretty(&proposal)?).await?;
+                if !json {
+                    println!("Proposal written to {}", path.display());
+                }
+                Ok(Box::new(()))
+            }
+            Command::multisig_sign => {
+                let wallet = wallet_opt.as_mut().unwrap();
+
+                if parameters.len() != 2 {
+                    bail!("wrong number of parameters");
+                }
+                let path = parameters[0].parse::<PathBuf>()?;
+                let addr = parameters[1].parse::<Address>()?;
+                let mut proposal: MultisigProposal =
+                    serde_json::from_slice(&get_file_as_byte_vec(&path).await?)?;
+                let Some(signature) = wallet.sign_message(&addr, proposal.message().into_bytes())
+                else {
+                    bail!("address {} not found in the wallet", addr);
+                };
+                proposal.add_signature(signature)?;
+                tokio::fs::write(&path, serde_json::to_vec_pretty(&proposal)?).await?;
+                if !json {
+                    println!("Proposal signed by:");
+                }
+                Ok(Box::new(proposal.signers()))
+            }
+            Command::multisig_submit => {
+                let wallet = wallet_opt.as_mut().unwrap();
+
+                if parameters.len() != 4 {
+                    bail!("wrong number of parameters");
+                }
+                let path = parameters[0].parse::<PathBuf>()?;
+                let addr = parameters[1].parse::<Address>()?;
+                let max_gas = parameters[2].parse::<u64>()?;
+                let fee = parameters[3].parse::<Amount>()?;
+                let proposal: MultisigProposal =
+                    serde_json::from_slice(&get_file_as_byte_vec(&path).await?)?;
+                if proposal.chain_id != client.chain_id {
+                    bail!(
+                        "the proposal is for the chain {}, not for the chain {} of the node",
+                        proposal.chain_id,
+                        client.chain_id
+                    );
+                }
+                if proposal.signatures.is_empty() {
+                    bail!("the proposal is not signed yet");
+                }
+                send_operation(
+                    client,
+                    wallet,
+                    OperationType::CallSC {
+                        target_addr: proposal.vault,
+                        target_func: multisig::EXECUTE_FUNCTION.to_string(),
+                        param: proposal.execute_parameter(),
+                        max_gas,
+                        coins: Amount::zero(),
+                    },
+                    fee,
+                    addr,
+                    json,
+                )
+                .await
+            }
+            #[cfg(feature = "ledger")]
+            Command::ledger_get_address => {
+                if parameters.len() != 1 {
+                    bail!("wrong number of parameters");
+                }
+                let account = parameters[0].parse::<u32>()?;
+                if !json {
+                    println!("Check the address displayed on the Ledger device");
+                }
+                let public_key = LedgerDevice::open()?.get_public_key(account, true)?;
+                Ok(Box::new(vec![Address::from_public_key(&public_key)]))
+            }
+            #[cfg(feature = "ledger")]
+            Command::ledger_send_transaction => {
+                if parameters.len() != 4 {
+                    bail!("wrong number of parameters");
+                }
+                let account = parameters[0].parse::<u32>()?;
+                let recipient_address = parameters[1].parse::<Address>()?;
+                let amount = parameters[2].parse::<Amount>()?;
+                let fee = parameters[3].parse::<Amount>()?;
+                send_ledger_operation(
+                    client,
+                    account,
+                    OperationType::Transaction {
+                        recipient_address,
+                        amount,
+                    },
+                    fee,
+                    json,
+                )
+                .await
+            }
+            #[cfg(feature = "ledger")]
+            Command::ledger_buy_rolls => {
+                if parameters.len() != 3 {
+                    bail!("wrong number of parameters");
+                }
+                let account = parameters[0].parse::<u32>()?;
+                let roll_count = parameters[1].parse::<u64>()?;
+                let fee = parameters[2].parse::<Amount>()?;
+                send_ledger_operation(
+                    client,
+                    account,
+                    OperationType::RollBuy { roll_count },
+                    fee,
+                    json,
+                )
+                .await
+            }
+            #[cfg(feature = "ledger")]
+            Command::ledger_sell_rolls => {
+                if parameters.len() != 3 {
+                    bail!("wrong number of parameters");
+                }
+                let account = parameters[0].parse::<u32>()?;
+                let roll_count = parameters[1].parse::<u64>()?;
+                let fee = parameters[2].parse::<Amount>()?;
+                send_ledger_operation(
+                    client,
+                    account,
+                    OperationType::RollSell { roll_count },
+                    fee,
+                    json,
+                )
+                .await
+            }
             Command::wallet_sign => {
                 let wallet = wallet_opt.as_mut().unwrap();
 
@@ -1162,6 +2102,7 @@ impl Command {
                         max_gas,
                         coins,
                         fee,
+                        state_overrides: None,
                     })
                     .await
                 {
@@ -1311,12 +2252,13 @@ impl Command {
                     };
                     let args = &parameters[1..];
                     if args.is_empty() {
-                        bail!("[IpAddr] parameter shouldn't be empty");
+                        bail!("[IpAddr|IpCidr] parameter shouldn't be empty");
                     }
-                    let ips = parse_vec::<IpAddr>(args)?;
+                    // the IP ranges are validated by the node
+                    let ranges = args.to_vec();
                     let res: Result<Box<dyn Output>> = match cli_op {
                         ListOperation::Add => {
-                            match client.private.node_add_to_peers_whitelist(ips).await {
+                            match client.private.node_add_to_peers_whitelist(ranges).await {
                                 Ok(()) => {
                                     if !json {
                                         println!("Request of peers whitelisting successfully sent!")
@@ -1327,7 +2269,11 @@ impl Command {
                             }
                         }
                         ListOperation::Remove => {
-                            match client.private.node_remove_from_peers_whitelist(ips).await {
+                            match client
+                                .private
+                                .node_remove_from_peers_whitelist(ranges)
+                                .await
+                            {
                                 Ok(()) => {
                                     if !json {
                                         println!("Request of remove from peers whitelist successfully sent!")
@@ -1347,6 +2293,8 @@ impl Command {
             Command::exit => {
                 std::process::exit(0);
             }
+
+            Command::profile_list => Ok(Box::new(serde_json::to_value(&SETTINGS.profiles)?)),
         }
     }
 }
@@ -1360,6 +2308,48 @@ async fn send_operation(
     addr: Address,
     json: bool,
 ) -> Result<Box<dyn Output>> {
+    let expire_period = get_expire_period(client, fee, addr).await?;
+    let op = wallet.create_operation(
+        Operation {
+            fee,
+            expire_period,
+            op,
+        },
+        addr,
+    )?;
+    submit_operation(client, op, json).await
+}
+
+/// helper to wrap an operation with proper validity period, sign it with the Ledger device
+/// and send it
+#[cfg(feature = "ledger")]
+async fn send_ledger_operation(
+    client: &Client,
+    account: u32,
+    op: OperationType,
+    fee: Amount,
+    json: bool,
+) -> Result<Box<dyn Output>> {
+    let mut ledger = LedgerDevice::open()?;
+    let addr = Address::from_public_key(&ledger.get_public_key(account, false)?);
+    let expire_period = get_expire_period(client, fee, addr).await?;
+    if !json {
+        println!("Review and approve the operation on the Ledger device");
+    }
+    let op = ledger.sign_operation(
+        account,
+        Operation {
+            fee,
+            expire_period,
+            op,
+        },
+        client.chain_id,
+    )?;
+    submit_operation(client, op, json).await
+}
+
+/// Checks the fee of an operation of `addr`, and returns the expire period of the operation
+pub(crate) async fn get_expire_period(client: &Client, fee: Amount, addr: Address) -> Result<u64> {
     let status = match client.public.get_status().await {
         Ok(node_status) => node_status,
         Err(e) => rpc_error!(e),
@@ -1383,16 +2373,15 @@ async fn send_operation(
     if slot.thread >= addr.get_thread(status.config.thread_count) {
         expire_period += 1;
     };
+    Ok(expire_period)
+}
 
-    let op = wallet.create_operation(
-        Operation {
-            fee,
-            expire_period,
-            op,
-        },
-        addr,
-    )?;
-
+/// helper to send a signed operation
+async fn submit_operation(
+    client: &Client,
+    op: SecureShareOperation,
+    json: bool,
+) -> Result<Box<dyn Output>> {
     match client
         .public
         .send_operations(vec![OperationInput {
@@ -1412,6 +2401,21 @@ async fn send_operation(
     }
 }
 
+/// Add the keys of the first `account_count` accounts of `mnemonic` to the wallet
+fn add_mnemonic_accounts(
+    wallet: &mut Wallet,
+    mnemonic: &Mnemonic,
+    account_count: u32,
+) -> Result<Vec<Address>> {
+    if account_count == 0 {
+        bail!("the number of accounts must be positive");
+    }
+    let keypairs = (0..account_count)
+        .map(|account| mnemonic.derive_keypair("", account))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(wallet.add_keypairs(keypairs)?)
+}
+
 /// TODO: ugly utilities functions
 /// takes a slice of string and makes it into a `Vec<T>`
 pub fn parse_vec<T: std::str::FromStr>(args: &[String]) -> anyhow::Result<Vec<T>, anyhow::Error>
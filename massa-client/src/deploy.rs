@@ -0,0 +1,221 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Deployment of smart contracts.
+//!
+//! The deployment bytecode is executed by an `ExecuteSC` operation. It reads the bytecode of the
+//! contracts to create and the arguments of their constructor from the datastore of the operation:
+//! under the keys of the datastore file, and under [`INIT_ARGS_KEY`] for the arguments given on the
+//! command line.
+//!
+//! The deployment is first executed read-only, to estimate its gas and the coins needed to store
+//! the bytecode of the created contracts. Once the operation is executed, the deployed addresses
+//! are the smart contracts found in the call stacks of the events it emitted, as the constructors
+//! of the created contracts are called by the deployment bytecode.
+
+use crate::cmds::get_expire_period;
+use crate::rpc_error;
+use anyhow::{anyhow, bail, Result};
+use massa_api_exports::{
+    execution::{ReadOnlyBytecodeExecution, ReadOnlyResult},
+    operation::OperationInput,
+};
+use massa_models::config::constants::{
+    LEDGER_COST_PER_BYTE, LEDGER_ENTRY_BASE_COST, MAX_GAS_PER_BLOCK,
+};
+use massa_models::{
+    address::Address,
+    amount::Amount,
+    datastore::{Datastore, DatastoreSerializer},
+    execution::EventFilter,
+    operation::{Operation, OperationId, OperationType},
+    output_event::SCOutputEvent,
+};
+use massa_sdk::Client;
+use massa_serialization::Serializer;
+use massa_wallet::Wallet;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// Key of the operation datastore holding the arguments of the constructor
+pub(crate) const INIT_ARGS_KEY: &[u8] = b"init_args";
+/// Margin added to the gas estimated by the read-only execution, in percent
+const GAS_MARGIN_PERCENT: u64 = 20;
+
+/// Value of an entry of a datastore file
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum DatastoreValue {
+    Text(String),
+    /// content of a file, whose path is relative to the datastore file
+    File {
+        file: PathBuf,
+    },
+}
+
+/// Smart contract deployment to send
+pub(crate) struct Deployment {
+    pub bytecode: Vec<u8>,
+    pub datastore: Datastore,
+    /// coins given to the deployment, on top of the storage cost of the created contracts
+    pub coins: Amount,
+    pub fee: Amount,
+}
+
+/// Result of a deployment
+#[derive(Debug, Serialize)]
+pub(crate) struct DeployReport {
+    pub operation_id: OperationId,
+    pub max_gas: u64,
+    pub max_coins: Amount,
+    /// true if the execution succeeded, false if it failed, None if it is not executed yet
+    pub executed: Option<bool>,
+    pub deployed_addresses: Vec<Address>,
+    pub events: Vec<SCOutputEvent>,
+}
+
+/// Read a datastore file: a JSON object whose values are either text or `{"file": path}`
+pub(crate) async fn read_datastore(path: &Path) -> Result<Datastore> {
+    let entries: BTreeMap<String, DatastoreValue> =
+        serde_json::from_slice(&tokio::fs::read(path).await?)?;
+    let mut datastore = Datastore::new();
+    for (key, value) in entries {
+        let value = match value {
+            DatastoreValue::Text(text) => text.into_bytes(),
+            DatastoreValue::File { file } => {
+                let file = path.parent().unwrap_or(Path::new("")).join(file);
+                tokio::fs::read(&file)
+                    .await
+                    .map_err(|e| anyhow!("{}: {}", file.display(), e))?
+            }
+        };
+        datastore.insert(key.into_bytes(), value);
+    }
+    Ok(datastore)
+}
+
+/// Estimate the gas and coins of a deployment, send it from `addr`, and wait for its execution
+pub(crate) async fn deploy(
+    client: &Client,
+    wallet: &Wallet,
+    addr: Address,
+    deployment: Deployment,
+    json: bool,
+) -> Result<DeployReport> {
+    let status = match client.public.get_status().await {
+        Ok(status) => status,
+        Err(e) => rpc_error!(e),
+    };
+
+    let mut operation_datastore = Vec::new();
+    DatastoreSerializer::new().serialize(&deployment.datastore, &mut operation_datastore)?;
+    let estimate = match client
+        .public
+        .execute_read_only_bytecode(ReadOnlyBytecodeExecution {
+            max_gas: MAX_GAS_PER_BLOCK,
+            bytecode: deployment.bytecode.clone(),
+            address: Some(addr),
+            operation_datastore: Some(operation_datastore),
+            fee: Some(deployment.fee),
+        })
+        .await
+    {
+        Ok(estimate) => estimate,
+        Err(e) => rpc_error!(e),
+    };
+    if let ReadOnlyResult::Error(e) = &estimate.result {
+        bail!("the deployment fails: {}", e);
+    }
+    let max_gas = estimate
+        .gas_cost
+        .saturating_add(estimate.gas_cost * GAS_MARGIN_PERCENT / 100)
+        .min(MAX_GAS_PER_BLOCK);
+    let storage_cost = estimate
+        .state_changes
+        .ledger_changes
+        .get_bytecode_updates()
+        .iter()
+        .try_fold(Amount::zero(), |total, bytecode| {
+            LEDGER_COST_PER_BYTE
+                .checked_mul_u64(bytecode.0.len() as u64)?
+                .checked_add(LEDGER_ENTRY_BASE_COST)?
+                .checked_add(total)
+        })
+        .ok_or_else(|| anyhow!("overflow in the storage cost of the contracts"))?;
+    let max_coins = deployment
+        .coins
+        .checked_add(storage_cost)
+        .ok_or_else(|| anyhow!("overflow in the coins of the deployment"))?;
+    if !json {
+        println!(
+            "Estimated gas: {}, storage cost of the contracts: {}",
+            estimate.gas_cost, storage_cost
+        );
+    }
+
+    let expire_period = get_expire_period(client, deployment.fee, addr).await?;
+    let op = wallet.create_operation(
+        Operation {
+            fee: deployment.fee,
+            expire_period,
+            op: OperationType::ExecuteSC {
+                data: deployment.bytecode,
+                max_gas,
+                max_coins,
+                datastore: deployment.datastore,
+            },
+        },
+        addr,
+    )?;
+    let operation_id = op.id;
+    let input = OperationInput {
+        creator_public_key: op.content_creator_pub_key,
+        signature: op.signature,
+        serialized_content: op.serialized_data,
+    };
+    if let Err(e) = client.public.send_operations(vec![input]).await {
+        rpc_error!(e);
+    }
+    if !json {
+        println!("Waiting for the execution of operation {}", operation_id);
+    }
+
+    // the operation is executed within its validity periods, if it is included in a block
+    let slot_duration = status.config.t0.to_duration() / status.config.thread_count as u32;
+    let deadline = Instant::now()
+        + status.config.t0.to_duration() * (status.config.operation_validity_periods as u32 + 1);
+    let mut executed = None;
+    while executed.is_none() && Instant::now() < deadline {
+        tokio::time::sleep(slot_duration).await;
+        if let Ok(operations) = client.public.get_operations(vec![operation_id]).await {
+            executed = operations.first().and_then(|info| info.op_exec_status);
+        }
+    }
+
+    let events = match client
+        .public
+        .get_filtered_sc_output_event(EventFilter {
+            original_operation_id: Some(operation_id),
+            ..Default::default()
+        })
+        .await
+    {
+        Ok(events) => events,
+        Err(e) => rpc_error!(e),
+    };
+    let mut deployed_addresses = Vec::new();
+    for address in events.iter().flat_map(|event| &event.context.call_stack) {
+        if matches!(address, Address::SC(_)) && !deployed_addresses.contains(address) {
+            deployed_addresses.push(*address);
+        }
+    }
+    Ok(DeployReport {
+        operation_id,
+        max_gas,
+        max_coins,
+        executed,
+        deployed_addresses,
+        events,
+    })
+}
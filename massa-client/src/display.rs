@@ -1,17 +1,29 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
 
-use crate::cmds::ExtendedWallet;
+use crate::cmds::{CommandHelp, ExtendedWallet};
+use crate::deploy::DeployReport;
 use console::style;
 use erased_serde::{Serialize, Serializer};
 use massa_api_exports::{
-    address::AddressInfo, block::BlockInfo, datastore::DatastoreEntryOutput,
-    endorsement::EndorsementInfo, execution::ExecuteReadOnlyResponse, node::NodeStatus,
-    operation::OperationInfo,
+    address::AddressInfo,
+    block::{BlockInfo, FinalityProof},
+    datastore::DatastoreEntryOutput,
+    endorsement::EndorsementInfo,
+    execution::ExecuteReadOnlyResponse,
+    node::{BandwidthLimits, NodeStatus, PeerInfo},
+    node_config::NodeConfigReload,
+    operation::{OperationInfo, PoolOperations},
+    storage::StorageInfo,
 };
+use massa_models::block::DiscardedBlock;
+use massa_models::clique::CliqueDetails;
 use massa_models::composite::PubkeySig;
+use massa_models::execution::{AsyncMessageFeeSuggestion, PendingAsyncMessage};
 use massa_models::output_event::SCOutputEvent;
 use massa_models::prehash::PreHashSet;
-use massa_models::stats::{ConsensusStats, ExecutionStats, NetworkStats};
+use massa_models::stats::{
+    ConsensusStats, CycleStaleBlockStats, ExecutionStats, NetworkStats, PoolStats,
+};
 use massa_models::{address::Address, config::CompactConfig, operation::OperationId};
 use massa_signature::{KeyPair, PublicKey};
 use massa_wallet::Wallet;
@@ -50,10 +62,10 @@ macro_rules! grpc_error {
 }
 
 #[macro_export]
-/// print a yellow warning
+/// print a yellow warning, on the standard error so that it does not mix with the JSON output
 macro_rules! client_warning {
     ($e:expr) => {
-        println!("{}: {}", style("WARNING").yellow(), $e)
+        eprintln!("{}: {}", style("WARNING").yellow(), $e)
     };
 }
 
@@ -89,7 +101,7 @@ pub enum Style {
 }
 
 impl Style {
-    fn style<T: ToString>(&self, msg: T) -> console::StyledObject<std::string::String> {
+    pub(crate) fn style<T: ToString>(&self, msg: T) -> console::StyledObject<std::string::String> {
         style(msg.to_string()).color256(match self {
             Style::Id => 218,        // #ffafd7
             Style::Pending => 172,   // #d78700
@@ -136,11 +148,13 @@ impl Output for ExtendedWallet {
         println!("{}", Style::Separator.style("====="));
         for entry in self.0.values() {
             if entry.show_keys {
-                println!("Secret key: {}", Style::Secret.style(&entry.keypair));
-                println!(
-                    "Public key: {}",
-                    Style::Wallet.style(entry.keypair.get_public_key())
-                );
+                if let Some(keypair) = &entry.keypair {
+                    println!("Secret key: {}", Style::Secret.style(keypair));
+                }
+                println!("Public key: {}", Style::Wallet.style(entry.public_key));
+            }
+            if entry.watch_only {
+                println!("{}", Style::Unknown.style("Watch-only"));
             }
             println!(
                 "Address: {} (thread {}):",
@@ -163,6 +177,13 @@ impl Output for ExtendedWallet {
                 Style::Pending.style("candidate"),
                 Style::Protocol.style(entry.address_info.candidate_rolls),
             );
+            for credit in &entry.deferred_credits {
+                println!(
+                    "\tLocked: {} until slot {}",
+                    Style::Coins.style(credit.amount),
+                    Style::Protocol.style(credit.slot),
+                );
+            }
             println!("{}", Style::Separator.style("====="));
         }
     }
@@ -202,6 +223,19 @@ impl Output for () {
     fn pretty_print(&self) {}
 }
 
+impl Output for Vec<CommandHelp> {
+    fn pretty_print(&self) {
+        for command in self {
+            println!(
+                "- {} {}: {}",
+                command.name,
+                command.args.as_deref().unwrap_or("no args"),
+                command.description
+            );
+        }
+    }
+}
+
 impl Output for String {
     fn pretty_print(&self) {
         println!("{}", self);
@@ -260,6 +294,13 @@ impl Output for NodeStatus {
 
         self.network_stats.pretty_print();
         self.execution_stats.pretty_print();
+        match self.history.first_available_period {
+            Some(period) => println!(
+                "History: pruned, available from period {}",
+                Style::Protocol.style(period)
+            ),
+            None => println!("History: {}", Style::Finished.style("full")),
+        }
 
         if !self.connected_nodes.is_empty() {
             println!("Connected nodes:");
@@ -399,6 +440,47 @@ impl Output for BlockInfo {
     }
 }
 
+impl Output for serde_json::Value {
+    fn pretty_print(&self) {
+        match serde_json::to_string_pretty(self) {
+            Ok(json) => println!("{}", json),
+            Err(e) => println!("{}", e),
+        }
+    }
+}
+
+impl Output for NodeConfigReload {
+    fn pretty_print(&self) {
+        print!("{}", self);
+    }
+}
+
+impl Output for StorageInfo {
+    fn pretty_print(&self) {
+        print!("{}", self);
+    }
+}
+
+impl Output for BandwidthLimits {
+    fn pretty_print(&self) {
+        print!("{}", self);
+    }
+}
+
+impl Output for Vec<PeerInfo> {
+    fn pretty_print(&self) {
+        for peer in self {
+            print!("{}", peer);
+        }
+    }
+}
+
+impl Output for FinalityProof {
+    fn pretty_print(&self) {
+        print!("{}", self);
+    }
+}
+
 impl Output for PreHashSet<Address> {
     fn pretty_print(&self) {
         println!(
@@ -494,6 +576,14 @@ impl Output for Vec<IpAddr> {
     }
 }
 
+impl Output for Vec<String> {
+    fn pretty_print(&self) {
+        for item in self {
+            println!("{}", item);
+        }
+    }
+}
+
 impl Output for Vec<OperationInfo> {
     fn pretty_print(&self) {
         for info in self {
@@ -579,6 +669,28 @@ impl Output for Vec<Address> {
     }
 }
 
+impl Output for DeployReport {
+    fn pretty_print(&self) {
+        println!("Operation: {}", Style::Id.style(self.operation_id));
+        println!(
+            "Max gas: {}, max coins: {}",
+            Style::Protocol.style(self.max_gas),
+            Style::Coins.style(self.max_coins)
+        );
+        match self.executed {
+            Some(true) => println!("{}", Style::Good.style("Executed")),
+            Some(false) => println!("{}", Style::Bad.style("Execution failed")),
+            None => println!("{}", Style::Pending.style("Not executed yet")),
+        }
+        for address in &self.deployed_addresses {
+            println!("Deployed address: {}", Style::Wallet.style(address));
+        }
+        for event in &self.events {
+            println!("{}", event);
+        }
+    }
+}
+
 impl Output for Vec<SCOutputEvent> {
     fn pretty_print(&self) {
         for addr in self {
@@ -587,6 +699,56 @@ impl Output for Vec<SCOutputEvent> {
     }
 }
 
+impl Output for Vec<PendingAsyncMessage> {
+    fn pretty_print(&self) {
+        for message in self {
+            println!("{}", message);
+        }
+    }
+}
+
+impl Output for AsyncMessageFeeSuggestion {
+    fn pretty_print(&self) {
+        print!("{}", self);
+    }
+}
+
+impl Output for PoolOperations {
+    fn pretty_print(&self) {
+        print!("{}", self);
+    }
+}
+
+impl Output for PoolStats {
+    fn pretty_print(&self) {
+        print!("{}", self);
+    }
+}
+
+impl Output for Vec<CliqueDetails> {
+    fn pretty_print(&self) {
+        for clique in self {
+            print!("{}", clique);
+        }
+    }
+}
+
+impl Output for Vec<CycleStaleBlockStats> {
+    fn pretty_print(&self) {
+        for cycle_stats in self {
+            print!("{}", cycle_stats);
+        }
+    }
+}
+
+impl Output for Vec<DiscardedBlock> {
+    fn pretty_print(&self) {
+        for block in self {
+            print!("{}", block);
+        }
+    }
+}
+
 impl Output for PubkeySig {
     fn pretty_print(&self) {
         println!("{}", self);
@@ -3,11 +3,11 @@
 #![warn(missing_docs)]
 #![warn(unused_crate_dependencies)]
 use crate::settings::SETTINGS;
-use anyhow::Result;
-use clap::{crate_version, Parser};
+use anyhow::{bail, Result};
+use clap::{crate_version, Parser, ValueEnum};
 use cmds::Command;
 use console::style;
-use dialoguer::Password;
+use dialoguer::{Confirm, Password};
 use is_terminal::IsTerminal;
 use massa_sdk::{Client, ClientConfig, HttpConfig};
 use massa_wallet::Wallet;
@@ -17,8 +17,11 @@ use std::net::IpAddr;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicUsize, Ordering};
 
+mod batch;
 mod cmds;
+mod deploy;
 mod display;
+mod monitor;
 mod repl;
 mod settings;
 
@@ -52,19 +55,36 @@ struct Args {
     /// Optional command parameter (as a JSON string)
     #[arg(name = "PARAMETERS")]
     parameters: Vec<String>,
-    /// Path of wallet folder
-    #[arg(short = 'w', long = "wallet", default_value = "wallets/")]
-    wallet: PathBuf,
-    /// Enable a mode where input/output are serialized as JSON
+    /// Path of wallet folder [default: the one of the profile, or wallets/]
+    #[arg(short = 'w', long = "wallet")]
+    wallet: Option<PathBuf>,
+    /// Profile of the configuration file to use: its wallet folder and node
+    #[arg(long)]
+    profile: Option<String>,
+    /// Enable a mode where input/output are serialized as JSON, same as `--output json`
     #[arg(short = 'j', long = "json")]
     json: bool,
+    /// Format of the output of the command (non-interactive mode)
+    #[arg(short = 'o', long = "output", value_enum, default_value_t = OutputFormat::Text)]
+    output: OutputFormat,
     #[arg(short = 'p', long = "pwd")]
     /// Wallet password
     password: Option<String>,
 }
 
+/// Format of the output of a command
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// human-readable text
+    Text,
+    /// the JSON serialization of the result of the command, or a `JsonError`
+    Json,
+}
+
+/// Output of a failed command in JSON mode
 #[derive(Serialize)]
 struct JsonError {
+    /// the error, followed by its causes
     error: String,
 }
 
@@ -85,6 +105,29 @@ pub(crate) fn ask_password(wallet_path: &Path) -> String {
     }
 }
 
+/// Offer to migrate the wallet files written in a previous version of the format
+pub(crate) fn confirm_migration(wallet: &mut Wallet) -> Result<()> {
+    let outdated = wallet.get_outdated_address_list();
+    if outdated.is_empty() || !std::io::stdin().is_terminal() {
+        return Ok(());
+    }
+    let confirmed = Confirm::new()
+        .with_prompt(format!(
+            "{} wallet file(s) use a previous format, migrate them now?",
+            outdated.len()
+        ))
+        .default(false)
+        .interact()?;
+    if confirmed {
+        let backup_path = wallet.migrate()?;
+        println!(
+            "Wallet migrated, the previous wallet is kept in {}",
+            backup_path.display()
+        );
+    }
+    Ok(())
+}
+
 fn main() -> anyhow::Result<()> {
     let args = Args::parse();
     let tokio_rt = tokio::runtime::Builder::new_multi_thread()
@@ -119,29 +162,46 @@ async fn run(args: Args) -> Result<()> {
     // TODO: move settings loading in another crate ... see #1277
     let settings = SETTINGS.clone();
 
+    // the command line arguments override the profile, which overrides the default node
+    let profile = match &args.profile {
+        Some(name) => match settings.profiles.get(name) {
+            Some(profile) => Some(profile),
+            None => bail!("unknown profile {}", name),
+        },
+        None => None,
+    };
+    let wallet_path = match (args.wallet, profile) {
+        (Some(wallet_path), _) => wallet_path,
+        (None, Some(profile)) => profile.wallet.clone(),
+        (None, None) => PathBuf::from("wallets/"),
+    };
+    let default_node = match profile {
+        Some(profile) => settings.default_node.with_profile(profile),
+        None => settings.default_node.clone(),
+    };
     let address = match args.ip {
         Some(ip) => ip,
-        None => settings.default_node.ip,
+        None => default_node.ip,
     };
     let public_port = match args.public_port {
         Some(public_port) => public_port,
-        None => settings.default_node.public_port,
+        None => default_node.public_port,
     };
     let private_port = match args.private_port {
         Some(private_port) => private_port,
-        None => settings.default_node.private_port,
+        None => default_node.private_port,
     };
     let grpc_port = match args.grpc_public_port {
         Some(grpc_port) => grpc_port,
-        None => settings.default_node.grpc_public_port,
+        None => default_node.grpc_public_port,
     };
     let grpc_priv_port = match args.grpc_private_port {
         Some(grpc_port) => grpc_port,
-        None => settings.default_node.grpc_private_port,
+        None => default_node.grpc_private_port,
     };
     let chain_id = match args.chain_id {
         Some(chain_id) => chain_id,
-        None => match settings.default_node.chain_id {
+        None => match default_node.chain_id {
             Some(chain_id) => chain_id,
             None => *massa_models::config::constants::CHAINID,
         },
@@ -168,9 +228,10 @@ async fn run(args: Args) -> Result<()> {
         &http_config,
     )
     .await?;
-    if std::io::stdout().is_terminal() && args.command == Command::help && !args.json {
+    let json = args.json || args.output == OutputFormat::Json;
+    if std::io::stdout().is_terminal() && args.command == Command::help && !json {
         // Interactive mode
-        repl::run(&mut client, &args.wallet, args.password).await?;
+        repl::run(&mut client, &wallet_path, args.password).await?;
     } else {
         // Non-Interactive mode
 
@@ -180,10 +241,13 @@ async fn run(args: Args) -> Result<()> {
                 let password = match (args.password, env::var("MASSA_CLIENT_PASSWORD")) {
                     (Some(pwd), _) => pwd,
                     (_, Ok(pwd)) => pwd,
-                    _ => ask_password(&args.wallet),
+                    _ => ask_password(&wallet_path),
                 };
 
-                let wallet = Wallet::new(args.wallet, password, chain_id)?;
+                let mut wallet = Wallet::new(wallet_path, password, chain_id)?;
+                if !json && args.command != Command::wallet_migrate {
+                    confirm_migration(&mut wallet)?;
+                }
                 Some(wallet)
             }
             false => None,
@@ -191,28 +255,30 @@ async fn run(args: Args) -> Result<()> {
 
         match args
             .command
-            .run(&mut client, &mut wallet_opt, &args.parameters, args.json)
+            .run(&mut client, &mut wallet_opt, &args.parameters, json)
             .await
         {
             Ok(output) => {
-                if args.json {
+                if json {
                     output
                         .stdout_json()
-                        .expect("fail to serialize to JSON command output")
+                        .expect("fail to serialize to JSON command output");
+                    println!();
                 } else {
                     output.pretty_print();
                 }
             }
             Err(e) => {
-                if args.json {
+                if json {
                     let error = serde_json::to_string(&JsonError {
-                        error: format!("{:?}", e),
+                        error: format!("{:#}", e),
                     })
                     .expect("fail to serialize to JSON error");
                     println!("{}", error);
                 } else {
                     println!("{}", style(format!("Error: {}", e)).red());
                 }
+                std::process::exit(1);
             }
         }
     }
@@ -0,0 +1,295 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Terminal dashboard of the node, for the operators without a Grafana stack.
+//!
+//! The dashboard is redrawn at each slot ticked by the gRPC API, with the status of the node and
+//! the state of the wallet and staking addresses queried through the JSON-RPC API. The recent
+//! blocks are the ones streamed by the gRPC API since the start of the dashboard.
+
+use crate::display::Style;
+use crate::{grpc_error, rpc_error};
+use anyhow::{bail, Result};
+use console::Term;
+use massa_api_exports::{address::AddressInfo, node::NodeStatus};
+use massa_models::{address::Address, amount::Amount, prehash::PreHashSet, slot::Slot};
+use massa_proto_rs::massa::{
+    api::v1::{NewBlocksRequest, SlotTicksRequest},
+    model::v1::SignedBlock,
+};
+use massa_sdk::Client;
+use massa_wallet::Wallet;
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use tokio_stream::wrappers::ReceiverStream;
+
+/// Number of recent blocks displayed
+const RECENT_BLOCK_COUNT: usize = 10;
+/// Number of upcoming block and endorsement draws displayed per staking address
+const NEXT_DRAW_COUNT: usize = 3;
+
+/// Block streamed by the gRPC API
+struct RecentBlock {
+    id: String,
+    slot: Option<Slot>,
+    creator: String,
+    operation_count: usize,
+}
+
+impl From<SignedBlock> for RecentBlock {
+    fn from(block: SignedBlock) -> Self {
+        let content = block.content.unwrap_or_default();
+        RecentBlock {
+            id: block.secure_hash,
+            slot: content
+                .header
+                .and_then(|header| header.content)
+                .and_then(|header| header.slot)
+                .map(Slot::from),
+            creator: block.content_creator_address,
+            operation_count: content.operations.len(),
+        }
+    }
+}
+
+/// Display the dashboard until Ctrl+C is pressed
+pub(crate) async fn run(client: &Client, wallet: &Wallet) -> Result<()> {
+    let Some(mut grpc) = client.grpc_public.clone() else {
+        bail!("check if your node is running and grpc api enabled");
+    };
+    let mut slot_ticks = match grpc.slot_ticks(SlotTicksRequest {}).await {
+        Ok(response) => response.into_inner(),
+        Err(e) => grpc_error!(e),
+    };
+    // the blocks are streamed as long as the stream of requests is open
+    let (blocks_request_tx, blocks_request_rx) = tokio::sync::mpsc::channel(1);
+    blocks_request_tx
+        .send(NewBlocksRequest {
+            filters: Vec::new(),
+            resume_from: None,
+        })
+        .await?;
+    let mut blocks = match grpc
+        .new_blocks(ReceiverStream::new(blocks_request_rx))
+        .await
+    {
+        Ok(response) => response.into_inner(),
+        Err(e) => grpc_error!(e),
+    };
+
+    let term = Term::stdout();
+    let mut recent_blocks = VecDeque::with_capacity(RECENT_BLOCK_COUNT);
+    refresh(client, wallet, &recent_blocks, &term).await?;
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+    loop {
+        tokio::select! {
+            _ = &mut ctrl_c => break,
+            tick = slot_ticks.message() => match tick {
+                Ok(Some(_)) => refresh(client, wallet, &recent_blocks, &term).await?,
+                Ok(None) => bail!("the node closed the stream of slot ticks"),
+                Err(e) => grpc_error!(e),
+            },
+            block = blocks.message() => match block {
+                Ok(Some(response)) => {
+                    if let Some(block) = response.signed_block {
+                        recent_blocks.push_front(RecentBlock::from(block));
+                        recent_blocks.truncate(RECENT_BLOCK_COUNT);
+                    }
+                }
+                Ok(None) => bail!("the node closed the stream of blocks"),
+                Err(e) => grpc_error!(e),
+            },
+        }
+    }
+    term.clear_screen()?;
+    Ok(())
+}
+
+/// Query the node and redraw the dashboard
+async fn refresh(
+    client: &Client,
+    wallet: &Wallet,
+    recent_blocks: &VecDeque<RecentBlock>,
+    term: &Term,
+) -> Result<()> {
+    let status = match client.public.get_status().await {
+        Ok(status) => status,
+        Err(e) => rpc_error!(e),
+    };
+    // the staking addresses are only known if the private API is reachable
+    let staking_addresses: PreHashSet<Address> = client
+        .private
+        .get_staking_addresses()
+        .await
+        .unwrap_or_default();
+    let mut addresses = wallet.get_wallet_address_list();
+    addresses.extend(wallet.get_watch_only_address_list());
+    addresses.extend(&staking_addresses);
+    let mut addresses: Vec<Address> = addresses.into_iter().collect();
+    addresses.sort_unstable();
+    let addresses_info = match client.public.get_addresses(addresses).await {
+        Ok(addresses_info) => addresses_info,
+        Err(e) => rpc_error!(e),
+    };
+
+    let mut screen = String::new();
+    write_status(&mut screen, &status)?;
+    write_staking(&mut screen, &addresses_info, &staking_addresses)?;
+    write_wallet(&mut screen, &addresses_info, wallet)?;
+    write_recent_blocks(&mut screen, recent_blocks)?;
+    writeln!(screen, "{}", Style::Separator.style("Press Ctrl+C to exit"))?;
+    term.clear_screen()?;
+    term.write_str(&screen)?;
+    Ok(())
+}
+
+fn write_status(screen: &mut String, status: &NodeStatus) -> std::fmt::Result {
+    writeln!(
+        screen,
+        "Node {} (version {}) - {}",
+        Style::Id.style(status.node_id),
+        Style::Id.style(status.version),
+        status.current_time.format_instant()
+    )?;
+    let sync = match status.last_slot {
+        None => Style::Pending.style("waiting for genesis"),
+        Some(last_slot) => {
+            let lag = last_slot
+                .slots_since(
+                    &status.execution_stats.active_cursor,
+                    status.config.thread_count,
+                )
+                .unwrap_or_default();
+            if lag <= status.config.thread_count as u64 {
+                Style::Good.style("synchronized")
+            } else {
+                Style::Pending.style(format!("synchronizing ({} slots behind)", lag))
+            }
+        }
+    };
+    writeln!(
+        screen,
+        "Sync: {} - cycle {} - final slot {} - active slot {}",
+        sync,
+        Style::Protocol.style(status.current_cycle),
+        Style::Protocol.style(status.execution_stats.final_cursor),
+        Style::Protocol.style(status.execution_stats.active_cursor)
+    )?;
+    writeln!(
+        screen,
+        "Peers: {} in, {} out, {} known, {} banned - {}",
+        Style::Protocol.style(status.network_stats.in_connection_count),
+        Style::Protocol.style(status.network_stats.out_connection_count),
+        Style::Protocol.style(status.network_stats.known_peer_count),
+        Style::Protocol.style(status.network_stats.banned_peer_count),
+        status.network_stats.reachability
+    )?;
+    writeln!(
+        screen,
+        "Pool: {} operations, {} endorsements",
+        Style::Protocol.style(status.pool_stats.0),
+        Style::Protocol.style(status.pool_stats.1)
+    )?;
+    writeln!(screen)
+}
+
+fn write_staking(
+    screen: &mut String,
+    addresses_info: &[AddressInfo],
+    staking_addresses: &PreHashSet<Address>,
+) -> std::fmt::Result {
+    writeln!(screen, "Staking")?;
+    if staking_addresses.is_empty() {
+        writeln!(screen, "{}", Style::Unknown.style("  no staking address"))?;
+    }
+    for info in addresses_info
+        .iter()
+        .filter(|info| staking_addresses.contains(&info.address))
+    {
+        let blocks: Vec<String> = info
+            .next_block_draws
+            .iter()
+            .take(NEXT_DRAW_COUNT)
+            .map(Slot::to_string)
+            .collect();
+        let endorsements: Vec<String> = info
+            .next_endorsement_draws
+            .iter()
+            .take(NEXT_DRAW_COUNT)
+            .map(|draw| draw.slot.to_string())
+            .collect();
+        writeln!(
+            screen,
+            "  {} - {} rolls - next blocks: {} - next endorsements: {}",
+            Style::Wallet.style(info.address),
+            Style::Protocol.style(info.candidate_roll_count),
+            Style::Block.style(blocks.join(", ")),
+            Style::Block.style(endorsements.join(", "))
+        )?;
+    }
+    writeln!(screen)
+}
+
+fn write_wallet(
+    screen: &mut String,
+    addresses_info: &[AddressInfo],
+    wallet: &Wallet,
+) -> std::fmt::Result {
+    writeln!(screen, "Wallet")?;
+    let wallet_addresses = wallet.get_wallet_address_list();
+    let watch_only_addresses = wallet.get_watch_only_address_list();
+    if wallet_addresses.is_empty() && watch_only_addresses.is_empty() {
+        writeln!(screen, "{}", Style::Unknown.style("  no address"))?;
+    }
+    for info in addresses_info.iter().filter(|info| {
+        wallet_addresses.contains(&info.address) || watch_only_addresses.contains(&info.address)
+    }) {
+        let locked = info
+            .deferred_credits
+            .iter()
+            .fold(Amount::zero(), |total, credit| {
+                total.saturating_add(credit.amount)
+            });
+        let watch_only = if watch_only_addresses.contains(&info.address) {
+            " (watch-only)"
+        } else {
+            ""
+        };
+        writeln!(
+            screen,
+            "  {}{} - final balance {} - candidate balance {} - locked {} - {} rolls",
+            Style::Wallet.style(info.address),
+            Style::Unknown.style(watch_only),
+            Style::Coins.style(info.final_balance),
+            Style::Pending.style(info.candidate_balance),
+            Style::Coins.style(locked),
+            Style::Protocol.style(info.candidate_roll_count)
+        )?;
+    }
+    writeln!(screen)
+}
+
+fn write_recent_blocks(
+    screen: &mut String,
+    recent_blocks: &VecDeque<RecentBlock>,
+) -> std::fmt::Result {
+    writeln!(screen, "Recent blocks")?;
+    if recent_blocks.is_empty() {
+        writeln!(screen, "{}", Style::Unknown.style("  waiting for blocks"))?;
+    }
+    for block in recent_blocks {
+        let slot = match block.slot {
+            Some(slot) => slot.to_string(),
+            None => "unknown slot".to_string(),
+        };
+        writeln!(
+            screen,
+            "  {} {} by {} - {} operations",
+            Style::Protocol.style(slot),
+            Style::Block.style(&block.id),
+            Style::Wallet.style(&block.creator),
+            block.operation_count
+        )?;
+    }
+    writeln!(screen)
+}
@@ -1,9 +1,9 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
-use crate::ask_password;
 use crate::cmds::Command;
 use crate::massa_fancy_ascii_art_logo;
 use crate::settings::SETTINGS;
+use crate::{ask_password, confirm_migration};
 use anyhow::Result;
 use console::style;
 use massa_sdk::Client;
@@ -135,7 +135,7 @@ pub(crate) async fn run(
                                     _ => ask_password(wallet_path),
                                 };
 
-                            let wallet = match Wallet::new(
+                            let mut wallet = match Wallet::new(
                                 wallet_path.to_path_buf(),
                                 password,
                                 client.chain_id,
@@ -146,6 +146,9 @@ pub(crate) async fn run(
                                     continue;
                                 }
                             };
+                            if let Err(e) = confirm_migration(&mut wallet) {
+                                println!("Could not migrate wallet: {}", e);
+                            }
                             wallet_opt = Some(wallet);
                         }
 
@@ -3,7 +3,8 @@
 //! Build here the default client settings from the configuration file toml
 use massa_models::config::build_massa_settings;
 use massa_time::MassaTime;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::{net::IpAddr, path::PathBuf};
 
 lazy_static::lazy_static! {
@@ -17,6 +18,8 @@ pub struct Settings {
     pub history_file_path: PathBuf,
     pub timeout: MassaTime,
     pub client: ClientSettings,
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -29,6 +32,33 @@ pub struct DefaultNode {
     pub chain_id: Option<u64>,
 }
 
+impl DefaultNode {
+    /// The default node with the fields set by `profile` overridden
+    pub fn with_profile(&self, profile: &Profile) -> DefaultNode {
+        DefaultNode {
+            ip: profile.ip.unwrap_or(self.ip),
+            private_port: profile.private_port.unwrap_or(self.private_port),
+            public_port: profile.public_port.unwrap_or(self.public_port),
+            grpc_public_port: profile.grpc_public_port.unwrap_or(self.grpc_public_port),
+            grpc_private_port: profile.grpc_private_port.unwrap_or(self.grpc_private_port),
+            chain_id: profile.chain_id.or(self.chain_id),
+        }
+    }
+}
+
+/// Named profile, selected with `--profile`: a wallet folder and the node it is used with.
+/// The node fields that are not set are the ones of the default node.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Profile {
+    pub wallet: PathBuf,
+    pub ip: Option<IpAddr>,
+    pub private_port: Option<u16>,
+    pub public_port: Option<u16>,
+    pub grpc_public_port: Option<u16>,
+    pub grpc_private_port: Option<u16>,
+    pub chain_id: Option<u64>,
+}
+
 /// Client settings
 /// the client settings.
 #[derive(Debug, Deserialize, Clone)]
@@ -56,3 +86,31 @@ pub struct HttpSettings {
 fn test_load_client_config() {
     let _ = *SETTINGS;
 }
+
+#[cfg(test)]
+#[test]
+fn test_profile_overrides_default_node() {
+    let profile: Profile = serde_json::from_str(
+        r#"{"wallet": "wallets/devnet/", "public_port": 34035, "chain_id": 77}"#,
+    )
+    .unwrap();
+    assert_eq!(profile.wallet, PathBuf::from("wallets/devnet/"));
+    assert_eq!(profile.ip, None);
+
+    let default_node = DefaultNode {
+        ip: "127.0.0.1".parse().unwrap(),
+        private_port: 33034,
+        public_port: 33035,
+        grpc_public_port: 33037,
+        grpc_private_port: 33038,
+        chain_id: None,
+    };
+    let node = default_node.with_profile(&profile);
+    // the fields set by the profile are overridden, the others are the ones of the default node
+    assert_eq!(node.public_port, 34035);
+    assert_eq!(node.chain_id, Some(77));
+    assert_eq!(node.ip, default_node.ip);
+    assert_eq!(node.private_port, 33034);
+    assert_eq!(node.grpc_public_port, 33037);
+    assert_eq!(node.grpc_private_port, 33038);
+}
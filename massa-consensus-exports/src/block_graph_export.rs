@@ -1,5 +1,6 @@
 use massa_models::{
     address::Address,
+    block::BlockGraphFormat,
     block_id::BlockId,
     clique::Clique,
     prehash::{PreHashMap, PreHashSet},
@@ -27,3 +28,264 @@ pub struct BlockGraphExport {
     /// List of maximal cliques of compatible blocks.
     pub max_cliques: Vec<Clique>,
 }
+
+/// Status of a block in a rendering of the block graph
+#[derive(Debug, Clone, Copy)]
+enum RenderedBlockStatus {
+    /// final
+    Final,
+    /// active in the blockclique
+    Blockclique,
+    /// active in alternative cliques only
+    Alternative,
+    /// discarded as incompatible with a final block
+    Stale,
+    /// discarded as invalid
+    Invalid,
+}
+
+impl RenderedBlockStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            RenderedBlockStatus::Final => "final",
+            RenderedBlockStatus::Blockclique => "blockclique",
+            RenderedBlockStatus::Alternative => "alternative",
+            RenderedBlockStatus::Stale => "stale",
+            RenderedBlockStatus::Invalid => "invalid",
+        }
+    }
+
+    /// fill color of the blocks of this status in the DOT rendering
+    fn dot_color(&self) -> &'static str {
+        match self {
+            RenderedBlockStatus::Final => "lightblue",
+            RenderedBlockStatus::Blockclique => "palegreen",
+            RenderedBlockStatus::Alternative => "khaki",
+            RenderedBlockStatus::Stale => "lightgrey",
+            RenderedBlockStatus::Invalid => "salmon",
+        }
+    }
+}
+
+/// Block of a rendering of the block graph
+struct RenderedBlock {
+    block_id: BlockId,
+    slot: Slot,
+    status: RenderedBlockStatus,
+    /// parents of the block that are part of the rendering
+    parents: Vec<BlockId>,
+    /// indices of the cliques containing the block
+    cliques: Vec<usize>,
+}
+
+impl RenderedBlock {
+    fn cliques_list(&self) -> String {
+        self.cliques
+            .iter()
+            .map(|clique_i| clique_i.to_string())
+            .collect::<Vec<String>>()
+            .join(",")
+    }
+}
+
+impl BlockGraphExport {
+    /// Render the exported part of the graph for visualization tools.
+    /// Each block is linked to its parents that are part of the export, and annotated with
+    /// its slot, its status (final, blockclique, alternative, stale or invalid),
+    /// and the indices of the cliques containing it.
+    pub fn render(&self, format: BlockGraphFormat) -> String {
+        let blocks = self.get_rendered_blocks();
+        match format {
+            BlockGraphFormat::Dot => render_dot(&blocks),
+            BlockGraphFormat::GraphMl => render_graphml(&blocks),
+        }
+    }
+
+    /// Get the active and discarded blocks of the export, by increasing slot
+    fn get_rendered_blocks(&self) -> Vec<RenderedBlock> {
+        let active_blocks = self.active_blocks.iter().map(|(block_id, block)| {
+            let cliques: Vec<usize> = self
+                .max_cliques
+                .iter()
+                .enumerate()
+                .filter(|(_clique_i, clique)| clique.block_ids.contains(block_id))
+                .map(|(clique_i, _clique)| clique_i)
+                .collect();
+            let status = if block.is_final {
+                RenderedBlockStatus::Final
+            } else if cliques
+                .iter()
+                .any(|clique_i| self.max_cliques[*clique_i].is_blockclique)
+            {
+                RenderedBlockStatus::Blockclique
+            } else {
+                RenderedBlockStatus::Alternative
+            };
+            RenderedBlock {
+                block_id: *block_id,
+                slot: block.header.content.slot,
+                status,
+                parents: block.header.content.parents.clone(),
+                cliques,
+            }
+        });
+        let discarded_blocks =
+            self.discarded_blocks
+                .iter()
+                .map(
+                    |(block_id, (reason, (slot, _creator, parents)))| RenderedBlock {
+                        block_id: *block_id,
+                        slot: *slot,
+                        status: match reason {
+                            DiscardReason::Invalid(_) => RenderedBlockStatus::Invalid,
+                            DiscardReason::Stale => RenderedBlockStatus::Stale,
+                            DiscardReason::Final => RenderedBlockStatus::Final,
+                        },
+                        parents: parents.clone(),
+                        cliques: Vec::new(),
+                    },
+                );
+        let mut blocks: Vec<RenderedBlock> = active_blocks.chain(discarded_blocks).collect();
+        blocks.sort_unstable_by_key(|block| (block.slot, block.block_id));
+
+        // only link the blocks to the parents that are part of the rendering
+        let block_ids: PreHashSet<BlockId> = blocks.iter().map(|block| block.block_id).collect();
+        for block in blocks.iter_mut() {
+            block.parents.retain(|parent| block_ids.contains(parent));
+        }
+        blocks
+    }
+}
+
+/// Render blocks in the Graphviz DOT language, each block pointing to its parents
+fn render_dot(blocks: &[RenderedBlock]) -> String {
+    let mut dot = String::from("digraph block_graph {\n");
+    dot.push_str("    rankdir=LR;\n");
+    dot.push_str("    node [shape=box, style=filled];\n");
+    for block in blocks {
+        dot.push_str(&format!(
+            "    \"{}\" [label=\"{}\\n({}, {})\", period={}, thread={}, status=\"{}\", cliques=\"{}\", fillcolor=\"{}\"];\n",
+            block.block_id,
+            block.block_id,
+            block.slot.period,
+            block.slot.thread,
+            block.slot.period,
+            block.slot.thread,
+            block.status.as_str(),
+            block.cliques_list(),
+            block.status.dot_color()
+        ));
+    }
+    for block in blocks {
+        for parent in block.parents.iter() {
+            dot.push_str(&format!("    \"{}\" -> \"{}\";\n", block.block_id, parent));
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+/// Render blocks in the GraphML format, each block having an edge to each of its parents
+fn render_graphml(blocks: &[RenderedBlock]) -> String {
+    let mut graphml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    graphml.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+    for (key, attr_type) in [
+        ("period", "long"),
+        ("thread", "int"),
+        ("status", "string"),
+        ("cliques", "string"),
+    ] {
+        graphml.push_str(&format!(
+            "  <key id=\"{}\" for=\"node\" attr.name=\"{}\" attr.type=\"{}\"/>\n",
+            key, key, attr_type
+        ));
+    }
+    graphml.push_str("  <graph id=\"block_graph\" edgedefault=\"directed\">\n");
+    for block in blocks {
+        graphml.push_str(&format!(
+            "    <node id=\"{}\"><data key=\"period\">{}</data><data key=\"thread\">{}</data><data key=\"status\">{}</data><data key=\"cliques\">{}</data></node>\n",
+            block.block_id,
+            block.slot.period,
+            block.slot.thread,
+            block.status.as_str(),
+            block.cliques_list()
+        ));
+    }
+    for block in blocks {
+        for parent in block.parents.iter() {
+            graphml.push_str(&format!(
+                "    <edge source=\"{}\" target=\"{}\"/>\n",
+                block.block_id, parent
+            ));
+        }
+    }
+    graphml.push_str("  </graph>\n");
+    graphml.push_str("</graphml>\n");
+    graphml
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_hash::Hash;
+    use massa_signature::KeyPair;
+
+    #[test]
+    fn test_render_discarded_blocks() {
+        let creator = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        let block_id =
+            |name: &str| BlockId::generate_from_hash(Hash::compute_from(name.as_bytes()));
+        let (pruned_parent, stale_block, invalid_block) =
+            (block_id("pruned"), block_id("stale"), block_id("invalid"));
+        let mut discarded_blocks = PreHashMap::default();
+        discarded_blocks.insert(
+            invalid_block,
+            (
+                DiscardReason::Invalid("wrong parents".to_string()),
+                (Slot::new(2, 0), creator, vec![stale_block]),
+            ),
+        );
+        discarded_blocks.insert(
+            stale_block,
+            (
+                DiscardReason::Stale,
+                (Slot::new(1, 0), creator, vec![pruned_parent]),
+            ),
+        );
+        let export = BlockGraphExport {
+            genesis_blocks: Vec::new(),
+            active_blocks: PreHashMap::default(),
+            discarded_blocks,
+            best_parents: Vec::new(),
+            latest_final_blocks_periods: Vec::new(),
+            gi_head: PreHashMap::default(),
+            max_cliques: Vec::new(),
+        };
+
+        let dot = export.render(BlockGraphFormat::Dot);
+        assert!(dot.starts_with("digraph block_graph {\n"));
+        assert!(dot.contains(&format!(
+            "\"{}\" [label=\"{}\\n(1, 0)\", period=1, thread=0, status=\"stale\", cliques=\"\", fillcolor=\"lightgrey\"];",
+            stale_block, stale_block
+        )));
+        assert!(dot.contains(&format!("\"{}\" -> \"{}\";", invalid_block, stale_block)));
+        // the parents that are not part of the export are not linked
+        assert!(!dot.contains(&pruned_parent.to_string()));
+
+        let graphml = export.render(BlockGraphFormat::GraphMl);
+        // the blocks are rendered by increasing slot
+        let stale_node = graphml
+            .find(&format!("<node id=\"{}\">", stale_block))
+            .unwrap();
+        let invalid_node = graphml
+            .find(&format!("<node id=\"{}\">", invalid_block))
+            .unwrap();
+        assert!(stale_node < invalid_node);
+        assert!(graphml.contains("<data key=\"status\">invalid</data>"));
+        assert!(graphml.contains(&format!(
+            "<edge source=\"{}\" target=\"{}\"/>",
+            invalid_block, stale_block
+        )));
+        assert!(graphml.ends_with("  </graph>\n</graphml>\n"));
+    }
+}
@@ -1,7 +1,7 @@
 use massa_models::{
     active_block::ActiveBlock,
     address::Address,
-    block::{Block, SecureShareBlock},
+    block::{Block, DiscardedBlockReason, SecureShareBlock},
     block_header::SecuredHeader,
     block_id::BlockId,
     prehash::PreHashSet,
@@ -42,6 +42,16 @@ pub enum DiscardReason {
     Final,
 }
 
+impl From<DiscardReason> for DiscardedBlockReason {
+    fn from(reason: DiscardReason) -> Self {
+        match reason {
+            DiscardReason::Invalid(reason) => DiscardedBlockReason::Invalid(reason),
+            DiscardReason::Stale => DiscardedBlockReason::Stale,
+            DiscardReason::Final => DiscardedBlockReason::Final,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum BlockStatusId {
     Incoming = 0,
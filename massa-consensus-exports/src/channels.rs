@@ -7,8 +7,9 @@ use massa_models::secure_share::SecureShare;
 use massa_pool_exports::PoolController;
 use massa_pos_exports::SelectorController;
 use massa_protocol_exports::ProtocolController;
+use massa_versioning::versioning::MipStore;
 
-use crate::events::ConsensusEvent;
+use crate::events::{BlockcliqueReorg, ConsensusEvent};
 
 /// Contains links to other modules of the node to be able to interact with them.
 #[derive(Clone)]
@@ -25,6 +26,8 @@ pub struct ConsensusChannels {
     pub controller_event_tx: MassaSender<ConsensusEvent>,
     /// Structure used by consensus to broadcast all the information about the blocks
     pub broadcasts: ConsensusBroadcasts,
+    /// MIP store, giving the types of operations that are active at the slot of a block
+    pub mip_store: MipStore,
 }
 
 /// Structure used to broadcast all the information about the blocks
@@ -36,4 +39,6 @@ pub struct ConsensusBroadcasts {
     pub block_header_sender: tokio::sync::broadcast::Sender<SecureShare<BlockHeader, BlockId>>,
     /// Channel use by Websocket (if they are enable) to broadcast a new block integrated
     pub filled_block_sender: tokio::sync::broadcast::Sender<FilledBlock>,
+    /// Channel used to broadcast the blockclique changes discarding blocks
+    pub reorg_sender: tokio::sync::broadcast::Sender<BlockcliqueReorg>,
 }
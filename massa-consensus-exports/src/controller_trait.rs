@@ -3,8 +3,13 @@ use crate::{bootstrapable_graph::BootstrapableGraph, error::ConsensusError};
 use massa_models::prehash::PreHashSet;
 use massa_models::streaming_step::StreamingStep;
 use massa_models::{
-    block::BlockGraphStatus, block_header::BlockHeader, block_id::BlockId, clique::Clique,
-    secure_share::SecureShare, slot::Slot, stats::ConsensusStats,
+    block::{BlockFinalityProof, BlockGraphFormat, BlockGraphStatus, DiscardedBlock},
+    block_header::BlockHeader,
+    block_id::BlockId,
+    clique::{Clique, CliqueDetails},
+    secure_share::SecureShare,
+    slot::Slot,
+    stats::{ConsensusStats, CycleStaleBlockStats},
 };
 use massa_storage::Storage;
 
@@ -37,12 +42,51 @@ pub trait ConsensusController: Send + Sync {
     /// The statuses of the blocks sorted by the order of the input list
     fn get_block_statuses(&self, ids: &[BlockId]) -> Vec<BlockGraphStatus>;
 
+    /// Get the discarded blocks retained by the graph, for debugging
+    ///
+    /// # Returns
+    /// The discarded blocks with the reason of their discard, by increasing slot
+    fn get_discarded_blocks(&self) -> Vec<DiscardedBlock>;
+
     /// Get all the cliques of the graph
     ///
     /// # Returns
     /// The list of cliques
     fn get_cliques(&self) -> Vec<Clique>;
 
+    /// Get the details of all the cliques of the graph, to diagnose the choice of the blockclique
+    ///
+    /// # Returns
+    /// The list of cliques with their blocks, fitness, and why they are the blockclique or not
+    fn get_cliques_details(&self) -> Result<Vec<CliqueDetails>, ConsensusError>;
+
+    /// Render a part of the graph for visualization tools, with the parents, the finality
+    /// status and the cliques of each block
+    ///
+    /// # Arguments
+    /// * `start_slot`: the slot to start the export from, if None, the export starts from the genesis
+    /// * `end_slot`: the slot to end the export at, if None, the export ends at the current slot
+    /// * `format`: the format of the rendering
+    ///
+    /// # Returns
+    /// The rendering of the graph
+    fn export_block_graph(
+        &self,
+        start_slot: Option<Slot>,
+        end_slot: Option<Slot>,
+        format: BlockGraphFormat,
+    ) -> Result<String, ConsensusError>;
+
+    /// Get the data proving to an external verifier that a block is final:
+    /// its header, its endorsements and the headers of its descendants
+    ///
+    /// # Arguments
+    /// * `block_id`: the id of the final block
+    ///
+    /// # Returns
+    /// The finality proof, or an error if the block is not final or too old
+    fn get_finality_proof(&self, block_id: BlockId) -> Result<BlockFinalityProof, ConsensusError>;
+
     /// Get a part of the graph to send to a node for it to setup its graph.
     /// Used for bootstrap.
     ///
@@ -74,6 +118,12 @@ pub trait ConsensusController: Send + Sync {
     /// The stats of the consensus
     fn get_stats(&self) -> Result<ConsensusStats, ConsensusError>;
 
+    /// Get the stale blocks of the stats history, with their creators and slots
+    ///
+    /// # Returns
+    /// The stale blocks grouped by cycle, by increasing cycle
+    fn get_stale_block_stats(&self) -> Vec<CycleStaleBlockStats>;
+
     /// Get the best parents for the next block to be produced
     ///
     /// # Returns
@@ -89,6 +139,16 @@ pub trait ConsensusController: Send + Sync {
     /// The block id of the block at the specified slot if exists
     fn get_blockclique_block_at_slot(&self, slot: Slot) -> Option<BlockId>;
 
+    /// Get the ids of the blocks in the blockclique (or final) at each slot of a slot range
+    ///
+    /// # Arguments
+    /// * `start`: first slot of the range (included)
+    /// * `end`: last slot of the range (excluded)
+    ///
+    /// # Returns
+    /// The slots of the range having a block, with the id of their block, by increasing slot
+    fn get_blockclique_blocks_in_slot_range(&self, start: Slot, end: Slot) -> Vec<(Slot, BlockId)>;
+
     /// Get the latest block, that is in the blockclique, in the thread of the given slot and before this `slot`.
     ///
     /// # Arguments:
@@ -21,6 +21,8 @@ pub enum ConsensusError {
     GenesisCreationError(String),
     /// missing block {0}
     MissingBlock(String),
+    /// block not final {0}
+    BlockNotFinal(String),
     /// missing operation {0}
     MissingOperation(String),
     /// there was an inconsistency between containers {0}
@@ -1,3 +1,5 @@
+use massa_models::block_id::BlockId;
+
 /// Events that are emitted by consensus.
 #[derive(Debug, Clone)]
 pub enum ConsensusEvent {
@@ -6,3 +8,16 @@ pub enum ConsensusEvent {
     /// Network is ended should be send after `end_timestamp`
     Stop,
 }
+
+/// Change of the blockclique discarding some of the blocks of the previous one
+#[derive(Debug, Clone)]
+pub struct BlockcliqueReorg {
+    /// latest block of the previous blockclique
+    pub old_tip: BlockId,
+    /// latest block of the new blockclique
+    pub new_tip: BlockId,
+    /// number of blocks discarded from the blockclique
+    pub depth: u64,
+    /// ids of the blocks discarded from the blockclique, by increasing slot
+    pub discarded_block_ids: Vec<BlockId>,
+}
@@ -14,6 +14,11 @@ pub struct ConsensusConfig {
     pub genesis_key: KeyPair,
     /// Maximum number of blocks allowed in discarded blocks.
     pub max_discarded_blocks: usize,
+    /// Number of periods before the latest final block of their thread during which
+    /// the discarded blocks are retained (0 for no limit).
+    pub discarded_blocks_retention_periods: u64,
+    /// Maximum estimated size in bytes of the retained discarded blocks (0 for no limit).
+    pub max_discarded_blocks_size: u64,
     /// Maximum number of blocks allowed in `FutureIncomingBlocks`.
     pub max_future_processing_blocks: usize,
     /// Maximum number of blocks allowed in `DependencyWaitingBlocks`.
@@ -50,6 +55,8 @@ pub struct ConsensusConfig {
     pub broadcast_blocks_channel_capacity: usize,
     /// filled blocks channel capacity
     pub broadcast_filled_blocks_channel_capacity: usize,
+    /// blockclique reorganizations channel capacity
+    pub broadcast_reorgs_channel_capacity: usize,
     /// last start period
     pub last_start_period: u64,
     /// chain id
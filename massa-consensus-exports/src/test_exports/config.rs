@@ -17,6 +17,8 @@ impl Default for ConsensusConfig {
             thread_count: THREAD_COUNT,
             genesis_key: GENESIS_KEY.clone(),
             max_discarded_blocks: 10000,
+            discarded_blocks_retention_periods: 0,
+            max_discarded_blocks_size: 0,
             max_future_processing_blocks: 100,
             max_dependency_blocks: 2048,
             block_db_prune_interval: MassaTime::from_millis(5000),
@@ -35,6 +37,7 @@ impl Default for ConsensusConfig {
             broadcast_blocks_headers_channel_capacity: 128,
             broadcast_blocks_channel_capacity: 128,
             broadcast_filled_blocks_channel_capacity: 128,
+            broadcast_reorgs_channel_capacity: 128,
             last_start_period: 0,
             chain_id: *CHAINID,
         }
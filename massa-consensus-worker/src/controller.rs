@@ -6,15 +6,15 @@ use massa_consensus_exports::{
     export_active_block::ExportActiveBlock, ConsensusController,
 };
 use massa_models::{
-    block::{BlockGraphStatus, FilledBlock},
+    block::{BlockFinalityProof, BlockGraphFormat, BlockGraphStatus, DiscardedBlock, FilledBlock},
     block_header::BlockHeader,
     block_id::BlockId,
-    clique::Clique,
+    clique::{Clique, CliqueDetails},
     operation::{Operation, OperationId},
     prehash::PreHashSet,
     secure_share::SecureShare,
     slot::Slot,
-    stats::ConsensusStats,
+    stats::{ConsensusStats, CycleStaleBlockStats},
     streaming_step::StreamingStep,
 };
 use massa_storage::Storage;
@@ -91,6 +91,14 @@ impl ConsensusController for ConsensusControllerImpl {
             .collect()
     }
 
+    /// Get the discarded blocks retained by the graph
+    ///
+    /// # Returns:
+    /// The discarded blocks with the reason of their discard, by increasing slot
+    fn get_discarded_blocks(&self) -> Vec<DiscardedBlock> {
+        self.shared_state.read().get_discarded_blocks()
+    }
+
     /// Get all the cliques possible in the block graph.
     ///
     /// # Returns:
@@ -99,6 +107,51 @@ impl ConsensusController for ConsensusControllerImpl {
         self.shared_state.read().max_cliques.clone()
     }
 
+    /// Get the details of all the cliques of the block graph,
+    /// with the reason why each of them is the blockclique or not.
+    ///
+    /// # Returns:
+    /// A vector of clique details
+    fn get_cliques_details(&self) -> Result<Vec<CliqueDetails>, ConsensusError> {
+        self.shared_state.read().get_cliques_details()
+    }
+
+    /// Render a part of the block graph in DOT or GraphML, for visualization tools.
+    /// The rendering is done here so that the callers get the whole graph in a single call.
+    ///
+    /// # Arguments:
+    /// * `start_slot`: the start slot of the export (included)
+    /// * `end_slot`: the end slot of the export (excluded)
+    /// * `format`: the format of the rendering
+    ///
+    /// # Returns:
+    /// The rendering of the block graph in this period
+    fn export_block_graph(
+        &self,
+        start_slot: Option<Slot>,
+        end_slot: Option<Slot>,
+        format: BlockGraphFormat,
+    ) -> Result<String, ConsensusError> {
+        let export = self
+            .shared_state
+            .read()
+            .extract_block_graph_part(start_slot, end_slot)?;
+        Ok(export.render(format))
+    }
+
+    /// Get the data proving to an external verifier that a block is final.
+    /// The descendants of a final block are kept in the graph
+    /// for `force_keep_final_periods` periods.
+    ///
+    /// # Arguments:
+    /// * `block_id`: the id of the final block
+    ///
+    /// # Returns:
+    /// The header and endorsements of the block, and the headers of its descendants
+    fn get_finality_proof(&self, block_id: BlockId) -> Result<BlockFinalityProof, ConsensusError> {
+        self.shared_state.read().get_finality_proof(&block_id)
+    }
+
     /// Get a part of the graph to send to a node so that he can setup his graph.
     /// Used for bootstrap.
     ///
@@ -196,6 +249,11 @@ impl ConsensusController for ConsensusControllerImpl {
         self.shared_state.read().get_stats()
     }
 
+    /// Get the stale blocks of the stats history, grouped by cycle
+    fn get_stale_block_stats(&self) -> Vec<CycleStaleBlockStats> {
+        self.shared_state.read().get_stale_block_stats()
+    }
+
     /// Get the current best parents for a block creation
     ///
     /// # Returns:
@@ -217,6 +275,20 @@ impl ConsensusController for ConsensusControllerImpl {
             .get_blockclique_block_at_slot(&slot)
     }
 
+    /// Get the blocks, that are in the blockclique, at each slot of a slot range.
+    ///
+    /// # Arguments:
+    /// * `start`: first slot of the range (included)
+    /// * `end`: last slot of the range (excluded)
+    ///
+    /// # Returns:
+    /// The slots of the range having a block, with the id of their block
+    fn get_blockclique_blocks_in_slot_range(&self, start: Slot, end: Slot) -> Vec<(Slot, BlockId)> {
+        self.shared_state
+            .read()
+            .get_blockclique_blocks_in_slot_range(start, end)
+    }
+
     /// Get the latest block, that is in the blockclique, in the thread of the given slot and before this `slot`.
     ///
     /// # Arguments:
@@ -6,8 +6,10 @@ use massa_consensus_exports::{
 };
 use massa_logging::massa_trace;
 use massa_models::{
+    block::BlockFinalityProof,
+    block_header::SecuredHeader,
     block_id::{BlockId, BlockIdSerializer},
-    clique::Clique,
+    clique::{BlockcliqueChoice, Clique, CliqueBlock, CliqueDetails},
     prehash::PreHashSet,
     slot::Slot,
 };
@@ -280,6 +282,108 @@ impl ConsensusState {
             .clone()
     }
 
+    /// Build the proof that a block is final from its descendants in the graph.
+    /// The descendants of the block are only kept while it has its operations in the graph.
+    pub fn get_finality_proof(
+        &self,
+        block_id: &BlockId,
+    ) -> Result<BlockFinalityProof, ConsensusError> {
+        let (active_block, storage_or_block) =
+            self.get_full_active_block(block_id).ok_or_else(|| {
+                ConsensusError::MissingBlock(format!("{} is not active in the graph", block_id))
+            })?;
+        if !active_block.is_final {
+            return Err(ConsensusError::BlockNotFinal(block_id.to_string()));
+        }
+        if self.active_index_without_ops.contains(block_id) {
+            return Err(ConsensusError::MissingBlock(format!(
+                "the descendants of {} are no longer kept in the graph",
+                block_id
+            )));
+        }
+
+        // stale descendants are not removed from the descendants of their ancestors
+        let blockclique = self.get_blockclique();
+        let mut descendants: Vec<SecuredHeader> = active_block
+            .descendants
+            .iter()
+            .filter_map(|descendant_id| {
+                let (descendant, descendant_storage) = self.get_full_active_block(descendant_id)?;
+                if !descendant.is_final && !blockclique.contains(descendant_id) {
+                    return None;
+                }
+                Some(descendant_storage.clone_block(descendant_id).content.header)
+            })
+            .collect();
+        descendants.sort_unstable_by_key(|header| (header.content.slot, header.id));
+        let endorsements = descendants
+            .iter()
+            .flat_map(|header| header.content.endorsements.iter())
+            .filter(|endorsement| endorsement.content.endorsed_block == *block_id)
+            .cloned()
+            .collect();
+        Ok(BlockFinalityProof {
+            header: storage_or_block.clone_block(block_id).content.header,
+            endorsements,
+            descendants_fitness: descendants.iter().map(|header| header.get_fitness()).sum(),
+            descendants,
+            fitness_threshold: self.config.delta_f0,
+        })
+    }
+
+    /// get the details of the cliques, with the reason why each of them is the blockclique or not
+    pub fn get_cliques_details(&self) -> Result<Vec<CliqueDetails>, ConsensusError> {
+        let blockclique = self
+            .max_cliques
+            .iter()
+            .find(|c| c.is_blockclique)
+            .ok_or_else(|| {
+                ConsensusError::ContainerInconsistency("blockclique missing".to_string())
+            })?;
+        let highest_fitness_count = self
+            .max_cliques
+            .iter()
+            .filter(|c| c.fitness == blockclique.fitness)
+            .count();
+        self.max_cliques
+            .iter()
+            .map(|clique| {
+                let mut blocks = clique
+                    .block_ids
+                    .iter()
+                    .map(|block_id| match self.blocks_state.get(block_id) {
+                        Some(BlockStatus::Active { a_block, .. }) => Ok(CliqueBlock {
+                            block_id: *block_id,
+                            slot: a_block.slot,
+                            fitness: a_block.fitness,
+                        }),
+                        _ => Err(ConsensusError::ContainerInconsistency(format!(
+                            "clique block {} is missing",
+                            block_id
+                        ))),
+                    })
+                    .collect::<Result<Vec<CliqueBlock>, ConsensusError>>()?;
+                blocks.sort_unstable_by_key(|block| block.slot);
+                // on equal fitness, the clique with the lowest sum of block ids is the blockclique
+                let choice = match (clique.is_blockclique, highest_fitness_count > 1) {
+                    (true, false) => BlockcliqueChoice::HighestFitness,
+                    (true, true) => BlockcliqueChoice::WonTieBreak,
+                    (false, _) if clique.fitness < blockclique.fitness => {
+                        BlockcliqueChoice::LowerFitness
+                    }
+                    (false, _) => BlockcliqueChoice::LostTieBreak,
+                };
+                Ok(CliqueDetails {
+                    blocks,
+                    fitness: clique.fitness,
+                    fitness_gap: blockclique.fitness.saturating_sub(clique.fitness),
+                    is_blockclique: clique.is_blockclique,
+                    choice,
+                })
+            })
+            .collect()
+    }
+
     pub fn mark_final_blocks(
         &mut self,
         add_block_id: &BlockId,
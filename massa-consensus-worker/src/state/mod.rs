@@ -14,7 +14,7 @@ use massa_metrics::MassaMetrics;
 use massa_models::{
     active_block::ActiveBlock,
     address::Address,
-    block::BlockGraphStatus,
+    block::{BlockGraphStatus, DiscardedBlock},
     block_header::SecuredHeader,
     block_id::BlockId,
     clique::Clique,
@@ -76,8 +76,8 @@ pub struct ConsensusState {
     pub final_block_stats: VecDeque<(MassaTime, Address, bool)>,
     /// Blocks that come from protocol used for stats and ids are removed when inserted in `final_block_stats`
     pub protocol_blocks: VecDeque<(MassaTime, BlockId)>,
-    /// Stale block timestamp
-    pub stale_block_stats: VecDeque<MassaTime>,
+    /// Stale block stats `(time, block id, creator, slot)`
+    pub stale_block_stats: VecDeque<(MassaTime, BlockId, Address, Slot)>,
     /// the time span considered for stats
     pub stats_history_timespan: MassaTime,
     /// the time span considered for desynchronization detection
@@ -161,6 +161,26 @@ impl ConsensusState {
             })
     }
 
+    /// get the blockclique (or final) block IDs at each slot of a slot range (end excluded)
+    pub fn get_blockclique_blocks_in_slot_range(
+        &self,
+        start: Slot,
+        end: Slot,
+    ) -> Vec<(Slot, BlockId)> {
+        let mut blocks = Vec::new();
+        let mut slot = start;
+        while slot < end {
+            if let Some(block_id) = self.get_blockclique_block_at_slot(&slot) {
+                blocks.push((slot, block_id));
+            }
+            slot = match slot.get_next_slot(self.config.thread_count) {
+                Ok(next_slot) => next_slot,
+                Err(_) => break,
+            };
+        }
+        blocks
+    }
+
     /// get the latest blockclique (or final) block ID at a given slot, if any
     pub fn get_latest_blockclique_block_at_slot(&self, slot: &Slot) -> BlockId {
         let (mut best_block_id, mut best_block_period) = self
@@ -220,6 +240,33 @@ impl ConsensusState {
         }
     }
 
+    /// Get the discarded blocks retained by the graph, by increasing slot
+    pub fn get_discarded_blocks(&self) -> Vec<DiscardedBlock> {
+        let mut discarded_blocks: Vec<DiscardedBlock> = self
+            .blocks_state
+            .discarded_blocks()
+            .iter()
+            .filter_map(|block_id| match self.blocks_state.get(block_id) {
+                Some(BlockStatus::Discarded {
+                    slot,
+                    creator,
+                    parents,
+                    reason,
+                    ..
+                }) => Some(DiscardedBlock {
+                    block_id: *block_id,
+                    slot: *slot,
+                    creator: *creator,
+                    parents: parents.clone(),
+                    reason: reason.clone().into(),
+                }),
+                _ => None,
+            })
+            .collect();
+        discarded_blocks.sort_unstable_by_key(|block| (block.slot, block.block_id));
+        discarded_blocks
+    }
+
     /// list the latest final blocks at the given slot
     ///
     /// exclusively used by `list_required_active_blocks`
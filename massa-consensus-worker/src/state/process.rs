@@ -6,6 +6,7 @@ use std::{
 use massa_consensus_exports::{
     block_status::{BlockStatus, DiscardReason, HeaderOrBlock, StorageOrBlock},
     error::ConsensusError,
+    events::BlockcliqueReorg,
 };
 use massa_execution_exports::ExecutionBlockMetadata;
 use massa_logging::massa_trace;
@@ -22,7 +23,7 @@ use massa_models::{
 use massa_signature::PublicKey;
 use massa_storage::Storage;
 use massa_time::MassaTime;
-use tracing::{debug, info};
+use tracing::{debug, info, trace};
 
 use crate::state::{
     clique_computation::compute_max_cliques,
@@ -162,11 +163,20 @@ impl ConsensusState {
                             .get(&block_id)
                             .cloned()
                             .expect("incoming block not found in storage");
-                        let res = self.check_header(
+                        let mut res = self.check_header(
                             &block_id,
                             &stored_block.content.header,
                             current_slot,
                         );
+                        if let HeaderCheckOutcome::Proceed { .. } = res {
+                            if let Some(reason) = self.check_operations_activation(
+                                stored_block.content.header.content.slot,
+                                &stored_block.content.operations,
+                                storage,
+                            ) {
+                                res = HeaderCheckOutcome::Discard(reason);
+                            }
+                        }
                         match &res {
                             HeaderCheckOutcome::Discard(reason) => {
                                 self.maybe_note_attack_attempt(reason, &block_id)
@@ -622,6 +632,13 @@ impl ConsensusState {
             })
             .collect();
 
+        // Latest block of the previous blockclique, to notify the reorganizations.
+        let old_tip = if self.config.broadcast_enabled {
+            get_blockclique_tip(&self.prev_blockclique)
+        } else {
+            None
+        };
+
         // Get new blockclique block list with slots.
         let mut blockclique_changed = false;
         let new_blockclique: PreHashMap<BlockId, Slot> = self
@@ -655,6 +672,9 @@ impl ConsensusState {
             // In that case, we mark the blockclique as having changed.
             blockclique_changed = true;
         }
+        if let Some(old_tip) = old_tip {
+            self.notify_reorg(old_tip, &new_blockclique, &finalized_blocks);
+        }
         // Overwrite previous blockclique.
         // Should still be done even if unchanged because elements were removed from it above.
         self.prev_blockclique = new_blockclique.clone();
@@ -678,6 +698,60 @@ impl ConsensusState {
             );
     }
 
+    /// Broadcast the blocks of the previous blockclique discarded from the new one, if any.
+    /// Must be called before overwriting `prev_blockclique`, which must only contain
+    /// the blocks of the previous blockclique that are not in the new one.
+    ///
+    /// # Arguments:
+    /// * `old_tip`: latest block of the previous blockclique
+    /// * `new_blockclique`: blocks of the new blockclique with their slots
+    /// * `finalized_blocks`: newly final blocks, that left the blockclique without being discarded
+    fn notify_reorg(
+        &self,
+        old_tip: BlockId,
+        new_blockclique: &PreHashMap<BlockId, Slot>,
+        finalized_blocks: &HashMap<Slot, BlockId>,
+    ) {
+        let mut discarded_blocks: Vec<(Slot, BlockId)> = self
+            .prev_blockclique
+            .iter()
+            .filter(|(b_id, slot)| finalized_blocks.get(*slot) != Some(*b_id))
+            .map(|(b_id, slot)| (*slot, *b_id))
+            .collect();
+        if discarded_blocks.is_empty() {
+            return;
+        }
+        discarded_blocks.sort_unstable();
+
+        // If the new blockclique is empty, its tip is the latest final block.
+        let new_tip = get_blockclique_tip(new_blockclique).unwrap_or_else(|| {
+            self.latest_final_blocks_periods
+                .iter()
+                .enumerate()
+                .max_by_key(|(thread, (_b_id, period))| (*period, *thread))
+                .map(|(_thread, (b_id, _period))| *b_id)
+                .expect("no latest final block")
+        });
+        debug!(
+            "blockclique reorganization from {} to {} discarding {} blocks",
+            old_tip,
+            new_tip,
+            discarded_blocks.len()
+        );
+        let reorg = BlockcliqueReorg {
+            old_tip,
+            new_tip,
+            depth: discarded_blocks.len() as u64,
+            discarded_block_ids: discarded_blocks.into_iter().map(|(_, b_id)| b_id).collect(),
+        };
+        if let Err(err) = self.channels.broadcasts.reorg_sender.send(reorg) {
+            trace!(
+                "error, failed to broadcast blockclique reorganization due to: {}",
+                err
+            );
+        }
+    }
+
     /// call me if the block database changed
     /// Processing of final blocks, pruning.
     ///
@@ -743,8 +817,11 @@ impl ConsensusState {
             // add stale blocks to stats
             let new_stale_block_ids_creators_slots = mem::take(&mut self.new_stale_blocks);
             let timestamp = MassaTime::now();
-            for (_b_id, (_b_creator, _b_slot)) in new_stale_block_ids_creators_slots.into_iter() {
-                self.stale_block_stats.push_back(timestamp);
+            self.massa_metrics
+                .inc_stale_blocks(new_stale_block_ids_creators_slots.len() as u64);
+            for (b_id, (b_creator, b_slot)) in new_stale_block_ids_creators_slots.into_iter() {
+                self.stale_block_stats
+                    .push_back((timestamp, b_id, b_creator, b_slot));
             }
             final_block_slots
         };
@@ -802,3 +879,11 @@ impl ConsensusState {
         Ok(())
     }
 }
+
+/// Latest block of a blockclique, or None if it is empty
+fn get_blockclique_tip(blockclique: &PreHashMap<BlockId, Slot>) -> Option<BlockId> {
+    blockclique
+        .iter()
+        .max_by_key(|(_b_id, slot)| **slot)
+        .map(|(b_id, _slot)| *b_id)
+}
@@ -132,31 +132,52 @@ impl ConsensusState {
         });
     }
 
-    // Keep only a certain (`config.max_discarded_blocks`) number of blocks that are discarded
-    // to avoid high memory consumption
+    // Keep only the discarded blocks allowed by the retention policy to avoid high memory
+    // consumption: the most recently discarded ones, up to `config.max_discarded_blocks` blocks
+    // and `config.max_discarded_blocks_size` bytes, that are at most
+    // `config.discarded_blocks_retention_periods` periods older than the latest final block
+    // of their thread
     fn prune_discarded(&mut self) -> Result<(), ConsensusError> {
-        if self.blocks_state.discarded_blocks().len() <= self.config.max_discarded_blocks {
-            return Ok(());
-        }
-        let mut discard_hashes: Vec<(u64, BlockId)> = self
-            .blocks_state
-            .discarded_blocks()
-            .iter()
-            .filter_map(|block_id| {
-                if let Some(BlockStatus::Discarded {
-                    sequence_number, ..
-                }) = self.blocks_state.get(block_id)
+        let retention_periods = self.config.discarded_blocks_retention_periods;
+        let max_size = self.config.max_discarded_blocks_size;
+        let mut to_remove: Vec<BlockId> = Vec::new();
+        // `(sequence number, block id, size)` of the blocks within the retention periods
+        let mut retained: Vec<(u64, BlockId, u64)> = Vec::new();
+        for block_id in self.blocks_state.discarded_blocks().iter() {
+            if let Some(BlockStatus::Discarded {
+                slot,
+                parents,
+                reason,
+                sequence_number,
+                ..
+            }) = self.blocks_state.get(block_id)
+            {
+                let (_, latest_final_period) =
+                    self.latest_final_blocks_periods[slot.thread as usize];
+                if retention_periods > 0
+                    && slot.period.saturating_add(retention_periods) < latest_final_period
                 {
-                    return Some((*sequence_number, *block_id));
+                    to_remove.push(*block_id);
+                } else {
+                    let size = discarded_block_size(parents, reason);
+                    retained.push((*sequence_number, *block_id, size));
                 }
-                None
-            })
-            .collect();
-        discard_hashes.sort_unstable();
-        discard_hashes.truncate(
-            self.blocks_state.discarded_blocks().len() - self.config.max_discarded_blocks,
-        );
-        for (_, block_id) in discard_hashes.iter() {
+            }
+        }
+
+        // drop the oldest blocks beyond the count and size limits
+        retained.sort_unstable();
+        let mut retained_size: u64 = 0;
+        for (retained_count, (_, block_id, size)) in retained.into_iter().rev().enumerate() {
+            retained_size = retained_size.saturating_add(size);
+            if retained_count >= self.config.max_discarded_blocks
+                || (max_size > 0 && retained_size > max_size)
+            {
+                to_remove.push(block_id);
+            }
+        }
+
+        for block_id in to_remove.iter() {
             self.blocks_state.transition_map(block_id, |_, _| None);
         }
         Ok(())
@@ -360,3 +381,17 @@ impl ConsensusState {
         Ok(())
     }
 }
+
+/// Estimated memory footprint in bytes of a discarded block in the graph
+fn discarded_block_size(parents: &[BlockId], reason: &DiscardReason) -> u64 {
+    let reason_size = match reason {
+        DiscardReason::Invalid(reason) => reason.len(),
+        DiscardReason::Stale | DiscardReason::Final => 0,
+    };
+    // the id of the block is stored both as a key of the statuses and in the discarded index
+    let size = std::mem::size_of::<BlockStatus>()
+        + 2 * std::mem::size_of::<BlockId>()
+        + parents.len() * std::mem::size_of::<BlockId>()
+        + reason_size;
+    size as u64
+}
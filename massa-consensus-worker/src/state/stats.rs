@@ -1,8 +1,11 @@
 use super::ConsensusState;
 use massa_consensus_exports::error::ConsensusError;
-use massa_models::stats::ConsensusStats;
+use massa_models::address::Address;
+use massa_models::prehash::PreHashMap;
+use massa_models::stats::{ConsensusStats, CycleStaleBlockStats, StaleBlock, StaleBlockCreator};
 use massa_time::MassaTime;
-use std::cmp::max;
+use std::cmp::{max, Reverse};
+use std::collections::BTreeMap;
 
 #[cfg(not(feature = "sandbox"))]
 use tracing::warn;
@@ -26,7 +29,7 @@ impl ConsensusState {
         let stale_block_count = self
             .stale_block_stats
             .iter()
-            .filter(|t| **t >= timespan_start && **t < timespan_end)
+            .filter(|(t, _, _, _)| *t >= timespan_start && *t < timespan_end)
             .count() as u64;
         let clique_count = self.get_clique_count() as u64;
         Ok(ConsensusStats {
@@ -38,6 +41,47 @@ impl ConsensusState {
         })
     }
 
+    /// Get the stale blocks of the stats history, grouped by cycle
+    pub fn get_stale_block_stats(&self) -> Vec<CycleStaleBlockStats> {
+        let mut cycles: BTreeMap<u64, Vec<StaleBlock>> = BTreeMap::new();
+        for (_time, block_id, creator, slot) in self.stale_block_stats.iter() {
+            cycles
+                .entry(slot.get_cycle(self.config.periods_per_cycle))
+                .or_default()
+                .push(StaleBlock {
+                    block_id: *block_id,
+                    slot: *slot,
+                    creator: *creator,
+                });
+        }
+        cycles
+            .into_iter()
+            .map(|(cycle, mut blocks)| {
+                blocks.sort_unstable_by_key(|block| (block.slot, block.block_id));
+                let mut creator_counts: PreHashMap<Address, u64> = PreHashMap::default();
+                for block in blocks.iter() {
+                    *creator_counts.entry(block.creator).or_default() += 1;
+                }
+                let mut creators: Vec<StaleBlockCreator> = creator_counts
+                    .into_iter()
+                    .map(|(address, stale_block_count)| StaleBlockCreator {
+                        address,
+                        stale_block_count,
+                    })
+                    .collect();
+                creators.sort_unstable_by_key(|creator| {
+                    (Reverse(creator.stale_block_count), creator.address)
+                });
+                CycleStaleBlockStats {
+                    cycle,
+                    stale_block_count: blocks.len() as u64,
+                    creators,
+                    blocks,
+                }
+            })
+            .collect()
+    }
+
     /// Must be called each tick to update stats. Will detect if a desynchronization happened
     pub fn stats_tick(&mut self) -> Result<(), ConsensusError> {
         #[cfg(not(feature = "sandbox"))]
@@ -91,7 +135,7 @@ impl ConsensusState {
                 break;
             }
         }
-        while let Some(t) = self.stale_block_stats.front() {
+        while let Some((t, _, _, _)) = self.stale_block_stats.front() {
             if t < &start_time {
                 self.stale_block_stats.pop_front();
             } else {
@@ -2,8 +2,11 @@ use super::{process::BlockInfos, ConsensusState};
 use massa_consensus_exports::block_status::{BlockStatus, DiscardReason, HeaderOrBlock};
 use massa_logging::massa_trace;
 use massa_models::{
-    block_header::SecuredHeader, block_id::BlockId, prehash::PreHashSet, slot::Slot,
+    block_header::SecuredHeader, block_id::BlockId, operation::OperationId, prehash::PreHashSet,
+    slot::Slot, timeslots::get_block_slot_timestamp,
 };
+use massa_storage::Storage;
+use massa_versioning::versioning::MipComponent;
 use tracing::warn;
 
 /// Possible output of a header check
@@ -499,4 +502,45 @@ impl ConsensusState {
 
         EndorsementsCheckOutcome::Proceed
     }
+
+    /// check that the types of the operations of a block are all active at its slot
+    pub(crate) fn check_operations_activation(
+        &self,
+        slot: Slot,
+        operation_ids: &[OperationId],
+        storage: &Storage,
+    ) -> Option<DiscardReason> {
+        let timestamp = match get_block_slot_timestamp(
+            self.config.thread_count,
+            self.config.t0,
+            self.config.genesis_timestamp,
+            slot,
+        ) {
+            Ok(timestamp) => timestamp,
+            Err(err) => {
+                return Some(DiscardReason::Invalid(format!(
+                    "could not get the timestamp of slot {}: {}",
+                    slot, err
+                )))
+            }
+        };
+        let execution_version = self
+            .channels
+            .mip_store
+            .get_latest_component_version_at(&MipComponent::Execution, timestamp);
+        let ops = storage.read_operations();
+        operation_ids
+            .iter()
+            .find(|op_id| {
+                ops.get(op_id).is_some_and(|op| {
+                    op.content.op.required_execution_version() > execution_version
+                })
+            })
+            .map(|op_id| {
+                DiscardReason::Invalid(format!(
+                    "operation {} of the block in slot {} is not active yet",
+                    op_id, slot
+                ))
+            })
+    }
 }
@@ -8,10 +8,14 @@ use super::{
     universe::{ConsensusForeignControllers, ConsensusTestUniverse},
 };
 use crate::tests::tools::create_block;
-use massa_consensus_exports::ConsensusConfig;
+use massa_consensus_exports::{error::ConsensusError, ConsensusConfig};
 use massa_execution_exports::MockExecutionController;
 use massa_models::{
-    address::Address, block::BlockGraphStatus, block_id::BlockId, config::ENDORSEMENT_COUNT,
+    address::Address,
+    block::{BlockGraphFormat, BlockGraphStatus, DiscardedBlockReason},
+    block_id::BlockId,
+    clique::BlockcliqueChoice,
+    config::ENDORSEMENT_COUNT,
     slot::Slot,
 };
 use massa_pool_exports::MockPoolController;
@@ -271,6 +275,38 @@ fn test_parallel_incompatibility() {
                 "wrong best_parents"
             );
 
+            let cliques_details = consensus_controller
+                .get_cliques_details()
+                .expect("could not get cliques details");
+            assert_eq!(cliques_details.len(), 2);
+            for clique in cliques_details.iter() {
+                assert!(clique.blocks.windows(2).all(|w| w[0].slot <= w[1].slot));
+                let has_block_3 = clique.blocks.iter().any(|b| b.block_id == block_3.id);
+                assert_eq!(clique.is_blockclique, has_block_3);
+                if clique.is_blockclique {
+                    assert_eq!(clique.fitness_gap, 0);
+                } else {
+                    assert!(matches!(
+                        clique.choice,
+                        BlockcliqueChoice::LowerFitness | BlockcliqueChoice::LostTieBreak
+                    ));
+                }
+            }
+
+            let dot = consensus_controller
+                .export_block_graph(None, None, BlockGraphFormat::Dot)
+                .expect("could not export block graph");
+            assert!(dot.starts_with("digraph block_graph {"));
+            assert!(dot.contains(&format!("\"{}\" -> \"{}\";", block_3.id, block_1.id)));
+            assert!(dot.contains("status=\"blockclique\""));
+            assert!(dot.contains("status=\"alternative\""));
+            let graphml = consensus_controller
+                .export_block_graph(Some(Slot::new(2, 0)), None, BlockGraphFormat::GraphMl)
+                .expect("could not export block graph");
+            assert!(graphml.contains(&format!("<node id=\"{}\">", block_3.id)));
+            assert!(!graphml.contains(&format!("<node id=\"{}\">", block_1.id)));
+            assert!(!graphml.contains(&format!("target=\"{}\"", block_1.id)));
+
             let mut latest_extra_blocks = VecDeque::new();
             for extend_i in 0..33 {
                 let status = consensus_controller
@@ -302,6 +338,122 @@ fn test_parallel_incompatibility() {
                 latest_extra_blocks,
                 "wrong cliques"
             );
+
+            // the block incompatible with the blockclique became stale
+            let stale_block_stats = consensus_controller.get_stale_block_stats();
+            let stale_block_ids: HashSet<BlockId> = stale_block_stats
+                .iter()
+                .flat_map(|cycle_stats| cycle_stats.blocks.iter().map(|block| block.block_id))
+                .collect();
+            assert_eq!(stale_block_ids, HashSet::from([block_4.id]));
+            let stale_block_count: u64 = stale_block_stats
+                .iter()
+                .flat_map(|cycle_stats| cycle_stats.creators.iter())
+                .filter(|creator| creator.address == staking_address)
+                .map(|creator| creator.stale_block_count)
+                .sum();
+            assert_eq!(stale_block_count, 1);
+
+            let finality_proof = consensus_controller
+                .get_finality_proof(block_3.id)
+                .expect("could not get finality proof");
+            assert_eq!(finality_proof.header.id, block_3.id);
+            assert!(finality_proof.descendants_fitness > finality_proof.fitness_threshold);
+            assert!(finality_proof
+                .descendants
+                .windows(2)
+                .all(|w| w[0].content.slot < w[1].content.slot));
+            let last_block_id = status.max_cliques[0]
+                .block_ids
+                .iter()
+                .max_by_key(|block_id| status.active_blocks[*block_id].header.content.slot)
+                .copied()
+                .unwrap();
+            assert!(matches!(
+                consensus_controller.get_finality_proof(last_block_id),
+                Err(ConsensusError::BlockNotFinal(_))
+            ));
+            assert!(consensus_controller.get_finality_proof(block_4.id).is_err());
+        },
+    );
+}
+
+#[test]
+fn test_discarded_blocks_retention() {
+    let staking_key: KeyPair = KeyPair::generate(0).unwrap();
+    let cfg = ConsensusConfig {
+        t0: MassaTime::from_millis(100),
+        thread_count: 2,
+        genesis_timestamp: MassaTime::now(),
+        force_keep_final_periods: 1,
+        force_keep_final_periods_without_ops: 2,
+        delta_f0: 4,
+        block_db_prune_interval: MassaTime::from_millis(100),
+        discarded_blocks_retention_periods: 5,
+        ..ConsensusConfig::default()
+    };
+    let storage = Storage::create_root();
+    let staking_address = Address::from_public_key(&staking_key.get_public_key());
+
+    let mut execution_controller = Box::new(MockExecutionController::new());
+    execution_controller
+        .expect_update_blockclique_status()
+        .returning(|_, _, _| {});
+    let mut pool_controller = Box::new(MockPoolController::new());
+    pool_controller
+        .expect_notify_final_cs_periods()
+        .returning(|_| {});
+    pool_controller
+        .expect_add_denunciation_precursor()
+        .returning(|_| {});
+    let mut selector_controller = Box::new(MockSelectorController::new());
+    selector_controller
+        .expect_get_producer()
+        .returning(move |_| Ok(staking_address));
+    selector_controller
+        .expect_get_selection()
+        .returning(move |_| {
+            Ok(Selection {
+                producer: staking_address,
+                endorsements: vec![staking_address; ENDORSEMENT_COUNT as usize],
+            })
+        });
+    consensus_test(
+        cfg.clone(),
+        execution_controller,
+        pool_controller,
+        selector_controller,
+        move |consensus_controller| {
+            // extend thread 0 so that its oldest final blocks are discarded from the graph
+            for period in 1..=20 {
+                let status = consensus_controller
+                    .get_block_graph_status(None, None)
+                    .expect("could not get block graph status");
+                let block = create_block(
+                    Slot::new(period, 0),
+                    status.best_parents.iter().map(|(b, _p)| *b).collect(),
+                    &staking_key,
+                );
+                register_block(&consensus_controller, block, storage.clone());
+                std::thread::sleep(Duration::from_millis(100));
+            }
+            std::thread::sleep(Duration::from_millis(500));
+
+            let status = consensus_controller
+                .get_block_graph_status(None, None)
+                .expect("could not get block graph status");
+            let (_, latest_final_period) = status.latest_final_blocks_periods[0];
+            let discarded_blocks = consensus_controller.get_discarded_blocks();
+            assert!(!discarded_blocks.is_empty(), "no discarded block retained");
+            for block in discarded_blocks.iter() {
+                assert_eq!(block.reason, DiscardedBlockReason::Final);
+                assert!(
+                    block.slot.period + cfg.discarded_blocks_retention_periods
+                        >= latest_final_period,
+                    "discarded block {} retained beyond the retention periods",
+                    block.block_id
+                );
+            }
         },
     );
 }
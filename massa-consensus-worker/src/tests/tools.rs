@@ -8,7 +8,7 @@ use massa_consensus_exports::{
 use massa_execution_exports::MockExecutionController;
 use massa_hash::Hash;
 use massa_metrics::MassaMetrics;
-use massa_models::config::CHAINID;
+use massa_models::config::{CHAINID, MIP_STORE_STATS_BLOCK_CONSIDERED};
 use massa_models::{
     block::{Block, BlockSerializer, SecureShareBlock},
     block_header::{BlockHeader, BlockHeaderSerializer},
@@ -22,6 +22,8 @@ use massa_pos_exports::MockSelectorController;
 use massa_protocol_exports::MockProtocolController;
 use massa_signature::KeyPair;
 use massa_storage::Storage;
+use massa_versioning::versioning::{MipStatsConfig, MipStore};
+use num::rational::Ratio;
 
 pub fn consensus_test<F>(
     cfg: ConsensusConfig,
@@ -52,6 +54,12 @@ pub fn consensus_test<F>(
     let (block_sender, _block_receiver) = tokio::sync::broadcast::channel(10);
     let (block_header_sender, _block_header_receiver) = tokio::sync::broadcast::channel(10);
     let (filled_block_sender, _filled_block_receiver) = tokio::sync::broadcast::channel(10);
+    let (reorg_sender, _reorg_receiver) = tokio::sync::broadcast::channel(10);
+    let mip_stats_config = MipStatsConfig {
+        block_count_considered: MIP_STORE_STATS_BLOCK_CONSIDERED,
+        warn_announced_version_ratio: Ratio::new_raw(30, 100),
+    };
+    let mip_store = MipStore::try_from(([], mip_stats_config)).unwrap();
     let (consensus_controller, mut consensus_manager) = start_consensus_worker(
         cfg.clone(),
         ConsensusChannels {
@@ -59,12 +67,14 @@ pub fn consensus_test<F>(
                 block_sender,
                 block_header_sender,
                 filled_block_sender,
+                reorg_sender,
             },
             controller_event_tx: consensus_event_sender,
             execution_controller,
             protocol_controller,
             pool_controller,
             selector_controller,
+            mip_store,
         },
         None,
         storage.clone(),
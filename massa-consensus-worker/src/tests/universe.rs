@@ -6,12 +6,14 @@ use massa_consensus_exports::{
 };
 use massa_execution_exports::MockExecutionController;
 use massa_metrics::MassaMetrics;
-use massa_models::config::THREAD_COUNT;
+use massa_models::config::{MIP_STORE_STATS_BLOCK_CONSIDERED, THREAD_COUNT};
 use massa_pool_exports::MockPoolController;
 use massa_pos_exports::MockSelectorController;
 use massa_protocol_exports::MockProtocolController;
 use massa_storage::Storage;
 use massa_test_framework::TestUniverse;
+use massa_versioning::versioning::{MipStatsConfig, MipStore};
+use num::rational::Ratio;
 
 use crate::start_consensus_worker;
 
@@ -21,6 +23,7 @@ pub struct ConsensusForeignControllers {
     pub pool_controller: Box<MockPoolController>,
     pub selector_controller: Box<MockSelectorController>,
     pub storage: Storage,
+    pub mip_store: MipStore,
 }
 
 impl ConsensusForeignControllers {
@@ -31,6 +34,14 @@ impl ConsensusForeignControllers {
             pool_controller: Box::new(MockPoolController::new()),
             selector_controller: Box::new(MockSelectorController::new()),
             storage: Storage::create_root(),
+            mip_store: MipStore::try_from((
+                [],
+                MipStatsConfig {
+                    block_count_considered: MIP_STORE_STATS_BLOCK_CONSIDERED,
+                    warn_announced_version_ratio: Ratio::new_raw(30, 100),
+                },
+            ))
+            .unwrap(),
         }
     }
 }
@@ -63,6 +74,7 @@ impl TestUniverse for ConsensusTestUniverse {
         let (block_sender, _block_receiver) = tokio::sync::broadcast::channel(10);
         let (block_header_sender, _block_header_receiver) = tokio::sync::broadcast::channel(10);
         let (filled_block_sender, _filled_block_receiver) = tokio::sync::broadcast::channel(10);
+        let (reorg_sender, _reorg_receiver) = tokio::sync::broadcast::channel(10);
         let (consensus_controller, _) = start_consensus_worker(
             config,
             ConsensusChannels {
@@ -70,12 +82,14 @@ impl TestUniverse for ConsensusTestUniverse {
                     block_sender,
                     block_header_sender,
                     filled_block_sender,
+                    reorg_sender,
                 },
                 controller_event_tx: consensus_event_sender,
                 execution_controller: foreign_controllers.execution_controller,
                 protocol_controller: foreign_controllers.protocol_controller,
                 pool_controller: foreign_controllers.pool_controller,
                 selector_controller: foreign_controllers.selector_controller,
+                mip_store: foreign_controllers.mip_store,
             },
             None,
             foreign_controllers.storage.clone(),
@@ -13,6 +13,9 @@ pub const CHANGE_ID_KEY: &[u8; 1] = b"c";
 pub const CHANGE_ID_DESER_ERROR: &str = "critical: change_id deserialization failed";
 pub const CHANGE_ID_SER_ERROR: &str = "critical: change_id serialization failed";
 
+// Bootstrap
+pub const BOOTSTRAP_CURSOR_KEY: &[u8; 1] = b"b";
+
 // Errors
 pub const CF_ERROR: &str = "critical: rocksdb column family operation failed";
 pub const OPEN_ERROR: &str = "critical: rocksdb open operation failed";
@@ -1,8 +1,8 @@
-use crate::{DBBatch, Key, MassaDBError, StreamBatch, Value};
+use crate::{DBBatch, Key, MassaDBError, StateRange, StreamBatch, Value};
 use massa_hash::{HashXof, HASH_XOF_SIZE_BYTES};
 use massa_models::{error::ModelsError, slot::Slot, streaming_step::StreamingStep};
 use parking_lot::RwLock;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::{fmt::Debug, sync::Arc};
 
 #[cfg(feature = "test-exports")]
@@ -16,12 +16,22 @@ pub trait MassaDBController: Send + Sync + Debug {
     /// Creates a new hard copy of the DB, for the given slot
     fn backup_db(&self, slot: Slot) -> PathBuf;
 
+    /// Creates a new hard copy of the DB at the given path
+    fn checkpoint_db(&self, path: &Path) -> Result<(), MassaDBError>;
+
     /// Get the current change_id attached to the database.
     fn get_change_id(&self) -> Result<Slot, ModelsError>;
 
     /// Set the initial change_id. This function should only be called at startup/reset, as it does not batch this set with other changes.
     fn set_initial_change_id(&self, change_id: Slot);
 
+    /// Get the cursor of the bootstrap being received, if it has not finished yet
+    fn get_bootstrap_cursor(&self) -> Option<Vec<u8>>;
+
+    /// Set the cursor of the bootstrap being received, or delete it once the bootstrap is finished.
+    /// This function does not batch this set with other changes.
+    fn set_bootstrap_cursor(&self, cursor: Option<&[u8]>);
+
     /// Writes the batch to the DB
     fn write_batch(&mut self, batch: DBBatch, versioning_batch: DBBatch, change_id: Option<Slot>);
 
@@ -79,6 +89,19 @@ pub trait MassaDBController: Send + Sync + Debug {
         last_change_id: Option<Slot>,
     ) -> Result<StreamBatch<Slot>, MassaDBError>;
 
+    /// Used for bootstrap servers (get a new batch of data from a range of STATE_CF to stream to the client)
+    ///
+    /// Returns a StreamBatch<Slot>, whose changes are restricted to the range
+    fn get_range_batch_to_stream(
+        &self,
+        range: &StateRange,
+        last_state_step: &StreamingStep<Vec<u8>>,
+        last_change_id: Option<Slot>,
+    ) -> Result<StreamBatch<Slot>, MassaDBError>;
+
+    /// Get the extended hash of the entries of a range of STATE_CF, used to verify a range streamed during bootstrap
+    fn get_range_xof_hash(&self, range: &StateRange) -> HashXof<HASH_XOF_SIZE_BYTES>;
+
     /// Used for bootstrap servers (get a new batch of data from VERSIONING_CF to stream to the client)
     ///
     /// Returns a StreamBatch<Slot>
@@ -27,3 +27,25 @@ impl<ChangeID: PartialOrd + Ord + PartialEq + Eq + Clone + std::fmt::Debug> Stre
         self.updates_on_previous_elements.is_empty() && self.new_elements.is_empty()
     }
 }
+
+/// A range of keys of the state, streamed on its own by a bootstrap server
+/// so that the state can be downloaded from several servers at once.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StateRange {
+    /// First key of the range (included)
+    pub start: Key,
+    /// End of the range (excluded), or `None` for a range up to the last key
+    pub end: Option<Key>,
+}
+
+impl StateRange {
+    /// Whether the range contains the given key
+    pub fn contains(&self, key: &[u8]) -> bool {
+        key >= self.start.as_slice() && self.end.as_ref().map_or(true, |end| key < end.as_slice())
+    }
+
+    /// Whether the range contains no key at all
+    pub fn is_empty(&self) -> bool {
+        self.end.as_ref().is_some_and(|end| *end <= self.start)
+    }
+}
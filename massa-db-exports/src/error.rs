@@ -21,4 +21,12 @@ pub enum MassaDBError {
     HashError(String),
     /// serialization error: {0}
     SerializeError(String),
+    /// snapshot error: {0}
+    SnapshotError(String),
+    /// invalid configuration: {0}
+    ConfigError(String),
+    /// invalid state range: {0}
+    InvalidRange(String),
+    /// integrity error: {0}
+    IntegrityError(String),
 }
@@ -1,3 +1,8 @@
+use crate::{MassaDBError, METADATA_CF, STATE_CF, VERSIONING_CF};
+use massa_time::MassaTime;
+use serde::Deserialize;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 /// Config structure for a `MassaDBRaw`
@@ -14,3 +19,147 @@ pub struct MassaDBConfig {
     /// Thread count for slot serialization
     pub thread_count: u8,
 }
+
+/// Compression of the data blocks of a RocksDB column family
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum RocksDBCompression {
+    /// no compression
+    None,
+    /// Snappy compression
+    Snappy,
+    /// LZ4 compression
+    Lz4,
+    /// Zstandard compression
+    Zstd,
+}
+
+/// RocksDB options of a column family, overriding the ones of the database when set
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RocksDBColumnFamilyConfig {
+    /// size of the write buffer, in bytes
+    pub write_buffer_size: Option<usize>,
+    /// compression of the data blocks
+    pub compression: Option<RocksDBCompression>,
+}
+
+/// Tuning options of the RocksDB instance wrapped by a `MassaDBRaw`
+#[derive(Debug, Clone, Deserialize)]
+pub struct RocksDBConfig {
+    /// size of the block cache shared by the column families, in bytes
+    pub block_cache_size: usize,
+    /// max number of files kept open by RocksDB, -1 for no limit
+    pub max_open_files: i32,
+    /// size of the write buffer of each column family, in bytes
+    pub write_buffer_size: usize,
+    /// compression of the data blocks of each column family
+    pub compression: RocksDBCompression,
+    /// options of the column families, by column family name
+    pub column_families: BTreeMap<String, RocksDBColumnFamilyConfig>,
+}
+
+impl Default for RocksDBConfig {
+    /// The options used before they were configurable
+    fn default() -> Self {
+        RocksDBConfig {
+            block_cache_size: 32 * 1024 * 1024,
+            max_open_files: 820,
+            write_buffer_size: 64 * 1024 * 1024,
+            compression: RocksDBCompression::Snappy,
+            column_families: BTreeMap::new(),
+        }
+    }
+}
+
+impl RocksDBConfig {
+    /// Check that the options can be applied to the database
+    pub fn validate(&self) -> Result<(), MassaDBError> {
+        if self.max_open_files != -1 && self.max_open_files < 16 {
+            return Err(MassaDBError::ConfigError(format!(
+                "max_open_files must be -1 or at least 16, got {}",
+                self.max_open_files
+            )));
+        }
+        for (name, cf_config) in self.column_families.iter() {
+            check_column_family(name)?;
+            if cf_config.write_buffer_size == Some(0) {
+                return Err(MassaDBError::ConfigError(format!(
+                    "the write buffer size of column family {} must not be 0",
+                    name
+                )));
+            }
+        }
+        if self.write_buffer_size == 0 {
+            return Err(MassaDBError::ConfigError(
+                "the write buffer size must not be 0".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Get the effective write buffer size of a column family
+    pub fn get_write_buffer_size(&self, cf: &str) -> usize {
+        self.column_families
+            .get(cf)
+            .and_then(|cf_config| cf_config.write_buffer_size)
+            .unwrap_or(self.write_buffer_size)
+    }
+
+    /// Get the effective compression of a column family
+    pub fn get_compression(&self, cf: &str) -> RocksDBCompression {
+        self.column_families
+            .get(cf)
+            .and_then(|cf_config| cf_config.compression)
+            .unwrap_or(self.compression)
+    }
+}
+
+/// Scheduling of the manual compactions of the database, reclaiming the space of the
+/// deleted and overwritten entries during a daily low-activity window
+#[derive(Debug, Clone, Deserialize)]
+pub struct MassaDBCompactionConfig {
+    /// column families to compact, none to disable the compactions
+    pub column_families: Vec<String>,
+    /// min time between two compactions of the column families
+    pub interval: MassaTime,
+    /// UTC hour (0 to 23) at which the low-activity window starts
+    pub window_start_hour: u8,
+    /// UTC hour (0 to 23) at which the low-activity window ends. The window wraps around midnight
+    /// if it ends before it starts, and lasts the whole day if it ends when it starts.
+    pub window_end_hour: u8,
+}
+
+impl MassaDBCompactionConfig {
+    /// Check that the compactions can be scheduled
+    pub fn validate(&self) -> Result<(), MassaDBError> {
+        for name in self.column_families.iter() {
+            check_column_family(name)?;
+        }
+        if self.window_start_hour > 23 || self.window_end_hour > 23 {
+            return Err(MassaDBError::ConfigError(
+                "the hours of the compaction window must be between 0 and 23".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Whether a time is in the low-activity window
+    pub fn is_in_window(&self, time: MassaTime) -> bool {
+        let hour = (time.as_millis() / 3_600_000 % 24) as u8;
+        match self.window_start_hour.cmp(&self.window_end_hour) {
+            Ordering::Less => self.window_start_hour <= hour && hour < self.window_end_hour,
+            Ordering::Greater => self.window_start_hour <= hour || hour < self.window_end_hour,
+            Ordering::Equal => true,
+        }
+    }
+}
+
+/// Check that a column family exists
+fn check_column_family(name: &str) -> Result<(), MassaDBError> {
+    if ![STATE_CF, METADATA_CF, VERSIONING_CF].contains(&name) {
+        return Err(MassaDBError::ConfigError(format!(
+            "unknown column family {}",
+            name
+        )));
+    }
+    Ok(())
+}
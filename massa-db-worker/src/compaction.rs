@@ -0,0 +1,173 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Scheduled manual compactions of the database.
+//!
+//! The entries deleted or overwritten in RocksDB are only dropped from the disk when the files
+//! holding them are compacted, which RocksDB does lazily. The column families with a high
+//! churn, like the state, are thus compacted in a background thread during a low-activity
+//! window of the day.
+
+use crate::MassaDB;
+use massa_db_exports::{MassaDBCompactionConfig, MassaDBError, CF_ERROR};
+use massa_metrics::MassaMetrics;
+use massa_time::MassaTime;
+use rocksdb::DB;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Time between two checks of whether the compactions are due
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Stops the thread of the scheduled compactions
+pub struct CompactionSchedulerStopper {
+    tx_stopper: Option<Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl CompactionSchedulerStopper {
+    /// Stop the thread, after the end of the ongoing compaction if any
+    pub fn stop(&mut self) {
+        if let Some(tx) = self.tx_stopper.take() {
+            if tx.send(()).is_err() {
+                warn!("failed to send stop signal to the db compaction thread");
+            }
+        }
+        if let Some(handle) = self.handle.take() {
+            if handle.join().is_err() {
+                warn!("failed to join the db compaction thread");
+            }
+        }
+    }
+}
+
+/// Start the thread compacting the configured column families of the database
+/// at every `interval` during the low-activity window.
+/// The compactions run concurrently with the reads and writes of the database.
+pub fn start_compaction_scheduler(
+    db: &MassaDB,
+    config: MassaDBCompactionConfig,
+    massa_metrics: MassaMetrics,
+) -> Result<CompactionSchedulerStopper, MassaDBError> {
+    config.validate()?;
+    if config.column_families.is_empty() {
+        return Ok(CompactionSchedulerStopper {
+            tx_stopper: None,
+            handle: None,
+        });
+    }
+    let db = db.db.clone();
+    let (tx_stopper, rx_stopper) = mpsc::channel();
+    let handle = std::thread::Builder::new()
+        .name("massa-db-compaction".to_string())
+        .spawn(move || {
+            let mut last_compaction: Option<MassaTime> = None;
+            loop {
+                // stop signal received, or stopper dropped
+                if rx_stopper.recv_timeout(CHECK_INTERVAL) != Err(RecvTimeoutError::Timeout) {
+                    break;
+                }
+                let now = MassaTime::now();
+                let due = match last_compaction {
+                    Some(last) => now.saturating_sub(last) >= config.interval,
+                    None => true,
+                };
+                if !due || !config.is_in_window(now) {
+                    continue;
+                }
+                for cf in config.column_families.iter() {
+                    let (reclaimed_bytes, duration) = compact_column_family(&db, cf);
+                    massa_metrics.on_db_compaction(reclaimed_bytes, duration);
+                    info!(
+                        "db column family {} compacted in {:?}, {} bytes reclaimed",
+                        cf, duration, reclaimed_bytes
+                    );
+                }
+                last_compaction = Some(now);
+            }
+        })
+        .expect("failed to spawn the db compaction thread");
+    Ok(CompactionSchedulerStopper {
+        tx_stopper: Some(tx_stopper),
+        handle: Some(handle),
+    })
+}
+
+/// Compact a whole column family
+///
+/// # Returns
+/// The disk space reclaimed in bytes, and the duration of the compaction
+fn compact_column_family(db: &DB, cf: &str) -> (u64, Duration) {
+    let handle = db.cf_handle(cf).expect(CF_ERROR);
+    let live_files_size = || {
+        db.property_int_value_cf(handle, "rocksdb.live-sst-files-size")
+            .ok()
+            .flatten()
+            .unwrap_or_default()
+    };
+    let size_before = live_files_size();
+    let start = Instant::now();
+    db.compact_range_cf(handle, None::<&[u8]>, None::<&[u8]>);
+    let duration = start.elapsed();
+    (size_before.saturating_sub(live_files_size()), duration)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_db_exports::{DBBatch, MassaDBConfig, MassaDBController, STATE_CF};
+    use massa_models::config::THREAD_COUNT;
+    use massa_models::slot::Slot;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_compact_column_family() {
+        let temp_dir_db = tempdir().expect("Unable to create a temp folder");
+        let mut db = MassaDB::new(MassaDBConfig {
+            path: temp_dir_db.path().to_path_buf(),
+            max_history_length: 100,
+            max_final_state_elements_size: 100,
+            max_versioning_elements_size: 100,
+            thread_count: THREAD_COUNT,
+        });
+
+        // write entries to the disk, then delete them
+        let keys: Vec<Vec<u8>> = (0..1000u32).map(|i| i.to_be_bytes().to_vec()).collect();
+        let batch = keys.iter().map(|key| (key.clone(), Some(vec![0; 100])));
+        db.write_batch(batch.collect(), DBBatch::new(), Some(Slot::new(1, 0)));
+        db.flush().unwrap();
+        let batch = keys.iter().map(|key| (key.clone(), None));
+        db.write_batch(batch.collect(), DBBatch::new(), Some(Slot::new(2, 0)));
+        db.flush().unwrap();
+
+        let (reclaimed_bytes, _duration) = compact_column_family(&db.db, STATE_CF);
+        assert!(reclaimed_bytes > 0);
+        let (reclaimed_bytes, _duration) = compact_column_family(&db.db, STATE_CF);
+        assert_eq!(reclaimed_bytes, 0);
+    }
+
+    #[test]
+    fn test_compaction_window() {
+        let at_hour = |hour: u64| MassaTime::from_millis((24 * 100 + hour) * 3_600_000);
+        let config = |window_start_hour, window_end_hour| MassaDBCompactionConfig {
+            column_families: vec![STATE_CF.to_string()],
+            interval: MassaTime::from_millis(3_600_000),
+            window_start_hour,
+            window_end_hour,
+        };
+
+        assert!(config(2, 5).is_in_window(at_hour(2)));
+        assert!(!config(2, 5).is_in_window(at_hour(5)));
+        assert!(!config(2, 5).is_in_window(at_hour(23)));
+        assert!(config(22, 3).is_in_window(at_hour(23)));
+        assert!(config(22, 3).is_in_window(at_hour(0)));
+        assert!(!config(22, 3).is_in_window(at_hour(12)));
+        assert!(config(4, 4).is_in_window(at_hour(12)));
+
+        assert!(config(2, 24).validate().is_err());
+        let mut invalid_config = config(2, 5);
+        invalid_config.column_families.push("unknown".to_string());
+        assert!(invalid_config.validate().is_err());
+    }
+}
@@ -0,0 +1,68 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Verification of the integrity of a final state stored on disk.
+//!
+//! The hash of the state is updated along with each write of its entries, and stored in the
+//! metadata column with the slot of the state. After a crash or a disk failure, the stored hash
+//! no longer matches the entries of the state column, or the slot is missing.
+
+use massa_db_exports::{
+    MassaDBController, MassaDBError, MassaIteratorMode, STATE_CF, STATE_HASH_INITIAL_BYTES,
+};
+use massa_hash::HashXof;
+use massa_models::slot::Slot;
+
+/// Check that the final state stored in `db` is consistent: its slot can be read, and its stored
+/// hash matches the one recomputed from its entries
+///
+/// # Returns
+/// The slot of the verified state
+pub fn verify_state_integrity(db: &dyn MassaDBController) -> Result<Slot, MassaDBError> {
+    let slot = db.get_change_id().map_err(|err| {
+        MassaDBError::IntegrityError(format!("could not read the slot of the state: {}", err))
+    })?;
+    let mut hash = HashXof(*STATE_HASH_INITIAL_BYTES);
+    for (key, value) in db.iterator_cf(STATE_CF, MassaIteratorMode::Start) {
+        hash ^= HashXof::compute_from_tuple(&[key.as_slice(), value.as_slice()]);
+    }
+    if hash != db.get_xof_db_hash() {
+        return Err(MassaDBError::IntegrityError(format!(
+            "the hash of the state at slot {} does not match its entries",
+            slot
+        )));
+    }
+    Ok(slot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MassaDB;
+    use massa_db_exports::{DBBatch, MassaDBConfig};
+    use massa_models::config::THREAD_COUNT;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_verify_state_integrity() {
+        let temp_dir = tempdir().unwrap();
+        let mut db = MassaDB::new(MassaDBConfig {
+            path: temp_dir.path().to_path_buf(),
+            max_history_length: 100,
+            max_final_state_elements_size: 100,
+            max_versioning_elements_size: 100,
+            thread_count: THREAD_COUNT,
+        });
+        let mut batch = DBBatch::new();
+        for i in 0..10u8 {
+            batch.insert(vec![1, i], Some(vec![i; 4]));
+        }
+        let slot = Slot::new(5, 0);
+        db.write_batch(batch, DBBatch::new(), Some(slot));
+        assert_eq!(verify_state_integrity(&db).unwrap(), slot);
+
+        // entry written without updating the hash, as a partially applied write would
+        let handle = db.db.cf_handle(STATE_CF).unwrap();
+        db.db.put_cf(handle, [1, 3], [0; 4]).unwrap();
+        assert!(verify_state_integrity(&db).is_err());
+    }
+}
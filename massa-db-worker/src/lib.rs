@@ -59,7 +59,21 @@
 //!    the updates (by querying only the cache)
 //! 3- Even after this process is finished (and as other things like consensus data are streamed),
 //!    we can send the updates
+//!
+//! # Compactions
+//!
+//! The deleted and overwritten entries are only dropped from the disk when RocksDB compacts
+//! the files holding them. The column families can also be compacted during a daily
+//! low-activity window, see `start_compaction_scheduler`.
 
+mod compaction;
+mod integrity;
 mod massa_db;
+mod snapshot;
 
+pub use crate::compaction::{start_compaction_scheduler, CompactionSchedulerStopper};
+pub use crate::integrity::verify_state_integrity;
 pub use crate::massa_db::*;
+pub use crate::snapshot::{
+    check_snapshot_hash, export_snapshot, import_snapshot, read_snapshot_header,
+};
@@ -1,8 +1,9 @@
 use massa_db_exports::{
     DBBatch, Key, MassaDBConfig, MassaDBController, MassaDBError, MassaDirection,
-    MassaIteratorMode, StreamBatch, Value, CF_ERROR, CHANGE_ID_DESER_ERROR, CHANGE_ID_KEY,
-    CHANGE_ID_SER_ERROR, CRUD_ERROR, METADATA_CF, OPEN_ERROR, STATE_CF, STATE_HASH_ERROR,
-    STATE_HASH_INITIAL_BYTES, STATE_HASH_KEY, VERSIONING_CF,
+    MassaIteratorMode, RocksDBCompression, RocksDBConfig, StateRange, StreamBatch, Value,
+    BOOTSTRAP_CURSOR_KEY, CF_ERROR, CHANGE_ID_DESER_ERROR, CHANGE_ID_KEY, CHANGE_ID_SER_ERROR,
+    CRUD_ERROR, METADATA_CF, OPEN_ERROR, STATE_CF, STATE_HASH_ERROR, STATE_HASH_INITIAL_BYTES,
+    STATE_HASH_KEY, VERSIONING_CF,
 };
 use massa_hash::{HashXof, HASH_XOF_SIZE_BYTES};
 use massa_models::{
@@ -14,10 +15,10 @@ use massa_models::{
 use massa_serialization::{DeserializeError, Deserializer, Serializer, U64VarIntSerializer};
 use parking_lot::Mutex;
 use rocksdb::{
-    checkpoint::Checkpoint, ColumnFamilyDescriptor, Direction, IteratorMode, Options, WriteBatch,
-    DB,
+    checkpoint::Checkpoint, BlockBasedOptions, Cache, ColumnFamilyDescriptor, DBCompressionType,
+    Direction, IteratorMode, Options, WriteBatch, DB,
 };
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::{
     collections::BTreeMap,
     format,
@@ -88,9 +89,28 @@ where
         last_state_step: &StreamingStep<Vec<u8>>,
         last_change_id: Option<ChangeID>,
     ) -> Result<StreamBatch<ChangeID>, MassaDBError> {
-        let bound_key_for_changes = match &last_state_step {
-            StreamingStep::Ongoing(max_key) => Included(max_key.clone()),
-            _ => Unbounded,
+        self.get_range_batch_to_stream(&StateRange::default(), last_state_step, last_change_id)
+    }
+
+    /// Used for bootstrap servers (get a new batch of data from a range of STATE_CF to stream to the client)
+    ///
+    /// Returns a StreamBatch<ChangeID>, whose changes are restricted to the range
+    pub fn get_range_batch_to_stream(
+        &self,
+        range: &StateRange,
+        last_state_step: &StreamingStep<Vec<u8>>,
+        last_change_id: Option<ChangeID>,
+    ) -> Result<StreamBatch<ChangeID>, MassaDBError> {
+        if range.is_empty() {
+            return Err(MassaDBError::InvalidRange(String::from(
+                "the end of the state range is not after its start",
+            )));
+        }
+        let start_key_for_changes = Included(range.start.clone());
+        let bound_key_for_changes = match (&last_state_step, &range.end) {
+            (StreamingStep::Ongoing(max_key), _) => Included(max_key.clone()),
+            (_, Some(end)) => Excluded(end.clone()),
+            (_, None) => Unbounded,
         };
 
         // Updates == "everything that changed since the last change_id streamed, up to a certain key".
@@ -132,7 +152,7 @@ where
                                     updates.extend(
                                         changes
                                             .range((
-                                                Bound::<Vec<u8>>::Unbounded,
+                                                start_key_for_changes.clone(),
                                                 bound_key_for_changes.clone(),
                                             ))
                                             .map(|(k, v)| (k.clone(), v.clone())),
@@ -166,7 +186,7 @@ where
         if !last_state_step.finished() {
             let handle = self.db.cf_handle(STATE_CF).expect(CF_ERROR);
 
-            // Creates an iterator from the next element after the last if defined, otherwise initialize it at the first key.
+            // Creates an iterator from the next element after the last if defined, otherwise initialize it at the first key of the range.
             let db_iterator = match &last_state_step {
                 StreamingStep::Ongoing(max_key) => {
                     let mut iter = self
@@ -175,11 +195,16 @@ where
                     iter.next();
                     iter
                 }
-                _ => self.db.iterator_cf(handle, IteratorMode::Start),
+                _ => self
+                    .db
+                    .iterator_cf(handle, IteratorMode::From(&range.start, Direction::Forward)),
             };
 
             let u64_ser = U64VarIntSerializer::new();
             for (serialized_key, serialized_value) in db_iterator.flatten() {
+                if !range.contains(&serialized_key) {
+                    break;
+                }
                 let key_len = serialized_key.len();
                 let value_len = serialized_value.len();
                 let mut buffer = Vec::new();
@@ -495,6 +520,25 @@ where
         }
     }
 
+    /// Get the cursor of the bootstrap being received, if it has not finished yet
+    pub fn get_bootstrap_cursor(&self) -> Option<Vec<u8>> {
+        let handle = self.db.cf_handle(METADATA_CF).expect(CF_ERROR);
+        self.db
+            .get_cf(handle, BOOTSTRAP_CURSOR_KEY)
+            .expect(CRUD_ERROR)
+    }
+
+    /// Set the cursor of the bootstrap being received, or delete it once the bootstrap is finished.
+    /// This function does not batch this set with other changes.
+    pub fn set_bootstrap_cursor(&self, cursor: Option<&[u8]>) {
+        let handle = self.db.cf_handle(METADATA_CF).expect(CF_ERROR);
+        match cursor {
+            Some(cursor) => self.db.put_cf(handle, BOOTSTRAP_CURSOR_KEY, cursor),
+            None => self.db.delete_cf(handle, BOOTSTRAP_CURSOR_KEY),
+        }
+        .expect(CRUD_ERROR);
+    }
+
     /// Set the current change_id in the batch
     pub fn set_change_id_to_batch(&self, change_id: ChangeID) {
         let handle_metadata = self.db.cf_handle(METADATA_CF).expect(CF_ERROR);
@@ -556,6 +600,26 @@ where
         Ok((new_cursor, new_cursor_versioning))
     }
 
+    /// Get the extended hash of the entries of a range of STATE_CF.
+    ///
+    /// Unlike the state hash, it does not include the initial bytes, so that the hashes of
+    /// consecutive ranges can be combined.
+    pub fn get_range_xof_hash(&self, range: &StateRange) -> HashXof<HASH_XOF_SIZE_BYTES> {
+        let handle = self.db.cf_handle(STATE_CF).expect(CF_ERROR);
+        let mut range_hash = HashXof([0u8; HASH_XOF_SIZE_BYTES]);
+        for (key, value) in self
+            .db
+            .iterator_cf(handle, IteratorMode::From(&range.start, Direction::Forward))
+            .flatten()
+        {
+            if !range.contains(&key) {
+                break;
+            }
+            range_hash ^= HashXof::compute_from_tuple(&[key.as_ref(), value.as_ref()]);
+        }
+        range_hash
+    }
+
     /// Get the current XOF state hash of the database
     pub fn get_xof_db_hash(&self) -> HashXof<HASH_XOF_SIZE_BYTES> {
         self.get_xof_db_hash_opt()
@@ -589,18 +653,89 @@ impl RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
         db_opts
     }
 
+    /// Returns a new `MassaDB` instance tuned with the given RocksDB options
+    pub fn new_with_rocksdb_config(
+        config: MassaDBConfig,
+        rocksdb_config: &RocksDBConfig,
+    ) -> Result<Self, MassaDBError> {
+        rocksdb_config.validate()?;
+        let mut db_opts = Self::default_db_opts();
+        db_opts.set_max_open_files(rocksdb_config.max_open_files);
+
+        // the block cache is shared by the column families
+        let block_cache = Cache::new_lru_cache(rocksdb_config.block_cache_size);
+        let cf_descriptors = [STATE_CF, METADATA_CF, VERSIONING_CF]
+            .into_iter()
+            .map(|cf| {
+                let mut block_opts = BlockBasedOptions::default();
+                block_opts.set_block_cache(&block_cache);
+                let mut cf_opts = Options::default();
+                cf_opts.set_block_based_table_factory(&block_opts);
+                cf_opts.set_write_buffer_size(rocksdb_config.get_write_buffer_size(cf));
+                cf_opts.set_compression_type(compression_type(rocksdb_config.get_compression(cf)));
+                ColumnFamilyDescriptor::new(cf, cf_opts)
+            })
+            .collect();
+
+        Self::new_with_cf_descriptors(config, db_opts, cf_descriptors)
+            .map_err(|err| MassaDBError::RocksDBError(err.to_string()))
+    }
+
     /// Returns a new `MassaDB` instance given a config and RocksDB options
     fn new_with_options(config: MassaDBConfig, db_opts: Options) -> Result<Self, rocksdb::Error> {
-        let db = DB::open_cf_descriptors(
-            &db_opts,
+        let cf_descriptors = vec![
+            ColumnFamilyDescriptor::new(STATE_CF, Options::default()),
+            ColumnFamilyDescriptor::new(METADATA_CF, Options::default()),
+            ColumnFamilyDescriptor::new(VERSIONING_CF, Options::default()),
+        ];
+        Self::new_with_cf_descriptors(config, db_opts, cf_descriptors)
+    }
+
+    /// Returns a new `MassaDB` instance given a config, RocksDB options and column family options
+    fn new_with_cf_descriptors(
+        config: MassaDBConfig,
+        db_opts: Options,
+        cf_descriptors: Vec<ColumnFamilyDescriptor>,
+    ) -> Result<Self, rocksdb::Error> {
+        let db = DB::open_cf_descriptors(&db_opts, &config.path, cf_descriptors)?;
+
+        let massa_db = Self::from_db(db, config);
+
+        if massa_db.get_change_id().is_err() {
+            massa_db.set_initial_change_id(Slot {
+                period: 0,
+                thread: 0,
+            });
+        }
+
+        Ok(massa_db)
+    }
+
+    /// Returns a `MassaDB` instance reading an existing database,
+    /// e.g. a copy made by `checkpoint_db`. Any write to this instance fails.
+    pub fn new_read_only(config: MassaDBConfig) -> Result<Self, MassaDBError> {
+        let db = DB::open_cf_for_read_only(
+            &Options::default(),
             &config.path,
-            vec![
-                ColumnFamilyDescriptor::new(STATE_CF, Options::default()),
-                ColumnFamilyDescriptor::new(METADATA_CF, Options::default()),
-                ColumnFamilyDescriptor::new(VERSIONING_CF, Options::default()),
-            ],
-        )?;
+            [STATE_CF, METADATA_CF, VERSIONING_CF],
+            false,
+        )
+        .map_err(|err| MassaDBError::RocksDBError(err.to_string()))?;
+        Ok(Self::from_db(db, config))
+    }
+
+    /// Get the usage and the capacity of the block cache, in bytes
+    pub fn get_block_cache_usage(&self) -> Option<(u64, u64)> {
+        let handle = self.db.cf_handle(STATE_CF).expect(CF_ERROR);
+        let property = |name: &str| self.db.property_int_value_cf(handle, name).ok().flatten();
+        Some((
+            property("rocksdb.block-cache-usage")?,
+            property("rocksdb.block-cache-capacity")?,
+        ))
+    }
 
+    /// Wraps an opened RocksDB instance
+    fn from_db(db: DB, config: MassaDBConfig) -> Self {
         let db = Arc::new(db);
         let current_batch = Arc::new(Mutex::new(WriteBatch::default()));
 
@@ -609,7 +744,7 @@ impl RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
             (Included(0), Excluded(config.thread_count)),
         );
 
-        let massa_db = Self {
+        Self {
             db,
             config,
             change_history: BTreeMap::new(),
@@ -617,16 +752,17 @@ impl RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
             change_id_serializer: SlotSerializer::new(),
             change_id_deserializer,
             current_batch,
-        };
-
-        if massa_db.get_change_id().is_err() {
-            massa_db.set_initial_change_id(Slot {
-                period: 0,
-                thread: 0,
-            });
         }
+    }
+}
 
-        Ok(massa_db)
+/// RocksDB compression type of a configured compression
+fn compression_type(compression: RocksDBCompression) -> DBCompressionType {
+    match compression {
+        RocksDBCompression::None => DBCompressionType::None,
+        RocksDBCompression::Snappy => DBCompressionType::Snappy,
+        RocksDBCompression::Lz4 => DBCompressionType::Lz4,
+        RocksDBCompression::Zstd => DBCompressionType::Zstd,
     }
 }
 
@@ -681,6 +817,13 @@ impl MassaDBController for RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
         backup_path
     }
 
+    /// Creates a new hard copy of the DB at the given path
+    fn checkpoint_db(&self, path: &Path) -> Result<(), MassaDBError> {
+        Checkpoint::new(&self.db)
+            .and_then(|checkpoint| checkpoint.create_checkpoint(path))
+            .map_err(|err| MassaDBError::RocksDBError(err.to_string()))
+    }
+
     /// Writes the batch to the DB
     fn write_batch(&mut self, batch: DBBatch, versioning_batch: DBBatch, change_id: Option<Slot>) {
         self.write_changes(batch, versioning_batch, change_id, false)
@@ -809,6 +952,16 @@ impl MassaDBController for RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
         self.set_initial_change_id(change_id)
     }
 
+    /// Get the cursor of the bootstrap being received, if it has not finished yet
+    fn get_bootstrap_cursor(&self) -> Option<Vec<u8>> {
+        self.get_bootstrap_cursor()
+    }
+
+    /// Set the cursor of the bootstrap being received, or delete it once the bootstrap is finished.
+    fn set_bootstrap_cursor(&self, cursor: Option<&[u8]>) {
+        self.set_bootstrap_cursor(cursor)
+    }
+
     /// Flushes the underlying db.
     fn flush(&self) -> Result<(), MassaDBError> {
         self.db
@@ -836,6 +989,23 @@ impl MassaDBController for RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
         self.get_batch_to_stream(last_state_step, last_change_id)
     }
 
+    /// Used for bootstrap servers (get a new batch of data from a range of STATE_CF to stream to the client)
+    ///
+    /// Returns a StreamBatch<Slot>, whose changes are restricted to the range
+    fn get_range_batch_to_stream(
+        &self,
+        range: &StateRange,
+        last_state_step: &StreamingStep<Vec<u8>>,
+        last_change_id: Option<Slot>,
+    ) -> Result<StreamBatch<Slot>, MassaDBError> {
+        self.get_range_batch_to_stream(range, last_state_step, last_change_id)
+    }
+
+    /// Get the extended hash of the entries of a range of STATE_CF, used to verify a range streamed during bootstrap
+    fn get_range_xof_hash(&self, range: &StateRange) -> HashXof<HASH_XOF_SIZE_BYTES> {
+        self.get_range_xof_hash(range)
+    }
+
     /// Used for bootstrap servers (get a new batch of data from VERSIONING_CF to stream to the client)
     ///
     /// Returns a StreamBatch<Slot>
@@ -881,6 +1051,7 @@ mod test {
 
     use assert_matches::assert_matches;
     use massa_db_exports::MassaDBError::TimeError;
+    use massa_db_exports::RocksDBColumnFamilyConfig;
     use parking_lot::RwLock;
     use tempfile::tempdir;
 
@@ -1305,6 +1476,104 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_checkpoint_read_only() {
+        // Init a db + add data
+        let temp_dir_db = tempdir().expect("Unable to create a temp folder");
+        let db_config = MassaDBConfig {
+            path: temp_dir_db.path().join("db"),
+            max_history_length: 100,
+            max_final_state_elements_size: 100,
+            max_versioning_elements_size: 100,
+            thread_count: THREAD_COUNT,
+        };
+        let mut db = MassaDB::new(db_config.clone());
+        let batch = DBBatch::from([(vec![1, 2, 3], Some(vec![4, 5, 6]))]);
+        let slot_1 = Slot::new(1, 0);
+        db.write_batch(batch, DBBatch::new(), Some(slot_1));
+        let hash_1 = db.get_xof_db_hash();
+
+        // Checkpoint, then add more data
+        let checkpoint_path = temp_dir_db.path().join("checkpoint");
+        db.checkpoint_db(&checkpoint_path).unwrap();
+        let batch = DBBatch::from([(vec![1, 2, 3], Some(vec![7, 8, 9]))]);
+        db.write_batch(batch, DBBatch::new(), Some(Slot::new(2, 0)));
+
+        // The checkpoint keeps the data at slot 1
+        let checkpoint = MassaDB::new_read_only(MassaDBConfig {
+            path: checkpoint_path.clone(),
+            ..db_config
+        })
+        .unwrap();
+        assert_eq!(checkpoint.get_change_id().unwrap(), slot_1);
+        assert_eq!(checkpoint.get_xof_db_hash(), hash_1);
+        assert_eq!(
+            checkpoint.get_cf(STATE_CF, vec![1, 2, 3]).unwrap(),
+            Some(vec![4, 5, 6])
+        );
+
+        // A checkpoint cannot be made twice at the same path
+        assert!(db.checkpoint_db(&checkpoint_path).is_err());
+    }
+
+    #[test]
+    fn test_rocksdb_config() {
+        let temp_dir_db = tempdir().expect("Unable to create a temp folder");
+        let db_config = MassaDBConfig {
+            path: temp_dir_db.path().to_path_buf(),
+            max_history_length: 100,
+            max_final_state_elements_size: 100,
+            max_versioning_elements_size: 100,
+            thread_count: THREAD_COUNT,
+        };
+
+        // unknown column families are rejected
+        let mut rocksdb_config = RocksDBConfig::default();
+        rocksdb_config
+            .column_families
+            .insert("unknown".to_string(), Default::default());
+        assert_matches!(
+            MassaDB::new_with_rocksdb_config(db_config.clone(), &rocksdb_config),
+            Err(MassaDBError::ConfigError(_))
+        );
+
+        let mut rocksdb_config = RocksDBConfig {
+            block_cache_size: 1024 * 1024,
+            compression: RocksDBCompression::Lz4,
+            ..Default::default()
+        };
+        rocksdb_config.column_families.insert(
+            STATE_CF.to_string(),
+            RocksDBColumnFamilyConfig {
+                write_buffer_size: Some(1024 * 1024),
+                compression: Some(RocksDBCompression::Zstd),
+            },
+        );
+        assert_eq!(rocksdb_config.get_write_buffer_size(STATE_CF), 1024 * 1024);
+        assert_eq!(
+            rocksdb_config.get_write_buffer_size(VERSIONING_CF),
+            RocksDBConfig::default().write_buffer_size
+        );
+        assert_eq!(
+            rocksdb_config.get_compression(STATE_CF),
+            RocksDBCompression::Zstd
+        );
+        assert_eq!(
+            rocksdb_config.get_compression(METADATA_CF),
+            RocksDBCompression::Lz4
+        );
+
+        let mut db = MassaDB::new_with_rocksdb_config(db_config, &rocksdb_config).unwrap();
+        let batch = DBBatch::from([(vec![1, 2, 3], Some(vec![4, 5, 6]))]);
+        db.write_batch(batch, DBBatch::new(), Some(Slot::new(1, 0)));
+        assert_eq!(
+            db.get_cf(STATE_CF, vec![1, 2, 3]).unwrap(),
+            Some(vec![4, 5, 6])
+        );
+        let (_usage, capacity) = db.get_block_cache_usage().unwrap();
+        assert_eq!(capacity, 1024 * 1024);
+    }
+
     #[test]
     fn test_db_stream() {
         // Init db + add data
@@ -1401,6 +1670,122 @@ mod test {
         assert_matches!(stream_batch, Err(TimeError(..)));
     }
 
+    #[test]
+    fn test_bootstrap_cursor() {
+        let temp_dir_db = tempdir().expect("Unable to create a temp folder");
+        let db_config = MassaDBConfig {
+            path: temp_dir_db.path().to_path_buf(),
+            max_history_length: 100,
+            max_final_state_elements_size: 100,
+            max_versioning_elements_size: 100,
+            thread_count: THREAD_COUNT,
+        };
+        let db = MassaDB::new_with_options(db_config, MassaDB::default_db_opts()).unwrap();
+        let hash = db.get_xof_db_hash();
+        assert_eq!(db.get_bootstrap_cursor(), None);
+
+        db.set_bootstrap_cursor(Some(&[1, 2, 3]));
+        assert_eq!(db.get_bootstrap_cursor(), Some(vec![1, 2, 3]));
+        // the cursor is not part of the state
+        assert_eq!(db.get_xof_db_hash(), hash);
+
+        db.set_bootstrap_cursor(None);
+        assert_eq!(db.get_bootstrap_cursor(), None);
+    }
+
+    #[test]
+    fn test_db_stream_range() {
+        // Stream a range of the state, then its changes, and check the range hashes
+
+        let temp_dir_db = tempdir().expect("Unable to create a temp folder");
+        let db_config = MassaDBConfig {
+            path: temp_dir_db.path().to_path_buf(),
+            max_history_length: 100,
+            max_final_state_elements_size: 100,
+            max_versioning_elements_size: 100,
+            thread_count: THREAD_COUNT,
+        };
+        let _db = MassaDB::new_with_options(db_config, MassaDB::default_db_opts()).unwrap();
+        let db = Arc::new(RwLock::new(
+            Box::new(_db) as Box<(dyn MassaDBController + 'static)>
+        ));
+
+        // Add data (at slot 1), one key in the range and one key on each side
+        let key_in_range = vec![11, 22, 33];
+        let batch = DBBatch::from([
+            (vec![1, 2, 3], Some(vec![4, 5, 6])),
+            (key_in_range.clone(), Some(vec![44, 55, 66])),
+            (vec![21, 0], Some(vec![1])),
+        ]);
+        let slot_1 = Slot::new(1, 0);
+        db.write().write_batch(batch, DBBatch::new(), Some(slot_1));
+
+        let range = StateRange {
+            start: vec![10],
+            end: Some(vec![21, 0]),
+        };
+        let stream_batch = db
+            .read()
+            .get_range_batch_to_stream(&range, &StreamingStep::Started, None)
+            .unwrap();
+        assert_eq!(
+            stream_batch.new_elements,
+            BTreeMap::from([(key_in_range.clone(), vec![44, 55, 66])])
+        );
+        assert_eq!(stream_batch.updates_on_previous_elements, BTreeMap::new());
+
+        // Update the keys inside and outside of the range (at slot 2): only the change of the key
+        // in the range is streamed
+        let batch = DBBatch::from([
+            (vec![1, 2, 3], Some(vec![7])),
+            (key_in_range.clone(), Some(vec![8])),
+        ]);
+        let slot_2 = Slot::new(2, 0);
+        db.write().write_batch(batch, DBBatch::new(), Some(slot_2));
+        let last_state_step = StreamingStep::Ongoing(key_in_range.clone());
+        let stream_batch = db
+            .read()
+            .get_range_batch_to_stream(&range, &last_state_step, Some(slot_1))
+            .unwrap();
+        assert_eq!(stream_batch.new_elements, BTreeMap::new());
+        assert_eq!(
+            stream_batch.updates_on_previous_elements,
+            BTreeMap::from([(key_in_range.clone(), Some(vec![8]))])
+        );
+        assert_eq!(stream_batch.change_id, slot_2);
+
+        // The hashes of consecutive ranges add up to the state hash
+        let range_hash = db.read().get_range_xof_hash(&range);
+        assert_eq!(
+            range_hash,
+            HashXof::compute_from_tuple(&[key_in_range.as_slice(), &[8]])
+        );
+        let before = StateRange {
+            start: vec![],
+            end: Some(range.start.clone()),
+        };
+        let after = StateRange {
+            start: range.end.clone().unwrap(),
+            end: None,
+        };
+        let state_hash = HashXof(*STATE_HASH_INITIAL_BYTES)
+            ^ db.read().get_range_xof_hash(&before)
+            ^ range_hash
+            ^ db.read().get_range_xof_hash(&after);
+        assert_eq!(state_hash, db.read().get_xof_db_hash());
+
+        // Empty range
+        let empty_range = StateRange {
+            start: vec![21],
+            end: Some(vec![10]),
+        };
+        assert_matches!(
+            db.read()
+                .get_range_batch_to_stream(&empty_range, &StreamingStep::Started, None),
+            Err(MassaDBError::InvalidRange(..))
+        );
+    }
+
     #[test]
     fn test_db_stream_versioning() {
         // Same as test_db_stream but for versioning
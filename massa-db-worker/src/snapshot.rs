@@ -0,0 +1,292 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Export and import of the complete final state, used for backups and to clone nodes.
+//!
+//! A snapshot starts with a header made of a magic number, the slot of the final state and its
+//! hash. It is followed by the entries of the state and versioning columns, each of them made of
+//! the identifier of its column and of its length-prefixed key and value.
+//! The hash of an imported state is checked against the one of the header.
+
+use massa_db_exports::{
+    MassaDBController, MassaDBError, MassaIteratorMode, StreamBatch, STATE_CF, VERSIONING_CF,
+};
+use massa_hash::{HashXof, HASH_XOF_SIZE_BYTES};
+use massa_models::slot::{Slot, SLOT_KEY_SIZE};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::Path;
+
+const SNAPSHOT_MAGIC: &[u8; 17] = b"MASSA_SNAPSHOT_V0";
+const STATE_IDENT: u8 = 0u8;
+const VERSIONING_IDENT: u8 = 1u8;
+/// Number of entries written to the database at once when importing a snapshot
+const IMPORT_BATCH_SIZE: usize = 10_000;
+
+/// Write the final state stored in `db` to a snapshot file at `path`
+///
+/// # Returns
+/// The slot and the hash of the exported state
+pub fn export_snapshot(
+    db: &dyn MassaDBController,
+    path: &Path,
+) -> Result<(Slot, HashXof<HASH_XOF_SIZE_BYTES>), MassaDBError> {
+    let slot = db.get_change_id().map_err(|err| {
+        MassaDBError::SnapshotError(format!("could not get the slot of the state: {}", err))
+    })?;
+    let hash = db.get_xof_db_hash();
+    write_snapshot(db, path, &slot, &hash).map_err(|err| {
+        MassaDBError::SnapshotError(format!("could not write the snapshot: {}", err))
+    })?;
+    Ok((slot, hash))
+}
+
+/// Restore in the empty database `db` the final state of the snapshot file at `path`,
+/// checking that its hash matches the one of the snapshot
+///
+/// # Returns
+/// The slot of the imported state
+pub fn import_snapshot(db: &mut dyn MassaDBController, path: &Path) -> Result<Slot, MassaDBError> {
+    if db
+        .iterator_cf(STATE_CF, MassaIteratorMode::Start)
+        .next()
+        .is_some()
+    {
+        return Err(MassaDBError::SnapshotError(
+            "the database to import the snapshot in is not empty".to_string(),
+        ));
+    }
+
+    let mut reader = BufReader::new(File::open(path).map_err(read_error)?);
+    let (slot, hash) = read_header(&mut reader)?;
+
+    let mut batch = empty_batch(slot);
+    let mut versioning_batch = empty_batch(slot);
+    while let Some((ident, key, value)) = read_entry(&mut reader).map_err(read_error)? {
+        let column_batch = match ident {
+            STATE_IDENT => &mut batch,
+            VERSIONING_IDENT => &mut versioning_batch,
+            _ => {
+                return Err(MassaDBError::SnapshotError(format!(
+                    "invalid column identifier: {}",
+                    ident
+                )))
+            }
+        };
+        column_batch.new_elements.insert(key, value);
+        if batch.new_elements.len() + versioning_batch.new_elements.len() >= IMPORT_BATCH_SIZE {
+            db.write_batch_bootstrap_client(
+                std::mem::replace(&mut batch, empty_batch(slot)),
+                std::mem::replace(&mut versioning_batch, empty_batch(slot)),
+            )?;
+        }
+    }
+    db.write_batch_bootstrap_client(batch, versioning_batch)?;
+
+    if db.get_xof_db_hash() != hash {
+        return Err(MassaDBError::SnapshotError(
+            "the hash of the imported state does not match the one of the snapshot".to_string(),
+        ));
+    }
+    Ok(slot)
+}
+
+/// Read the slot and the hash of the final state of the snapshot file at `path`,
+/// without importing it
+pub fn read_snapshot_header(
+    path: &Path,
+) -> Result<(Slot, HashXof<HASH_XOF_SIZE_BYTES>), MassaDBError> {
+    let mut reader = BufReader::new(File::open(path).map_err(read_error)?);
+    read_header(&mut reader)
+}
+
+/// Check that the hash of the final state of the snapshot file at `path` is the trusted one,
+/// as logged when the snapshot was exported
+///
+/// # Returns
+/// The slot of the state of the snapshot
+pub fn check_snapshot_hash(path: &Path, trusted_hash: &str) -> Result<Slot, MassaDBError> {
+    let (slot, hash) = read_snapshot_header(path)?;
+    if hash.to_string() != trusted_hash {
+        return Err(MassaDBError::SnapshotError(format!(
+            "the hash {} of the snapshot {} does not match the trusted one",
+            hash,
+            path.display()
+        )));
+    }
+    Ok(slot)
+}
+
+fn read_header(
+    reader: &mut impl Read,
+) -> Result<(Slot, HashXof<HASH_XOF_SIZE_BYTES>), MassaDBError> {
+    let mut magic = [0u8; SNAPSHOT_MAGIC.len()];
+    reader.read_exact(&mut magic).map_err(read_error)?;
+    if magic != *SNAPSHOT_MAGIC {
+        return Err(MassaDBError::SnapshotError(
+            "the file is not a final state snapshot".to_string(),
+        ));
+    }
+    let mut slot_key = [0u8; SLOT_KEY_SIZE];
+    reader.read_exact(&mut slot_key).map_err(read_error)?;
+    let slot = Slot::from_bytes_key(&slot_key);
+    let mut hash = HashXof([0u8; HASH_XOF_SIZE_BYTES]);
+    reader.read_exact(&mut hash.0).map_err(read_error)?;
+    Ok((slot, hash))
+}
+
+fn write_snapshot(
+    db: &dyn MassaDBController,
+    path: &Path,
+    slot: &Slot,
+    hash: &HashXof<HASH_XOF_SIZE_BYTES>,
+) -> std::io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    writer.write_all(SNAPSHOT_MAGIC)?;
+    writer.write_all(&slot.to_bytes_key())?;
+    writer.write_all(&hash.0)?;
+    for (ident, column) in [(STATE_IDENT, STATE_CF), (VERSIONING_IDENT, VERSIONING_CF)] {
+        for (key, value) in db.iterator_cf(column, MassaIteratorMode::Start) {
+            writer.write_all(&[ident])?;
+            writer.write_all(&(key.len() as u32).to_be_bytes())?;
+            writer.write_all(&key)?;
+            writer.write_all(&(value.len() as u32).to_be_bytes())?;
+            writer.write_all(&value)?;
+        }
+    }
+    writer.flush()
+}
+
+/// Read the next entry of a snapshot, if any
+fn read_entry(reader: &mut impl Read) -> std::io::Result<Option<(u8, Vec<u8>, Vec<u8>)>> {
+    let mut ident = [0u8; 1];
+    match reader.read_exact(&mut ident) {
+        Ok(()) => {}
+        Err(err) if err.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+        Err(err) => return Err(err),
+    }
+    let key = read_bytes(reader)?;
+    let value = read_bytes(reader)?;
+    Ok(Some((ident[0], key, value)))
+}
+
+fn read_bytes(reader: &mut impl Read) -> std::io::Result<Vec<u8>> {
+    let mut len = [0u8; 4];
+    reader.read_exact(&mut len)?;
+    let mut bytes = vec![0u8; u32::from_be_bytes(len) as usize];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn empty_batch(slot: Slot) -> StreamBatch<Slot> {
+    StreamBatch {
+        new_elements: Default::default(),
+        updates_on_previous_elements: Default::default(),
+        change_id: slot,
+    }
+}
+
+fn read_error(err: std::io::Error) -> MassaDBError {
+    MassaDBError::SnapshotError(format!("could not read the snapshot: {}", err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MassaDB;
+    use massa_db_exports::{DBBatch, MassaDBConfig};
+    use massa_models::config::THREAD_COUNT;
+    use std::collections::BTreeMap;
+    use tempfile::tempdir;
+
+    fn new_db(path: &Path) -> MassaDB {
+        MassaDB::new(MassaDBConfig {
+            path: path.to_path_buf(),
+            max_history_length: 100,
+            max_final_state_elements_size: 100,
+            max_versioning_elements_size: 100,
+            thread_count: THREAD_COUNT,
+        })
+    }
+
+    fn dump_column(db: &MassaDB, column: &str) -> BTreeMap<Vec<u8>, Vec<u8>> {
+        db.iterator_cf(column, MassaIteratorMode::Start).collect()
+    }
+
+    #[test]
+    fn test_snapshot_export_import() {
+        let temp_dir = tempdir().unwrap();
+        let mut db = new_db(&temp_dir.path().join("db"));
+        let mut batch = DBBatch::new();
+        let mut versioning_batch = DBBatch::new();
+        for i in 0..100u8 {
+            batch.insert(vec![1, i], Some(vec![i; i as usize]));
+        }
+        versioning_batch.insert(vec![2], Some(vec![3, 4]));
+        let slot = Slot::new(3, 1);
+        db.write_batch(batch, versioning_batch, Some(slot));
+
+        let snapshot_path = temp_dir.path().join("snapshot");
+        let (exported_slot, hash) = export_snapshot(&db, &snapshot_path).unwrap();
+        assert_eq!(exported_slot, slot);
+        assert_eq!(read_snapshot_header(&snapshot_path).unwrap(), (slot, hash));
+
+        // the imported state is identical to the exported one
+        let mut imported_db = new_db(&temp_dir.path().join("imported_db"));
+        assert_eq!(
+            import_snapshot(&mut imported_db, &snapshot_path).unwrap(),
+            slot
+        );
+        assert_eq!(imported_db.get_change_id().unwrap(), slot);
+        assert_eq!(imported_db.get_xof_db_hash(), hash);
+        assert_eq!(
+            dump_column(&imported_db, STATE_CF),
+            dump_column(&db, STATE_CF)
+        );
+        assert_eq!(
+            dump_column(&imported_db, VERSIONING_CF),
+            dump_column(&db, VERSIONING_CF)
+        );
+
+        // the database must be empty
+        assert!(import_snapshot(&mut imported_db, &snapshot_path).is_err());
+
+        // a corrupted snapshot is rejected
+        let mut content = std::fs::read(&snapshot_path).unwrap();
+        // first byte of the key of the first state entry
+        content[SNAPSHOT_MAGIC.len() + SLOT_KEY_SIZE + HASH_XOF_SIZE_BYTES + 5] ^= 1;
+        std::fs::write(&snapshot_path, content).unwrap();
+        let mut corrupted_db = new_db(&temp_dir.path().join("corrupted_db"));
+        assert!(import_snapshot(&mut corrupted_db, &snapshot_path).is_err());
+    }
+
+    #[test]
+    fn test_snapshot_hash_check() {
+        let temp_dir = tempdir().unwrap();
+        let mut db = new_db(&temp_dir.path().join("db"));
+        let mut batch = DBBatch::new();
+        batch.insert(vec![1, 2], Some(vec![3, 4]));
+        let slot = Slot::new(5, 0);
+        db.write_batch(batch, DBBatch::new(), Some(slot));
+        let snapshot_path = temp_dir.path().join("snapshot");
+        let (_, hash) = export_snapshot(&db, &snapshot_path).unwrap();
+        let trusted_hash = hash.to_string();
+
+        assert_eq!(
+            check_snapshot_hash(&snapshot_path, &trusted_hash).unwrap(),
+            slot
+        );
+        // another hash is not trusted
+        let other_hash = HashXof::<HASH_XOF_SIZE_BYTES>::compute_from(b"other state");
+        assert!(check_snapshot_hash(&snapshot_path, &other_hash.to_string()).is_err());
+
+        // a snapshot whose header hash was changed is rejected
+        let mut content = std::fs::read(&snapshot_path).unwrap();
+        content[SNAPSHOT_MAGIC.len() + SLOT_KEY_SIZE] ^= 1;
+        std::fs::write(&snapshot_path, content).unwrap();
+        assert!(check_snapshot_hash(&snapshot_path, &trusted_hash).is_err());
+
+        // so is a file that is not a snapshot
+        std::fs::write(&snapshot_path, b"not a snapshot").unwrap();
+        assert!(check_snapshot_hash(&snapshot_path, &trusted_hash).is_err());
+    }
+}
@@ -1,5 +1,6 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
 
+use crate::export::SerializedSlotExecutionOutput;
 use crate::types::SlotExecutionOutput;
 
 #[cfg(feature = "execution-trace")]
@@ -10,6 +11,9 @@ use crate::types::SlotAbiCallStack;
 pub struct ExecutionChannels {
     /// Broadcast channel for new slot execution outputs
     pub slot_execution_output_sender: tokio::sync::broadcast::Sender<SlotExecutionOutput>,
+    /// Broadcast channel for new slot execution outputs exported in JSON or CBOR
+    pub slot_execution_serialized_output_sender:
+        tokio::sync::broadcast::Sender<SerializedSlotExecutionOutput>,
     #[cfg(feature = "execution-trace")]
     /// Broadcast channel for execution traces (abi call stacks, boolean true if the slot is finalized, false otherwise)
     pub slot_execution_traces_sender: tokio::sync::broadcast::Sender<(SlotAbiCallStack, bool)>,
@@ -3,17 +3,21 @@
 //! This module exports generic traits representing interfaces for interacting with the Execution worker
 
 use crate::types::{
-    ExecutionBlockMetadata, ExecutionQueryRequest, ExecutionQueryResponse, ReadOnlyExecutionRequest,
+    ExecutionBlockMetadata, ExecutionQueryRequest, ExecutionQueryResponse,
+    OperationSimulationOutput, ReadOnlyExecutionRequest, SlotExecutionStats, StateDiff,
 };
 
 use crate::ExecutionError;
 use crate::{ExecutionAddressInfo, ReadOnlyExecutionOutput};
+use massa_ledger_exports::LedgerEntryInfo;
 use massa_models::address::Address;
 use massa_models::amount::Amount;
 use massa_models::block_id::BlockId;
 use massa_models::denunciation::DenunciationIndex;
-use massa_models::execution::EventFilter;
-use massa_models::operation::OperationId;
+use massa_models::execution::{
+    AsyncMessageFeeSuggestion, AsyncMessageFilter, EventFilter, PendingAsyncMessage,
+};
+use massa_models::operation::{OperationAddressRole, OperationId, SecureShareOperation};
 use massa_models::output_event::SCOutputEvent;
 use massa_models::prehash::PreHashMap;
 use massa_models::slot::Slot;
@@ -22,7 +26,10 @@ use std::collections::BTreeMap;
 use std::collections::HashMap;
 
 #[cfg(feature = "execution-trace")]
-use crate::types::{AbiTrace, SlotAbiCallStack, Transfer};
+use crate::types::{
+    AbiTrace, DebugCommand, DebugSessionState, GasProfile, OperationTrace, SlotAbiCallStack,
+    Transfer,
+};
 
 #[cfg_attr(feature = "test-exports", mockall::automock)]
 /// interface that communicates with the execution worker thread
@@ -51,6 +58,17 @@ pub trait ExecutionController: Send + Sync {
     /// * operation id
     fn get_filtered_sc_output_event(&self, filter: EventFilter) -> Vec<SCOutputEvent>;
 
+    /// Get the asynchronous messages of the final pool, from the highest priority to the lowest,
+    /// optionally filtered by:
+    /// * sender address
+    /// * destination address
+    /// * execution status
+    fn get_async_messages(&self, filter: AsyncMessageFilter) -> Vec<PendingAsyncMessage>;
+
+    /// Get the occupancy of the asynchronous pool, including the messages emitted in the active
+    /// slots, and the minimum fee for a new message using `max_gas` to be retained in it
+    fn get_async_message_fee_suggestion(&self, max_gas: u64) -> AsyncMessageFeeSuggestion;
+
     /// Get the final and active values of balance.
     ///
     /// # Return value
@@ -98,6 +116,18 @@ pub trait ExecutionController: Send + Sync {
         req: ReadOnlyExecutionRequest,
     ) -> Result<ReadOnlyExecutionOutput, ExecutionError>;
 
+    /// Simulate the execution of an operation against the current speculative state,
+    /// as if it was included in the next block of its thread.
+    /// The simulation does not modify the consensus state, and the operation is not propagated.
+    ///
+    /// # returns
+    /// The effects of the operation and the status it would have, or an error if the simulation
+    /// could not be run
+    fn simulate_operation(
+        &self,
+        operation: SecureShareOperation,
+    ) -> Result<OperationSimulationOutput, ExecutionError>;
+
     /// Check if a denunciation has been executed given a `DenunciationIndex`
     /// (speculative, final)
     fn get_denunciation_execution_status(
@@ -111,6 +141,10 @@ pub trait ExecutionController: Send + Sync {
     /// Get execution statistics
     fn get_stats(&self) -> ExecutionStats;
 
+    /// Get the execution statistics of the latest final slots, at most `last_slot_count` of them,
+    /// sorted by slot
+    fn get_slot_execution_stats(&self, last_slot_count: usize) -> Vec<(Slot, SlotExecutionStats)>;
+
     #[cfg(feature = "execution-trace")]
     /// Get the abi call stack for a given operation id
     fn get_operation_abi_call_stack(&self, operation_id: OperationId) -> Option<Vec<AbiTrace>>;
@@ -127,6 +161,101 @@ pub trait ExecutionController: Send + Sync {
     /// Get the transfer of MAS for a given operation id
     fn get_transfer_for_op(&self, op_id: &OperationId) -> Option<Transfer>;
 
+    #[cfg(feature = "execution-trace")]
+    /// Get the execution trace of a given operation id:
+    /// its ABI calls, gas consumed, datastore accesses and transfers
+    fn get_operation_trace(&self, op_id: &OperationId) -> Option<OperationTrace>;
+
+    #[cfg(feature = "execution-trace")]
+    /// Get the gas charged for the ABI calls of the executed slots from `start` (included)
+    /// to `end` (excluded), among the slots kept in the execution trace history
+    fn get_gas_profile(&self, start: Slot, end: Slot) -> GasProfile;
+
+    #[cfg(feature = "execution-trace")]
+    /// Executes a read-only request and opens a debug session to step through its ABI calls.
+    /// The session is paused before the first ABI call.
+    ///
+    /// # Arguments
+    /// * `req`: the read-only request to debug
+    /// * `breakpoints`: names of the ABIs on which the session pauses when continuing
+    fn start_debug_session(
+        &self,
+        req: ReadOnlyExecutionRequest,
+        breakpoints: Vec<String>,
+    ) -> Result<DebugSessionState, ExecutionError>;
+
+    #[cfg(feature = "execution-trace")]
+    /// Moves a debug session forward, by one ABI call or up to the next breakpoint
+    fn resume_debug_session(
+        &self,
+        session_id: u64,
+        command: DebugCommand,
+    ) -> Result<DebugSessionState, ExecutionError>;
+
+    #[cfg(feature = "execution-trace")]
+    /// Replaces the breakpoints of a debug session
+    fn set_debug_breakpoints(
+        &self,
+        session_id: u64,
+        breakpoints: Vec<String>,
+    ) -> Result<DebugSessionState, ExecutionError>;
+
+    #[cfg(feature = "execution-trace")]
+    /// Get a datastore entry as seen by the execution of a debug session:
+    /// the changes made by the execution applied over the current candidate state
+    fn get_debug_datastore_entry(
+        &self,
+        session_id: u64,
+        address: &Address,
+        key: &[u8],
+    ) -> Result<Option<Vec<u8>>, ExecutionError>;
+
+    #[cfg(feature = "execution-trace")]
+    /// Closes a debug session
+    fn stop_debug_session(&self, session_id: u64) -> Result<(), ExecutionError>;
+
+    /// Get the final operations in which an address has the given role, from the most recent one.
+    ///
+    /// # Arguments
+    /// * `address`: the address to look for
+    /// * `role`: whether the address is the emitter or the recipient of the operations
+    /// * `offset`: number of operations to skip
+    /// * `limit`: max number of operations to return
+    ///
+    /// # Returns
+    /// The operation ids with the slot of their block, or an error if the operation index is disabled
+    fn get_operations_by_address(
+        &self,
+        address: &Address,
+        role: OperationAddressRole,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<(Slot, OperationId)>, ExecutionError>;
+
+    /// Get the changes made to the final state between two final slots,
+    /// aggregating the changes of the slots following `from_slot` up to `to_slot` (included).
+    ///
+    /// # Returns
+    /// The state diff, or an error if the changes of those slots are not kept anymore
+    fn get_state_diff(&self, from_slot: Slot, to_slot: Slot) -> Result<StateDiff, ExecutionError>;
+
+    /// Get the summaries of up to `page_size` entries of the final ledger in address order,
+    /// starting from the one of `page_token`, or from the first entry if `None`.
+    ///
+    /// # Returns
+    /// The summaries, and the address of the entry following them if any,
+    /// to be used as the token of the next page
+    fn iter_ledger(
+        &self,
+        page_token: Option<Address>,
+        page_size: usize,
+    ) -> (Vec<LedgerEntryInfo>, Option<Address>);
+
+    /// Suspend or resume the writes to the persistent operation and event indexes,
+    /// for example when the node storage exceeds its allowed disk usage.
+    /// The final operations and events are not indexed while the writes are suspended.
+    fn set_index_writes_suspended(&self, suspended: bool);
+
     /// Returns a boxed clone of self.
     /// Useful to allow cloning `Box<dyn ExecutionController>`.
     fn clone_box(&self) -> Box<dyn ExecutionController>;
@@ -36,6 +36,9 @@ pub enum ExecutionError {
     /// `Transaction` error: {0}
     TransactionError(String),
 
+    /// `CancelAsyncMessage` error: {0}
+    CancelAsyncMessageError(String),
+
     /// Block gas error: {0}
     BlockGasError(String),
 
@@ -67,6 +70,21 @@ pub enum ExecutionError {
 
     /// Factory error: {0}
     FactoryError(#[from] FactoryError),
+
+    /// Operation index error: {0}
+    OperationIndexError(String),
+
+    /// Debug session error: {0}
+    DebugSessionError(String),
+
+    /// State diff error: {0}
+    StateDiffError(String),
+
+    /// Read-only quota error: {0}
+    ReadOnlyQuotaError(String),
+
+    /// Export error: {0}
+    ExportError(String),
 }
 
 /// Execution query errors
@@ -0,0 +1,410 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Canonical serializations of the execution outputs, for external indexers.
+//!
+//! The binary serializations of the state changes follow the internal structures of the node.
+//! The structures of this file have stable field names, the amounts being written as decimal
+//! strings and the maps as lists sorted by key, so that they can be exported in JSON or CBOR.
+
+use crate::error::ExecutionError;
+use crate::types::{ExecutionOutput, SlotExecutionOutput};
+use massa_async_pool::{AsyncMessage, AsyncMessageUpdate};
+use massa_final_state::StateChanges;
+use massa_hash::Hash;
+use massa_ledger_exports::{
+    LedgerEntry, LedgerEntryUpdate, SetOrDelete, SetOrKeep, SetUpdateOrDelete,
+};
+use massa_models::address::Address;
+use massa_models::amount::Amount;
+use massa_models::block_id::BlockId;
+use massa_models::denunciation::DenunciationIndex;
+use massa_models::operation::OperationId;
+use massa_models::output_event::SCOutputEvent;
+use massa_models::slot::Slot;
+use serde::{Deserialize, Serialize};
+
+/// Format of the exported execution outputs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    /// JSON text
+    Json,
+    /// CBOR binary (RFC 8949)
+    Cbor,
+}
+
+impl ExportFormat {
+    /// Serialize a value in this format
+    pub fn serialize<T: Serialize>(&self, value: &T) -> Result<Vec<u8>, ExecutionError> {
+        let mut buffer = Vec::new();
+        match self {
+            ExportFormat::Json => serde_json::to_writer(&mut buffer, value)
+                .map_err(|err| ExecutionError::ExportError(err.to_string()))?,
+            ExportFormat::Cbor => ciborium::into_writer(value, &mut buffer)
+                .map_err(|err| ExecutionError::ExportError(err.to_string()))?,
+        }
+        Ok(buffer)
+    }
+}
+
+/// Execution output of a slot exported in the configured format
+#[derive(Debug, Clone)]
+pub struct SerializedSlotExecutionOutput {
+    /// executed slot
+    pub slot: Slot,
+    /// whether the slot is final
+    pub is_final: bool,
+    /// format of `data`
+    pub format: ExportFormat,
+    /// serialized `ExecutionOutputExport`
+    pub data: Vec<u8>,
+}
+
+/// Exported output of the execution of a slot
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionOutputExport {
+    /// executed slot
+    pub slot: Slot,
+    /// whether the slot is final
+    pub is_final: bool,
+    /// id of the block executed at that slot (None if miss)
+    pub block_id: Option<BlockId>,
+    /// state changes caused by the execution of the slot
+    pub state_changes: StateChangesExport,
+    /// events emitted by the execution of the slot
+    pub events: Vec<EventExport>,
+    /// gas used by the operations of the block (0 if miss)
+    pub gas_used: u64,
+    /// number of operations included in the block (0 if miss)
+    pub operation_count: usize,
+}
+
+impl ExecutionOutputExport {
+    /// Export an execution output
+    pub fn new(output: &ExecutionOutput, is_final: bool) -> Self {
+        Self {
+            slot: output.slot,
+            is_final,
+            block_id: output.block_info.as_ref().map(|info| info.block_id),
+            state_changes: StateChangesExport::from(&output.state_changes),
+            events: output.events.0.iter().map(EventExport::from).collect(),
+            gas_used: output.stats.gas_used,
+            operation_count: output.stats.operation_count,
+        }
+    }
+}
+
+impl From<&SlotExecutionOutput> for ExecutionOutputExport {
+    fn from(output: &SlotExecutionOutput) -> Self {
+        match output {
+            SlotExecutionOutput::ExecutedSlot(output) => Self::new(output, false),
+            SlotExecutionOutput::FinalizedSlot(output) => Self::new(output, true),
+        }
+    }
+}
+
+/// Exported smart contract event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventExport {
+    /// index of the event in the slot
+    pub index_in_slot: u64,
+    /// call stack of the emission, the emitter being the last address
+    pub call_stack: Vec<Address>,
+    /// operation at the origin of the event, if any
+    pub origin_operation_id: Option<OperationId>,
+    /// whether the smart contract that emitted the event failed
+    pub is_error: bool,
+    /// event data
+    pub data: String,
+}
+
+impl From<&SCOutputEvent> for EventExport {
+    fn from(event: &SCOutputEvent) -> Self {
+        Self {
+            index_in_slot: event.context.index_in_slot,
+            call_stack: event.context.call_stack.iter().copied().collect(),
+            origin_operation_id: event.context.origin_operation_id,
+            is_error: event.context.is_error,
+            data: event.data.clone(),
+        }
+    }
+}
+
+/// Exported state changes
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateChangesExport {
+    /// changes of the ledger entries, sorted by address
+    pub ledger_changes: Vec<LedgerChangeExport>,
+    /// changes of the asynchronous messages, sorted by priority
+    pub async_pool_changes: Vec<AsyncMessageChangeExport>,
+    /// new roll counts, sorted by address
+    pub roll_changes: Vec<RollChangeExport>,
+    /// updated production statistics, sorted by address
+    pub production_stats: Vec<ProductionStatsExport>,
+    /// set deferred credits, sorted by slot and address
+    pub deferred_credits: Vec<DeferredCreditExport>,
+    /// extra block seed bits
+    pub seed_bits: Vec<bool>,
+    /// newly executed operations, sorted by id
+    pub executed_ops: Vec<ExecutedOpExport>,
+    /// newly executed denunciations, sorted by slot and index
+    pub executed_denunciations: Vec<DenunciationIndex>,
+    /// new execution trail hash, if changed
+    pub execution_trail_hash: Option<Hash>,
+}
+
+impl From<&StateChanges> for StateChangesExport {
+    fn from(changes: &StateChanges) -> Self {
+        let mut ledger_changes: Vec<LedgerChangeExport> = changes
+            .ledger_changes
+            .0
+            .iter()
+            .map(|(address, change)| LedgerChangeExport {
+                address: *address,
+                change: LedgerEntryChangeExport::from(change),
+            })
+            .collect();
+        ledger_changes.sort_by_key(|change| change.address);
+
+        let async_pool_changes = changes
+            .async_pool_changes
+            .0
+            .iter()
+            .map(|(id, change)| AsyncMessageChangeExport {
+                emission_slot: id.1,
+                emission_index: id.2,
+                change: match change {
+                    SetUpdateOrDelete::Set(message) => AsyncMessageChangeKindExport::Set {
+                        message: message.clone(),
+                    },
+                    SetUpdateOrDelete::Update(update) => AsyncMessageChangeKindExport::Update {
+                        update: update.clone(),
+                    },
+                    SetUpdateOrDelete::Delete => AsyncMessageChangeKindExport::Delete,
+                },
+            })
+            .collect();
+
+        let pos_changes = &changes.pos_changes;
+        let mut roll_changes: Vec<RollChangeExport> = pos_changes
+            .roll_changes
+            .iter()
+            .map(|(address, roll_count)| RollChangeExport {
+                address: *address,
+                roll_count: *roll_count,
+            })
+            .collect();
+        roll_changes.sort_by_key(|change| change.address);
+        let mut production_stats: Vec<ProductionStatsExport> = pos_changes
+            .production_stats
+            .iter()
+            .map(|(address, stats)| ProductionStatsExport {
+                address: *address,
+                block_success_count: stats.block_success_count,
+                block_failure_count: stats.block_failure_count,
+            })
+            .collect();
+        production_stats.sort_by_key(|stats| stats.address);
+        let mut deferred_credits = Vec::new();
+        for (slot, credits) in pos_changes.deferred_credits.credits.iter() {
+            let mut slot_credits: Vec<DeferredCreditExport> = credits
+                .iter()
+                .map(|(address, amount)| DeferredCreditExport {
+                    slot: *slot,
+                    address: *address,
+                    amount: *amount,
+                })
+                .collect();
+            slot_credits.sort_by_key(|credit| credit.address);
+            deferred_credits.extend(slot_credits);
+        }
+
+        let mut executed_ops: Vec<ExecutedOpExport> = changes
+            .executed_ops_changes
+            .iter()
+            .map(|(operation_id, (success, expiry_slot))| ExecutedOpExport {
+                operation_id: *operation_id,
+                success: *success,
+                expiry_slot: *expiry_slot,
+            })
+            .collect();
+        executed_ops.sort_by_key(|op| op.operation_id);
+        let mut executed_denunciations: Vec<DenunciationIndex> = changes
+            .executed_denunciations_changes
+            .iter()
+            .copied()
+            .collect();
+        executed_denunciations.sort_by_key(|index| (*index.get_slot(), index.get_index().copied()));
+
+        StateChangesExport {
+            ledger_changes,
+            async_pool_changes,
+            roll_changes,
+            production_stats,
+            deferred_credits,
+            seed_bits: pos_changes.seed_bits.iter().by_vals().collect(),
+            executed_ops,
+            executed_denunciations,
+            execution_trail_hash: match changes.execution_trail_hash_change {
+                SetOrKeep::Set(hash) => Some(hash),
+                SetOrKeep::Keep => None,
+            },
+        }
+    }
+}
+
+/// Exported change of the ledger entry of an address
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LedgerChangeExport {
+    /// address of the entry
+    pub address: Address,
+    /// change of the entry
+    pub change: LedgerEntryChangeExport,
+}
+
+/// Exported change of a ledger entry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LedgerEntryChangeExport {
+    /// the entry is created or replaced
+    Set {
+        /// balance of the entry
+        balance: Amount,
+        /// bytecode of the entry
+        bytecode: Vec<u8>,
+        /// datastore of the entry
+        datastore: Vec<DatastoreChangeExport>,
+    },
+    /// some fields of the entry are updated
+    Update {
+        /// new balance, if changed
+        balance: Option<Amount>,
+        /// new bytecode, if changed
+        bytecode: Option<Vec<u8>>,
+        /// changed datastore entries
+        datastore: Vec<DatastoreChangeExport>,
+    },
+    /// the entry is deleted
+    Delete,
+}
+
+impl From<&SetUpdateOrDelete<LedgerEntry, LedgerEntryUpdate>> for LedgerEntryChangeExport {
+    fn from(change: &SetUpdateOrDelete<LedgerEntry, LedgerEntryUpdate>) -> Self {
+        match change {
+            SetUpdateOrDelete::Set(entry) => LedgerEntryChangeExport::Set {
+                balance: entry.balance,
+                bytecode: entry.bytecode.0.clone(),
+                datastore: entry
+                    .datastore
+                    .iter()
+                    .map(|(key, value)| DatastoreChangeExport {
+                        key: key.clone(),
+                        value: Some(value.clone()),
+                    })
+                    .collect(),
+            },
+            SetUpdateOrDelete::Update(update) => LedgerEntryChangeExport::Update {
+                balance: match update.balance {
+                    SetOrKeep::Set(balance) => Some(balance),
+                    SetOrKeep::Keep => None,
+                },
+                bytecode: match &update.bytecode {
+                    SetOrKeep::Set(bytecode) => Some(bytecode.0.clone()),
+                    SetOrKeep::Keep => None,
+                },
+                datastore: update
+                    .datastore
+                    .iter()
+                    .map(|(key, value)| DatastoreChangeExport {
+                        key: key.clone(),
+                        value: match value {
+                            SetOrDelete::Set(value) => Some(value.clone()),
+                            SetOrDelete::Delete => None,
+                        },
+                    })
+                    .collect(),
+            },
+            SetUpdateOrDelete::Delete => LedgerEntryChangeExport::Delete,
+        }
+    }
+}
+
+/// Exported change of a datastore entry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DatastoreChangeExport {
+    /// key of the entry
+    pub key: Vec<u8>,
+    /// new value of the entry, None if it is deleted
+    pub value: Option<Vec<u8>>,
+}
+
+/// Exported change of an asynchronous message
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AsyncMessageChangeExport {
+    /// emission slot of the message
+    pub emission_slot: Slot,
+    /// index of the message in its emission slot
+    pub emission_index: u64,
+    /// change of the message
+    pub change: AsyncMessageChangeKindExport,
+}
+
+/// Exported kind of change of an asynchronous message
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AsyncMessageChangeKindExport {
+    /// the message is emitted
+    Set {
+        /// emitted message
+        message: AsyncMessage,
+    },
+    /// some fields of the message are updated
+    Update {
+        /// updated fields
+        update: AsyncMessageUpdate,
+    },
+    /// the message is executed or dropped
+    Delete,
+}
+
+/// Exported new roll count of an address
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RollChangeExport {
+    /// address of the staker
+    pub address: Address,
+    /// new roll count (0 removes the address from the registry)
+    pub roll_count: u64,
+}
+
+/// Exported production statistics of an address
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProductionStatsExport {
+    /// address of the staker
+    pub address: Address,
+    /// number of successfully created blocks
+    pub block_success_count: u64,
+    /// number of missed blocks
+    pub block_failure_count: u64,
+}
+
+/// Exported deferred credit
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeferredCreditExport {
+    /// slot at which the credit is applied
+    pub slot: Slot,
+    /// credited address
+    pub address: Address,
+    /// credited amount (0 cancels the credit)
+    pub amount: Amount,
+}
+
+/// Exported executed operation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutedOpExport {
+    /// id of the operation
+    pub operation_id: OperationId,
+    /// whether the execution of the operation succeeded
+    pub success: bool,
+    /// slot at which the operation expires
+    pub expiry_slot: Slot,
+}
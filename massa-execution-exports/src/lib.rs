@@ -34,6 +34,9 @@
 //! ## `event_store.rs`
 //! Defines an indexed, finite-size storage system for execution events.
 //!
+//! ## `export.rs`
+//! Defines the canonical JSON and CBOR exports of the execution outputs, for external indexers.
+//!
 //! ## `types.rs`
 //! Defines useful shared structures.
 //!
@@ -48,6 +51,7 @@ mod channels;
 mod controller_traits;
 mod error;
 mod event_store;
+mod export;
 /// mapping grpc
 pub mod mapping_grpc;
 mod settings;
@@ -59,14 +63,22 @@ pub use controller_traits::MockExecutionController;
 pub use controller_traits::{ExecutionController, ExecutionManager};
 pub use error::{ExecutionError, ExecutionQueryError};
 pub use event_store::EventStore;
+pub use export::{
+    AsyncMessageChangeExport, AsyncMessageChangeKindExport, DatastoreChangeExport,
+    DeferredCreditExport, EventExport, ExecutedOpExport, ExecutionOutputExport, ExportFormat,
+    LedgerChangeExport, LedgerEntryChangeExport, ProductionStatsExport, RollChangeExport,
+    SerializedSlotExecutionOutput, StateChangesExport,
+};
 pub use massa_sc_runtime::GasCosts;
-pub use settings::{ExecutionConfig, StorageCostsConstants};
+pub use settings::{EventOverflowPolicy, ExecutionConfig, StorageCostsConstants};
 pub use types::{
-    ExecutedBlockInfo, ExecutionAddressInfo, ExecutionBlockMetadata, ExecutionOutput,
-    ExecutionQueryCycleInfos, ExecutionQueryExecutionStatus, ExecutionQueryRequest,
-    ExecutionQueryRequestItem, ExecutionQueryResponse, ExecutionQueryResponseItem,
-    ExecutionQueryStakerInfo, ExecutionStackElement, ReadOnlyCallRequest, ReadOnlyExecutionOutput,
-    ReadOnlyExecutionRequest, ReadOnlyExecutionTarget, SlotExecutionOutput,
+    EventOverflow, ExecutedBlockInfo, ExecutionAddressInfo, ExecutionBlockMetadata,
+    ExecutionOutput, ExecutionQueryCycleInfos, ExecutionQueryExecutionStatus,
+    ExecutionQueryRequest, ExecutionQueryRequestItem, ExecutionQueryResponse,
+    ExecutionQueryResponseItem, ExecutionQueryStakerInfo, ExecutionStackElement,
+    OperationSimulationOutput, OperationSimulationStatus, ReadOnlyCallRequest,
+    ReadOnlyExecutionOutput, ReadOnlyExecutionRequest, ReadOnlyExecutionTarget, ReadOnlyPriority,
+    ReadOnlyStateOverride, SlotExecutionOutput, SlotExecutionStats, StateDiff,
 };
 
 #[cfg(any(feature = "test-exports", feature = "gas_calibration"))]
@@ -74,5 +86,7 @@ pub mod test_exports;
 
 #[cfg(feature = "execution-trace")]
 pub use types::{
-    AbiTrace, SCRuntimeAbiTraceType, SCRuntimeAbiTraceValue, SlotAbiCallStack, Transfer,
+    AbiGasUsage, AbiTrace, DebugCommand, DebugSessionState, DebugStep, GasProfile, OperationTrace,
+    SCRuntimeAbiTraceType, SCRuntimeAbiTraceValue, SlotAbiCallStack, StorageAccess,
+    StorageAccessType, Transfer,
 };
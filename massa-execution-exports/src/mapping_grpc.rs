@@ -5,7 +5,7 @@ use std::str::FromStr;
 use crate::{
     ExecutionOutput, ExecutionQueryCycleInfos, ExecutionQueryError, ExecutionQueryExecutionStatus,
     ExecutionQueryRequestItem, ExecutionQueryResponseItem, ExecutionQueryStakerInfo,
-    SlotExecutionOutput,
+    SlotExecutionOutput, SlotExecutionStats,
 };
 use grpc_api::execution_query_request_item as exec;
 use massa_models::address::Address;
@@ -14,6 +14,7 @@ use massa_models::execution::EventFilter;
 use massa_models::mapping_grpc::to_denunciation_index;
 use massa_models::operation::OperationId;
 use massa_models::prehash::{CapacityAllocator, PreHashSet};
+use massa_models::slot::Slot;
 use massa_proto_rs::massa::api::v1 as grpc_api;
 use massa_proto_rs::massa::model::v1 as grpc_model;
 
@@ -134,6 +135,11 @@ pub fn to_querystate_filter(
                 let event_filter = to_event_filter(value.filters)?;
                 Ok(ExecutionQueryRequestItem::Events(event_filter))
             }
+            exec::RequestItem::AddressIncomingAsyncMessagesFinal(value) => Ok(
+                ExecutionQueryRequestItem::AddressIncomingAsyncMessagesFinal(Address::from_str(
+                    &value.address,
+                )?),
+            ),
         }
     } else {
         Err(ModelsError::ErrorRaised("no filter provided".to_string()))
@@ -266,6 +272,13 @@ fn to_execution_query_result(
                 },
             )
         }
+        ExecutionQueryResponseItem::IncomingAsyncMessages(result) => {
+            grpc_api::execution_query_response_item::ResponseItem::IncomingAsyncMessages(
+                grpc_api::IncomingAsyncMessagesWrapper {
+                    messages: result.into_iter().map(|message| message.into()).collect(),
+                },
+            )
+        }
     };
 
     grpc_api::ExecutionQueryResponseItem {
@@ -341,6 +354,21 @@ impl From<ExecutionOutput> for grpc_model::ExecutionOutput {
     }
 }
 
+/// Convert the execution statistics of a slot to a `grpc_model::SlotExecutionStats`
+pub fn to_slot_execution_stats(
+    slot: Slot,
+    stats: SlotExecutionStats,
+) -> grpc_model::SlotExecutionStats {
+    grpc_model::SlotExecutionStats {
+        slot: Some(slot.into()),
+        gas_used: stats.gas_used,
+        gas_limit: stats.gas_limit,
+        operation_count: stats.operation_count as u64,
+        event_count: stats.event_count as u64,
+        execution_time_micros: stats.execution_time.as_micros() as u64,
+    }
+}
+
 impl From<ExecutionQueryError> for grpc_model::Error {
     fn from(value: ExecutionQueryError) -> Self {
         match value {
@@ -2,12 +2,24 @@
 
 //! This module provides the structures used to provide configuration parameters to the Execution system
 
+use crate::export::ExportFormat;
 use massa_models::amount::Amount;
 use massa_sc_runtime::GasCosts;
 use massa_time::MassaTime;
 use num::rational::Ratio;
+use serde::Deserialize;
 use std::path::PathBuf;
 
+/// Handling of the events emitted in a slot beyond the max number of events per slot
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventOverflowPolicy {
+    /// the overflowing events are dropped, the first of them being replaced by a marker event
+    Truncate,
+    /// the overflowing events are kept apart from the events of the slot
+    Spill,
+}
+
 /// Storage cost constants
 #[derive(Debug, Clone, Copy)]
 pub struct StorageCostsConstants {
@@ -24,8 +36,20 @@ pub struct StorageCostsConstants {
 pub struct ExecutionConfig {
     /// read-only execution request queue length
     pub readonly_queue_length: usize,
+    /// max number of read-only requests queued or being executed at once (0 for no limit)
+    pub max_concurrent_readonly_requests: usize,
+    /// max number of read-only requests of a same caller queued or being executed at once
+    /// (0 for no limit)
+    pub max_concurrent_readonly_requests_per_caller: usize,
+    /// max number of read-only call outputs kept in cache (0 disables the cache)
+    pub readonly_cache_size: usize,
     /// maximum number of SC output events kept in cache
     pub max_final_events: usize,
+    /// maximum number of SC output events of a slot, the overflowing ones being handled
+    /// according to `event_overflow_policy`
+    pub max_events_per_slot: usize,
+    /// handling of the events emitted beyond `max_events_per_slot`
+    pub event_overflow_policy: EventOverflowPolicy,
     /// maximum available gas for asynchronous messages execution
     pub max_async_gas: u64,
     /// constant cost for async messages
@@ -70,6 +94,8 @@ pub struct ExecutionConfig {
     pub max_read_only_gas: u64,
     /// Gas costs
     pub gas_costs: GasCosts,
+    /// Path to the gas costs of the ABIs, used to profile the gas charged for the ABI calls
+    pub abi_gas_costs_file: PathBuf,
     /// Gas used by a transaction, a roll buy or a roll sell)
     pub base_operation_gas_cost: u64,
     /// last start period, used to attach to the correct execution slot if the network has restarted
@@ -90,6 +116,9 @@ pub struct ExecutionConfig {
     pub broadcast_enabled: bool,
     /// slot execution outputs channel capacity
     pub broadcast_slot_execution_output_channel_capacity: usize,
+    /// format in which the slot execution outputs are also broadcast, for external indexers
+    /// (None disables it)
+    pub broadcast_serialized_output_format: Option<ExportFormat>,
     /// max size of event data, in bytes
     pub max_event_size: usize,
     /// chain id
@@ -100,4 +129,26 @@ pub struct ExecutionConfig {
     pub broadcast_slot_execution_traces_channel_capacity: usize,
     /// Max execution traces slot to keep in trace history cache
     pub max_execution_traces_slot_limit: usize,
+    /// Max number of open debug sessions, the least recently used one being closed beyond it
+    pub max_debug_sessions: usize,
+    /// number of final slots whose state changes are kept to compute state diffs (0 disables them)
+    pub state_diff_history_length: usize,
+    /// number of final slots whose execution statistics are kept
+    pub slot_stats_history_length: usize,
+    /// whether the final operations are indexed by emitter and recipient address
+    pub operation_index_enabled: bool,
+    /// Path to the operation index storage
+    pub operation_index_path: PathBuf,
+    /// number of periods the operation index keeps the final operations for (0 keeps them forever)
+    pub operation_index_retention_periods: u64,
+    /// whether the final events are stored in a persistent index instead of in RAM
+    pub event_index_enabled: bool,
+    /// Path to the event index storage
+    pub event_index_path: PathBuf,
+    /// number of periods the event index keeps the final events for (0 keeps them forever)
+    pub event_index_retention_periods: u64,
+    /// balance below which a ledger entry without bytecode nor datastore is pruned
+    pub dust_account_max_balance: Amount,
+    /// number of ledger entries examined for dust account pruning at each final slot
+    pub dust_pruning_scan_length: usize,
 }
@@ -2,7 +2,7 @@
 
 //! This file defines testing tools related to the configuration
 
-use crate::{ExecutionConfig, StorageCostsConstants};
+use crate::{EventOverflowPolicy, ExecutionConfig, StorageCostsConstants};
 use massa_models::config::*;
 use massa_sc_runtime::GasCosts;
 use massa_time::MassaTime;
@@ -21,7 +21,12 @@ impl Default for ExecutionConfig {
 
         Self {
             readonly_queue_length: 100,
+            max_concurrent_readonly_requests: 100,
+            max_concurrent_readonly_requests_per_caller: 10,
+            readonly_cache_size: 100,
             max_final_events: 1000,
+            max_events_per_slot: 10_000,
+            event_overflow_policy: EventOverflowPolicy::Truncate,
             max_async_gas: MAX_ASYNC_GAS,
             async_msg_cst_gas_cost: ASYNC_MSG_CST_GAS_COST,
             thread_count: THREAD_COUNT,
@@ -55,6 +60,11 @@ impl Default for ExecutionConfig {
                 .into(),
             )
             .unwrap(),
+            abi_gas_costs_file: concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/../massa-node/base_config/gas_costs/abi_gas_costs.json"
+            )
+            .into(),
             base_operation_gas_cost: BASE_OPERATION_GAS_COST,
             last_start_period: 0,
             hd_cache_path: TempDir::new().unwrap().path().to_path_buf(),
@@ -65,6 +75,7 @@ impl Default for ExecutionConfig {
             denunciation_expire_periods: DENUNCIATION_EXPIRE_PERIODS,
             broadcast_enabled: true,
             broadcast_slot_execution_output_channel_capacity: 5000,
+            broadcast_serialized_output_format: None,
             max_event_size: 50_000,
             max_function_length: 1000,
             max_parameter_length: 1000,
@@ -72,6 +83,17 @@ impl Default for ExecutionConfig {
             broadcast_traces_enabled: true,
             broadcast_slot_execution_traces_channel_capacity: 5000,
             max_execution_traces_slot_limit: 320,
+            max_debug_sessions: 10,
+            state_diff_history_length: 10,
+            slot_stats_history_length: 10,
+            operation_index_enabled: false,
+            operation_index_path: TempDir::new().unwrap().path().to_path_buf(),
+            operation_index_retention_periods: 0,
+            event_index_enabled: false,
+            event_index_path: TempDir::new().unwrap().path().to_path_buf(),
+            event_index_retention_periods: 0,
+            dust_account_max_balance: DUST_ACCOUNT_MAX_BALANCE,
+            dust_pruning_scan_length: DUST_PRUNING_SCAN_LENGTH,
         }
     }
 }
@@ -4,6 +4,7 @@
 
 use crate::error::ExecutionQueryError;
 use crate::event_store::EventStore;
+use crate::settings::EventOverflowPolicy;
 use massa_final_state::StateChanges;
 use massa_hash::Hash;
 use massa_models::block_id::BlockId;
@@ -15,11 +16,13 @@ use massa_models::operation::OperationId;
 use massa_models::output_event::SCOutputEvent;
 use massa_models::prehash::PreHashSet;
 use massa_models::{
-    address::Address, address::ExecutionAddressCycleInfo, amount::Amount, slot::Slot,
+    address::Address, address::ExecutionAddressCycleInfo, address::IncomingAsyncMessage,
+    amount::Amount, slot::Slot,
 };
 use massa_pos_exports::ProductionStats;
 use massa_storage::Storage;
 use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
 
 #[cfg(feature = "execution-trace")]
 use massa_models::prehash::PreHashMap;
@@ -130,6 +133,9 @@ pub enum ExecutionQueryRequestItem {
 
     /// get filtered events. Returns ExecutionQueryResponseItem::Events
     Events(EventFilter),
+
+    /// gets the asynchronous messages (final) targeting an address, returns ExecutionQueryResponseItem::IncomingAsyncMessages(messages)
+    AddressIncomingAsyncMessagesFinal(Address),
 }
 
 /// Execution state query response item
@@ -154,6 +160,8 @@ pub enum ExecutionQueryResponseItem {
     CycleInfos(ExecutionQueryCycleInfos),
     /// Events
     Events(Vec<SCOutputEvent>),
+    /// asynchronous messages targeting an address
+    IncomingAsyncMessages(Vec<IncomingAsyncMessage>),
 }
 
 /// Execution status of an operation or denunciation
@@ -212,6 +220,10 @@ pub struct ExecutionAddressInfo {
 
     /// cycle information
     pub cycle_infos: Vec<ExecutionAddressCycleInfo>,
+
+    /// asynchronous messages of the final pool targeting the address, from the highest priority
+    /// to the lowest, at most `MAX_INCOMING_ASYNC_MESSAGES_PER_ADDRESS`
+    pub incoming_async_messages: Vec<IncomingAsyncMessage>,
 }
 
 #[cfg(feature = "execution-trace")]
@@ -358,11 +370,54 @@ pub struct ExecutionOutput {
     pub state_changes: StateChanges,
     /// events emitted by the execution step
     pub events: EventStore,
+    /// events emitted beyond the max number of events per slot, if any
+    pub event_overflow: Option<EventOverflow>,
+    /// gas usage, fullness and execution time of the slot
+    pub stats: SlotExecutionStats,
+    /// addresses whose ledger entry was read from the final state by the execution step
+    pub final_ledger_reads: PreHashSet<Address>,
     /// slot trace
     #[cfg(feature = "execution-trace")]
     pub slot_trace: Option<(SlotAbiCallStack, Vec<Transfer>)>,
 }
 
+/// Gas usage, fullness and execution time of an executed slot
+#[derive(Debug, Clone, Default)]
+pub struct SlotExecutionStats {
+    /// gas used by the operations of the block (0 if miss)
+    pub gas_used: u64,
+    /// max gas usable by the operations of a block
+    pub gas_limit: u64,
+    /// number of operations included in the block (0 if miss)
+    pub operation_count: usize,
+    /// number of events emitted during the execution of the slot, including the overflowing ones
+    pub event_count: usize,
+    /// wall-clock time spent executing the slot
+    pub execution_time: Duration,
+}
+
+/// Changes made to the final state between two final slots
+#[derive(Debug, Clone)]
+pub struct StateDiff {
+    /// slot from which the changes are aggregated (excluded)
+    pub from_slot: Slot,
+    /// slot up to which the changes are aggregated (included)
+    pub to_slot: Slot,
+    /// aggregated ledger, async pool, PoS, executed operations and denunciations changes
+    pub state_changes: StateChanges,
+}
+
+/// Events emitted in a slot beyond the max number of events per slot
+#[derive(Debug, Clone)]
+pub struct EventOverflow {
+    /// policy applied to the overflowing events
+    pub policy: EventOverflowPolicy,
+    /// number of events emitted beyond the limit
+    pub overflowing_event_count: usize,
+    /// overflowing events, kept with the `Spill` policy
+    pub spilled_events: EventStore,
+}
+
 #[cfg(feature = "execution-trace")]
 #[derive(Debug, Clone)]
 /// structure describing a transfer
@@ -383,6 +438,115 @@ pub struct Transfer {
     pub fee: Amount,
 }
 
+#[cfg(feature = "execution-trace")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// type of access to a datastore entry
+pub enum StorageAccessType {
+    /// the entry was read or checked for existence
+    Read,
+    /// the entry was set or appended to
+    Write,
+    /// the entry was deleted
+    Delete,
+}
+
+#[cfg(feature = "execution-trace")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// structure describing an access to a datastore entry made during an execution
+pub struct StorageAccess {
+    /// Address owning the datastore
+    pub address: Address,
+    /// Datastore key
+    pub key: Vec<u8>,
+    /// Type of access
+    pub access_type: StorageAccessType,
+}
+
+#[cfg(feature = "execution-trace")]
+#[derive(Debug, Clone)]
+/// structure describing the execution trace of an operation
+pub struct OperationTrace {
+    /// Operation id
+    pub op_id: OperationId,
+    /// Slot at which the operation was executed
+    pub slot: Slot,
+    /// Block including the operation
+    pub block_id: BlockId,
+    /// success or not
+    pub succeed: bool,
+    /// Gas consumed by the smart contract execution of the operation
+    /// (0 if the execution failed or if it is not a smart contract operation)
+    pub gas_used: u64,
+    /// Ordered ABI calls made by the operation
+    pub abi_calls: Vec<AbiTrace>,
+    /// Ordered datastore accesses made by the operation
+    pub storage_accesses: Vec<StorageAccess>,
+    /// Transfers of the operation itself
+    pub transfers: Vec<Transfer>,
+}
+
+#[cfg(feature = "execution-trace")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// structure describing the gas charged for the calls to an ABI
+pub struct AbiGasUsage {
+    /// Number of calls
+    pub call_count: u64,
+    /// Gas charged for the calls, according to the ABI gas costs
+    pub gas: u64,
+}
+
+#[cfg(feature = "execution-trace")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// structure describing the gas charged for the ABI calls of executed slots
+pub struct GasProfile {
+    /// Number of executed slots covered by the profile
+    pub slot_count: u64,
+    /// Gas usage of each called ABI, by ABI name
+    pub abi_usage: BTreeMap<String, AbiGasUsage>,
+}
+
+#[cfg(feature = "execution-trace")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// command moving a debug session forward
+pub enum DebugCommand {
+    /// move to the next ABI call
+    Step,
+    /// move to the next ABI call matching a breakpoint, or to the end of the execution
+    Continue,
+}
+
+#[cfg(feature = "execution-trace")]
+#[derive(Debug, Clone)]
+/// ABI call on which a debug session is paused
+pub struct DebugStep {
+    /// Index of the ABI call in the execution order
+    pub index: usize,
+    /// The ABI call, without its sub calls (they are the next steps)
+    pub abi_call: AbiTrace,
+    /// Names of the ABI calls enclosing this one, outermost first
+    pub call_stack: Vec<String>,
+}
+
+#[cfg(feature = "execution-trace")]
+#[derive(Debug, Clone)]
+/// structure describing the state of a debug session
+pub struct DebugSessionState {
+    /// Session id
+    pub session_id: u64,
+    /// ABI call the session is paused on (None before the first step and once finished)
+    pub current_step: Option<DebugStep>,
+    /// Whether all the ABI calls of the execution were stepped through
+    pub finished: bool,
+    /// Number of ABI calls made by the execution
+    pub step_count: usize,
+    /// Names of the ABIs on which the session pauses when continuing
+    pub breakpoints: Vec<String>,
+    /// Gas cost of the debugged execution
+    pub gas_cost: u64,
+    /// Returned value of the debugged execution
+    pub call_result: Vec<u8>,
+}
+
 /// structure describing the output of a read only execution
 #[derive(Debug, Clone)]
 pub struct ReadOnlyExecutionOutput {
@@ -392,6 +556,31 @@ pub struct ReadOnlyExecutionOutput {
     pub gas_cost: u64,
     /// Returned value from the module call
     pub call_result: Vec<u8>,
+    /// Ordered ABI calls made by the execution
+    #[cfg(feature = "execution-trace")]
+    pub abi_calls: Vec<AbiTrace>,
+}
+
+/// Status an operation would have if it was included in the next block of its thread
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OperationSimulationStatus {
+    /// the operation would be executed successfully
+    Success,
+    /// the fee of the operation would be paid but its execution would fail
+    Failure,
+    /// the operation could not be included in a block, for the given reason
+    Rejected(String),
+}
+
+/// structure describing the output of the simulation of an operation
+#[derive(Debug, Clone)]
+pub struct OperationSimulationOutput {
+    /// Output of the simulation, including the fee payment
+    pub out: ExecutionOutput,
+    /// Gas the operation would use in its block
+    pub gas_usage: u64,
+    /// Status the operation would have
+    pub status: OperationSimulationStatus,
 }
 
 /// structure describing different types of read-only execution request
@@ -407,6 +596,37 @@ pub struct ReadOnlyExecutionRequest {
     pub coins: Option<Amount>,
     /// Fee
     pub fee: Option<Amount>,
+    /// Temporary overrides of the state, applied before the execution
+    pub state_overrides: Vec<ReadOnlyStateOverride>,
+    /// Priority of the request: the queued requests of higher priority are executed first
+    pub priority: ReadOnlyPriority,
+    /// Client the request is accounted to for the read-only quotas, None if not limited
+    pub caller_id: Option<String>,
+}
+
+/// Priority of a read-only execution request
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ReadOnlyPriority {
+    /// requests of the public APIs
+    #[default]
+    Normal,
+    /// requests of the node operator, executed before the normal ones
+    High,
+}
+
+/// Temporary override of the state of an address, applied to the speculative ledger
+/// of a read-only execution before running it.
+/// The address is created if it does not exist.
+#[derive(Debug, Clone)]
+pub struct ReadOnlyStateOverride {
+    /// Overridden address
+    pub address: Address,
+    /// Balance to set, if any
+    pub balance: Option<Amount>,
+    /// Bytecode to set, if any
+    pub bytecode: Option<Bytecode>,
+    /// Datastore entries to set, or to delete if their value is `None`
+    pub datastore: BTreeMap<Vec<u8>, Option<Vec<u8>>>,
 }
 
 /// structure describing different possible targets of a read-only execution request
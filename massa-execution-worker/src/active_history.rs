@@ -14,6 +14,9 @@ use std::collections::VecDeque;
 #[derive(Default)]
 /// History of the outputs of recently executed slots.
 /// Slots should be consecutive, oldest at the beginning and latest at the back.
+/// Each output holds the changes of its slot on top of the previous ones, so that the outputs act
+/// as per-slot checkpoints of the speculative state: when the blockclique changes,
+/// only the slots from the first changed one are cancelled and executed again.
 pub(crate) struct ActiveHistory(pub VecDeque<ExecutionOutput>);
 
 /// Result of a lazy, active history search
@@ -17,8 +17,8 @@ use massa_async_pool::{AsyncMessage, AsyncPoolChanges};
 use massa_async_pool::{AsyncMessageId, AsyncMessageInfo};
 use massa_executed_ops::{ExecutedDenunciationsChanges, ExecutedOpsChanges};
 use massa_execution_exports::{
-    EventStore, ExecutedBlockInfo, ExecutionConfig, ExecutionError, ExecutionOutput,
-    ExecutionStackElement,
+    EventOverflow, EventOverflowPolicy, EventStore, ExecutedBlockInfo, ExecutionConfig,
+    ExecutionError, ExecutionOutput, ExecutionStackElement, ReadOnlyStateOverride,
 };
 use massa_final_state::{FinalStateController, StateChanges};
 use massa_hash::Hash;
@@ -49,6 +49,9 @@ use std::collections::{BTreeMap, BTreeSet};
 use std::sync::Arc;
 use tracing::{debug, warn};
 
+#[cfg(feature = "execution-trace")]
+use massa_execution_exports::{StorageAccess, StorageAccessType};
+
 /// A snapshot taken from an `ExecutionContext` and that represents its current state.
 /// The `ExecutionContext` state can then be restored later from this snapshot.
 pub struct ExecutionContextSnapshot {
@@ -85,6 +88,9 @@ pub struct ExecutionContextSnapshot {
     /// keep the count of event emitted in the context
     pub event_count: usize,
 
+    /// count of the events spilled beyond the max number of events per slot
+    pub spilled_event_count: usize,
+
     /// Unsafe random state
     pub unsafe_rng: Xoshiro256PlusPlus,
 
@@ -158,6 +164,9 @@ pub struct ExecutionContext {
     /// generated events during this execution, with multiple indexes
     pub events: EventStore,
 
+    /// events generated beyond the max number of events per slot, if any
+    pub event_overflow: Option<EventOverflow>,
+
     /// Unsafe random state (can be predicted and manipulated)
     pub unsafe_rng: Xoshiro256PlusPlus,
 
@@ -179,6 +188,14 @@ pub struct ExecutionContext {
     /// The gas remaining before the last subexecution.
     /// so *excluding* the gas used by the last sc call.
     pub gas_remaining_before_subexecution: Option<u64>,
+
+    /// datastore accesses made by the operation being executed, for its execution trace
+    #[cfg(feature = "execution-trace")]
+    pub storage_accesses: Vec<StorageAccess>,
+
+    /// gas consumed by the operation being executed, for its execution trace
+    #[cfg(feature = "execution-trace")]
+    pub gas_used: u64,
 }
 
 impl ExecutionContext {
@@ -235,6 +252,7 @@ impl ExecutionContext {
             stack: Default::default(),
             read_only: Default::default(),
             events: Default::default(),
+            event_overflow: None,
             unsafe_rng: init_prng(&execution_trail_hash),
             creator_address: Default::default(),
             origin_operation_id: Default::default(),
@@ -243,6 +261,10 @@ impl ExecutionContext {
             address_factory: AddressFactory { mip_store },
             execution_trail_hash,
             gas_remaining_before_subexecution: None,
+            #[cfg(feature = "execution-trace")]
+            storage_accesses: Vec::new(),
+            #[cfg(feature = "execution-trace")]
+            gas_used: 0,
         }
     }
 
@@ -262,6 +284,10 @@ impl ExecutionContext {
             created_message_index: self.created_message_index,
             stack: self.stack.clone(),
             event_count: self.events.0.len(),
+            spilled_event_count: self
+                .event_overflow
+                .as_ref()
+                .map_or(0, |overflow| overflow.spilled_events.0.len()),
             unsafe_rng: self.unsafe_rng.clone(),
             gas_remaining_before_subexecution: self.gas_remaining_before_subexecution,
         }
@@ -297,6 +323,15 @@ impl ExecutionContext {
         for event in self.events.0.range_mut(snapshot.event_count..) {
             event.context.is_error = true;
         }
+        if let Some(overflow) = self.event_overflow.as_mut() {
+            for event in overflow
+                .spilled_events
+                .0
+                .range_mut(snapshot.spilled_event_count..)
+            {
+                event.context.is_error = true;
+            }
+        }
 
         // Emit the error event.
         // Note that the context event counter is properly handled by event_emit (see doc).
@@ -560,6 +595,42 @@ impl ExecutionContext {
         self.speculative_ledger.get_balance(address)
     }
 
+    /// Applies temporary overrides of the state of addresses in the speculative ledger.
+    /// Only meant to be used in read-only executions, before running them.
+    ///
+    /// # Arguments
+    /// * `state_overrides`: the overridden states of the addresses
+    pub fn apply_state_overrides(
+        &mut self,
+        state_overrides: Vec<ReadOnlyStateOverride>,
+    ) -> Result<(), ExecutionError> {
+        for state_override in state_overrides {
+            self.speculative_ledger
+                .apply_state_override(state_override)?;
+        }
+        Ok(())
+    }
+
+    /// Records an access to a datastore entry in the execution trace of the current operation
+    ///
+    /// # Arguments
+    /// * address: the address owning the datastore
+    /// * key: the datastore key
+    /// * access_type: the type of access
+    #[cfg(feature = "execution-trace")]
+    pub fn trace_storage_access(
+        &mut self,
+        address: &Address,
+        key: &[u8],
+        access_type: StorageAccessType,
+    ) {
+        self.storage_accesses.push(StorageAccess {
+            address: *address,
+            key: key.to_vec(),
+            access_type,
+        });
+    }
+
     /// Sets a datastore entry for an address in the speculative ledger.
     /// Fail if the address is absent from the ledger.
     /// The datastore entry is created if it is absent for that address.
@@ -574,6 +645,9 @@ impl ExecutionContext {
         key: Vec<u8>,
         data: Vec<u8>,
     ) -> Result<(), ExecutionError> {
+        #[cfg(feature = "execution-trace")]
+        self.trace_storage_access(address, &key, StorageAccessType::Write);
+
         // check access right
         if !self.has_write_rights_on(address) {
             return Err(ExecutionError::RuntimeError(format!(
@@ -601,6 +675,9 @@ impl ExecutionContext {
         key: Vec<u8>,
         data: Vec<u8>,
     ) -> Result<(), ExecutionError> {
+        #[cfg(feature = "execution-trace")]
+        self.trace_storage_access(address, &key, StorageAccessType::Write);
+
         // check access right
         if !self.has_write_rights_on(address) {
             return Err(ExecutionError::RuntimeError(format!(
@@ -639,6 +716,9 @@ impl ExecutionContext {
         address: &Address,
         key: &[u8],
     ) -> Result<(), ExecutionError> {
+        #[cfg(feature = "execution-trace")]
+        self.trace_storage_access(address, key, StorageAccessType::Delete);
+
         // check access right
         if !self.has_write_rights_on(address) {
             return Err(ExecutionError::RuntimeError(format!(
@@ -717,6 +797,39 @@ impl ExecutionContext {
         }
     }
 
+    /// Cancels an asynchronous message emitted by `sender` that was not executed yet,
+    /// reimbursing its coins to the sender
+    ///
+    /// # Arguments
+    /// * `sender`: address that emitted the message
+    /// * `emission_slot`: slot at which the message was emitted
+    /// * `emission_index`: index of the message within its emission slot
+    pub fn cancel_emitted_message(
+        &mut self,
+        sender: &Address,
+        emission_slot: Slot,
+        emission_index: u64,
+    ) -> Result<(), ExecutionError> {
+        let msg = self
+            .speculative_async_pool
+            .remove_message(emission_slot, emission_index)
+            .ok_or_else(|| {
+                ExecutionError::CancelAsyncMessageError(format!(
+                    "no pending async message emitted at slot {} with index {}",
+                    emission_slot, emission_index
+                ))
+            })?;
+        // the removal is reverted with the rest of the context if the operation fails
+        if msg.sender != *sender {
+            return Err(ExecutionError::CancelAsyncMessageError(format!(
+                "the async message was emitted by {}, not by {}",
+                msg.sender, sender
+            )));
+        }
+        self.cancel_async_message(&msg);
+        Ok(())
+    }
+
     /// Add `roll_count` rolls to the buyer address.
     /// Validity checks must be performed _outside_ of this function.
     ///
@@ -911,6 +1024,9 @@ impl ExecutionContext {
             block_info,
             state_changes,
             events: std::mem::take(&mut self.events),
+            event_overflow: self.event_overflow.take(),
+            stats: Default::default(),
+            final_ledger_reads: self.speculative_ledger.take_final_reads(),
             #[cfg(feature = "execution-trace")]
             slot_trace: None,
         }
@@ -973,6 +1089,7 @@ impl ExecutionContext {
 
     /// Emits a previously created event.
     /// Overrides the event's index with the current event counter value, and increments the event counter.
+    /// The events beyond the max number of events per slot are handled according to the overflow policy.
     pub fn event_emit(&mut self, mut event: SCOutputEvent) {
         // Set the event index
         event.context.index_in_slot = self.created_event_index;
@@ -980,8 +1097,34 @@ impl ExecutionContext {
         // Increment the event counter fot this slot
         self.created_event_index += 1;
 
-        // Add the event to the context store
-        self.events.push(event);
+        // Add the event to the context store, unless the max number of events per slot is reached
+        if self.events.0.len() < self.config.max_events_per_slot {
+            self.events.push(event);
+            return;
+        }
+        let policy = self.config.event_overflow_policy;
+        let overflow = self.event_overflow.get_or_insert_with(|| EventOverflow {
+            policy,
+            overflowing_event_count: 0,
+            spilled_events: EventStore::default(),
+        });
+        overflow.overflowing_event_count += 1;
+        match policy {
+            EventOverflowPolicy::Truncate => {
+                // the first overflowing event is replaced by a marker, the next ones are dropped
+                if overflow.overflowing_event_count == 1 {
+                    event.data = serde_json::json!({
+                        "massa_events_truncated": format!(
+                            "the limit of {} events per slot was reached",
+                            self.config.max_events_per_slot
+                        )
+                    })
+                    .to_string();
+                    self.events.push(event);
+                }
+            }
+            EventOverflowPolicy::Spill => overflow.spilled_events.push(event),
+        }
     }
 
     /// Check if an operation was previously executed (to prevent reuse)
@@ -4,20 +4,27 @@
 //! See `massa-execution-exports/controller_traits.rs` for functional details.
 
 use crate::execution::ExecutionState;
+use crate::readonly_cache::{ReadOnlyCache, ReadOnlyCacheKey};
+use crate::readonly_limiter::ReadOnlyLimiter;
 use crate::request_queue::{RequestQueue, RequestWithResponseSender};
 use massa_channel::MassaChannel;
 use massa_execution_exports::{
     ExecutionAddressInfo, ExecutionBlockMetadata, ExecutionConfig, ExecutionController,
     ExecutionError, ExecutionManager, ExecutionQueryError, ExecutionQueryExecutionStatus,
     ExecutionQueryRequest, ExecutionQueryRequestItem, ExecutionQueryResponse,
-    ExecutionQueryResponseItem, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
+    ExecutionQueryResponseItem, OperationSimulationOutput, ReadOnlyExecutionOutput,
+    ReadOnlyExecutionRequest, SlotExecutionStats, StateDiff,
 };
+use massa_ledger_exports::LedgerEntryInfo;
 use massa_models::denunciation::DenunciationIndex;
-use massa_models::execution::EventFilter;
+use massa_models::execution::{
+    AsyncMessageFeeSuggestion, AsyncMessageFilter, EventFilter, PendingAsyncMessage,
+};
+use massa_models::operation::{OperationAddressRole, OperationId, SecureShareOperation};
 use massa_models::output_event::SCOutputEvent;
 use massa_models::prehash::PreHashMap;
 use massa_models::stats::ExecutionStats;
-use massa_models::{address::Address, amount::Amount, operation::OperationId};
+use massa_models::{address::Address, amount::Amount};
 use massa_models::{block_id::BlockId, slot::Slot};
 use parking_lot::{Condvar, Mutex, RwLock};
 use std::collections::{BTreeMap, HashMap};
@@ -26,7 +33,12 @@ use std::sync::Arc;
 use tracing::info;
 
 #[cfg(feature = "execution-trace")]
-use massa_execution_exports::{AbiTrace, SlotAbiCallStack, Transfer};
+use crate::debugger::DebugSessions;
+#[cfg(feature = "execution-trace")]
+use massa_execution_exports::{
+    AbiTrace, DebugCommand, DebugSessionState, GasProfile, OperationTrace, SlotAbiCallStack,
+    Transfer,
+};
 
 /// structure used to communicate with execution thread
 pub(crate) struct ExecutionInputData {
@@ -40,6 +52,8 @@ pub(crate) struct ExecutionInputData {
     pub block_metadata: PreHashMap<BlockId, ExecutionBlockMetadata>,
     /// queue for read-only execution requests and response MPSCs to send back their outputs
     pub readonly_requests: RequestQueue<ReadOnlyExecutionRequest, ReadOnlyExecutionOutput>,
+    /// queue for operations to simulate and response MPSCs to send back their outputs
+    pub operation_simulations: RequestQueue<SecureShareOperation, OperationSimulationOutput>,
 }
 
 impl Display for ExecutionInputData {
@@ -71,6 +85,7 @@ impl ExecutionInputData {
             new_blockclique: Default::default(),
             block_metadata: Default::default(),
             readonly_requests: RequestQueue::new(config.max_final_events),
+            operation_simulations: RequestQueue::new(config.max_final_events),
         }
     }
 
@@ -87,6 +102,10 @@ impl ExecutionInputData {
                 &mut self.readonly_requests,
                 RequestQueue::new(max_final_events),
             ),
+            operation_simulations: std::mem::replace(
+                &mut self.operation_simulations,
+                RequestQueue::new(max_final_events),
+            ),
         }
     }
 }
@@ -99,6 +118,13 @@ pub struct ExecutionControllerImpl {
     pub(crate) input_data: Arc<(Condvar, Mutex<ExecutionInputData>)>,
     /// current execution state (see execution.rs for details)
     pub(crate) execution_state: Arc<RwLock<ExecutionState>>,
+    /// outputs of the read-only calls executed on the current state (None if disabled)
+    pub(crate) readonly_cache: Option<Arc<Mutex<ReadOnlyCache>>>,
+    /// quotas of the read-only requests
+    pub(crate) readonly_limiter: Arc<ReadOnlyLimiter>,
+    /// open debug sessions
+    #[cfg(feature = "execution-trace")]
+    pub(crate) debug_sessions: Arc<Mutex<DebugSessions>>,
 }
 
 impl ExecutionController for ExecutionControllerImpl {
@@ -327,6 +353,11 @@ impl ExecutionController for ExecutionControllerImpl {
                         execution_lock.get_filtered_sc_output_event(filter),
                     ))
                 }
+                ExecutionQueryRequestItem::AddressIncomingAsyncMessagesFinal(addr) => {
+                    Ok(ExecutionQueryResponseItem::IncomingAsyncMessages(
+                        execution_lock.get_address_incoming_async_messages(&addr),
+                    ))
+                }
             };
             resp.responses.push(resp_item);
         }
@@ -388,6 +419,21 @@ impl ExecutionController for ExecutionControllerImpl {
         &self,
         req: ReadOnlyExecutionRequest,
     ) -> Result<ReadOnlyExecutionOutput, ExecutionError> {
+        // identical calls on the same state are answered from the cache
+        let cache_entry = self.readonly_cache.as_ref().and_then(|cache| {
+            let key = ReadOnlyCacheKey::new(&req)?;
+            let state_version = self.execution_state.read().get_execution_trail_hash();
+            Some((cache, key, state_version))
+        });
+        if let Some((cache, key, state_version)) = &cache_entry {
+            if let Some(output) = cache.lock().get(state_version, key) {
+                return Ok(output);
+            }
+        }
+
+        // the request is accounted to its caller until its output is received
+        let _permit = self.readonly_limiter.acquire(req.caller_id.as_deref())?;
+
         let resp_rx = {
             let mut input_data = self.input_data.1.lock();
 
@@ -413,12 +459,59 @@ impl ExecutionController for ExecutionControllerImpl {
         };
 
         // Wait for the result of the execution
-        match resp_rx.recv() {
+        let result = match resp_rx.recv() {
             Ok(result) => result,
             Err(err) => Err(ExecutionError::ChannelError(format!(
                 "readonly execution response channel readout failed: {}",
                 err
             ))),
+        };
+
+        // the output is cached only if the state did not change during the execution
+        if let (Ok(output), Some((cache, key, state_version))) = (&result, cache_entry) {
+            if self.execution_state.read().get_execution_trail_hash() == state_version {
+                cache.lock().insert(state_version, key, output.clone());
+            }
+        }
+        result
+    }
+
+    /// Simulates an operation, through the queue of the read-only executions
+    fn simulate_operation(
+        &self,
+        operation: SecureShareOperation,
+    ) -> Result<OperationSimulationOutput, ExecutionError> {
+        let resp_rx = {
+            let mut input_data = self.input_data.1.lock();
+
+            // if the simulation queue is already full, return an error
+            if input_data.operation_simulations.is_full() {
+                return Err(ExecutionError::ChannelError(
+                    "too many queued operation simulations".into(),
+                ));
+            }
+
+            // prepare the channel to send back the result of the simulation
+            let (resp_tx, resp_rx) = MassaChannel::new("operation_simulation".to_string(), None);
+
+            // append the operation to the queue of input operation simulations
+            input_data
+                .operation_simulations
+                .push(RequestWithResponseSender::new(operation, resp_tx));
+
+            // wake up the execution main loop
+            self.input_data.0.notify_one();
+
+            resp_rx
+        };
+
+        // Wait for the result of the simulation
+        match resp_rx.recv() {
+            Ok(result) => result,
+            Err(err) => Err(ExecutionError::ChannelError(format!(
+                "operation simulation response channel readout failed: {}",
+                err
+            ))),
         }
     }
 
@@ -453,6 +546,7 @@ impl ExecutionController for ExecutionControllerImpl {
                 candidate_roll_count,
                 future_deferred_credits: exec_state.get_address_future_deferred_credits(addr),
                 cycle_infos: exec_state.get_address_cycle_infos(addr),
+                incoming_async_messages: exec_state.get_address_incoming_async_messages(addr),
             });
         }
         res
@@ -463,6 +557,13 @@ impl ExecutionController for ExecutionControllerImpl {
         self.execution_state.read().get_stats()
     }
 
+    /// Get the execution statistics of the latest final slots
+    fn get_slot_execution_stats(&self, last_slot_count: usize) -> Vec<(Slot, SlotExecutionStats)> {
+        self.execution_state
+            .read()
+            .get_slot_execution_stats(last_slot_count)
+    }
+
     #[cfg(feature = "execution-trace")]
     fn get_operation_abi_call_stack(&self, operation_id: OperationId) -> Option<Vec<AbiTrace>> {
         self.execution_state
@@ -499,6 +600,127 @@ impl ExecutionController for ExecutionControllerImpl {
             .fetch_transfer_for_op(op_id)
     }
 
+    #[cfg(feature = "execution-trace")]
+    fn get_operation_trace(&self, op_id: &OperationId) -> Option<OperationTrace> {
+        self.execution_state
+            .read()
+            .trace_history
+            .read()
+            .fetch_operation_trace(op_id)
+    }
+
+    #[cfg(feature = "execution-trace")]
+    fn get_gas_profile(&self, start: Slot, end: Slot) -> GasProfile {
+        self.execution_state
+            .read()
+            .trace_history
+            .read()
+            .fetch_gas_profile(start, end)
+    }
+
+    #[cfg(feature = "execution-trace")]
+    fn start_debug_session(
+        &self,
+        req: ReadOnlyExecutionRequest,
+        breakpoints: Vec<String>,
+    ) -> Result<DebugSessionState, ExecutionError> {
+        let output = self.execute_readonly_request(req)?;
+        self.debug_sessions.lock().open(output, breakpoints)
+    }
+
+    #[cfg(feature = "execution-trace")]
+    fn resume_debug_session(
+        &self,
+        session_id: u64,
+        command: DebugCommand,
+    ) -> Result<DebugSessionState, ExecutionError> {
+        self.debug_sessions.lock().resume(session_id, command)
+    }
+
+    #[cfg(feature = "execution-trace")]
+    fn set_debug_breakpoints(
+        &self,
+        session_id: u64,
+        breakpoints: Vec<String>,
+    ) -> Result<DebugSessionState, ExecutionError> {
+        self.debug_sessions
+            .lock()
+            .set_breakpoints(session_id, breakpoints)
+    }
+
+    #[cfg(feature = "execution-trace")]
+    fn get_debug_datastore_entry(
+        &self,
+        session_id: u64,
+        address: &Address,
+        key: &[u8],
+    ) -> Result<Option<Vec<u8>>, ExecutionError> {
+        self.debug_sessions
+            .lock()
+            .get_datastore_entry(session_id, address, key, || {
+                self.execution_state
+                    .read()
+                    .get_final_and_active_data_entry(address, key)
+                    .1
+            })
+    }
+
+    #[cfg(feature = "execution-trace")]
+    fn stop_debug_session(&self, session_id: u64) -> Result<(), ExecutionError> {
+        self.debug_sessions.lock().close(session_id)
+    }
+
+    /// Get the final operations in which an address has the given role, from the most recent one
+    fn get_operations_by_address(
+        &self,
+        address: &Address,
+        role: OperationAddressRole,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<(Slot, OperationId)>, ExecutionError> {
+        self.execution_state
+            .read()
+            .get_operations_by_address(address, role, offset, limit)
+    }
+
+    /// See trait definition
+    fn get_state_diff(&self, from_slot: Slot, to_slot: Slot) -> Result<StateDiff, ExecutionError> {
+        self.execution_state
+            .read()
+            .get_state_diff(from_slot, to_slot)
+    }
+
+    /// Get the summaries of up to `page_size` entries of the final ledger in address order,
+    /// starting from the one of `page_token`
+    fn iter_ledger(
+        &self,
+        page_token: Option<Address>,
+        page_size: usize,
+    ) -> (Vec<LedgerEntryInfo>, Option<Address>) {
+        self.execution_state
+            .read()
+            .iter_ledger(page_token, page_size)
+    }
+
+    /// See trait definition
+    fn get_async_messages(&self, filter: AsyncMessageFilter) -> Vec<PendingAsyncMessage> {
+        self.execution_state.read().get_async_messages(&filter)
+    }
+
+    /// See trait definition
+    fn get_async_message_fee_suggestion(&self, max_gas: u64) -> AsyncMessageFeeSuggestion {
+        self.execution_state
+            .read()
+            .get_async_message_fee_suggestion(max_gas)
+    }
+
+    /// Suspend or resume the writes to the operation and event indexes
+    fn set_index_writes_suspended(&self, suspended: bool) {
+        self.execution_state
+            .write()
+            .set_index_writes_suspended(suspended);
+    }
+
     /// Returns a boxed clone of self.
     /// Allows cloning `Box<dyn ExecutionController>`,
     /// see `massa-execution-exports/controller_traits.rs`
@@ -0,0 +1,265 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Debug sessions stepping through the ABI calls of read-only executions.
+//!
+//! The read-only call of a session is executed once, like any other read-only request,
+//! so that the execution thread is never paused by a debugger.
+//! Its ABI calls are recorded in execution order, and the session then goes through them
+//! one by one or up to the next breakpoint, exposing the call stack of each of them.
+
+use massa_execution_exports::{
+    AbiTrace, DebugCommand, DebugSessionState, DebugStep, ExecutionError, ReadOnlyExecutionOutput,
+};
+use massa_ledger_exports::LedgerChanges;
+use massa_models::address::Address;
+use schnellru::{ByLength, LruMap};
+use std::collections::BTreeSet;
+
+/// A debug session over a recorded read-only execution
+struct DebugSession {
+    /// ABI calls of the execution, in execution order
+    steps: Vec<DebugStep>,
+    /// index of the step the session is paused on, if any
+    current: Option<usize>,
+    /// whether all the steps were gone through
+    finished: bool,
+    /// names of the ABIs on which the session pauses when continuing
+    breakpoints: BTreeSet<String>,
+    /// ledger changes made by the execution
+    ledger_changes: LedgerChanges,
+    /// gas cost of the execution
+    gas_cost: u64,
+    /// returned value of the execution
+    call_result: Vec<u8>,
+}
+
+impl DebugSession {
+    fn new(output: ReadOnlyExecutionOutput, breakpoints: Vec<String>) -> Self {
+        let mut steps = Vec::new();
+        let mut call_stack = Vec::new();
+        for abi_call in output.abi_calls {
+            push_steps(abi_call, &mut call_stack, &mut steps);
+        }
+        DebugSession {
+            steps,
+            current: None,
+            finished: false,
+            breakpoints: breakpoints.into_iter().collect(),
+            ledger_changes: output.out.state_changes.ledger_changes,
+            gas_cost: output.gas_cost,
+            call_result: output.call_result,
+        }
+    }
+
+    /// Moves the session forward, the finished sessions staying at the end of the execution
+    fn resume(&mut self, command: DebugCommand) {
+        if self.finished {
+            return;
+        }
+        let next = self.current.map_or(0, |index| index + 1);
+        let target = match command {
+            DebugCommand::Step => Some(next).filter(|index| *index < self.steps.len()),
+            DebugCommand::Continue => self.steps[next..]
+                .iter()
+                .position(|step| self.breakpoints.contains(&step.abi_call.name))
+                .map(|offset| next + offset),
+        };
+        self.current = target;
+        self.finished = target.is_none();
+    }
+
+    fn state(&self, session_id: u64) -> DebugSessionState {
+        DebugSessionState {
+            session_id,
+            current_step: self.current.map(|index| self.steps[index].clone()),
+            finished: self.finished,
+            step_count: self.steps.len(),
+            breakpoints: self.breakpoints.iter().cloned().collect(),
+            gas_cost: self.gas_cost,
+            call_result: self.call_result.clone(),
+        }
+    }
+}
+
+/// Appends the steps of an ABI call and of its sub calls, in execution order
+fn push_steps(mut abi_call: AbiTrace, call_stack: &mut Vec<String>, steps: &mut Vec<DebugStep>) {
+    let sub_calls = abi_call.sub_calls.take().unwrap_or_default();
+    let name = abi_call.name.clone();
+    steps.push(DebugStep {
+        index: steps.len(),
+        abi_call,
+        call_stack: call_stack.clone(),
+    });
+    call_stack.push(name);
+    for sub_call in sub_calls {
+        push_steps(sub_call, call_stack, steps);
+    }
+    call_stack.pop();
+}
+
+/// Open debug sessions, the least recently used one being closed when the limit is reached
+pub(crate) struct DebugSessions {
+    /// sessions by id
+    sessions: LruMap<u64, DebugSession>,
+    /// id of the next session
+    next_session_id: u64,
+}
+
+impl DebugSessions {
+    pub fn new(max_sessions: usize) -> Self {
+        Self {
+            sessions: LruMap::new(ByLength::new(max_sessions as u32)),
+            next_session_id: 0,
+        }
+    }
+
+    /// Opens a session over the output of a read-only execution, paused before its first ABI call
+    pub(crate) fn open(
+        &mut self,
+        output: ReadOnlyExecutionOutput,
+        breakpoints: Vec<String>,
+    ) -> Result<DebugSessionState, ExecutionError> {
+        let session_id = self.next_session_id;
+        let session = DebugSession::new(output, breakpoints);
+        let state = session.state(session_id);
+        if !self.sessions.insert(session_id, session) {
+            return Err(ExecutionError::DebugSessionError(
+                "debug sessions are disabled".to_string(),
+            ));
+        }
+        self.next_session_id += 1;
+        Ok(state)
+    }
+
+    /// Moves a session forward
+    pub(crate) fn resume(
+        &mut self,
+        session_id: u64,
+        command: DebugCommand,
+    ) -> Result<DebugSessionState, ExecutionError> {
+        let session = self.get_session(session_id)?;
+        session.resume(command);
+        Ok(session.state(session_id))
+    }
+
+    /// Replaces the breakpoints of a session
+    pub(crate) fn set_breakpoints(
+        &mut self,
+        session_id: u64,
+        breakpoints: Vec<String>,
+    ) -> Result<DebugSessionState, ExecutionError> {
+        let session = self.get_session(session_id)?;
+        session.breakpoints = breakpoints.into_iter().collect();
+        Ok(session.state(session_id))
+    }
+
+    /// Get a datastore entry as seen by the execution of a session,
+    /// calling `f` to read it from the state when the execution did not change it
+    pub(crate) fn get_datastore_entry<F: FnOnce() -> Option<Vec<u8>>>(
+        &mut self,
+        session_id: u64,
+        address: &Address,
+        key: &[u8],
+        f: F,
+    ) -> Result<Option<Vec<u8>>, ExecutionError> {
+        let session = self.get_session(session_id)?;
+        Ok(session
+            .ledger_changes
+            .get_data_entry_or_else(address, key, f))
+    }
+
+    /// Closes a session
+    pub(crate) fn close(&mut self, session_id: u64) -> Result<(), ExecutionError> {
+        self.sessions
+            .remove(&session_id)
+            .map(|_| ())
+            .ok_or_else(|| unknown_session(session_id))
+    }
+
+    fn get_session(&mut self, session_id: u64) -> Result<&mut DebugSession, ExecutionError> {
+        self.sessions
+            .get(&session_id)
+            .ok_or_else(|| unknown_session(session_id))
+    }
+}
+
+fn unknown_session(session_id: u64) -> ExecutionError {
+    ExecutionError::DebugSessionError(format!("unknown debug session {}", session_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_execution_exports::{EventStore, ExecutionOutput, SCRuntimeAbiTraceType};
+    use massa_final_state::StateChanges;
+    use massa_models::slot::Slot;
+
+    fn abi_call(name: &str, sub_calls: Vec<AbiTrace>) -> AbiTrace {
+        AbiTrace {
+            name: name.to_string(),
+            parameters: vec![],
+            return_value: SCRuntimeAbiTraceType::U64(0),
+            sub_calls: Some(sub_calls),
+        }
+    }
+
+    fn readonly_output(abi_calls: Vec<AbiTrace>) -> ReadOnlyExecutionOutput {
+        ReadOnlyExecutionOutput {
+            out: ExecutionOutput {
+                slot: Slot::new(1, 0),
+                block_info: None,
+                state_changes: StateChanges::default(),
+                events: EventStore::default(),
+                event_overflow: None,
+                stats: Default::default(),
+                final_ledger_reads: Default::default(),
+                slot_trace: None,
+            },
+            gas_cost: 0,
+            call_result: vec![],
+            abi_calls,
+        }
+    }
+
+    #[test]
+    fn test_debug_session_steps() {
+        let mut sessions = DebugSessions::new(1);
+        let output = readonly_output(vec![
+            abi_call("get_data", vec![]),
+            abi_call("call", vec![abi_call("set_data", vec![])]),
+            abi_call("set_data", vec![]),
+        ]);
+        let state = sessions.open(output, vec!["set_data".to_string()]).unwrap();
+        assert_eq!(state.step_count, 4);
+        assert!(state.current_step.is_none());
+
+        // step into the first ABI call
+        let state = sessions.resume(0, DebugCommand::Step).unwrap();
+        let step = state.current_step.unwrap();
+        assert_eq!((step.index, step.abi_call.name.as_str()), (0, "get_data"));
+
+        // continue to the breakpoint, inside the sub call
+        let state = sessions.resume(0, DebugCommand::Continue).unwrap();
+        let step = state.current_step.unwrap();
+        assert_eq!((step.index, step.abi_call.name.as_str()), (2, "set_data"));
+        assert_eq!(step.call_stack, vec!["call".to_string()]);
+
+        let state = sessions.resume(0, DebugCommand::Continue).unwrap();
+        let step = state.current_step.unwrap();
+        assert_eq!(step.index, 3);
+        assert!(step.call_stack.is_empty());
+
+        // no more breakpoint: the execution is over
+        let state = sessions.resume(0, DebugCommand::Continue).unwrap();
+        assert!(state.finished);
+        assert!(state.current_step.is_none());
+        let state = sessions.resume(0, DebugCommand::Step).unwrap();
+        assert!(state.finished);
+
+        // the least recently used session is closed
+        sessions.open(readonly_output(vec![]), vec![]).unwrap();
+        assert!(sessions.resume(0, DebugCommand::Step).is_err());
+        sessions.close(1).unwrap();
+        assert!(sessions.close(1).is_err());
+    }
+}
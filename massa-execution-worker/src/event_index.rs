@@ -0,0 +1,284 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Persistent index of the final execution events.
+//!
+//! The events are stored under a key made of the slot of their execution and of their position
+//! among the events of the slot, so that they are sorted by slot.
+//! They are indexed by emitter address and by original operation id with keys without value,
+//! made of the length-prefixed address or operation id followed by the key of the event.
+//! The events older than the retention period are removed as new slots become final.
+
+use crate::operation_index::next_prefix;
+use massa_models::address::Address;
+use massa_models::execution::EventFilter;
+use massa_models::operation::{OperationId, OperationIdSerializer};
+use massa_models::output_event::SCOutputEvent;
+use massa_models::slot::{Slot, SLOT_KEY_SIZE};
+use massa_serialization::Serializer;
+use rocksdb::{Direction, IteratorMode, WriteBatch, DB};
+use std::path::PathBuf;
+
+const OPEN_ERROR: &str = "critical: rocksdb open operation failed";
+const CRUD_ERROR: &str = "critical: rocksdb crud operation failed";
+const EVENT_SER_ERROR: &str = "critical: event serialization failed";
+const EVENT_DESER_ERROR: &str = "critical: event deserialization failed";
+const OP_ID_SER_ERROR: &str = "critical: operation id serialization failed";
+const EVENT_IDENT: u8 = 0u8;
+const EMITTER_IDENT: u8 = 1u8;
+const OPERATION_IDENT: u8 = 2u8;
+
+pub(crate) struct EventIndex {
+    /// RocksDB database
+    db: DB,
+    /// number of periods during which the final events are kept (0 keeps them forever)
+    retention_periods: u64,
+    /// Operation id serializer
+    op_id_ser: OperationIdSerializer,
+}
+
+impl EventIndex {
+    /// Open (or create) the event index stored at `path`
+    pub fn new(path: PathBuf, retention_periods: u64) -> Self {
+        Self {
+            db: DB::open_default(path).expect(OPEN_ERROR),
+            retention_periods,
+            op_id_ser: OperationIdSerializer::new(),
+        }
+    }
+
+    /// Store and index the final events of a slot,
+    /// removing the events that went beyond the retention period
+    ///
+    /// # Arguments
+    /// * `slot`: the final slot
+    /// * `events`: final events emitted during the execution of the slot
+    pub fn index_events<'a, I>(&self, slot: &Slot, events: I)
+    where
+        I: IntoIterator<Item = &'a SCOutputEvent>,
+    {
+        let mut batch = WriteBatch::default();
+        for (position, event) in events.into_iter().enumerate() {
+            let event_key = event_key(slot, position as u64);
+            for index_prefix in self.index_prefixes(event) {
+                batch.put([&index_prefix[..], &event_key[1..]].concat(), b"");
+            }
+            batch.put(event_key, serde_json::to_vec(event).expect(EVENT_SER_ERROR));
+        }
+        if self.retention_periods > 0 && slot.period > self.retention_periods {
+            self.prune(
+                &Slot::new(slot.period - self.retention_periods, 0),
+                &mut batch,
+            );
+        }
+        self.db.write(batch).expect(CRUD_ERROR);
+    }
+
+    /// Add to `batch` the deletion of the events of the slots before `first_kept_slot`,
+    /// with their index entries
+    fn prune(&self, first_kept_slot: &Slot, batch: &mut WriteBatch) {
+        let upper_bound = event_key(first_kept_slot, 0);
+        for entry in self
+            .db
+            .iterator(IteratorMode::From(&[EVENT_IDENT], Direction::Forward))
+        {
+            let (key, value) = entry.expect(CRUD_ERROR);
+            if key[..] >= upper_bound[..] {
+                break;
+            }
+            let event: SCOutputEvent = serde_json::from_slice(&value).expect(EVENT_DESER_ERROR);
+            for index_prefix in self.index_prefixes(&event) {
+                batch.delete([&index_prefix[..], &key[1..]].concat());
+            }
+            batch.delete(key);
+        }
+    }
+
+    /// Get the final events matching a filter, up to the `limit` most recent ones.
+    /// The emitter and operation id indexes are used when the filter is on them.
+    pub fn get_events(&self, filter: &EventFilter, limit: usize) -> Vec<SCOutputEvent> {
+        let prefix = match (filter.original_operation_id, filter.emitter_address) {
+            (Some(operation_id), _) => self.operation_prefix(&operation_id),
+            (None, Some(address)) => emitter_prefix(&address),
+            (None, None) => vec![EVENT_IDENT],
+        };
+        let is_index = prefix[0] != EVENT_IDENT;
+
+        // iterate backwards over the keys of the slots within the range of the filter
+        let lower_bound = filter
+            .start
+            .map(|start| [&prefix[..], &start.to_bytes_key()].concat())
+            .unwrap_or_else(|| prefix.clone());
+        let upper_bound = match filter.end {
+            Some(end) => Some([&prefix[..], &end.to_bytes_key()].concat()),
+            None => next_prefix(&prefix),
+        };
+        let mode = match &upper_bound {
+            Some(upper_bound) => IteratorMode::From(upper_bound, Direction::Reverse),
+            None => IteratorMode::End,
+        };
+        let mut events: Vec<SCOutputEvent> = self
+            .db
+            .iterator(mode)
+            .map(|entry| entry.expect(CRUD_ERROR))
+            .take_while(|(key, _)| key[..] >= lower_bound[..])
+            .filter_map(|(key, value)| {
+                if !is_index {
+                    return Some(value.to_vec());
+                }
+                let event_key = [&[EVENT_IDENT][..], &key[prefix.len()..]].concat();
+                self.db.get(event_key).expect(CRUD_ERROR)
+            })
+            .map(|value| serde_json::from_slice(&value).expect(EVENT_DESER_ERROR))
+            .filter(|event| filter.matches(event))
+            .take(limit)
+            .collect();
+        events.reverse();
+        events
+    }
+
+    /// Prefixes of the index entries of an event
+    fn index_prefixes(&self, event: &SCOutputEvent) -> Vec<Vec<u8>> {
+        let mut prefixes = Vec::new();
+        if let Some(emitter_address) = event.context.call_stack.back() {
+            prefixes.push(emitter_prefix(emitter_address));
+        }
+        if let Some(operation_id) = &event.context.origin_operation_id {
+            prefixes.push(self.operation_prefix(operation_id));
+        }
+        prefixes
+    }
+
+    /// Prefix of the index entries of the events originating from an operation
+    fn operation_prefix(&self, operation_id: &OperationId) -> Vec<u8> {
+        let mut op_id = Vec::new();
+        self.op_id_ser
+            .serialize(operation_id, &mut op_id)
+            .expect(OP_ID_SER_ERROR);
+        [&[OPERATION_IDENT, op_id.len() as u8][..], &op_id].concat()
+    }
+}
+
+/// Key of an event, from the slot of its execution and its position among the events of the slot
+fn event_key(slot: &Slot, position: u64) -> Vec<u8> {
+    let mut key = Vec::with_capacity(1 + SLOT_KEY_SIZE + 8);
+    key.push(EVENT_IDENT);
+    key.extend(slot.to_bytes_key());
+    key.extend(position.to_be_bytes());
+    key
+}
+
+/// Prefix of the index entries of the events emitted by an address
+fn emitter_prefix(address: &Address) -> Vec<u8> {
+    let address_bytes = address.to_prefixed_bytes();
+    [
+        &[EMITTER_IDENT, address_bytes.len() as u8][..],
+        &address_bytes,
+    ]
+    .concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_hash::Hash;
+    use massa_models::output_event::EventExecutionContext;
+    use massa_signature::KeyPair;
+    use std::collections::VecDeque;
+    use tempfile::TempDir;
+
+    fn event(slot: Slot, emitter: Address, operation_id: Option<OperationId>) -> SCOutputEvent {
+        SCOutputEvent {
+            context: EventExecutionContext {
+                slot,
+                block: None,
+                read_only: false,
+                index_in_slot: 0,
+                call_stack: VecDeque::from(vec![emitter]),
+                origin_operation_id: operation_id,
+                is_final: true,
+                is_error: false,
+            },
+            data: slot.to_string(),
+        }
+    }
+
+    fn slots(events: Vec<SCOutputEvent>) -> Vec<Slot> {
+        events.into_iter().map(|event| event.context.slot).collect()
+    }
+
+    #[test]
+    fn test_events_by_emitter_and_operation() {
+        let tmp_dir = TempDir::new().unwrap();
+        let index = EventIndex::new(tmp_dir.path().to_path_buf(), 0);
+        let address_1 = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        let address_2 = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        let op_id = OperationId::new(Hash::compute_from(b"operation"));
+        let (slot_1, slot_2, slot_3) = (Slot::new(1, 0), Slot::new(1, 1), Slot::new(2, 0));
+
+        index.index_events(
+            &slot_1,
+            &[
+                event(slot_1, address_1, Some(op_id)),
+                event(slot_1, address_2, Some(op_id)),
+            ],
+        );
+        index.index_events(&slot_2, &[event(slot_2, address_1, None)]);
+        index.index_events(&slot_3, &[event(slot_3, address_2, None)]);
+
+        // all the events, sorted by slot
+        let events = index.get_events(&EventFilter::default(), 10);
+        assert_eq!(slots(events), vec![slot_1, slot_1, slot_2, slot_3]);
+
+        // by emitter
+        let filter = EventFilter {
+            emitter_address: Some(address_1),
+            ..Default::default()
+        };
+        let events = index.get_events(&filter, 10);
+        assert_eq!(slots(events), vec![slot_1, slot_2]);
+
+        // by operation, the emitter being filtered as well
+        let filter = EventFilter {
+            original_operation_id: Some(op_id),
+            emitter_address: Some(address_2),
+            ..Default::default()
+        };
+        let events = index.get_events(&filter, 10);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].context.call_stack.back(), Some(&address_2));
+
+        // by slot range, the most recent events being returned
+        let filter = EventFilter {
+            start: Some(slot_1),
+            end: Some(slot_3),
+            ..Default::default()
+        };
+        let events = index.get_events(&filter, 1);
+        assert_eq!(slots(events), vec![slot_2]);
+    }
+
+    #[test]
+    fn test_events_retention() {
+        let tmp_dir = TempDir::new().unwrap();
+        let index = EventIndex::new(tmp_dir.path().to_path_buf(), 2);
+        let address = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+
+        for period in 1..=4 {
+            let slot = Slot::new(period, 0);
+            index.index_events(&slot, &[event(slot, address, None)]);
+        }
+
+        // the events of the periods before the retention period are removed with their index
+        let events = index.get_events(&EventFilter::default(), 10);
+        assert_eq!(
+            slots(events),
+            vec![Slot::new(2, 0), Slot::new(3, 0), Slot::new(4, 0)]
+        );
+        let filter = EventFilter {
+            emitter_address: Some(address),
+            ..Default::default()
+        };
+        assert_eq!(index.get_events(&filter, 10).len(), 3);
+        assert_eq!(index.db.iterator(IteratorMode::Start).count(), 6);
+    }
+}
@@ -10,49 +10,60 @@
 
 use crate::active_history::{ActiveHistory, HistorySearchResult};
 use crate::context::{ExecutionContext, ExecutionContextSnapshot};
+use crate::event_index::EventIndex;
 use crate::interface_impl::InterfaceImpl;
+use crate::operation_index::OperationIndex;
+use crate::speculative_async_pool::SpeculativeAsyncPool;
 use crate::stats::ExecutionStatsCounter;
 use massa_async_pool::AsyncMessage;
 use massa_execution_exports::{
     EventStore, ExecutedBlockInfo, ExecutionBlockMetadata, ExecutionChannels, ExecutionConfig,
-    ExecutionError, ExecutionOutput, ExecutionQueryCycleInfos, ExecutionQueryStakerInfo,
-    ExecutionStackElement, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
-    ReadOnlyExecutionTarget, SlotExecutionOutput,
+    ExecutionError, ExecutionOutput, ExecutionOutputExport, ExecutionQueryCycleInfos,
+    ExecutionQueryStakerInfo, ExecutionStackElement, OperationSimulationOutput,
+    OperationSimulationStatus, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
+    ReadOnlyExecutionTarget, SerializedSlotExecutionOutput, SlotExecutionOutput,
+    SlotExecutionStats, StateDiff,
 };
-use massa_final_state::FinalStateController;
-use massa_ledger_exports::{SetOrDelete, SetUpdateOrDelete};
+use massa_final_state::{FinalStateController, StateChanges};
+use massa_ledger_exports::{LedgerEntryInfo, SetOrDelete, SetUpdateOrDelete};
 use massa_metrics::MassaMetrics;
-use massa_models::address::ExecutionAddressCycleInfo;
+use massa_models::address::{ExecutionAddressCycleInfo, IncomingAsyncMessage};
 use massa_models::bytecode::Bytecode;
+use massa_models::config::{
+    DUST_PRUNING_EXECUTION_VERSION, MAX_INCOMING_ASYNC_MESSAGES_PER_ADDRESS,
+};
 
 use massa_models::datastore::get_prefix_bounds;
 use massa_models::denunciation::{Denunciation, DenunciationIndex};
-use massa_models::execution::EventFilter;
-use massa_models::output_event::SCOutputEvent;
+use massa_models::execution::{
+    AsyncMessageFeeSuggestion, AsyncMessageFilter, EventFilter, PendingAsyncMessage,
+};
+use massa_models::output_event::{EventExecutionContext, SCOutputEvent};
 use massa_models::prehash::PreHashSet;
 use massa_models::stats::ExecutionStats;
 use massa_models::timeslots::get_block_slot_timestamp;
 use massa_models::{
-    address::Address,
+    address::{Address, UserAddress, UserAddressV0},
     block_id::BlockId,
-    operation::{OperationId, OperationType, SecureShareOperation},
+    operation::{OperationAddressRole, OperationId, OperationType, SecureShareOperation},
 };
 use massa_models::{amount::Amount, slot::Slot};
 use massa_module_cache::config::ModuleCacheConfig;
 use massa_module_cache::controller::ModuleCache;
 use massa_pos_exports::SelectorController;
 use massa_sc_runtime::{Interface, Response, VMError};
-use massa_versioning::versioning::MipStore;
+use massa_versioning::versioning::{MipComponent, MipStore};
 use massa_wallet::Wallet;
 use parking_lot::{Mutex, RwLock};
-use std::collections::{BTreeMap, BTreeSet};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
 use std::sync::Arc;
+use std::time::Instant;
 use tracing::{debug, info, trace, warn};
 
 #[cfg(feature = "execution-trace")]
-use crate::trace_history::TraceHistory;
+use crate::trace_history::{load_abi_gas_costs, TraceHistory};
 #[cfg(feature = "execution-trace")]
-use massa_execution_exports::{AbiTrace, SlotAbiCallStack, Transfer};
+use massa_execution_exports::{AbiTrace, OperationTrace, SlotAbiCallStack, Transfer};
 #[cfg(feature = "execution-trace")]
 use massa_models::config::{BASE_OPERATION_GAS_COST, MAX_GAS_PER_BLOCK, MAX_OPERATIONS_PER_BLOCK};
 #[cfg(feature = "execution-trace")]
@@ -95,6 +106,12 @@ pub(crate) struct ExecutionState {
     pub final_cursor: Slot,
     // store containing execution events that became final
     final_events: EventStore,
+    // store containing the final events spilled beyond the max number of events of their slot
+    spilled_events: EventStore,
+    // state changes of the latest final slots, to compute state diffs
+    final_state_changes: VecDeque<(Slot, StateChanges)>,
+    // execution statistics of the latest final slots
+    final_slot_stats: VecDeque<(Slot, SlotExecutionStats)>,
     // final state with atomic R/W access
     final_state: Arc<RwLock<dyn FinalStateController>>,
     // execution context (see documentation in context.rs)
@@ -115,6 +132,12 @@ pub(crate) struct ExecutionState {
     channels: ExecutionChannels,
     /// prometheus metrics
     massa_metrics: MassaMetrics,
+    // persistent index of the final operations by address, if enabled
+    operation_index: Option<OperationIndex>,
+    // persistent index of the final events, replacing the final event stores if enabled
+    event_index: Option<EventIndex>,
+    // whether the writes to the operation and event indexes are suspended
+    index_writes_suspended: bool,
     #[cfg(feature = "execution-trace")]
     pub(crate) trace_history: Arc<RwLock<TraceHistory>>,
 }
@@ -185,6 +208,10 @@ impl ExecutionState {
             active_history,
             // empty final event store: it is not recovered through bootstrap
             final_events: Default::default(),
+            spilled_events: Default::default(),
+            // empty state changes history: it is not recovered through bootstrap
+            final_state_changes: Default::default(),
+            final_slot_stats: Default::default(),
             // no active slots executed yet: set active_cursor to the last final block
             active_cursor: last_final_slot,
             final_cursor: last_final_slot,
@@ -195,6 +222,19 @@ impl ExecutionState {
             channels,
             wallet,
             massa_metrics,
+            operation_index: config.operation_index_enabled.then(|| {
+                OperationIndex::new(
+                    config.operation_index_path.clone(),
+                    config.operation_index_retention_periods,
+                )
+            }),
+            event_index: config.event_index_enabled.then(|| {
+                EventIndex::new(
+                    config.event_index_path.clone(),
+                    config.event_index_retention_periods,
+                )
+            }),
+            index_writes_suspended: false,
             #[cfg(feature = "execution-trace")]
             trace_history: Arc::new(RwLock::new(TraceHistory::new(
                 config.max_execution_traces_slot_limit as u32,
@@ -202,6 +242,7 @@ impl ExecutionState {
                     MAX_OPERATIONS_PER_BLOCK,
                     (MAX_GAS_PER_BLOCK / BASE_OPERATION_GAS_COST) as u32,
                 ),
+                load_abi_gas_costs(&config.abi_gas_costs_file),
             ))),
             config,
         }
@@ -212,6 +253,29 @@ impl ExecutionState {
         self.final_state.read().get_fingerprint()
     }
 
+    /// Get the execution trail hash of the last executed slot,
+    /// which identifies the state the read-only requests are executed on
+    pub fn get_execution_trail_hash(&self) -> massa_hash::Hash {
+        match self.active_history.read().get_execution_trail_hash() {
+            HistorySearchResult::Present(hash) => hash,
+            _ => self.final_state.read().get_execution_trail_hash(),
+        }
+    }
+
+    /// Get the execution statistics of the latest final slots, at most `last_slot_count` of them,
+    /// sorted by slot
+    pub fn get_slot_execution_stats(
+        &self,
+        last_slot_count: usize,
+    ) -> Vec<(Slot, SlotExecutionStats)> {
+        let skipped = self.final_slot_stats.len().saturating_sub(last_slot_count);
+        self.final_slot_stats
+            .iter()
+            .skip(skipped)
+            .cloned()
+            .collect()
+    }
+
     /// Get execution statistics
     pub fn get_stats(&self) -> ExecutionStats {
         self.stats_counter
@@ -244,7 +308,18 @@ impl ExecutionState {
         // as it will also write the MIP store on disk
         self.update_versioning_stats(&exec_out.block_info, &exec_out.slot);
 
+        // add the deletion of the dust accounts to the state changes of the slot
+        self.prune_dust_accounts(&mut exec_out);
+
         let exec_out_2 = exec_out.clone();
+        // keep the state changes of the slot to compute state diffs
+        if self.config.state_diff_history_length > 0 {
+            self.final_state_changes
+                .push_back((exec_out.slot, exec_out.state_changes.clone()));
+            while self.final_state_changes.len() > self.config.state_diff_history_length {
+                self.final_state_changes.pop_front();
+            }
+        }
         // apply state changes to the final ledger
         self.final_state
             .write()
@@ -259,10 +334,26 @@ impl ExecutionState {
             self.active_cursor = self.final_cursor;
         }
 
-        // append generated events to the final event store
+        // append generated events to the final event stores, or to the event index if enabled
         exec_out.events.finalize();
-        self.final_events.extend(exec_out.events);
-        self.final_events.prune(self.config.max_final_events);
+        let mut spilled_events = exec_out
+            .event_overflow
+            .map(|event_overflow| event_overflow.spilled_events)
+            .unwrap_or_default();
+        spilled_events.finalize();
+        if let Some(event_index) = &self.event_index {
+            if !self.index_writes_suspended {
+                event_index.index_events(
+                    &exec_out.slot,
+                    exec_out.events.0.iter().chain(spilled_events.0.iter()),
+                );
+            }
+        } else {
+            self.final_events.extend(exec_out.events);
+            self.final_events.prune(self.config.max_final_events);
+            self.spilled_events.extend(spilled_events);
+            self.spilled_events.prune(self.config.max_final_events);
+        }
 
         // update the prometheus metrics
         self.massa_metrics
@@ -290,9 +381,27 @@ impl ExecutionState {
         if exec_out.block_info.is_some() {
             self.massa_metrics.inc_executed_final_slot_with_block();
         }
+        let stats = &exec_out.stats;
+        self.massa_metrics.set_final_slot_stats(
+            stats.gas_used,
+            stats.gas_limit,
+            stats.operation_count,
+            stats.event_count,
+            stats.execution_time,
+        );
+
+        // keep the execution statistics of the slot
+        if self.config.slot_stats_history_length > 0 {
+            self.final_slot_stats
+                .push_back((exec_out.slot, exec_out.stats));
+            while self.final_slot_stats.len() > self.config.slot_stats_history_length {
+                self.final_slot_stats.pop_front();
+            }
+        }
 
         // Broadcast a final slot execution output to active channel subscribers.
         if self.config.broadcast_enabled {
+            self.broadcast_serialized_output(&exec_out_2, true);
             let slot_exec_out = SlotExecutionOutput::FinalizedSlot(exec_out_2);
             if let Err(err) = self
                 .channels
@@ -327,6 +436,40 @@ impl ExecutionState {
         }
     }
 
+    /// Broadcast the execution output of a slot exported in the configured format, if any
+    fn broadcast_serialized_output(&self, exec_out: &ExecutionOutput, is_final: bool) {
+        let Some(format) = self.config.broadcast_serialized_output_format else {
+            return;
+        };
+        let data = match format.serialize(&ExecutionOutputExport::new(exec_out, is_final)) {
+            Ok(data) => data,
+            Err(err) => {
+                warn!(
+                    "failed to export execution output for slot {}: {}",
+                    exec_out.slot, err
+                );
+                return;
+            }
+        };
+        let serialized_out = SerializedSlotExecutionOutput {
+            slot: exec_out.slot,
+            is_final,
+            format,
+            data,
+        };
+        if let Err(err) = self
+            .channels
+            .slot_execution_serialized_output_sender
+            .send(serialized_out)
+        {
+            trace!(
+                "error, failed to broadcast serialized execution output for slot {} due to: {}",
+                exec_out.slot,
+                err
+            );
+        }
+    }
+
     /// Applies an execution output to the active (non-final) state
     /// The newly active final output should be from the slot just after the last executed active slot
     ///
@@ -407,6 +550,13 @@ impl ExecutionState {
         // set the context origin operation ID
         context.origin_operation_id = Some(operation_id);
 
+        // start the execution trace of the operation
+        #[cfg(feature = "execution-trace")]
+        {
+            context.storage_accesses.clear();
+            context.gas_used = 0;
+        }
+
         Ok(context_snapshot)
     }
 
@@ -493,6 +643,9 @@ impl ExecutionState {
             OperationType::Transaction { .. } => self
                 .execute_transaction_op(&operation.content.op, sender_addr)
                 .map(|_| res),
+            OperationType::CancelAsyncMessage { .. } => self
+                .execute_cancel_async_message_op(&operation.content.op, sender_addr)
+                .map(|_| res),
         };
 
         {
@@ -743,6 +896,49 @@ impl ExecutionState {
         Ok(())
     }
 
+    /// Execute an operation of type `CancelAsyncMessage`
+    /// Will panic if called with another operation type
+    ///
+    /// # Arguments
+    /// * `operation`: the `WrappedOperation` to process, must be a `CancelAsyncMessage`
+    /// * `sender_addr`: address of the sender, that must have emitted the message
+    pub fn execute_cancel_async_message_op(
+        &self,
+        operation: &OperationType,
+        sender_addr: Address,
+    ) -> Result<(), ExecutionError> {
+        // process async message cancellations only
+        let (emission_slot, emission_index) = match operation {
+            OperationType::CancelAsyncMessage {
+                emission_slot,
+                emission_index,
+            } => (*emission_slot, *emission_index),
+            _ => panic!("unexpected operation type"),
+        };
+
+        // acquire write access to the context
+        let mut context = context_guard!(self);
+
+        // Set call stack
+        // This needs to be defined before anything can fail, so that the emitted event contains the right stack
+        context.stack = vec![ExecutionStackElement {
+            address: sender_addr,
+            coins: Amount::default(),
+            owned_addresses: vec![sender_addr],
+            operation_datastore: None,
+        }];
+
+        // the cancellations are only valid once activated by the execution MIP component
+        if self.get_execution_version(&context.slot) < operation.required_execution_version() {
+            return Err(ExecutionError::CancelAsyncMessageError(
+                "the cancellation of async messages is not active yet".to_string(),
+            ));
+        }
+
+        // remove the message from the pool and reimburse its coins
+        context.cancel_emitted_message(&sender_addr, emission_slot, emission_index)
+    }
+
     /// Execute an operation of type `RollBuy`
     /// Will panic if called with another operation type
     ///
@@ -901,6 +1097,7 @@ impl ExecutionState {
 
         #[cfg(feature = "execution-trace")]
         {
+            context_guard!(self).gas_used = max_gas.saturating_sub(_res.remaining_gas);
             Ok(_res.trace.into_iter().map(|t| t.into()).collect())
         }
         #[cfg(not(feature = "execution-trace"))]
@@ -1009,6 +1206,7 @@ impl ExecutionState {
         })?;
         #[cfg(feature = "execution-trace")]
         {
+            context_guard!(self).gas_used = max_gas.saturating_sub(_response.remaining_gas);
             Ok(_response.trace.into_iter().map(|t| t.into()).collect())
         }
         #[cfg(not(feature = "execution-trace"))]
@@ -1148,6 +1346,11 @@ impl ExecutionState {
         exec_target: Option<&(BlockId, ExecutionBlockMetadata)>,
         selector: Box<dyn SelectorController>,
     ) -> ExecutionOutput {
+        let execution_start = Instant::now();
+        let mut stats = SlotExecutionStats {
+            gas_limit: self.config.max_gas_per_block,
+            ..Default::default()
+        };
         #[cfg(feature = "execution-trace")]
         let mut slot_trace = SlotAbiCallStack {
             slot: *slot,
@@ -1156,6 +1359,9 @@ impl ExecutionState {
         };
         #[cfg(feature = "execution-trace")]
         let mut transfers = vec![];
+        #[cfg(feature = "execution-trace")]
+        let mut operation_traces = vec![];
+        self.set_module_cache_version(slot);
         // Create a new execution context for the whole active slot
         let mut execution_context = ExecutionContext::active_slot(
             self.config.clone(),
@@ -1230,6 +1436,7 @@ impl ExecutionState {
             };
 
             debug!("executing {} operations at slot {}", operations.len(), slot);
+            stats.operation_count = operations.len();
 
             // gather all available endorsement creators and target blocks
             let endorsement_creators: Vec<Address> = stored_block
@@ -1264,7 +1471,7 @@ impl ExecutionState {
                         {
                             slot_trace
                                 .operation_call_stacks
-                                .insert(operation.id, _op_return.0);
+                                .insert(operation.id, _op_return.0.clone());
                             match &operation.content.op {
                                 OperationType::Transaction {
                                     recipient_address,
@@ -1317,6 +1524,27 @@ impl ExecutionState {
                                 }
                                 _ => {}
                             }
+                            let (storage_accesses, gas_used) = {
+                                let mut context = context_guard!(self);
+                                (
+                                    std::mem::take(&mut context.storage_accesses),
+                                    context.gas_used,
+                                )
+                            };
+                            operation_traces.push(OperationTrace {
+                                op_id: operation.id,
+                                slot: *slot,
+                                block_id: *block_id,
+                                succeed: _op_return.1,
+                                gas_used,
+                                abi_calls: _op_return.0,
+                                storage_accesses,
+                                transfers: transfers
+                                    .iter()
+                                    .filter(|transfer| transfer.op_id == operation.id)
+                                    .cloned()
+                                    .collect(),
+                            });
                         }
                     }
                     Err(err) => {
@@ -1328,6 +1556,11 @@ impl ExecutionState {
                 }
             }
 
+            stats.gas_used = self
+                .config
+                .max_gas_per_block
+                .saturating_sub(remaining_block_gas);
+
             // Try executing the denunciations of this block
             for denunciation in &stored_block.content.header.content.denunciations {
                 if let Err(e) = self.execute_denunciation(
@@ -1419,19 +1652,27 @@ impl ExecutionState {
         self.trace_history
             .write()
             .save_transfers_for_slot(*slot, transfers.clone());
+        #[cfg(feature = "execution-trace")]
+        self.trace_history
+            .write()
+            .save_operation_traces(operation_traces);
         // Finish slot
-        #[cfg(not(feature = "execution-trace"))]
-        let exec_out = context_guard!(self).settle_slot(block_info);
-
+        let mut exec_out = context_guard!(self).settle_slot(block_info);
         #[cfg(feature = "execution-trace")]
-        let exec_out = {
-            let mut out = context_guard!(self).settle_slot(block_info);
-            out.slot_trace = Some((slot_trace, transfers));
-            out
-        };
+        {
+            exec_out.slot_trace = Some((slot_trace, transfers));
+        }
+        stats.event_count = exec_out.events.0.len()
+            + exec_out
+                .event_overflow
+                .as_ref()
+                .map_or(0, |overflow| overflow.overflowing_event_count);
+        stats.execution_time = execution_start.elapsed();
+        exec_out.stats = stats;
 
         // Broadcast a slot execution output to active channel subscribers.
         if self.config.broadcast_enabled {
+            self.broadcast_serialized_output(&exec_out, false);
             let slot_exec_out = SlotExecutionOutput::ExecutedSlot(exec_out.clone());
             if let Err(err) = self
                 .channels
@@ -1450,6 +1691,116 @@ impl ExecutionState {
         exec_out
     }
 
+    /// Protocol rule pruning the dust accounts of the final ledger, applied at each final slot
+    /// once the execution MIP component reaches `DUST_PRUNING_EXECUTION_VERSION`.
+    ///
+    /// The `dust_pruning_scan_length` ledger entries starting from the user address whose hash
+    /// is the hash of the slot are examined, wrapping around at the end of the ledger.
+    /// The ones with a balance strictly below `dust_account_max_balance`, neither bytecode nor
+    /// datastore entries, and that are not changed by the slot, are deleted along with the
+    /// changes of the slot. The examined entries only depend on the final state before the slot,
+    /// so that all the nodes applying the rule prune the same accounts.
+    /// The balances of the pruned accounts are burned, as reported by an event of the slot.
+    ///
+    /// The speculative executions of the following slots that changed a pruned account, or read it
+    /// from the final state, are cancelled from the first of them, to be executed again.
+    fn prune_dust_accounts(&mut self, exec_out: &mut ExecutionOutput) {
+        if self.get_execution_version(&exec_out.slot) < DUST_PRUNING_EXECUTION_VERSION {
+            return;
+        }
+        let from = Address::User(UserAddress::UserAddressV0(UserAddressV0(
+            massa_hash::Hash::compute_from(&exec_out.slot.to_bytes_key()),
+        )));
+        let dust_accounts: Vec<(Address, Amount)> = self
+            .final_state
+            .read()
+            .get_ledger()
+            .get_dust_accounts(
+                &from,
+                self.config.dust_account_max_balance,
+                self.config.dust_pruning_scan_length,
+            )
+            .into_iter()
+            .filter(|(address, _)| {
+                !exec_out
+                    .state_changes
+                    .ledger_changes
+                    .0
+                    .contains_key(address)
+            })
+            .collect();
+        if dust_accounts.is_empty() {
+            return;
+        }
+        let burned = dust_accounts
+            .iter()
+            .fold(Amount::zero(), |burned, (_, balance)| {
+                burned.saturating_add(*balance)
+            });
+        debug!(
+            "pruning {} dust accounts at slot {}, burning {}",
+            dust_accounts.len(),
+            exec_out.slot,
+            burned
+        );
+        for (address, _) in &dust_accounts {
+            exec_out
+                .state_changes
+                .ledger_changes
+                .0
+                .insert(*address, SetUpdateOrDelete::Delete);
+        }
+
+        // report the pruned accounts and the burned amount in the events of the slot
+        let index_in_slot = exec_out
+            .events
+            .0
+            .back()
+            .map_or(0, |event| event.context.index_in_slot + 1);
+        exec_out.events.push(SCOutputEvent {
+            context: EventExecutionContext {
+                slot: exec_out.slot,
+                block: exec_out.block_info.as_ref().map(|info| info.block_id),
+                read_only: false,
+                index_in_slot,
+                call_stack: VecDeque::new(),
+                origin_operation_id: None,
+                is_final: false,
+                is_error: false,
+            },
+            data: serde_json::json!({
+                "massa_dust_accounts_pruned": dust_accounts
+                    .iter()
+                    .map(|(address, _)| address.to_string())
+                    .collect::<Vec<_>>(),
+                "massa_dust_burned": burned.to_string(),
+            })
+            .to_string(),
+        });
+
+        let mut active_history = self.active_history.write();
+        let first_affected_slot = active_history
+            .0
+            .iter()
+            .find(|output| {
+                dust_accounts.iter().any(|(address, _)| {
+                    output.final_ledger_reads.contains(address)
+                        || output.state_changes.ledger_changes.0.contains_key(address)
+                })
+            })
+            .map(|output| output.slot);
+        if let Some(slot) = first_affected_slot {
+            debug!(
+                "pruned dust accounts used by the active slots, truncating down from slot {}",
+                slot
+            );
+            active_history.truncate_from(&slot, self.config.thread_count);
+            self.active_cursor = slot
+                .get_prev_slot(self.config.thread_count)
+                .expect("overflow when iterating on slots");
+        }
+    }
+
     /// Execute a candidate slot
     pub fn execute_candidate_slot(
         &mut self,
@@ -1531,6 +1882,9 @@ impl ExecutionState {
             return;
         }
 
+        // index the operations of the final block
+        self.index_final_operations(slot, exec_target);
+
         // check if the final slot execution result is already cached at the front of the speculative execution history
         let first_exec_output = self.active_history.write().0.pop_front();
 
@@ -1572,6 +1926,44 @@ impl ExecutionState {
         );
     }
 
+    /// Suspend or resume the writes to the operation and event indexes
+    pub fn set_index_writes_suspended(&mut self, suspended: bool) {
+        self.index_writes_suspended = suspended;
+    }
+
+    /// Indexes the operations of a final block by emitter and recipient address,
+    /// if the operation index is enabled and its writes are not suspended
+    fn index_final_operations(
+        &self,
+        slot: &Slot,
+        exec_target: Option<&(BlockId, ExecutionBlockMetadata)>,
+    ) {
+        if self.index_writes_suspended {
+            return;
+        }
+        let (Some(operation_index), Some((block_id, block_metadata))) =
+            (&self.operation_index, exec_target)
+        else {
+            return;
+        };
+        let Some(block_store) = block_metadata.storage.as_ref() else {
+            return;
+        };
+        let operations: Vec<SecureShareOperation> = {
+            let Some(block) = block_store.read_blocks().get(block_id).cloned() else {
+                return;
+            };
+            let ops = block_store.read_operations();
+            block
+                .content
+                .operations
+                .iter()
+                .filter_map(|op_id| ops.get(op_id).cloned())
+                .collect()
+        };
+        operation_index.index_operations(slot, &operations);
+    }
+
     /// Runs a read-only execution request.
     /// The executed bytecode appears to be able to read and write the consensus state,
     /// but all accumulated changes are simply returned as an `ExecutionOutput` object,
@@ -1602,9 +1994,10 @@ impl ExecutionState {
             .active_cursor
             .get_next_slot(self.config.thread_count)
             .expect("slot overflow in readonly execution from active slot");
+        self.set_module_cache_version(&slot);
 
         // create a readonly execution context
-        let execution_context = ExecutionContext::readonly(
+        let mut execution_context = ExecutionContext::readonly(
             self.config.clone(),
             slot,
             req.call_stack,
@@ -1614,6 +2007,9 @@ impl ExecutionState {
             self.mip_store.clone(),
         );
 
+        // apply the temporary state overrides to the speculative ledger of the context
+        execution_context.apply_state_overrides(req.state_overrides)?;
+
         // run the interpreter according to the target type
         let exec_response = match req.target {
             ReadOnlyExecutionTarget::BytecodeExecution(bytecode) => {
@@ -1742,6 +2138,73 @@ impl ExecutionState {
             out: execution_output,
             gas_cost: estimated_cost,
             call_result: exec_response.ret,
+            #[cfg(feature = "execution-trace")]
+            abi_calls: exec_response.trace.into_iter().map(|t| t.into()).collect(),
+        })
+    }
+
+    /// Simulates the execution of an operation, as if it was included in a block
+    /// at the first slot of its thread following the latest executed active slot.
+    /// Like read-only executions, the accumulated changes are returned
+    /// and not applied to the consensus state.
+    ///
+    /// # Arguments
+    /// * `operation`: the operation to simulate, whose signature is not checked
+    ///
+    /// # Returns
+    /// `OperationSimulationOutput` describing the effects of the operation and its status
+    pub(crate) fn simulate_operation(
+        &self,
+        operation: SecureShareOperation,
+    ) -> Result<OperationSimulationOutput, ExecutionError> {
+        let op_thread = operation
+            .content_creator_address
+            .get_thread(self.config.thread_count);
+        let mut slot = self.active_cursor;
+        loop {
+            slot = slot
+                .get_next_slot(self.config.thread_count)
+                .map_err(|_| ExecutionError::InvalidSlotRange)?;
+            if slot.thread == op_thread {
+                break;
+            }
+        }
+
+        // the operation is executed in the context of a slot without block
+        self.set_module_cache_version(&slot);
+        *context_guard!(self) = ExecutionContext::active_slot(
+            self.config.clone(),
+            slot,
+            None,
+            self.final_state.clone(),
+            self.active_history.clone(),
+            self.module_cache.clone(),
+            self.mip_store.clone(),
+        );
+        let mut remaining_block_gas = self.config.max_gas_per_block;
+        let mut block_credits = Amount::zero();
+        let execution_result = self.execute_operation(
+            &operation,
+            slot,
+            &mut remaining_block_gas,
+            &mut block_credits,
+        );
+
+        let out = context_guard!(self).settle_slot(None);
+        let status = match execution_result {
+            Err(err) => OperationSimulationStatus::Rejected(err.to_string()),
+            Ok(_) => match out.state_changes.executed_ops_changes.get(&operation.id) {
+                Some((true, _)) => OperationSimulationStatus::Success,
+                _ => OperationSimulationStatus::Failure,
+            },
+        };
+        Ok(OperationSimulationOutput {
+            out,
+            gas_usage: self
+                .config
+                .max_gas_per_block
+                .saturating_sub(remaining_block_gas),
+            status,
         })
     }
 
@@ -1830,7 +2293,7 @@ impl ExecutionState {
             .final_state
             .read()
             .get_ledger()
-            .get_datastore_keys(addr, prefix);
+            .get_datastore_keys(addr, prefix, None, None);
 
         let mut candidate_keys = final_keys.clone();
 
@@ -1882,6 +2345,88 @@ impl ExecutionState {
         context_guard!(self).get_address_cycle_infos(address, self.config.periods_per_cycle)
     }
 
+    /// Gets the asynchronous messages of the final pool targeting an address,
+    /// at most `MAX_INCOMING_ASYNC_MESSAGES_PER_ADDRESS` from the highest priority to the lowest
+    pub fn get_address_incoming_async_messages(
+        &self,
+        address: &Address,
+    ) -> Vec<IncomingAsyncMessage> {
+        self.final_state
+            .read()
+            .get_async_pool()
+            .fetch_messages_by_destination(address, MAX_INCOMING_ASYNC_MESSAGES_PER_ADDRESS)
+            .into_iter()
+            .map(|message| IncomingAsyncMessage {
+                emission_slot: message.emission_slot,
+                emission_index: message.emission_index,
+                sender: message.sender,
+                function: message.function,
+                coins: message.coins,
+                validity_start: message.validity_start,
+                validity_end: message.validity_end,
+            })
+            .collect()
+    }
+
+    /// Gets the asynchronous messages of the final pool matching a filter,
+    /// from the highest priority to the lowest
+    pub fn get_async_messages(&self, filter: &AsyncMessageFilter) -> Vec<PendingAsyncMessage> {
+        self.final_state
+            .read()
+            .get_async_pool()
+            .fetch_filtered_messages(filter)
+            .into_iter()
+            .map(|message| {
+                let (std::cmp::Reverse(priority), _, _) = message.compute_id();
+                PendingAsyncMessage {
+                    emission_slot: message.emission_slot,
+                    emission_index: message.emission_index,
+                    sender: message.sender,
+                    destination: message.destination,
+                    function: message.function,
+                    max_gas: message.max_gas,
+                    fee: message.fee,
+                    coins: message.coins,
+                    validity_start: message.validity_start,
+                    validity_end: message.validity_end,
+                    can_be_executed: message.can_be_executed,
+                    priority: *priority.numer() as f64 / *priority.denom() as f64,
+                }
+            })
+            .collect()
+    }
+
+    /// Gets the occupancy of the asynchronous pool, including the messages emitted in the active
+    /// slots, and the minimum fee for a new message using `max_gas` to be retained in it.
+    /// The priority of a message only depends on its fee and max gas, not on its size.
+    pub fn get_async_message_fee_suggestion(&self, max_gas: u64) -> AsyncMessageFeeSuggestion {
+        let max_pool_length = self.final_state.read().get_async_pool().config.max_length;
+        let (pool_length, lowest_priority) =
+            SpeculativeAsyncPool::new(self.final_state.clone(), self.active_history.clone())
+                .get_occupancy();
+
+        // a new message is placed after the ones of the same priority,
+        // so it must exceed the lowest priority to be retained in a full pool
+        let eviction_threshold = lowest_priority.filter(|_| pool_length as u64 >= max_pool_length);
+        let min_fee = match eviction_threshold {
+            Some(threshold) => {
+                let fee = u128::from(*threshold.numer()) * u128::from(max_gas.max(1))
+                    / u128::from(*threshold.denom())
+                    + 1;
+                Amount::from_raw(u64::try_from(fee).unwrap_or(u64::MAX))
+            }
+            None => Amount::zero(),
+        };
+        AsyncMessageFeeSuggestion {
+            pool_length: pool_length as u64,
+            max_pool_length,
+            eviction_threshold: eviction_threshold
+                .map(|threshold| *threshold.numer() as f64 / *threshold.denom() as f64),
+            max_gas,
+            min_fee,
+        }
+    }
+
     /// Returns for a given cycle the stakers taken into account
     /// by the selector. That correspond to the `roll_counts` in `cycle - 3`.
     ///
@@ -1901,31 +2446,39 @@ impl ExecutionState {
     /// * operation id
     /// * event state (final, candidate or both)
     pub fn get_filtered_sc_output_event(&self, filter: EventFilter) -> Vec<SCOutputEvent> {
-        match filter.is_final {
-            Some(true) => self
-                .final_events
+        // the events spilled beyond the max number of events of their slot are included
+        let final_events = || -> Vec<SCOutputEvent> {
+            if let Some(event_index) = &self.event_index {
+                return event_index.get_events(&filter, self.config.max_final_events);
+            }
+            self.final_events
                 .get_filtered_sc_output_events(&filter)
                 .into_iter()
-                .collect(),
-            Some(false) => self
-                .active_history
+                .chain(self.spilled_events.get_filtered_sc_output_events(&filter))
+                .collect()
+        };
+        let active_events = || -> Vec<SCOutputEvent> {
+            self.active_history
                 .read()
                 .0
                 .iter()
-                .flat_map(|item| item.events.get_filtered_sc_output_events(&filter))
-                .collect(),
-            None => self
-                .final_events
-                .get_filtered_sc_output_events(&filter)
-                .into_iter()
-                .chain(
-                    self.active_history
-                        .read()
-                        .0
-                        .iter()
-                        .flat_map(|item| item.events.get_filtered_sc_output_events(&filter)),
-                )
-                .collect(),
+                .flat_map(|item| {
+                    let spilled_events = item.event_overflow.as_ref().map(|event_overflow| {
+                        event_overflow
+                            .spilled_events
+                            .get_filtered_sc_output_events(&filter)
+                    });
+                    item.events
+                        .get_filtered_sc_output_events(&filter)
+                        .into_iter()
+                        .chain(spilled_events.into_iter().flatten())
+                })
+                .collect()
+        };
+        match filter.is_final {
+            Some(true) => final_events(),
+            Some(false) => active_events(),
+            None => final_events().into_iter().chain(active_events()).collect(),
         }
     }
 
@@ -2084,6 +2637,109 @@ impl ExecutionState {
             .collect()
     }
 
+    /// Gets the final operations in which an address has the given role, from the most recent one
+    pub fn get_operations_by_address(
+        &self,
+        address: &Address,
+        role: OperationAddressRole,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<(Slot, OperationId)>, ExecutionError> {
+        match &self.operation_index {
+            Some(operation_index) => operation_index.get_operations(address, role, offset, limit),
+            None => Err(ExecutionError::OperationIndexError(
+                "the operation index is disabled".to_string(),
+            )),
+        }
+    }
+
+    /// Gets the changes made to the final state after `from_slot` and up to `to_slot` (included)
+    pub fn get_state_diff(
+        &self,
+        from_slot: Slot,
+        to_slot: Slot,
+    ) -> Result<StateDiff, ExecutionError> {
+        if from_slot >= to_slot {
+            return Err(ExecutionError::StateDiffError(
+                "from_slot must be before to_slot".to_string(),
+            ));
+        }
+        if to_slot > self.final_cursor {
+            return Err(ExecutionError::StateDiffError(format!(
+                "slot {} is not final yet",
+                to_slot
+            )));
+        }
+        // the history must start at the slot following from_slot at the latest
+        let first_slot = from_slot
+            .get_next_slot(self.config.thread_count)
+            .map_err(|err| ExecutionError::StateDiffError(err.to_string()))?;
+        match self.final_state_changes.front() {
+            Some((oldest_slot, _)) if *oldest_slot <= first_slot => {}
+            _ => {
+                return Err(ExecutionError::StateDiffError(format!(
+                    "the state changes of slot {} are not available anymore",
+                    first_slot
+                )));
+            }
+        }
+
+        let mut state_changes = StateChanges::default();
+        for (_, slot_changes) in self
+            .final_state_changes
+            .iter()
+            .filter(|(slot, _)| *slot > from_slot && *slot <= to_slot)
+        {
+            state_changes.apply(slot_changes.clone());
+        }
+        Ok(StateDiff {
+            from_slot,
+            to_slot,
+            state_changes,
+        })
+    }
+
+    /// Get the summaries of up to `page_size` entries of the final ledger in address order,
+    /// starting from the one of `page_token`, and the token of the next page if any
+    pub fn iter_ledger(
+        &self,
+        page_token: Option<Address>,
+        page_size: usize,
+    ) -> (Vec<LedgerEntryInfo>, Option<Address>) {
+        self.final_state
+            .read()
+            .get_ledger()
+            .iter_ledger(page_token, page_size)
+    }
+
+    /// Load the modules executed at `slot` for the execution runtime version active at that slot
+    fn set_module_cache_version(&self, slot: &Slot) {
+        let slot_ts = get_block_slot_timestamp(
+            self.config.thread_count,
+            self.config.t0,
+            self.config.genesis_timestamp,
+            *slot,
+        )
+        .expect("Cannot get timestamp from slot");
+        let version = self
+            .mip_store
+            .get_latest_component_version_at(&MipComponent::VM, slot_ts);
+        self.module_cache.write().set_runtime_version(version);
+    }
+
+    /// Get the version of the execution MIP component active at `slot`
+    fn get_execution_version(&self, slot: &Slot) -> u32 {
+        let slot_ts = get_block_slot_timestamp(
+            self.config.thread_count,
+            self.config.t0,
+            self.config.genesis_timestamp,
+            *slot,
+        )
+        .expect("Cannot get timestamp from slot");
+        self.mip_store
+            .get_latest_component_version_at(&MipComponent::Execution, slot_ts)
+    }
+
     /// Update MipStore with block header stats
     pub fn update_versioning_stats(&mut self, block_info: &Option<ExecutedBlockInfo>, slot: &Slot) {
         let slot_ts = get_block_slot_timestamp(
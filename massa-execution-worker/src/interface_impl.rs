@@ -51,6 +51,9 @@ use tracing::warn;
 ))]
 use massa_models::datastore::Datastore;
 
+#[cfg(feature = "execution-trace")]
+use massa_execution_exports::StorageAccessType;
+
 /// helper for locking the context mutex
 macro_rules! context_guard {
     ($self:ident) => {
@@ -441,8 +444,11 @@ impl Interface for InterfaceImpl {
     ///
     /// [DeprecatedByNewRuntime] Replaced by `raw_get_data_wasmv1`
     fn raw_get_data(&self, key: &[u8]) -> Result<Vec<u8>> {
-        let context = context_guard!(self);
+        #[cfg_attr(not(feature = "execution-trace"), allow(unused_mut))]
+        let mut context = context_guard!(self);
         let addr = context.get_current_address()?;
+        #[cfg(feature = "execution-trace")]
+        context.trace_storage_access(&addr, key, StorageAccessType::Read);
         match context.get_data_entry(&addr, key) {
             Some(value) => Ok(value),
             _ => bail!("data entry not found"),
@@ -461,7 +467,10 @@ impl Interface for InterfaceImpl {
     /// [DeprecatedByNewRuntime] Replaced by `raw_get_data_wasmv1`
     fn raw_get_data_for(&self, address: &str, key: &[u8]) -> Result<Vec<u8>> {
         let addr = &massa_models::address::Address::from_str(address)?;
-        let context = context_guard!(self);
+        #[cfg_attr(not(feature = "execution-trace"), allow(unused_mut))]
+        let mut context = context_guard!(self);
+        #[cfg(feature = "execution-trace")]
+        context.trace_storage_access(addr, key, StorageAccessType::Read);
         match context.get_data_entry(addr, key) {
             Some(value) => Ok(value),
             _ => bail!("data entry not found"),
@@ -477,8 +486,11 @@ impl Interface for InterfaceImpl {
     /// # Returns
     /// The datastore value matching the provided key, if found, otherwise an error.
     fn get_ds_value_wasmv1(&self, key: &[u8], address: Option<String>) -> Result<Vec<u8>> {
-        let context = context_guard!(self);
+        #[cfg_attr(not(feature = "execution-trace"), allow(unused_mut))]
+        let mut context = context_guard!(self);
         let address = get_address_from_opt_or_context(&context, address)?;
+        #[cfg(feature = "execution-trace")]
+        context.trace_storage_access(&address, key, StorageAccessType::Read);
 
         match context.get_data_entry(&address, key) {
             Some(value) => Ok(value),
@@ -630,8 +642,11 @@ impl Interface for InterfaceImpl {
     ///
     /// [DeprecatedByNewRuntime] Replaced by `has_data_wasmv1`
     fn has_data(&self, key: &[u8]) -> Result<bool> {
-        let context = context_guard!(self);
+        #[cfg_attr(not(feature = "execution-trace"), allow(unused_mut))]
+        let mut context = context_guard!(self);
         let addr = context.get_current_address()?;
+        #[cfg(feature = "execution-trace")]
+        context.trace_storage_access(&addr, key, StorageAccessType::Read);
         Ok(context.has_data_entry(&addr, key))
     }
 
@@ -647,7 +662,10 @@ impl Interface for InterfaceImpl {
     /// [DeprecatedByNewRuntime] Replaced by `has_data_wasmv1`
     fn has_data_for(&self, address: &str, key: &[u8]) -> Result<bool> {
         let addr = massa_models::address::Address::from_str(address)?;
-        let context = context_guard!(self);
+        #[cfg_attr(not(feature = "execution-trace"), allow(unused_mut))]
+        let mut context = context_guard!(self);
+        #[cfg(feature = "execution-trace")]
+        context.trace_storage_access(&addr, key, StorageAccessType::Read);
         Ok(context.has_data_entry(&addr, key))
     }
 
@@ -660,8 +678,11 @@ impl Interface for InterfaceImpl {
     /// # Returns
     /// true if the address exists and has the entry matching the provided key in its datastore, otherwise false
     fn ds_entry_exists_wasmv1(&self, key: &[u8], address: Option<String>) -> Result<bool> {
-        let context = context_guard!(self);
+        #[cfg_attr(not(feature = "execution-trace"), allow(unused_mut))]
+        let mut context = context_guard!(self);
         let address = get_address_from_opt_or_context(&context, address)?;
+        #[cfg(feature = "execution-trace")]
+        context.trace_storage_access(&address, key, StorageAccessType::Read);
 
         Ok(context.has_data_entry(&address, key))
     }
@@ -82,8 +82,12 @@
 mod active_history;
 mod context;
 mod controller;
+mod event_index;
 mod execution;
 mod interface_impl;
+mod operation_index;
+mod readonly_cache;
+mod readonly_limiter;
 mod request_queue;
 mod slot_sequencer;
 mod speculative_async_pool;
@@ -94,6 +98,8 @@ mod speculative_roll_state;
 mod stats;
 mod worker;
 
+#[cfg(feature = "execution-trace")]
+mod debugger;
 #[cfg(feature = "execution-trace")]
 mod trace_history;
 
@@ -0,0 +1,296 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Persistent index of the final operations by emitter and recipient address.
+//!
+//! Each entry is a key without value, made of the role of the address, the length-prefixed address,
+//! the slot of the block including the operation and the operation id.
+//! The entries of an address and role are thus sorted by slot.
+//! Each entry is also listed by slot, under a key made of the slot followed by the entry key,
+//! so that the operations older than the retention period are removed as new slots become final.
+
+use massa_execution_exports::ExecutionError;
+use massa_models::address::Address;
+use massa_models::operation::{
+    OperationAddressRole, OperationId, OperationIdDeserializer, OperationIdSerializer,
+    SecureShareOperation,
+};
+use massa_models::slot::{Slot, SLOT_KEY_SIZE};
+use massa_serialization::{DeserializeError, Deserializer, Serializer};
+use rocksdb::{Direction, IteratorMode, WriteBatch, DB};
+use std::path::PathBuf;
+
+const OPEN_ERROR: &str = "critical: rocksdb open operation failed";
+const CRUD_ERROR: &str = "critical: rocksdb crud operation failed";
+const OP_ID_SER_ERROR: &str = "critical: operation id serialization failed";
+const EMITTER_IDENT: u8 = 0u8;
+const RECIPIENT_IDENT: u8 = 1u8;
+const SLOT_IDENT: u8 = 2u8;
+
+pub(crate) struct OperationIndex {
+    /// RocksDB database
+    db: DB,
+    /// number of periods during which the final operations are kept (0 keeps them forever)
+    retention_periods: u64,
+    /// Operation id serializer
+    op_id_ser: OperationIdSerializer,
+    /// Operation id deserializer
+    op_id_deser: OperationIdDeserializer,
+}
+
+impl OperationIndex {
+    /// Open (or create) the operation index stored at `path`
+    pub fn new(path: PathBuf, retention_periods: u64) -> Self {
+        Self {
+            db: DB::open_default(path).expect(OPEN_ERROR),
+            retention_periods,
+            op_id_ser: OperationIdSerializer::new(),
+            op_id_deser: OperationIdDeserializer::new(),
+        }
+    }
+
+    /// Index the operations of a final block by emitter and recipient address,
+    /// removing the operations that went beyond the retention period
+    ///
+    /// # Arguments
+    /// * `slot`: slot of the block
+    /// * `operations`: operations included in the block
+    pub fn index_operations(&self, slot: &Slot, operations: &[SecureShareOperation]) {
+        let slot_key = slot.to_bytes_key();
+        let mut batch = WriteBatch::default();
+        for operation in operations {
+            let mut op_id = Vec::new();
+            self.op_id_ser
+                .serialize(&operation.id, &mut op_id)
+                .expect(OP_ID_SER_ERROR);
+            let mut prefixes = vec![address_prefix(
+                &operation.content_creator_address,
+                OperationAddressRole::Emitter,
+            )];
+            if let Some(recipient_address) = operation.get_recipient_address() {
+                let recipient_prefix =
+                    address_prefix(&recipient_address, OperationAddressRole::Recipient);
+                prefixes.push(recipient_prefix);
+            }
+            for prefix in prefixes {
+                let key = [&prefix[..], &slot_key, &op_id].concat();
+                batch.put([&[SLOT_IDENT][..], &slot_key, &key].concat(), b"");
+                batch.put(key, b"");
+            }
+        }
+        if self.retention_periods > 0 && slot.period > self.retention_periods {
+            self.prune(
+                &Slot::new(slot.period - self.retention_periods, 0),
+                &mut batch,
+            );
+        }
+        self.db.write(batch).expect(CRUD_ERROR);
+    }
+
+    /// Add to `batch` the deletion of the entries of the operations of the slots
+    /// before `first_kept_slot`
+    fn prune(&self, first_kept_slot: &Slot, batch: &mut WriteBatch) {
+        let upper_bound = [&[SLOT_IDENT][..], &first_kept_slot.to_bytes_key()].concat();
+        for entry in self
+            .db
+            .iterator(IteratorMode::From(&[SLOT_IDENT], Direction::Forward))
+        {
+            let (key, _) = entry.expect(CRUD_ERROR);
+            if key[..] >= upper_bound[..] {
+                break;
+            }
+            batch.delete(&key[1 + SLOT_KEY_SIZE..]);
+            batch.delete(key);
+        }
+    }
+
+    /// Get the operations in which an address has the given role, from the most recent one
+    ///
+    /// # Arguments
+    /// * `address`: the address to look for
+    /// * `role`: role of the address in the operations
+    /// * `offset`: number of operations to skip
+    /// * `limit`: max number of operations to return
+    pub fn get_operations(
+        &self,
+        address: &Address,
+        role: OperationAddressRole,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<(Slot, OperationId)>, ExecutionError> {
+        let prefix = address_prefix(address, role);
+        // iterate backwards from the first key following the entries of the address and role
+        let upper_bound = next_prefix(&prefix);
+        let mode = match &upper_bound {
+            Some(upper_bound) => IteratorMode::From(upper_bound, Direction::Reverse),
+            None => IteratorMode::End,
+        };
+        self.db
+            .iterator(mode)
+            .map(|entry| entry.expect(CRUD_ERROR).0)
+            .take_while(|key| key.starts_with(&prefix))
+            .skip(offset)
+            .take(limit)
+            .map(|key| self.parse_key(&key[prefix.len()..]))
+            .collect()
+    }
+
+    /// Parse the slot and operation id following the address prefix of a key
+    fn parse_key(&self, key: &[u8]) -> Result<(Slot, OperationId), ExecutionError> {
+        if key.len() < SLOT_KEY_SIZE {
+            return Err(ExecutionError::OperationIndexError(
+                "invalid key: too short".to_string(),
+            ));
+        }
+        let slot = Slot::from_bytes_key(
+            key[..SLOT_KEY_SIZE]
+                .try_into()
+                .expect("slot key size already checked"),
+        );
+        let (_, op_id) = self
+            .op_id_deser
+            .deserialize::<DeserializeError>(&key[SLOT_KEY_SIZE..])
+            .map_err(|err| {
+                ExecutionError::OperationIndexError(format!("invalid operation id: {}", err))
+            })?;
+        Ok((slot, op_id))
+    }
+}
+
+/// Prefix of the keys of the operations in which an address has the given role
+fn address_prefix(address: &Address, role: OperationAddressRole) -> Vec<u8> {
+    let address_bytes = address.to_prefixed_bytes();
+    let role_ident = match role {
+        OperationAddressRole::Emitter => EMITTER_IDENT,
+        OperationAddressRole::Recipient => RECIPIENT_IDENT,
+    };
+    [&[role_ident, address_bytes.len() as u8][..], &address_bytes].concat()
+}
+
+/// Smallest key greater than all the keys starting with `prefix`, if any
+pub(crate) fn next_prefix(prefix: &[u8]) -> Option<Vec<u8>> {
+    let mut next = prefix.to_vec();
+    while let Some(last) = next.pop() {
+        if last < u8::MAX {
+            next.push(last + 1);
+            return Some(next);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_models::amount::Amount;
+    use massa_models::config::CHAINID;
+    use massa_models::operation::{Operation, OperationSerializer, OperationType};
+    use massa_models::secure_share::SecureShareContent;
+    use massa_signature::KeyPair;
+    use tempfile::TempDir;
+
+    fn create_transaction(
+        keypair: &KeyPair,
+        recipient_address: Address,
+        expire_period: u64,
+    ) -> SecureShareOperation {
+        Operation::new_verifiable(
+            Operation {
+                fee: Amount::zero(),
+                expire_period,
+                op: OperationType::Transaction {
+                    recipient_address,
+                    amount: Amount::zero(),
+                },
+            },
+            OperationSerializer::new(),
+            keypair,
+            *CHAINID,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_operations_by_address() {
+        let index = OperationIndex::new(TempDir::new().unwrap().path().to_path_buf(), 0);
+        let emitter = KeyPair::generate(0).unwrap();
+        let emitter_address = Address::from_public_key(&emitter.get_public_key());
+        let recipient_address =
+            Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+
+        let op_1 = create_transaction(&emitter, recipient_address, 10);
+        let op_2 = create_transaction(&emitter, emitter_address, 11);
+        let op_3 = create_transaction(&emitter, recipient_address, 12);
+        index.index_operations(&Slot::new(1, 0), &[op_1.clone()]);
+        index.index_operations(&Slot::new(1, 1), &[op_2.clone()]);
+        index.index_operations(&Slot::new(2, 0), &[op_3.clone()]);
+
+        // from the most recent operation
+        let emitted = index
+            .get_operations(&emitter_address, OperationAddressRole::Emitter, 0, 10)
+            .unwrap();
+        assert_eq!(
+            emitted,
+            vec![
+                (Slot::new(2, 0), op_3.id),
+                (Slot::new(1, 1), op_2.id),
+                (Slot::new(1, 0), op_1.id),
+            ]
+        );
+        let received = index
+            .get_operations(&recipient_address, OperationAddressRole::Recipient, 0, 10)
+            .unwrap();
+        assert_eq!(
+            received,
+            vec![(Slot::new(2, 0), op_3.id), (Slot::new(1, 0), op_1.id)]
+        );
+        let received = index
+            .get_operations(&emitter_address, OperationAddressRole::Recipient, 0, 10)
+            .unwrap();
+        assert_eq!(received, vec![(Slot::new(1, 1), op_2.id)]);
+        assert!(index
+            .get_operations(&recipient_address, OperationAddressRole::Emitter, 0, 10)
+            .unwrap()
+            .is_empty());
+
+        // pagination
+        let emitted = index
+            .get_operations(&emitter_address, OperationAddressRole::Emitter, 1, 1)
+            .unwrap();
+        assert_eq!(emitted, vec![(Slot::new(1, 1), op_2.id)]);
+    }
+
+    #[test]
+    fn test_operations_retention() {
+        let tmp_dir = TempDir::new().unwrap();
+        let index = OperationIndex::new(tmp_dir.path().to_path_buf(), 2);
+        let emitter = KeyPair::generate(0).unwrap();
+        let emitter_address = Address::from_public_key(&emitter.get_public_key());
+        let recipient_address =
+            Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+
+        let mut operations = Vec::new();
+        for period in 1..=4 {
+            let op = create_transaction(&emitter, recipient_address, period + 10);
+            index.index_operations(&Slot::new(period, 0), &[op.clone()]);
+            operations.push(op);
+        }
+
+        // the operations of the periods before the retention period are removed with their entries
+        let emitted = index
+            .get_operations(&emitter_address, OperationAddressRole::Emitter, 0, 10)
+            .unwrap();
+        assert_eq!(
+            emitted,
+            vec![
+                (Slot::new(4, 0), operations[3].id),
+                (Slot::new(3, 0), operations[2].id),
+                (Slot::new(2, 0), operations[1].id),
+            ]
+        );
+        let received = index
+            .get_operations(&recipient_address, OperationAddressRole::Recipient, 0, 10)
+            .unwrap();
+        assert_eq!(received.len(), 3);
+        assert_eq!(index.db.iterator(IteratorMode::Start).count(), 12);
+    }
+}
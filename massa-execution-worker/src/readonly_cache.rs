@@ -0,0 +1,225 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Cache of the outputs of the read-only calls.
+//!
+//! The output of a read-only call only depends on its inputs and on the state it is executed on,
+//! which is identified by the execution trail hash of the last executed slot.
+//! Identical calls on an unchanged state (ex: a price oracle polled every second)
+//! are thus answered from the cache instead of being executed again.
+//! The cached outputs are dropped as soon as an output is cached for a newer state.
+
+use massa_execution_exports::{
+    ReadOnlyExecutionOutput, ReadOnlyExecutionRequest, ReadOnlyExecutionTarget,
+};
+use massa_hash::Hash;
+use massa_models::address::Address;
+use schnellru::{ByLength, LruMap};
+
+/// Target of a cached read-only call
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum CachedTarget {
+    /// execution of a bytecode, identified by its hash
+    Bytecode(Hash),
+    /// call of a smart contract function
+    FunctionCall {
+        address: Address,
+        function: String,
+        parameter: Vec<u8>,
+    },
+}
+
+/// Inputs of a read-only call
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct ReadOnlyCacheKey {
+    target: CachedTarget,
+    /// address, raw amount of coins and owned addresses of each element of the call stack
+    call_stack: Vec<(Address, u64, Vec<Address>)>,
+    max_gas: u64,
+    coins: Option<u64>,
+    fee: Option<u64>,
+}
+
+impl ReadOnlyCacheKey {
+    /// Returns the key of a read-only request, if its output can be cached.
+    /// The requests overriding the state or carrying an operation datastore are always executed.
+    pub fn new(req: &ReadOnlyExecutionRequest) -> Option<Self> {
+        if !req.state_overrides.is_empty()
+            || req
+                .call_stack
+                .iter()
+                .any(|element| element.operation_datastore.is_some())
+        {
+            return None;
+        }
+        let target = match &req.target {
+            ReadOnlyExecutionTarget::BytecodeExecution(bytecode) => {
+                CachedTarget::Bytecode(Hash::compute_from(bytecode))
+            }
+            ReadOnlyExecutionTarget::FunctionCall {
+                target_addr,
+                target_func,
+                parameter,
+            } => CachedTarget::FunctionCall {
+                address: *target_addr,
+                function: target_func.clone(),
+                parameter: parameter.clone(),
+            },
+        };
+        Some(ReadOnlyCacheKey {
+            target,
+            call_stack: req
+                .call_stack
+                .iter()
+                .map(|element| {
+                    (
+                        element.address,
+                        element.coins.to_raw(),
+                        element.owned_addresses.clone(),
+                    )
+                })
+                .collect(),
+            max_gas: req.max_gas,
+            coins: req.coins.map(|coins| coins.to_raw()),
+            fee: req.fee.map(|fee| fee.to_raw()),
+        })
+    }
+}
+
+/// Outputs of the read-only calls executed on the latest known state
+pub(crate) struct ReadOnlyCache {
+    /// execution trail hash of the state the cached outputs were computed on
+    state_version: Option<Hash>,
+    /// cached outputs, the least recently used one being dropped when the cache is full
+    outputs: LruMap<ReadOnlyCacheKey, ReadOnlyExecutionOutput>,
+}
+
+impl ReadOnlyCache {
+    pub fn new(max_size: usize) -> Self {
+        Self {
+            state_version: None,
+            outputs: LruMap::new(ByLength::new(max_size as u32)),
+        }
+    }
+
+    /// Get the output of a call on the given state, if cached
+    pub fn get(
+        &mut self,
+        state_version: &Hash,
+        key: &ReadOnlyCacheKey,
+    ) -> Option<ReadOnlyExecutionOutput> {
+        if self.state_version.as_ref() != Some(state_version) {
+            return None;
+        }
+        self.outputs.get(key).cloned()
+    }
+
+    /// Cache the output of a call on the given state,
+    /// dropping the outputs computed on another state
+    pub fn insert(
+        &mut self,
+        state_version: Hash,
+        key: ReadOnlyCacheKey,
+        output: ReadOnlyExecutionOutput,
+    ) {
+        if self.state_version != Some(state_version) {
+            self.outputs.clear();
+            self.state_version = Some(state_version);
+        }
+        self.outputs.insert(key, output);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_execution_exports::{
+        EventStore, ExecutionOutput, ExecutionStackElement, ReadOnlyStateOverride,
+    };
+    use massa_final_state::StateChanges;
+    use massa_models::amount::Amount;
+    use massa_models::slot::Slot;
+    use massa_signature::KeyPair;
+    use std::str::FromStr;
+
+    fn function_call(address: Address, parameter: Vec<u8>) -> ReadOnlyExecutionRequest {
+        ReadOnlyExecutionRequest {
+            max_gas: 1_000_000,
+            call_stack: vec![ExecutionStackElement {
+                address,
+                coins: Amount::zero(),
+                owned_addresses: vec![],
+                operation_datastore: None,
+            }],
+            target: ReadOnlyExecutionTarget::FunctionCall {
+                target_addr: address,
+                target_func: "getPrice".to_string(),
+                parameter,
+            },
+            coins: None,
+            fee: None,
+            priority: Default::default(),
+            caller_id: None,
+            state_overrides: vec![],
+        }
+    }
+
+    fn readonly_output(call_result: Vec<u8>) -> ReadOnlyExecutionOutput {
+        ReadOnlyExecutionOutput {
+            out: ExecutionOutput {
+                slot: Slot::new(1, 0),
+                block_info: None,
+                state_changes: StateChanges::default(),
+                events: EventStore::default(),
+                event_overflow: None,
+                stats: Default::default(),
+                final_ledger_reads: Default::default(),
+                #[cfg(feature = "execution-trace")]
+                slot_trace: None,
+            },
+            gas_cost: 0,
+            call_result,
+            #[cfg(feature = "execution-trace")]
+            abi_calls: vec![],
+        }
+    }
+
+    #[test]
+    fn test_readonly_cache() {
+        let address = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        let state_1 = Hash::compute_from(b"state_1");
+        let state_2 = Hash::compute_from(b"state_2");
+        let mut cache = ReadOnlyCache::new(10);
+
+        let key = ReadOnlyCacheKey::new(&function_call(address, vec![1])).unwrap();
+        cache.insert(state_1, key.clone(), readonly_output(vec![42]));
+        let output = cache.get(&state_1, &key).unwrap();
+        assert_eq!(output.call_result, vec![42]);
+
+        // another parameter is another call
+        let other_key = ReadOnlyCacheKey::new(&function_call(address, vec![2])).unwrap();
+        assert!(cache.get(&state_1, &other_key).is_none());
+        // as well as other coins
+        let mut req = function_call(address, vec![1]);
+        req.coins = Some(Amount::from_str("1").unwrap());
+        assert!(cache
+            .get(&state_1, &ReadOnlyCacheKey::new(&req).unwrap())
+            .is_none());
+
+        // the outputs are dropped once the state changed
+        assert!(cache.get(&state_2, &key).is_none());
+        cache.insert(state_2, other_key.clone(), readonly_output(vec![43]));
+        assert!(cache.get(&state_1, &key).is_none());
+        assert!(cache.get(&state_2, &key).is_none());
+        assert!(cache.get(&state_2, &other_key).is_some());
+
+        // the calls overriding the state are never cached
+        let mut req = function_call(address, vec![1]);
+        req.state_overrides.push(ReadOnlyStateOverride {
+            address,
+            balance: Some(Amount::zero()),
+            bytecode: None,
+            datastore: Default::default(),
+        });
+        assert!(ReadOnlyCacheKey::new(&req).is_none());
+    }
+}
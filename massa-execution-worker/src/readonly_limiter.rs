@@ -0,0 +1,157 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Quotas of the read-only execution requests.
+//!
+//! The read-only requests are executed one at a time by the execution thread, once no slot
+//! is waiting to be executed.
+//! To prevent a single client from filling the queue and delaying the requests of the others,
+//! the number of requests queued or being executed at once is limited, overall and per caller.
+//! The requests without caller, made by the node itself or its operator, are not limited.
+
+use massa_execution_exports::ExecutionError;
+use massa_metrics::MassaMetrics;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Read-only requests queued or being executed
+#[derive(Default)]
+struct InFlightRequests {
+    /// total number of requests
+    total: usize,
+    /// number of requests by caller
+    by_caller: HashMap<String, usize>,
+}
+
+/// Admission of the read-only requests within the quotas
+pub(crate) struct ReadOnlyLimiter {
+    /// max number of requests at once (0 for no limit)
+    max_requests: usize,
+    /// max number of requests of a same caller at once (0 for no limit)
+    max_requests_per_caller: usize,
+    /// requests currently admitted
+    in_flight: Mutex<InFlightRequests>,
+    /// prometheus metrics
+    massa_metrics: MassaMetrics,
+}
+
+/// Admission of a read-only request, released when dropped
+pub(crate) struct ReadOnlyPermit {
+    limiter: Arc<ReadOnlyLimiter>,
+    caller_id: String,
+}
+
+impl Drop for ReadOnlyPermit {
+    fn drop(&mut self) {
+        self.limiter.release(&self.caller_id);
+    }
+}
+
+impl ReadOnlyLimiter {
+    pub fn new(
+        max_requests: usize,
+        max_requests_per_caller: usize,
+        massa_metrics: MassaMetrics,
+    ) -> Self {
+        Self {
+            max_requests,
+            max_requests_per_caller,
+            in_flight: Default::default(),
+            massa_metrics,
+        }
+    }
+
+    /// Admits a request of `caller_id`, to be kept until its response is received.
+    /// Returns None for the requests without caller, which are not limited.
+    pub fn acquire(
+        self: &Arc<Self>,
+        caller_id: Option<&str>,
+    ) -> Result<Option<ReadOnlyPermit>, ExecutionError> {
+        let Some(caller_id) = caller_id else {
+            return Ok(None);
+        };
+        let mut in_flight = self.in_flight.lock();
+        let caller_count = in_flight.by_caller.get(caller_id).copied().unwrap_or(0);
+        let error = if self.max_requests > 0 && in_flight.total >= self.max_requests {
+            Some("too many concurrent read-only requests".to_string())
+        } else if self.max_requests_per_caller > 0 && caller_count >= self.max_requests_per_caller {
+            Some(format!(
+                "too many concurrent read-only requests from {}",
+                caller_id
+            ))
+        } else {
+            None
+        };
+        if let Some(error) = error {
+            self.massa_metrics.inc_readonly_requests_rejected();
+            return Err(ExecutionError::ReadOnlyQuotaError(error));
+        }
+
+        in_flight.total += 1;
+        in_flight
+            .by_caller
+            .insert(caller_id.to_string(), caller_count + 1);
+        self.massa_metrics
+            .set_readonly_requests_in_flight(in_flight.total);
+        Ok(Some(ReadOnlyPermit {
+            limiter: self.clone(),
+            caller_id: caller_id.to_string(),
+        }))
+    }
+
+    fn release(&self, caller_id: &str) {
+        let mut in_flight = self.in_flight.lock();
+        in_flight.total = in_flight.total.saturating_sub(1);
+        if let Some(count) = in_flight.by_caller.get_mut(caller_id) {
+            *count -= 1;
+            if *count == 0 {
+                in_flight.by_caller.remove(caller_id);
+            }
+        }
+        self.massa_metrics
+            .set_readonly_requests_in_flight(in_flight.total);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(max_requests: usize, max_requests_per_caller: usize) -> Arc<ReadOnlyLimiter> {
+        let massa_metrics = MassaMetrics::new(
+            false,
+            "0.0.0.0:9898".parse().unwrap(),
+            32,
+            std::time::Duration::from_secs(5),
+        )
+        .0;
+        Arc::new(ReadOnlyLimiter::new(
+            max_requests,
+            max_requests_per_caller,
+            massa_metrics,
+        ))
+    }
+
+    #[test]
+    fn test_readonly_quotas() {
+        let limiter = limiter(3, 2);
+
+        // a caller cannot take more than its quota
+        let permit_1 = limiter.acquire(Some("client_1")).unwrap();
+        let permit_2 = limiter.acquire(Some("client_1")).unwrap();
+        assert!(limiter.acquire(Some("client_1")).is_err());
+
+        // the other callers are admitted up to the global limit
+        let _permit_3 = limiter.acquire(Some("client_2")).unwrap();
+        assert!(limiter.acquire(Some("client_3")).is_err());
+
+        // the requests without caller are not limited
+        assert!(limiter.acquire(None).unwrap().is_none());
+
+        // the quota is released with the permits
+        drop(permit_1);
+        assert!(limiter.acquire(Some("client_3")).unwrap().is_some());
+        drop(permit_2);
+        assert_eq!(limiter.in_flight.lock().by_caller.get("client_1"), None);
+    }
+}
@@ -4,6 +4,7 @@
 
 use massa_channel::sender::MassaSender;
 use massa_execution_exports::ExecutionError;
+use std::cmp::Reverse;
 use std::collections::VecDeque;
 
 /// Represents an execution request T coupled with an MPSC sender for a result of type R
@@ -119,6 +120,26 @@ impl<T, R> RequestQueue<T, R> {
         self.queue.pop_front()
     }
 
+    /// Pop out the oldest of the elements of highest priority
+    ///
+    /// # Arguments
+    /// * `priority`: function returning the priority of a request
+    ///
+    /// # Returns
+    /// The oldest element of highest priority, or None if the queue is empty
+    pub fn pop_by_priority<P: Ord, F: Fn(&T) -> P>(
+        &mut self,
+        priority: F,
+    ) -> Option<RequestWithResponseSender<T, R>> {
+        let index = self
+            .queue
+            .iter()
+            .enumerate()
+            .max_by_key(|(index, req)| (priority(&req.request), Reverse(*index)))
+            .map(|(index, _)| index)?;
+        self.queue.remove(index)
+    }
+
     /// Push a new element at the end of the queue.
     /// May fail if maximum capacity is reached,
     /// in which case the request is canceled and dropped.
@@ -161,4 +182,9 @@ impl<T, R> RequestQueue<T, R> {
     pub fn is_empty(&self) -> bool {
         self.queue.is_empty()
     }
+
+    /// Returns the number of items in the queue
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
 }
@@ -11,8 +11,10 @@ use massa_async_pool::{
 use massa_final_state::FinalStateController;
 use massa_ledger_exports::{Applicable, LedgerChanges, SetUpdateOrDelete};
 use massa_models::slot::Slot;
+use num::rational::Ratio;
 use parking_lot::RwLock;
 use std::{
+    cmp::Reverse,
     collections::{BTreeMap, HashMap},
     sync::Arc,
 };
@@ -90,12 +92,76 @@ impl SpeculativeAsyncPool {
         self.message_infos = snapshot.1;
     }
 
+    /// Returns the number of messages of the pool, and the priority of the last one,
+    /// which is the first to be eliminated when the pool overflows
+    pub fn get_occupancy(&self) -> (usize, Option<Ratio<u64>>) {
+        let lowest_priority = self
+            .message_infos
+            .last_key_value()
+            .map(|((Reverse(priority), _, _), _)| *priority);
+        (self.message_infos.len(), lowest_priority)
+    }
+
     /// Add a new message to the list of changes of this `SpeculativeAsyncPool`
     pub fn push_new_message(&mut self, msg: AsyncMessage) {
         self.pool_changes.push_add(msg.compute_id(), msg.clone());
         self.message_infos.insert(msg.compute_id(), msg.into());
     }
 
+    /// Removes the message emitted at `emission_slot` with `emission_index` from the pool,
+    /// if it was neither executed nor eliminated yet
+    ///
+    /// # Returns
+    /// The removed message, used for reimbursement
+    pub fn remove_message(
+        &mut self,
+        emission_slot: Slot,
+        emission_index: u64,
+    ) -> Option<AsyncMessage> {
+        let message_id = self.get_message_id(emission_slot, emission_index)?;
+        let (_, message) = self.fetch_msgs(vec![&message_id], true).pop()?;
+        self.message_infos.remove(&message_id);
+        Some(message)
+    }
+
+    /// Gets the id of the message of the pool emitted at `emission_slot` with `emission_index`.
+    /// It is looked up in the changes of its emission slot if that slot is not final yet,
+    /// and in the final pool otherwise.
+    fn get_message_id(&self, emission_slot: Slot, emission_index: u64) -> Option<AsyncMessageId> {
+        let is_emitted =
+            |(_, slot, index): &&AsyncMessageId| *slot == emission_slot && *index == emission_index;
+        let message_id = self
+            .pool_changes
+            .0
+            .keys()
+            .find(is_emitted)
+            .copied()
+            .or_else(|| {
+                self.active_history
+                    .read()
+                    .0
+                    .iter()
+                    .find(|history_item| history_item.slot == emission_slot)
+                    .and_then(|history_item| {
+                        history_item
+                            .state_changes
+                            .async_pool_changes
+                            .0
+                            .keys()
+                            .find(is_emitted)
+                            .copied()
+                    })
+            })
+            .or_else(|| {
+                self.final_state
+                    .read()
+                    .get_async_pool()
+                    .get_message_id(emission_slot, emission_index)
+            });
+        // the message may have been executed or eliminated since
+        message_id.filter(|message_id| self.message_infos.contains_key(message_id))
+    }
+
     /// Takes a batch of asynchronous messages to execute,
     /// removing them from the speculative asynchronous pool and settling their deletion from it in the changes accumulator.
     ///
@@ -7,13 +7,15 @@
 
 use crate::active_history::{ActiveHistory, HistorySearchResult};
 use massa_execution_exports::ExecutionError;
+use massa_execution_exports::ReadOnlyStateOverride;
 use massa_execution_exports::StorageCostsConstants;
 use massa_final_state::FinalStateController;
 use massa_ledger_exports::{Applicable, LedgerChanges, SetOrDelete, SetUpdateOrDelete};
 use massa_models::bytecode::Bytecode;
 use massa_models::datastore::get_prefix_bounds;
+use massa_models::prehash::PreHashSet;
 use massa_models::{address::Address, amount::Amount};
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use std::cmp::Ordering;
 use std::collections::BTreeSet;
 use std::sync::Arc;
@@ -49,6 +51,10 @@ pub(crate) struct SpeculativeLedger {
     ))]
     pub added_changes: LedgerChanges,
 
+    /// addresses whose ledger entry was read from the final state since the creation of the
+    /// `SpeculativeLedger`, kept even if the changes are reset to a snapshot
+    final_reads: Mutex<PreHashSet<Address>>,
+
     /// max datastore key length
     max_datastore_key_length: u8,
 
@@ -79,6 +85,7 @@ impl SpeculativeLedger {
         SpeculativeLedger {
             final_state,
             added_changes: Default::default(),
+            final_reads: Default::default(),
             active_history,
             max_datastore_key_length,
             max_datastore_value_size,
@@ -103,6 +110,12 @@ impl SpeculativeLedger {
         self.added_changes = snapshot;
     }
 
+    /// Returns the addresses whose ledger entry was read from the final state
+    /// since the creation of the `SpeculativeLedger`, and resets them.
+    pub fn take_final_reads(&mut self) -> PreHashSet<Address> {
+        std::mem::take(self.final_reads.get_mut())
+    }
+
     /// Gets the effective balance of an address
     ///
     /// # Arguments:
@@ -116,6 +129,7 @@ impl SpeculativeLedger {
             match self.active_history.read().fetch_balance(addr) {
                 HistorySearchResult::Present(par_balance) => Some(par_balance),
                 HistorySearchResult::NoInfo => {
+                    self.final_reads.lock().insert(*addr);
                     self.final_state.read().get_ledger().get_balance(addr)
                 }
                 HistorySearchResult::Absent => None,
@@ -136,6 +150,7 @@ impl SpeculativeLedger {
             match self.active_history.read().fetch_bytecode(addr) {
                 HistorySearchResult::Present(bytecode) => Some(bytecode),
                 HistorySearchResult::NoInfo => {
+                    self.final_reads.lock().insert(*addr);
                     self.final_state.read().get_ledger().get_bytecode(addr)
                 }
                 HistorySearchResult::Absent => None,
@@ -225,6 +240,7 @@ impl SpeculativeLedger {
             match self.active_history.read().fetch_balance(addr) {
                 HistorySearchResult::Present(_balance) => true,
                 HistorySearchResult::NoInfo => {
+                    self.final_reads.lock().insert(*addr);
                     self.final_state.read().get_ledger().entry_exists(addr)
                 }
                 HistorySearchResult::Absent => false,
@@ -373,11 +389,12 @@ impl SpeculativeLedger {
         let range_ref = (prefix_range.0.as_ref(), prefix_range.1.as_ref());
 
         // init keys with final state
+        self.final_reads.lock().insert(*addr);
         let mut candidate_keys: Option<BTreeSet<Vec<u8>>> = self
             .final_state
             .read()
             .get_ledger()
-            .get_datastore_keys(addr, prefix);
+            .get_datastore_keys(addr, prefix, None, None);
 
         // here, traverse the history from oldest to newest with added_changes at the end, applying additions and deletions
         let active_history = self.active_history.read();
@@ -665,4 +682,63 @@ impl SpeculativeLedger {
 
         Ok(())
     }
+
+    /// Overrides the state of an address, without charging any storage cost.
+    /// Only meant to simulate a modified ledger in read-only executions.
+    /// The address is created if it does not exist.
+    ///
+    /// # Arguments
+    /// * `state_override`: the overridden values of the address
+    pub fn apply_state_override(
+        &mut self,
+        state_override: ReadOnlyStateOverride,
+    ) -> Result<(), ExecutionError> {
+        let addr = state_override.address;
+
+        // check the overridden values
+        if let Some(bytecode) = &state_override.bytecode {
+            if bytecode.0.len() > self.max_bytecode_size as usize {
+                return Err(ExecutionError::RuntimeError(format!(
+                    "could not override bytecode for address {}: bytecode size exceeds maximum allowed size",
+                    addr
+                )));
+            }
+        }
+        for (key, value) in state_override.datastore.iter() {
+            if key.is_empty() || key.len() > self.max_datastore_key_length as usize {
+                return Err(ExecutionError::RuntimeError(format!(
+                    "key length is {}, but it must be in [0..={}]",
+                    key.len(),
+                    self.max_datastore_key_length
+                )));
+            }
+            if let Some(value) = value {
+                if value.len() > self.max_datastore_value_size as usize {
+                    return Err(ExecutionError::RuntimeError(format!(
+                        "value length is {}, but it must be in [0..={}]",
+                        value.len(),
+                        self.max_datastore_value_size
+                    )));
+                }
+            }
+        }
+
+        if !self.entry_exists(&addr) {
+            self.added_changes.create_address(&addr);
+        }
+        if let Some(balance) = state_override.balance {
+            self.added_changes.set_balance(addr, balance);
+        }
+        if let Some(bytecode) = state_override.bytecode {
+            self.added_changes.set_bytecode(addr, bytecode);
+        }
+        for (key, value) in state_override.datastore {
+            match value {
+                Some(value) => self.added_changes.set_data_entry(addr, key, value),
+                None => self.added_changes.delete_data_entry(addr, key),
+            }
+        }
+
+        Ok(())
+    }
 }
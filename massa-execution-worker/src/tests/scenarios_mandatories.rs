@@ -4,8 +4,9 @@ use massa_async_pool::{AsyncMessage, AsyncPool, AsyncPoolChanges, AsyncPoolConfi
 use massa_db_exports::{DBBatch, ShareableMassaDBController};
 use massa_executed_ops::{ExecutedDenunciations, ExecutedDenunciationsConfig};
 use massa_execution_exports::{
-    ExecutionConfig, ExecutionQueryRequest, ExecutionQueryRequestItem, ExecutionStackElement,
-    ReadOnlyExecutionRequest, ReadOnlyExecutionTarget,
+    EventOverflowPolicy, ExecutionConfig, ExecutionOutputExport, ExecutionQueryRequest,
+    ExecutionQueryRequestItem, ExecutionStackElement, ExportFormat, ReadOnlyExecutionRequest,
+    ReadOnlyExecutionTarget, ReadOnlyStateOverride,
 };
 use massa_final_state::test_exports::get_initials;
 use massa_final_state::MockFinalStateController;
@@ -15,7 +16,8 @@ use massa_ledger_exports::{
 };
 use massa_models::bytecode::Bytecode;
 use massa_models::config::{
-    CHAINID, ENDORSEMENT_COUNT, LEDGER_ENTRY_DATASTORE_BASE_SIZE, THREAD_COUNT,
+    CANCEL_ASYNC_MESSAGE_EXECUTION_VERSION, CHAINID, ENDORSEMENT_COUNT,
+    LEDGER_ENTRY_DATASTORE_BASE_SIZE, THREAD_COUNT,
 };
 use massa_models::prehash::PreHashMap;
 use massa_models::test_exports::gen_endorsements_for_denunciation;
@@ -31,6 +33,7 @@ use massa_pos_exports::{
 };
 use massa_signature::KeyPair;
 use massa_test_framework::{TestUniverse, WaitPoint};
+use massa_versioning::versioning::MipStore;
 use mockall::predicate;
 use num::rational::Ratio;
 use parking_lot::RwLock;
@@ -40,7 +43,9 @@ use std::{cmp::Reverse, collections::BTreeMap, str::FromStr, time::Duration};
 use super::universe::{ExecutionForeignControllers, ExecutionTestUniverse};
 
 #[cfg(feature = "execution-trace")]
-use massa_execution_exports::{AbiTrace, SCRuntimeAbiTraceType, SCRuntimeAbiTraceValue};
+use massa_execution_exports::{
+    AbiTrace, SCRuntimeAbiTraceType, SCRuntimeAbiTraceValue, StorageAccessType,
+};
 #[cfg(feature = "execution-trace")]
 use massa_models::operation::OperationId;
 #[cfg(feature = "execution-trace")]
@@ -279,6 +284,9 @@ fn test_readonly_execution() {
             ),
             coins: None,
             fee: Some(Amount::from_str("40").unwrap()),
+            priority: Default::default(),
+            caller_id: None,
+            state_overrides: vec![],
         })
         .expect("readonly execution failed");
 
@@ -324,6 +332,9 @@ fn test_readonly_execution() {
             },
             coins: Some(Amount::from_str("20").unwrap()),
             fee: Some(Amount::from_str("30").unwrap()),
+            priority: Default::default(),
+            caller_id: None,
+            state_overrides: vec![],
         })
         .expect("readonly execution failed");
 
@@ -340,6 +351,177 @@ fn test_readonly_execution() {
     );
 }
 
+#[test]
+fn test_readonly_execution_with_state_overrides() {
+    let exec_cfg = ExecutionConfig::default();
+    let mut foreign_controllers = ExecutionForeignControllers::new_with_mocks();
+    selector_boilerplate(&mut foreign_controllers.selector_controller);
+    foreign_controllers
+        .ledger_controller
+        .set_expectations(|ledger_controller| {
+            ledger_controller
+                .expect_get_balance()
+                .returning(move |_| Some(Amount::from_str("100").unwrap()));
+            ledger_controller
+                .expect_entry_exists()
+                .returning(move |_| true);
+            ledger_controller
+                .expect_get_data_entry()
+                .returning(move |_, _| None);
+        });
+    final_state_boilerplate(
+        &mut foreign_controllers.final_state,
+        foreign_controllers.db.clone(),
+        &foreign_controllers.selector_controller,
+        &mut foreign_controllers.ledger_controller,
+        None,
+        None,
+        None,
+    );
+    let universe = ExecutionTestUniverse::new(foreign_controllers, exec_cfg);
+
+    let addr = Address::from_str("AU1LQrXPJ3DVL8SFRqACk31E9MVxBcmCATFiRdpEmgztGxWAx48D").unwrap();
+
+    // the fee is paid from the overridden balance
+    let res = universe
+        .module_controller
+        .execute_readonly_request(ReadOnlyExecutionRequest {
+            max_gas: 100_000_000,
+            call_stack: vec![ExecutionStackElement {
+                address: addr,
+                coins: Amount::zero(),
+                owned_addresses: vec![],
+                operation_datastore: None,
+            }],
+            target: ReadOnlyExecutionTarget::BytecodeExecution(
+                include_bytes!("./wasm/event_test.wasm").to_vec(),
+            ),
+            coins: None,
+            fee: Some(Amount::from_str("40").unwrap()),
+            priority: Default::default(),
+            caller_id: None,
+            state_overrides: vec![ReadOnlyStateOverride {
+                address: addr,
+                balance: Some(Amount::from_str("1000").unwrap()),
+                bytecode: None,
+                datastore: BTreeMap::from([(b"key".to_vec(), Some(b"value".to_vec()))]),
+            }],
+        })
+        .expect("readonly execution failed");
+
+    assert_eq!(
+        res.out.state_changes.ledger_changes.0.get(&addr).unwrap(),
+        &SetUpdateOrDelete::Update(LedgerEntryUpdate {
+            balance: massa_ledger_exports::SetOrKeep::Set(Amount::from_str("960").unwrap()),
+            bytecode: massa_ledger_exports::SetOrKeep::Keep,
+            datastore: BTreeMap::from([(
+                b"key".to_vec(),
+                massa_ledger_exports::SetOrDelete::Set(b"value".to_vec()),
+            )])
+        })
+    );
+
+    // the overrides are checked
+    let res = universe
+        .module_controller
+        .execute_readonly_request(ReadOnlyExecutionRequest {
+            max_gas: 100_000_000,
+            call_stack: vec![ExecutionStackElement {
+                address: addr,
+                coins: Amount::zero(),
+                owned_addresses: vec![],
+                operation_datastore: None,
+            }],
+            target: ReadOnlyExecutionTarget::BytecodeExecution(
+                include_bytes!("./wasm/event_test.wasm").to_vec(),
+            ),
+            coins: None,
+            fee: None,
+            priority: Default::default(),
+            caller_id: None,
+            state_overrides: vec![ReadOnlyStateOverride {
+                address: addr,
+                balance: None,
+                bytecode: None,
+                datastore: BTreeMap::from([(vec![], Some(b"value".to_vec()))]),
+            }],
+        });
+    assert!(res.is_err());
+}
+
+/// Test the handling of the events emitted beyond the max number of events per slot
+#[test]
+fn test_readonly_execution_event_overflow() {
+    for policy in [EventOverflowPolicy::Truncate, EventOverflowPolicy::Spill] {
+        let exec_cfg = ExecutionConfig {
+            max_events_per_slot: 0,
+            event_overflow_policy: policy,
+            ..Default::default()
+        };
+        let mut foreign_controllers = ExecutionForeignControllers::new_with_mocks();
+        selector_boilerplate(&mut foreign_controllers.selector_controller);
+        foreign_controllers
+            .ledger_controller
+            .set_expectations(|ledger_controller| {
+                ledger_controller
+                    .expect_get_balance()
+                    .returning(move |_| Some(Amount::from_str("100").unwrap()));
+                ledger_controller
+                    .expect_entry_exists()
+                    .returning(move |_| true);
+            });
+        final_state_boilerplate(
+            &mut foreign_controllers.final_state,
+            foreign_controllers.db.clone(),
+            &foreign_controllers.selector_controller,
+            &mut foreign_controllers.ledger_controller,
+            None,
+            None,
+            None,
+        );
+        let universe = ExecutionTestUniverse::new(foreign_controllers, exec_cfg);
+
+        let addr =
+            Address::from_str("AU1LQrXPJ3DVL8SFRqACk31E9MVxBcmCATFiRdpEmgztGxWAx48D").unwrap();
+        let res = universe
+            .module_controller
+            .execute_readonly_request(ReadOnlyExecutionRequest {
+                max_gas: 100_000_000,
+                call_stack: vec![ExecutionStackElement {
+                    address: addr,
+                    coins: Amount::zero(),
+                    owned_addresses: vec![],
+                    operation_datastore: None,
+                }],
+                target: ReadOnlyExecutionTarget::BytecodeExecution(
+                    include_bytes!("./wasm/event_test.wasm").to_vec(),
+                ),
+                coins: None,
+                fee: None,
+                priority: Default::default(),
+                caller_id: None,
+                state_overrides: vec![],
+            })
+            .expect("readonly execution failed");
+
+        // the only event of the execution overflows
+        let event_overflow = res.out.event_overflow.expect("no event overflow");
+        assert_eq!(event_overflow.policy, policy);
+        assert_eq!(event_overflow.overflowing_event_count, 1);
+        match policy {
+            EventOverflowPolicy::Truncate => {
+                assert_eq!(res.out.events.0.len(), 1);
+                assert!(res.out.events.0[0].data.contains("massa_events_truncated"));
+                assert!(event_overflow.spilled_events.0.is_empty());
+            }
+            EventOverflowPolicy::Spill => {
+                assert!(res.out.events.0.is_empty());
+                assert_eq!(event_overflow.spilled_events.0.len(), 1);
+            }
+        }
+    }
+}
+
 /// Test the gas usage in nested calls using call SC operation
 ///
 /// Create a smart contract and send it in the blockclique.
@@ -907,6 +1089,149 @@ fn cancel_async_message() {
     assert!(events[0].data.contains(" is not a smart contract address"));
 }
 
+#[test]
+fn cancel_async_message_operation() {
+    run_cancel_async_message_operation(
+        ExecutionTestUniverse::mip_store_with_execution_version(
+            CANCEL_ASYNC_MESSAGE_EXECUTION_VERSION,
+        ),
+        true,
+    );
+}
+
+#[test]
+fn cancel_async_message_operation_before_activation() {
+    // the execution component of the MIP store is still at its initial version
+    run_cancel_async_message_operation(
+        ExecutionTestUniverse::mip_store_with_execution_version(0),
+        false,
+    );
+}
+
+/// Execute a block cancelling an async message with the given MIP store, and check that the
+/// message is cancelled only if `cancelled` is true
+fn run_cancel_async_message_operation(mip_store: MipStore, cancelled: bool) {
+    let exec_cfg = ExecutionConfig::default();
+    let finalized_waitpoint = WaitPoint::new();
+    let finalized_waitpoint_trigger_handle = finalized_waitpoint.get_trigger_handle();
+    let mut foreign_controllers = ExecutionForeignControllers::new_with_mocks();
+    selector_boilerplate(&mut foreign_controllers.selector_controller);
+    let keypair = KeyPair::from_str(TEST_SK_1).unwrap();
+    let sender_addr = Address::from_public_key(&keypair.get_public_key());
+    // message waiting for its validity start, emitted directly by the sender
+    let message = AsyncMessage {
+        emission_slot: Slot::new(0, 5),
+        emission_index: 2,
+        sender: sender_addr,
+        destination: Address::from_str("AS12DSPbsNvvdP1ScCivmKpbQfcJJ3tCQFkNb8ewkRuNjsgoL2AeQ")
+            .unwrap(),
+        function: String::from("receive"),
+        max_gas: 3000000,
+        fee: Amount::from_raw(1),
+        coins: Amount::from_raw(100),
+        validity_start: Slot::new(10, 0),
+        validity_end: Slot::new(20, 0),
+        function_params: vec![42, 42, 42, 42],
+        trigger: None,
+        can_be_executed: true,
+    };
+    let message_id = message.compute_id();
+    let create_operation = |emission_index: u64| {
+        Operation::new_verifiable(
+            Operation {
+                fee: Amount::zero(),
+                expire_period: 10,
+                op: OperationType::CancelAsyncMessage {
+                    emission_slot: Slot::new(0, 5),
+                    emission_index,
+                },
+            },
+            OperationSerializer::new(),
+            &keypair,
+            *CHAINID,
+        )
+        .unwrap()
+    };
+    // the cancellation of a message that does not exist fails
+    let failing_operation = create_operation(3);
+    let operation = create_operation(2);
+    let (failing_operation_id, operation_id) = (failing_operation.id, operation.id);
+    let mut async_pool = AsyncPool::new(AsyncPoolConfig::default(), foreign_controllers.db.clone());
+    let mut changes = AsyncPoolChanges::default();
+    changes.push_add(message_id, message);
+    let mut db_batch = DBBatch::default();
+    async_pool.apply_changes_to_batch(&changes, &mut db_batch);
+    foreign_controllers.db.write().write_batch(
+        db_batch,
+        DBBatch::default(),
+        Some(Slot::new(0, 31)),
+    );
+    final_state_boilerplate(
+        &mut foreign_controllers.final_state,
+        foreign_controllers.db.clone(),
+        &foreign_controllers.selector_controller,
+        &mut foreign_controllers.ledger_controller,
+        None,
+        Some(async_pool),
+        None,
+    );
+    foreign_controllers
+        .final_state
+        .write()
+        .expect_finalize()
+        .times(1)
+        .with(predicate::eq(Slot::new(1, 0)), predicate::always())
+        .returning(move |_, changes| {
+            assert_eq!(
+                changes.executed_ops_changes.get(&failing_operation_id),
+                Some(&(false, Slot::new(10, 0)))
+            );
+            assert_eq!(
+                changes.executed_ops_changes.get(&operation_id),
+                Some(&(cancelled, Slot::new(10, 0)))
+            );
+            // the message is deleted from the pool
+            assert_eq!(
+                changes.async_pool_changes.0.get(&message_id),
+                cancelled.then_some(&SetUpdateOrDelete::Delete)
+            );
+            // 100 in the get_balance in the `final_state_boilerplate`, the block rewards
+            // and the reimbursed coins of the message
+            let reimbursed = if cancelled {
+                Amount::from_raw(100)
+            } else {
+                Amount::zero()
+            };
+            assert_eq!(
+                changes
+                    .ledger_changes
+                    .get_balance_or_else(&sender_addr, || None),
+                Some(
+                    Amount::from_str("100")
+                        .unwrap()
+                        .saturating_add(exec_cfg.block_reward)
+                        .saturating_add(reimbursed)
+                )
+            );
+            finalized_waitpoint_trigger_handle.trigger();
+        });
+    let mut universe =
+        ExecutionTestUniverse::new_with_mip_store(foreign_controllers, exec_cfg.clone(), mip_store);
+
+    universe
+        .storage
+        .store_operations(vec![failing_operation.clone(), operation.clone()]);
+    let block = ExecutionTestUniverse::create_block(
+        &keypair,
+        Slot::new(1, 0),
+        vec![failing_operation, operation],
+        vec![],
+        vec![],
+    );
+    universe.send_and_finalize(&keypair, block);
+    finalized_waitpoint.wait();
+}
+
 /// Context
 ///
 /// Functional test for local smart-contract execution
@@ -1281,6 +1606,39 @@ fn send_and_receive_transaction() {
     // store the block in storage
     universe.send_and_finalize(&KeyPair::from_str(TEST_SK_1).unwrap(), block);
     finalized_waitpoint.wait();
+
+    // the changes of the final slot are returned as a state diff
+    let from_slot = Slot::new(1, 0).get_prev_slot(THREAD_COUNT).unwrap();
+    let state_diff = universe
+        .module_controller
+        .get_state_diff(from_slot, Slot::new(1, 0))
+        .unwrap();
+    assert_eq!(
+        state_diff
+            .state_changes
+            .ledger_changes
+            .get_balance_or_else(&recipient_address, || None),
+        Some(Amount::from_str("190").unwrap())
+    );
+    // the changes of the previous slots are not kept
+    assert!(universe
+        .module_controller
+        .get_state_diff(Slot::new(0, 0), Slot::new(1, 0))
+        .is_err());
+    // the slots must be final
+    assert!(universe
+        .module_controller
+        .get_state_diff(from_slot, Slot::new(10, 0))
+        .is_err());
+
+    // the execution statistics of the final slot are kept
+    let slot_stats = universe.module_controller.get_slot_execution_stats(1);
+    assert_eq!(slot_stats.len(), 1);
+    let (slot, stats) = &slot_stats[0];
+    assert_eq!(*slot, Slot::new(1, 0));
+    assert_eq!(stats.operation_count, 1);
+    assert_eq!(stats.gas_used, exec_cfg.base_operation_gas_cost);
+    assert_eq!(stats.gas_limit, exec_cfg.max_gas_per_block);
 }
 
 #[test]
@@ -2036,7 +2394,7 @@ fn datastore_manipulations() {
                 .returning(move |_, _| None);
             ledger_controller
                 .expect_get_datastore_keys()
-                .returning(move |_, _| None);
+                .returning(move |_, _, _, _| None);
             ledger_controller
                 .expect_get_bytecode()
                 .returning(move |_| None);
@@ -2154,6 +2512,28 @@ fn datastore_manipulations() {
 
     let key_a: Vec<u8> = [1, 0, 4, 255].to_vec();
 
+    // the execution trace of the operation records its datastore writes
+    #[cfg(feature = "execution-trace")]
+    {
+        let key_b: Vec<u8> = [2, 0, 254, 255].to_vec();
+        let op_trace = universe
+            .module_controller
+            .get_operation_trace(&operation.id)
+            .expect("operation trace not found");
+        assert_eq!(op_trace.slot, Slot::new(1, 0));
+        for key in [&key_a, &key_b] {
+            assert!(
+                op_trace.storage_accesses.iter().any(|access| {
+                    access.address == addr
+                        && access.key == *key
+                        && access.access_type == StorageAccessType::Write
+                }),
+                "{:?}",
+                op_trace.storage_accesses
+            );
+        }
+    }
+
     universe
         .module_controller
         .query_state(ExecutionQueryRequest {
@@ -2614,6 +2994,69 @@ fn chain_id() {
     assert_eq!(events[0].data, format!("Chain id: {}", *CHAINID));
 }
 
+#[test]
+fn broadcast_serialized_output() {
+    let mut exec_cfg = ExecutionConfig::default();
+    exec_cfg.broadcast_enabled = true;
+    exec_cfg.broadcast_serialized_output_format = Some(ExportFormat::Json);
+    let finalized_waitpoint = WaitPoint::new();
+    let mut foreign_controllers = ExecutionForeignControllers::new_with_mocks();
+    let keypair = KeyPair::from_str(TEST_SK_1).unwrap();
+    selector_boilerplate(&mut foreign_controllers.selector_controller);
+    expect_finalize_deploy_and_call_blocks(
+        Slot::new(1, 0),
+        None,
+        finalized_waitpoint.get_trigger_handle(),
+        &mut foreign_controllers.final_state,
+    );
+    final_state_boilerplate(
+        &mut foreign_controllers.final_state,
+        foreign_controllers.db.clone(),
+        &foreign_controllers.selector_controller,
+        &mut foreign_controllers.ledger_controller,
+        None,
+        None,
+        None,
+    );
+    let mut universe = ExecutionTestUniverse::new(foreign_controllers, exec_cfg);
+    let mut receiver = universe
+        .broadcast_serialized_channel_receiver
+        .take()
+        .unwrap();
+    universe.deploy_bytecode_block(
+        &keypair,
+        Slot::new(1, 0),
+        include_bytes!("./wasm/chain_id.wasm"),
+        //unused
+        include_bytes!("./wasm/chain_id.wasm"),
+    );
+    finalized_waitpoint.wait();
+
+    // the slots are broadcast once executed, then once finalized
+    let (serialized_out, export) = loop {
+        let serialized_out = receiver
+            .blocking_recv()
+            .expect("no serialized execution output");
+        assert_eq!(serialized_out.format, ExportFormat::Json);
+        let export: ExecutionOutputExport = serde_json::from_slice(&serialized_out.data).unwrap();
+        assert_eq!(export.slot, serialized_out.slot);
+        assert_eq!(export.is_final, serialized_out.is_final);
+        if export.is_final && export.slot == Slot::new(1, 0) {
+            break (serialized_out, export);
+        }
+    };
+    assert!(export.block_id.is_some());
+    assert_eq!(export.events.len(), 1);
+    assert_eq!(export.events[0].data, format!("Chain id: {}", *CHAINID));
+
+    // the amounts are exported as decimal strings
+    let value: serde_json::Value = serde_json::from_slice(&serialized_out.data).unwrap();
+    let ledger_changes = value["state_changes"]["ledger_changes"].as_array().unwrap();
+    assert!(ledger_changes
+        .iter()
+        .any(|change| change["change"]["balance"].is_string()));
+}
+
 #[cfg(feature = "execution-trace")]
 #[test]
 fn execution_trace() {
@@ -2729,6 +3172,39 @@ fn execution_trace() {
             }
         ]
     );
+
+    // the execution trace of the operation gathers its ABI calls and the gas it consumed
+    let (op_id, op_call_stack) = broadcast_result
+        .operation_call_stacks
+        .iter()
+        .next()
+        .unwrap();
+    let op_trace = universe
+        .module_controller
+        .get_operation_trace(op_id)
+        .expect("operation trace not found");
+    assert_eq!(op_trace.op_id, *op_id);
+    assert_eq!(op_trace.slot, Slot::new(1, 0));
+    assert!(op_trace.succeed);
+    assert!(op_trace.gas_used > 0);
+    assert_eq!(op_trace.abi_calls.len(), op_call_stack.len());
+
+    // the gas profile of the slot accounts for the transfer
+    let gas_profile = universe
+        .module_controller
+        .get_gas_profile(Slot::new(1, 0), Slot::new(1, 1));
+    assert_eq!(gas_profile.slot_count, 1);
+    let transfer_usage = gas_profile
+        .abi_usage
+        .get(abi_name_2)
+        .expect("transfer ABI not profiled");
+    assert_eq!(transfer_usage.call_count, 1);
+    assert!(transfer_usage.gas > 0);
+    assert!(universe
+        .module_controller
+        .get_gas_profile(Slot::new(1, 1), Slot::new(2, 0))
+        .abi_usage
+        .is_empty());
 }
 
 #[cfg(feature = "execution-trace")]
@@ -1,13 +1,19 @@
 use crate::active_history::ActiveHistory;
-use massa_execution_exports::ExecutionOutput;
+use crate::speculative_ledger::SpeculativeLedger;
+use massa_execution_exports::{ExecutionConfig, ExecutionOutput};
 use massa_models::slot::Slot;
+use parking_lot::RwLock;
 use std::collections::{BTreeMap, VecDeque};
+use std::sync::Arc;
 
-use massa_final_state::StateChanges;
+use massa_final_state::{MockFinalStateController, StateChanges};
 use massa_hash::Hash;
+use massa_ledger_exports::{
+    LedgerChanges, LedgerEntry, MockLedgerControllerWrapper, SetUpdateOrDelete,
+};
 use massa_models::address::{Address, UserAddress, UserAddressV0};
 use massa_models::amount::Amount;
-use massa_models::prehash::{CapacityAllocator, PreHashMap};
+use massa_models::prehash::{CapacityAllocator, PreHashMap, PreHashSet};
 use massa_pos_exports::{DeferredCredits, PoSChanges};
 
 #[test]
@@ -54,6 +60,9 @@ fn test_active_history_deferred_credits() {
             execution_trail_hash_change: Default::default(),
         },
         events: Default::default(),
+        event_overflow: None,
+        stats: Default::default(),
+        final_ledger_reads: Default::default(),
         #[cfg(feature = "execution-trace")]
         slot_trace: Default::default(),
     };
@@ -75,3 +84,74 @@ fn test_active_history_deferred_credits() {
         Some(amount_a2_s1)
     );
 }
+
+#[test]
+fn test_speculative_ledger_final_reads() {
+    let addr1 = Address::User(UserAddress::UserAddressV0(UserAddressV0(
+        Hash::compute_from("AU1".as_bytes()),
+    )));
+    let addr2 = Address::User(UserAddress::UserAddressV0(UserAddressV0(
+        Hash::compute_from("AU2".as_bytes()),
+    )));
+
+    // the final ledger holds both addresses
+    let ledger_controller = MockLedgerControllerWrapper::new();
+    ledger_controller.set_expectations(|ledger_controller| {
+        ledger_controller
+            .expect_get_balance()
+            .returning(|_| Some(Amount::from_raw(1)));
+    });
+    let mut final_state = MockFinalStateController::new();
+    final_state
+        .expect_get_ledger()
+        .return_const(Box::new(ledger_controller));
+
+    // the active history changes the first one
+    let mut ledger_changes = LedgerChanges::default();
+    ledger_changes.0.insert(
+        addr1,
+        SetUpdateOrDelete::Set(LedgerEntry {
+            balance: Amount::from_raw(2),
+            ..Default::default()
+        }),
+    );
+    let exec_output = ExecutionOutput {
+        slot: Slot::new(1, 0),
+        block_info: None,
+        state_changes: StateChanges {
+            ledger_changes,
+            ..Default::default()
+        },
+        events: Default::default(),
+        event_overflow: None,
+        stats: Default::default(),
+        final_ledger_reads: Default::default(),
+        #[cfg(feature = "execution-trace")]
+        slot_trace: Default::default(),
+    };
+
+    let config = ExecutionConfig::default();
+    let mut speculative_ledger = SpeculativeLedger::new(
+        Arc::new(RwLock::new(final_state)),
+        Arc::new(RwLock::new(ActiveHistory(VecDeque::from([exec_output])))),
+        config.max_datastore_key_length,
+        config.max_bytecode_size,
+        config.max_datastore_value_size,
+        config.storage_costs_constants,
+    );
+    assert_eq!(
+        speculative_ledger.get_balance(&addr1),
+        Some(Amount::from_raw(2))
+    );
+    assert_eq!(
+        speculative_ledger.get_balance(&addr2),
+        Some(Amount::from_raw(1))
+    );
+
+    // only the address read from the final ledger is reported, and the reads are reset
+    assert_eq!(
+        speculative_ledger.take_final_reads(),
+        PreHashSet::from_iter([addr2])
+    );
+    assert!(speculative_ledger.take_final_reads().is_empty());
+}
@@ -8,7 +8,7 @@ use massa_db_exports::{MassaDBConfig, MassaDBController, ShareableMassaDBControl
 use massa_db_worker::MassaDB;
 use massa_execution_exports::{
     ExecutionBlockMetadata, ExecutionChannels, ExecutionConfig, ExecutionController,
-    ExecutionError, ExecutionManager, SlotExecutionOutput,
+    ExecutionError, ExecutionManager, SerializedSlotExecutionOutput, SlotExecutionOutput,
 };
 use massa_final_state::{FinalStateController, MockFinalStateController};
 use massa_ledger_exports::MockLedgerControllerWrapper;
@@ -19,7 +19,9 @@ use massa_models::{
     amount::Amount,
     block::SecureShareBlock,
     block_id::BlockId,
-    config::{MIP_STORE_STATS_BLOCK_CONSIDERED, THREAD_COUNT},
+    config::{
+        MIP_STORE_STATS_BLOCK_CONSIDERED, THREAD_COUNT, VERSIONING_THRESHOLD_TRANSITION_ACCEPTED,
+    },
     datastore::Datastore,
     execution::EventFilter,
     operation::{Operation, OperationSerializer, OperationType, SecureShareOperation},
@@ -31,7 +33,10 @@ use massa_pos_exports::MockSelectorControllerWrapper;
 use massa_signature::KeyPair;
 use massa_storage::Storage;
 use massa_test_framework::TestUniverse;
-use massa_versioning::versioning::{MipStatsConfig, MipStore};
+use massa_time::MassaTime;
+use massa_versioning::versioning::{
+    Advance, MipComponent, MipInfo, MipState, MipStatsConfig, MipStore,
+};
 use massa_wallet::test_exports::create_test_wallet;
 use num::rational::Ratio;
 use parking_lot::RwLock;
@@ -79,6 +84,8 @@ pub struct ExecutionTestUniverse {
     pub final_state: Arc<RwLock<dyn FinalStateController>>,
     module_manager: Box<dyn ExecutionManager>,
     pub broadcast_channel_receiver: Option<tokio::sync::broadcast::Receiver<SlotExecutionOutput>>,
+    pub broadcast_serialized_channel_receiver:
+        Option<tokio::sync::broadcast::Receiver<SerializedSlotExecutionOutput>>,
     #[cfg(feature = "execution-trace")]
     pub broadcast_traces_channel_receiver:
         Option<tokio::sync::broadcast::Receiver<(SlotAbiCallStack, bool)>>,
@@ -89,17 +96,39 @@ impl TestUniverse for ExecutionTestUniverse {
     type Config = ExecutionConfig;
 
     fn new(controllers: Self::ForeignControllers, config: Self::Config) -> Self {
+        let mip_store = MipStore::try_from(([], mip_stats_config())).unwrap();
+        Self::new_with_mip_store(controllers, config, mip_store)
+    }
+}
+
+fn mip_stats_config() -> MipStatsConfig {
+    MipStatsConfig {
+        block_count_considered: MIP_STORE_STATS_BLOCK_CONSIDERED,
+        warn_announced_version_ratio: Ratio::new_raw(30, 100),
+    }
+}
+
+impl Drop for ExecutionTestUniverse {
+    fn drop(&mut self) {
+        self.module_manager.stop();
+    }
+}
+
+impl ExecutionTestUniverse {
+    /// Create a universe whose execution worker uses `mip_store`
+    pub fn new_with_mip_store(
+        controllers: ExecutionForeignControllers,
+        config: ExecutionConfig,
+        mip_store: MipStore,
+    ) -> Self {
         let storage = Storage::create_root();
-        let mip_stats_config = MipStatsConfig {
-            block_count_considered: MIP_STORE_STATS_BLOCK_CONSIDERED,
-            warn_announced_version_ratio: Ratio::new_raw(30, 100),
-        };
-        let mip_store = MipStore::try_from(([], mip_stats_config)).unwrap();
         let (tx, rx) = broadcast::channel(16);
+        let (tx_serialized, rx_serialized) = broadcast::channel(16);
         #[cfg(feature = "execution-trace")]
         let (tx_traces, rx_traces) = broadcast::channel(16);
         let exec_channels = ExecutionChannels {
             slot_execution_output_sender: tx,
+            slot_execution_serialized_output_sender: tx_serialized,
             #[cfg(feature = "execution-trace")]
             slot_execution_traces_sender: tx_traces,
         };
@@ -125,21 +154,42 @@ impl TestUniverse for ExecutionTestUniverse {
             module_controller,
             module_manager,
             broadcast_channel_receiver: Some(rx),
+            broadcast_serialized_channel_receiver: Some(rx_serialized),
             #[cfg(feature = "execution-trace")]
             broadcast_traces_channel_receiver: Some(rx_traces),
         };
         universe.initialize();
         universe
     }
-}
 
-impl Drop for ExecutionTestUniverse {
-    fn drop(&mut self) {
-        self.module_manager.stop();
+    /// Create a MIP store in which `version` of the execution component is active
+    pub fn mip_store_with_execution_version(version: u32) -> MipStore {
+        let mip_info = MipInfo {
+            name: "MIP-EXECUTION".to_string(),
+            version: 1,
+            components: BTreeMap::from([(MipComponent::Execution, version)]),
+            start: MassaTime::from_millis(2),
+            timeout: MassaTime::from_millis(10),
+            activation_delay: MassaTime::from_millis(2),
+        };
+        // advance the state of the MIP until it is active
+        let mut mip_state = MipState::new(MassaTime::from_millis(1));
+        let mut advance = Advance {
+            start_timestamp: mip_info.start,
+            timeout: mip_info.timeout,
+            activation_delay: mip_info.activation_delay,
+            threshold: Default::default(),
+            now: MassaTime::from_millis(3),
+        };
+        mip_state.on_advance(&advance);
+        advance.threshold = VERSIONING_THRESHOLD_TRANSITION_ACCEPTED;
+        advance.now = MassaTime::from_millis(4);
+        mip_state.on_advance(&advance);
+        advance.now = MassaTime::from_millis(7);
+        mip_state.on_advance(&advance);
+        MipStore::try_from(([(mip_info, mip_state)], mip_stats_config())).unwrap()
     }
-}
 
-impl ExecutionTestUniverse {
     /// Create an operation for the given sender with `data` as bytecode.
     pub fn create_execute_sc_operation(
         sender_keypair: &KeyPair,
@@ -1,6 +1,11 @@
-use massa_execution_exports::{AbiTrace, SlotAbiCallStack, Transfer};
+use massa_execution_exports::{
+    AbiGasUsage, AbiTrace, GasProfile, OperationTrace, SlotAbiCallStack, Transfer,
+};
 use massa_models::{operation::OperationId, slot::Slot};
 use schnellru::{ByLength, LruMap};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use tracing::warn;
 
 /// Execution traces history
 pub struct TraceHistory {
@@ -10,14 +15,27 @@ pub struct TraceHistory {
     transfer_per_slot: LruMap<Slot, Vec<Transfer>>,
     /// Execution op linked to slot
     op_per_slot: LruMap<OperationId, Slot>,
+    /// Execution traces by operation
+    trace_per_op: LruMap<OperationId, OperationTrace>,
+    /// Gas charged for the ABI calls by slot
+    abi_gas_per_slot: LruMap<Slot, BTreeMap<String, AbiGasUsage>>,
+    /// Gas cost of each ABI
+    abi_gas_costs: HashMap<String, u64>,
 }
 
 impl TraceHistory {
-    pub fn new(max_slot_size_cache: u32, op_per_slot: u32) -> Self {
+    pub fn new(
+        max_slot_size_cache: u32,
+        op_per_slot: u32,
+        abi_gas_costs: HashMap<String, u64>,
+    ) -> Self {
         Self {
             trace_per_slot: LruMap::new(ByLength::new(max_slot_size_cache)),
             op_per_slot: LruMap::new(ByLength::new(max_slot_size_cache * op_per_slot)),
             transfer_per_slot: LruMap::new(ByLength::new(max_slot_size_cache)),
+            trace_per_op: LruMap::new(ByLength::new(max_slot_size_cache * op_per_slot)),
+            abi_gas_per_slot: LruMap::new(ByLength::new(max_slot_size_cache)),
+            abi_gas_costs,
         }
     }
 
@@ -57,11 +75,45 @@ impl TraceHistory {
             .flatten()
     }
 
+    /// Fetch the execution trace of a given operation id
+    pub(crate) fn fetch_operation_trace(&self, op_id: &OperationId) -> Option<OperationTrace> {
+        self.trace_per_op.peek(op_id).cloned()
+    }
+
+    /// Fetch the gas charged for the ABI calls of the slots from `start` (included)
+    /// to `end` (excluded)
+    pub(crate) fn fetch_gas_profile(&self, start: Slot, end: Slot) -> GasProfile {
+        let mut profile = GasProfile::default();
+        for (_, abi_usage) in self
+            .abi_gas_per_slot
+            .iter()
+            .filter(|(slot, _)| **slot >= start && **slot < end)
+        {
+            profile.slot_count += 1;
+            for (name, usage) in abi_usage {
+                let total = profile.abi_usage.entry(name.clone()).or_default();
+                total.call_count = total.call_count.saturating_add(usage.call_count);
+                total.gas = total.gas.saturating_add(usage.gas);
+            }
+        }
+        profile
+    }
+
     /// Save execution traces for a given slot
     pub(crate) fn save_traces_for_slot(&mut self, slot: Slot, traces: SlotAbiCallStack) {
         for (op_id, _) in traces.operation_call_stacks.iter() {
             self.op_per_slot.insert(*op_id, slot);
         }
+        let mut abi_usage = BTreeMap::new();
+        for abi_trace in traces
+            .operation_call_stacks
+            .values()
+            .chain(traces.asc_call_stacks.iter())
+            .flatten()
+        {
+            add_abi_gas_usage(abi_trace, &self.abi_gas_costs, &mut abi_usage);
+        }
+        self.abi_gas_per_slot.insert(slot, abi_usage);
         self.trace_per_slot.insert(slot, traces);
     }
 
@@ -72,4 +124,137 @@ impl TraceHistory {
         }
         self.transfer_per_slot.insert(slot, transfers);
     }
+
+    /// Save the execution traces of the operations of a slot
+    pub(crate) fn save_operation_traces(&mut self, traces: Vec<OperationTrace>) {
+        for trace in traces {
+            self.trace_per_op.insert(trace.op_id, trace);
+        }
+    }
+}
+
+/// Adds the gas charged for an ABI call and its sub calls
+fn add_abi_gas_usage(
+    abi_trace: &AbiTrace,
+    abi_gas_costs: &HashMap<String, u64>,
+    abi_usage: &mut BTreeMap<String, AbiGasUsage>,
+) {
+    let usage = abi_usage.entry(abi_trace.name.clone()).or_default();
+    usage.call_count = usage.call_count.saturating_add(1);
+    let gas = abi_gas_costs
+        .get(&abi_trace.name)
+        .copied()
+        .unwrap_or_default();
+    usage.gas = usage.gas.saturating_add(gas);
+    for sub_call in abi_trace.sub_calls.iter().flatten() {
+        add_abi_gas_usage(sub_call, abi_gas_costs, abi_usage);
+    }
+}
+
+/// Load the gas cost of each ABI from the ABI gas costs file
+pub(crate) fn load_abi_gas_costs(path: &Path) -> HashMap<String, u64> {
+    std::fs::read_to_string(path)
+        .map_err(|err| err.to_string())
+        .and_then(|costs| serde_json::from_str(&costs).map_err(|err| err.to_string()))
+        .unwrap_or_else(|err| {
+            warn!(
+                "failed to load the ABI gas costs from {}, ABI calls are profiled without gas: {}",
+                path.display(),
+                err
+            );
+            HashMap::new()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_execution_exports::SCRuntimeAbiTraceType;
+    use massa_models::prehash::PreHashMap;
+
+    fn abi_trace(name: &str, sub_calls: Option<Vec<AbiTrace>>) -> AbiTrace {
+        AbiTrace {
+            name: name.to_string(),
+            parameters: vec![],
+            return_value: SCRuntimeAbiTraceType::U64(0),
+            sub_calls,
+        }
+    }
+
+    fn slot_traces(slot: Slot, asc_call_stacks: Vec<Vec<AbiTrace>>) -> SlotAbiCallStack {
+        SlotAbiCallStack {
+            slot,
+            asc_call_stacks,
+            operation_call_stacks: PreHashMap::default(),
+        }
+    }
+
+    #[test]
+    fn test_gas_profile() {
+        let abi_gas_costs = HashMap::from([
+            ("call".to_string(), 100),
+            ("transfer_coins".to_string(), 10),
+        ]);
+        let mut trace_history = TraceHistory::new(10, 10, abi_gas_costs);
+        // the sub calls of a call are profiled, and the ABIs without cost are charged no gas
+        trace_history.save_traces_for_slot(
+            Slot::new(1, 0),
+            slot_traces(
+                Slot::new(1, 0),
+                vec![vec![abi_trace(
+                    "call",
+                    Some(vec![
+                        abi_trace("transfer_coins", None),
+                        abi_trace("generate_event", None),
+                    ]),
+                )]],
+            ),
+        );
+        trace_history.save_traces_for_slot(
+            Slot::new(1, 1),
+            slot_traces(
+                Slot::new(1, 1),
+                vec![vec![abi_trace("transfer_coins", None)]],
+            ),
+        );
+        trace_history.save_traces_for_slot(
+            Slot::new(2, 0),
+            slot_traces(Slot::new(2, 0), vec![vec![abi_trace("call", None)]]),
+        );
+
+        // the end of the range is excluded
+        let profile = trace_history.fetch_gas_profile(Slot::new(1, 0), Slot::new(2, 0));
+        assert_eq!(profile.slot_count, 2);
+        assert_eq!(
+            profile.abi_usage,
+            BTreeMap::from([
+                (
+                    "call".to_string(),
+                    AbiGasUsage {
+                        call_count: 1,
+                        gas: 100,
+                    },
+                ),
+                (
+                    "generate_event".to_string(),
+                    AbiGasUsage {
+                        call_count: 1,
+                        gas: 0,
+                    },
+                ),
+                (
+                    "transfer_coins".to_string(),
+                    AbiGasUsage {
+                        call_count: 2,
+                        gas: 20,
+                    },
+                ),
+            ])
+        );
+
+        // the slots missing from the history are not counted
+        let profile = trace_history.fetch_gas_profile(Slot::new(2, 0), Slot::new(5, 0));
+        assert_eq!(profile.slot_count, 1);
+        assert_eq!(profile.abi_usage["call"].call_count, 1);
+    }
 }
@@ -7,15 +7,19 @@
 
 use crate::controller::{ExecutionControllerImpl, ExecutionInputData, ExecutionManagerImpl};
 use crate::execution::ExecutionState;
+use crate::readonly_cache::ReadOnlyCache;
+use crate::readonly_limiter::ReadOnlyLimiter;
 use crate::request_queue::RequestQueue;
 use crate::slot_sequencer::SlotSequencer;
 use massa_execution_exports::{
     ExecutionBlockMetadata, ExecutionChannels, ExecutionConfig, ExecutionController,
-    ExecutionError, ExecutionManager, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
+    ExecutionError, ExecutionManager, OperationSimulationOutput, ReadOnlyExecutionOutput,
+    ReadOnlyExecutionRequest,
 };
 use massa_final_state::FinalStateController;
 use massa_metrics::MassaMetrics;
 use massa_models::block_id::BlockId;
+use massa_models::operation::SecureShareOperation;
 use massa_models::slot::Slot;
 use massa_pos_exports::SelectorController;
 use massa_time::MassaTime;
@@ -26,6 +30,9 @@ use std::sync::Arc;
 use std::thread;
 use tracing::debug;
 
+#[cfg(feature = "execution-trace")]
+use crate::debugger::DebugSessions;
+
 /// Structure gathering all elements needed by the execution thread
 pub(crate) struct ExecutionThread {
     // A copy of the input data allowing access to incoming requests
@@ -36,8 +43,12 @@ pub(crate) struct ExecutionThread {
     execution_state: Arc<RwLock<ExecutionState>>,
     /// queue for read-only requests and response MPSCs to send back their outputs
     readonly_requests: RequestQueue<ReadOnlyExecutionRequest, ReadOnlyExecutionOutput>,
+    /// queue for operations to simulate and response MPSCs to send back their outputs
+    operation_simulations: RequestQueue<SecureShareOperation, OperationSimulationOutput>,
     /// Selector controller
     selector: Box<dyn SelectorController>,
+    /// prometheus metrics
+    massa_metrics: MassaMetrics,
 }
 
 impl ExecutionThread {
@@ -48,11 +59,13 @@ impl ExecutionThread {
     /// * `config`: execution configuration
     /// * `input_data`: a copy of the input data interface to get incoming requests from
     /// * `execution_state`: an thread-safe shared access to the execution state, which can be bootstrapped or newly created
+    /// * `massa_metrics`: prometheus metrics
     pub fn new(
         config: ExecutionConfig,
         input_data: Arc<(Condvar, Mutex<ExecutionInputData>)>,
         execution_state: Arc<RwLock<ExecutionState>>,
         selector: Box<dyn SelectorController>,
+        massa_metrics: MassaMetrics,
     ) -> Self {
         // get the latest executed final slot, at the output of which the final ledger is attached
         // if we are restarting the network, use last genesis slot of the last start.
@@ -69,9 +82,11 @@ impl ExecutionThread {
         ExecutionThread {
             input_data,
             readonly_requests: RequestQueue::new(config.readonly_queue_length),
+            operation_simulations: RequestQueue::new(config.readonly_queue_length),
             execution_state,
             slot_sequencer: SlotSequencer::new(config, final_cursor),
             selector,
+            massa_metrics,
         }
     }
 
@@ -80,19 +95,25 @@ impl ExecutionThread {
     fn update_readonly_requests(
         &mut self,
         new_requests: RequestQueue<ReadOnlyExecutionRequest, ReadOnlyExecutionOutput>,
+        new_simulations: RequestQueue<SecureShareOperation, OperationSimulationOutput>,
     ) {
         // Append incoming readonly requests to our readonly request queue
         // Excess requests are cancelled
         self.readonly_requests.extend(new_requests);
+        self.operation_simulations.extend(new_simulations);
+        self.massa_metrics
+            .set_readonly_queue_depth(self.readonly_requests.len());
     }
 
-    /// Executes a read-only request from the queue, if any.
+    /// Executes the oldest of the read-only requests of highest priority from the queue, if any.
     /// The result of the execution is sent asynchronously through the response channel provided with the request.
     ///
     /// # Returns
     /// true if a request was executed, false otherwise
     fn execute_one_readonly_request(&mut self) -> bool {
-        if let Some(req_resp) = self.readonly_requests.pop() {
+        if let Some(req_resp) = self.readonly_requests.pop_by_priority(|req| req.priority) {
+            self.massa_metrics
+                .set_readonly_queue_depth(self.readonly_requests.len());
             let (req, resp_tx) = req_resp.into_request_sender_pair();
 
             // Acquire write access to the execution state (for cache updates) and execute the read-only request
@@ -108,6 +129,24 @@ impl ExecutionThread {
         false
     }
 
+    /// Simulates an operation from the queue, if any.
+    /// The result of the simulation is sent through the response channel provided with the request.
+    ///
+    /// # Returns
+    /// true if an operation was simulated, false otherwise
+    fn execute_one_operation_simulation(&mut self) -> bool {
+        if let Some(req_resp) = self.operation_simulations.pop() {
+            let (operation, resp_tx) = req_resp.into_request_sender_pair();
+            let outcome = self.execution_state.write().simulate_operation(operation);
+
+            // Ignore send errors: the request emitter does not need the response anymore
+            let _ = resp_tx.send(outcome);
+
+            return true;
+        }
+        false
+    }
+
     /// Waits for an event to trigger a new iteration in the execution main loop.
     ///
     /// # Returns
@@ -131,6 +170,7 @@ impl ExecutionThread {
                 || !input_data.finalized_blocks.is_empty()
                 || !input_data.block_metadata.is_empty()
                 || !input_data.readonly_requests.is_empty()
+                || !input_data.operation_simulations.is_empty()
             {
                 return (input_data, false);
             }
@@ -141,7 +181,7 @@ impl ExecutionThread {
             }
 
             // there are read-only requests ready
-            if !self.readonly_requests.is_empty() {
+            if !self.readonly_requests.is_empty() || !self.operation_simulations.is_empty() {
                 return (input_data, false);
             }
 
@@ -177,7 +217,10 @@ impl ExecutionThread {
             debug!("Execution loop triggered, input_data = {}", input_data);
 
             // update the sequence of read-only requests
-            self.update_readonly_requests(input_data.readonly_requests);
+            self.update_readonly_requests(
+                input_data.readonly_requests,
+                input_data.operation_simulations,
+            );
 
             if stop {
                 // we need to stop
@@ -217,7 +260,10 @@ impl ExecutionThread {
             }
 
             // low priority: execute a read-only request (note that the queue is of finite length), if there is one ready.
-            self.execute_one_readonly_request();
+            // The operation simulations are read-only executions as well.
+            if !self.execute_one_readonly_request() {
+                self.execute_one_operation_simulation();
+            }
         }
 
         // We are quitting the loop.
@@ -226,12 +272,9 @@ impl ExecutionThread {
         let cancel_err = ExecutionError::ChannelError(
             "readonly execution cancelled because the execution worker is closing".into(),
         );
-        self.input_data
-            .1
-            .lock()
-            .take()
-            .readonly_requests
-            .cancel(cancel_err);
+        let mut input_data = self.input_data.1.lock().take();
+        input_data.readonly_requests.cancel(cancel_err.clone());
+        input_data.operation_simulations.cancel(cancel_err);
     }
 }
 
@@ -262,7 +305,7 @@ pub fn start_execution_worker(
         selector.clone(),
         channels,
         wallet,
-        massa_metrics,
+        massa_metrics.clone(),
     )));
 
     // define the input data interface
@@ -275,6 +318,15 @@ pub fn start_execution_worker(
     let controller = ExecutionControllerImpl {
         input_data: input_data.clone(),
         execution_state: execution_state.clone(),
+        readonly_cache: (config.readonly_cache_size > 0)
+            .then(|| Arc::new(Mutex::new(ReadOnlyCache::new(config.readonly_cache_size)))),
+        readonly_limiter: Arc::new(ReadOnlyLimiter::new(
+            config.max_concurrent_readonly_requests,
+            config.max_concurrent_readonly_requests_per_caller,
+            massa_metrics.clone(),
+        )),
+        #[cfg(feature = "execution-trace")]
+        debug_sessions: Arc::new(Mutex::new(DebugSessions::new(config.max_debug_sessions))),
     };
 
     // launch the execution thread
@@ -282,7 +334,14 @@ pub fn start_execution_worker(
     let thread_builder = thread::Builder::new().name("execution".into());
     let thread_handle = thread_builder
         .spawn(move || {
-            ExecutionThread::new(config, input_data_clone, execution_state, selector).main_loop();
+            ExecutionThread::new(
+                config,
+                input_data_clone,
+                execution_state,
+                selector,
+                massa_metrics,
+            )
+            .main_loop();
         })
         .expect("failed to spawn thread : execution");
     // create a manager
@@ -7,13 +7,14 @@ use massa_models::{
     block_header::{BlockHeader, BlockHeaderSerializer, SecuredHeader},
     block_id::BlockId,
     endorsement::SecureShareEndorsement,
-    operation::{compute_operations_hash, OperationIdSerializer},
+    operation::{compute_operations_hash, OperationId, OperationIdSerializer},
+    prehash::PreHashSet,
     secure_share::SecureShareContent,
     slot::Slot,
     timeslots::{get_block_slot_timestamp, get_closest_slot_to_timestamp},
 };
 use massa_time::MassaTime;
-use massa_versioning::versioning::MipStore;
+use massa_versioning::versioning::{MipComponent, MipStore};
 use massa_wallet::Wallet;
 use parking_lot::RwLock;
 use std::{sync::Arc, thread, time::Instant};
@@ -122,6 +123,17 @@ impl BlockFactoryWorker {
 
     /// Process a slot: produce a block at that slot if one of the managed keys is drawn.
     fn process_slot(&mut self, slot: Slot) {
+        // apply the staking key changes scheduled for the cycle of that slot
+        let cycle = slot.get_cycle(self.cfg.periods_per_cycle);
+        if self.wallet.read().has_key_changes_due(cycle) {
+            if let Err(err) = self.wallet.write().apply_scheduled_key_changes(cycle) {
+                warn!(
+                    "block factory could not apply staking key changes for cycle {}: {}",
+                    cycle, err
+                );
+            }
+        }
+
         // get block producer address for that slot
         let block_producer_addr = match self.channels.selector.get_producer(slot) {
             Ok(addr) => addr,
@@ -200,12 +212,40 @@ impl BlockFactoryWorker {
         block_storage.extend(endo_storage);
 
         // gather operations and compute global operations hash
-        let (op_ids, op_storage) = self.channels.pool.get_block_operations(&slot);
+        let (mut op_ids, mut op_storage) = self.channels.pool.get_block_operations(&slot);
         if op_ids.len() > self.cfg.max_operations_per_block as usize {
             warn!("Too many operations returned");
             return;
         }
 
+        // exclude the operations whose type is not active yet at the slot, as they would make the block invalid
+        if let Ok(slot_timestamp) = get_block_slot_timestamp(
+            self.cfg.thread_count,
+            self.cfg.t0,
+            self.cfg.genesis_timestamp,
+            slot,
+        ) {
+            let execution_version = self
+                .mip_store
+                .get_latest_component_version_at(&MipComponent::Execution, slot_timestamp);
+            let inactive_ops: PreHashSet<OperationId> = {
+                let ops = op_storage.read_operations();
+                op_ids
+                    .iter()
+                    .filter(|op_id| {
+                        ops.get(op_id).is_some_and(|op| {
+                            op.content.op.required_execution_version() > execution_version
+                        })
+                    })
+                    .copied()
+                    .collect()
+            };
+            if !inactive_ops.is_empty() {
+                op_ids.retain(|op_id| !inactive_ops.contains(op_id));
+                op_storage.drop_operation_refs(&inactive_ops);
+            }
+        }
+
         block_storage.extend(op_storage);
 
         // create header
@@ -122,6 +122,17 @@ impl EndorsementFactoryWorker {
 
     /// Process a slot: produce an endorsement at that slot if one of the managed keys is drawn.
     fn process_slot(&mut self, slot: Slot) {
+        // apply the staking key changes scheduled for the cycle of that slot
+        let cycle = slot.get_cycle(self.cfg.periods_per_cycle);
+        if self.wallet.read().has_key_changes_due(cycle) {
+            if let Err(err) = self.wallet.write().apply_scheduled_key_changes(cycle) {
+                warn!(
+                    "endorsement factory could not apply staking key changes for cycle {}: {}",
+                    cycle, err
+                );
+            }
+        }
+
         // get endorsement producer addresses for that slot
         let producer_addrs = match self.channels.selector.get_selection(slot) {
             Ok(sel) => sel.endorsements,
@@ -43,4 +43,8 @@ pub struct FinalStateConfig {
     pub t0: MassaTime,
     /// TODO
     pub genesis_timestamp: MassaTime,
+    /// number of periods between two archived final states (0 disables the archive mode)
+    pub archive_interval: u64,
+    /// path of the directory of the archived final states
+    pub archive_path: PathBuf,
 }
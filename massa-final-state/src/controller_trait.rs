@@ -3,7 +3,7 @@ use massa_db_exports::{DBBatch, ShareableMassaDBController};
 use massa_executed_ops::ExecutedDenunciations;
 use massa_hash::Hash;
 use massa_ledger_exports::LedgerController;
-use massa_models::{operation::OperationId, slot::Slot};
+use massa_models::{address::Address, operation::OperationId, slot::Slot};
 use massa_pos_exports::PoSFinalState;
 use massa_versioning::versioning::MipStore;
 
@@ -90,4 +90,22 @@ pub trait FinalStateController: Send + Sync {
 
     /// Get mutable reference to MIP Store
     fn get_mip_store_mut(&mut self) -> &mut MipStore;
+
+    /// Get the slots at the output of which the final state was archived, in slot order
+    fn get_archived_slots(&self) -> Vec<Slot>;
+
+    /// Get a read-only ledger of the final state archived at the output of `slot`
+    fn get_archived_ledger(&self, slot: Slot)
+        -> Result<Box<dyn LedgerController>, FinalStateError>;
+
+    /// Get a datastore entry of an address in the final state archived at the output of `slot`
+    ///
+    /// # Returns
+    /// The datastore value, or `None` if the ledger entry or datastore entry was not found
+    fn get_datastore_entry_at_slot(
+        &self,
+        slot: Slot,
+        addr: &Address,
+        key: &[u8],
+    ) -> Result<Option<Vec<u8>>, FinalStateError>;
 }
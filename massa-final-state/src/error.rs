@@ -19,6 +19,8 @@ pub enum FinalStateError {
     PosError(String),
     /// Snapshot error: {0}
     SnapshotError(String),
+    /// Archive error: {0}
+    ArchiveError(String),
     /// ExtendFromDbError
     ExtendFromDbError(#[from] ExtendFromDbError),
     /// IsConsistentWithShutdownPeriodError
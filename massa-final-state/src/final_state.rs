@@ -11,26 +11,38 @@ use crate::{config::FinalStateConfig, error::FinalStateError, state_changes::Sta
 use anyhow::{anyhow, Result as AnyResult};
 use massa_async_pool::AsyncPool;
 use massa_db_exports::{
-    DBBatch, MassaIteratorMode, ShareableMassaDBController, ASYNC_POOL_PREFIX,
-    CYCLE_HISTORY_PREFIX, DEFERRED_CREDITS_PREFIX, EXECUTED_DENUNCIATIONS_PREFIX,
-    EXECUTED_OPS_PREFIX, LEDGER_PREFIX, MIP_STORE_PREFIX, STATE_CF,
+    DBBatch, MassaDBConfig, MassaDBController, MassaIteratorMode, ShareableMassaDBController,
+    ASYNC_POOL_PREFIX, CYCLE_HISTORY_PREFIX, DEFERRED_CREDITS_PREFIX,
+    EXECUTED_DENUNCIATIONS_PREFIX, EXECUTED_OPS_PREFIX, LEDGER_PREFIX, MIP_STORE_PREFIX, STATE_CF,
 };
 use massa_db_exports::{EXECUTION_TRAIL_HASH_PREFIX, MIP_STORE_STATS_PREFIX, VERSIONING_CF};
+use massa_db_worker::MassaDB;
 use massa_executed_ops::ExecutedDenunciations;
 use massa_executed_ops::ExecutedOps;
 use massa_hash::Hash;
 use massa_ledger_exports::LedgerController;
 use massa_ledger_exports::SetOrKeep;
+use massa_ledger_worker::FinalLedger;
+use massa_models::address::Address;
+use massa_models::config::{
+    MAX_BOOTSTRAP_FINAL_STATE_PARTS_SIZE, MAX_BOOTSTRAP_VERSIONING_ELEMENTS_SIZE,
+};
 use massa_models::operation::OperationId;
 use massa_models::slot::Slot;
 use massa_pos_exports::{PoSFinalState, SelectorController};
 use massa_versioning::versioning::MipStore;
+use parking_lot::RwLock;
+use std::path::PathBuf;
+use std::sync::Arc;
 use tracing::{debug, info, warn};
 
 #[cfg(feature = "bootstrap_server")]
 use massa_models::config::PERIODS_BETWEEN_BACKUPS;
 use massa_models::timeslots::get_block_slot_timestamp;
 
+/// prefix of the names of the directories of the archived final states
+pub const ARCHIVE_DIR_PREFIX: &str = "archive_";
+
 /// Represents a final state `(ledger, async pool, executed_ops, executed_de and the state of the PoS)`
 pub struct FinalState {
     /// execution state configuration
@@ -523,6 +535,14 @@ impl FinalState {
             self.db.read().backup_db(slot);
         }
 
+        // Archive the final state at the end of every `archive_interval` periods
+        if self.config.archive_interval != 0
+            && slot.period % self.config.archive_interval == 0
+            && slot.thread == self.config.thread_count.saturating_sub(1)
+        {
+            self.archive(slot);
+        }
+
         // feed final_state_hash to the last cycle
         let cycle = slot.get_cycle(self.config.periods_per_cycle);
         self.pos_state
@@ -531,6 +551,29 @@ impl FinalState {
         Ok(())
     }
 
+    /// Keep a copy of the final state at the output of `slot` in the archive directory
+    fn archive(&self, slot: Slot) {
+        let path = self.get_archived_state_path(slot);
+        let res = std::fs::create_dir_all(&self.config.archive_path)
+            .map_err(|err| err.to_string())
+            .and_then(|_| {
+                self.db
+                    .read()
+                    .checkpoint_db(&path)
+                    .map_err(|err| err.to_string())
+            });
+        match res {
+            Ok(()) => info!("final state archived in {}", path.display()),
+            Err(err) => warn!("final state archiving at slot {} failed: {}", slot, err),
+        }
+    }
+
+    /// Path of the copy of the final state archived at the output of `slot`
+    fn get_archived_state_path(&self, slot: Slot) -> PathBuf {
+        let dir_name = format!("{}{}_{}", ARCHIVE_DIR_PREFIX, slot.period, slot.thread);
+        self.config.archive_path.join(dir_name)
+    }
+
     /// Internal function called by is_db_valid
     pub fn _is_db_valid(&self) -> AnyResult<()> {
         let db = self.db.read();
@@ -917,12 +960,63 @@ impl FinalStateController for FinalState {
     fn get_mip_store(&self) -> &MipStore {
         &self.mip_store
     }
+
+    fn get_archived_slots(&self) -> Vec<Slot> {
+        let Ok(dir) = std::fs::read_dir(&self.config.archive_path) else {
+            return Vec::new();
+        };
+        let mut slots: Vec<Slot> = dir
+            .filter_map(|entry| {
+                // skip the other files, and the archives still being written
+                let name = entry.ok()?.file_name().into_string().ok()?;
+                let (period, thread) = name.strip_prefix(ARCHIVE_DIR_PREFIX)?.split_once('_')?;
+                Some(Slot::new(period.parse().ok()?, thread.parse().ok()?))
+            })
+            .collect();
+        slots.sort();
+        slots
+    }
+
+    fn get_archived_ledger(
+        &self,
+        slot: Slot,
+    ) -> Result<Box<dyn LedgerController>, FinalStateError> {
+        let path = self.get_archived_state_path(slot);
+        if !path.is_dir() {
+            return Err(FinalStateError::ArchiveError(format!(
+                "no final state archived at slot {}",
+                slot
+            )));
+        }
+        let db = MassaDB::new_read_only(MassaDBConfig {
+            path,
+            max_history_length: self.config.final_history_length,
+            max_final_state_elements_size: MAX_BOOTSTRAP_FINAL_STATE_PARTS_SIZE as usize,
+            max_versioning_elements_size: MAX_BOOTSTRAP_VERSIONING_ELEMENTS_SIZE as usize,
+            thread_count: self.config.thread_count,
+        })
+        .map_err(|err| FinalStateError::ArchiveError(err.to_string()))?;
+        let db = Arc::new(RwLock::new(
+            Box::new(db) as Box<(dyn MassaDBController + 'static)>
+        ));
+        let ledger = FinalLedger::new(self.config.ledger_config.clone(), db);
+        Ok(Box::new(ledger))
+    }
+
+    fn get_datastore_entry_at_slot(
+        &self,
+        slot: Slot,
+        addr: &Address,
+        key: &[u8],
+    ) -> Result<Option<Vec<u8>>, FinalStateError> {
+        Ok(self.get_archived_ledger(slot)?.get_data_entry(addr, key))
+    }
 }
 
 #[cfg(test)]
 mod test {
     use std::collections::BTreeMap;
-    use std::path::PathBuf;
+    use std::path::{Path, PathBuf};
     use std::str::FromStr;
     use std::sync::Arc;
 
@@ -935,7 +1029,9 @@ mod test {
     use massa_db_worker::MassaDB;
     use massa_executed_ops::{ExecutedDenunciationsConfig, ExecutedOpsConfig};
     use massa_hash::Hash;
-    use massa_ledger_exports::{LedgerChanges, LedgerConfig, LedgerEntryUpdate, SetUpdateOrDelete};
+    use massa_ledger_exports::{
+        LedgerChanges, LedgerConfig, LedgerEntry, LedgerEntryUpdate, SetOrDelete, SetUpdateOrDelete,
+    };
     use massa_ledger_worker::FinalLedger;
     use massa_models::address::Address;
     use massa_models::amount::Amount;
@@ -1011,6 +1107,8 @@ mod test {
             max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
             t0: T0,
             genesis_timestamp,
+            archive_interval: 0,
+            archive_path: PathBuf::new(),
         };
 
         (final_state_config, ledger_config)
@@ -1022,8 +1120,16 @@ mod test {
         let temp_dir_db = tempdir().expect("Unable to create a temp folder");
         // println!("Using temp dir: {:?}", temp_dir.path());
 
+        create_final_state(temp_dir_db.path(), final_state_config, ledger_config)
+    }
+
+    fn create_final_state(
+        db_path: &Path,
+        final_state_config: FinalStateConfig,
+        ledger_config: LedgerConfig,
+    ) -> FinalState {
         let db_config = MassaDBConfig {
-            path: temp_dir_db.path().to_path_buf(),
+            path: db_path.to_path_buf(),
             max_history_length: 100,
             max_final_state_elements_size: 100,
             max_versioning_elements_size: 100,
@@ -1148,6 +1254,76 @@ mod test {
         assert_eq!(fstate.get_slot(), ok_next_slot);
     }
 
+    #[test]
+    fn test_final_state_archive() {
+        // 0- Create a final state archived every period
+        // 1- Finalize the slots of period 0, the final state is archived at the last one
+        // 2- Update the datastore in period 1, the archived final state is unchanged
+
+        let temp_dir = tempdir().expect("Unable to create a temp folder");
+        let (mut final_state_config, ledger_config) = get_final_state_config();
+        final_state_config.archive_interval = 1;
+        final_state_config.archive_path = temp_dir.path().join("archive");
+        let mut fstate = create_final_state(
+            &temp_dir.path().join("db"),
+            final_state_config,
+            ledger_config,
+        );
+        let mut batch = DBBatch::new();
+        fstate.pos_state.create_initial_cycle(&mut batch);
+        assert!(fstate.get_archived_slots().is_empty());
+
+        let addr =
+            Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x").unwrap();
+        let mut changes = StateChanges::default();
+        changes.ledger_changes.0.insert(
+            addr,
+            SetUpdateOrDelete::Set(LedgerEntry {
+                balance: Amount::from_str("1").unwrap(),
+                datastore: BTreeMap::from([(b"key".to_vec(), b"value_0".to_vec())]),
+                ..Default::default()
+            }),
+        );
+        let last_slot = Slot::new(0, THREAD_COUNT - 1);
+        let mut slot = Slot::new(0, 1);
+        fstate._finalize(slot, changes).unwrap();
+        while slot < last_slot {
+            slot = slot.get_next_slot(THREAD_COUNT).unwrap();
+            fstate._finalize(slot, StateChanges::default()).unwrap();
+        }
+        assert_eq!(fstate.get_archived_slots(), vec![last_slot]);
+
+        let mut changes = StateChanges::default();
+        changes.ledger_changes.0.insert(
+            addr,
+            SetUpdateOrDelete::Update(LedgerEntryUpdate {
+                datastore: BTreeMap::from([(
+                    b"key".to_vec(),
+                    SetOrDelete::Set(b"value_1".to_vec()),
+                )]),
+                ..Default::default()
+            }),
+        );
+        fstate._finalize(Slot::new(1, 0), changes).unwrap();
+
+        assert_eq!(
+            fstate.get_ledger().get_data_entry(&addr, b"key"),
+            Some(b"value_1".to_vec())
+        );
+        assert_eq!(
+            fstate
+                .get_datastore_entry_at_slot(last_slot, &addr, b"key")
+                .unwrap(),
+            Some(b"value_0".to_vec())
+        );
+        let archived_ledger = fstate.get_archived_ledger(last_slot).unwrap();
+        assert_eq!(
+            archived_ledger.get_balance(&addr),
+            Amount::from_str("1").ok()
+        );
+        assert!(fstate.get_archived_ledger(Slot::new(1, 0)).is_err());
+    }
+
     #[test]
     fn test_final_state_from_snapshot_1() {
         // 0- Create a final state
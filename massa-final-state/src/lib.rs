@@ -97,7 +97,7 @@ mod state_changes;
 pub use config::FinalStateConfig;
 pub use controller_trait::FinalStateController;
 pub use error::FinalStateError;
-pub use final_state::FinalState;
+pub use final_state::{FinalState, ARCHIVE_DIR_PREFIX};
 use num as _;
 pub use state_changes::{StateChanges, StateChangesDeserializer, StateChangesSerializer};
 
@@ -217,6 +217,8 @@ impl StateChanges {
         self.pos_changes.extend(changes.pos_changes);
         self.executed_ops_changes
             .extend(changes.executed_ops_changes);
+        self.executed_denunciations_changes
+            .extend(changes.executed_denunciations_changes);
         self.execution_trail_hash_change
             .apply(changes.execution_trail_hash_change);
     }
@@ -232,6 +234,7 @@ mod test {
     use massa_models::address::Address;
     use massa_models::amount::Amount;
     use massa_models::bytecode::Bytecode;
+    use massa_models::denunciation::DenunciationIndex;
     use massa_models::slot::Slot;
     use massa_serialization::DeserializeError;
 
@@ -370,4 +373,45 @@ mod test {
         let state_changes_deser = serde_json::from_str(&serialized).unwrap();
         assert_eq!(state_changes, state_changes_deser);
     }
+
+    #[test]
+    fn test_state_changes_apply() {
+        let address =
+            Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x").unwrap();
+        let slot_changes = |balance: &str, denunciation_slot: Slot| {
+            let mut changes = StateChanges::default();
+            changes.ledger_changes.0.insert(
+                address,
+                SetUpdateOrDelete::Update(LedgerEntryUpdate {
+                    balance: SetOrKeep::Set(Amount::from_str(balance).unwrap()),
+                    bytecode: SetOrKeep::Keep,
+                    datastore: BTreeMap::default(),
+                }),
+            );
+            changes
+                .executed_denunciations_changes
+                .insert(DenunciationIndex::BlockHeader {
+                    slot: denunciation_slot,
+                });
+            changes
+        };
+
+        let mut state_changes = slot_changes("1", Slot::new(1, 0));
+        state_changes.apply(slot_changes("2", Slot::new(2, 0)));
+
+        // the latest changes override the previous ones, and the executed denunciations add up
+        assert_eq!(
+            state_changes
+                .ledger_changes
+                .get_balance_or_else(&address, || None),
+            Some(Amount::from_str("2").unwrap())
+        );
+        assert_eq!(
+            state_changes.executed_denunciations_changes,
+            [Slot::new(1, 0), Slot::new(2, 0)]
+                .into_iter()
+                .map(|slot| DenunciationIndex::BlockHeader { slot })
+                .collect()
+        );
+    }
 }
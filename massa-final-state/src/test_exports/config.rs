@@ -91,6 +91,8 @@ impl Default for FinalStateConfig {
             max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
             t0: T0,
             genesis_timestamp: *GENESIS_TIMESTAMP,
+            archive_interval: 0,
+            archive_path: PathBuf::new(),
         }
     }
 }
@@ -201,6 +201,8 @@ pub fn get_sample_state(
         max_denunciations_per_block_header: 0,
         t0: T0,
         genesis_timestamp: *GENESIS_TIMESTAMP,
+        archive_interval: default_config.archive_interval,
+        archive_path: default_config.archive_path,
     };
 
     let mut final_state = if last_start_period > 0 {
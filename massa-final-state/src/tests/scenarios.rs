@@ -92,6 +92,8 @@ fn create_final_state(temp_dir: &TempDir, reset_final_state: bool) -> Arc<RwLock
         max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
         t0: T0,
         genesis_timestamp: *GENESIS_TIMESTAMP,
+        archive_interval: 0,
+        archive_path: PathBuf::new(),
     };
 
     // setup selector local config
@@ -4,7 +4,7 @@ use massa_models::amount::Amount;
 use massa_signature::KeyPair;
 use massa_time::MassaTime;
 use serde::Deserialize;
-use std::{net::SocketAddr, path::PathBuf, time::Duration};
+use std::{collections::HashMap, net::SocketAddr, path::PathBuf, time::Duration};
 
 /// gRPC configuration.
 /// the gRPC configuration
@@ -32,6 +32,10 @@ pub struct GrpcConfig {
     pub subject_alt_names: Vec<String>,
     /// bind for the Massa gRPC API
     pub bind: SocketAddr,
+    /// path of an additional Unix socket listener for the Massa gRPC API (Unix only)
+    pub uds_path: Option<PathBuf>,
+    /// file permissions of the Unix socket, used to restrict the local users allowed to connect
+    pub uds_permissions: u32,
     /// which compression encodings does the server accept for requests
     pub accept_compressed: Option<String>,
     /// which compression encodings might the server use for responses
@@ -40,10 +44,16 @@ pub struct GrpcConfig {
     pub max_decoding_message_size: usize,
     /// limits the maximum size of an encoded message. Defaults to 4MB
     pub max_encoding_message_size: usize,
+    /// maximum size of a decoded message for specific methods, by method name (overrides `max_decoding_message_size`)
+    pub max_decoding_message_size_per_method: HashMap<String, usize>,
+    /// maximum size of an encoded message for specific methods, by method name (overrides `max_encoding_message_size`)
+    pub max_encoding_message_size_per_method: HashMap<String, usize>,
     /// set the concurrency limit applied to on requests inbound per connection. Defaults to 32
     pub concurrency_limit_per_connection: usize,
     /// set a timeout on for all request handlers
     pub timeout: Duration,
+    /// max duration of the handlers of specific methods, by method name (ex: `ExecuteReadOnlyCall`)
+    pub method_timeouts: HashMap<String, Duration>,
     /// sets the SETTINGS_INITIAL_WINDOW_SIZE spec option for HTTP2 stream-level flow control. Default is 65,535
     pub initial_stream_window_size: Option<u32>,
     /// sets the max connection-level flow control for HTTP2. Default is 65,535
@@ -52,6 +62,10 @@ pub struct GrpcConfig {
     pub max_concurrent_streams: Option<u32>,
     /// max number of arguments per gRPC request
     pub max_arguments: u64,
+    /// max number of streaming requests simultaneously opened by a client IP (0 means unlimited)
+    pub max_streams_per_ip: usize,
+    /// number of events of each kind kept to be replayed to the streams resuming from a cursor
+    pub event_replay_buffer_size: usize,
     /// set whether TCP keepalive messages are enabled on accepted connections
     pub tcp_keepalive: Option<Duration>,
     /// set the value of `TCP_NODELAY` option for accepted connections. Enabled by default
@@ -120,6 +134,8 @@ pub struct GrpcConfig {
     pub max_filters_per_request: u32,
     /// max number of query items that can be included in a single request
     pub max_query_items_per_request: u32,
+    /// max number of ledger entries that can be returned by a single request
+    pub max_ledger_entries_per_request: u32,
     /// certificate authority root path
     pub certificate_authority_root_path: PathBuf,
     /// server certificate path
@@ -0,0 +1,143 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use futures_util::future::BoxFuture;
+use hyper::http::HeaderMap;
+use hyper::{Request, Response};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::task::AbortHandle;
+use tonic::body::BoxBody;
+use tonic::Status;
+use tower::{Layer, Service};
+
+/// Layer enforcing the deadline of the gRPC calls.
+///
+/// The deadline of a call is the shortest of the `grpc-timeout` sent by the client and
+/// of the max duration configured for the called method. Handlers are run in their own task
+/// so that a blocking handler can not prevent the deadline from being enforced, and
+/// DEADLINE_EXCEEDED is returned once it is reached. The task of the handler is then aborted: it
+/// stops at its next await point.
+#[derive(Clone)]
+pub struct GrpcDeadlineLayer {
+    method_timeouts: Arc<HashMap<String, Duration>>,
+}
+
+impl GrpcDeadlineLayer {
+    /// Creates a new layer with the given max duration per method name
+    pub fn new(method_timeouts: HashMap<String, Duration>) -> Self {
+        GrpcDeadlineLayer {
+            method_timeouts: Arc::new(method_timeouts),
+        }
+    }
+}
+
+impl<S> Layer<S> for GrpcDeadlineLayer {
+    type Service = GrpcDeadlineService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        GrpcDeadlineService {
+            inner,
+            method_timeouts: self.method_timeouts.clone(),
+        }
+    }
+}
+
+/// Service wrapping gRPC calls to enforce their deadline
+#[derive(Clone)]
+pub struct GrpcDeadlineService<S> {
+    inner: S,
+    method_timeouts: Arc<HashMap<String, Duration>>,
+}
+
+impl<S, ReqBody> Service<Request<ReqBody>> for GrpcDeadlineService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    S::Error: Send + 'static,
+    ReqBody: Send + 'static,
+{
+    type Response = Response<BoxBody>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<ReqBody>) -> Self::Future {
+        // take the service that was driven to readiness and leave a clone in its place
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        // gRPC paths are in the form "/massa.api.v1.PublicService/GetBlocks"
+        let method = request
+            .uri()
+            .path()
+            .rsplit('/')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        let timeout = [
+            parse_grpc_timeout(request.headers()),
+            self.method_timeouts.get(&method).copied(),
+        ]
+        .into_iter()
+        .flatten()
+        .min();
+
+        let Some(timeout) = timeout else {
+            return Box::pin(inner.call(request));
+        };
+
+        Box::pin(async move {
+            let mut handle = tokio::spawn(inner.call(request));
+            // abort the handler if the deadline is exceeded or if the call is dropped
+            let _abort_handler = AbortOnDrop(handle.abort_handle());
+            match tokio::time::timeout(timeout, &mut handle).await {
+                Ok(Ok(response)) => response,
+                Ok(Err(err)) => {
+                    Ok(Status::internal(format!("{} handler failed: {}", method, err)).to_http())
+                }
+                Err(_) => Ok(Status::deadline_exceeded(format!(
+                    "{} did not complete within {:?}",
+                    method, timeout
+                ))
+                .to_http()),
+            }
+        })
+    }
+}
+
+/// Aborts a task when dropped
+struct AbortOnDrop(AbortHandle);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Parses the `grpc-timeout` header of a request.
+/// Its value is a positive integer of at most 8 digits followed by a time unit.
+pub(crate) fn parse_grpc_timeout(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get("grpc-timeout")?.to_str().ok()?;
+    if value.len() < 2 {
+        return None;
+    }
+    let (amount, unit) = value.split_at(value.len() - 1);
+    if amount.len() > 8 {
+        return None;
+    }
+    let amount: u64 = amount.parse().ok()?;
+    match unit {
+        "H" => Some(Duration::from_secs(amount * 60 * 60)),
+        "M" => Some(Duration::from_secs(amount * 60)),
+        "S" => Some(Duration::from_secs(amount)),
+        "m" => Some(Duration::from_millis(amount)),
+        "u" => Some(Duration::from_micros(amount)),
+        "n" => Some(Duration::from_nanos(amount)),
+        _ => None,
+    }
+}
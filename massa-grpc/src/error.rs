@@ -1,6 +1,8 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
 
+use std::collections::HashMap;
 use std::error::Error;
+use std::time::Duration;
 
 use displaydoc::Display;
 
@@ -13,6 +15,8 @@ use massa_signature::MassaSignatureError;
 use massa_time::TimeError;
 use massa_versioning::versioning_factory::FactoryError;
 use massa_wallet::WalletError;
+use tonic::Code;
+use tonic_types::{ErrorDetails, StatusExt};
 use tracing::error;
 
 /// Errors of the gRPC component.
@@ -43,28 +47,75 @@ pub enum GrpcError {
     InternalServerError(String),
     /// Invalid argument error: {0}
     InvalidArgument(String),
+    /// {1}
+    InvalidField(String, String),
     /// Not implemented error: {0}
     Unimplemented(String),
+    /// Resource exhausted error: {0}
+    ResourceExhausted(String),
 }
 
+/// Domain of the `ErrorInfo` details attached to the gRPC errors
+const ERROR_DOMAIN: &str = "massa.net";
+
+/// Delay after which a client may retry a call rejected for lack of resources
+const RESOURCE_EXHAUSTED_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+impl GrpcError {
+    /// Machine readable reason of the error, sent in the `ErrorInfo` details
+    pub fn reason(&self) -> &'static str {
+        match self {
+            GrpcError::MassaHashError(_) => "HASH_ERROR",
+            GrpcError::MassaSignatureError(_) => "SIGNATURE_ERROR",
+            GrpcError::ConsensusError(_) => "CONSENSUS_ERROR",
+            GrpcError::ExecutionError(_) => "EXECUTION_ERROR",
+            GrpcError::ProtocolError(_) => "PROTOCOL_ERROR",
+            GrpcError::ReflectionError(_) => "REFLECTION_ERROR",
+            GrpcError::ModelsError(_) => "MODELS_ERROR",
+            GrpcError::TimeError(_) => "TIME_ERROR",
+            GrpcError::FactoryError(_) => "VERSIONING_ERROR",
+            GrpcError::WalletError(_) => "WALLET_ERROR",
+            GrpcError::InternalServerError(_) => "INTERNAL_ERROR",
+            GrpcError::InvalidArgument(_) => "INVALID_ARGUMENT",
+            GrpcError::InvalidField(_, _) => "INVALID_FIELD",
+            GrpcError::Unimplemented(_) => "UNIMPLEMENTED",
+            GrpcError::ResourceExhausted(_) => "RESOURCE_EXHAUSTED",
+        }
+    }
+}
+
+/// Converts the error to a gRPC status carrying `google.rpc` error details:
+/// an `ErrorInfo` with the reason of the error, a `BadRequest` listing the invalid field
+/// of the request when known, and a `RetryInfo` when the call can be retried later
 impl From<GrpcError> for tonic::Status {
     fn from(error: GrpcError) -> Self {
         error!("{}", error);
-        match error {
-            GrpcError::MassaHashError(e) => tonic::Status::internal(e.to_string()),
-            GrpcError::MassaSignatureError(e) => tonic::Status::internal(e.to_string()),
-            GrpcError::ConsensusError(e) => tonic::Status::internal(e.to_string()),
-            GrpcError::ExecutionError(e) => tonic::Status::internal(e.to_string()),
-            GrpcError::ProtocolError(e) => tonic::Status::internal(e.to_string()),
-            GrpcError::ModelsError(e) => tonic::Status::internal(e.to_string()),
-            GrpcError::TimeError(e) => tonic::Status::internal(e.to_string()),
-            GrpcError::FactoryError(e) => tonic::Status::internal(e.to_string()),
-            GrpcError::WalletError(e) => tonic::Status::internal(e.to_string()),
-            GrpcError::InternalServerError(e) => tonic::Status::internal(e),
-            GrpcError::ReflectionError(e) => tonic::Status::internal(e.to_string()),
-            GrpcError::InvalidArgument(e) => tonic::Status::invalid_argument(e),
-            GrpcError::Unimplemented(e) => tonic::Status::unimplemented(e),
-        }
+        let mut details = ErrorDetails::new();
+        details.set_error_info(error.reason(), ERROR_DOMAIN, HashMap::new());
+        let (code, message) = match error {
+            GrpcError::MassaHashError(e) => (Code::Internal, e.to_string()),
+            GrpcError::MassaSignatureError(e) => (Code::Internal, e.to_string()),
+            GrpcError::ConsensusError(e) => (Code::Internal, e.to_string()),
+            GrpcError::ExecutionError(e) => (Code::Internal, e.to_string()),
+            GrpcError::ProtocolError(e) => (Code::Internal, e.to_string()),
+            GrpcError::ModelsError(e) => (Code::Internal, e.to_string()),
+            GrpcError::TimeError(e) => (Code::Internal, e.to_string()),
+            GrpcError::FactoryError(e) => (Code::Internal, e.to_string()),
+            GrpcError::WalletError(e) => (Code::Internal, e.to_string()),
+            GrpcError::InternalServerError(e) => (Code::Internal, e),
+            GrpcError::ReflectionError(e) => (Code::Internal, e.to_string()),
+            GrpcError::InvalidArgument(e) => (Code::InvalidArgument, e),
+            GrpcError::InvalidField(field, e) => {
+                details.add_bad_request_violation(field, e.clone());
+                (Code::InvalidArgument, e)
+            }
+            GrpcError::Unimplemented(e) => (Code::Unimplemented, e),
+            GrpcError::ResourceExhausted(e) => {
+                details.set_retry_info(Some(RESOURCE_EXHAUSTED_RETRY_DELAY));
+                (Code::ResourceExhausted, e)
+            }
+        };
+        tonic::Status::with_error_details(code, message, details)
     }
 }
 
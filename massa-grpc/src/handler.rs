@@ -3,20 +3,27 @@
 use massa_proto_rs::massa::api::v1 as grpc_api;
 
 use crate::private::{
-    add_staking_secret_keys, add_to_bootstrap_blacklist, add_to_bootstrap_whitelist,
-    add_to_peers_whitelist, allow_everyone_to_bootstrap, ban_nodes_by_ids, ban_nodes_by_ips,
-    get_bootstrap_blacklist, get_bootstrap_whitelist, get_mip_status, get_node_status,
-    get_peers_whitelist, remove_from_bootstrap_blacklist, remove_from_bootstrap_whitelist,
-    remove_from_peers_whitelist, remove_staking_addresses, shutdown_gracefully, sign_messages,
-    unban_nodes_by_ids, unban_nodes_by_ips,
+    add_staking_keys, add_staking_secret_keys, add_to_bootstrap_blacklist,
+    add_to_bootstrap_whitelist, add_to_peers_whitelist, allow_everyone_to_bootstrap,
+    ban_nodes_by_ids, ban_nodes_by_ips, get_bootstrap_blacklist, get_bootstrap_whitelist,
+    get_mip_status, get_node_status, get_peers_whitelist, remove_from_bootstrap_blacklist,
+    remove_from_bootstrap_whitelist, remove_from_peers_whitelist, remove_staking_addresses,
+    remove_staking_keys, shutdown_gracefully, sign_messages, swap_staking_key, unban_nodes_by_ids,
+    unban_nodes_by_ips,
 };
 use crate::public::{
-    execute_read_only_call, get_blocks, get_datastore_entries, get_endorsements,
-    get_next_block_best_parents, get_operations, get_sc_execution_events, get_selector_draws,
-    get_stakers, get_status, get_transactions_throughput, query_state, search_blocks,
-    search_endorsements, search_operations,
+    execute_read_only_call, get_blocks, get_blocks_by_slot_range, get_datastore_entries,
+    get_endorsements, get_next_block_best_parents, get_operations, get_sc_execution_events,
+    get_selector_draws, get_slot_execution_stats, get_stakers, get_status, get_sync_status,
+    get_transactions_throughput, iter_ledger, query_state, search_blocks, search_endorsements,
+    search_operations,
 };
 
+#[cfg(feature = "execution-trace")]
+use crate::private::{
+    get_debug_datastore_entry, resume_debug_session, set_debug_breakpoints, start_debug_session,
+    stop_debug_session,
+};
 #[cfg(feature = "execution-trace")]
 use crate::public::{get_operation_abi_call_stacks, get_slot_abi_call_stacks, get_slot_transfers};
 #[cfg(feature = "execution-trace")]
@@ -24,16 +31,19 @@ use crate::stream::new_slot_transfers::new_slot_transfers;
 
 use crate::server::{MassaPrivateGrpc, MassaPublicGrpc};
 use crate::stream::{
+    limiter::with_permit,
     new_blocks::{new_blocks, NewBlocksStreamType},
     new_endorsements::{new_endorsements, NewEndorsementsStreamType},
     new_filled_blocks::{new_filled_blocks, NewFilledBlocksStreamType},
     new_operations::{new_operations, NewOperationsStreamType},
+    new_reorgs::{new_reorgs, NewReorgsStreamType},
     new_slot_abi_call_stacks::{new_slot_abi_call_stacks, NewSlotABICallStacksStreamType},
     new_slot_execution_outputs::{new_slot_execution_outputs, NewSlotExecutionOutputsStreamType},
     new_slot_transfers::NewSlotTransfersStreamType,
     send_blocks::SendBlocksStreamType,
     send_endorsements::{send_endorsements, SendEndorsementsStreamType},
     send_operations::{send_operations, SendOperationsStreamType},
+    slot_ticks::{slot_ticks, SlotTicksStreamType},
     tx_throughput::{transactions_throughput, TransactionsThroughputStreamType},
 };
 
@@ -118,6 +128,16 @@ impl grpc_api::public_service_server::PublicService for MassaPublicGrpc {
         Ok(tonic::Response::new(get_blocks(self, request)?))
     }
 
+    /// handler for get blocks by slot range
+    async fn get_blocks_by_slot_range(
+        &self,
+        request: tonic::Request<grpc_api::GetBlocksBySlotRangeRequest>,
+    ) -> Result<tonic::Response<grpc_api::GetBlocksBySlotRangeResponse>, tonic::Status> {
+        Ok(tonic::Response::new(get_blocks_by_slot_range(
+            self, request,
+        )?))
+    }
+
     /// handler for get multiple datastore entries
     async fn get_datastore_entries(
         &self,
@@ -150,6 +170,14 @@ impl grpc_api::public_service_server::PublicService for MassaPublicGrpc {
         Ok(tonic::Response::new(get_status(self, request)?))
     }
 
+    /// handler for get sync status
+    async fn get_sync_status(
+        &self,
+        request: tonic::Request<grpc_api::GetSyncStatusRequest>,
+    ) -> Result<tonic::Response<grpc_api::GetSyncStatusResponse>, tonic::Status> {
+        Ok(tonic::Response::new(get_sync_status(self, request)?))
+    }
+
     /// handler for get next block best parents
     async fn get_next_block_best_parents(
         &self,
@@ -186,6 +214,16 @@ impl grpc_api::public_service_server::PublicService for MassaPublicGrpc {
         Ok(tonic::Response::new(get_selector_draws(self, request)?))
     }
 
+    /// handler for get slot execution stats
+    async fn get_slot_execution_stats(
+        &self,
+        request: tonic::Request<grpc_api::GetSlotExecutionStatsRequest>,
+    ) -> Result<tonic::Response<grpc_api::GetSlotExecutionStatsResponse>, tonic::Status> {
+        Ok(tonic::Response::new(get_slot_execution_stats(
+            self, request,
+        )?))
+    }
+
     /// handler for get transactions throughput
     async fn get_transactions_throughput(
         &self,
@@ -196,6 +234,14 @@ impl grpc_api::public_service_server::PublicService for MassaPublicGrpc {
         )?))
     }
 
+    /// handler for iter ledger
+    async fn iter_ledger(
+        &self,
+        request: tonic::Request<grpc_api::IterLedgerRequest>,
+    ) -> Result<tonic::Response<grpc_api::IterLedgerResponse>, tonic::Status> {
+        Ok(tonic::Response::new(iter_ledger(self, request)?))
+    }
+
     /// handler for get version
     async fn query_state(
         &self,
@@ -241,7 +287,11 @@ impl grpc_api::public_service_server::PublicService for MassaPublicGrpc {
         &self,
         request: tonic::Request<tonic::Streaming<grpc_api::NewBlocksRequest>>,
     ) -> Result<tonic::Response<Self::NewBlocksStream>, tonic::Status> {
-        Ok(tonic::Response::new(new_blocks(self, request).await?))
+        let permit = self.stream_limiter.acquire(request.remote_addr())?;
+        Ok(tonic::Response::new(with_permit(
+            new_blocks(self, request).await?,
+            permit,
+        )))
     }
 
     type NewEndorsementsStream = NewEndorsementsStreamType;
@@ -251,7 +301,11 @@ impl grpc_api::public_service_server::PublicService for MassaPublicGrpc {
         &self,
         request: tonic::Request<tonic::Streaming<grpc_api::NewEndorsementsRequest>>,
     ) -> Result<tonic::Response<Self::NewEndorsementsStream>, tonic::Status> {
-        Ok(tonic::Response::new(new_endorsements(self, request).await?))
+        let permit = self.stream_limiter.acquire(request.remote_addr())?;
+        Ok(tonic::Response::new(with_permit(
+            new_endorsements(self, request).await?,
+            permit,
+        )))
     }
 
     type NewFilledBlocksStream = NewFilledBlocksStreamType;
@@ -261,9 +315,11 @@ impl grpc_api::public_service_server::PublicService for MassaPublicGrpc {
         &self,
         request: tonic::Request<tonic::Streaming<grpc_api::NewFilledBlocksRequest>>,
     ) -> Result<tonic::Response<Self::NewFilledBlocksStream>, tonic::Status> {
-        Ok(tonic::Response::new(
+        let permit = self.stream_limiter.acquire(request.remote_addr())?;
+        Ok(tonic::Response::new(with_permit(
             new_filled_blocks(self, request).await?,
-        ))
+            permit,
+        )))
     }
 
     type NewOperationsStream = NewOperationsStreamType;
@@ -273,7 +329,25 @@ impl grpc_api::public_service_server::PublicService for MassaPublicGrpc {
         &self,
         request: tonic::Request<tonic::Streaming<grpc_api::NewOperationsRequest>>,
     ) -> Result<tonic::Response<Self::NewOperationsStream>, tonic::Status> {
-        Ok(tonic::Response::new(new_operations(self, request).await?))
+        let permit = self.stream_limiter.acquire(request.remote_addr())?;
+        Ok(tonic::Response::new(with_permit(
+            new_operations(self, request).await?,
+            permit,
+        )))
+    }
+
+    type NewReorgsStream = NewReorgsStreamType;
+
+    /// handler for subscribe blockclique reorganizations
+    async fn new_reorgs(
+        &self,
+        request: tonic::Request<grpc_api::NewReorgsRequest>,
+    ) -> Result<tonic::Response<Self::NewReorgsStream>, tonic::Status> {
+        let permit = self.stream_limiter.acquire(request.remote_addr())?;
+        Ok(tonic::Response::new(with_permit(
+            new_reorgs(self, request).await?,
+            permit,
+        )))
     }
 
     type NewSlotExecutionOutputsStream = NewSlotExecutionOutputsStreamType;
@@ -283,9 +357,11 @@ impl grpc_api::public_service_server::PublicService for MassaPublicGrpc {
         &self,
         request: tonic::Request<tonic::Streaming<grpc_api::NewSlotExecutionOutputsRequest>>,
     ) -> Result<tonic::Response<Self::NewSlotExecutionOutputsStream>, tonic::Status> {
-        Ok(tonic::Response::new(
+        let permit = self.stream_limiter.acquire(request.remote_addr())?;
+        Ok(tonic::Response::new(with_permit(
             new_slot_execution_outputs(self, request).await?,
-        ))
+            permit,
+        )))
     }
 
     type NewSlotTransfersStream = NewSlotTransfersStreamType;
@@ -296,9 +372,11 @@ impl grpc_api::public_service_server::PublicService for MassaPublicGrpc {
         &self,
         request: tonic::Request<tonic::Streaming<grpc_api::NewSlotTransfersRequest>>,
     ) -> Result<tonic::Response<Self::NewSlotTransfersStream>, tonic::Status> {
-        Ok(tonic::Response::new(
+        let permit = self.stream_limiter.acquire(request.remote_addr())?;
+        Ok(tonic::Response::new(with_permit(
             new_slot_transfers(self, request).await?,
-        ))
+            permit,
+        )))
     }
 
     #[cfg(not(feature = "execution-trace"))]
@@ -318,9 +396,11 @@ impl grpc_api::public_service_server::PublicService for MassaPublicGrpc {
         request: tonic::Request<tonic::Streaming<grpc_api::NewSlotAbiCallStacksRequest>>,
     ) -> Result<tonic::Response<Self::NewSlotABICallStacksStream>, tonic::Status> {
         if cfg!(feature = "execution-trace") {
-            Ok(tonic::Response::new(
+            let permit = self.stream_limiter.acquire(request.remote_addr())?;
+            Ok(tonic::Response::new(with_permit(
                 new_slot_abi_call_stacks(self, request).await?,
-            ))
+                permit,
+            )))
         } else {
             Err(tonic::Status::unimplemented("feature not enabled"))
         }
@@ -344,9 +424,11 @@ impl grpc_api::public_service_server::PublicService for MassaPublicGrpc {
         &self,
         request: tonic::Request<tonic::Streaming<grpc_api::SendEndorsementsRequest>>,
     ) -> Result<tonic::Response<Self::SendEndorsementsStream>, tonic::Status> {
-        Ok(tonic::Response::new(
+        let permit = self.stream_limiter.acquire(request.remote_addr())?;
+        Ok(tonic::Response::new(with_permit(
             send_endorsements(self, request).await?,
-        ))
+            permit,
+        )))
     }
 
     type SendOperationsStream = SendOperationsStreamType;
@@ -356,7 +438,25 @@ impl grpc_api::public_service_server::PublicService for MassaPublicGrpc {
         &self,
         request: tonic::Request<tonic::Streaming<grpc_api::SendOperationsRequest>>,
     ) -> Result<tonic::Response<Self::SendOperationsStream>, tonic::Status> {
-        Ok(tonic::Response::new(send_operations(self, request).await?))
+        let permit = self.stream_limiter.acquire(request.remote_addr())?;
+        Ok(tonic::Response::new(with_permit(
+            send_operations(self, request).await?,
+            permit,
+        )))
+    }
+
+    type SlotTicksStream = SlotTicksStreamType;
+
+    /// handler for slot ticks
+    async fn slot_ticks(
+        &self,
+        request: tonic::Request<grpc_api::SlotTicksRequest>,
+    ) -> Result<tonic::Response<Self::SlotTicksStream>, tonic::Status> {
+        let permit = self.stream_limiter.acquire(request.remote_addr())?;
+        Ok(tonic::Response::new(with_permit(
+            slot_ticks(self, request).await?,
+            permit,
+        )))
     }
 
     type TransactionsThroughputStream = TransactionsThroughputStreamType;
@@ -366,9 +466,11 @@ impl grpc_api::public_service_server::PublicService for MassaPublicGrpc {
         &self,
         request: tonic::Request<tonic::Streaming<grpc_api::TransactionsThroughputRequest>>,
     ) -> Result<tonic::Response<Self::TransactionsThroughputStream>, tonic::Status> {
-        Ok(tonic::Response::new(
+        let permit = self.stream_limiter.acquire(request.remote_addr())?;
+        Ok(tonic::Response::new(with_permit(
             transactions_throughput(self, request).await?,
-        ))
+            permit,
+        )))
     }
 }
 
@@ -409,6 +511,13 @@ impl grpc_api::private_service_server::PrivateService for MassaPrivateGrpc {
             self, request,
         )?))
     }
+    /// Add staking keys to wallet at the beginning of the next cycle
+    async fn add_staking_keys(
+        &self,
+        request: tonic::Request<grpc_api::AddStakingKeysRequest>,
+    ) -> Result<tonic::Response<grpc_api::AddStakingKeysResponse>, tonic::Status> {
+        Ok(tonic::Response::new(add_staking_keys(self, request)?))
+    }
     /// Ban multiple nodes by their individual ids
     async fn ban_nodes_by_ids(
         &self,
@@ -511,6 +620,13 @@ impl grpc_api::private_service_server::PrivateService for MassaPrivateGrpc {
             self, request,
         )?))
     }
+    /// Remove staking keys from wallet at the beginning of the next cycle
+    async fn remove_staking_keys(
+        &self,
+        request: tonic::Request<grpc_api::RemoveStakingKeysRequest>,
+    ) -> Result<tonic::Response<grpc_api::RemoveStakingKeysResponse>, tonic::Status> {
+        Ok(tonic::Response::new(remove_staking_keys(self, request)?))
+    }
     /// Sign messages with node's key
     async fn sign_messages(
         &self,
@@ -518,6 +634,13 @@ impl grpc_api::private_service_server::PrivateService for MassaPrivateGrpc {
     ) -> Result<tonic::Response<grpc_api::SignMessagesResponse>, tonic::Status> {
         Ok(tonic::Response::new(sign_messages(self, request)?))
     }
+    /// Replace a staking key by a new one at the beginning of the next cycle
+    async fn swap_staking_key(
+        &self,
+        request: tonic::Request<grpc_api::SwapStakingKeyRequest>,
+    ) -> Result<tonic::Response<grpc_api::SwapStakingKeyResponse>, tonic::Status> {
+        Ok(tonic::Response::new(swap_staking_key(self, request)?))
+    }
     /// Shutdown the node gracefully
     async fn shutdown_gracefully(
         &self,
@@ -541,4 +664,96 @@ impl grpc_api::private_service_server::PrivateService for MassaPrivateGrpc {
     ) -> Result<tonic::Response<grpc_api::UnbanNodesByIpsResponse>, tonic::Status> {
         Ok(tonic::Response::new(unban_nodes_by_ips(self, request)?))
     }
+
+    #[cfg(feature = "execution-trace")]
+    /// Get a datastore entry as seen by the execution of a debug session
+    async fn get_debug_datastore_entry(
+        &self,
+        request: tonic::Request<grpc_api::GetDebugDatastoreEntryRequest>,
+    ) -> Result<tonic::Response<grpc_api::GetDebugDatastoreEntryResponse>, tonic::Status> {
+        Ok(tonic::Response::new(get_debug_datastore_entry(
+            self, request,
+        )?))
+    }
+
+    #[cfg(not(feature = "execution-trace"))]
+    /// Get a datastore entry as seen by the execution of a debug session
+    async fn get_debug_datastore_entry(
+        &self,
+        _request: tonic::Request<grpc_api::GetDebugDatastoreEntryRequest>,
+    ) -> Result<tonic::Response<grpc_api::GetDebugDatastoreEntryResponse>, tonic::Status> {
+        Err(tonic::Status::unimplemented("feature not enabled"))
+    }
+
+    #[cfg(feature = "execution-trace")]
+    /// Move a debug session forward, by one ABI call or up to the next breakpoint
+    async fn resume_debug_session(
+        &self,
+        request: tonic::Request<grpc_api::ResumeDebugSessionRequest>,
+    ) -> Result<tonic::Response<grpc_api::ResumeDebugSessionResponse>, tonic::Status> {
+        Ok(tonic::Response::new(resume_debug_session(self, request)?))
+    }
+
+    #[cfg(not(feature = "execution-trace"))]
+    /// Move a debug session forward, by one ABI call or up to the next breakpoint
+    async fn resume_debug_session(
+        &self,
+        _request: tonic::Request<grpc_api::ResumeDebugSessionRequest>,
+    ) -> Result<tonic::Response<grpc_api::ResumeDebugSessionResponse>, tonic::Status> {
+        Err(tonic::Status::unimplemented("feature not enabled"))
+    }
+
+    #[cfg(feature = "execution-trace")]
+    /// Replace the breakpoints of a debug session
+    async fn set_debug_breakpoints(
+        &self,
+        request: tonic::Request<grpc_api::SetDebugBreakpointsRequest>,
+    ) -> Result<tonic::Response<grpc_api::SetDebugBreakpointsResponse>, tonic::Status> {
+        Ok(tonic::Response::new(set_debug_breakpoints(self, request)?))
+    }
+
+    #[cfg(not(feature = "execution-trace"))]
+    /// Replace the breakpoints of a debug session
+    async fn set_debug_breakpoints(
+        &self,
+        _request: tonic::Request<grpc_api::SetDebugBreakpointsRequest>,
+    ) -> Result<tonic::Response<grpc_api::SetDebugBreakpointsResponse>, tonic::Status> {
+        Err(tonic::Status::unimplemented("feature not enabled"))
+    }
+
+    #[cfg(feature = "execution-trace")]
+    /// Execute a read-only call and open a debug session to step through its ABI calls
+    async fn start_debug_session(
+        &self,
+        request: tonic::Request<grpc_api::StartDebugSessionRequest>,
+    ) -> Result<tonic::Response<grpc_api::StartDebugSessionResponse>, tonic::Status> {
+        Ok(tonic::Response::new(start_debug_session(self, request)?))
+    }
+
+    #[cfg(not(feature = "execution-trace"))]
+    /// Execute a read-only call and open a debug session to step through its ABI calls
+    async fn start_debug_session(
+        &self,
+        _request: tonic::Request<grpc_api::StartDebugSessionRequest>,
+    ) -> Result<tonic::Response<grpc_api::StartDebugSessionResponse>, tonic::Status> {
+        Err(tonic::Status::unimplemented("feature not enabled"))
+    }
+
+    #[cfg(feature = "execution-trace")]
+    /// Close a debug session
+    async fn stop_debug_session(
+        &self,
+        request: tonic::Request<grpc_api::StopDebugSessionRequest>,
+    ) -> Result<tonic::Response<grpc_api::StopDebugSessionResponse>, tonic::Status> {
+        Ok(tonic::Response::new(stop_debug_session(self, request)?))
+    }
+
+    #[cfg(not(feature = "execution-trace"))]
+    /// Close a debug session
+    async fn stop_debug_session(
+        &self,
+        _request: tonic::Request<grpc_api::StopDebugSessionRequest>,
+    ) -> Result<tonic::Response<grpc_api::StopDebugSessionResponse>, tonic::Status> {
+        Err(tonic::Status::unimplemented("feature not enabled"))
+    }
 }
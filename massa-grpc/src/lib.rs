@@ -8,7 +8,10 @@
 //! ## **Structure**
 //!
 //! * `api.rs`: implements gRPC service methods without streams.
+//! * `deadline.rs`: enforces the deadline of the gRPC requests.
 //! * `handler.rs`: defines the logic for handling incoming gRPC requests.
+//! * `message_size.rs`: enforces the max message sizes of each gRPC method.
+//! * `metrics.rs`: records per-method metrics of the gRPC requests.
 //! * `server`: initializes the gRPC service and serve It.
 //! * `stream/`: contains the gRPC streaming methods implementations files.
 
@@ -28,10 +31,16 @@ use serde_json as _;
 
 /// gRPC configuration
 pub mod config;
+/// gRPC requests deadline enforcement
+pub mod deadline;
 /// models error
 pub mod error;
 /// gRPC API implementation
 pub mod handler;
+/// per method max message sizes
+pub mod message_size;
+/// gRPC requests metrics
+pub mod metrics;
 /// business code for node management methods
 pub mod private;
 /// business code for non stream methods
@@ -0,0 +1,297 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use futures_util::future::BoxFuture;
+use hyper::body::{Bytes, HttpBody};
+use hyper::http::{HeaderMap, HeaderValue};
+use hyper::{Body, Request, Response};
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tonic::body::BoxBody;
+use tonic::Status;
+use tower::{Layer, Service};
+
+/// Length of the header preceding each message in a gRPC body (compression flag + message length)
+const FRAME_HEADER_LEN: usize = 5;
+
+/// Max message sizes of the gRPC methods
+#[derive(Debug, Clone)]
+pub struct MessageSizeLimits {
+    /// max size of a decoded message for the methods without specific limit
+    pub default_decoding: usize,
+    /// max size of an encoded message for the methods without specific limit
+    pub default_encoding: usize,
+    /// max size of a decoded message, by method name
+    pub decoding_per_method: HashMap<String, usize>,
+    /// max size of an encoded message, by method name
+    pub encoding_per_method: HashMap<String, usize>,
+}
+
+impl MessageSizeLimits {
+    /// Largest max size of a decoded message, to be applied to the whole service
+    pub fn service_decoding_limit(&self) -> usize {
+        self.decoding_per_method
+            .values()
+            .copied()
+            .fold(self.default_decoding, usize::max)
+    }
+
+    /// Largest max size of an encoded message, to be applied to the whole service
+    pub fn service_encoding_limit(&self) -> usize {
+        self.encoding_per_method
+            .values()
+            .copied()
+            .fold(self.default_encoding, usize::max)
+    }
+
+    fn decoding_limit(&self, method: &str) -> usize {
+        self.decoding_per_method
+            .get(method)
+            .copied()
+            .unwrap_or(self.default_decoding)
+    }
+
+    fn encoding_limit(&self, method: &str) -> usize {
+        self.encoding_per_method
+            .get(method)
+            .copied()
+            .unwrap_or(self.default_encoding)
+    }
+}
+
+/// Layer enforcing the max message sizes of each gRPC method.
+///
+/// The service level limits of tonic are set to the largest of the method limits,
+/// this layer checks the length of the messages of the methods having a lower limit.
+/// The length of compressed messages is checked before decompression.
+#[derive(Clone)]
+pub struct GrpcMessageSizeLayer {
+    limits: Arc<MessageSizeLimits>,
+}
+
+impl GrpcMessageSizeLayer {
+    /// Creates a new layer enforcing the given limits
+    pub fn new(limits: MessageSizeLimits) -> Self {
+        GrpcMessageSizeLayer {
+            limits: Arc::new(limits),
+        }
+    }
+}
+
+impl<S> Layer<S> for GrpcMessageSizeLayer {
+    type Service = GrpcMessageSizeService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        GrpcMessageSizeService {
+            inner,
+            limits: self.limits.clone(),
+        }
+    }
+}
+
+/// Service checking the message sizes of gRPC calls
+#[derive(Clone)]
+pub struct GrpcMessageSizeService<S> {
+    inner: S,
+    limits: Arc<MessageSizeLimits>,
+}
+
+impl<S> Service<Request<Body>> for GrpcMessageSizeService<S>
+where
+    S: Service<Request<Body>, Response = Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<BoxBody>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        // take the service that was driven to readiness and leave a clone in its place
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        // gRPC paths are in the form "/massa.api.v1.PublicService/GetBlocks"
+        let method = request
+            .uri()
+            .path()
+            .rsplit('/')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+
+        // only the methods with a limit lower than the service one need to be checked
+        let decoding_limit = self.limits.decoding_limit(&method);
+        let request = if decoding_limit < self.limits.service_decoding_limit() {
+            request.map(|body| {
+                let method = method.clone();
+                let checker = FrameSizeChecker::new(decoding_limit);
+                // the stream ends after an error
+                let messages = futures_util::stream::unfold(Some((body, checker)), move |state| {
+                    let method = method.clone();
+                    async move {
+                        let (mut body, mut checker) = state?;
+                        match body.data().await? {
+                            Ok(data) => match checker.check(&data) {
+                                Ok(()) => Some((Ok(data), Some((body, checker)))),
+                                Err((_, len)) => Some((
+                                    Err(too_large(&method, "decoded", len, decoding_limit)),
+                                    None,
+                                )),
+                            },
+                            Err(err) => Some((Err(Status::from_error(Box::new(err))), None)),
+                        }
+                    }
+                });
+                Body::wrap_stream(messages)
+            })
+        } else {
+            request
+        };
+        let encoding_limit = self.limits.encoding_limit(&method);
+        let check_response = encoding_limit < self.limits.service_encoding_limit();
+
+        Box::pin(async move {
+            let response = inner.call(request).await?;
+            if !check_response {
+                return Ok(response);
+            }
+            Ok(response.map(|body| {
+                tonic::body::boxed(LimitedBody {
+                    inner: body,
+                    checker: FrameSizeChecker::new(encoding_limit),
+                    method,
+                    error: None,
+                })
+            }))
+        })
+    }
+}
+
+/// Response body ending the call with RESOURCE_EXHAUSTED when a message is too large
+struct LimitedBody {
+    inner: BoxBody,
+    checker: FrameSizeChecker,
+    method: String,
+    /// set once a message too large has been found, the call is then ended with this status
+    error: Option<Status>,
+}
+
+impl HttpBody for LimitedBody {
+    type Data = Bytes;
+    type Error = Status;
+
+    fn poll_data(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+        if self.error.is_some() {
+            return Poll::Ready(None);
+        }
+        match Pin::new(&mut self.inner).poll_data(cx) {
+            Poll::Ready(Some(Ok(mut data))) => match self.checker.check(&data) {
+                Ok(()) => Poll::Ready(Some(Ok(data))),
+                Err((offset, len)) => {
+                    let limit = self.checker.limit;
+                    self.error = Some(too_large(&self.method, "encoded", len, limit));
+                    // forward the messages preceding the one too large
+                    let data = data.split_to(offset);
+                    if data.is_empty() {
+                        Poll::Ready(None)
+                    } else {
+                        Poll::Ready(Some(Ok(data)))
+                    }
+                }
+            },
+            poll => poll,
+        }
+    }
+
+    fn poll_trailers(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+        match self.error.take() {
+            Some(status) => {
+                let mut trailers = HeaderMap::new();
+                trailers.insert("grpc-status", HeaderValue::from(status.code() as i32));
+                if let Ok(message) = HeaderValue::from_str(status.message()) {
+                    trailers.insert("grpc-message", message);
+                }
+                Poll::Ready(Ok(Some(trailers)))
+            }
+            None => Pin::new(&mut self.inner).poll_trailers(cx),
+        }
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.error.is_none() && self.inner.is_end_stream()
+    }
+}
+
+fn too_large(method: &str, direction: &str, len: usize, limit: usize) -> Status {
+    Status::resource_exhausted(format!(
+        "{} message of {} is too large: {} bytes, the max size for this method is {} bytes",
+        direction, method, len, limit
+    ))
+}
+
+/// Reads the headers of the gRPC messages of a body to check their length
+struct FrameSizeChecker {
+    limit: usize,
+    /// bytes of the current message not yet read
+    remaining: usize,
+    /// header of the next message
+    header: [u8; FRAME_HEADER_LEN],
+    /// bytes of the header of the next message already read
+    header_len: usize,
+}
+
+impl FrameSizeChecker {
+    fn new(limit: usize) -> Self {
+        FrameSizeChecker {
+            limit,
+            remaining: 0,
+            header: [0; FRAME_HEADER_LEN],
+            header_len: 0,
+        }
+    }
+
+    /// Checks the next chunk of the body.
+    /// Returns the offset in the chunk and the length of the first message larger than the limit.
+    fn check(&mut self, data: &[u8]) -> Result<(), (usize, usize)> {
+        let mut offset = 0;
+        while offset < data.len() {
+            if self.remaining > 0 {
+                let read = self.remaining.min(data.len() - offset);
+                self.remaining -= read;
+                offset += read;
+                continue;
+            }
+            let header_start = offset.saturating_sub(self.header_len);
+            let read = (FRAME_HEADER_LEN - self.header_len).min(data.len() - offset);
+            self.header[self.header_len..self.header_len + read]
+                .copy_from_slice(&data[offset..offset + read]);
+            self.header_len += read;
+            offset += read;
+            if self.header_len == FRAME_HEADER_LEN {
+                self.header_len = 0;
+                let len = u32::from_be_bytes([
+                    self.header[1],
+                    self.header[2],
+                    self.header[3],
+                    self.header[4],
+                ]) as usize;
+                if len > self.limit {
+                    return Err((header_start, len));
+                }
+                self.remaining = len;
+            }
+        }
+        Ok(())
+    }
+}
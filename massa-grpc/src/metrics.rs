@@ -0,0 +1,227 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use futures_util::future::BoxFuture;
+use hyper::body::{Buf, HttpBody};
+use hyper::http::HeaderMap;
+use hyper::{Request, Response};
+use massa_metrics::MassaMetrics;
+use prost::Message;
+use prost_types::FileDescriptorSet;
+use std::collections::HashSet;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Instant;
+use tower::{Layer, Service};
+use tracing::warn;
+
+/// gRPC status code of a call interrupted before the server sent its status
+const CANCELLED_CODE: &str = "1";
+
+/// Service and method label of the calls to a path that is not a known method
+const UNKNOWN_LABEL: &str = "unknown";
+
+/// Methods of the health and reflection services, that can be served along the Massa service
+const STANDARD_METHODS: [&str; 3] = [
+    "grpc.health.v1.Health/Check",
+    "grpc.health.v1.Health/Watch",
+    "grpc.reflection.v1alpha.ServerReflection/ServerReflectionInfo",
+];
+
+/// Layer recording, for each gRPC method, the number of requests, their latency,
+/// the size of the responses and their status code into the node metrics.
+///
+/// Only the methods of the served services are used as labels, the calls to any other path
+/// are recorded under the `unknown` service and method so that clients can not create
+/// new metric series.
+#[derive(Clone)]
+pub struct GrpcMetricsLayer {
+    massa_metrics: MassaMetrics,
+    known_methods: Arc<HashSet<String>>,
+}
+
+impl GrpcMetricsLayer {
+    /// Creates a new layer recording into `massa_metrics` the calls to the methods of
+    /// the services described by the encoded `file_descriptor_set`
+    pub fn new(massa_metrics: MassaMetrics, file_descriptor_set: &[u8]) -> Self {
+        let mut known_methods: HashSet<String> =
+            match FileDescriptorSet::decode(file_descriptor_set) {
+                Ok(descriptors) => descriptors
+                    .file
+                    .iter()
+                    .flat_map(|file| {
+                        file.service.iter().flat_map(move |service| {
+                            service.method.iter().map(move |method| {
+                                format!("{}.{}/{}", file.package(), service.name(), method.name())
+                            })
+                        })
+                    })
+                    .collect(),
+                Err(err) => {
+                    warn!("failed to decode the gRPC file descriptor set: {}", err);
+                    HashSet::new()
+                }
+            };
+        known_methods.extend(STANDARD_METHODS.iter().map(|method| method.to_string()));
+        GrpcMetricsLayer {
+            massa_metrics,
+            known_methods: Arc::new(known_methods),
+        }
+    }
+
+    /// Get the service and method labels of a call to `path`, `unknown` if it is not a known method.
+    /// gRPC paths are in the form "/massa.api.v1.PublicService/GetBlocks".
+    pub(crate) fn labels(&self, path: &str) -> (String, String) {
+        let path = path.trim_start_matches('/');
+        match path.split_once('/') {
+            Some((service, method)) if self.known_methods.contains(path) => {
+                (service.to_string(), method.to_string())
+            }
+            _ => (UNKNOWN_LABEL.to_string(), UNKNOWN_LABEL.to_string()),
+        }
+    }
+}
+
+impl<S> Layer<S> for GrpcMetricsLayer {
+    type Service = GrpcMetricsService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        GrpcMetricsService {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+/// Service wrapping gRPC calls to record their metrics
+#[derive(Clone)]
+pub struct GrpcMetricsService<S> {
+    inner: S,
+    layer: GrpcMetricsLayer,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for GrpcMetricsService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+{
+    type Response = Response<MetricsBody<ResBody>>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<ReqBody>) -> Self::Future {
+        // take the service that was driven to readiness and leave a clone in its place
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        let (service, method) = self.layer.labels(request.uri().path());
+        let mut recorder = Recorder {
+            massa_metrics: self.layer.massa_metrics.clone(),
+            service,
+            method,
+            start: Instant::now(),
+            code: None,
+            response_size: 0,
+        };
+
+        Box::pin(async move {
+            let response = inner.call(request).await?;
+            // errors are usually returned as "trailers-only" responses, with the status in the headers
+            recorder.set_code(response.headers());
+            Ok(response.map(|body| MetricsBody {
+                inner: body,
+                recorder: Some(recorder),
+            }))
+        })
+    }
+}
+
+/// Response body counting the bytes sent and reading the status code from the trailers.
+/// Metrics are recorded once the body is fully sent, or dropped.
+pub struct MetricsBody<B> {
+    inner: B,
+    recorder: Option<Recorder>,
+}
+
+impl<B> HttpBody for MetricsBody<B>
+where
+    B: HttpBody + Unpin,
+{
+    type Data = B::Data;
+    type Error = B::Error;
+
+    fn poll_data(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+        let poll = Pin::new(&mut self.inner).poll_data(cx);
+        if let Poll::Ready(Some(Ok(data))) = &poll {
+            let size = data.remaining() as u64;
+            if let Some(recorder) = self.recorder.as_mut() {
+                recorder.response_size = recorder.response_size.saturating_add(size);
+            }
+        }
+        poll
+    }
+
+    fn poll_trailers(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+        let poll = Pin::new(&mut self.inner).poll_trailers(cx);
+        if let Poll::Ready(result) = &poll {
+            if let Some(mut recorder) = self.recorder.take() {
+                if let Ok(Some(trailers)) = result {
+                    recorder.set_code(trailers);
+                }
+            }
+        }
+        poll
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> hyper::body::SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+/// Metrics of a single gRPC call, recorded when dropped
+struct Recorder {
+    massa_metrics: MassaMetrics,
+    service: String,
+    method: String,
+    start: Instant,
+    code: Option<String>,
+    response_size: u64,
+}
+
+impl Recorder {
+    fn set_code(&mut self, headers: &HeaderMap) {
+        if let Some(code) = headers
+            .get("grpc-status")
+            .and_then(|code| code.to_str().ok())
+        {
+            self.code = Some(code.to_string());
+        }
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        self.massa_metrics.record_grpc_request(
+            &self.service,
+            &self.method,
+            self.code.as_deref().unwrap_or(CANCELLED_CODE),
+            self.start.elapsed(),
+            self.response_size,
+        );
+    }
+}
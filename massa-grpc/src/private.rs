@@ -7,18 +7,24 @@ use crate::error::GrpcError;
 use crate::server::MassaPrivateGrpc;
 use massa_execution_exports::ExecutionQueryRequest;
 use massa_hash::Hash;
+use massa_models::address::Address;
 use massa_models::config::CompactConfig;
 use massa_models::node::NodeId;
 use massa_models::slot::Slot;
 use massa_models::timeslots::get_latest_block_slot_at_timestamp;
 use massa_proto_rs::massa::api::v1 as grpc_api;
 use massa_proto_rs::massa::model::v1 as grpc_model;
-use massa_protocol_exports::{PeerConnectionType, PeerId};
+use massa_protocol_exports::{IpCidr, PeerConnectionType, PeerId};
 use massa_signature::KeyPair;
 use massa_time::MassaTime;
 use tracing::warn;
 // use massa_proto_rs::massa::model::v1 "add_to_bootstrap_blacklist"as grpc_model;
 
+#[cfg(feature = "execution-trace")]
+use crate::public::to_read_only_request;
+#[cfg(feature = "execution-trace")]
+use massa_execution_exports::{DebugCommand, DebugSessionState, ReadOnlyPriority};
+
 /// Add IP addresses to node bootstrap blacklist
 pub(crate) fn add_to_bootstrap_blacklist(
     grpc: &MassaPrivateGrpc,
@@ -113,6 +119,44 @@ pub(crate) fn add_staking_secret_keys(
     Ok(grpc_api::AddStakingSecretKeysResponse {})
 }
 
+/// Add staking keys to the wallet at the beginning of the next cycle
+pub(crate) fn add_staking_keys(
+    grpc: &MassaPrivateGrpc,
+    request: tonic::Request<grpc_api::AddStakingKeysRequest>,
+) -> Result<grpc_api::AddStakingKeysResponse, GrpcError> {
+    let secret_keys = request.into_inner().secret_keys;
+
+    if secret_keys.is_empty() {
+        return Err(GrpcError::InvalidArgument(
+            "no secret key received".to_string(),
+        ));
+    }
+
+    if secret_keys.len() as u64 > grpc.grpc_config.max_arguments {
+        return Err(GrpcError::InvalidArgument(format!(
+            "too many secret keys received. Only a maximum of {} secret keys are accepted per request",
+            grpc.grpc_config.max_arguments
+        )));
+    }
+
+    let keypairs = secret_keys
+        .iter()
+        .map(|x| KeyPair::from_str(x))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| GrpcError::InvalidArgument(e.to_string()))?;
+
+    let effective_cycle = get_next_cycle(grpc)?;
+    let addresses =
+        grpc.node_wallet
+            .write()
+            .schedule_key_change(effective_cycle, keypairs, Vec::new())?;
+
+    Ok(grpc_api::AddStakingKeysResponse {
+        addresses: addresses.into_iter().map(|a| a.to_string()).collect(),
+        effective_cycle,
+    })
+}
+
 /// Ban multiple nodes by their individual ids
 pub(crate) fn ban_nodes_by_ids(
     grpc: &MassaPrivateGrpc,
@@ -146,12 +190,17 @@ pub(crate) fn ban_nodes_by_ids(
     Ok(grpc_api::BanNodesByIdsResponse {})
 }
 
-/// Ban multiple nodes by their individual IP addresses
+/// Ban multiple nodes by their IP addresses, IP ranges (CIDR notation) or autonomous system numbers
 pub(crate) fn ban_nodes_by_ips(
-    _grpc: &MassaPrivateGrpc,
-    _request: tonic::Request<grpc_api::BanNodesByIpsRequest>,
+    grpc: &MassaPrivateGrpc,
+    request: tonic::Request<grpc_api::BanNodesByIpsRequest>,
 ) -> Result<grpc_api::BanNodesByIpsResponse, GrpcError> {
-    Err(GrpcError::Unimplemented("ban_nodes_by_ips".to_string()))
+    let inner_req = request.into_inner();
+    let (ranges, asns) = parse_ip_bans(grpc, inner_req.node_ips, inner_req.asns)?;
+
+    grpc.protocol_controller.ban_ips(ranges, asns)?;
+
+    Ok(grpc_api::BanNodesByIpsResponse {})
 }
 
 /// Get node bootstrap blacklist IP addresses
@@ -388,6 +437,83 @@ pub(crate) fn remove_staking_addresses(
         "remove_staking_addresses".to_string(),
     ))
 }
+/// Remove staking keys from the wallet at the beginning of the next cycle
+pub(crate) fn remove_staking_keys(
+    grpc: &MassaPrivateGrpc,
+    request: tonic::Request<grpc_api::RemoveStakingKeysRequest>,
+) -> Result<grpc_api::RemoveStakingKeysResponse, GrpcError> {
+    let addresses = request.into_inner().addresses;
+
+    if addresses.is_empty() {
+        return Err(GrpcError::InvalidArgument(
+            "no address received".to_string(),
+        ));
+    }
+
+    if addresses.len() as u64 > grpc.grpc_config.max_arguments {
+        return Err(GrpcError::InvalidArgument(format!(
+            "too many addresses received. Only a maximum of {} addresses are accepted per request",
+            grpc.grpc_config.max_arguments
+        )));
+    }
+
+    let addresses = addresses
+        .into_iter()
+        .map(|address| {
+            Address::from_str(&address).map_err(|_| {
+                GrpcError::InvalidField(
+                    "addresses".to_string(),
+                    format!("invalid address: {}", address),
+                )
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let effective_cycle = get_next_cycle(grpc)?;
+    grpc.node_wallet
+        .write()
+        .schedule_key_change(effective_cycle, Vec::new(), addresses)?;
+
+    Ok(grpc_api::RemoveStakingKeysResponse { effective_cycle })
+}
+
+/// Replace a staking key by a new one at the beginning of the next cycle
+pub(crate) fn swap_staking_key(
+    grpc: &MassaPrivateGrpc,
+    request: tonic::Request<grpc_api::SwapStakingKeyRequest>,
+) -> Result<grpc_api::SwapStakingKeyResponse, GrpcError> {
+    let inner_req = request.into_inner();
+
+    let old_address = Address::from_str(&inner_req.old_address).map_err(|_| {
+        GrpcError::InvalidField(
+            "old_address".to_string(),
+            format!("invalid address: {}", inner_req.old_address),
+        )
+    })?;
+    let new_keypair = KeyPair::from_str(&inner_req.new_secret_key)
+        .map_err(|e| GrpcError::InvalidArgument(e.to_string()))?;
+
+    let effective_cycle = get_next_cycle(grpc)?;
+    let new_address = {
+        let mut wallet = grpc.node_wallet.write();
+        if wallet.find_associated_keypair(&old_address).is_none() {
+            return Err(GrpcError::InvalidArgument(format!(
+                "address {} is not a staking address of this node",
+                old_address
+            )));
+        }
+        wallet.schedule_key_change(effective_cycle, vec![new_keypair], vec![old_address])?
+    };
+
+    Ok(grpc_api::SwapStakingKeyResponse {
+        new_address: new_address
+            .first()
+            .map(|a| a.to_string())
+            .unwrap_or_default(),
+        effective_cycle,
+    })
+}
+
 /// Sign messages with node's key
 pub(crate) fn sign_messages(
     grpc: &MassaPrivateGrpc,
@@ -464,10 +590,200 @@ pub(crate) fn unban_nodes_by_ids(
     Ok(grpc_api::UnbanNodesByIdsResponse {})
 }
 
-/// Unban multiple nodes by their individual IP addresses
+/// Unban multiple nodes by their IP addresses, IP ranges (CIDR notation) or autonomous system numbers
 pub(crate) fn unban_nodes_by_ips(
-    _grpc: &MassaPrivateGrpc,
-    _request: tonic::Request<grpc_api::UnbanNodesByIpsRequest>,
+    grpc: &MassaPrivateGrpc,
+    request: tonic::Request<grpc_api::UnbanNodesByIpsRequest>,
 ) -> Result<grpc_api::UnbanNodesByIpsResponse, GrpcError> {
-    Err(GrpcError::Unimplemented("unban_nodes_by_ips".to_string()))
+    let inner_req = request.into_inner();
+    let (ranges, asns) = parse_ip_bans(grpc, inner_req.node_ips, inner_req.asns)?;
+
+    grpc.protocol_controller.unban_ips(ranges, asns)?;
+
+    Ok(grpc_api::UnbanNodesByIpsResponse {})
+}
+
+#[cfg(feature = "execution-trace")]
+/// Execute a read-only call and open a debug session to step through its ABI calls
+pub(crate) fn start_debug_session(
+    grpc: &MassaPrivateGrpc,
+    request: tonic::Request<grpc_api::StartDebugSessionRequest>,
+) -> Result<grpc_api::StartDebugSessionResponse, GrpcError> {
+    let inner_req = request.into_inner();
+    let call = inner_req
+        .call
+        .ok_or_else(|| GrpcError::InvalidArgument("no call provided".to_string()))?;
+    let caller_address = call
+        .caller_address
+        .as_deref()
+        .map(Address::from_str)
+        .transpose()?
+        .ok_or_else(|| GrpcError::InvalidArgument("no caller address provided".to_string()))?;
+    if inner_req.breakpoints.len() as u64 > grpc.grpc_config.max_arguments {
+        return Err(GrpcError::InvalidArgument(format!(
+            "too many breakpoints received. Only a maximum of {} breakpoints are accepted per request",
+            grpc.grpc_config.max_arguments
+        )));
+    }
+
+    // the debug sessions are opened by the node operator
+    let mut read_only_call = to_read_only_request(call, caller_address, &grpc.grpc_config)?;
+    read_only_call.priority = ReadOnlyPriority::High;
+    let session = grpc
+        .execution_controller
+        .start_debug_session(read_only_call, inner_req.breakpoints)?;
+
+    Ok(grpc_api::StartDebugSessionResponse {
+        session: Some(into_debug_session(session)),
+    })
+}
+
+#[cfg(feature = "execution-trace")]
+/// Move a debug session forward, by one ABI call or up to the next breakpoint
+pub(crate) fn resume_debug_session(
+    grpc: &MassaPrivateGrpc,
+    request: tonic::Request<grpc_api::ResumeDebugSessionRequest>,
+) -> Result<grpc_api::ResumeDebugSessionResponse, GrpcError> {
+    let inner_req = request.into_inner();
+    // See grpc_api::DebugCommand
+    let command = match inner_req.command {
+        1 => DebugCommand::Step,
+        2 => DebugCommand::Continue,
+        _ => {
+            return Err(GrpcError::InvalidArgument(
+                "invalid debug command".to_string(),
+            ));
+        }
+    };
+    let session = grpc
+        .execution_controller
+        .resume_debug_session(inner_req.session_id, command)?;
+
+    Ok(grpc_api::ResumeDebugSessionResponse {
+        session: Some(into_debug_session(session)),
+    })
+}
+
+#[cfg(feature = "execution-trace")]
+/// Replace the breakpoints of a debug session
+pub(crate) fn set_debug_breakpoints(
+    grpc: &MassaPrivateGrpc,
+    request: tonic::Request<grpc_api::SetDebugBreakpointsRequest>,
+) -> Result<grpc_api::SetDebugBreakpointsResponse, GrpcError> {
+    let inner_req = request.into_inner();
+    if inner_req.breakpoints.len() as u64 > grpc.grpc_config.max_arguments {
+        return Err(GrpcError::InvalidArgument(format!(
+            "too many breakpoints received. Only a maximum of {} breakpoints are accepted per request",
+            grpc.grpc_config.max_arguments
+        )));
+    }
+    let session = grpc
+        .execution_controller
+        .set_debug_breakpoints(inner_req.session_id, inner_req.breakpoints)?;
+
+    Ok(grpc_api::SetDebugBreakpointsResponse {
+        session: Some(into_debug_session(session)),
+    })
+}
+
+#[cfg(feature = "execution-trace")]
+/// Get a datastore entry as seen by the execution of a debug session
+pub(crate) fn get_debug_datastore_entry(
+    grpc: &MassaPrivateGrpc,
+    request: tonic::Request<grpc_api::GetDebugDatastoreEntryRequest>,
+) -> Result<grpc_api::GetDebugDatastoreEntryResponse, GrpcError> {
+    let inner_req = request.into_inner();
+    let address = Address::from_str(&inner_req.address)?;
+    let value = grpc.execution_controller.get_debug_datastore_entry(
+        inner_req.session_id,
+        &address,
+        &inner_req.key,
+    )?;
+
+    Ok(grpc_api::GetDebugDatastoreEntryResponse { value })
+}
+
+#[cfg(feature = "execution-trace")]
+/// Close a debug session
+pub(crate) fn stop_debug_session(
+    grpc: &MassaPrivateGrpc,
+    request: tonic::Request<grpc_api::StopDebugSessionRequest>,
+) -> Result<grpc_api::StopDebugSessionResponse, GrpcError> {
+    grpc.execution_controller
+        .stop_debug_session(request.into_inner().session_id)?;
+
+    Ok(grpc_api::StopDebugSessionResponse {})
+}
+
+#[cfg(feature = "execution-trace")]
+/// Convert the state of a debug session
+fn into_debug_session(session: DebugSessionState) -> grpc_api::DebugSession {
+    grpc_api::DebugSession {
+        session_id: session.session_id,
+        current_step: session.current_step.map(|step| grpc_api::DebugStep {
+            index: step.index as u64,
+            abi_call: Some(grpc_api::AbiCallStackElement {
+                name: step.abi_call.name,
+                parameters: step
+                    .abi_call
+                    .parameters
+                    .iter()
+                    .map(|p| serde_json::to_string(p).unwrap_or_default())
+                    .collect(),
+                return_value: serde_json::to_string(&step.abi_call.return_value)
+                    .unwrap_or_default(),
+            }),
+            call_stack: step.call_stack,
+        }),
+        finished: session.finished,
+        step_count: session.step_count as u64,
+        breakpoints: session.breakpoints,
+        used_gas: session.gas_cost,
+        call_result: session.call_result,
+    }
+}
+
+/// Parse the IP ranges and ASNs of a (un)ban request
+fn parse_ip_bans(
+    grpc: &MassaPrivateGrpc,
+    node_ips: Vec<String>,
+    asns: Vec<u32>,
+) -> Result<(Vec<IpCidr>, Vec<u32>), GrpcError> {
+    if node_ips.is_empty() && asns.is_empty() {
+        return Err(GrpcError::InvalidArgument(
+            "no node ip or asn received".to_string(),
+        ));
+    }
+
+    if (node_ips.len() + asns.len()) as u64 > grpc.grpc_config.max_arguments {
+        return Err(GrpcError::InvalidArgument(format!(
+            "too many node ips and asns received. Only a maximum of {} arguments are accepted per request",
+            grpc.grpc_config.max_arguments
+        )));
+    }
+
+    let ranges = node_ips
+        .into_iter()
+        .map(|ip| {
+            IpCidr::from_str(&ip).map_err(|_| {
+                GrpcError::InvalidField("node_ips".to_string(), format!("invalid ip range: {}", ip))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok((ranges, asns))
+}
+
+// Get the cycle following the current one, at which staking key changes take effect
+fn get_next_cycle(grpc: &MassaPrivateGrpc) -> Result<u64, GrpcError> {
+    let last_slot = get_latest_block_slot_at_timestamp(
+        grpc.grpc_config.thread_count,
+        grpc.grpc_config.t0,
+        grpc.grpc_config.genesis_timestamp,
+        MassaTime::now(),
+    )?;
+    let current_cycle = last_slot
+        .unwrap_or_else(|| Slot::new(0, 0))
+        .get_cycle(grpc.grpc_config.periods_per_cycle);
+    Ok(current_cycle.saturating_add(1))
 }
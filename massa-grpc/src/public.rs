@@ -1,15 +1,17 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
 
+use crate::config::GrpcConfig;
 use crate::error::GrpcError;
 use crate::server::MassaPublicGrpc;
 use crate::{EndorsementDraw, SlotDraw, SlotRange};
 
 use itertools::{izip, Itertools};
 use massa_execution_exports::mapping_grpc::{
-    to_event_filter, to_execution_query_response, to_querystate_filter,
+    to_event_filter, to_execution_query_response, to_querystate_filter, to_slot_execution_stats,
 };
 use massa_execution_exports::{
-    ExecutionQueryRequest, ExecutionStackElement, ReadOnlyExecutionRequest, ReadOnlyExecutionTarget,
+    ExecutionError, ExecutionQueryRequest, ExecutionStackElement, ReadOnlyExecutionRequest,
+    ReadOnlyExecutionTarget, ReadOnlyPriority,
 };
 use massa_models::address::Address;
 use massa_models::amount::Amount;
@@ -21,7 +23,8 @@ use massa_models::endorsement::{EndorsementId, SecureShareEndorsement};
 use massa_models::operation::{OperationId, SecureShareOperation};
 use massa_models::prehash::{PreHashMap, PreHashSet};
 use massa_models::slot::Slot;
-use massa_models::timeslots::get_latest_block_slot_at_timestamp;
+use massa_models::stats::ExecutionStats;
+use massa_models::timeslots::{get_latest_block_slot_at_timestamp, slot_count_in_range};
 use massa_proto_rs::massa::api::v1::{self as grpc_api};
 use massa_proto_rs::massa::model::v1::{self as grpc_model, read_only_execution_call};
 use massa_serialization::{DeserializeError, Deserializer};
@@ -46,6 +49,8 @@ pub(crate) fn execute_read_only_call(
     grpc: &MassaPublicGrpc,
     request: tonic::Request<grpc_api::ExecuteReadOnlyCallRequest>,
 ) -> Result<grpc_api::ExecuteReadOnlyCallResponse, GrpcError> {
+    // the quotas of read-only requests are accounted to the client
+    let remote_ip = request.remote_addr().map(|addr| addr.ip().to_string());
     let call: grpc_model::ReadOnlyExecutionCall = request
         .into_inner()
         .call
@@ -60,6 +65,47 @@ pub(crate) fn execute_read_only_call(
         }
     };
 
+    let mut read_only_call = to_read_only_request(call, caller_address, &grpc.grpc_config)?;
+    read_only_call.caller_id = Some(remote_ip.unwrap_or_else(|| caller_address.to_string()));
+
+    if read_only_call
+        .fee
+        .unwrap_or_default()
+        .checked_sub(grpc.grpc_config.minimal_fees)
+        .is_none()
+    {
+        return Err(GrpcError::InvalidArgument(format!(
+            "fee is too low provided: {} , minimal_fees required: {}",
+            read_only_call.fee.unwrap_or_default(),
+            grpc.grpc_config.minimal_fees
+        )));
+    }
+
+    let output = grpc
+        .execution_controller
+        .execute_readonly_request(read_only_call)
+        .map_err(|err| match err {
+            ExecutionError::ReadOnlyQuotaError(msg) => GrpcError::ResourceExhausted(msg),
+            err => GrpcError::ExecutionError(err),
+        })?;
+
+    let result = grpc_model::ReadOnlyExecutionOutput {
+        out: Some(output.out.into()),
+        used_gas: output.gas_cost,
+        call_result: output.call_result,
+    };
+
+    Ok(grpc_api::ExecuteReadOnlyCallResponse {
+        output: Some(result),
+    })
+}
+
+/// Converts a read-only call to a read-only execution request made by `caller_address`
+pub(crate) fn to_read_only_request(
+    call: grpc_model::ReadOnlyExecutionCall,
+    caller_address: Address,
+    grpc_config: &GrpcConfig,
+) -> Result<ReadOnlyExecutionRequest, GrpcError> {
     let mut call_stack = Vec::new();
     let mut coins = None;
     let target = if let Some(call_target) = call.target {
@@ -69,9 +115,9 @@ pub(crate) fn execute_read_only_call(
                     None
                 } else {
                     let deserializer = DatastoreDeserializer::new(
-                        grpc.grpc_config.max_op_datastore_entry_count,
-                        grpc.grpc_config.max_op_datastore_key_length,
-                        grpc.grpc_config.max_op_datastore_value_length,
+                        grpc_config.max_op_datastore_entry_count,
+                        grpc_config.max_op_datastore_key_length,
+                        grpc_config.max_op_datastore_value_length,
                     );
                     match deserializer.deserialize::<DeserializeError>(&value.operation_datastore) {
                         Ok((_, deserialized)) => Some(deserialized),
@@ -129,7 +175,7 @@ pub(crate) fn execute_read_only_call(
         ));
     };
 
-    let read_only_call = ReadOnlyExecutionRequest {
+    Ok(ReadOnlyExecutionRequest {
         max_gas: call.max_gas,
         call_stack,
         target,
@@ -141,33 +187,9 @@ pub(crate) fn execute_read_only_call(
                     .map_err(|_| GrpcError::InvalidArgument("invalid amount".to_string()))
             })
             .transpose()?,
-    };
-
-    if read_only_call
-        .fee
-        .unwrap_or_default()
-        .checked_sub(grpc.grpc_config.minimal_fees)
-        .is_none()
-    {
-        return Err(GrpcError::InvalidArgument(format!(
-            "fee is too low provided: {} , minimal_fees required: {}",
-            read_only_call.fee.unwrap_or_default(),
-            grpc.grpc_config.minimal_fees
-        )));
-    }
-
-    let output = grpc
-        .execution_controller
-        .execute_readonly_request(read_only_call)?;
-
-    let result = grpc_model::ReadOnlyExecutionOutput {
-        out: Some(output.out.into()),
-        used_gas: output.gas_cost,
-        call_result: output.call_result,
-    };
-
-    Ok(grpc_api::ExecuteReadOnlyCallResponse {
-        output: Some(result),
+        state_overrides: Vec::new(),
+        priority: ReadOnlyPriority::Normal,
+        caller_id: None,
     })
 }
 
@@ -195,8 +217,12 @@ pub(crate) fn get_blocks(
         .into_iter()
         .take(grpc.grpc_config.max_operation_ids_per_request as usize + 1)
         .map(|id| {
-            BlockId::from_str(id.as_str())
-                .map_err(|_| GrpcError::InvalidArgument(format!("invalid block id: {}", id)))
+            BlockId::from_str(id.as_str()).map_err(|_| {
+                GrpcError::InvalidField(
+                    "block_ids".to_string(),
+                    format!("invalid block id: {}", id),
+                )
+            })
         })
         .collect::<Result<_, _>>()?;
 
@@ -228,6 +254,59 @@ pub(crate) fn get_blocks(
     })
 }
 
+/// Get the blockclique (or final) blocks of a slot range
+pub(crate) fn get_blocks_by_slot_range(
+    grpc: &MassaPublicGrpc,
+    request: tonic::Request<grpc_api::GetBlocksBySlotRangeRequest>,
+) -> Result<grpc_api::GetBlocksBySlotRangeResponse, GrpcError> {
+    let inner_req = request.into_inner();
+    let start_slot: Slot = inner_req
+        .start_slot
+        .ok_or_else(|| GrpcError::InvalidArgument("no start slot provided".to_string()))?
+        .into();
+    let end_slot: Slot = inner_req
+        .end_slot
+        .ok_or_else(|| GrpcError::InvalidArgument("no end slot provided".to_string()))?
+        .into();
+
+    let slot_count = end_slot
+        .slots_since(&start_slot, grpc.grpc_config.thread_count)
+        .map_err(|_| {
+            GrpcError::InvalidArgument(format!(
+                "invalid slot range: start slot {} is after end slot {}",
+                start_slot, end_slot
+            ))
+        })?;
+    if slot_count == 0 || slot_count > grpc.grpc_config.max_block_ids_per_request as u64 {
+        return Err(GrpcError::InvalidArgument(format!(
+            "the slot range must contain between 1 and {} slots",
+            grpc.grpc_config.max_block_ids_per_request
+        )));
+    }
+
+    let blocks = grpc
+        .consensus_controller
+        .get_blockclique_blocks_in_slot_range(start_slot, end_slot);
+
+    let block_storage_lock = grpc.storage.read_blocks();
+    let slot_blocks = blocks
+        .into_iter()
+        .map(|(slot, block_id)| grpc_api::SlotBlock {
+            slot: Some(slot.into()),
+            block_id: block_id.to_string(),
+            header: if inner_req.with_headers {
+                block_storage_lock
+                    .get(&block_id)
+                    .map(|block| block.content.header.clone().into())
+            } else {
+                None
+            },
+        })
+        .collect();
+
+    Ok(grpc_api::GetBlocksBySlotRangeResponse { slot_blocks })
+}
+
 /// Get multiple datastore entries
 pub(crate) fn get_datastore_entries(
     grpc: &MassaPublicGrpc,
@@ -303,8 +382,12 @@ pub(crate) fn get_endorsements(
         .into_iter()
         .take(grpc.grpc_config.max_operation_ids_per_request as usize + 1)
         .map(|id| {
-            EndorsementId::from_str(id.as_str())
-                .map_err(|_| GrpcError::InvalidArgument(format!("invalid endorsement id: {}", id)))
+            EndorsementId::from_str(id.as_str()).map_err(|_| {
+                GrpcError::InvalidField(
+                    "endorsement_ids".to_string(),
+                    format!("invalid endorsement id: {}", id),
+                )
+            })
         })
         .collect::<Result<_, _>>()?;
 
@@ -728,8 +811,12 @@ pub(crate) fn get_operations(
         .into_iter()
         .take(grpc.grpc_config.max_operation_ids_per_request as usize + 1)
         .map(|id| {
-            OperationId::from_str(id.as_str())
-                .map_err(|_| GrpcError::InvalidArgument(format!("invalid operation id: {}", id)))
+            OperationId::from_str(id.as_str()).map_err(|_| {
+                GrpcError::InvalidField(
+                    "operation_ids".to_string(),
+                    format!("invalid operation id: {}", id),
+                )
+            })
         })
         .collect::<Result<_, _>>()?;
 
@@ -782,15 +869,44 @@ pub(crate) fn get_sc_execution_events(
     grpc: &MassaPublicGrpc,
     request: tonic::Request<grpc_api::GetScExecutionEventsRequest>,
 ) -> Result<grpc_api::GetScExecutionEventsResponse, GrpcError> {
-    let event_filter = to_event_filter(request.into_inner().filters)?;
-    let events: Vec<grpc_model::ScExecutionEvent> = grpc
+    let inner_req = request.into_inner();
+    let sort_by = inner_req.sort_by();
+    let event_filter = to_event_filter(inner_req.filters)?;
+    let mut events = grpc
         .execution_controller
-        .get_filtered_sc_output_event(event_filter)
+        .get_filtered_sc_output_event(event_filter);
+
+    // events are returned by the event store in their emission order
+    match sort_by {
+        grpc_api::ScExecutionEventsSortBy::Unspecified => {}
+        grpc_api::ScExecutionEventsSortBy::Slot => {
+            events.sort_by_key(|event| (event.context.slot, event.context.index_in_slot))
+        }
+        grpc_api::ScExecutionEventsSortBy::Emitter => events.sort_by_key(|event| {
+            (
+                event.context.call_stack.back().copied(),
+                event.context.slot,
+                event.context.index_in_slot,
+            )
+        }),
+    }
+    if inner_req.descending {
+        events.reverse();
+    }
+
+    // paginate
+    let total_count = events.len() as u64;
+    let events: Vec<grpc_model::ScExecutionEvent> = events
         .into_iter()
+        .skip(usize::try_from(inner_req.offset).unwrap_or(usize::MAX))
+        .take(inner_req.limit.map_or(usize::MAX, |limit| limit as usize))
         .map(|event| event.into())
         .collect();
 
-    Ok(grpc_api::GetScExecutionEventsResponse { events })
+    Ok(grpc_api::GetScExecutionEventsResponse {
+        events,
+        total_count,
+    })
 }
 
 //  Get selector draws
@@ -822,7 +938,10 @@ pub(crate) fn get_selector_draws(
                     let addresses = addresses_filter.get_or_insert_with(PreHashSet::default);
                     for address in addrs.addresses {
                         addresses.insert(Address::from_str(&address).map_err(|_| {
-                            GrpcError::InvalidArgument(format!("invalid address: {}", address))
+                            GrpcError::InvalidField(
+                                "filters.addresses".to_string(),
+                                format!("invalid address: {}", address),
+                            )
                         })?);
                     }
                 }
@@ -910,6 +1029,28 @@ pub(crate) fn get_selector_draws(
     })
 }
 
+/// Get the gas usage, fullness and execution time of the latest final slots
+pub(crate) fn get_slot_execution_stats(
+    grpc: &MassaPublicGrpc,
+    request: tonic::Request<grpc_api::GetSlotExecutionStatsRequest>,
+) -> Result<grpc_api::GetSlotExecutionStatsResponse, GrpcError> {
+    let last_slot_count = request.into_inner().last_slot_count;
+    if last_slot_count == 0 {
+        return Err(GrpcError::InvalidArgument(
+            "last_slot_count must be positive".to_string(),
+        ));
+    }
+
+    let stats = grpc
+        .execution_controller
+        .get_slot_execution_stats(last_slot_count as usize)
+        .into_iter()
+        .map(|(slot, stats)| to_slot_execution_stats(slot, stats))
+        .collect();
+
+    Ok(grpc_api::GetSlotExecutionStatsResponse { stats })
+}
+
 //  Get status
 pub(crate) fn get_status(
     grpc: &MassaPublicGrpc,
@@ -967,6 +1108,71 @@ pub(crate) fn get_status(
     })
 }
 
+/// Get the synchronization status of the node
+///
+/// The gRPC API is only served once the node has bootstrapped, so the node is reported as
+/// synced when its active execution cursor is less than a period behind the wall-clock slot,
+/// and as syncing otherwise.
+pub(crate) fn get_sync_status(
+    grpc: &MassaPublicGrpc,
+    _request: tonic::Request<grpc_api::GetSyncStatusRequest>,
+) -> Result<grpc_api::GetSyncStatusResponse, GrpcError> {
+    let thread_count = grpc.grpc_config.thread_count;
+    let t0 = grpc.grpc_config.t0;
+    let current_slot = get_latest_block_slot_at_timestamp(
+        thread_count,
+        t0,
+        grpc.grpc_config.genesis_timestamp,
+        MassaTime::now(),
+    )?
+    .unwrap_or_else(|| Slot::new(0, 0));
+    let stats = grpc.execution_controller.get_stats();
+    let (_, peers) = grpc.protocol_controller.get_stats()?;
+
+    // number of slots the node still has to process to catch up with the wall clock
+    let lag = slot_count_in_range(stats.active_cursor, current_slot, thread_count).unwrap_or(0);
+    let (state, estimated_remaining_time) = if lag < thread_count as u64 {
+        (grpc_api::SyncState::Synced, Some(MassaTime::from_millis(0)))
+    } else {
+        (
+            grpc_api::SyncState::Syncing,
+            estimate_sync_time(&stats, lag, thread_count, t0),
+        )
+    };
+
+    Ok(grpc_api::GetSyncStatusResponse {
+        state: state as i32,
+        last_final_slot: Some(stats.final_cursor.into()),
+        current_slot: Some(current_slot.into()),
+        estimated_remaining_time: estimated_remaining_time.map(|time| time.into()),
+        connected_peer_count: peers.len() as u64,
+    })
+}
+
+/// Estimates the time needed to process `lag` slots, from the rate at which blocks were
+/// finalized during the execution stats time window and the rate at which new slots happen.
+/// Returns None if the node is not catching up.
+fn estimate_sync_time(
+    stats: &ExecutionStats,
+    lag: u64,
+    thread_count: u8,
+    t0: MassaTime,
+) -> Option<MassaTime> {
+    let window = stats
+        .time_window_end
+        .saturating_sub(stats.time_window_start)
+        .as_millis() as u128;
+    // rates of processed blocks and of new slots, both multiplied by `window * t0`
+    let processed = (stats.final_block_count as u128).checked_mul(t0.as_millis() as u128)?;
+    let produced = (thread_count as u128).checked_mul(window)?;
+    let catch_up = processed.checked_sub(produced).filter(|rate| *rate > 0)?;
+    let remaining = (lag as u128)
+        .checked_mul(window)?
+        .checked_mul(t0.as_millis() as u128)?
+        / catch_up;
+    Some(MassaTime::from_millis(u64::try_from(remaining).ok()?))
+}
+
 /// Get transactions throughput
 pub(crate) fn get_transactions_throughput(
     grpc: &MassaPublicGrpc,
@@ -987,6 +1193,37 @@ pub(crate) fn get_transactions_throughput(
     Ok(grpc_api::GetTransactionsThroughputResponse { throughput })
 }
 
+/// Get a page of the entries of the final ledger, in address order
+pub(crate) fn iter_ledger(
+    grpc: &MassaPublicGrpc,
+    request: tonic::Request<grpc_api::IterLedgerRequest>,
+) -> Result<grpc_api::IterLedgerResponse, GrpcError> {
+    let inner_req = request.into_inner();
+    let max_page_size = grpc.grpc_config.max_ledger_entries_per_request;
+    if inner_req.page_size == 0 || inner_req.page_size > max_page_size {
+        return Err(GrpcError::InvalidArgument(format!(
+            "page_size must be between 1 and {}",
+            max_page_size
+        )));
+    }
+    let page_token = inner_req
+        .page_token
+        .map(|token| {
+            Address::from_str(&token)
+                .map_err(|_| GrpcError::InvalidArgument(format!("invalid page token: {}", token)))
+        })
+        .transpose()?;
+
+    let (entries, next_page_token) = grpc
+        .execution_controller
+        .iter_ledger(page_token, inner_req.page_size as usize);
+
+    Ok(grpc_api::IterLedgerResponse {
+        entries: entries.into_iter().map(Into::into).collect(),
+        next_page_token: next_page_token.map(|address| address.to_string()),
+    })
+}
+
 /// Get query state
 pub(crate) fn query_state(
     grpc: &MassaPublicGrpc,
@@ -1056,7 +1293,10 @@ pub(crate) fn search_blocks(
                     let block_ids = block_ids_filter.get_or_insert_with(PreHashSet::default);
                     for block_id in ids.block_ids {
                         block_ids.insert(BlockId::from_str(&block_id).map_err(|_| {
-                            GrpcError::InvalidArgument(format!("invalid block id: {}", block_id))
+                            GrpcError::InvalidField(
+                                "filters.block_ids".to_string(),
+                                format!("invalid block id: {}", block_id),
+                            )
                         })?);
                     }
                 }
@@ -1070,7 +1310,10 @@ pub(crate) fn search_blocks(
                     let addresses = addresses_filter.get_or_insert_with(PreHashSet::default);
                     for address in addrs.addresses {
                         addresses.insert(Address::from_str(&address).map_err(|_| {
-                            GrpcError::InvalidArgument(format!("invalid address: {}", address))
+                            GrpcError::InvalidField(
+                                "filters.addresses".to_string(),
+                                format!("invalid address: {}", address),
+                            )
                         })?);
                     }
                 }
@@ -1215,7 +1458,10 @@ pub(crate) fn search_endorsements(
                         endorsement_ids_filter.get_or_insert_with(PreHashSet::default);
                     for id in ids.endorsement_ids {
                         endorsement_ids.insert(EndorsementId::from_str(&id).map_err(|_| {
-                            GrpcError::InvalidArgument(format!("invalid endorsement id: {}", id))
+                            GrpcError::InvalidField(
+                                "filters.endorsement_ids".to_string(),
+                                format!("invalid endorsement id: {}", id),
+                            )
                         })?);
                     }
                 }
@@ -1229,7 +1475,10 @@ pub(crate) fn search_endorsements(
                     let addresses = addresses_filter.get_or_insert_with(PreHashSet::default);
                     for address in addrs.addresses {
                         addresses.insert(Address::from_str(&address).map_err(|_| {
-                            GrpcError::InvalidArgument(format!("invalid address: {}", address))
+                            GrpcError::InvalidField(
+                                "filters.addresses".to_string(),
+                                format!("invalid address: {}", address),
+                            )
                         })?);
                     }
                 }
@@ -1243,7 +1492,10 @@ pub(crate) fn search_endorsements(
                     let block_ids = block_ids_filter.get_or_insert_with(PreHashSet::default);
                     for block_id in ids.block_ids {
                         block_ids.insert(BlockId::from_str(&block_id).map_err(|_| {
-                            GrpcError::InvalidArgument(format!("invalid block id: {}", block_id))
+                            GrpcError::InvalidField(
+                                "filters.block_ids".to_string(),
+                                format!("invalid block id: {}", block_id),
+                            )
                         })?);
                     }
                 }
@@ -1421,7 +1673,10 @@ pub(crate) fn search_operations(
                         operation_ids_filter.get_or_insert_with(PreHashSet::default);
                     for id in ids.operation_ids {
                         operation_ids.insert(OperationId::from_str(&id).map_err(|_| {
-                            GrpcError::InvalidArgument(format!("invalid operation id: {}", id))
+                            GrpcError::InvalidField(
+                                "filters.operation_ids".to_string(),
+                                format!("invalid operation id: {}", id),
+                            )
                         })?);
                     }
                 }
@@ -1435,7 +1690,10 @@ pub(crate) fn search_operations(
                     let addresses = addresses_filter.get_or_insert_with(PreHashSet::default);
                     for address in addrs.addresses {
                         addresses.insert(Address::from_str(&address).map_err(|_| {
-                            GrpcError::InvalidArgument(format!("invalid address: {}", address))
+                            GrpcError::InvalidField(
+                                "filters.addresses".to_string(),
+                                format!("invalid address: {}", address),
+                            )
                         })?);
                     }
                 }
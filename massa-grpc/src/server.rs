@@ -10,12 +10,18 @@ use std::path::Path;
 use std::sync::{Arc, Condvar, Mutex};
 
 use crate::config::{GrpcConfig, ServiceName};
+use crate::deadline::GrpcDeadlineLayer;
 use crate::error::GrpcError;
+use crate::message_size::{GrpcMessageSizeLayer, MessageSizeLimits};
+use crate::metrics::GrpcMetricsLayer;
+use crate::stream::journal::EventJournals;
+use crate::stream::limiter::StreamLimiter;
 use futures_util::FutureExt;
 use hyper::service::Service;
 use hyper::{Body, Method, Request, Response};
 use massa_consensus_exports::{ConsensusBroadcasts, ConsensusController};
 use massa_execution_exports::{ExecutionChannels, ExecutionController};
+use massa_metrics::MassaMetrics;
 use massa_pool_exports::{PoolBroadcasts, PoolController};
 use massa_pos_exports::SelectorController;
 use massa_proto_rs::massa::api::v1::{
@@ -29,6 +35,10 @@ use massa_storage::Storage;
 use massa_wallet::Wallet;
 
 use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tokio::time::{timeout_at, Instant};
+#[cfg(unix)]
+use tokio_stream::wrappers::UnixListenerStream;
 use tonic::body::BoxBody;
 use tonic::codegen::CompressionEncoding;
 use tonic::transport::NamedService;
@@ -66,14 +76,18 @@ pub struct MassaPrivateGrpc {
     pub version: massa_models::version::Version,
     /// white/black list of bootstrap
     pub bs_white_black_list: Option<SharedWhiteBlackList<'static>>,
+    /// node metrics
+    pub massa_metrics: MassaMetrics,
 }
 
 impl MassaPrivateGrpc {
     /// Start the gRPC PRIVATE API
     pub async fn serve(self, config: &GrpcConfig) -> Result<StopHandle, GrpcError> {
+        let massa_metrics = self.massa_metrics.clone();
+        let limits = message_size_limits(config);
         let mut service = PrivateServiceServer::new(self)
-            .max_decoding_message_size(config.max_decoding_message_size)
-            .max_encoding_message_size(config.max_encoding_message_size);
+            .max_decoding_message_size(limits.service_decoding_limit())
+            .max_encoding_message_size(limits.service_encoding_limit());
 
         if let Some(encoding) = &config.accept_compressed {
             if encoding.eq_ignore_ascii_case("Gzip") {
@@ -87,7 +101,7 @@ impl MassaPrivateGrpc {
             };
         }
 
-        serve(service, config).await
+        serve(service, config, massa_metrics).await
     }
 }
 
@@ -122,14 +136,27 @@ pub struct MassaPublicGrpc {
     pub version: massa_models::version::Version,
     /// keypair factory
     pub keypair_factory: KeyPairFactory,
+    /// limit of open streams per client IP
+    pub stream_limiter: StreamLimiter,
+    /// journals of the streamed events
+    pub event_journals: EventJournals,
+    /// node metrics
+    pub massa_metrics: MassaMetrics,
 }
 
 impl MassaPublicGrpc {
     /// Start the gRPC PUBLIC API
     pub async fn serve(self, config: &GrpcConfig) -> Result<StopHandle, GrpcError> {
+        let massa_metrics = self.massa_metrics.clone();
+        self.event_journals.start(
+            &self.consensus_broadcasts,
+            &self.pool_broadcasts,
+            &self.execution_channels,
+        );
+        let limits = message_size_limits(config);
         let mut service = PublicServiceServer::new(self)
-            .max_decoding_message_size(config.max_decoding_message_size)
-            .max_encoding_message_size(config.max_encoding_message_size);
+            .max_decoding_message_size(limits.service_decoding_limit())
+            .max_encoding_message_size(limits.service_encoding_limit());
 
         if let Some(encoding) = &config.accept_compressed {
             if encoding.eq_ignore_ascii_case("Gzip") {
@@ -142,13 +169,15 @@ impl MassaPublicGrpc {
                 service = service.send_compressed(CompressionEncoding::Gzip);
             };
         }
-        serve(service, config).await
+        serve(service, config, massa_metrics).await
     }
 }
 
 /// Used to be able to stop the gRPC API
 pub struct StopHandle {
     stop_cmd_sender: oneshot::Sender<()>,
+    /// tasks of the servers, finished once all their connections are closed
+    servers: Vec<JoinHandle<Result<(), tonic::transport::Error>>>,
 }
 
 impl StopHandle {
@@ -160,6 +189,30 @@ impl StopHandle {
             info!("gRPC API stop signal sent successfully");
         }
     }
+
+    /// Stop the gRPC API and drain its connections: they receive a GOAWAY, and their in-flight
+    /// requests and streams are given until `deadline` to finish before being cancelled
+    pub async fn stop_and_drain(self, deadline: Instant) {
+        let StopHandle {
+            stop_cmd_sender,
+            servers,
+        } = self;
+        if let Err(e) = stop_cmd_sender.send(()) {
+            warn!("gRPC API thread panicked: {:?}", e);
+            return;
+        }
+        for mut server in servers {
+            match timeout_at(deadline, &mut server).await {
+                Ok(Ok(Ok(()))) => {}
+                Ok(Ok(Err(e))) => warn!("gRPC API server error: {}", e),
+                Ok(Err(e)) => warn!("gRPC API thread panicked: {:?}", e),
+                Err(_) => {
+                    warn!("gRPC API connections still open after the grace period, closing them");
+                    server.abort();
+                }
+            }
+        }
+    }
 }
 
 /// Massa service health check implementation
@@ -171,7 +224,11 @@ async fn massa_service_status(mut reporter: HealthReporter) {
 }
 
 // Configure and start the gRPC API with the given service
-async fn serve<S>(service: S, config: &GrpcConfig) -> Result<StopHandle, GrpcError>
+async fn serve<S>(
+    service: S,
+    config: &GrpcConfig,
+    massa_metrics: MassaMetrics,
+) -> Result<StopHandle, GrpcError>
 where
     S: Service<Request<Body>, Response = Response<BoxBody>, Error = Infallible>
         + NamedService
@@ -234,11 +291,13 @@ where
         }
     }
 
+    let file_descriptor_set = match config.name {
+        ServiceName::Public => FILE_DESCRIPTOR_SET_PUBLIC,
+        ServiceName::Private => FILE_DESCRIPTOR_SET_PRIVATE,
+    };
+    let metrics_layer = GrpcMetricsLayer::new(massa_metrics, file_descriptor_set);
+
     let reflection_service_opt = if config.enable_reflection {
-        let file_descriptor_set = match config.name {
-            ServiceName::Public => FILE_DESCRIPTOR_SET_PUBLIC,
-            ServiceName::Private => FILE_DESCRIPTOR_SET_PRIVATE,
-        };
         let reflection_service = tonic_reflection::server::Builder::configure()
             .register_encoded_file_descriptor_set(file_descriptor_set)
             .build()?;
@@ -260,6 +319,28 @@ where
         None
     };
 
+    // the TCP and Unix socket servers are stopped by the same signal
+    let shutdown = shutdown_recv.map(drop).shared();
+    let mut servers = Vec::new();
+
+    #[cfg(unix)]
+    let mut uds_incoming = match &config.uds_path {
+        Some(path) => {
+            let incoming = bind_unix_socket(path, config.uds_permissions)?;
+            info!(
+                "gRPC | {:?} | listening on Unix socket: {}",
+                config.name,
+                path.display()
+            );
+            Some(incoming)
+        }
+        None => None,
+    };
+    #[cfg(not(unix))]
+    if config.uds_path.is_some() {
+        warn!("gRPC Unix socket listener is only available on Unix systems, ignoring `uds_path`");
+    }
+
     if config.accept_http1 {
         if config.enable_cors {
             let cors = CorsLayer::new()
@@ -269,43 +350,124 @@ where
                 .allow_origin(Any)
                 .allow_headers(Any);
 
-            let router_with_http1 = server_builder
-                .accept_http1(true)
-                .layer(cors)
-                .layer(GrpcWebLayer::new())
-                .add_optional_service(reflection_service_opt)
-                .add_optional_service(health_service_opt)
-                .add_service(service);
+            let router_with_http1 = || {
+                server_builder
+                    .clone()
+                    .accept_http1(true)
+                    .layer(cors.clone())
+                    .layer(GrpcWebLayer::new())
+                    .layer(metrics_layer.clone())
+                    .layer(GrpcMessageSizeLayer::new(message_size_limits(config)))
+                    .layer(GrpcDeadlineLayer::new(config.method_timeouts.clone()))
+                    .add_optional_service(reflection_service_opt.clone())
+                    .add_optional_service(health_service_opt.clone())
+                    .add_service(service.clone())
+            };
 
-            tokio::spawn(
-                router_with_http1.serve_with_shutdown(config.bind, shutdown_recv.map(drop)),
-            );
+            servers.push(tokio::spawn(
+                router_with_http1().serve_with_shutdown(config.bind, shutdown.clone()),
+            ));
+            #[cfg(unix)]
+            if let Some(incoming) = uds_incoming.take() {
+                servers.push(tokio::spawn(
+                    router_with_http1().serve_with_incoming_shutdown(incoming, shutdown.clone()),
+                ));
+            }
         } else {
-            let router_with_http1 = server_builder
-                .accept_http1(true)
-                .layer(GrpcWebLayer::new())
-                .add_optional_service(reflection_service_opt)
-                .add_optional_service(health_service_opt)
-                .add_service(service);
-
-            tokio::spawn(
-                router_with_http1.serve_with_shutdown(config.bind, shutdown_recv.map(drop)),
-            );
+            let router_with_http1 = || {
+                server_builder
+                    .clone()
+                    .accept_http1(true)
+                    .layer(GrpcWebLayer::new())
+                    .layer(metrics_layer.clone())
+                    .layer(GrpcMessageSizeLayer::new(message_size_limits(config)))
+                    .layer(GrpcDeadlineLayer::new(config.method_timeouts.clone()))
+                    .add_optional_service(reflection_service_opt.clone())
+                    .add_optional_service(health_service_opt.clone())
+                    .add_service(service.clone())
+            };
+
+            servers.push(tokio::spawn(
+                router_with_http1().serve_with_shutdown(config.bind, shutdown.clone()),
+            ));
+            #[cfg(unix)]
+            if let Some(incoming) = uds_incoming.take() {
+                servers.push(tokio::spawn(
+                    router_with_http1().serve_with_incoming_shutdown(incoming, shutdown.clone()),
+                ));
+            }
         }
     } else {
-        let router = server_builder
-            .add_optional_service(reflection_service_opt)
-            .add_optional_service(health_service_opt)
-            .add_service(service);
+        let router = || {
+            server_builder
+                .clone()
+                .layer(metrics_layer.clone())
+                .layer(GrpcMessageSizeLayer::new(message_size_limits(config)))
+                .layer(GrpcDeadlineLayer::new(config.method_timeouts.clone()))
+                .add_optional_service(reflection_service_opt.clone())
+                .add_optional_service(health_service_opt.clone())
+                .add_service(service.clone())
+        };
 
-        tokio::spawn(router.serve_with_shutdown(config.bind, shutdown_recv.map(drop)));
+        servers.push(tokio::spawn(
+            router().serve_with_shutdown(config.bind, shutdown.clone()),
+        ));
+        #[cfg(unix)]
+        if let Some(incoming) = uds_incoming.take() {
+            servers.push(tokio::spawn(
+                router().serve_with_incoming_shutdown(incoming, shutdown.clone()),
+            ));
+        }
     }
 
     Ok(StopHandle {
         stop_cmd_sender: shutdown_send,
+        servers,
     })
 }
 
+// Max message sizes of the methods of the service
+fn message_size_limits(config: &GrpcConfig) -> MessageSizeLimits {
+    MessageSizeLimits {
+        default_decoding: config.max_decoding_message_size,
+        default_encoding: config.max_encoding_message_size,
+        decoding_per_method: config.max_decoding_message_size_per_method.clone(),
+        encoding_per_method: config.max_encoding_message_size_per_method.clone(),
+    }
+}
+
+// Bind a Unix socket listener at `path`, restricting its access with the given file permissions
+#[cfg(unix)]
+fn bind_unix_socket(path: &Path, permissions: u32) -> Result<UnixListenerStream, GrpcError> {
+    use std::os::unix::fs::PermissionsExt;
+
+    // remove the socket left by a previous run
+    if path.exists() {
+        std::fs::remove_file(path).map_err(|e| {
+            GrpcError::InternalServerError(format!(
+                "failed to remove existing Unix socket {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+    }
+    let listener = tokio::net::UnixListener::bind(path).map_err(|e| {
+        GrpcError::InternalServerError(format!(
+            "failed to bind Unix socket {}: {}",
+            path.display(),
+            e
+        ))
+    })?;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(permissions)).map_err(|e| {
+        GrpcError::InternalServerError(format!(
+            "failed to set permissions of Unix socket {}: {}",
+            path.display(),
+            e
+        ))
+    })?;
+    Ok(UnixListenerStream::new(listener))
+}
+
 // Generate self signed certificates
 fn generate_self_signed_certificates(config: &GrpcConfig) {
     let ca_cert = gen_cert_for_ca().expect("error, failed to generate CA cert");
@@ -0,0 +1,173 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::error::GrpcError;
+use massa_consensus_exports::ConsensusBroadcasts;
+use massa_execution_exports::{ExecutionChannels, SlotExecutionOutput};
+use massa_models::block::SecureShareBlock;
+use massa_models::operation::SecureShareOperation;
+use massa_pool_exports::PoolBroadcasts;
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::warn;
+
+/// Broadcast events numbered with a monotonically increasing sequence number,
+/// the latest ones being kept to be replayed to the streams resuming from a cursor
+pub struct EventJournal<T> {
+    /// recorded events
+    inner: Arc<Mutex<JournalInner<T>>>,
+    /// broadcast of the numbered events
+    sender: broadcast::Sender<(u64, T)>,
+}
+
+struct JournalInner<T> {
+    /// sequence number of the next event
+    next_sequence: u64,
+    /// latest events, by increasing sequence number
+    events: VecDeque<(u64, T)>,
+    /// max number of events kept
+    capacity: usize,
+}
+
+impl<T> Clone for EventJournal<T> {
+    fn clone(&self) -> Self {
+        EventJournal {
+            inner: self.inner.clone(),
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl<T: Clone + Send + 'static> EventJournal<T> {
+    /// Creates a journal keeping the last `capacity` events
+    pub fn new(capacity: usize, channel_size: usize) -> Self {
+        let (sender, _) = broadcast::channel(channel_size.max(1));
+        EventJournal {
+            inner: Arc::new(Mutex::new(JournalInner {
+                next_sequence: 0,
+                events: VecDeque::with_capacity(capacity),
+                capacity,
+            })),
+            sender,
+        }
+    }
+
+    /// Numbers, keeps and broadcasts a new event. Returns its sequence number.
+    pub fn record(&self, event: T) -> u64 {
+        let mut inner = self.inner.lock();
+        let sequence = inner.next_sequence;
+        inner.next_sequence += 1;
+        if inner.capacity > 0 {
+            if inner.events.len() == inner.capacity {
+                inner.events.pop_front();
+            }
+            inner.events.push_back((sequence, event.clone()));
+        }
+        // sent while locked so that replayed and live events are numbered in the same order
+        let _ = self.sender.send((sequence, event));
+        sequence
+    }
+
+    /// Skips `count` sequence numbers, for the events missed by the journal itself
+    fn skip(&self, count: u64) {
+        let mut inner = self.inner.lock();
+        inner.next_sequence = inner.next_sequence.saturating_add(count);
+    }
+
+    /// Subscribes to the events recorded from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<(u64, T)> {
+        self.sender.subscribe()
+    }
+
+    /// Returns the kept events following the `cursor` sequence number.
+    /// Fails if some of these events are not kept anymore, or if the cursor is unknown.
+    pub fn events_after(&self, cursor: u64) -> Result<Vec<(u64, T)>, GrpcError> {
+        let inner = self.inner.lock();
+        if cursor >= inner.next_sequence {
+            return Err(GrpcError::InvalidField(
+                "resume_from".to_string(),
+                format!(
+                    "unknown cursor {}: only {} events were recorded since the node started",
+                    cursor, inner.next_sequence
+                ),
+            ));
+        }
+        let oldest = inner
+            .events
+            .front()
+            .map(|(sequence, _)| *sequence)
+            .unwrap_or(inner.next_sequence);
+        if cursor.saturating_add(1) < oldest {
+            return Err(GrpcError::InvalidField(
+                "resume_from".to_string(),
+                format!(
+                    "the events following cursor {} are not available anymore, the oldest kept event is {}",
+                    cursor, oldest
+                ),
+            ));
+        }
+        Ok(inner
+            .events
+            .iter()
+            .filter(|(sequence, _)| *sequence > cursor)
+            .cloned()
+            .collect())
+    }
+
+    /// Records the events broadcast by `sender` until it is closed
+    fn record_from(&self, sender: &broadcast::Sender<T>) {
+        let journal = self.clone();
+        let mut receiver = sender.subscribe();
+        tokio::spawn(async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => {
+                        journal.record(event);
+                    }
+                    Err(RecvError::Lagged(count)) => {
+                        warn!("event journal lagged, {} events were missed", count);
+                        journal.skip(count);
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        });
+    }
+}
+
+/// Journals of the events streamed by the public API
+#[derive(Clone)]
+pub struct EventJournals {
+    /// new blocks
+    pub blocks: EventJournal<SecureShareBlock>,
+    /// new operations
+    pub operations: EventJournal<SecureShareOperation>,
+    /// new slot execution outputs
+    pub slot_execution_outputs: EventJournal<SlotExecutionOutput>,
+}
+
+impl EventJournals {
+    /// Creates journals keeping the last `capacity` events of each kind
+    pub fn new(capacity: usize, channel_size: usize) -> Self {
+        EventJournals {
+            blocks: EventJournal::new(capacity, channel_size),
+            operations: EventJournal::new(capacity, channel_size),
+            slot_execution_outputs: EventJournal::new(capacity, channel_size),
+        }
+    }
+
+    /// Starts recording the events broadcast by the node components
+    pub(crate) fn start(
+        &self,
+        consensus_broadcasts: &ConsensusBroadcasts,
+        pool_broadcasts: &PoolBroadcasts,
+        execution_channels: &ExecutionChannels,
+    ) {
+        self.blocks.record_from(&consensus_broadcasts.block_sender);
+        self.operations
+            .record_from(&pool_broadcasts.operation_sender);
+        self.slot_execution_outputs
+            .record_from(&execution_channels.slot_execution_output_sender);
+    }
+}
@@ -0,0 +1,95 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::error::GrpcError;
+use futures_util::StreamExt;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::pin::Pin;
+use std::sync::Arc;
+
+/// Limits the number of streaming RPCs simultaneously opened by each remote IP
+#[derive(Clone)]
+pub struct StreamLimiter {
+    /// max number of open streams per remote IP (0 means unlimited)
+    max_streams_per_ip: usize,
+    /// number of open streams per remote IP
+    open_streams: Arc<Mutex<HashMap<IpAddr, usize>>>,
+}
+
+impl StreamLimiter {
+    /// Creates a new limiter allowing `max_streams_per_ip` open streams per remote IP (0 means unlimited)
+    pub fn new(max_streams_per_ip: usize) -> Self {
+        StreamLimiter {
+            max_streams_per_ip,
+            open_streams: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Reserves a stream slot for the given remote address.
+    /// The slot is released when the returned permit is dropped.
+    /// Requests without remote address (ex: Unix socket) are not limited.
+    pub(crate) fn acquire(
+        &self,
+        remote_addr: Option<SocketAddr>,
+    ) -> Result<Option<StreamPermit>, GrpcError> {
+        let Some(ip) = remote_addr.map(|addr| addr.ip()) else {
+            return Ok(None);
+        };
+        if self.max_streams_per_ip == 0 {
+            return Ok(None);
+        }
+        let mut open_streams = self.open_streams.lock();
+        let count = open_streams.entry(ip).or_default();
+        if *count >= self.max_streams_per_ip {
+            return Err(GrpcError::ResourceExhausted(format!(
+                "too many open streams for {}. Only a maximum of {} streams are accepted per IP",
+                ip, self.max_streams_per_ip
+            )));
+        }
+        *count += 1;
+        Ok(Some(StreamPermit {
+            ip,
+            open_streams: self.open_streams.clone(),
+        }))
+    }
+
+    /// Returns the number of streams currently opened by `ip`
+    pub fn open_streams(&self, ip: &IpAddr) -> usize {
+        self.open_streams
+            .lock()
+            .get(ip)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+/// Stream slot reserved for a remote IP, released on drop
+pub(crate) struct StreamPermit {
+    ip: IpAddr,
+    open_streams: Arc<Mutex<HashMap<IpAddr, usize>>>,
+}
+
+impl Drop for StreamPermit {
+    fn drop(&mut self) {
+        let mut open_streams = self.open_streams.lock();
+        if let Some(count) = open_streams.get_mut(&self.ip) {
+            *count = count.saturating_sub(1);
+            if *count == 0 {
+                open_streams.remove(&self.ip);
+            }
+        }
+    }
+}
+
+/// Ties a permit to a response stream, so that the stream slot is released
+/// once the stream is dropped (completed or client disconnected)
+pub(crate) fn with_permit<T: 'static>(
+    stream: Pin<Box<dyn futures_util::Stream<Item = T> + Send + 'static>>,
+    permit: Option<StreamPermit>,
+) -> Pin<Box<dyn futures_util::Stream<Item = T> + Send + 'static>> {
+    Box::pin(stream.map(move |item| {
+        let _permit = &permit;
+        item
+    }))
+}
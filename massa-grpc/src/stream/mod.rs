@@ -1,5 +1,9 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
 
+/// sequence numbered events replayed to the resuming streams
+pub mod journal;
+/// limit of open streams per client IP
+pub mod limiter;
 /// stream new blocks
 pub mod new_blocks;
 /// stream new endorsements
@@ -8,6 +12,8 @@ pub mod new_endorsements;
 pub mod new_filled_blocks;
 /// subscribe new operations
 pub mod new_operations;
+/// subscribe blockclique reorganizations
+pub mod new_reorgs;
 /// subscribe new slot abi call stacks
 pub mod new_slot_abi_call_stacks;
 /// subscribe new slot execution outputs
@@ -20,5 +26,7 @@ pub mod send_blocks;
 pub mod send_endorsements;
 /// send operations
 pub mod send_operations;
+/// subscribe slot ticks
+pub mod slot_ticks;
 /// subscribe tx througput
 pub mod tx_throughput;
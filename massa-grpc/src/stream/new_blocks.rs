@@ -47,13 +47,15 @@ pub(crate) async fn new_blocks(
     let (tx, rx) = tokio::sync::mpsc::channel(grpc.grpc_config.max_channel_size);
     // Get the inner stream from the request
     let mut in_stream = request.into_inner();
-    // Subscribe to the new blocks channel
-    let mut subscriber = grpc.consensus_broadcasts.block_sender.subscribe();
+    // Subscribe to the new blocks journal
+    let journal = grpc.event_journals.blocks.clone();
+    let mut subscriber = journal.subscribe();
     // Clone grpc to be able to use it in the spawned task
     let grpc_config = grpc.grpc_config.clone();
 
     tokio::spawn(async move {
         if let Some(Ok(request)) = in_stream.next().await {
+            let resume_from = request.resume_from;
             let mut filters = match get_filter(request, &grpc_config) {
                 Ok(filter) => filter,
                 Err(err) => {
@@ -66,19 +68,55 @@ pub(crate) async fn new_blocks(
                 }
             };
 
+            // Replay the blocks following the cursor before sending the new ones
+            let mut last_sequence = None;
+            if let Some(cursor) = resume_from {
+                let missed_blocks = match journal.events_after(cursor) {
+                    Ok(missed_blocks) => missed_blocks,
+                    Err(err) => {
+                        error!("failed to resume new blocks: {}", err);
+                        if let Err(e) = tx.send(Err(err.into())).await {
+                            error!("failed to send back NewBlocks error response: {}", e);
+                        }
+                        return;
+                    }
+                };
+                for (sequence, massa_block) in missed_blocks {
+                    last_sequence = Some(sequence);
+                    if !should_send(&massa_block, &filters, &grpc_config) {
+                        continue;
+                    }
+                    if let Err(e) = tx
+                        .send(Ok(grpc_api::NewBlocksResponse {
+                            signed_block: Some(massa_block.into()),
+                            sequence,
+                        }))
+                        .await
+                    {
+                        error!("failed to send missed block : {}", e);
+                        return;
+                    }
+                }
+            }
+
             loop {
                 select! {
                     // Receive a new block from the subscriber
                     event = subscriber.recv() => {
                         match event {
-                            Ok(massa_block) => {
+                            Ok((sequence, massa_block)) => {
+                                // Skip the blocks already replayed
+                                if last_sequence.map_or(false, |last| sequence <= last) {
+                                    continue;
+                                }
                                 // Check if the block should be sent
                                 if !should_send(&massa_block, &filters, &grpc_config) {
                                     continue;
                                 }
                                 // Send the new block through the channel
                                 if let Err(e) = tx.send(Ok(grpc_api::NewBlocksResponse {
-                                    signed_block: Some(massa_block.into())
+                                    signed_block: Some(massa_block.into()),
+                                    sequence,
                                 })).await {
                                     error!("failed to send new block : {}", e);
                                     break;
@@ -173,7 +211,10 @@ fn get_filter(
                     let block_ids = block_ids_filter.get_or_insert_with(HashSet::new);
                     for block_id in ids.block_ids {
                         block_ids.insert(BlockId::from_str(&block_id).map_err(|_| {
-                            GrpcError::InvalidArgument(format!("invalid block id: {}", block_id))
+                            GrpcError::InvalidField(
+                                "filters.block_ids".to_string(),
+                                format!("invalid block id: {}", block_id),
+                            )
                         })?);
                     }
                 }
@@ -188,7 +229,10 @@ fn get_filter(
                     let addresses = addresses_filter.get_or_insert_with(HashSet::new);
                     for address in addrs.addresses {
                         addresses.insert(Address::from_str(&address).map_err(|_| {
-                            GrpcError::InvalidArgument(format!("invalid address: {}", address))
+                            GrpcError::InvalidField(
+                                "filters.addresses".to_string(),
+                                format!("invalid address: {}", address),
+                            )
                         })?);
                     }
                 }
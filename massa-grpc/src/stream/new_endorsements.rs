@@ -174,7 +174,10 @@ fn get_filter(
                     let endorsement_ids = endorsement_ids_filter.get_or_insert_with(HashSet::new);
                     for id in ids.endorsement_ids {
                         endorsement_ids.insert(EndorsementId::from_str(&id).map_err(|_| {
-                            GrpcError::InvalidArgument(format!("invalid endorsement id: {}", id))
+                            GrpcError::InvalidField(
+                                "filters.endorsement_ids".to_string(),
+                                format!("invalid endorsement id: {}", id),
+                            )
                         })?);
                     }
                 }
@@ -188,7 +191,10 @@ fn get_filter(
                     let addresses = addresses_filter.get_or_insert_with(HashSet::new);
                     for address in addrs.addresses {
                         addresses.insert(Address::from_str(&address).map_err(|_| {
-                            GrpcError::InvalidArgument(format!("invalid address: {}", address))
+                            GrpcError::InvalidField(
+                                "filters.addresses".to_string(),
+                                format!("invalid address: {}", address),
+                            )
                         })?);
                     }
                 }
@@ -202,7 +208,10 @@ fn get_filter(
                     let block_ids = block_ids_filter.get_or_insert_with(HashSet::new);
                     for block_id in ids.block_ids {
                         block_ids.insert(BlockId::from_str(&block_id).map_err(|_| {
-                            GrpcError::InvalidArgument(format!("invalid block id: {}", block_id))
+                            GrpcError::InvalidField(
+                                "filters.block_ids".to_string(),
+                                format!("invalid block id: {}", block_id),
+                            )
                         })?);
                     }
                 }
@@ -3,9 +3,11 @@
 use crate::config::GrpcConfig;
 use crate::error::{match_for_io_error, GrpcError};
 use crate::server::MassaPublicGrpc;
+use crate::stream::new_operations::{self, get_operations_filter};
 use crate::SlotRange;
 use futures_util::StreamExt;
 use massa_models::address::Address;
+use massa_models::block::FilledBlock;
 use massa_models::block_header::BlockHeader;
 use massa_models::block_id::BlockId;
 use massa_models::secure_share::SecureShare;
@@ -37,6 +39,10 @@ struct Filter {
     addresses: Option<HashSet<Address>>,
     // Slot range to filter
     slot_ranges: Option<HashSet<SlotRange>>,
+    // Blocks must contain at least one operation matching this filter
+    operations: Option<new_operations::Filter>,
+    // Remove the operations not matching the operations filter from the sent blocks
+    strip_operations: bool,
 }
 
 /// Creates a new stream of new produced and received filled blocks
@@ -72,11 +78,14 @@ pub(crate) async fn new_filled_blocks(
                     // Receive a new filled block from the subscriber
                      event = subscriber.recv() => {
                         match event {
-                            Ok(massa_filled_block) => {
+                            Ok(mut massa_filled_block) => {
                                 // Check if the block should be sent
-                                if !should_send(&massa_filled_block.header, &filters, &grpc_config) {
+                                if !should_send(&massa_filled_block, &filters, &grpc_config) {
                                     continue;
                                 }
+                                if filters.strip_operations {
+                                    strip_operations(&mut massa_filled_block, &filters);
+                                }
                                 // Send the new filled block through the channel
                                 if let Err(e) = tx.send(Ok(grpc_api::NewFilledBlocksResponse {
                                         filled_block: Some(massa_filled_block.into())
@@ -171,7 +180,10 @@ fn get_filter(
                     let block_ids = block_ids_filter.get_or_insert_with(HashSet::new);
                     for block_id in ids.block_ids {
                         block_ids.insert(BlockId::from_str(&block_id).map_err(|_| {
-                            GrpcError::InvalidArgument(format!("invalid block id: {}", block_id))
+                            GrpcError::InvalidField(
+                                "filters.block_ids".to_string(),
+                                format!("invalid block id: {}", block_id),
+                            )
                         })?);
                     }
                 }
@@ -186,7 +198,10 @@ fn get_filter(
                     let addresses = addresses_filter.get_or_insert_with(HashSet::new);
                     for address in addrs.addresses {
                         addresses.insert(Address::from_str(&address).map_err(|_| {
-                            GrpcError::InvalidArgument(format!("invalid address: {}", address))
+                            GrpcError::InvalidField(
+                                "filters.addresses".to_string(),
+                                format!("invalid address: {}", address),
+                            )
                         })?);
                     }
                 }
@@ -213,15 +228,54 @@ fn get_filter(
         }
     }
 
+    let operations_filter = if request.operation_filters.is_empty() {
+        None
+    } else {
+        Some(get_operations_filter(
+            request.operation_filters,
+            grpc_config,
+        )?)
+    };
+
     Ok(Filter {
         block_ids: block_ids_filter,
         addresses: addresses_filter,
         slot_ranges: slot_ranges_filter,
+        operations: operations_filter,
+        strip_operations: request.strip_operations,
     })
 }
 
 // This function checks if the block should be sent
-fn should_send(
+fn should_send(filled_block: &FilledBlock, filters: &Filter, grpc_config: &GrpcConfig) -> bool {
+    if !should_send_header(&filled_block.header, filters, grpc_config) {
+        return false;
+    }
+
+    if let Some(operations_filter) = &filters.operations {
+        return filled_block.operations.iter().any(|(_, operation)| {
+            operation.as_ref().map_or(false, |op| {
+                new_operations::should_send(op, operations_filter)
+            })
+        });
+    }
+
+    true
+}
+
+// This function removes the operations not matching the operations filter from the block
+fn strip_operations(filled_block: &mut FilledBlock, filters: &Filter) {
+    if let Some(operations_filter) = &filters.operations {
+        filled_block.operations.retain(|(_, operation)| {
+            operation.as_ref().map_or(false, |op| {
+                new_operations::should_send(op, operations_filter)
+            })
+        });
+    }
+}
+
+// This function checks if the block header matches the filters
+fn should_send_header(
     signed_block_header: &SecureShare<BlockHeader, BlockId>,
     filters: &Filter,
     grpc_config: &GrpcConfig,
@@ -5,7 +5,8 @@ use crate::error::GrpcError;
 use crate::server::MassaPublicGrpc;
 use futures_util::StreamExt;
 use massa_models::address::Address;
-use massa_models::operation::{OperationId, SecureShareOperation};
+use massa_models::amount::Amount;
+use massa_models::operation::{OperationId, OperationType, SecureShareOperation};
 use massa_proto_rs::massa::api::v1::{self as grpc_api, NewOperationsRequest};
 use massa_proto_rs::massa::model::v1 as grpc_model;
 use std::collections::HashSet;
@@ -25,14 +26,24 @@ pub type NewOperationsStreamType = Pin<
 >;
 
 // Type declaration for NewOperationsFilter
-#[derive(Debug)]
-struct Filter {
+#[derive(Clone, Debug)]
+pub(crate) struct Filter {
     // Operation ids to filter
     operation_ids: Option<HashSet<OperationId>>,
     // Addresses to filter
     addresses: Option<HashSet<Address>>,
     // Operation types to filter
     operation_types: Option<HashSet<i32>>,
+    // Recipient addresses to filter
+    recipient_addresses: Option<HashSet<Address>>,
+    // Minimum transferred amount (inclusive)
+    min_amount: Option<Amount>,
+    // Maximum transferred amount (inclusive)
+    max_amount: Option<Amount>,
+    // Minimum fee (inclusive)
+    min_fee: Option<Amount>,
+    // Maximum fee (inclusive)
+    max_fee: Option<Amount>,
 }
 
 /// Creates a new stream of new produced and received operations
@@ -44,8 +55,9 @@ pub(crate) async fn new_operations(
     let (tx, rx) = tokio::sync::mpsc::channel(grpc.grpc_config.max_channel_size);
     // Get the inner stream from the request
     let mut in_stream = request.into_inner();
-    // Subscribe to the new operations channel
-    let mut subscriber = grpc.pool_broadcasts.operation_sender.subscribe();
+    // Subscribe to the new operations journal
+    let journal = grpc.event_journals.operations.clone();
+    let mut subscriber = journal.subscribe();
     // Clone grpc to be able to use it in the spawned task
     // let grpc = grpc.clone();
 
@@ -53,6 +65,7 @@ pub(crate) async fn new_operations(
 
     tokio::spawn(async move {
         if let Some(Ok(request)) = in_stream.next().await {
+            let resume_from = request.resume_from;
             // Spawn a new task for sending new operations
             let mut filters = match get_filter(request, &config) {
                 Ok(filter) => filter,
@@ -66,19 +79,54 @@ pub(crate) async fn new_operations(
                 }
             };
 
+            // Replay the operations following the cursor before sending the new ones
+            let mut last_sequence = None;
+            if let Some(cursor) = resume_from {
+                let missed_operations = match journal.events_after(cursor) {
+                    Ok(missed_operations) => missed_operations,
+                    Err(err) => {
+                        error!("failed to resume new operations: {}", err);
+                        if let Err(e) = tx.send(Err(err.into())).await {
+                            error!("failed to send back NewOperations error response: {}", e);
+                        }
+                        return;
+                    }
+                };
+                for (sequence, massa_operation) in missed_operations {
+                    last_sequence = Some(sequence);
+                    if !should_send(&massa_operation, &filters) {
+                        continue;
+                    }
+                    if let Err(e) = tx
+                        .send(Ok(grpc_api::NewOperationsResponse {
+                            signed_operation: Some(massa_operation.into()),
+                            sequence,
+                        }))
+                        .await
+                    {
+                        error!("failed to send missed operation : {}", e);
+                        return;
+                    }
+                }
+            }
+
             loop {
                 select! {
                     // Receive a new operation from the subscriber
                      event = subscriber.recv() => {
                         match event {
-                            Ok(massa_operation) => {
+                            Ok((sequence, massa_operation)) => {
+                                // Skip the operations already replayed
+                                if last_sequence.map_or(false, |last| sequence <= last) {
+                                    continue;
+                                }
                                 // Check if the operation should be sent
                                 if !should_send(&massa_operation, &filters) {
                                     continue;
                                 }
 
                                 // Send the new operation through the channel
-                                if let Err(e) = tx.send(Ok(grpc_api::NewOperationsResponse {signed_operation: Some(massa_operation.into())})).await {
+                                if let Err(e) = tx.send(Ok(grpc_api::NewOperationsResponse {signed_operation: Some(massa_operation.into()), sequence})).await {
                                     error!("failed to send operation : {}", e);
                                     break;
                                 }
@@ -133,7 +181,15 @@ fn get_filter(
     request: NewOperationsRequest,
     grpc_config: &GrpcConfig,
 ) -> Result<Filter, GrpcError> {
-    if request.filters.len() as u32 > grpc_config.max_filters_per_request {
+    get_operations_filter(request.filters, grpc_config)
+}
+
+// This function returns a filter from a list of operations filters
+pub(crate) fn get_operations_filter(
+    filters: Vec<grpc_api::NewOperationsFilter>,
+    grpc_config: &GrpcConfig,
+) -> Result<Filter, GrpcError> {
+    if filters.len() as u32 > grpc_config.max_filters_per_request {
         return Err(GrpcError::InvalidArgument(format!(
             "too many filters received. Only a maximum of {} filters are accepted per request",
             grpc_config.max_filters_per_request
@@ -143,9 +199,14 @@ fn get_filter(
     let mut operation_ids_filter: Option<HashSet<OperationId>> = None;
     let mut addresses_filter: Option<HashSet<Address>> = None;
     let mut operation_types_filter: Option<HashSet<i32>> = None;
+    let mut recipient_addresses_filter: Option<HashSet<Address>> = None;
+    let mut min_amount_filter: Option<Amount> = None;
+    let mut max_amount_filter: Option<Amount> = None;
+    let mut min_fee_filter: Option<Amount> = None;
+    let mut max_fee_filter: Option<Amount> = None;
 
     // Get params filter from the request.
-    for query in request.filters.into_iter() {
+    for query in filters.into_iter() {
         if let Some(filter) = query.filter {
             match filter {
                 grpc_api::new_operations_filter::Filter::OperationIds(ids) => {
@@ -158,7 +219,10 @@ fn get_filter(
                     let operation_ids = operation_ids_filter.get_or_insert_with(HashSet::new);
                     for id in ids.operation_ids {
                         operation_ids.insert(OperationId::from_str(&id).map_err(|_| {
-                            GrpcError::InvalidArgument(format!("invalid operation id: {}", id))
+                            GrpcError::InvalidField(
+                                "filters.operation_ids".to_string(),
+                                format!("invalid operation id: {}", id),
+                            )
                         })?);
                     }
                 }
@@ -172,7 +236,10 @@ fn get_filter(
                     let addresses = addresses_filter.get_or_insert_with(HashSet::new);
                     for address in addrs.addresses {
                         addresses.insert(Address::from_str(&address).map_err(|_| {
-                            GrpcError::InvalidArgument(format!("invalid address: {}", address))
+                            GrpcError::InvalidField(
+                                "filters.addresses".to_string(),
+                                format!("invalid address: {}", address),
+                            )
                         })?);
                     }
                 }
@@ -186,6 +253,71 @@ fn get_filter(
                     let operation_types = operation_types_filter.get_or_insert_with(HashSet::new);
                     operation_types.extend(&ope_types.op_types);
                 }
+                grpc_api::new_operations_filter::Filter::RecipientAddresses(addrs) => {
+                    if addrs.addresses.len() as u32 > grpc_config.max_addresses_per_request {
+                        return Err(GrpcError::InvalidArgument(format!(
+                            "too many recipient addresses received. Only a maximum of {} addresses are accepted per request",
+                         grpc_config.max_addresses_per_request
+                        )));
+                    }
+                    let addresses = recipient_addresses_filter.get_or_insert_with(HashSet::new);
+                    for address in addrs.addresses {
+                        addresses.insert(Address::from_str(&address).map_err(|_| {
+                            GrpcError::InvalidField(
+                                "filters.recipient_addresses".to_string(),
+                                format!("invalid address: {}", address),
+                            )
+                        })?);
+                    }
+                }
+                grpc_api::new_operations_filter::Filter::AmountRange(range) => {
+                    if let Some(min) = range.min {
+                        min_amount_filter = Some(
+                            Amount::from_mantissa_scale(min.mantissa, min.scale).map_err(|_| {
+                                GrpcError::InvalidArgument("invalid min amount".to_string())
+                            })?,
+                        );
+                    }
+                    if let Some(max) = range.max {
+                        max_amount_filter = Some(
+                            Amount::from_mantissa_scale(max.mantissa, max.scale).map_err(|_| {
+                                GrpcError::InvalidArgument("invalid max amount".to_string())
+                            })?,
+                        );
+                    }
+                    if let (Some(min), Some(max)) = (min_amount_filter, max_amount_filter) {
+                        if min > max {
+                            return Err(GrpcError::InvalidArgument(format!(
+                                "invalid amount range: min amount {} is greater than max amount {}",
+                                min, max
+                            )));
+                        }
+                    }
+                }
+                grpc_api::new_operations_filter::Filter::FeeRange(range) => {
+                    if let Some(min) = range.min {
+                        min_fee_filter = Some(
+                            Amount::from_mantissa_scale(min.mantissa, min.scale).map_err(|_| {
+                                GrpcError::InvalidArgument("invalid min fee".to_string())
+                            })?,
+                        );
+                    }
+                    if let Some(max) = range.max {
+                        max_fee_filter = Some(
+                            Amount::from_mantissa_scale(max.mantissa, max.scale).map_err(|_| {
+                                GrpcError::InvalidArgument("invalid max fee".to_string())
+                            })?,
+                        );
+                    }
+                    if let (Some(min), Some(max)) = (min_fee_filter, max_fee_filter) {
+                        if min > max {
+                            return Err(GrpcError::InvalidArgument(format!(
+                                "invalid fee range: min fee {} is greater than max fee {}",
+                                min, max
+                            )));
+                        }
+                    }
+                }
             }
         }
     }
@@ -194,11 +326,16 @@ fn get_filter(
         operation_ids: operation_ids_filter,
         addresses: addresses_filter,
         operation_types: operation_types_filter,
+        recipient_addresses: recipient_addresses_filter,
+        min_amount: min_amount_filter,
+        max_amount: max_amount_filter,
+        min_fee: min_fee_filter,
+        max_fee: max_fee_filter,
     })
 }
 
 // This function checks if the operation should be sent
-fn should_send(signed_operation: &SecureShareOperation, filters: &Filter) -> bool {
+pub(crate) fn should_send(signed_operation: &SecureShareOperation, filters: &Filter) -> bool {
     if let Some(operation_ids) = &filters.operation_ids {
         if !operation_ids.contains(&signed_operation.id) {
             return false;
@@ -218,5 +355,61 @@ fn should_send(signed_operation: &SecureShareOperation, filters: &Filter) -> boo
         }
     }
 
+    if let Some(recipient_addresses) = &filters.recipient_addresses {
+        match get_recipient(&signed_operation.content.op) {
+            Some(recipient) if recipient_addresses.contains(&recipient) => {}
+            _ => return false,
+        }
+    }
+
+    if filters.min_amount.is_some() || filters.max_amount.is_some() {
+        // operations that do not transfer coins never match an amount range
+        let Some(amount) = get_transferred_amount(&signed_operation.content.op) else {
+            return false;
+        };
+        if let Some(min) = filters.min_amount {
+            if amount < min {
+                return false;
+            }
+        }
+        if let Some(max) = filters.max_amount {
+            if amount > max {
+                return false;
+            }
+        }
+    }
+
+    if let Some(min) = filters.min_fee {
+        if signed_operation.content.fee < min {
+            return false;
+        }
+    }
+
+    if let Some(max) = filters.max_fee {
+        if signed_operation.content.fee > max {
+            return false;
+        }
+    }
+
     true
 }
+
+// Returns the address receiving coins or the call for the given operation type, if any
+fn get_recipient(op: &OperationType) -> Option<Address> {
+    match op {
+        OperationType::Transaction {
+            recipient_address, ..
+        } => Some(*recipient_address),
+        OperationType::CallSC { target_addr, .. } => Some(*target_addr),
+        _ => None,
+    }
+}
+
+// Returns the amount of coins transferred by the given operation type, if any
+fn get_transferred_amount(op: &OperationType) -> Option<Amount> {
+    match op {
+        OperationType::Transaction { amount, .. } => Some(*amount),
+        OperationType::CallSC { coins, .. } => Some(*coins),
+        _ => None,
+    }
+}
@@ -0,0 +1,66 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::{error::GrpcError, server::MassaPublicGrpc};
+use massa_proto_rs::massa::api::v1 as grpc_api;
+use std::pin::Pin;
+use tokio::select;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{error, warn};
+
+/// Type declaration for NewReorgs
+pub type NewReorgsStreamType = Pin<
+    Box<
+        dyn futures_util::Stream<Item = Result<grpc_api::NewReorgsResponse, tonic::Status>>
+            + Send
+            + 'static,
+    >,
+>;
+
+/// Creates a stream of the blockclique changes discarding blocks,
+/// for the clients to react to the rollback of their confirmations
+pub(crate) async fn new_reorgs(
+    grpc: &MassaPublicGrpc,
+    _request: tonic::Request<grpc_api::NewReorgsRequest>,
+) -> Result<NewReorgsStreamType, GrpcError> {
+    // Create a channel for sending responses to the client
+    let (tx, rx) = tokio::sync::mpsc::channel(grpc.grpc_config.max_channel_size);
+    // Subscribe to the blockclique reorganizations channel
+    let mut subscriber = grpc.consensus_broadcasts.reorg_sender.subscribe();
+
+    tokio::spawn(async move {
+        loop {
+            select! {
+                // Receive a new reorganization from the subscriber
+                event = subscriber.recv() => {
+                    match event {
+                        Ok(reorg) => {
+                            let response = grpc_api::NewReorgsResponse {
+                                old_tip_id: reorg.old_tip.to_string(),
+                                new_tip_id: reorg.new_tip.to_string(),
+                                depth: reorg.depth,
+                                discarded_block_ids: reorg
+                                    .discarded_block_ids
+                                    .iter()
+                                    .map(|block_id| block_id.to_string())
+                                    .collect(),
+                            };
+                            if let Err(e) = tx.send(Ok(response)).await {
+                                error!("failed to send new reorg : {}", e);
+                                break;
+                            }
+                        },
+                        Err(RecvError::Lagged(skipped)) => {
+                            warn!("new reorgs stream lagged, {} reorgs skipped", skipped)
+                        },
+                        Err(RecvError::Closed) => break,
+                    }
+                },
+                // Client disconnected
+                _ = tx.closed() => break,
+            }
+        }
+    });
+
+    let out_stream = tokio_stream::wrappers::ReceiverStream::new(rx);
+    Ok(Box::pin(out_stream) as NewReorgsStreamType)
+}
@@ -85,11 +85,9 @@ pub(crate) async fn new_slot_execution_outputs(
     let (tx, rx) = tokio::sync::mpsc::channel(grpc.grpc_config.max_channel_size);
     // Get the inner stream from the request
     let mut in_stream = request.into_inner();
-    // Subscribe to the new slot execution events channel
-    let mut subscriber = grpc
-        .execution_channels
-        .slot_execution_output_sender
-        .subscribe();
+    // Subscribe to the new slot execution outputs journal
+    let journal = grpc.event_journals.slot_execution_outputs.clone();
+    let mut subscriber = journal.subscribe();
     let grpc_config = grpc.grpc_config.clone();
 
     tokio::spawn(async move {
@@ -106,18 +104,59 @@ pub(crate) async fn new_slot_execution_outputs(
                 }
             };
 
+            // Replay the slot execution outputs following the cursor before sending the new ones
+            let mut last_sequence = None;
+            if let Some(cursor) = request.resume_from {
+                let missed_outputs = match journal.events_after(cursor) {
+                    Ok(missed_outputs) => missed_outputs,
+                    Err(err) => {
+                        error!("failed to resume new slot execution outputs: {}", err);
+                        if let Err(e) = tx.send(Err(err.into())).await {
+                            error!(
+                                "failed to send back NewSlotExecutionOutputs error response: {}",
+                                e
+                            );
+                        }
+                        return;
+                    }
+                };
+                for (sequence, massa_slot_execution_output) in missed_outputs {
+                    last_sequence = Some(sequence);
+                    let Some(slot_execution_output) =
+                        filter_map(massa_slot_execution_output, &filters, &grpc_config)
+                    else {
+                        continue;
+                    };
+                    if let Err(e) = tx
+                        .send(Ok(grpc_api::NewSlotExecutionOutputsResponse {
+                            output: Some(slot_execution_output.into()),
+                            sequence,
+                        }))
+                        .await
+                    {
+                        error!("failed to send missed slot execution output : {}", e);
+                        return;
+                    }
+                }
+            }
+
             loop {
                 select! {
                     // Receive a new slot execution output from the subscriber
                     event = subscriber.recv() => {
                         match event {
-                            Ok(massa_slot_execution_output) => {
+                            Ok((sequence, massa_slot_execution_output)) => {
+                                // Skip the slot execution outputs already replayed
+                                if last_sequence.map_or(false, |last| sequence <= last) {
+                                    continue;
+                                }
                                 let slot_execution_output = filter_map(massa_slot_execution_output, &filters, &grpc_config);
                                 // Check if the slot execution output should be sent
                                 if let Some(slot_execution_output) = slot_execution_output {
                                     // Send the new slot execution output through the channel
                                     if let Err(e) = tx.send(Ok(grpc_api::NewSlotExecutionOutputsResponse {
-                                            output: Some(slot_execution_output.into())
+                                            output: Some(slot_execution_output.into()),
+                                            sequence,
                                     })).await {
                                         error!("failed to send new slot execution output : {}", e);
                                         break;
@@ -10,6 +10,7 @@ use massa_proto_rs::massa::api::v1 as grpc_api;
 use massa_proto_rs::massa::model::v1 as grpc_model;
 use massa_serialization::{DeserializeError, Deserializer};
 use massa_time::MassaTime;
+use massa_versioning::versioning::MipComponent;
 use std::collections::HashMap;
 use std::io::ErrorKind;
 use std::pin::Pin;
@@ -35,6 +36,7 @@ pub(crate) async fn send_operations(
     let protocol_controller = grpc.protocol_controller.clone();
     let config = grpc.grpc_config.clone();
     let storage = grpc.storage.clone_without_refs();
+    let mip_store = grpc.keypair_factory.mip_store.clone();
 
     // Create a channel for sending responses to the client
     let (tx, rx) = tokio::sync::mpsc::channel(config.max_channel_size);
@@ -70,6 +72,8 @@ pub(crate) async fn send_operations(
                             .await;
                             continue;
                         };
+                        let execution_version = mip_store
+                            .get_latest_component_version_at(&MipComponent::Execution, now);
                         // If there are too many operations in the incoming message, send an error message back to the client
                         if req_content.operations.len() as u32 > config.max_operations_per_message {
                             report_error(
@@ -82,6 +86,7 @@ pub(crate) async fn send_operations(
                             // Deserialize and verify each operation in the incoming message
                             let operation_deserializer = SecureShareDeserializer::new(
                                 OperationDeserializer::new(
+                                    config.thread_count,
                                     config.max_datastore_value_length,
                                     config.max_function_name_length,
                                     config.max_parameter_size,
@@ -106,6 +111,9 @@ pub(crate) async fn send_operations(
                                                 },
                                                 _ => {}
                                             };
+                                            if res_operation.content.op.required_execution_version() > execution_version {
+                                                return Err(GrpcError::InvalidArgument("This type of operation is not active yet.".into()));
+                                            }
                                             if let Some(slot) = last_slot {
                                                 if res_operation.content.expire_period < slot.period {
                                                     return Err(GrpcError::InvalidArgument("Operation expire_period is lower than the current period of this node. Your operation will never be included in a block.".into()));
@@ -138,11 +146,23 @@ pub(crate) async fn send_operations(
                             match verified_ops_res {
                                 // If all operations in the incoming message are valid, store and propagate them
                                 Ok(verified_ops) => {
+                                    let operations: Vec<SecureShareOperation> =
+                                        verified_ops.values().cloned().collect();
+                                    // Reject the operations of senders over their limits in the pool
+                                    if let Err(e) = pool_controller.check_sender_limits(&operations)
+                                    {
+                                        report_error(
+                                            tx.clone(),
+                                            tonic::Code::ResourceExhausted,
+                                            e.to_string(),
+                                        )
+                                        .await;
+                                        continue;
+                                    }
                                     let mut operation_storage = storage.clone_without_refs();
-                                    operation_storage
-                                        .store_operations(verified_ops.values().cloned().collect());
-                                    // Add the received operations to the operations pool
-                                    pool_controller.add_operations(operation_storage.clone());
+                                    operation_storage.store_operations(operations);
+                                    // Add the received operations to the operations pool, which propagates them again
+                                    pool_controller.add_local_operations(operation_storage.clone());
 
                                     // Propagate the operations to the network
                                     if let Err(e) =
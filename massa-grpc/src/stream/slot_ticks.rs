@@ -0,0 +1,96 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::{error::GrpcError, server::MassaPublicGrpc};
+use massa_models::slot::Slot;
+use massa_models::timeslots::{get_block_slot_timestamp, get_closest_slot_to_timestamp};
+use massa_proto_rs::massa::api::v1 as grpc_api;
+use massa_time::MassaTime;
+use std::pin::Pin;
+use tokio::select;
+use tracing::error;
+
+/// Type declaration for SlotTicks
+pub type SlotTicksStreamType = Pin<
+    Box<
+        dyn futures_util::Stream<Item = Result<grpc_api::SlotTicksResponse, tonic::Status>>
+            + Send
+            + 'static,
+    >,
+>;
+
+/// Creates a stream emitting each new slot when the node's clock reaches it
+pub(crate) async fn slot_ticks(
+    grpc: &MassaPublicGrpc,
+    _request: tonic::Request<grpc_api::SlotTicksRequest>,
+) -> Result<SlotTicksStreamType, GrpcError> {
+    let thread_count = grpc.grpc_config.thread_count;
+    let t0 = grpc.grpc_config.t0;
+    let genesis_timestamp = grpc.grpc_config.genesis_timestamp;
+
+    // Create a channel for sending responses to the client
+    let (tx, rx) = tokio::sync::mpsc::channel(grpc.grpc_config.max_channel_size);
+
+    // Spawn a new Tokio task ticking at each slot
+    tokio::spawn(async move {
+        let mut previous_slot: Option<Slot> = None;
+        loop {
+            let (slot, timestamp) =
+                match next_slot(thread_count, t0, genesis_timestamp, previous_slot) {
+                    Ok(next) => next,
+                    Err(e) => {
+                        error!("failed to compute the next slot tick: {}", e);
+                        let _ = tx.send(Err(e.into())).await;
+                        break;
+                    }
+                };
+            let Ok(instant) = timestamp.estimate_instant() else {
+                error!("failed to estimate the instant of slot {}", slot);
+                break;
+            };
+
+            select! {
+                // wait until the slot is reached
+                _ = tokio::time::sleep_until(instant.into()) => {
+                    let response = grpc_api::SlotTicksResponse {
+                        slot: Some(slot.into()),
+                        timestamp: timestamp.as_millis(),
+                        // the last thread of a period closes it
+                        is_final_cutoff: slot.thread == thread_count.saturating_sub(1),
+                    };
+                    if let Err(e) = tx.send(Ok(response)).await {
+                        error!("failed to send back slot_ticks response: {}", e);
+                        break;
+                    }
+                    previous_slot = Some(slot);
+                },
+                // Client disconnected
+                _ = tx.closed() => break,
+            }
+        }
+    });
+
+    let out_stream = tokio_stream::wrappers::ReceiverStream::new(rx);
+    Ok(Box::pin(out_stream) as SlotTicksStreamType)
+}
+
+/// Returns the first slot after `previous_slot` that has not happened yet, and its timestamp
+fn next_slot(
+    thread_count: u8,
+    t0: MassaTime,
+    genesis_timestamp: MassaTime,
+    previous_slot: Option<Slot>,
+) -> Result<(Slot, MassaTime), GrpcError> {
+    let now = MassaTime::now();
+    let mut slot = get_closest_slot_to_timestamp(thread_count, t0, genesis_timestamp, now);
+    if get_block_slot_timestamp(thread_count, t0, genesis_timestamp, slot)? < now {
+        slot = slot.get_next_slot(thread_count)?;
+    }
+    // protection against unexpected system clock adjustments
+    if let Some(previous_slot) = previous_slot {
+        if slot <= previous_slot {
+            slot = previous_slot.get_next_slot(thread_count)?;
+        }
+    }
+    let timestamp = get_block_slot_timestamp(thread_count, t0, genesis_timestamp, slot)?;
+    Ok((slot, timestamp))
+}
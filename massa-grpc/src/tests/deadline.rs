@@ -0,0 +1,86 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::deadline::GrpcDeadlineLayer;
+use futures_util::future::BoxFuture;
+use hyper::{Request, Response};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tonic::body::BoxBody;
+use tonic::Status;
+use tower::{Layer, Service};
+
+/// Service whose handler takes `duration` to complete
+#[derive(Clone)]
+struct SlowService {
+    duration: Duration,
+    completed: Arc<AtomicBool>,
+}
+
+impl Service<Request<()>> for SlowService {
+    type Response = Response<BoxBody>;
+    type Error = Infallible;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _request: Request<()>) -> Self::Future {
+        let duration = self.duration;
+        let completed = self.completed.clone();
+        Box::pin(async move {
+            tokio::time::sleep(duration).await;
+            completed.store(true, Ordering::SeqCst);
+            Ok(Response::new(tonic::body::empty_body()))
+        })
+    }
+}
+
+fn get_status_request() -> Request<()> {
+    Request::builder()
+        .uri("/massa.api.v1.PublicService/GetStatus")
+        .body(())
+        .unwrap()
+}
+
+#[tokio::test]
+async fn deadline_exceeded_cancels_handler() {
+    let completed = Arc::new(AtomicBool::new(false));
+    let layer = GrpcDeadlineLayer::new(HashMap::from([(
+        "GetStatus".to_string(),
+        Duration::from_millis(100),
+    )]));
+    let mut service = layer.layer(SlowService {
+        duration: Duration::from_millis(500),
+        completed: completed.clone(),
+    });
+
+    let response = service.call(get_status_request()).await.unwrap();
+    let status = Status::from_header_map(response.headers()).unwrap();
+    assert_eq!(status.code(), tonic::Code::DeadlineExceeded);
+
+    // the handler would have completed by now if it was still running
+    tokio::time::sleep(Duration::from_millis(1000)).await;
+    assert!(!completed.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn handler_within_deadline_completes() {
+    let completed = Arc::new(AtomicBool::new(false));
+    let layer = GrpcDeadlineLayer::new(HashMap::from([(
+        "GetStatus".to_string(),
+        Duration::from_millis(1000),
+    )]));
+    let mut service = layer.layer(SlowService {
+        duration: Duration::from_millis(10),
+        completed: completed.clone(),
+    });
+
+    let response = service.call(get_status_request()).await.unwrap();
+    assert!(Status::from_header_map(response.headers()).is_none());
+    assert!(completed.load(Ordering::SeqCst));
+}
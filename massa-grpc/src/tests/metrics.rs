@@ -0,0 +1,41 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::metrics::GrpcMetricsLayer;
+use massa_metrics::MassaMetrics;
+use massa_proto_rs::massa::api::v1::FILE_DESCRIPTOR_SET_PUBLIC;
+
+fn labels(service: &str, method: &str) -> (String, String) {
+    (service.to_string(), method.to_string())
+}
+
+#[test]
+fn only_known_methods_are_labelled() {
+    let massa_metrics = MassaMetrics::new(
+        false,
+        "0.0.0.0:9898".parse().unwrap(),
+        32,
+        std::time::Duration::from_secs(5),
+    )
+    .0;
+    let layer = GrpcMetricsLayer::new(massa_metrics, FILE_DESCRIPTOR_SET_PUBLIC);
+
+    assert_eq!(
+        layer.labels("/massa.api.v1.PublicService/GetStatus"),
+        labels("massa.api.v1.PublicService", "GetStatus")
+    );
+    assert_eq!(
+        layer.labels("/grpc.health.v1.Health/Check"),
+        labels("grpc.health.v1.Health", "Check")
+    );
+
+    // any other path is recorded under a single label
+    for path in [
+        "/massa.api.v1.PublicService/GetStatus2",
+        "/massa.api.v1.PrivateService/GetStatus",
+        "/random/path",
+        "/GetStatus",
+        "",
+    ] {
+        assert_eq!(layer.labels(path), labels("unknown", "unknown"));
+    }
+}
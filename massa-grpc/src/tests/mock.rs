@@ -1,10 +1,14 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
+use std::collections::HashMap;
 use std::net::SocketAddr;
 
 use crate::config::{GrpcConfig, ServiceName};
 use crate::server::MassaPublicGrpc;
+use crate::stream::journal::EventJournals;
+use crate::stream::limiter::StreamLimiter;
 use massa_consensus_exports::{ConsensusBroadcasts, MockConsensusController};
 use massa_execution_exports::{ExecutionChannels, MockExecutionController};
+use massa_metrics::MassaMetrics;
 use massa_models::amount::Amount;
 use massa_models::{
     config::{
@@ -58,18 +62,25 @@ pub(crate) fn grpc_public_service(addr: &SocketAddr) -> MassaPublicGrpc {
         subject_alt_names: vec![],
         // bind: "[::]:8888".parse().unwrap(),
         bind: *addr,
+        uds_path: None,
+        uds_permissions: 0o600,
         // bind: SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8888),
         accept_compressed: None,
         send_compressed: None,
         max_decoding_message_size: 4194304,
         max_encoding_message_size: 4194304,
+        max_decoding_message_size_per_method: HashMap::new(),
+        max_encoding_message_size_per_method: HashMap::new(),
         max_gas_per_block: u32::MAX as u64,
         concurrency_limit_per_connection: 5,
         timeout: Default::default(),
+        method_timeouts: HashMap::new(),
         initial_stream_window_size: None,
         initial_connection_window_size: None,
         max_concurrent_streams: None,
         max_arguments: 128,
+        max_streams_per_ip: 0,
+        event_replay_buffer_size: 10,
         tcp_keepalive: None,
         tcp_nodelay: false,
         http2_keepalive_interval: None,
@@ -109,6 +120,7 @@ pub(crate) fn grpc_public_service(addr: &SocketAddr) -> MassaPublicGrpc {
         client_certificate_path: PathBuf::default(),
         client_private_key_path: PathBuf::default(),
         max_query_items_per_request: 50,
+        max_ledger_entries_per_request: 100,
         chain_id: *CHAINID,
         minimal_fees: Amount::zero(),
     };
@@ -125,11 +137,13 @@ pub(crate) fn grpc_public_service(addr: &SocketAddr) -> MassaPublicGrpc {
             block_sender: tokio::sync::broadcast::channel(100).0,
             block_header_sender: tokio::sync::broadcast::channel(100).0,
             filled_block_sender: tokio::sync::broadcast::channel(100).0,
+            reorg_sender: tokio::sync::broadcast::channel(100).0,
         },
         consensus_controller: consensus_ctrl,
         execution_controller: execution_ctrl,
         execution_channels: ExecutionChannels {
             slot_execution_output_sender,
+            slot_execution_serialized_output_sender: tokio::sync::broadcast::channel(5000).0,
             #[cfg(feature = "execution-trace")]
             slot_execution_traces_sender: tokio::sync::broadcast::channel(5000).0,
         },
@@ -148,5 +162,17 @@ pub(crate) fn grpc_public_service(addr: &SocketAddr) -> MassaPublicGrpc {
         keypair_factory: KeyPairFactory {
             mip_store: mip_store.clone(),
         },
+        stream_limiter: StreamLimiter::new(grpc_config.max_streams_per_ip),
+        event_journals: EventJournals::new(
+            grpc_config.event_replay_buffer_size,
+            grpc_config.max_channel_size,
+        ),
+        massa_metrics: MassaMetrics::new(
+            false,
+            "0.0.0.0:9898".parse().unwrap(),
+            32,
+            std::time::Duration::from_secs(5),
+        )
+        .0,
     }
 }
@@ -3,6 +3,10 @@
 #[cfg(test)]
 pub mod mock;
 
+#[cfg(test)]
+mod deadline;
+#[cfg(test)]
+mod metrics;
 #[cfg(test)]
 mod public;
 #[cfg(test)]
@@ -2,24 +2,28 @@
 
 use crate::tests::mock::grpc_public_service;
 use massa_consensus_exports::MockConsensusController;
-use massa_execution_exports::{EventStore, MockExecutionController};
+use massa_execution_exports::{EventStore, MockExecutionController, SlotExecutionStats};
+use massa_ledger_exports::LedgerEntryInfo;
 use massa_models::address::Address;
+use massa_models::amount::Amount;
 use massa_models::block::BlockGraphStatus;
 use massa_models::block_id::BlockId;
 use massa_models::config::VERSION;
 use massa_models::slot::Slot;
-use massa_models::stats::ExecutionStats;
+use massa_models::stats::{ExecutionStats, NetworkStats, Reachability};
+use massa_models::timeslots::get_latest_block_slot_at_timestamp;
 use massa_pool_exports::MockPoolController;
 use massa_pos_exports::{MockSelectorController, Selection};
 use massa_proto_rs::massa::api::v1::get_datastore_entry_filter::Filter;
 use massa_proto_rs::massa::api::v1::public_service_client::PublicServiceClient;
 use massa_proto_rs::massa::api::v1::{
     search_blocks_filter, AddressBalanceCandidate, ExecuteReadOnlyCallRequest,
-    ExecutionQueryRequestItem, GetBlocksRequest, GetEndorsementsRequest,
-    GetNextBlockBestParentsRequest, GetOperationsRequest, GetScExecutionEventsRequest,
-    GetSelectorDrawsRequest, GetStatusRequest, GetTransactionsThroughputRequest, QueryStateRequest,
-    SearchBlocksFilter, SearchBlocksRequest, SearchEndorsementsRequest, SearchOperationsRequest,
-    SelectorDrawsFilter,
+    ExecutionQueryRequestItem, GetBlocksBySlotRangeRequest, GetBlocksRequest,
+    GetEndorsementsRequest, GetNextBlockBestParentsRequest, GetOperationsRequest,
+    GetScExecutionEventsRequest, GetSelectorDrawsRequest, GetSlotExecutionStatsRequest,
+    GetStatusRequest, GetSyncStatusRequest, GetTransactionsThroughputRequest, IterLedgerRequest,
+    QueryStateRequest, ScExecutionEventsSortBy, SearchBlocksFilter, SearchBlocksRequest,
+    SearchEndorsementsRequest, SearchOperationsRequest, SelectorDrawsFilter, SyncState,
 };
 use massa_proto_rs::massa::model::v1::read_only_execution_call::Target;
 use massa_proto_rs::massa::model::v1::{
@@ -30,12 +34,14 @@ use massa_protocol_exports::test_exports::tools::{
     create_block, create_block_with_endorsements, create_block_with_operations, create_endorsement,
     create_operation_with_expire_period,
 };
+use massa_protocol_exports::{MockProtocolController, PeerConnectionType, PeerId};
 use massa_signature::KeyPair;
 use massa_time::MassaTime;
-use std::collections::{BTreeMap, VecDeque};
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::net::SocketAddr;
 use std::str::FromStr;
 use std::time::Duration;
+use tonic_types::StatusExt;
 
 #[tokio::test]
 async fn get_status() {
@@ -74,6 +80,221 @@ async fn get_status() {
     stop_handle.stop();
 }
 
+#[cfg(unix)]
+#[tokio::test]
+async fn get_status_unix_socket() {
+    let addr: SocketAddr = "[::]:4036".parse().unwrap();
+    let mut public_server = grpc_public_service(&addr);
+
+    let mut exec_ctrl = Box::new(MockExecutionController::new());
+    exec_ctrl
+        .expect_query_state()
+        .returning(|_| massa_execution_exports::ExecutionQueryResponse {
+            responses: vec![],
+            candidate_cursor: Slot::new(0, 2),
+            final_cursor: Slot::new(0, 0),
+            final_state_fingerprint: massa_hash::Hash::compute_from(&Vec::new()),
+        });
+    public_server.execution_controller = exec_ctrl;
+
+    let socket_path =
+        std::env::temp_dir().join(format!("massa_grpc_test_{}.sock", std::process::id()));
+    public_server.grpc_config.uds_path = Some(socket_path.clone());
+
+    let config = public_server.grpc_config.clone();
+    let stop_handle = public_server.serve(&config).await.unwrap();
+
+    // the socket is only accessible to its owner
+    use std::os::unix::fs::PermissionsExt;
+    let mode = std::fs::metadata(&socket_path)
+        .unwrap()
+        .permissions()
+        .mode();
+    assert_eq!(mode & 0o777, 0o600);
+
+    // start grpc client and connect to the server through the Unix socket
+    let path = socket_path.clone();
+    let channel = tonic::transport::Endpoint::try_from("http://[::]:50051")
+        .unwrap()
+        .connect_with_connector(tower::service_fn(move |_: tonic::transport::Uri| {
+            tokio::net::UnixStream::connect(path.clone())
+        }))
+        .await
+        .unwrap();
+    let mut public_client = PublicServiceClient::new(channel);
+    let status = public_client
+        .get_status(GetStatusRequest {})
+        .await
+        .unwrap()
+        .into_inner()
+        .status
+        .unwrap();
+    assert_eq!(status.version, *VERSION.to_string());
+
+    stop_handle.stop();
+    let _ = std::fs::remove_file(socket_path);
+}
+
+#[tokio::test]
+async fn method_max_encoding_message_size() {
+    let addr: SocketAddr = "[::]:4037".parse().unwrap();
+    let mut public_server = grpc_public_service(&addr);
+
+    let mut exec_ctrl = Box::new(MockExecutionController::new());
+    exec_ctrl
+        .expect_query_state()
+        .returning(|_| massa_execution_exports::ExecutionQueryResponse {
+            responses: vec![],
+            candidate_cursor: Slot::new(0, 2),
+            final_cursor: Slot::new(0, 0),
+            final_state_fingerprint: massa_hash::Hash::compute_from(&Vec::new()),
+        });
+    public_server.execution_controller = exec_ctrl;
+    // the status response is larger than 16 bytes
+    public_server
+        .grpc_config
+        .max_encoding_message_size_per_method
+        .insert("GetStatus".to_string(), 16);
+
+    let config = public_server.grpc_config.clone();
+    let stop_handle = public_server.serve(&config).await.unwrap();
+    // start grpc client and connect to the server
+    let mut public_client = PublicServiceClient::connect(format!(
+        "grpc://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .await
+    .unwrap();
+    let status = public_client
+        .get_status(GetStatusRequest {})
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::ResourceExhausted);
+
+    stop_handle.stop();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn method_deadline_exceeded() {
+    let addr: SocketAddr = "[::]:4034".parse().unwrap();
+    let mut public_server = grpc_public_service(&addr);
+
+    let mut exec_ctrl = Box::new(MockExecutionController::new());
+    exec_ctrl.expect_query_state().returning(|_| {
+        // simulate a handler blocked on a slow component
+        std::thread::sleep(Duration::from_secs(2));
+        massa_execution_exports::ExecutionQueryResponse {
+            responses: vec![],
+            candidate_cursor: Slot::new(0, 2),
+            final_cursor: Slot::new(0, 0),
+            final_state_fingerprint: massa_hash::Hash::compute_from(&Vec::new()),
+        }
+    });
+
+    public_server.execution_controller = exec_ctrl;
+    public_server
+        .grpc_config
+        .method_timeouts
+        .insert("GetStatus".to_string(), Duration::from_millis(100));
+
+    let config = public_server.grpc_config.clone();
+    let stop_handle = public_server.serve(&config).await.unwrap();
+    // start grpc client and connect to the server
+    let mut public_client = PublicServiceClient::connect(format!(
+        "grpc://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .await
+    .unwrap();
+    let status = public_client
+        .get_status(GetStatusRequest {})
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::DeadlineExceeded);
+
+    stop_handle.stop();
+}
+
+#[tokio::test]
+async fn get_sync_status() {
+    let addr: SocketAddr = "[::]:4040".parse().unwrap();
+    let mut public_server = grpc_public_service(&addr);
+    let config = public_server.grpc_config.clone();
+
+    // the node is 2 periods behind the wall clock and finalizes 3 blocks per slot
+    let current_slot = get_latest_block_slot_at_timestamp(
+        config.thread_count,
+        config.t0,
+        config.genesis_timestamp,
+        MassaTime::now(),
+    )
+    .unwrap()
+    .unwrap();
+    let active_cursor = Slot::new(current_slot.period - 2, current_slot.thread);
+    let slot_duration = config.t0.as_millis() / config.thread_count as u64;
+    let mut exec_ctrl = Box::new(MockExecutionController::new());
+    exec_ctrl
+        .expect_get_stats()
+        .returning(move || ExecutionStats {
+            time_window_start: MassaTime::from_millis(0),
+            time_window_end: MassaTime::from_millis(10 * slot_duration),
+            final_block_count: 30,
+            final_executed_operations_count: 0,
+            active_cursor,
+            final_cursor: Slot::new(active_cursor.period - 1, active_cursor.thread),
+        });
+    public_server.execution_controller = exec_ctrl;
+
+    let mut protocol_ctrl = Box::new(MockProtocolController::new());
+    protocol_ctrl.expect_get_stats().returning(|| {
+        let mut peers = HashMap::new();
+        peers.insert(
+            PeerId::from_public_key(KeyPair::generate(0).unwrap().get_public_key()),
+            ("127.0.0.1:31244".parse().unwrap(), PeerConnectionType::OUT),
+        );
+        Ok((
+            NetworkStats {
+                in_connection_count: 0,
+                out_connection_count: 1,
+                known_peer_count: 1,
+                banned_peer_count: 0,
+                active_node_count: 1,
+                reachability: Reachability::Unknown,
+                mapped_address: None,
+            },
+            peers,
+        ))
+    });
+    public_server.protocol_controller = protocol_ctrl;
+
+    let stop_handle = public_server.serve(&config).await.unwrap();
+
+    // start grpc client and connect to the server
+    let mut public_client = PublicServiceClient::connect(format!(
+        "grpc://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .await
+    .unwrap();
+
+    let response = public_client
+        .get_sync_status(GetSyncStatusRequest {})
+        .await
+        .unwrap()
+        .into_inner();
+
+    assert_eq!(response.state, SyncState::Syncing as i32);
+    assert_eq!(response.connected_peer_count, 1);
+    let last_final_slot = response.last_final_slot.unwrap();
+    assert_eq!(last_final_slot.period, active_cursor.period - 1);
+    assert!(response.current_slot.unwrap().period >= current_slot.period);
+    // 64 slots (65 if a slot happened meanwhile) to catch up at 2 slots per slot duration
+    let remaining = response.estimated_remaining_time.unwrap().milliseconds;
+    assert!(remaining >= 32 * slot_duration && remaining <= 33 * slot_duration);
+
+    stop_handle.stop();
+}
+
 #[tokio::test]
 async fn get_transactions_throughput() {
     let addr: SocketAddr = "[::]:4002".parse().unwrap();
@@ -110,6 +331,132 @@ async fn get_transactions_throughput() {
     stop_handle.stop();
 }
 
+#[tokio::test]
+async fn get_slot_execution_stats() {
+    let addr: SocketAddr = "[::]:4045".parse().unwrap();
+    let mut public_server = grpc_public_service(&addr);
+
+    let mut exec_ctrl = Box::new(MockExecutionController::new());
+    exec_ctrl
+        .expect_get_slot_execution_stats()
+        .returning(|last_slot_count| {
+            (0..last_slot_count as u8)
+                .map(|thread| {
+                    let stats = SlotExecutionStats {
+                        gas_used: 1_000 * thread as u64,
+                        gas_limit: 4_294_967_295,
+                        operation_count: thread as usize,
+                        event_count: 2,
+                        execution_time: Duration::from_micros(1_500),
+                    };
+                    (Slot::new(1, thread), stats)
+                })
+                .collect()
+        });
+    public_server.execution_controller = exec_ctrl;
+
+    let config = public_server.grpc_config.clone();
+    let stop_handle = public_server.serve(&config).await.unwrap();
+    // start grpc client and connect to the server
+    let mut public_client = PublicServiceClient::connect(format!(
+        "grpc://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .await
+    .unwrap();
+
+    let response = public_client
+        .get_slot_execution_stats(GetSlotExecutionStatsRequest { last_slot_count: 2 })
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.stats.len(), 2);
+    let stats = &response.stats[1];
+    assert_eq!(stats.slot.as_ref().unwrap().thread, 1);
+    assert_eq!(stats.gas_used, 1_000);
+    assert_eq!(stats.gas_limit, 4_294_967_295);
+    assert_eq!(stats.operation_count, 1);
+    assert_eq!(stats.event_count, 2);
+    assert_eq!(stats.execution_time_micros, 1_500);
+
+    // at least one slot is requested
+    let result = public_client
+        .get_slot_execution_stats(GetSlotExecutionStatsRequest { last_slot_count: 0 })
+        .await;
+    assert_eq!(result.unwrap_err().code(), tonic::Code::InvalidArgument);
+
+    stop_handle.stop();
+}
+
+#[tokio::test]
+async fn iter_ledger() {
+    let addr: SocketAddr = "[::]:4046".parse().unwrap();
+    let mut public_server = grpc_public_service(&addr);
+    let address = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+    let next_address = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+
+    let mut exec_ctrl = Box::new(MockExecutionController::new());
+    exec_ctrl
+        .expect_iter_ledger()
+        .returning(move |page_token, page_size| {
+            assert_eq!(page_token, Some(address));
+            assert_eq!(page_size, 1);
+            let entry = LedgerEntryInfo {
+                address,
+                balance: Amount::from_str("10").unwrap(),
+                bytecode_len: 3,
+                datastore_key_count: 2,
+            };
+            (vec![entry], Some(next_address))
+        });
+    public_server.execution_controller = exec_ctrl;
+
+    let config = public_server.grpc_config.clone();
+    let stop_handle = public_server.serve(&config).await.unwrap();
+    // start grpc client and connect to the server
+    let mut public_client = PublicServiceClient::connect(format!(
+        "grpc://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .await
+    .unwrap();
+
+    let response = public_client
+        .iter_ledger(IterLedgerRequest {
+            page_token: Some(address.to_string()),
+            page_size: 1,
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.entries.len(), 1);
+    let entry = &response.entries[0];
+    assert_eq!(entry.address, address.to_string());
+    assert_eq!(entry.bytecode_len, 3);
+    assert_eq!(entry.datastore_key_count, 2);
+    assert_eq!(response.next_page_token, Some(next_address.to_string()));
+
+    // the page size is limited
+    let result = public_client
+        .iter_ledger(IterLedgerRequest {
+            page_token: None,
+            page_size: 1_000,
+        })
+        .await;
+    assert_eq!(result.unwrap_err().code(), tonic::Code::InvalidArgument);
+
+    // the page token must be an address
+    let result = public_client
+        .iter_ledger(IterLedgerRequest {
+            page_token: Some("AU0".to_string()),
+            page_size: 1,
+        })
+        .await;
+    assert_eq!(result.unwrap_err().code(), tonic::Code::InvalidArgument);
+
+    stop_handle.stop();
+}
+
 #[tokio::test]
 async fn get_operations() {
     let addr: SocketAddr = "[::]:4003".parse().unwrap();
@@ -160,6 +507,45 @@ async fn get_operations() {
     stop_handle.stop();
 }
 
+#[tokio::test]
+async fn invalid_field_error_details() {
+    let addr: SocketAddr = "[::]:4038".parse().unwrap();
+    let public_server = grpc_public_service(&addr);
+    let config = public_server.grpc_config.clone();
+
+    // start the server
+    let stop_handle = public_server.serve(&config).await.unwrap();
+
+    // start grpc client and connect to the server
+    let mut public_client = PublicServiceClient::connect(format!(
+        "grpc://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .await
+    .unwrap();
+
+    let status = public_client
+        .get_operations(GetOperationsRequest {
+            operation_ids: vec!["massa".to_string()],
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    assert_eq!(status.message(), "invalid operation id: massa");
+
+    let details = status.get_error_details();
+    let error_info = details.error_info().unwrap();
+    assert_eq!(error_info.reason, "INVALID_FIELD");
+    assert_eq!(error_info.domain, "massa.net");
+    let violations = &details.bad_request().unwrap().field_violations;
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].field, "operation_ids");
+    assert_eq!(violations[0].description, "invalid operation id: massa");
+    assert!(details.retry_info().is_none());
+
+    stop_handle.stop();
+}
+
 #[tokio::test]
 async fn get_blocks() {
     let addr: SocketAddr = "[::]:4004".parse().unwrap();
@@ -201,6 +587,63 @@ async fn get_blocks() {
     stop_handle.stop();
 }
 
+#[tokio::test]
+async fn get_blocks_by_slot_range() {
+    let addr: SocketAddr = "[::]:4044".parse().unwrap();
+    let mut public_server = grpc_public_service(&addr);
+
+    let block = create_block(&KeyPair::generate(0).unwrap());
+    let block_id = block.id;
+    let block_slot = block.content.header.content.slot;
+    public_server.storage.store_block(block.clone());
+
+    let mut consensus_ctrl = Box::new(MockConsensusController::new());
+    consensus_ctrl
+        .expect_get_blockclique_blocks_in_slot_range()
+        .returning(move |_start, _end| vec![(block_slot, block_id)]);
+    public_server.consensus_controller = consensus_ctrl;
+
+    let config = public_server.grpc_config.clone();
+
+    // start the server
+    let stop_handle = public_server.serve(&config).await.unwrap();
+
+    // start grpc client and connect to the server
+    let mut public_client = PublicServiceClient::connect(format!(
+        "grpc://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .await
+    .unwrap();
+
+    // the end slot is before the start slot
+    let result = public_client
+        .get_blocks_by_slot_range(GetBlocksBySlotRangeRequest {
+            start_slot: Some(Slot::new(2, 0).into()),
+            end_slot: Some(Slot::new(1, 0).into()),
+            with_headers: false,
+        })
+        .await;
+    assert_eq!(result.unwrap_err().code(), tonic::Code::InvalidArgument);
+
+    let result = public_client
+        .get_blocks_by_slot_range(GetBlocksBySlotRangeRequest {
+            start_slot: Some(Slot::new(block_slot.period, 0).into()),
+            end_slot: Some(Slot::new(block_slot.period + 1, 0).into()),
+            with_headers: true,
+        })
+        .await
+        .unwrap()
+        .into_inner();
+
+    assert_eq!(result.slot_blocks.len(), 1);
+    let slot_block = &result.slot_blocks[0];
+    assert_eq!(slot_block.block_id, block_id.to_string());
+    assert_eq!(slot_block.slot.clone().map(Slot::from), Some(block_slot));
+    assert!(slot_block.header.is_some());
+    stop_handle.stop();
+}
+
 #[tokio::test]
 async fn get_stakers() {
     let addr: SocketAddr = "[::]:4005".parse().unwrap();
@@ -355,6 +798,9 @@ async fn execute_read_only_call() {
                     block_info: None,
                     state_changes: massa_final_state::StateChanges::default(),
                     events: EventStore::default(),
+                    event_overflow: None,
+                    stats: Default::default(),
+                    final_ledger_reads: Default::default(),
                 },
                 gas_cost: 100,
                 call_result: "toto".as_bytes().to_vec(),
@@ -639,6 +1085,10 @@ async fn get_sc_execution_events() {
     let result = public_client
         .get_sc_execution_events(GetScExecutionEventsRequest {
             filters: vec![filter.clone()],
+            sort_by: ScExecutionEventsSortBy::Unspecified as i32,
+            descending: false,
+            offset: 0,
+            limit: None,
         })
         .await
         .unwrap()
@@ -663,6 +1113,79 @@ async fn get_sc_execution_events() {
     stop_handle.stop();
 }
 
+#[tokio::test]
+async fn get_sc_execution_events_sorted_and_paginated() {
+    let addr: SocketAddr = "[::]:4043".parse().unwrap();
+    let mut public_server = grpc_public_service(&addr);
+    let config = public_server.grpc_config.clone();
+
+    let mut exec_ctrl = Box::new(MockExecutionController::new());
+    exec_ctrl
+        .expect_get_filtered_sc_output_event()
+        .returning(|_| {
+            [(2, "b"), (1, "c"), (3, "a")]
+                .into_iter()
+                .map(|(period, data)| massa_models::output_event::SCOutputEvent {
+                    context: massa_models::output_event::EventExecutionContext {
+                        slot: Slot::new(period, 0),
+                        block: None,
+                        read_only: false,
+                        index_in_slot: 0,
+                        call_stack: VecDeque::new(),
+                        origin_operation_id: None,
+                        is_final: true,
+                        is_error: false,
+                    },
+                    data: data.to_string(),
+                })
+                .collect()
+        });
+
+    public_server.execution_controller = exec_ctrl;
+
+    let stop_handle = public_server.serve(&config).await.unwrap();
+    let mut public_client = PublicServiceClient::connect(format!(
+        "grpc://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .await
+    .unwrap();
+
+    let result = public_client
+        .get_sc_execution_events(GetScExecutionEventsRequest {
+            filters: vec![],
+            sort_by: ScExecutionEventsSortBy::Slot as i32,
+            descending: true,
+            offset: 1,
+            limit: Some(1),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+
+    assert_eq!(result.total_count, 3);
+    assert_eq!(result.events.len(), 1);
+    assert_eq!(result.events[0].data, "b".as_bytes().to_vec());
+
+    let result = public_client
+        .get_sc_execution_events(GetScExecutionEventsRequest {
+            filters: vec![],
+            sort_by: ScExecutionEventsSortBy::Slot as i32,
+            descending: false,
+            offset: 1,
+            limit: None,
+        })
+        .await
+        .unwrap()
+        .into_inner();
+
+    assert_eq!(result.total_count, 3);
+    let data: Vec<_> = result.events.into_iter().map(|event| event.data).collect();
+    assert_eq!(data, vec!["b".as_bytes().to_vec(), "a".as_bytes().to_vec()]);
+
+    stop_handle.stop();
+}
+
 #[tokio::test]
 async fn get_selector_draws() {
     let addr: SocketAddr = "[::]:4011".parse().unwrap();
@@ -1,8 +1,9 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
 
+use crate::stream::limiter::StreamLimiter;
 use crate::tests::mock::grpc_public_service;
 use core::panic;
-use massa_consensus_exports::MockConsensusController;
+use massa_consensus_exports::{events::BlockcliqueReorg, MockConsensusController};
 use massa_execution_exports::{ExecutionOutput, MockExecutionController, SlotExecutionOutput};
 use massa_models::{
     address::Address, amount::Amount, block::FilledBlock, secure_share::SecureShareSerializer,
@@ -12,8 +13,9 @@ use massa_pool_exports::MockPoolController;
 use massa_proto_rs::massa::{
     api::v1::{
         public_service_client::PublicServiceClient, NewBlocksRequest, NewFilledBlocksRequest,
-        NewOperationsRequest, NewSlotExecutionOutputsRequest, SendEndorsementsRequest,
-        SendOperationsRequest, TransactionsThroughputRequest,
+        NewOperationsRequest, NewReorgsRequest, NewSlotExecutionOutputsRequest,
+        SendEndorsementsRequest, SendOperationsRequest, SlotTicksRequest,
+        TransactionsThroughputRequest,
     },
     model::v1::{Addresses, Slot as ProtoSlot, SlotRange},
 };
@@ -212,6 +214,7 @@ async fn new_operations() {
     tx_request
         .send(NewOperationsRequest {
             filters: vec![filter],
+            resume_from: None,
         })
         .await
         .unwrap();
@@ -237,6 +240,7 @@ async fn new_operations() {
     tx_request
         .send(NewOperationsRequest {
             filters: vec![filter_id.clone()],
+            resume_from: None,
         })
         .await
         .unwrap();
@@ -268,6 +272,7 @@ async fn new_operations() {
     tx_request
         .send(NewOperationsRequest {
             filters: vec![filter_type],
+            resume_from: None,
         })
         .await
         .unwrap();
@@ -291,6 +296,7 @@ async fn new_operations() {
     tx_request
         .send(NewOperationsRequest {
             filters: vec![filter_type.clone()],
+            resume_from: None,
         })
         .await
         .unwrap();
@@ -310,6 +316,7 @@ async fn new_operations() {
     tx_request
         .send(NewOperationsRequest {
             filters: vec![filter_type, filter_id],
+            resume_from: None,
         })
         .await
         .unwrap();
@@ -340,6 +347,7 @@ async fn new_operations() {
     tx_request
         .send(NewOperationsRequest {
             filters: vec![filter_addr.clone()],
+            resume_from: None,
         })
         .await
         .unwrap();
@@ -362,6 +370,7 @@ async fn new_operations() {
     tx_request
         .send(NewOperationsRequest {
             filters: vec![filter_addr.clone()],
+            resume_from: None,
         })
         .await
         .unwrap();
@@ -376,6 +385,137 @@ async fn new_operations() {
         keypair.get_public_key().to_string()
     );
 
+    // the creator address is not the recipient of the transaction
+    let filter_recipient = massa_proto_rs::massa::api::v1::NewOperationsFilter {
+        filter: Some(
+            massa_proto_rs::massa::api::v1::new_operations_filter::Filter::RecipientAddresses(
+                massa_proto_rs::massa::model::v1::Addresses {
+                    addresses: vec![address.to_string()],
+                },
+            ),
+        ),
+    };
+
+    tx_request
+        .send(NewOperationsRequest {
+            filters: vec![filter_recipient],
+            resume_from: None,
+        })
+        .await
+        .unwrap();
+    op_send_signal.send(()).await.unwrap();
+
+    let result = tokio::time::timeout(Duration::from_secs(2), resp_stream.next()).await;
+    assert!(result.is_err());
+
+    // the transaction amount is zero, so it is outside of the range
+    let mut filter_amount = massa_proto_rs::massa::api::v1::NewOperationsFilter {
+        filter: Some(
+            massa_proto_rs::massa::api::v1::new_operations_filter::Filter::AmountRange(
+                massa_proto_rs::massa::model::v1::AmountRange {
+                    min: Some(Amount::from_str("1").unwrap().into()),
+                    max: None,
+                },
+            ),
+        ),
+    };
+
+    tx_request
+        .send(NewOperationsRequest {
+            filters: vec![filter_amount],
+            resume_from: None,
+        })
+        .await
+        .unwrap();
+    op_send_signal.send(()).await.unwrap();
+
+    let result = tokio::time::timeout(Duration::from_secs(2), resp_stream.next()).await;
+    assert!(result.is_err());
+
+    filter_amount = massa_proto_rs::massa::api::v1::NewOperationsFilter {
+        filter: Some(
+            massa_proto_rs::massa::api::v1::new_operations_filter::Filter::AmountRange(
+                massa_proto_rs::massa::model::v1::AmountRange {
+                    min: None,
+                    max: Some(Amount::from_str("1").unwrap().into()),
+                },
+            ),
+        ),
+    };
+
+    tx_request
+        .send(NewOperationsRequest {
+            filters: vec![filter_amount],
+            resume_from: None,
+        })
+        .await
+        .unwrap();
+    op_send_signal.send(()).await.unwrap();
+
+    let received = tokio::time::timeout(Duration::from_secs(5), resp_stream.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        received.signed_operation.unwrap().content_creator_pub_key,
+        keypair.get_public_key().to_string()
+    );
+
+    // the operation fee is zero, so it is outside of the range
+    let mut filter_fee = massa_proto_rs::massa::api::v1::NewOperationsFilter {
+        filter: Some(
+            massa_proto_rs::massa::api::v1::new_operations_filter::Filter::FeeRange(
+                massa_proto_rs::massa::model::v1::AmountRange {
+                    min: Some(Amount::from_str("1").unwrap().into()),
+                    max: None,
+                },
+            ),
+        ),
+    };
+
+    tx_request
+        .send(NewOperationsRequest {
+            filters: vec![filter_fee],
+            resume_from: None,
+        })
+        .await
+        .unwrap();
+    op_send_signal.send(()).await.unwrap();
+
+    let result = tokio::time::timeout(Duration::from_secs(2), resp_stream.next()).await;
+    assert!(result.is_err());
+
+    filter_fee = massa_proto_rs::massa::api::v1::NewOperationsFilter {
+        filter: Some(
+            massa_proto_rs::massa::api::v1::new_operations_filter::Filter::FeeRange(
+                massa_proto_rs::massa::model::v1::AmountRange {
+                    min: None,
+                    max: Some(Amount::from_str("1").unwrap().into()),
+                },
+            ),
+        ),
+    };
+
+    tx_request
+        .send(NewOperationsRequest {
+            filters: vec![filter_fee],
+            resume_from: None,
+        })
+        .await
+        .unwrap();
+    op_send_signal.send(()).await.unwrap();
+
+    let received = tokio::time::timeout(Duration::from_secs(5), resp_stream.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        received.signed_operation.unwrap().content_creator_pub_key,
+        keypair.get_public_key().to_string()
+    );
+
     stop_handle.stop();
 }
 
@@ -433,6 +573,7 @@ async fn new_blocks() {
     tx_request
         .send(NewBlocksRequest {
             filters: vec![filter_slot.clone()],
+            resume_from: None,
         })
         .await
         .unwrap();
@@ -465,6 +606,7 @@ async fn new_blocks() {
     tx_request
         .send(NewBlocksRequest {
             filters: vec![filter_slot],
+            resume_from: None,
         })
         .await
         .unwrap();
@@ -493,6 +635,7 @@ async fn new_blocks() {
     tx_request
         .send(NewBlocksRequest {
             filters: vec![filter_slot],
+            resume_from: None,
         })
         .await
         .unwrap();
@@ -521,6 +664,7 @@ async fn new_blocks() {
     tx_request
         .send(NewBlocksRequest {
             filters: vec![filter_addr],
+            resume_from: None,
         })
         .await
         .unwrap();
@@ -545,6 +689,7 @@ async fn new_blocks() {
     tx_request
         .send(NewBlocksRequest {
             filters: vec![filter_addr.clone()],
+            resume_from: None,
         })
         .await
         .unwrap();
@@ -577,6 +722,7 @@ async fn new_blocks() {
     tx_request
         .send(NewBlocksRequest {
             filters: vec![filter_ids],
+            resume_from: None,
         })
         .await
         .unwrap();
@@ -603,6 +749,7 @@ async fn new_blocks() {
     tx_request
         .send(NewBlocksRequest {
             filters: vec![filter_ids],
+            resume_from: None,
         })
         .await
         .unwrap();
@@ -631,6 +778,7 @@ async fn new_blocks() {
     tx_request
         .send(NewBlocksRequest {
             filters: vec![filter_addr],
+            resume_from: None,
         })
         .await
         .unwrap();
@@ -897,6 +1045,8 @@ async fn new_filled_blocks() {
     tx_request
         .send(NewFilledBlocksRequest {
             filters: vec![filter],
+            operation_filters: vec![],
+            strip_operations: false,
         })
         .await
         .unwrap();
@@ -928,6 +1078,8 @@ async fn new_filled_blocks() {
     tx_request
         .send(NewFilledBlocksRequest {
             filters: vec![filter],
+            operation_filters: vec![],
+            strip_operations: false,
         })
         .await
         .unwrap();
@@ -955,6 +1107,8 @@ async fn new_filled_blocks() {
     tx_request
         .send(NewFilledBlocksRequest {
             filters: vec![filter],
+            operation_filters: vec![],
+            strip_operations: false,
         })
         .await
         .unwrap();
@@ -980,6 +1134,8 @@ async fn new_filled_blocks() {
     tx_request
         .send(NewFilledBlocksRequest {
             filters: vec![filter],
+            operation_filters: vec![],
+            strip_operations: false,
         })
         .await
         .unwrap();
@@ -1007,6 +1163,8 @@ async fn new_filled_blocks() {
     tx_request
         .send(NewFilledBlocksRequest {
             filters: vec![filter],
+            operation_filters: vec![],
+            strip_operations: false,
         })
         .await
         .unwrap();
@@ -1030,6 +1188,8 @@ async fn new_filled_blocks() {
     tx_request
         .send(NewFilledBlocksRequest {
             filters: vec![filter],
+            operation_filters: vec![],
+            strip_operations: false,
         })
         .await
         .unwrap();
@@ -1049,6 +1209,119 @@ async fn new_filled_blocks() {
     stop_handle.stop();
 }
 
+#[tokio::test]
+async fn new_filled_blocks_operation_filters() {
+    let addr: SocketAddr = "[::]:4041".parse().unwrap();
+    let mut public_server = grpc_public_service(&addr);
+    let config = public_server.grpc_config.clone();
+
+    let (filled_block_tx, _filled_block_rx) = tokio::sync::broadcast::channel(10);
+
+    public_server.consensus_broadcasts.filled_block_sender = filled_block_tx.clone();
+
+    let stop_handle = public_server.serve(&config).await.unwrap();
+
+    let (tx_request, rx) = tokio::sync::mpsc::channel(10);
+    let request_stream = tokio_stream::wrappers::ReceiverStream::new(rx);
+    let keypair = KeyPair::generate(0).unwrap();
+    let op_keypair = KeyPair::generate(0).unwrap();
+    let op_address = Address::from_public_key(&op_keypair.get_public_key());
+    let op = create_operation_with_expire_period(&op_keypair, 4);
+    let other_op = create_operation_with_expire_period(&KeyPair::generate(0).unwrap(), 4);
+    let block = create_block(&keypair);
+
+    let filled_block = FilledBlock {
+        header: block.content.header.clone(),
+        operations: vec![(op.id, Some(op.clone())), (other_op.id, Some(other_op))],
+    };
+
+    let mut public_client = PublicServiceClient::connect(format!(
+        "grpc://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .await
+    .unwrap();
+
+    let mut resp_stream = public_client
+        .new_filled_blocks(request_stream)
+        .await
+        .unwrap()
+        .into_inner();
+
+    let operations_filter = |address: String| massa_proto_rs::massa::api::v1::NewOperationsFilter {
+        filter: Some(
+            massa_proto_rs::massa::api::v1::new_operations_filter::Filter::Addresses(Addresses {
+                addresses: vec![address],
+            }),
+        ),
+    };
+
+    // no operation of the block matches
+    tx_request
+        .send(NewFilledBlocksRequest {
+            filters: vec![],
+            operation_filters: vec![operations_filter(
+                "AU12BTfZ7k1z6PsLEUZeHYNirz6WJ3NdrWto9H4TkVpkV9xE2TJg2".to_string(),
+            )],
+            strip_operations: false,
+        })
+        .await
+        .unwrap();
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    filled_block_tx.send(filled_block.clone()).unwrap();
+
+    let result = tokio::time::timeout(Duration::from_secs(2), resp_stream.next()).await;
+    assert!(result.is_err());
+
+    // one operation matches, the whole block is sent
+    tx_request
+        .send(NewFilledBlocksRequest {
+            filters: vec![],
+            operation_filters: vec![operations_filter(op_address.to_string())],
+            strip_operations: false,
+        })
+        .await
+        .unwrap();
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    filled_block_tx.send(filled_block.clone()).unwrap();
+
+    let result = tokio::time::timeout(Duration::from_secs(5), resp_stream.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    assert_eq!(result.filled_block.unwrap().operations.len(), 2);
+
+    // the operations not matching are stripped
+    tx_request
+        .send(NewFilledBlocksRequest {
+            filters: vec![],
+            operation_filters: vec![operations_filter(op_address.to_string())],
+            strip_operations: true,
+        })
+        .await
+        .unwrap();
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    filled_block_tx.send(filled_block.clone()).unwrap();
+
+    let result = tokio::time::timeout(Duration::from_secs(5), resp_stream.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    let operations = result.filled_block.unwrap().operations;
+    assert_eq!(operations.len(), 1);
+    assert_eq!(operations[0].operation_id, op.id.to_string());
+
+    stop_handle.stop();
+}
+
 #[tokio::test]
 async fn new_slot_execution_outputs() {
     let addr: SocketAddr = "[::]:4022".parse().unwrap();
@@ -1068,6 +1341,9 @@ async fn new_slot_execution_outputs() {
         block_info: None,
         state_changes: massa_final_state::StateChanges::default(),
         events: Default::default(),
+        event_overflow: None,
+        stats: Default::default(),
+        final_ledger_reads: Default::default(),
     };
 
     let (tx_request, rx) = tokio::sync::mpsc::channel(10);
@@ -1105,6 +1381,7 @@ async fn new_slot_execution_outputs() {
     tx_request
         .send(NewSlotExecutionOutputsRequest {
             filters: vec![filter],
+            resume_from: None,
         })
         .await
         .unwrap();
@@ -1142,6 +1419,7 @@ async fn new_slot_execution_outputs() {
     tx_request
         .send(NewSlotExecutionOutputsRequest {
             filters: vec![filter],
+            resume_from: None,
         })
         .await
         .unwrap();
@@ -1176,6 +1454,7 @@ async fn new_slot_execution_outputs() {
     tx_request
         .send(NewSlotExecutionOutputsRequest {
             filters: vec![filter],
+            resume_from: None,
         })
         .await
         .unwrap();
@@ -1208,6 +1487,7 @@ async fn new_slot_execution_outputs() {
     tx_request
         .send(NewSlotExecutionOutputsRequest {
             filters: vec![filter],
+            resume_from: None,
         })
         .await
         .unwrap();
@@ -1234,7 +1514,8 @@ async fn send_operations_low_fee() {
     pool_ctrl.expect_clone_box().returning(|| {
         let mut pool_ctrl = Box::new(MockPoolController::new());
 
-        pool_ctrl.expect_add_operations().returning(|_| ());
+        pool_ctrl.expect_add_local_operations().returning(|_| ());
+        pool_ctrl.expect_check_sender_limits().returning(|_| Ok(()));
 
         pool_ctrl
     });
@@ -1314,7 +1595,8 @@ async fn send_operations() {
     pool_ctrl.expect_clone_box().returning(|| {
         let mut pool_ctrl = Box::new(MockPoolController::new());
 
-        pool_ctrl.expect_add_operations().returning(|_| ());
+        pool_ctrl.expect_add_local_operations().returning(|_| ());
+        pool_ctrl.expect_check_sender_limits().returning(|_| Ok(()));
 
         pool_ctrl
     });
@@ -1661,3 +1943,289 @@ async fn send_blocks() {
 
     stop_handle.stop();
 }
+
+#[tokio::test]
+async fn max_streams_per_ip() {
+    let addr: SocketAddr = "[::]:4035".parse().unwrap();
+    let mut public_server = grpc_public_service(&addr);
+    public_server.stream_limiter = StreamLimiter::new(1);
+    let config = public_server.grpc_config.clone();
+
+    let stop_handle = public_server.serve(&config).await.unwrap();
+
+    let mut public_client = PublicServiceClient::connect(format!(
+        "grpc://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .await
+    .unwrap();
+
+    let (_tx_request, rx) = tokio::sync::mpsc::channel::<NewBlocksRequest>(10);
+    let resp_stream = public_client
+        .new_blocks(tokio_stream::wrappers::ReceiverStream::new(rx))
+        .await
+        .unwrap()
+        .into_inner();
+
+    // a second stream from the same IP is rejected
+    let (_tx_request_2, rx_2) = tokio::sync::mpsc::channel::<NewBlocksRequest>(10);
+    let status = public_client
+        .new_blocks(tokio_stream::wrappers::ReceiverStream::new(rx_2))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::ResourceExhausted);
+
+    // closing the first stream releases its slot
+    drop(resp_stream);
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let (_tx_request_3, rx_3) = tokio::sync::mpsc::channel::<NewBlocksRequest>(10);
+    assert!(public_client
+        .new_blocks(tokio_stream::wrappers::ReceiverStream::new(rx_3))
+        .await
+        .is_ok());
+
+    stop_handle.stop();
+}
+
+#[tokio::test]
+async fn slot_ticks() {
+    let addr: SocketAddr = "[::]:4039".parse().unwrap();
+    let public_server = grpc_public_service(&addr);
+    let config = public_server.grpc_config.clone();
+
+    let stop_handle = public_server.serve(&config).await.unwrap();
+
+    let mut public_client = PublicServiceClient::connect(format!(
+        "grpc://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .await
+    .unwrap();
+
+    let mut resp_stream = public_client
+        .slot_ticks(SlotTicksRequest {})
+        .await
+        .unwrap()
+        .into_inner();
+
+    let slot_duration = config.t0.as_millis() / config.thread_count as u64;
+    let mut previous: Option<(Slot, u64)> = None;
+    for _ in 0..3 {
+        let received = tokio::time::timeout(Duration::from_secs(5), resp_stream.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        let proto_slot = received.slot.unwrap();
+        let slot = Slot::new(proto_slot.period, proto_slot.thread as u8);
+        // ticks are sent once the slot is reached
+        assert!(received.timestamp <= MassaTime::now().as_millis());
+        assert_eq!(
+            received.is_final_cutoff,
+            slot.thread == config.thread_count - 1
+        );
+        if let Some((previous_slot, previous_timestamp)) = previous {
+            assert_eq!(
+                slot,
+                previous_slot.get_next_slot(config.thread_count).unwrap()
+            );
+            assert_eq!(received.timestamp, previous_timestamp + slot_duration);
+        }
+        previous = Some((slot, received.timestamp));
+    }
+
+    stop_handle.stop();
+}
+
+#[tokio::test]
+async fn new_reorgs() {
+    let addr: SocketAddr = "[::]:4047".parse().unwrap();
+    let mut public_server = grpc_public_service(&addr);
+    let config = public_server.grpc_config.clone();
+
+    let (reorg_tx, _reorg_rx) = tokio::sync::broadcast::channel(10);
+    public_server.consensus_broadcasts.reorg_sender = reorg_tx.clone();
+
+    let stop_handle = public_server.serve(&config).await.unwrap();
+
+    let mut public_client = PublicServiceClient::connect(format!(
+        "grpc://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .await
+    .unwrap();
+
+    let mut resp_stream = public_client
+        .new_reorgs(NewReorgsRequest {})
+        .await
+        .unwrap()
+        .into_inner();
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let old_tip = create_block(&KeyPair::generate(0).unwrap()).id;
+    let new_tip = create_block(&KeyPair::generate(0).unwrap()).id;
+    reorg_tx
+        .send(BlockcliqueReorg {
+            old_tip,
+            new_tip,
+            depth: 1,
+            discarded_block_ids: vec![old_tip],
+        })
+        .unwrap();
+
+    let result = tokio::time::timeout(Duration::from_secs(5), resp_stream.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(result.old_tip_id, old_tip.to_string());
+    assert_eq!(result.new_tip_id, new_tip.to_string());
+    assert_eq!(result.depth, 1);
+    assert_eq!(result.discarded_block_ids, vec![old_tip.to_string()]);
+
+    stop_handle.stop();
+}
+
+#[tokio::test]
+async fn new_operations_resume_from() {
+    let addr: SocketAddr = "[::]:4042".parse().unwrap();
+    let mut public_server = grpc_public_service(&addr);
+    let config = public_server.grpc_config.clone();
+    let (op_tx, _op_rx) = tokio::sync::broadcast::channel(10);
+    public_server.pool_broadcasts.operation_sender = op_tx.clone();
+
+    let stop_handle = public_server.serve(&config).await.unwrap();
+    let mut public_client = PublicServiceClient::connect(format!(
+        "grpc://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .await
+    .unwrap();
+
+    let (tx_request, rx) = tokio::sync::mpsc::channel(10);
+    let mut resp_stream = public_client
+        .new_operations(tokio_stream::wrappers::ReceiverStream::new(rx))
+        .await
+        .unwrap()
+        .into_inner();
+    tx_request
+        .send(NewOperationsRequest {
+            filters: vec![],
+            resume_from: None,
+        })
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let op = create_operation_with_expire_period(&KeyPair::generate(0).unwrap(), 10);
+    op_tx.send(op).unwrap();
+    let received = tokio::time::timeout(Duration::from_secs(5), resp_stream.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    let cursor = received.sequence;
+
+    // the client disconnects, and operations are broadcast meanwhile
+    drop(resp_stream);
+    drop(tx_request);
+    let missed_ops: Vec<_> = (0..2)
+        .map(|_| create_operation_with_expire_period(&KeyPair::generate(0).unwrap(), 10))
+        .collect();
+    for op in missed_ops.iter() {
+        op_tx.send(op.clone()).unwrap();
+    }
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    // the missed operations are replayed on resume
+    let (tx_request, rx) = tokio::sync::mpsc::channel(10);
+    let mut resp_stream = public_client
+        .new_operations(tokio_stream::wrappers::ReceiverStream::new(rx))
+        .await
+        .unwrap()
+        .into_inner();
+    tx_request
+        .send(NewOperationsRequest {
+            filters: vec![],
+            resume_from: Some(cursor),
+        })
+        .await
+        .unwrap();
+    for (i, op) in missed_ops.iter().enumerate() {
+        let received = tokio::time::timeout(Duration::from_secs(5), resp_stream.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(received.sequence, cursor + 1 + i as u64);
+        assert_eq!(
+            received.signed_operation.unwrap().secure_hash,
+            op.id.to_string()
+        );
+    }
+
+    // a cursor ahead of the recorded events is rejected
+    let (tx_request, rx) = tokio::sync::mpsc::channel(10);
+    let mut resp_stream = public_client
+        .new_operations(tokio_stream::wrappers::ReceiverStream::new(rx))
+        .await
+        .unwrap()
+        .into_inner();
+    tx_request
+        .send(NewOperationsRequest {
+            filters: vec![],
+            resume_from: Some(cursor + 100),
+        })
+        .await
+        .unwrap();
+    let status = tokio::time::timeout(Duration::from_secs(5), resp_stream.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+
+    stop_handle.stop();
+}
+
+#[tokio::test]
+async fn stop_and_drain_open_stream() {
+    let addr: SocketAddr = "[::]:4048".parse().unwrap();
+    let public_server = grpc_public_service(&addr);
+    let config = public_server.grpc_config.clone();
+
+    let stop_handle = public_server.serve(&config).await.unwrap();
+
+    let mut public_client = PublicServiceClient::connect(format!(
+        "grpc://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .await
+    .unwrap();
+
+    let mut resp_stream = public_client
+        .slot_ticks(SlotTicksRequest {})
+        .await
+        .unwrap()
+        .into_inner();
+    tokio::time::timeout(Duration::from_secs(5), resp_stream.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+
+    // the stream never ends by itself, it is cancelled at the end of the grace period
+    let start = tokio::time::Instant::now();
+    stop_handle
+        .stop_and_drain(start + Duration::from_millis(500))
+        .await;
+    assert!(start.elapsed() >= Duration::from_millis(500));
+
+    // the ticks sent before the cancellation are received, then the stream ends
+    while let Some(Ok(_)) = tokio::time::timeout(Duration::from_secs(5), resp_stream.next())
+        .await
+        .unwrap()
+    {}
+}
@@ -1,7 +1,7 @@
 use massa_models::{address::Address, amount::Amount, bytecode::Bytecode};
 use std::collections::BTreeSet;
 
-use crate::{LedgerChanges, LedgerError};
+use crate::{LedgerChanges, LedgerEntryInfo, LedgerError};
 use massa_db_exports::DBBatch;
 
 #[cfg(feature = "test-exports")]
@@ -40,11 +40,43 @@ pub trait LedgerController: Send + Sync {
     /// A copy of the datastore value, or `None` if the ledger entry or datastore entry was not found
     fn get_data_entry(&self, addr: &Address, key: &[u8]) -> Option<Vec<u8>>;
 
-    /// Get every key of the datastore for a given address.
+    /// Get the keys of the datastore of a given address starting with `prefix`, in key order,
+    /// only keeping the ones strictly greater than `start_after` if set,
+    /// and at most `limit` of them if set.
     ///
     /// # Returns
-    /// A `BTreeSet` of the datastore keys
-    fn get_datastore_keys(&self, addr: &Address, prefix: &[u8]) -> Option<BTreeSet<Vec<u8>>>;
+    /// A `BTreeSet` of the datastore keys, or None if the address does not exist
+    #[allow(clippy::needless_lifetimes)] // lifetime elision conflicts with Mockall
+    fn get_datastore_keys<'a>(
+        &self,
+        addr: &Address,
+        prefix: &[u8],
+        start_after: Option<&'a [u8]>,
+        limit: Option<usize>,
+    ) -> Option<BTreeSet<Vec<u8>>>;
+
+    /// Get the dust accounts among the `scan_length` ledger entries starting from the one
+    /// of `from`, in address order and wrapping around at the end of the ledger, with their
+    /// balance: the accounts with a balance strictly below `max_balance`,
+    /// and neither bytecode nor datastore entries. Each entry is examined at most once.
+    fn get_dust_accounts(
+        &self,
+        from: &Address,
+        max_balance: Amount,
+        scan_length: usize,
+    ) -> Vec<(Address, Amount)>;
+
+    /// Get the summaries of up to `page_size` ledger entries in address order,
+    /// starting from the one of `page_token`, or from the first entry if `None`.
+    ///
+    /// # Returns
+    /// The summaries, and the address of the entry following them if any,
+    /// to be used as the token of the next page
+    fn iter_ledger(
+        &self,
+        page_token: Option<Address>,
+        page_size: usize,
+    ) -> (Vec<LedgerEntryInfo>, Option<Address>);
 
     /// Reset the ledger
     ///
@@ -4,6 +4,7 @@
 
 use crate::ledger_changes::LedgerEntryUpdate;
 use crate::types::{Applicable, SetOrDelete};
+use massa_models::address::Address;
 use massa_models::amount::{Amount, AmountDeserializer, AmountSerializer};
 use massa_models::bytecode::{Bytecode, BytecodeDeserializer, BytecodeSerializer};
 use massa_models::datastore::{Datastore, DatastoreDeserializer, DatastoreSerializer};
@@ -30,6 +31,22 @@ pub struct LedgerEntry {
     pub datastore: Datastore,
 }
 
+/// Summary of an entry of the `FinalLedger`, as returned by the ledger iteration
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LedgerEntryInfo {
+    /// The address of the entry
+    pub address: Address,
+
+    /// The balance of the entry
+    pub balance: Amount,
+
+    /// Size of the bytecode, in bytes
+    pub bytecode_len: usize,
+
+    /// Number of datastore entries
+    pub datastore_key_count: usize,
+}
+
 /// Serializer for `LedgerEntry`
 pub struct LedgerEntrySerializer {
     amount_serializer: AmountSerializer,
@@ -16,14 +16,16 @@ pub use controller::LedgerController;
 pub use error::LedgerError;
 pub use key::{
     datastore_prefix_from_address, Key, KeyDeserializer, KeySerializer, KeyType, BALANCE_IDENT,
-    BYTECODE_IDENT, DATASTORE_IDENT, VERSION_IDENT,
+    BYTECODE_IDENT, DATASTORE_IDENT, KEY_VERSION, VERSION_IDENT,
 };
 pub use ledger_changes::{
     DatastoreUpdateDeserializer, DatastoreUpdateSerializer, LedgerChanges,
     LedgerChangesDeserializer, LedgerChangesSerializer, LedgerEntryUpdate,
     LedgerEntryUpdateDeserializer, LedgerEntryUpdateSerializer,
 };
-pub use ledger_entry::{LedgerEntry, LedgerEntryDeserializer, LedgerEntrySerializer};
+pub use ledger_entry::{
+    LedgerEntry, LedgerEntryDeserializer, LedgerEntryInfo, LedgerEntrySerializer,
+};
 pub use types::{
     Applicable, SetOrDelete, SetOrKeep, SetOrKeepDeserializer, SetOrKeepSerializer,
     SetUpdateOrDelete, SetUpdateOrDeleteDeserializer, SetUpdateOrDeleteSerializer,
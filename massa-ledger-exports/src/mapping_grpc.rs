@@ -1,6 +1,6 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
 
-use crate::{LedgerEntry, LedgerEntryUpdate, SetOrDelete, SetOrKeep};
+use crate::{LedgerEntry, LedgerEntryInfo, LedgerEntryUpdate, SetOrDelete, SetOrKeep};
 use massa_proto_rs::massa::model::v1 as grpc_model;
 
 impl From<LedgerEntry> for grpc_model::LedgerEntry {
@@ -17,6 +17,17 @@ impl From<LedgerEntry> for grpc_model::LedgerEntry {
     }
 }
 
+impl From<LedgerEntryInfo> for grpc_model::LedgerEntryInfo {
+    fn from(value: LedgerEntryInfo) -> Self {
+        grpc_model::LedgerEntryInfo {
+            address: value.address.to_string(),
+            balance: Some(value.balance.into()),
+            bytecode_len: value.bytecode_len as u64,
+            datastore_key_count: value.datastore_key_count as u64,
+        }
+    }
+}
+
 impl From<LedgerEntryUpdate> for grpc_model::LedgerEntryUpdate {
     fn from(value: LedgerEntryUpdate) -> Self {
         grpc_model::LedgerEntryUpdate {
@@ -5,7 +5,7 @@
 use crate::ledger_db::{LedgerDB, LedgerSubEntry};
 use massa_db_exports::{DBBatch, ShareableMassaDBController};
 use massa_ledger_exports::{
-    LedgerChanges, LedgerConfig, LedgerController, LedgerEntry, LedgerError,
+    LedgerChanges, LedgerConfig, LedgerController, LedgerEntry, LedgerEntryInfo, LedgerError,
 };
 use massa_models::{
     address::Address,
@@ -134,12 +134,42 @@ impl LedgerController for FinalLedger {
             .get_sub_entry(addr, LedgerSubEntry::Datastore(key.to_owned()))
     }
 
-    /// Get every key of the datastore for a given address.
+    /// Get the keys of the datastore of a given address starting with `prefix`, in key order.
     ///
     /// # Returns
-    /// A `BTreeSet` of the datastore keys
-    fn get_datastore_keys(&self, addr: &Address, prefix: &[u8]) -> Option<BTreeSet<Vec<u8>>> {
-        self.sorted_ledger.get_datastore_keys(addr, prefix)
+    /// A `BTreeSet` of the datastore keys, or None if the address does not exist
+    #[allow(clippy::needless_lifetimes)] // lifetime elision conflicts with Mockall
+    fn get_datastore_keys<'a>(
+        &self,
+        addr: &Address,
+        prefix: &[u8],
+        start_after: Option<&'a [u8]>,
+        limit: Option<usize>,
+    ) -> Option<BTreeSet<Vec<u8>>> {
+        self.sorted_ledger
+            .get_datastore_keys(addr, prefix, start_after, limit)
+    }
+
+    /// Get the dust accounts among the `scan_length` ledger entries starting from the one of `from`
+    fn get_dust_accounts(
+        &self,
+        from: &Address,
+        max_balance: Amount,
+        scan_length: usize,
+    ) -> Vec<(Address, Amount)> {
+        self.sorted_ledger
+            .get_dust_accounts(from, max_balance, scan_length)
+    }
+
+    /// Get the summaries of up to `page_size` ledger entries in address order,
+    /// starting from the one of `page_token`
+    fn iter_ledger(
+        &self,
+        page_token: Option<Address>,
+        page_size: usize,
+    ) -> (Vec<LedgerEntryInfo>, Option<Address>) {
+        self.sorted_ledger
+            .get_entries_info(page_token.as_ref(), page_size)
     }
 
     /// Reset the disk ledger.
@@ -3,15 +3,18 @@
 //! Module to interact with the disk ledger
 
 use massa_db_exports::{
-    DBBatch, MassaDirection, MassaIteratorMode, ShareableMassaDBController, CRUD_ERROR,
-    KEY_SER_ERROR, LEDGER_PREFIX, STATE_CF,
+    DBBatch, MassaDBController, MassaDirection, MassaIteratorMode, ShareableMassaDBController,
+    CRUD_ERROR, KEY_SER_ERROR, LEDGER_PREFIX, STATE_CF,
 };
 use massa_ledger_exports::*;
 use massa_models::amount::AmountDeserializer;
 use massa_models::bytecode::BytecodeDeserializer;
 use massa_models::datastore::get_prefix_bounds;
 use massa_models::{
-    address::Address, amount::AmountSerializer, bytecode::BytecodeSerializer, slot::Slot,
+    address::{Address, AddressSerializer},
+    amount::AmountSerializer,
+    bytecode::{Bytecode, BytecodeSerializer},
+    slot::Slot,
 };
 use massa_serialization::{
     DeserializeError, Deserializer, Serializer, U64VarIntDeserializer, U64VarIntSerializer,
@@ -167,11 +170,23 @@ impl LedgerDB {
         db.get_cf(STATE_CF, serialized_key).expect(CRUD_ERROR)
     }
 
-    /// Get every key of the datastore for a given address.
+    /// Get the keys of the datastore of a given address starting with a prefix, in key order.
+    ///
+    /// # Arguments
+    /// * `addr`: address to query
+    /// * `prefix`: prefix of the keys
+    /// * `start_after`: if set, only the keys strictly greater than this one are returned
+    /// * `limit`: if set, max number of keys to return
     ///
     /// # Returns
-    /// A `BTreeSet` of the datastore keys
-    pub fn get_datastore_keys(&self, addr: &Address, prefix: &[u8]) -> Option<BTreeSet<Vec<u8>>> {
+    /// A `BTreeSet` of the datastore keys, or None if the address does not exist
+    pub fn get_datastore_keys(
+        &self,
+        addr: &Address,
+        prefix: &[u8],
+        start_after: Option<&[u8]>,
+        limit: Option<usize>,
+    ) -> Option<BTreeSet<Vec<u8>>> {
         let db = self.db.read();
 
         // check if address exists, return None if it does not
@@ -184,13 +199,21 @@ impl LedgerDB {
             db.get_cf(STATE_CF, serialized_key).expect(CRUD_ERROR)?;
         }
 
-        // collect keys starting with prefix
+        // collect keys starting with prefix, from the one following start_after
         let start_prefix = datastore_prefix_from_address(addr, prefix);
         let end_prefix = end_prefix(&start_prefix);
+        let start_key = match start_after {
+            Some(key) => {
+                // the smallest key greater than start_after is followed by a zero byte
+                let after_key = datastore_prefix_from_address(addr, &[key, &[0]].concat());
+                after_key.max(start_prefix)
+            }
+            None => start_prefix,
+        };
         Some(
             db.iterator_cf(
                 STATE_CF,
-                MassaIteratorMode::From(&start_prefix, MassaDirection::Forward),
+                MassaIteratorMode::From(&start_key, MassaDirection::Forward),
             )
             .take_while(|(key, _)| match &end_prefix {
                 Some(end) => key < end,
@@ -206,10 +229,107 @@ impl LedgerDB {
                     _ => None,
                 }
             })
+            .take(limit.unwrap_or(usize::MAX))
             .collect(),
         )
     }
 
+    /// Get the dust accounts among the `scan_length` ledger entries starting from the one
+    /// of `from`, in key order and wrapping around at the end of the ledger, with their balance:
+    /// the accounts with a balance strictly below `max_balance`, and neither bytecode nor
+    /// datastore entries. Each entry is examined at most once.
+    pub fn get_dust_accounts(
+        &self,
+        from: &Address,
+        max_balance: Amount,
+        scan_length: usize,
+    ) -> Vec<(Address, Amount)> {
+        let db = self.db.read();
+        let mut dust_accounts = Vec::new();
+        let mut cursor = entry_prefix_from_address(from);
+        let mut first_address = None;
+        for _ in 0..scan_length {
+            // wrap around to the first entry at the end of the ledger
+            let Some(address) = self
+                .next_entry_address(&**db, &cursor)
+                .or_else(|| self.next_entry_address(&**db, LEDGER_PREFIX.as_bytes()))
+            else {
+                break;
+            };
+            if first_address == Some(address) {
+                break;
+            }
+            first_address.get_or_insert(address);
+            let balance = self.read_balance(&**db, &address);
+            let bytecode = self.read_bytecode(&**db, &address);
+            let datastore_prefix = datastore_prefix_from_address(&address, &[]);
+            let has_datastore = db
+                .iterator_cf(
+                    STATE_CF,
+                    MassaIteratorMode::From(&datastore_prefix, MassaDirection::Forward),
+                )
+                .next()
+                .map_or(false, |(key, _)| key.starts_with(&datastore_prefix));
+            if let Some(balance) = balance.filter(|balance| *balance < max_balance) {
+                if bytecode.map_or(true, |bytecode| bytecode.0.is_empty()) && !has_datastore {
+                    dust_accounts.push((address, balance));
+                }
+            }
+
+            // move to the entry of the next address
+            cursor = end_prefix(&entry_prefix_from_address(&address))
+                .unwrap_or_else(|| LEDGER_PREFIX.as_bytes().to_vec());
+        }
+        dust_accounts
+    }
+
+    /// Get the summaries of up to `page_size` ledger entries in key order, starting from the one
+    /// of `page_token`, or from the first entry if `None`.
+    ///
+    /// # Returns
+    /// The summaries, and the address of the entry following them if any,
+    /// to be used as the token of the next page
+    pub fn get_entries_info(
+        &self,
+        page_token: Option<&Address>,
+        page_size: usize,
+    ) -> (Vec<LedgerEntryInfo>, Option<Address>) {
+        let db = self.db.read();
+        let mut entries = Vec::new();
+        let mut cursor = match page_token {
+            Some(address) => entry_prefix_from_address(address),
+            None => LEDGER_PREFIX.as_bytes().to_vec(),
+        };
+        while let Some(address) = self.next_entry_address(&**db, &cursor) {
+            if entries.len() >= page_size {
+                return (entries, Some(address));
+            }
+            let datastore_prefix = datastore_prefix_from_address(&address, &[]);
+            let datastore_key_count = db
+                .iterator_cf(
+                    STATE_CF,
+                    MassaIteratorMode::From(&datastore_prefix, MassaDirection::Forward),
+                )
+                .take_while(|(key, _)| key.starts_with(&datastore_prefix))
+                .count();
+            entries.push(LedgerEntryInfo {
+                address,
+                balance: self.read_balance(&**db, &address).unwrap_or_default(),
+                bytecode_len: self
+                    .read_bytecode(&**db, &address)
+                    .map_or(0, |bytecode| bytecode.0.len()),
+                datastore_key_count,
+            });
+
+            // move to the entry of the next address
+            match end_prefix(&entry_prefix_from_address(&address)) {
+                Some(next) => cursor = next,
+                None => break,
+            }
+        }
+        (entries, None)
+    }
+
     pub fn reset(&self) {
         self.db.write().delete_prefix(LEDGER_PREFIX, STATE_CF, None);
     }
@@ -428,10 +548,59 @@ impl LedgerDB {
         }
     }
 
+    /// Serialize the key of a sub-entry of an address
+    fn serialize_key(&self, addr: &Address, key_type: KeyType) -> Vec<u8> {
+        let mut serialized_key = Vec::new();
+        self.key_serializer_db
+            .serialize(&Key::new(addr, key_type), &mut serialized_key)
+            .expect(KEY_SER_ERROR);
+        serialized_key
+    }
+
     /// Delete every sub-entry associated to the given address.
     ///
     /// # Arguments
     /// * batch: the given operation batch to update
+    /// Address of the first ledger entry with a key greater than or equal to `cursor`, if any
+    fn next_entry_address(&self, db: &dyn MassaDBController, cursor: &[u8]) -> Option<Address> {
+        let (key, _) = db
+            .iterator_cf(
+                STATE_CF,
+                MassaIteratorMode::From(cursor, MassaDirection::Forward),
+            )
+            .next()?;
+        if !key.starts_with(LEDGER_PREFIX.as_bytes()) {
+            return None;
+        }
+        let (_, key) = self
+            .key_deserializer_db
+            .deserialize::<DeserializeError>(&key)
+            .expect("could not deserialize ledger key from state db");
+        Some(key.address)
+    }
+
+    fn read_balance(&self, db: &dyn MassaDBController, addr: &Address) -> Option<Amount> {
+        db.get_cf(STATE_CF, self.serialize_key(addr, KeyType::BALANCE))
+            .expect(CRUD_ERROR)
+            .map(|bytes| {
+                self.amount_deserializer
+                    .deserialize::<DeserializeError>(&bytes)
+                    .expect("critical: invalid balance format")
+                    .1
+            })
+    }
+
+    fn read_bytecode(&self, db: &dyn MassaDBController, addr: &Address) -> Option<Bytecode> {
+        db.get_cf(STATE_CF, self.serialize_key(addr, KeyType::BYTECODE))
+            .expect(CRUD_ERROR)
+            .map(|bytes| {
+                self.bytecode_deserializer
+                    .deserialize::<DeserializeError>(&bytes)
+                    .expect("critical: invalid bytecode format")
+                    .1
+            })
+    }
+
     fn delete_entry(&self, addr: &Address, batch: &mut DBBatch) {
         let db = self.db.read();
 
@@ -542,6 +711,18 @@ impl LedgerDB {
     }
 }
 
+/// Prefix of the keys of all the sub-entries of an address
+fn entry_prefix_from_address(address: &Address) -> Vec<u8> {
+    let mut prefix = LEDGER_PREFIX.as_bytes().to_vec();
+    U64VarIntSerializer::new()
+        .serialize(&KEY_VERSION, &mut prefix)
+        .expect(KEY_SER_ERROR);
+    AddressSerializer::new()
+        .serialize(address, &mut prefix)
+        .expect(KEY_SER_ERROR);
+    prefix
+}
+
 /// For a given start prefix (inclusive), returns the correct end prefix (non-inclusive).
 /// This assumes the key bytes are ordered in lexicographical order.
 /// Since key length is not limited, for some case we return `None` because there is
@@ -564,6 +745,7 @@ mod tests {
     use massa_models::{
         address::Address,
         amount::{Amount, AmountDeserializer},
+        bytecode::Bytecode,
     };
     use massa_serialization::{DeserializeError, Deserializer};
     use massa_signature::KeyPair;
@@ -674,6 +856,142 @@ mod tests {
         assert!(ledger_db.get_entire_datastore(&addr).is_empty());
     }
 
+    #[test]
+    fn test_dust_accounts() {
+        let new_address =
+            || Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        let entry = |balance: &str, bytecode: Vec<u8>| LedgerEntry {
+            balance: Amount::from_str(balance).unwrap(),
+            bytecode: Bytecode(bytecode),
+            ..Default::default()
+        };
+
+        // an entry with a balance of 21 and datastore entries
+        let datastore_addr = new_address();
+        let (ledger_db, _) = init_test_ledger(datastore_addr);
+        let dust_addr = new_address();
+        let rich_addr = new_address();
+        let sc_addr = new_address();
+        let mut batch = DBBatch::new();
+        ledger_db.put_entry(&dust_addr, entry("10", vec![]), &mut batch);
+        ledger_db.put_entry(&rich_addr, entry("100", vec![]), &mut batch);
+        ledger_db.put_entry(&sc_addr, entry("10", vec![1, 2, 3]), &mut batch);
+        ledger_db
+            .db
+            .write()
+            .write_batch(batch, Default::default(), None);
+
+        // scan all the entries, sorted by key
+        let mut addresses = vec![datastore_addr, dust_addr, rich_addr, sc_addr];
+        addresses.sort_by_key(entry_prefix_from_address);
+        let max_balance = Amount::from_str("50").unwrap();
+        let dust_accounts = vec![(dust_addr, Amount::from_str("10").unwrap())];
+        assert_eq!(
+            ledger_db.get_dust_accounts(&addresses[0], max_balance, 10),
+            dust_accounts
+        );
+
+        // only the entries within the scan length are examined
+        let dust_index = addresses
+            .iter()
+            .position(|addr| addr == &dust_addr)
+            .unwrap();
+        assert!(ledger_db
+            .get_dust_accounts(&addresses[0], max_balance, dust_index)
+            .is_empty());
+        assert_eq!(
+            ledger_db.get_dust_accounts(&dust_addr, max_balance, 1),
+            dust_accounts
+        );
+
+        // the scan wraps around at the end of the ledger, examining each entry once
+        let next_addr = addresses[(dust_index + 1) % addresses.len()];
+        assert!(ledger_db
+            .get_dust_accounts(&next_addr, max_balance, addresses.len() - 1)
+            .is_empty());
+        assert_eq!(
+            ledger_db.get_dust_accounts(&next_addr, max_balance, addresses.len()),
+            dust_accounts
+        );
+        assert_eq!(
+            ledger_db.get_dust_accounts(&next_addr, max_balance, 10),
+            dust_accounts
+        );
+    }
+
+    #[test]
+    fn test_datastore_keys() {
+        let addr = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        let (ledger_db, data) = init_test_ledger(addr);
+        let keys = |prefix: &[u8], start_after: Option<&[u8]>, limit: Option<usize>| {
+            ledger_db
+                .get_datastore_keys(&addr, prefix, start_after, limit)
+                .unwrap()
+                .into_iter()
+                .collect::<Vec<_>>()
+        };
+
+        let all_keys: Vec<Vec<u8>> = data.keys().cloned().collect();
+        assert_eq!(keys(b"", None, None), all_keys);
+        assert_eq!(keys(b"2", None, None), vec![b"2".to_vec()]);
+
+        // pages of keys
+        assert_eq!(keys(b"", None, Some(2)), all_keys[..2]);
+        assert_eq!(keys(b"", Some(b"2"), Some(2)), all_keys[2..]);
+        assert_eq!(keys(b"", Some(b"10"), None), all_keys[1..]);
+        assert!(keys(b"", Some(b"3"), None).is_empty());
+
+        // the prefix is kept when starting after a key outside of it
+        assert_eq!(keys(b"2", Some(b"1"), None), vec![b"2".to_vec()]);
+        assert!(keys(b"2", Some(b"3"), None).is_empty());
+
+        let other_addr = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        assert!(ledger_db
+            .get_datastore_keys(&other_addr, b"", None, None)
+            .is_none());
+    }
+
+    #[test]
+    fn test_entries_info() {
+        let addr = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        let (ledger_db, _) = init_test_ledger(addr);
+        let other_addr = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        let mut batch = DBBatch::new();
+        let entry = LedgerEntry {
+            balance: Amount::from_str("10").unwrap(),
+            bytecode: Bytecode(vec![1, 2, 3]),
+            ..Default::default()
+        };
+        ledger_db.put_entry(&other_addr, entry, &mut batch);
+        ledger_db
+            .db
+            .write()
+            .write_batch(batch, Default::default(), None);
+        let mut addresses = vec![addr, other_addr];
+        addresses.sort_by_key(entry_prefix_from_address);
+
+        // one entry per page
+        let (first_page, page_token) = ledger_db.get_entries_info(None, 1);
+        assert_eq!(first_page.len(), 1);
+        assert_eq!(first_page[0].address, addresses[0]);
+        assert_eq!(page_token, Some(addresses[1]));
+        let (second_page, page_token) = ledger_db.get_entries_info(page_token.as_ref(), 1);
+        assert_eq!(second_page[0].address, addresses[1]);
+        assert_eq!(page_token, None);
+
+        // the summaries of the entries
+        let (entries, _) = ledger_db.get_entries_info(None, 10);
+        let info = entries.iter().find(|info| info.address == addr).unwrap();
+        assert_eq!(info.balance, Amount::from_str("21").unwrap());
+        assert_eq!(info.datastore_key_count, 3);
+        let info = entries
+            .iter()
+            .find(|info| info.address == other_addr)
+            .unwrap();
+        assert_eq!(info.bytecode_len, 3);
+        assert_eq!(info.datastore_key_count, 0);
+    }
+
     #[test]
     fn test_end_prefix() {
         assert_eq!(end_prefix(&[5, 6, 7]), Some(vec![5, 6, 8]));
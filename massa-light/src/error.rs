@@ -0,0 +1,25 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! This file defines the errors of the light client verifications
+
+use displaydoc::Display;
+use massa_hash::Hash;
+use massa_models::block_id::BlockId;
+use thiserror::Error;
+
+/// Light client error
+#[non_exhaustive]
+#[derive(Display, Error, Debug)]
+pub enum LightError {
+    /// state fingerprint mismatch: expected {expected}, computed {computed}
+    StateHashMismatch {
+        /// trusted fingerprint
+        expected: Hash,
+        /// fingerprint of the received state
+        computed: Hash,
+    },
+    /// invalid block header {0}: {1}
+    InvalidHeader(BlockId, String),
+    /// broken header chain at block {0}: {1}
+    BrokenChain(BlockId, String),
+}
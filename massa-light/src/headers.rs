@@ -0,0 +1,135 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Verification of block headers and of chains of block headers
+
+use crate::error::LightError;
+use massa_models::block_header::{BlockHeaderSerializer, SecuredHeader};
+use massa_models::block_id::BlockId;
+use massa_models::secure_share::{Id, SecureShareContent};
+use massa_models::slot::Slot;
+use massa_serialization::Serializer;
+
+/// Verify that a block header matches its id and is signed by its creator
+///
+/// # Arguments
+/// * `header`: the block header, with its serialized content
+/// * `chain_id`: id of the chain of the block
+pub fn verify_header(header: &SecuredHeader, chain_id: u64) -> Result<(), LightError> {
+    let invalid = |reason: &str| LightError::InvalidHeader(header.id, reason.to_string());
+    let mut serialized_content = Vec::new();
+    BlockHeaderSerializer::new()
+        .serialize(&header.content, &mut serialized_content)
+        .map_err(|err| invalid(&err.to_string()))?;
+    if serialized_content != header.serialized_data {
+        return Err(invalid("the content does not match the serialized data"));
+    }
+    let hash = header.content.compute_hash(
+        &header.serialized_data,
+        &header.content_creator_pub_key,
+        chain_id,
+    );
+    if hash != *header.id.get_hash() {
+        return Err(invalid("the id does not match the content"));
+    }
+    header
+        .verify_signature()
+        .map_err(|err| invalid(&err.to_string()))
+}
+
+/// Verify a chain of block headers of a same thread, starting from a trusted block:
+/// every header must be valid, and the child in its thread of the previous one,
+/// the first one being the child of the trusted block.
+///
+/// # Arguments
+/// * `trusted_block_id`: id of the block the chain starts from
+/// * `headers`: the headers of the chain, from the oldest one
+/// * `chain_id`: id of the chain of the blocks
+pub fn verify_header_chain(
+    trusted_block_id: &BlockId,
+    headers: &[SecuredHeader],
+    chain_id: u64,
+) -> Result<(), LightError> {
+    let mut parent_id = *trusted_block_id;
+    let mut parent_slot: Option<Slot> = None;
+    for header in headers {
+        verify_header(header, chain_id)?;
+        let slot = header.content.slot;
+        if header.content.parents.get(slot.thread as usize) != Some(&parent_id) {
+            return Err(LightError::BrokenChain(
+                header.id,
+                format!("its parent in thread {} is not {}", slot.thread, parent_id),
+            ));
+        }
+        if let Some(parent_slot) = parent_slot {
+            if slot.thread != parent_slot.thread || slot <= parent_slot {
+                return Err(LightError::BrokenChain(
+                    header.id,
+                    format!("its slot {} does not follow the one of its parent", slot),
+                ));
+            }
+        }
+        parent_id = header.id;
+        parent_slot = Some(slot);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_hash::Hash;
+    use massa_models::block_header::BlockHeader;
+    use massa_models::config::{CHAINID, THREAD_COUNT};
+    use massa_signature::KeyPair;
+
+    fn create_header(slot: Slot, parent: BlockId, keypair: &KeyPair) -> SecuredHeader {
+        let mut parents: Vec<BlockId> = (0..THREAD_COUNT)
+            .map(|thread| BlockId::new(Hash::compute_from(&[thread])))
+            .collect();
+        parents[slot.thread as usize] = parent;
+        BlockHeader::new_verifiable(
+            BlockHeader {
+                current_version: 0,
+                announced_version: None,
+                slot,
+                parents,
+                operation_merkle_root: Hash::compute_from(&Vec::new()),
+                endorsements: Vec::new(),
+                denunciations: Vec::new(),
+            },
+            BlockHeaderSerializer::new(),
+            keypair,
+            *CHAINID,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_header_chain() {
+        let keypair = KeyPair::generate(0).unwrap();
+        let trusted_block_id = BlockId::new(Hash::compute_from(b"trusted"));
+        let header_1 = create_header(Slot::new(1, 2), trusted_block_id, &keypair);
+        let header_2 = create_header(Slot::new(3, 2), header_1.id, &keypair);
+        let headers = vec![header_1.clone(), header_2];
+        verify_header_chain(&trusted_block_id, &headers, *CHAINID).unwrap();
+
+        // the chain must start from the trusted block
+        let other_block_id = BlockId::new(Hash::compute_from(b"other"));
+        assert!(verify_header_chain(&other_block_id, &headers, *CHAINID).is_err());
+
+        // the slots must increase
+        let header_3 = create_header(Slot::new(1, 2), header_1.id, &keypair);
+        let headers = vec![header_1.clone(), header_3];
+        assert!(verify_header_chain(&trusted_block_id, &headers, *CHAINID).is_err());
+
+        // a modified header is rejected
+        let mut header = header_1;
+        header.content.slot = Slot::new(2, 2);
+        assert!(verify_header(&header, *CHAINID).is_err());
+        header.serialized_data.clear();
+        BlockHeaderSerializer::new()
+            .serialize(&header.content, &mut header.serialized_data)
+            .unwrap();
+        assert!(verify_header(&header, *CHAINID).is_err());
+    }
+}
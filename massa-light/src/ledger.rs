@@ -0,0 +1,110 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Reads of the ledger entries of a verified final state.
+//!
+//! The sub-entries of an address are stored under a key made of the ledger prefix,
+//! the key version, the address and the identifier of the sub-entry,
+//! followed by the key of the entry for the datastore entries.
+
+use crate::state::VerifiedState;
+use massa_models::address::{Address, AddressSerializer};
+use massa_models::amount::{Amount, AmountDeserializer};
+use massa_models::bytecode::{Bytecode, BytecodeDeserializer};
+use massa_models::config::MAX_BYTECODE_LENGTH;
+use massa_serialization::{DeserializeError, Deserializer, Serializer, U64VarIntSerializer};
+use std::ops::Bound::Included;
+
+const LEDGER_PREFIX: &str = "ledger/";
+const KEY_VERSION: u64 = 0;
+const BALANCE_IDENT: u8 = 1u8;
+const BYTECODE_IDENT: u8 = 2u8;
+const DATASTORE_IDENT: u8 = 3u8;
+
+impl VerifiedState {
+    /// Get the balance of an address, or None if it has no ledger entry
+    pub fn get_balance(&self, address: &Address) -> Option<Amount> {
+        let value = self.get(&ledger_key(address, BALANCE_IDENT, &[]))?;
+        AmountDeserializer::new(Included(Amount::MIN), Included(Amount::MAX))
+            .deserialize::<DeserializeError>(value)
+            .ok()
+            .map(|(_, balance)| balance)
+    }
+
+    /// Get the bytecode of an address, or None if it has no ledger entry
+    pub fn get_bytecode(&self, address: &Address) -> Option<Bytecode> {
+        let value = self.get(&ledger_key(address, BYTECODE_IDENT, &[]))?;
+        BytecodeDeserializer::new(MAX_BYTECODE_LENGTH)
+            .deserialize::<DeserializeError>(value)
+            .ok()
+            .map(|(_, bytecode)| bytecode)
+    }
+
+    /// Get the value of a datastore entry of an address, or None if it does not exist
+    pub fn get_data_entry(&self, address: &Address, key: &[u8]) -> Option<Vec<u8>> {
+        self.get(&ledger_key(address, DATASTORE_IDENT, key))
+            .map(|value| value.to_vec())
+    }
+}
+
+/// Key of a sub-entry of the ledger entry of an address
+fn ledger_key(address: &Address, ident: u8, datastore_key: &[u8]) -> Vec<u8> {
+    let mut key = LEDGER_PREFIX.as_bytes().to_vec();
+    U64VarIntSerializer::new()
+        .serialize(&KEY_VERSION, &mut key)
+        .expect("critical: key version serialization failed");
+    AddressSerializer::new()
+        .serialize(address, &mut key)
+        .expect("critical: address serialization failed");
+    key.push(ident);
+    key.extend(datastore_key);
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::StateHasher;
+    use massa_models::amount::AmountSerializer;
+    use massa_models::bytecode::BytecodeSerializer;
+    use massa_signature::KeyPair;
+    use std::collections::BTreeMap;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_ledger_entries() {
+        let address = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        let mut balance = Vec::new();
+        AmountSerializer::new()
+            .serialize(&Amount::from_str("42").unwrap(), &mut balance)
+            .unwrap();
+        let mut bytecode = Vec::new();
+        BytecodeSerializer::new()
+            .serialize(&Bytecode(vec![1, 2, 3]), &mut bytecode)
+            .unwrap();
+        let entries = BTreeMap::from([
+            (ledger_key(&address, BALANCE_IDENT, &[]), balance),
+            (ledger_key(&address, BYTECODE_IDENT, &[]), bytecode),
+            (
+                ledger_key(&address, DATASTORE_IDENT, b"key"),
+                b"value".to_vec(),
+            ),
+        ]);
+        let mut hasher = StateHasher::new();
+        for (key, value) in entries.iter() {
+            hasher.add_entry(key, value);
+        }
+
+        let state = VerifiedState::new(entries, &hasher.get_fingerprint()).unwrap();
+        assert_eq!(state.get_balance(&address), Amount::from_str("42").ok());
+        assert_eq!(state.get_bytecode(&address), Some(Bytecode(vec![1, 2, 3])));
+        assert_eq!(
+            state.get_data_entry(&address, b"key"),
+            Some(b"value".to_vec())
+        );
+        assert_eq!(state.get_data_entry(&address, b"other_key"), None);
+
+        let other_address =
+            Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        assert_eq!(state.get_balance(&other_address), None);
+    }
+}
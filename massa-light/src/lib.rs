@@ -0,0 +1,35 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! # General description
+//!
+//! Verification of the data returned by the nodes, without running a node.
+//! It allows wallets and bridges to check the responses of the nodes they query.
+//!
+//! # Final state
+//!
+//! The final state hash is the XOR of the hashes of all the entries of the final state,
+//! its fingerprint being the hash of this XOR hash.
+//! [`StateHasher`] computes it from the entries of a state, and [`VerifiedState`] holds
+//! a complete state whose fingerprint was checked.
+//!
+//! # Ledger
+//!
+//! The XOR hash does not allow proving the presence or the absence of a single entry:
+//! any entry can be made consistent with a given hash by choosing the hash of the others.
+//! The ledger entries are thus read from a [`VerifiedState`], that is from a complete final state
+//! (e.g. a final state snapshot) checked against a trusted fingerprint.
+//!
+//! # Block headers
+//!
+//! [`verify_header`] checks that a block header matches its id and is signed by its creator,
+//! and [`verify_header_chain`] checks a chain of headers of a same thread from a trusted block.
+//! The selection of the block producers is not checked.
+
+mod error;
+mod headers;
+mod ledger;
+mod state;
+
+pub use error::LightError;
+pub use headers::{verify_header, verify_header_chain};
+pub use state::{StateHasher, VerifiedState};
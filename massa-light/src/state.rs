@@ -0,0 +1,102 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Final state hash computation and verification
+
+use crate::error::LightError;
+use massa_hash::{Hash, HashXof, HASH_XOF_SIZE_BYTES};
+use std::collections::BTreeMap;
+
+/// Incremental computation of the final state hash, from the key-value entries of the state
+pub struct StateHasher {
+    hash: HashXof<HASH_XOF_SIZE_BYTES>,
+}
+
+impl Default for StateHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StateHasher {
+    /// Hasher of an empty state
+    pub fn new() -> Self {
+        Self {
+            hash: HashXof([0u8; HASH_XOF_SIZE_BYTES]),
+        }
+    }
+
+    /// Add an entry of the state, in any order
+    pub fn add_entry(&mut self, key: &[u8], value: &[u8]) {
+        self.hash ^= HashXof::compute_from_tuple(&[key, value]);
+    }
+
+    /// Get the XOR hash of the entries added so far
+    pub fn get_hash(&self) -> HashXof<HASH_XOF_SIZE_BYTES> {
+        self.hash
+    }
+
+    /// Get the fingerprint of the entries added so far, as returned by the nodes
+    pub fn get_fingerprint(&self) -> Hash {
+        Hash::compute_from(self.hash.to_bytes())
+    }
+}
+
+/// A complete final state, whose fingerprint matches a trusted one
+pub struct VerifiedState {
+    pub(crate) entries: BTreeMap<Vec<u8>, Vec<u8>>,
+}
+
+impl VerifiedState {
+    /// Check all the entries of a final state against a trusted fingerprint
+    ///
+    /// # Arguments
+    /// * `entries`: all the key-value entries of the final state
+    /// * `fingerprint`: trusted fingerprint of the final state
+    pub fn new(
+        entries: BTreeMap<Vec<u8>, Vec<u8>>,
+        fingerprint: &Hash,
+    ) -> Result<Self, LightError> {
+        let mut hasher = StateHasher::new();
+        for (key, value) in entries.iter() {
+            hasher.add_entry(key, value);
+        }
+        let computed = hasher.get_fingerprint();
+        if computed != *fingerprint {
+            return Err(LightError::StateHashMismatch {
+                expected: *fingerprint,
+                computed,
+            });
+        }
+        Ok(Self { entries })
+    }
+
+    /// Get the value of an entry of the state
+    pub fn get(&self, key: &[u8]) -> Option<&[u8]> {
+        self.entries.get(key).map(|value| value.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_hash() {
+        let entries: BTreeMap<Vec<u8>, Vec<u8>> =
+            (0..10u8).map(|i| (vec![i], vec![i; i as usize])).collect();
+
+        // the hash does not depend on the order of the entries
+        let mut hasher = StateHasher::new();
+        for (key, value) in entries.iter().rev() {
+            hasher.add_entry(key, value);
+        }
+        let fingerprint = hasher.get_fingerprint();
+        let state = VerifiedState::new(entries.clone(), &fingerprint).unwrap();
+        assert_eq!(state.get(&[3]), Some(&[3u8, 3, 3][..]));
+
+        // a modified state is rejected
+        let mut modified_entries = entries;
+        modified_entries.insert(vec![3], vec![4]);
+        assert!(VerifiedState::new(modified_entries, &fingerprint).is_err());
+    }
+}
@@ -13,7 +13,10 @@ use std::{
 };
 
 use lazy_static::lazy_static;
-use prometheus::{register_int_gauge, Gauge, Histogram, IntCounter, IntGauge};
+use prometheus::{
+    register_int_gauge, Gauge, Histogram, HistogramOpts, HistogramVec, IntCounter, IntCounterVec,
+    IntGauge, IntGaugeVec, Opts,
+};
 use tokio::sync::oneshot::Sender;
 use tracing::warn;
 
@@ -97,6 +100,14 @@ pub struct MassaMetrics {
     endorsements_pool: IntGauge,
     /// number of elements in the denunciation pool
     denunciations_pool: IntGauge,
+    /// time elapsed since the slot of the oldest endorsement of the endorsement pool, in ms
+    endorsement_pool_oldest_age: IntGauge,
+    /// time elapsed since the slot denounced by the oldest denunciation of the pool, in ms
+    denunciation_pool_oldest_age: IntGauge,
+    /// mean share of the endorsement indexes covered at the slots of the endorsement pool
+    endorsement_pool_coverage: Gauge,
+    /// number of operations evicted from the operation pool, by reason
+    operation_pool_evictions: IntCounterVec,
 
     // number of autonomous SCs messages in pool
     async_message_pool_size: IntGauge,
@@ -110,6 +121,22 @@ pub struct MassaMetrics {
     bootstrap_peers_success: IntCounter,
     /// number of times we failed/refused to bootstrap someone
     bootstrap_peers_failed: IntCounter,
+    /// number of bootstrap sessions our server is serving
+    bootstrap_server_sessions: IntGauge,
+    /// number of bootstrap sessions waiting for a free slot on our server
+    bootstrap_server_queued_sessions: IntGauge,
+    /// phase of the bootstrap of our node
+    bootstrap_phase: IntGauge,
+    /// bootstrap servers used by our node (1), by address
+    bootstrap_server: IntGaugeVec,
+    /// bytes received from the bootstrap servers
+    bootstrap_bytes_received: IntCounter,
+    /// final state and consensus parts received from the bootstrap servers
+    bootstrap_parts_received: IntCounter,
+    /// estimated share of the final state downloaded by the bootstrap
+    bootstrap_state_progress: Gauge,
+    /// estimated time left to download the final state, in seconds
+    bootstrap_eta: IntGauge,
 
     /// number of times we successfully tested someone
     protocol_tester_success: IntCounter,
@@ -126,6 +153,29 @@ pub struct MassaMetrics {
     /// executed final slot with block (not miss)
     executed_final_slot_with_block: IntCounter,
 
+    /// gas used by the operations of the last executed final slot
+    final_slot_gas_used: IntGauge,
+    /// max gas usable by the operations of a block
+    final_slot_gas_limit: IntGauge,
+    /// number of operations of the last executed final slot
+    final_slot_operations: IntGauge,
+    /// number of events emitted by the last executed final slot
+    final_slot_events: IntGauge,
+    /// execution time of the final slots, in seconds
+    final_slot_execution_time: Histogram,
+
+    /// number of read-only execution requests waiting to be executed
+    readonly_queue_depth: IntGauge,
+    /// number of read-only execution requests queued or being executed
+    readonly_requests_in_flight: IntGauge,
+    /// number of read-only execution requests rejected by the quotas
+    readonly_requests_rejected: IntCounter,
+
+    // scheduled compactions of the database
+    db_compactions: IntCounter,
+    db_compaction_reclaimed_bytes: IntCounter,
+    db_compaction_time: Histogram,
+
     /// total bytes receive by peernet manager
     peernet_total_bytes_received: IntCounter,
     /// total bytes sent by peernet manager
@@ -157,6 +207,8 @@ pub struct MassaMetrics {
     consensus_state_incoming_index: IntGauge,
     consensus_state_discarded_index: IntGauge,
     consensus_state_block_statuses: IntGauge,
+    /// number of blocks that became stale
+    consensus_stale_blocks: IntCounter,
 
     // endorsement cache
     endorsement_cache_checked_endorsements: IntGauge,
@@ -169,6 +221,23 @@ pub struct MassaMetrics {
     final_cursor_thread: IntGauge,
     final_cursor_period: IntGauge,
 
+    /// number of gRPC requests handled, by service, method and status code
+    grpc_requests: IntCounterVec,
+    /// gRPC request duration in seconds, by service and method
+    grpc_request_duration: HistogramVec,
+    /// gRPC response size in bytes, by service and method
+    grpc_response_size: HistogramVec,
+
+    /// disk usage in bytes, by node subsystem
+    disk_usage: IntGaugeVec,
+
+    /// number of network messages, by message type and direction
+    network_messages: IntCounterVec,
+    /// size in bytes of the network messages, by message type and direction
+    network_message_bytes: IntCounterVec,
+    /// time in seconds spent processing the received network messages, by message type
+    network_message_processing_time: HistogramVec,
+
     // peer bandwidth (bytes sent, bytes received)
     peers_bandwidth: Arc<RwLock<HashMap<String, (IntCounter, IntCounter)>>>,
 
@@ -222,6 +291,77 @@ impl MassaMetrics {
         )
         .unwrap();
 
+        // final slot execution
+        let final_slot_gas_used = IntGauge::new(
+            "final_slot_gas_used",
+            "gas used by the operations of the last executed final slot",
+        )
+        .unwrap();
+        let final_slot_gas_limit = IntGauge::new(
+            "final_slot_gas_limit",
+            "max gas usable by the operations of a block",
+        )
+        .unwrap();
+        let final_slot_operations = IntGauge::new(
+            "final_slot_operations",
+            "number of operations of the last executed final slot",
+        )
+        .unwrap();
+        let final_slot_events = IntGauge::new(
+            "final_slot_events",
+            "number of events emitted by the last executed final slot",
+        )
+        .unwrap();
+        let final_slot_execution_time = Histogram::with_opts(
+            HistogramOpts::new(
+                "final_slot_execution_time",
+                "execution time of the final slots in seconds",
+            )
+            .buckets(vec![
+                0.001, 0.005, 0.010, 0.025, 0.050, 0.100, 0.250, 0.500, 1.0, 2.5, 5.0, 10.0,
+            ]),
+        )
+        .unwrap();
+
+        // read-only executions
+        let readonly_queue_depth = IntGauge::new(
+            "readonly_queue_depth",
+            "number of read-only execution requests waiting to be executed",
+        )
+        .unwrap();
+        let readonly_requests_in_flight = IntGauge::new(
+            "readonly_requests_in_flight",
+            "number of read-only execution requests queued or being executed",
+        )
+        .unwrap();
+        let readonly_requests_rejected = IntCounter::new(
+            "readonly_requests_rejected",
+            "number of read-only execution requests rejected by the quotas",
+        )
+        .unwrap();
+
+        // scheduled compactions of the database
+        let db_compactions = IntCounter::new(
+            "db_compactions",
+            "number of scheduled compactions of the database column families",
+        )
+        .unwrap();
+        let db_compaction_reclaimed_bytes = IntCounter::new(
+            "db_compaction_reclaimed_bytes",
+            "disk space reclaimed by the scheduled compactions of the database, in bytes",
+        )
+        .unwrap();
+        let db_compaction_time = Histogram::with_opts(
+            HistogramOpts::new(
+                "db_compaction_time",
+                "duration of the scheduled compactions of the database column families in seconds",
+            )
+            .buckets(vec![
+                1.0, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0, 600.0, 1800.0,
+            ]),
+        )
+        .unwrap();
+
         let protocol_tester_success = IntCounter::new(
             "protocol_tester_success",
             "number of times we successfully tested someone",
@@ -249,6 +389,29 @@ impl MassaMetrics {
             "number of elements in the denunciation pool",
         )
         .unwrap();
+        let endorsement_pool_oldest_age = IntGauge::new(
+            "endorsement_pool_oldest_age",
+            "time elapsed since the slot of the oldest endorsement of the endorsement pool, in ms",
+        )
+        .unwrap();
+        let denunciation_pool_oldest_age = IntGauge::new(
+            "denunciation_pool_oldest_age",
+            "time elapsed since the slot denounced by the oldest denunciation of the pool, in ms",
+        )
+        .unwrap();
+        let endorsement_pool_coverage = Gauge::new(
+            "endorsement_pool_coverage",
+            "mean share of the endorsement indexes covered at the slots of the endorsement pool",
+        )
+        .unwrap();
+        let operation_pool_evictions = IntCounterVec::new(
+            Opts::new(
+                "operation_pool_evictions",
+                "number of operations evicted from the operation pool",
+            ),
+            &["reason"],
+        )
+        .unwrap();
 
         let async_message_pool_size = IntGauge::new(
             "async_message_pool_size",
@@ -277,6 +440,46 @@ impl MassaMetrics {
             "number of times we failed/refused to bootstrap someone",
         )
         .unwrap();
+        let bootstrap_phase = IntGauge::new(
+            "bootstrap_phase",
+            "phase of the bootstrap of our node: 0 not started, 1 connecting, 2 final state, 3 consensus, 4 peers, 5 finished",
+        )
+        .unwrap();
+        let bootstrap_server = IntGaugeVec::new(
+            Opts::new("bootstrap_server", "bootstrap servers used by our node"),
+            &["address"],
+        )
+        .unwrap();
+        let bootstrap_bytes_received = IntCounter::new(
+            "bootstrap_bytes_received",
+            "bytes received from the bootstrap servers",
+        )
+        .unwrap();
+        let bootstrap_parts_received = IntCounter::new(
+            "bootstrap_parts_received",
+            "final state and consensus parts received from the bootstrap servers",
+        )
+        .unwrap();
+        let bootstrap_state_progress = Gauge::new(
+            "bootstrap_state_progress",
+            "estimated share of the final state downloaded by the bootstrap, from 0 to 1",
+        )
+        .unwrap();
+        let bootstrap_eta = IntGauge::new(
+            "bootstrap_eta",
+            "estimated time left to download the final state, in seconds",
+        )
+        .unwrap();
+        let bootstrap_server_sessions = IntGauge::new(
+            "bootstrap_server_sessions",
+            "number of bootstrap sessions our server is serving",
+        )
+        .unwrap();
+        let bootstrap_server_queued_sessions = IntGauge::new(
+            "bootstrap_server_queued_sessions",
+            "number of bootstrap sessions waiting for a free slot on our server",
+        )
+        .unwrap();
 
         let active_history = IntGauge::new(
             "active_history",
@@ -375,6 +578,12 @@ impl MassaMetrics {
         )
         .unwrap();
 
+        let consensus_stale_blocks = IntCounter::new(
+            "consensus_stale_blocks",
+            "number of blocks discarded for being incompatible with a final block",
+        )
+        .unwrap();
+
         let endorsement_cache_checked_endorsements = IntGauge::new(
             "endorsement_cache_checked_endorsements",
             "endorsement cache checked endorsements size",
@@ -406,6 +615,59 @@ impl MassaMetrics {
         )
         .unwrap();
 
+        // gRPC
+        let grpc_requests = IntCounterVec::new(
+            Opts::new("grpc_requests", "number of gRPC requests handled"),
+            &["service", "method", "code"],
+        )
+        .unwrap();
+        let grpc_request_duration = HistogramVec::new(
+            HistogramOpts::new("grpc_request_duration", "gRPC request duration in seconds")
+                .buckets(vec![
+                    0.001, 0.005, 0.010, 0.025, 0.050, 0.100, 0.250, 0.500, 1.0, 2.5, 5.0, 10.0,
+                ]),
+            &["service", "method"],
+        )
+        .unwrap();
+        let grpc_response_size = HistogramVec::new(
+            HistogramOpts::new("grpc_response_size", "gRPC response size in bytes")
+                .buckets(prometheus::exponential_buckets(64.0, 4.0, 10).unwrap()),
+            &["service", "method"],
+        )
+        .unwrap();
+
+        let disk_usage = IntGaugeVec::new(
+            Opts::new("disk_usage", "disk usage in bytes of the node subsystems"),
+            &["subsystem"],
+        )
+        .unwrap();
+
+        // network messages (protocol and bootstrap)
+        let network_messages = IntCounterVec::new(
+            Opts::new("network_messages", "number of network messages"),
+            &["type", "direction"],
+        )
+        .unwrap();
+        let network_message_bytes = IntCounterVec::new(
+            Opts::new(
+                "network_message_bytes",
+                "size in bytes of the network messages",
+            ),
+            &["type", "direction"],
+        )
+        .unwrap();
+        let network_message_processing_time = HistogramVec::new(
+            HistogramOpts::new(
+                "network_message_processing_time",
+                "time in seconds spent processing the received network messages",
+            )
+            .buckets(vec![
+                0.0001, 0.0005, 0.001, 0.005, 0.010, 0.025, 0.050, 0.100, 0.250, 0.500, 1.0,
+            ]),
+            &["type"],
+        )
+        .unwrap();
+
         let mut stopper = MetricsStopper::default();
 
         if enabled {
@@ -428,6 +690,7 @@ impl MassaMetrics {
                 let _ = prometheus::register(Box::new(consensus_state_incoming_index.clone()));
                 let _ = prometheus::register(Box::new(consensus_state_discarded_index.clone()));
                 let _ = prometheus::register(Box::new(consensus_state_block_statuses.clone()));
+                let _ = prometheus::register(Box::new(consensus_stale_blocks.clone()));
                 let _ = prometheus::register(Box::new(
                     operation_cache_checked_operations_prefix.clone(),
                 ));
@@ -443,14 +706,37 @@ impl MassaMetrics {
                 let _ = prometheus::register(Box::new(banned_peers.clone()));
                 let _ = prometheus::register(Box::new(executed_final_slot.clone()));
                 let _ = prometheus::register(Box::new(executed_final_slot_with_block.clone()));
+                let _ = prometheus::register(Box::new(final_slot_gas_used.clone()));
+                let _ = prometheus::register(Box::new(final_slot_gas_limit.clone()));
+                let _ = prometheus::register(Box::new(final_slot_operations.clone()));
+                let _ = prometheus::register(Box::new(final_slot_events.clone()));
+                let _ = prometheus::register(Box::new(final_slot_execution_time.clone()));
+                let _ = prometheus::register(Box::new(readonly_queue_depth.clone()));
+                let _ = prometheus::register(Box::new(readonly_requests_in_flight.clone()));
+                let _ = prometheus::register(Box::new(readonly_requests_rejected.clone()));
+                let _ = prometheus::register(Box::new(db_compactions.clone()));
+                let _ = prometheus::register(Box::new(db_compaction_reclaimed_bytes.clone()));
+                let _ = prometheus::register(Box::new(db_compaction_time.clone()));
                 let _ = prometheus::register(Box::new(active_history.clone()));
                 let _ = prometheus::register(Box::new(bootstrap_counter.clone()));
                 let _ = prometheus::register(Box::new(bootstrap_success.clone()));
                 let _ = prometheus::register(Box::new(bootstrap_failed.clone()));
+                let _ = prometheus::register(Box::new(bootstrap_phase.clone()));
+                let _ = prometheus::register(Box::new(bootstrap_server.clone()));
+                let _ = prometheus::register(Box::new(bootstrap_bytes_received.clone()));
+                let _ = prometheus::register(Box::new(bootstrap_parts_received.clone()));
+                let _ = prometheus::register(Box::new(bootstrap_state_progress.clone()));
+                let _ = prometheus::register(Box::new(bootstrap_eta.clone()));
+                let _ = prometheus::register(Box::new(bootstrap_server_sessions.clone()));
+                let _ = prometheus::register(Box::new(bootstrap_server_queued_sessions.clone()));
                 let _ = prometheus::register(Box::new(process_available_processors.clone()));
                 let _ = prometheus::register(Box::new(operations_pool.clone()));
                 let _ = prometheus::register(Box::new(endorsements_pool.clone()));
                 let _ = prometheus::register(Box::new(denunciations_pool.clone()));
+                let _ = prometheus::register(Box::new(endorsement_pool_oldest_age.clone()));
+                let _ = prometheus::register(Box::new(denunciation_pool_oldest_age.clone()));
+                let _ = prometheus::register(Box::new(endorsement_pool_coverage.clone()));
+                let _ = prometheus::register(Box::new(operation_pool_evictions.clone()));
                 let _ = prometheus::register(Box::new(protocol_tester_success.clone()));
                 let _ = prometheus::register(Box::new(protocol_tester_failed.clone()));
                 let _ = prometheus::register(Box::new(sc_messages_final.clone()));
@@ -458,6 +744,13 @@ impl MassaMetrics {
                 let _ = prometheus::register(Box::new(current_time_period.clone()));
                 let _ = prometheus::register(Box::new(current_time_thread.clone()));
                 let _ = prometheus::register(Box::new(block_slot_delay.clone()));
+                let _ = prometheus::register(Box::new(grpc_requests.clone()));
+                let _ = prometheus::register(Box::new(grpc_request_duration.clone()));
+                let _ = prometheus::register(Box::new(grpc_response_size.clone()));
+                let _ = prometheus::register(Box::new(disk_usage.clone()));
+                let _ = prometheus::register(Box::new(network_messages.clone()));
+                let _ = prometheus::register(Box::new(network_message_bytes.clone()));
+                let _ = prometheus::register(Box::new(network_message_processing_time.clone()));
 
                 stopper = server::bind_metrics(addr);
             }
@@ -476,17 +769,40 @@ impl MassaMetrics {
                 operations_pool,
                 endorsements_pool,
                 denunciations_pool,
+                endorsement_pool_oldest_age,
+                denunciation_pool_oldest_age,
+                endorsement_pool_coverage,
+                operation_pool_evictions,
                 async_message_pool_size,
                 sc_messages_final,
                 bootstrap_counter,
                 bootstrap_peers_success: bootstrap_success,
                 bootstrap_peers_failed: bootstrap_failed,
+                bootstrap_server_sessions,
+                bootstrap_server_queued_sessions,
+                bootstrap_phase,
+                bootstrap_server,
+                bootstrap_bytes_received,
+                bootstrap_parts_received,
+                bootstrap_state_progress,
+                bootstrap_eta,
                 protocol_tester_success,
                 protocol_tester_failed,
                 protocol_known_peers: know_peers,
                 protocol_banned_peers: banned_peers,
                 executed_final_slot,
                 executed_final_slot_with_block,
+                final_slot_gas_used,
+                final_slot_gas_limit,
+                final_slot_operations,
+                final_slot_events,
+                final_slot_execution_time,
+                readonly_queue_depth,
+                readonly_requests_in_flight,
+                readonly_requests_rejected,
+                db_compactions,
+                db_compaction_reclaimed_bytes,
+                db_compaction_time,
                 peernet_total_bytes_received,
                 peernet_total_bytes_sent,
                 block_slot_delay,
@@ -503,6 +819,7 @@ impl MassaMetrics {
                 consensus_state_incoming_index,
                 consensus_state_discarded_index,
                 consensus_state_block_statuses,
+                consensus_stale_blocks,
                 endorsement_cache_checked_endorsements,
                 endorsement_cache_known_by_peer,
                 // blocks_counter,
@@ -512,6 +829,13 @@ impl MassaMetrics {
                 active_cursor_period,
                 final_cursor_thread,
                 final_cursor_period,
+                grpc_requests,
+                grpc_request_duration,
+                grpc_response_size,
+                disk_usage,
+                network_messages,
+                network_message_bytes,
+                network_message_processing_time,
                 peers_bandwidth: Arc::new(RwLock::new(HashMap::new())),
                 tick_delay,
             },
@@ -572,6 +896,10 @@ impl MassaMetrics {
             .set(active_index_without_ops as i64);
     }
 
+    pub fn inc_stale_blocks(&self, diff: u64) {
+        self.consensus_stale_blocks.inc_by(diff);
+    }
+
     pub fn set_block_cache_metrics(&self, checked_header_size: usize, blocks_known_by_peer: usize) {
         self.block_cache_checked_headers_size
             .set(checked_header_size as i64);
@@ -634,6 +962,41 @@ impl MassaMetrics {
         self.executed_final_slot_with_block.inc();
     }
 
+    /// Record the gas usage, fullness and execution time of an executed final slot
+    pub fn set_final_slot_stats(
+        &self,
+        gas_used: u64,
+        gas_limit: u64,
+        operation_count: usize,
+        event_count: usize,
+        execution_time: Duration,
+    ) {
+        self.final_slot_gas_used.set(gas_used as i64);
+        self.final_slot_gas_limit.set(gas_limit as i64);
+        self.final_slot_operations.set(operation_count as i64);
+        self.final_slot_events.set(event_count as i64);
+        self.final_slot_execution_time
+            .observe(execution_time.as_secs_f64());
+    }
+
+    pub fn set_readonly_queue_depth(&self, nb: usize) {
+        self.readonly_queue_depth.set(nb as i64);
+    }
+
+    pub fn set_readonly_requests_in_flight(&self, nb: usize) {
+        self.readonly_requests_in_flight.set(nb as i64);
+    }
+
+    pub fn inc_readonly_requests_rejected(&self) {
+        self.readonly_requests_rejected.inc();
+    }
+
+    pub fn on_db_compaction(&self, reclaimed_bytes: u64, duration: Duration) {
+        self.db_compactions.inc();
+        self.db_compaction_reclaimed_bytes.inc_by(reclaimed_bytes);
+        self.db_compaction_time.observe(duration.as_secs_f64());
+    }
+
     pub fn set_active_history(&self, nb: usize) {
         self.active_history.set(nb as i64);
     }
@@ -650,6 +1013,39 @@ impl MassaMetrics {
         self.bootstrap_peers_failed.inc();
     }
 
+    pub fn set_bootstrap_server_sessions(&self, active: usize, queued: usize) {
+        self.bootstrap_server_sessions.set(active as i64);
+        self.bootstrap_server_queued_sessions.set(queued as i64);
+    }
+
+    pub fn set_bootstrap_phase(&self, phase: i64) {
+        self.bootstrap_phase.set(phase);
+    }
+
+    /// Set the addresses of the bootstrap servers used by our node, replacing the previous ones
+    pub fn set_bootstrap_servers(&self, addresses: &[String]) {
+        self.bootstrap_server.reset();
+        for address in addresses {
+            self.bootstrap_server
+                .with_label_values(&[address.as_str()])
+                .set(1);
+        }
+    }
+
+    pub fn inc_bootstrap_bytes_received(&self, bytes: u64) {
+        self.bootstrap_bytes_received.inc_by(bytes);
+    }
+
+    /// Record a final state and consensus part received from a bootstrap server, along with
+    /// the estimated share of the final state downloaded and the time left to download it
+    pub fn on_bootstrap_part(&self, state_progress: f64, eta: Option<Duration>) {
+        self.bootstrap_parts_received.inc();
+        self.bootstrap_state_progress.set(state_progress);
+        if let Some(eta) = eta {
+            self.bootstrap_eta.set(eta.as_secs() as i64);
+        }
+    }
+
     pub fn set_operations_pool(&self, nb: usize) {
         self.operations_pool.set(nb as i64);
     }
@@ -662,6 +1058,27 @@ impl MassaMetrics {
         self.denunciations_pool.set(nb as i64);
     }
 
+    /// Set the time elapsed since the slots of the oldest endorsement and denunciation
+    /// of the pools, in milliseconds (0 for an empty pool)
+    pub fn set_pool_oldest_ages(&self, endorsement_age: u64, denunciation_age: u64) {
+        self.endorsement_pool_oldest_age.set(endorsement_age as i64);
+        self.denunciation_pool_oldest_age
+            .set(denunciation_age as i64);
+    }
+
+    /// Set the mean share of the endorsement indexes covered at the slots of the endorsement pool
+    pub fn set_endorsement_pool_coverage(&self, coverage: f64) {
+        self.endorsement_pool_coverage.set(coverage);
+    }
+
+    /// Count operations evicted from the operation pool
+    /// `reason` is "pool_size", "address_cap" or "excess" (dropped in-between refreshes)
+    pub fn inc_operation_pool_evictions(&self, reason: &str, diff: u64) {
+        self.operation_pool_evictions
+            .with_label_values(&[reason])
+            .inc_by(diff);
+    }
+
     pub fn inc_protocol_tester_success(&self) {
         self.protocol_tester_success.inc();
     }
@@ -702,6 +1119,60 @@ impl MassaMetrics {
         self.block_slot_delay.observe(delay);
     }
 
+    /// Record a handled gRPC request
+    /// `code` is the gRPC status code of the response ("0" when successful)
+    pub fn record_grpc_request(
+        &self,
+        service: &str,
+        method: &str,
+        code: &str,
+        duration: Duration,
+        response_size: u64,
+    ) {
+        self.grpc_requests
+            .with_label_values(&[service, method, code])
+            .inc();
+        self.grpc_request_duration
+            .with_label_values(&[service, method])
+            .observe(duration.as_secs_f64());
+        self.grpc_response_size
+            .with_label_values(&[service, method])
+            .observe(response_size as f64);
+    }
+
+    /// Record a network message of `size` bytes received from a peer
+    pub fn record_network_message_received(&self, message_type: &str, size: u64) {
+        self.record_network_message(message_type, "received", size);
+    }
+
+    /// Record a network message of `size` bytes sent to a peer
+    pub fn record_network_message_sent(&self, message_type: &str, size: u64) {
+        self.record_network_message(message_type, "sent", size);
+    }
+
+    fn record_network_message(&self, message_type: &str, direction: &str, size: u64) {
+        self.network_messages
+            .with_label_values(&[message_type, direction])
+            .inc();
+        self.network_message_bytes
+            .with_label_values(&[message_type, direction])
+            .inc_by(size);
+    }
+
+    /// Record the time spent processing a received network message
+    pub fn record_network_message_processing(&self, message_type: &str, duration: Duration) {
+        self.network_message_processing_time
+            .with_label_values(&[message_type])
+            .observe(duration.as_secs_f64());
+    }
+
+    /// Set the disk usage in bytes of a subsystem of the node
+    pub fn set_disk_usage(&self, subsystem: &str, size: u64) {
+        self.disk_usage
+            .with_label_values(&[subsystem])
+            .set(size as i64);
+    }
+
     /// Update the bandwidth metrics for all peers
     /// HashMap<peer_id, (tx, rx)>
     pub fn update_peers_tx_rx(&self, data: HashMap<String, (u64, u64)>) {
@@ -1,7 +1,9 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use crate::amount::Amount;
 use crate::error::ModelsError;
 use crate::prehash::PreHashed;
+use crate::slot::Slot;
 use massa_hash::{Hash, HashDeserializer, HASH_SIZE_BYTES};
 use massa_serialization::{
     DeserializeError, Deserializer, SerializeError, Serializer, U64VarIntDeserializer,
@@ -660,6 +662,25 @@ pub struct ExecutionAddressCycleInfo {
     pub active_rolls: Option<u64>,
 }
 
+/// Asynchronous message of the final pool targeting a given address
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct IncomingAsyncMessage {
+    /// slot at which the message was emitted
+    pub emission_slot: Slot,
+    /// index of the message within its emission slot
+    pub emission_index: u64,
+    /// address that sent the message
+    pub sender: Address,
+    /// function of the address called by the message
+    pub function: String,
+    /// coins sent to the address with the message
+    pub coins: Amount,
+    /// slot at which the message starts being valid (included)
+    pub validity_start: Slot,
+    /// slot at which the message stops being valid (excluded)
+    pub validity_end: Slot,
+}
+
 #[cfg(test)]
 mod test {
     use crate::config::THREAD_COUNT;
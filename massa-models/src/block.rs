@@ -7,11 +7,14 @@ use crate::secure_share::{
     Id, SecureShare, SecureShareContent, SecureShareDeserializer, SecureShareSerializer,
 };
 use crate::{
+    address::Address,
     // endorsement::{Endorsement, EndorsementDeserializerLW, SecureShareEndorsement},
+    endorsement::SecureShareEndorsement,
     error::ModelsError,
     operation::{
         OperationId, OperationIdsDeserializer, OperationIdsSerializer, SecureShareOperation,
     },
+    slot::Slot,
     // slot::{Slot, SlotDeserializer, SlotSerializer},
 };
 // use massa_hash::{Hash, HashDeserializer};
@@ -40,9 +43,9 @@ use serde::{Deserialize, Serialize};
 // use std::convert::TryInto;
 use std::fmt::Formatter;
 // use std::ops::Bound::{Excluded, Included};
-// use std::str::FromStr;
 use crate::block_header::{BlockHeader, BlockHeaderDeserializer, SecuredHeader};
 use crate::block_id::BlockId;
+use std::str::FromStr;
 
 /// block
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -431,6 +434,103 @@ pub enum BlockGraphStatus {
     NotFound,
 }
 
+/// Format of a rendering of the block graph for visualization tools
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BlockGraphFormat {
+    /// Graphviz DOT language
+    Dot,
+    /// GraphML XML format
+    GraphMl,
+}
+
+impl FromStr for BlockGraphFormat {
+    type Err = ModelsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "dot" => Ok(BlockGraphFormat::Dot),
+            "graphml" => Ok(BlockGraphFormat::GraphMl),
+            _ => Err(ModelsError::DeserializeError(format!(
+                "unknown block graph format: {}",
+                s
+            ))),
+        }
+    }
+}
+
+/// Reason why a block was discarded from the graph
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DiscardedBlockReason {
+    /// invalid, either structurally or because of some incompatibility, with the details
+    Invalid(String),
+    /// incompatible with a final block
+    Stale,
+    /// final, and no longer needed in the graph
+    Final,
+}
+
+impl std::fmt::Display for DiscardedBlockReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DiscardedBlockReason::Invalid(reason) => write!(f, "invalid: {}", reason),
+            DiscardedBlockReason::Stale => write!(f, "stale"),
+            DiscardedBlockReason::Final => write!(f, "final"),
+        }
+    }
+}
+
+/// Discarded block retained by the graph
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscardedBlock {
+    /// block id
+    pub block_id: BlockId,
+    /// slot of the block
+    pub slot: Slot,
+    /// creator of the block
+    pub creator: Address,
+    /// parents of the block, one per thread
+    pub parents: Vec<BlockId>,
+    /// why the block was discarded
+    pub reason: DiscardedBlockReason,
+}
+
+impl std::fmt::Display for DiscardedBlock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Block {} at slot {} created by {}: {}",
+            self.block_id, self.slot, self.creator, self.reason
+        )?;
+        writeln!(
+            f,
+            "\tParents: {}",
+            self.parents
+                .iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<String>>()
+                .join(" ")
+        )?;
+        Ok(())
+    }
+}
+
+/// Data of the block graph proving that a block is final, for external verifiers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockFinalityProof {
+    /// header of the final block, signed by its creator
+    pub header: SecuredHeader,
+    /// endorsements of the block, included in the headers of its descendants
+    pub endorsements: Vec<SecureShareEndorsement>,
+    /// headers of the descendants of the block that are final or in the blockclique,
+    /// by increasing slot
+    pub descendants: Vec<SecuredHeader>,
+    /// sum of the fitness of the descendants
+    pub descendants_fitness: u64,
+    /// fitness that the descendants of a block must exceed for it to be final
+    pub fitness_threshold: u64,
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
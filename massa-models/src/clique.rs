@@ -18,6 +18,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::block_id::{BlockId, BlockIdDeserializer, BlockIdSerializer};
 use crate::prehash::PreHashSet;
+use crate::slot::Slot;
 use std::ops::Bound::{Excluded, Included};
 
 /// Mutually compatible blocks in the graph
@@ -32,6 +33,87 @@ pub struct Clique {
     pub is_blockclique: bool,
 }
 
+/// Reason why a clique is the blockclique or not
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub enum BlockcliqueChoice {
+    /// Blockclique, being the only clique of highest fitness
+    HighestFitness,
+    /// Blockclique, having the lowest sum of block ids among the cliques of highest fitness
+    WonTieBreak,
+    /// Not the blockclique, having a lower fitness
+    LowerFitness,
+    /// Not the blockclique, having the same fitness but a higher sum of block ids
+    LostTieBreak,
+}
+
+impl std::fmt::Display for BlockcliqueChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BlockcliqueChoice::HighestFitness => write!(f, "blockclique: highest fitness"),
+            BlockcliqueChoice::WonTieBreak => {
+                write!(
+                    f,
+                    "blockclique: highest fitness tie broken by lowest block ids sum"
+                )
+            }
+            BlockcliqueChoice::LowerFitness => write!(f, "lower fitness than the blockclique"),
+            BlockcliqueChoice::LostTieBreak => {
+                write!(
+                    f,
+                    "same fitness as the blockclique but higher block ids sum"
+                )
+            }
+        }
+    }
+}
+
+/// Block of a clique
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CliqueBlock {
+    /// id of the block
+    pub block_id: BlockId,
+    /// slot of the block
+    pub slot: Slot,
+    /// fitness of the block
+    pub fitness: u64,
+}
+
+/// Details of a clique, to diagnose the choice of the blockclique
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CliqueDetails {
+    /// the blocks of the clique, by increasing slot
+    pub blocks: Vec<CliqueBlock>,
+    /// sum of the fitness of the blocks of the clique
+    pub fitness: u64,
+    /// fitness of the blockclique minus the one of this clique
+    pub fitness_gap: u64,
+    /// True if it is the blockclique
+    pub is_blockclique: bool,
+    /// why the clique is the blockclique or not
+    pub choice: BlockcliqueChoice,
+}
+
+impl std::fmt::Display for CliqueDetails {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Clique of {} blocks, fitness {} (gap {}): {}",
+            self.blocks.len(),
+            self.fitness,
+            self.fitness_gap,
+            self.choice
+        )?;
+        for block in self.blocks.iter() {
+            writeln!(
+                f,
+                "\tBlock {} at slot {}, fitness {}",
+                block.block_id, block.slot, block.fitness
+            )?;
+        }
+        Ok(())
+    }
+}
+
 impl Default for Clique {
     fn default() -> Self {
         Clique {
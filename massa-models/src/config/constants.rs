@@ -126,6 +126,12 @@ pub const LEDGER_COST_PER_BYTE: Amount = Amount::const_init(1, 4);
 pub const LEDGER_ENTRY_BASE_COST: Amount = Amount::const_init(1, 3);
 /// Base size of a empty datastore entry (not counting the key nor the value)
 pub const LEDGER_ENTRY_DATASTORE_BASE_SIZE: usize = 4;
+/// Balance below which a ledger entry without bytecode nor datastore is pruned as a dust account
+pub const DUST_ACCOUNT_MAX_BALANCE: Amount = Amount::const_init(1, 3);
+/// Number of ledger entries examined for dust account pruning at each final slot
+pub const DUST_PRUNING_SCAN_LENGTH: usize = 10;
+/// Version of the execution MIP component from which the dust accounts are pruned
+pub const DUST_PRUNING_EXECUTION_VERSION: u32 = 1;
 /// Time between the periods in the same thread.
 pub const T0: MassaTime = MassaTime::from_millis(16000);
 /// Proof of stake seed for the initial draw
@@ -142,8 +148,13 @@ pub const MAX_OPERATIONS_PER_BLOCK: u32 = 5000;
 pub const MAX_BLOCK_SIZE: u32 = 300_000;
 /// Maximum capacity of the asynchronous messages pool
 pub const MAX_ASYNC_POOL_LENGTH: u64 = 1_000;
+/// Maximum number of asynchronous messages returned for the address targeted by them
+pub const MAX_INCOMING_ASYNC_MESSAGES_PER_ADDRESS: usize = 100;
 /// Maximum operation validity period count
 pub const OPERATION_VALIDITY_PERIODS: u64 = 10;
+/// Version of the execution MIP component from which the `CancelAsyncMessage` operations are valid.
+/// It comes after `DUST_PRUNING_EXECUTION_VERSION` so that both features are activated by their own MIP.
+pub const CANCEL_ASYNC_MESSAGE_EXECUTION_VERSION: u32 = 2;
 /// Number of periods of executed operation and denunciation history to keep
 pub const KEEP_EXECUTED_HISTORY_EXTRA_PERIODS: u64 = 10;
 /// cycle duration in periods
@@ -23,6 +23,7 @@
 //! The last step is to merge the environment variable prefixed with
 //! `MASSA_CLIENT`, override if duplicated
 //!
+use crate::error::ModelsError;
 use directories::ProjectDirs;
 use serde::Deserialize;
 use std::path::Path;
@@ -33,6 +34,16 @@ use std::path::Path;
 /// 3. in path specified in `MASSA_CONFIG_OVERRIDE_PATH` environment variable (`config/config.toml` by default)
 #[inline]
 pub fn build_massa_settings<T: Deserialize<'static>>(app_name: &str, env_prefix: &str) -> T {
+    try_build_massa_settings(app_name, env_prefix).unwrap()
+}
+
+/// Same as `build_massa_settings` but returns an error instead of panicking
+/// if the configuration sources cannot be read or deserialized.
+/// Useful to reload the configuration of a running node.
+pub fn try_build_massa_settings<T: Deserialize<'static>>(
+    app_name: &str,
+    env_prefix: &str,
+) -> Result<T, ModelsError> {
     let mut builder = config::Config::builder();
     let config_path = std::env::var("MASSA_CONFIG_PATH")
         .unwrap_or_else(|_| "base_config/config.toml".to_string());
@@ -58,7 +69,8 @@ pub fn build_massa_settings<T: Deserialize<'static>>(app_name: &str, env_prefix:
     let s = builder
         .add_source(config::Environment::with_prefix(env_prefix))
         .build()
-        .unwrap();
+        .map_err(|err| ModelsError::DeserializeError(err.to_string()))?;
 
-    s.try_deserialize().unwrap()
+    s.try_deserialize()
+        .map_err(|err| ModelsError::DeserializeError(err.to_string()))
 }
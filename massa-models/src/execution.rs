@@ -1,7 +1,11 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
-use crate::{address::Address, operation::OperationId, slot::Slot};
+use crate::{
+    address::Address, amount::Amount, operation::OperationId, output_event::SCOutputEvent,
+    slot::Slot,
+};
 use serde::{Deserialize, Serialize};
+use std::fmt::Display;
 
 /// filter used when retrieving SC output events
 #[derive(Default, Debug, Deserialize, Clone, Serialize)]
@@ -29,3 +33,158 @@ pub struct EventFilter {
     /// None means both
     pub is_error: Option<bool>,
 }
+
+impl EventFilter {
+    /// Returns whether an event matches the filter
+    pub fn matches(&self, event: &SCOutputEvent) -> bool {
+        if let Some(start) = self.start {
+            if event.context.slot < start {
+                return false;
+            }
+        }
+        if let Some(end) = self.end {
+            if event.context.slot >= end {
+                return false;
+            }
+        }
+        if let Some(is_final) = self.is_final {
+            if event.context.is_final != is_final {
+                return false;
+            }
+        }
+        if let Some(is_error) = self.is_error {
+            if event.context.is_error != is_error {
+                return false;
+            }
+        }
+        match (
+            self.original_caller_address,
+            event.context.call_stack.front(),
+        ) {
+            (Some(addr1), Some(addr2)) if addr1 != *addr2 => return false,
+            (Some(_), None) => return false,
+            _ => (),
+        }
+        match (self.emitter_address, event.context.call_stack.back()) {
+            (Some(addr1), Some(addr2)) if addr1 != *addr2 => return false,
+            (Some(_), None) => return false,
+            _ => (),
+        }
+        match (
+            self.original_operation_id,
+            event.context.origin_operation_id,
+        ) {
+            (Some(addr1), Some(addr2)) if addr1 != addr2 => return false,
+            (Some(_), None) => return false,
+            _ => (),
+        }
+        true
+    }
+}
+
+/// filter used when retrieving the asynchronous messages of the pool
+#[derive(Default, Debug, Deserialize, Clone, Serialize)]
+pub struct AsyncMessageFilter {
+    /// optional sender address
+    pub sender: Option<Address>,
+    /// optional destination address
+    pub destination: Option<Address>,
+    /// optional execution status
+    ///
+    /// Some(true) means messages that can be executed
+    /// Some(false) means messages waiting for their trigger
+    /// None means both
+    pub can_be_executed: Option<bool>,
+}
+
+/// Asynchronous message waiting in the pool to be executed
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PendingAsyncMessage {
+    /// slot at which the message was emitted
+    pub emission_slot: Slot,
+    /// index of the message within its emission slot
+    pub emission_index: u64,
+    /// address that sent the message
+    pub sender: Address,
+    /// address targeted by the message
+    pub destination: Address,
+    /// function of the destination called by the message
+    pub function: String,
+    /// max gas used to execute the message
+    pub max_gas: u64,
+    /// fee paid by the sender when the message is executed
+    pub fee: Amount,
+    /// coins sent to the destination with the message
+    pub coins: Amount,
+    /// slot at which the message starts being valid (included)
+    pub validity_start: Slot,
+    /// slot at which the message stops being valid (excluded)
+    pub validity_end: Slot,
+    /// whether the message can be executed, false while waiting for its trigger
+    pub can_be_executed: bool,
+    /// fee per unit of max gas in nanoMAS: the messages with the highest priority
+    /// are executed first
+    pub priority: f64,
+}
+
+impl Display for PendingAsyncMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Message emitted at slot {} (index {}) by {}",
+            self.emission_slot, self.emission_index, self.sender
+        )?;
+        writeln!(f, "\tCalling {} of {}", self.function, self.destination)?;
+        writeln!(
+            f,
+            "\tCoins: {}, fee: {}, max gas: {}, priority: {}",
+            self.coins, self.fee, self.max_gas, self.priority
+        )?;
+        writeln!(
+            f,
+            "\tValid from slot {} to slot {}{}",
+            self.validity_start,
+            self.validity_end,
+            if self.can_be_executed {
+                ""
+            } else {
+                ", waiting for its trigger"
+            }
+        )
+    }
+}
+
+/// Occupancy of the asynchronous pool, and minimum fee for a new message to be retained in it
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AsyncMessageFeeSuggestion {
+    /// number of messages in the pool, including the ones emitted in the active slots
+    pub pool_length: u64,
+    /// max number of messages in the pool, the ones with the lowest priority being eliminated
+    pub max_pool_length: u64,
+    /// lowest priority (fee per unit of max gas in nanoMAS) of the messages of the pool when it
+    /// is full, that a new message must exceed to be retained. None if the pool is not full
+    pub eviction_threshold: Option<f64>,
+    /// max gas of the new message
+    pub max_gas: u64,
+    /// minimum fee for a new message using `max_gas` to be retained in the pool
+    pub min_fee: Amount,
+}
+
+impl Display for AsyncMessageFeeSuggestion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Async pool: {}/{} messages",
+            self.pool_length, self.max_pool_length
+        )?;
+        match self.eviction_threshold {
+            Some(threshold) => writeln!(f, "Eviction threshold: {} nanoMAS per gas", threshold)?,
+            None => writeln!(f, "Eviction threshold: none, the pool is not full")?,
+        }
+        writeln!(
+            f,
+            "Min fee for a message with a max gas of {}: {}",
+            self.max_gas, self.min_fee
+        )
+    }
+}
@@ -1,5 +1,6 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
 
+use crate::address::IncomingAsyncMessage;
 use crate::amount::Amount;
 use crate::block::{Block, BlockGraphStatus, FilledBlock, SecureShareBlock};
 use crate::block_header::{BlockHeader, SecuredHeader};
@@ -226,6 +227,18 @@ impl From<OperationType> for grpc_model::OperationType {
                 grpc_operation_type.r#type =
                     Some(grpc_model::operation_type::Type::CallSc(call_sc));
             }
+            OperationType::CancelAsyncMessage {
+                emission_slot,
+                emission_index,
+            } => {
+                let cancel_async_message = grpc_model::CancelAsyncMessage {
+                    emission_slot: Some(emission_slot.into()),
+                    emission_index,
+                };
+                grpc_operation_type.r#type = Some(
+                    grpc_model::operation_type::Type::CancelAsyncMessage(cancel_async_message),
+                );
+            }
         }
 
         grpc_operation_type
@@ -250,6 +263,7 @@ impl From<OperationType> for grpc_model::OpType {
             OperationType::RollSell { .. } => grpc_model::OpType::RollSell,
             OperationType::ExecuteSC { .. } => grpc_model::OpType::ExecuteSc,
             OperationType::CallSC { .. } => grpc_model::OpType::CallSc,
+            OperationType::CancelAsyncMessage { .. } => grpc_model::OpType::CancelAsyncMessage,
         }
     }
 }
@@ -327,6 +341,20 @@ impl From<EventExecutionContext> for grpc_model::ScExecutionEventContext {
     }
 }
 
+impl From<IncomingAsyncMessage> for grpc_model::IncomingAsyncMessage {
+    fn from(value: IncomingAsyncMessage) -> Self {
+        grpc_model::IncomingAsyncMessage {
+            emission_slot: Some(value.emission_slot.into()),
+            emission_index: value.emission_index,
+            sender: value.sender.to_string(),
+            function: value.function,
+            coins: Some(value.coins.into()),
+            validity_start: Some(value.validity_start.into()),
+            validity_end: Some(value.validity_end.into()),
+        }
+    }
+}
+
 impl From<DenunciationIndex> for grpc_model::DenunciationIndex {
     fn from(value: DenunciationIndex) -> Self {
         grpc_model::DenunciationIndex {
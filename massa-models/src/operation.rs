@@ -9,8 +9,10 @@ use crate::secure_share::{
 use crate::{
     address::{Address, AddressDeserializer},
     amount::{Amount, AmountDeserializer, AmountSerializer},
+    config::CANCEL_ASYNC_MESSAGE_EXECUTION_VERSION,
     error::ModelsError,
     serialization::{StringDeserializer, StringSerializer, VecU8Deserializer, VecU8Serializer},
+    slot::{Slot, SlotDeserializer, SlotSerializer},
 };
 use massa_hash::{Hash, HashDeserializer};
 use massa_serialization::{
@@ -19,6 +21,7 @@ use massa_serialization::{
     U64VarIntSerializer,
 };
 use massa_signature::PublicKey;
+use massa_time::MassaTime;
 use nom::error::{context, ErrorKind};
 use nom::multi::length_count;
 use nom::sequence::tuple;
@@ -33,7 +36,11 @@ use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DeserializeFromStr, SerializeDisplay};
 use std::convert::TryInto;
 use std::fmt::Formatter;
-use std::{ops::Bound::Included, ops::RangeInclusive, str::FromStr};
+use std::{
+    ops::Bound::{Excluded, Included},
+    ops::RangeInclusive,
+    str::FromStr,
+};
 use transition::Versioned;
 
 /// Size in bytes of the serialized operation ID prefix
@@ -386,6 +393,7 @@ enum OperationTypeId {
     RollSell = 2,
     ExecuteSC = 3,
     CallSC = 4,
+    CancelAsyncMessage = 5,
 }
 
 /// the operation as sent in the network
@@ -495,6 +503,7 @@ pub struct OperationDeserializer {
 impl OperationDeserializer {
     /// Creates a `OperationDeserializer`
     pub fn new(
+        thread_count: u8,
         max_datastore_value_length: u64,
         max_function_name_length: u16,
         max_parameters_size: u32,
@@ -509,6 +518,7 @@ impl OperationDeserializer {
                 Included(Amount::MAX),
             ),
             op_type_deserializer: OperationTypeDeserializer::new(
+                thread_count,
                 max_datastore_value_length,
                 max_function_name_length,
                 max_parameters_size,
@@ -540,7 +550,7 @@ impl Deserializer<Operation> for OperationDeserializer {
     /// };
     /// let mut buffer = Vec::new();
     /// OperationSerializer::new().serialize(&operation, &mut buffer).unwrap();
-    /// let (rest, deserialized_operation) = OperationDeserializer::new(10000, 10000, 10000, 100, 255, 10_000).deserialize::<DeserializeError>(&buffer).unwrap();
+    /// let (rest, deserialized_operation) = OperationDeserializer::new(32, 10000, 10000, 10000, 100, 255, 10_000).deserialize::<DeserializeError>(&buffer).unwrap();
     /// assert_eq!(rest.len(), 0);
     /// assert_eq!(deserialized_operation.fee, operation.fee);
     /// assert_eq!(deserialized_operation.expire_period, operation.expire_period);
@@ -629,6 +639,58 @@ pub enum OperationType {
         /// Extra coins that are spent from the caller's balance and transferred to the target
         coins: Amount,
     },
+    /// Cancels an asynchronous message sent by the sender that was not executed yet,
+    /// the coins of the message being reimbursed to the sender
+    CancelAsyncMessage {
+        /// Slot at which the message was emitted
+        emission_slot: Slot,
+        /// Index of the message within its emission slot
+        emission_index: u64,
+    },
+}
+
+/// kind of operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum OperationKind {
+    /// transfer coins from sender to recipient
+    Transaction,
+    /// buy rolls
+    RollBuy,
+    /// sell rolls
+    RollSell,
+    /// execute a smart contract
+    ExecuteSC,
+    /// call a function of a stored smart contract
+    CallSC,
+    /// cancel an asynchronous message
+    CancelAsyncMessage,
+}
+
+impl From<&OperationType> for OperationKind {
+    fn from(op: &OperationType) -> Self {
+        match op {
+            OperationType::Transaction { .. } => OperationKind::Transaction,
+            OperationType::RollBuy { .. } => OperationKind::RollBuy,
+            OperationType::RollSell { .. } => OperationKind::RollSell,
+            OperationType::ExecuteSC { .. } => OperationKind::ExecuteSC,
+            OperationType::CallSC { .. } => OperationKind::CallSC,
+            OperationType::CancelAsyncMessage { .. } => OperationKind::CancelAsyncMessage,
+        }
+    }
+}
+
+impl OperationType {
+    /// Version of the execution MIP component from which this type of operation is valid
+    pub fn required_execution_version(&self) -> u32 {
+        match self {
+            OperationType::Transaction { .. }
+            | OperationType::RollBuy { .. }
+            | OperationType::RollSell { .. }
+            | OperationType::ExecuteSC { .. }
+            | OperationType::CallSC { .. } => 0,
+            OperationType::CancelAsyncMessage { .. } => CANCEL_ASYNC_MESSAGE_EXECUTION_VERSION,
+        }
+    }
 }
 
 impl std::fmt::Display for OperationType {
@@ -674,6 +736,14 @@ impl std::fmt::Display for OperationType {
                 writeln!(f, "\t- max_gas:{}", max_gas)?;
                 writeln!(f, "\t- coins:{}", coins)?;
             }
+            OperationType::CancelAsyncMessage {
+                emission_slot,
+                emission_index,
+            } => {
+                writeln!(f, "CancelAsyncMessage:")?;
+                writeln!(f, "\t- emission slot:{}", emission_slot)?;
+                writeln!(f, "\t- emission index:{}", emission_index)?;
+            }
         }
         Ok(())
     }
@@ -688,6 +758,7 @@ pub struct OperationTypeSerializer {
     address_serializer: AddressSerializer,
     function_name_serializer: StringSerializer<U16VarIntSerializer, u16>,
     datastore_serializer: DatastoreSerializer,
+    slot_serializer: SlotSerializer,
 }
 
 impl OperationTypeSerializer {
@@ -701,6 +772,7 @@ impl OperationTypeSerializer {
             address_serializer: AddressSerializer::new(),
             function_name_serializer: StringSerializer::new(U16VarIntSerializer::new()),
             datastore_serializer: DatastoreSerializer::new(),
+            slot_serializer: SlotSerializer::new(),
         }
     }
 }
@@ -781,6 +853,15 @@ impl Serializer<OperationType> for OperationTypeSerializer {
                     .serialize(target_func, buffer)?;
                 self.vec_u8_serializer.serialize(param, buffer)?;
             }
+            OperationType::CancelAsyncMessage {
+                emission_slot,
+                emission_index,
+            } => {
+                self.u32_serializer
+                    .serialize(&u32::from(OperationTypeId::CancelAsyncMessage), buffer)?;
+                self.slot_serializer.serialize(emission_slot, buffer)?;
+                self.u64_serializer.serialize(emission_index, buffer)?;
+            }
         }
         Ok(())
     }
@@ -797,11 +878,14 @@ pub struct OperationTypeDeserializer {
     function_name_deserializer: StringDeserializer<U16VarIntDeserializer, u16>,
     parameter_deserializer: VecU8Deserializer,
     datastore_deserializer: DatastoreDeserializer,
+    slot_deserializer: SlotDeserializer,
+    emission_index_deserializer: U64VarIntDeserializer,
 }
 
 impl OperationTypeDeserializer {
     /// Creates a new `OperationTypeDeserializer`
     pub fn new(
+        thread_count: u8,
         max_datastore_value_length: u64,
         max_function_name_length: u16,
         max_parameters_size: u32,
@@ -835,6 +919,14 @@ impl OperationTypeDeserializer {
                 max_op_datastore_key_length,
                 max_op_datastore_value_length,
             ),
+            slot_deserializer: SlotDeserializer::new(
+                (Included(0), Included(u64::MAX)),
+                (Included(0), Excluded(thread_count)),
+            ),
+            emission_index_deserializer: U64VarIntDeserializer::new(
+                Included(0),
+                Included(u64::MAX),
+            ),
         }
     }
 }
@@ -857,7 +949,7 @@ impl Deserializer<OperationType> for OperationTypeDeserializer {
     /// };
     /// let mut buffer = Vec::new();
     /// OperationTypeSerializer::new().serialize(&op, &mut buffer).unwrap();
-    /// let (rest, op_deserialized) = OperationTypeDeserializer::new(10000, 10000, 10000, 10, 255, 10_000).deserialize::<DeserializeError>(&buffer).unwrap();
+    /// let (rest, op_deserialized) = OperationTypeDeserializer::new(32, 10000, 10000, 10000, 10, 255, 10_000).deserialize::<DeserializeError>(&buffer).unwrap();
     /// assert_eq!(rest.len(), 0);
     /// match op_deserialized {
     ///    OperationType::ExecuteSC {
@@ -969,12 +1061,82 @@ impl Deserializer<OperationType> for OperationTypeDeserializer {
                     },
                 )
                 .parse(input),
+                OperationTypeId::CancelAsyncMessage => context(
+                    "Failed CancelAsyncMessage deserialization",
+                    tuple((
+                        context("Failed emission_slot deserialization", |input| {
+                            self.slot_deserializer.deserialize(input)
+                        }),
+                        context("Failed emission_index deserialization", |input| {
+                            self.emission_index_deserializer.deserialize(input)
+                        }),
+                    )),
+                )
+                .map(
+                    |(emission_slot, emission_index)| OperationType::CancelAsyncMessage {
+                        emission_slot,
+                        emission_index,
+                    },
+                )
+                .parse(input),
             }
         })
         .parse(buffer)
     }
 }
 
+/// Role of an address in an operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationAddressRole {
+    /// the address created the operation
+    Emitter,
+    /// the address receives the coins of a transaction, or is the target of a smart contract call
+    Recipient,
+}
+
+/// Order of the operations of the pool returned by the API
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PoolOperationSort {
+    /// by decreasing fee
+    Fee,
+    /// by decreasing time spent in the pool
+    Age,
+}
+
+/// Lightweight summary of an operation of the pool
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PoolOperationSummary {
+    /// operation id
+    pub id: OperationId,
+    /// address of the sender
+    pub sender: Address,
+    /// fee of the operation
+    pub fee: Amount,
+    /// kind of the operation
+    pub kind: OperationKind,
+    /// period after which the operation cannot be included in a block
+    pub expire_period: u64,
+    /// time at which the operation entered the pool
+    pub added_at: MassaTime,
+}
+
+impl std::fmt::Display for PoolOperationSummary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Operation {}: {:?} from {}, fee {}, expire period {}, in the pool since {}",
+            self.id,
+            self.kind,
+            self.sender,
+            self.fee,
+            self.expire_period,
+            self.added_at.format_instant()
+        )
+    }
+}
+
 impl SecureShareOperation {
     /// get the range of periods during which an operation is valid
     /// Range: `(op.expire_period - cfg.operation_validity_period) -> op.expire_period` (included)
@@ -997,6 +1159,7 @@ impl SecureShareOperation {
             OperationType::RollBuy { .. } => 0,
             OperationType::RollSell { .. } => 0,
             OperationType::Transaction { .. } => 0,
+            OperationType::CancelAsyncMessage { .. } => 0,
         }
         .saturating_add(base_operation_gas_cost)
     }
@@ -1018,10 +1181,25 @@ impl SecureShareOperation {
             OperationType::CallSC { target_addr, .. } => {
                 res.insert(*target_addr);
             }
+            OperationType::CancelAsyncMessage { .. } => {}
         }
         res
     }
 
+    /// get the address receiving the coins of a transaction or called by a smart contract call
+    pub fn get_recipient_address(&self) -> Option<Address> {
+        match &self.content.op {
+            OperationType::Transaction {
+                recipient_address, ..
+            } => Some(*recipient_address),
+            OperationType::CallSC { target_addr, .. } => Some(*target_addr),
+            OperationType::RollBuy { .. }
+            | OperationType::RollSell { .. }
+            | OperationType::ExecuteSC { .. }
+            | OperationType::CancelAsyncMessage { .. } => None,
+        }
+    }
+
     /// Gets the maximal amount of coins that may be spent by this operation (incl. fee)
     pub fn get_max_spending(&self, roll_price: Amount) -> Amount {
         // compute the max amount of coins spent outside of the fees
@@ -1031,6 +1209,7 @@ impl SecureShareOperation {
             OperationType::RollSell { .. } => Amount::zero(),
             OperationType::ExecuteSC { max_coins, .. } => *max_coins,
             OperationType::CallSC { coins, .. } => *coins,
+            OperationType::CancelAsyncMessage { .. } => Amount::zero(),
         };
 
         // add all fees and return
@@ -1050,6 +1229,7 @@ impl SecureShareOperation {
             }
             OperationType::ExecuteSC { .. } => {}
             OperationType::CallSC { .. } => {}
+            OperationType::CancelAsyncMessage { .. } => {}
         }
         Ok(res)
     }
@@ -1386,6 +1566,7 @@ impl OperationsDeserializer {
     #[allow(clippy::too_many_arguments)]
     /// Creates a new `OperationsDeserializer`
     pub fn new(
+        thread_count: u8,
         max_operations_per_message: u32,
         max_datastore_value_length: u64,
         max_function_name_length: u16,
@@ -1402,6 +1583,7 @@ impl OperationsDeserializer {
             ),
             signed_op_deserializer: SecureShareDeserializer::new(
                 OperationDeserializer::new(
+                    thread_count,
                     max_datastore_value_length,
                     max_function_name_length,
                     max_parameters_size,
@@ -1438,7 +1620,7 @@ impl Deserializer<Vec<SecureShareOperation>> for OperationsDeserializer {
     /// let operations = vec![op_secured.clone(), op_secured.clone()];
     /// let mut buffer = Vec::new();
     /// OperationsSerializer::new().serialize(&operations, &mut buffer).unwrap();
-    /// let (rest, deserialized_operations) = OperationsDeserializer::new(10000, 10000, 10000, 10000, 10, 255, 10_000, *CHAINID).deserialize::<DeserializeError>(&buffer).unwrap();
+    /// let (rest, deserialized_operations) = OperationsDeserializer::new(32, 10000, 10000, 10000, 10000, 10, 255, 10_000, *CHAINID).deserialize::<DeserializeError>(&buffer).unwrap();
     /// for (operation1, operation2) in deserialized_operations.iter().zip(operations.iter()) {
     ///     assert_eq!(operation1.id, operation2.id);
     ///     assert_eq!(operation1.signature, operation2.signature);
@@ -1493,7 +1675,7 @@ mod tests {
     use crate::config::{
         CHAINID, MAX_DATASTORE_VALUE_LENGTH, MAX_FUNCTION_NAME_LENGTH,
         MAX_OPERATION_DATASTORE_ENTRY_COUNT, MAX_OPERATION_DATASTORE_KEY_LENGTH,
-        MAX_OPERATION_DATASTORE_VALUE_LENGTH, MAX_PARAMETERS_SIZE,
+        MAX_OPERATION_DATASTORE_VALUE_LENGTH, MAX_PARAMETERS_SIZE, THREAD_COUNT,
     };
 
     use super::*;
@@ -1570,6 +1752,7 @@ mod tests {
             .serialize(&op, &mut ser_type)
             .unwrap();
         let (_, res_type) = OperationTypeDeserializer::new(
+            THREAD_COUNT,
             MAX_DATASTORE_VALUE_LENGTH,
             MAX_FUNCTION_NAME_LENGTH,
             MAX_PARAMETERS_SIZE,
@@ -1593,6 +1776,7 @@ mod tests {
             .serialize(&content, &mut ser_content)
             .unwrap();
         let (_, res_content) = OperationDeserializer::new(
+            THREAD_COUNT,
             MAX_DATASTORE_VALUE_LENGTH,
             MAX_FUNCTION_NAME_LENGTH,
             MAX_PARAMETERS_SIZE,
@@ -1615,6 +1799,7 @@ mod tests {
             .unwrap();
         let (_, res_op): (&[u8], SecureShareOperation) = SecureShareDeserializer::new(
             OperationDeserializer::new(
+                THREAD_COUNT,
                 MAX_DATASTORE_VALUE_LENGTH,
                 MAX_FUNCTION_NAME_LENGTH,
                 MAX_PARAMETERS_SIZE,
@@ -1650,6 +1835,7 @@ mod tests {
             .serialize(&op, &mut ser_type)
             .unwrap();
         let (_, res_type) = OperationTypeDeserializer::new(
+            THREAD_COUNT,
             MAX_DATASTORE_VALUE_LENGTH,
             MAX_FUNCTION_NAME_LENGTH,
             MAX_PARAMETERS_SIZE,
@@ -1672,6 +1858,7 @@ mod tests {
             .serialize(&content, &mut ser_content)
             .unwrap();
         let (_, res_content) = OperationDeserializer::new(
+            THREAD_COUNT,
             MAX_DATASTORE_VALUE_LENGTH,
             MAX_FUNCTION_NAME_LENGTH,
             MAX_PARAMETERS_SIZE,
@@ -1693,6 +1880,7 @@ mod tests {
             .unwrap();
         let (_, res_op): (&[u8], SecureShareOperation) = SecureShareDeserializer::new(
             OperationDeserializer::new(
+                THREAD_COUNT,
                 MAX_DATASTORE_VALUE_LENGTH,
                 MAX_FUNCTION_NAME_LENGTH,
                 MAX_PARAMETERS_SIZE,
@@ -1729,6 +1917,7 @@ mod tests {
             .serialize(&op, &mut ser_type)
             .unwrap();
         let (_, res_type) = OperationTypeDeserializer::new(
+            THREAD_COUNT,
             MAX_DATASTORE_VALUE_LENGTH,
             MAX_FUNCTION_NAME_LENGTH,
             MAX_PARAMETERS_SIZE,
@@ -1751,6 +1940,7 @@ mod tests {
             .serialize(&content, &mut ser_content)
             .unwrap();
         let (_, res_content) = OperationDeserializer::new(
+            THREAD_COUNT,
             MAX_DATASTORE_VALUE_LENGTH,
             MAX_FUNCTION_NAME_LENGTH,
             MAX_PARAMETERS_SIZE,
@@ -1772,6 +1962,7 @@ mod tests {
             .unwrap();
         let (_, res_op): (&[u8], SecureShareOperation) = SecureShareDeserializer::new(
             OperationDeserializer::new(
+                THREAD_COUNT,
                 MAX_DATASTORE_VALUE_LENGTH,
                 MAX_FUNCTION_NAME_LENGTH,
                 MAX_PARAMETERS_SIZE,
@@ -1802,6 +1993,7 @@ mod tests {
             .serialize(&op, &mut ser_type)
             .unwrap();
         let (_, res_type) = OperationTypeDeserializer::new(
+            THREAD_COUNT,
             MAX_DATASTORE_VALUE_LENGTH,
             MAX_FUNCTION_NAME_LENGTH,
             MAX_PARAMETERS_SIZE,
@@ -1844,6 +2036,7 @@ mod tests {
             .serialize(&op, &mut ser_type)
             .unwrap();
         let (_, res_type) = OperationTypeDeserializer::new(
+            THREAD_COUNT,
             MAX_DATASTORE_VALUE_LENGTH,
             MAX_FUNCTION_NAME_LENGTH,
             MAX_PARAMETERS_SIZE,
@@ -1886,6 +2079,44 @@ mod tests {
             .serialize(&op, &mut ser_type)
             .unwrap();
         let (_, res_type) = OperationTypeDeserializer::new(
+            THREAD_COUNT,
+            MAX_DATASTORE_VALUE_LENGTH,
+            MAX_FUNCTION_NAME_LENGTH,
+            MAX_PARAMETERS_SIZE,
+            MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+            MAX_OPERATION_DATASTORE_KEY_LENGTH,
+            MAX_OPERATION_DATASTORE_VALUE_LENGTH,
+        )
+        .deserialize::<DeserializeError>(&ser_type)
+        .unwrap();
+        assert_eq!(res_type, op);
+
+        let orig_operation = Operation {
+            fee: Amount::from_str("20").unwrap(),
+            op,
+            expire_period: 50,
+        };
+
+        let serialized_operation = serde_json::to_string(&orig_operation).unwrap();
+        let res_operation: Value = serde_json::from_str(&serialized_operation).unwrap();
+        // check equality
+        assert_eq!(orig_operation.fee.to_string(), res_operation["fee"]);
+        assert_eq!(orig_operation.expire_period, res_operation["expire_period"]);
+    }
+
+    #[test]
+    #[serial]
+    fn test_cancel_async_message_serde() {
+        let op = OperationType::CancelAsyncMessage {
+            emission_slot: Slot::new(12, 3),
+            emission_index: 4,
+        };
+        let mut ser_type = Vec::new();
+        OperationTypeSerializer::new()
+            .serialize(&op, &mut ser_type)
+            .unwrap();
+        let (_, res_type) = OperationTypeDeserializer::new(
+            THREAD_COUNT,
             MAX_DATASTORE_VALUE_LENGTH,
             MAX_FUNCTION_NAME_LENGTH,
             MAX_PARAMETERS_SIZE,
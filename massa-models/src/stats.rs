@@ -1,9 +1,10 @@
 //! Copyright (c) 2022 MASSA LABS <info@massa.net>
 
-use crate::slot::Slot;
+use crate::{address::Address, block_id::BlockId, slot::Slot};
 use massa_time::MassaTime;
 use serde::{Deserialize, Serialize};
 use std::fmt::Formatter;
+use std::net::SocketAddr;
 
 /// execution statistics
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -51,6 +52,27 @@ impl std::fmt::Display for ExecutionStats {
     }
 }
 
+/// whether the other peers can connect to the node
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Reachability {
+    /// the node accepted connections from other peers recently
+    Reachable,
+    /// the node did not accept any connection from the other peers for a while
+    Unreachable,
+    /// the node was started too recently to know
+    Unknown,
+}
+
+impl std::fmt::Display for Reachability {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Reachability::Reachable => write!(f, "reachable"),
+            Reachability::Unreachable => write!(f, "unreachable"),
+            Reachability::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
 /// stats produced by network module
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NetworkStats {
@@ -64,6 +86,10 @@ pub struct NetworkStats {
     pub banned_peer_count: u64,
     /// active node count
     pub active_node_count: u64,
+    /// whether the other peers can connect to the node
+    pub reachability: Reachability,
+    /// external address of the node, obtained through an automatic port mapping
+    pub mapped_address: Option<SocketAddr>,
 }
 
 impl std::fmt::Display for NetworkStats {
@@ -74,6 +100,10 @@ impl std::fmt::Display for NetworkStats {
         writeln!(f, "\tKnown peers: {}", self.known_peer_count)?;
         writeln!(f, "\tBanned peers: {}", self.banned_peer_count)?;
         writeln!(f, "\tActive nodes: {}", self.active_node_count)?;
+        writeln!(f, "\tReachability: {}", self.reachability)?;
+        if let Some(mapped_address) = self.mapped_address {
+            writeln!(f, "\tMapped address: {}", mapped_address)?;
+        }
         Ok(())
     }
 }
@@ -113,13 +143,93 @@ impl std::fmt::Display for ConsensusStats {
     }
 }
 
+/// block that became stale: discarded for being incompatible with a final block
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StaleBlock {
+    /// block id
+    pub block_id: BlockId,
+    /// slot of the block
+    pub slot: Slot,
+    /// creator of the block
+    pub creator: Address,
+}
+
+/// number of stale blocks of a block creator
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StaleBlockCreator {
+    /// creator address
+    pub address: Address,
+    /// number of stale blocks created
+    pub stale_block_count: u64,
+}
+
+/// stale blocks of a cycle, to detect timing or propagation problems of the block creators
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CycleStaleBlockStats {
+    /// cycle of the slots of the blocks
+    pub cycle: u64,
+    /// number of stale blocks
+    pub stale_block_count: u64,
+    /// creators of the stale blocks, by decreasing number of stale blocks
+    pub creators: Vec<StaleBlockCreator>,
+    /// stale blocks, by increasing slot
+    pub blocks: Vec<StaleBlock>,
+}
+
+impl std::fmt::Display for CycleStaleBlockStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Cycle {}: {} stale blocks",
+            self.cycle, self.stale_block_count
+        )?;
+        for creator in self.creators.iter() {
+            writeln!(
+                f,
+                "\tCreator {}: {} stale blocks",
+                creator.address, creator.stale_block_count
+            )?;
+        }
+        for block in self.blocks.iter() {
+            writeln!(
+                f,
+                "\tBlock {} at slot {} created by {}",
+                block.block_id, block.slot, block.creator
+            )?;
+        }
+        Ok(())
+    }
+}
+
 /// stats produced by pool module
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PoolStats {
     /// number of operations in the pool
     pub operation_count: u64,
     /// number of endorsement in the pool
     pub endorsement_count: u64,
+    /// number of denunciations in the pool
+    pub denunciation_count: u64,
+    /// time elapsed since the slot of the oldest endorsement of the pool, if any
+    pub oldest_endorsement_age: Option<MassaTime>,
+    /// time elapsed since the slot of the items denounced by the oldest denunciation
+    /// of the pool, if any
+    pub oldest_denunciation_age: Option<MassaTime>,
+    /// max number of endorsements of a block
+    pub max_block_endorsement_count: u32,
+    /// endorsements of the pool at each slot, by increasing slot
+    pub endorsement_slots: Vec<SlotEndorsementCoverage>,
+}
+
+/// endorsements of the endorsement pool at a slot
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SlotEndorsementCoverage {
+    /// inclusion slot of the endorsements
+    pub slot: Slot,
+    /// number of endorsements at this slot, whatever the endorsed block
+    pub endorsement_count: usize,
+    /// endorsement indexes covered by at least one endorsement, increasing
+    pub indexes: Vec<u32>,
 }
 
 impl std::fmt::Display for PoolStats {
@@ -127,6 +237,50 @@ impl std::fmt::Display for PoolStats {
         writeln!(f, "Pool stats:")?;
         writeln!(f, "\tOperations: {}", self.operation_count)?;
         writeln!(f, "\tEndorsements: {}", self.endorsement_count)?;
+        writeln!(f, "\tDenunciations: {}", self.denunciation_count)?;
+        if let Some(age) = self.oldest_endorsement_age {
+            writeln!(f, "\tOldest endorsement age: {} ms", age)?;
+        }
+        if let Some(age) = self.oldest_denunciation_age {
+            writeln!(f, "\tOldest denunciation age: {} ms", age)?;
+        }
+        for coverage in self.endorsement_slots.iter() {
+            writeln!(
+                f,
+                "\tSlot {}: {} endorsements, indexes {:?} of {}",
+                coverage.slot,
+                coverage.endorsement_count,
+                coverage.indexes,
+                self.max_block_endorsement_count
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// occupancy of the operation pool
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct OperationPoolStats {
+    /// number of operations in the pool
+    pub operation_count: usize,
+    /// max number of operations kept in the pool at each refresh
+    pub max_operation_count: usize,
+    /// total serialized size of the operations of the pool, in bytes
+    pub total_size: usize,
+    /// number of distinct senders of the operations of the pool
+    pub sender_count: usize,
+}
+
+impl std::fmt::Display for OperationPoolStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Operation pool stats:")?;
+        writeln!(
+            f,
+            "\tOperations: {}/{}",
+            self.operation_count, self.max_operation_count
+        )?;
+        writeln!(f, "\tTotal size: {} bytes", self.total_size)?;
+        writeln!(f, "\tSenders: {}", self.sender_count)?;
         Ok(())
     }
 }
@@ -23,6 +23,8 @@ pub struct ModuleCache {
     /// Disk stored cache.
     /// See the `HDCache` documentation for more information.
     hd_cache: HDCache,
+    /// Version of the execution runtime the modules are loaded for
+    runtime_version: u32,
 }
 
 impl ModuleCache {
@@ -35,10 +37,37 @@ impl ModuleCache {
                 cfg.hd_cache_size,
                 cfg.snip_amount,
             ),
+            runtime_version: 0,
             cfg,
         }
     }
 
+    /// Set the version of the execution runtime (`MipComponent::VM` of massa-versioning)
+    /// the modules are loaded for.
+    ///
+    /// The modules of each runtime version are cached under distinct keys, so that the activation
+    /// of a new version never reuses the modules compiled for the previous one.
+    pub fn set_runtime_version(&mut self, version: u32) {
+        if version != self.runtime_version {
+            debug!(
+                "module cache runtime version changed from {} to {}",
+                self.runtime_version, version
+            );
+            self.runtime_version = version;
+        }
+    }
+
+    /// Key of the cached module of a bytecode for the current runtime version.
+    /// The modules of the initial version are keyed by the hash of their bytecode,
+    /// for the modules cached before the versioning of the runtime to stay valid.
+    fn module_hash(&self, bytecode: &[u8]) -> Hash {
+        let hash = Hash::compute_from(bytecode);
+        match self.runtime_version {
+            0 => hash,
+            version => Hash::compute_from_tuple(&[&hash.to_bytes()[..], &version.to_be_bytes()]),
+        }
+    }
+
     /// Internal function to compile and build `ModuleInfo`
     fn compile_cached(&mut self, bytecode: &[u8], hash: Hash) -> ModuleInfo {
         match RuntimeModule::new(bytecode, self.cfg.gas_costs.clone(), Compiler::CL) {
@@ -56,7 +85,7 @@ impl ModuleCache {
 
     /// Save a new or an already existing module in the cache
     pub fn save_module(&mut self, bytecode: &[u8]) {
-        let hash = Hash::compute_from(bytecode);
+        let hash = self.module_hash(bytecode);
         if let Some(hd_module_info) = self.hd_cache.get(hash, self.cfg.gas_costs.clone()) {
             debug!("save_module: {} present in hd", hash);
             self.lru_cache.insert(hash, hd_module_info);
@@ -73,14 +102,14 @@ impl ModuleCache {
 
     /// Set the initialization cost of a cached module
     pub fn set_init_cost(&mut self, bytecode: &[u8], init_cost: u64) {
-        let hash = Hash::compute_from(bytecode);
+        let hash = self.module_hash(bytecode);
         self.lru_cache.set_init_cost(hash, init_cost);
         self.hd_cache.set_init_cost(hash, init_cost);
     }
 
     /// Set a cached module as invalid
     pub fn set_invalid(&mut self, bytecode: &[u8], err_msg: String) {
-        let hash = Hash::compute_from(bytecode);
+        let hash = self.module_hash(bytecode);
         self.lru_cache.set_invalid(hash, err_msg.clone());
         self.hd_cache.set_invalid(hash, err_msg);
     }
@@ -106,7 +135,7 @@ impl ModuleCache {
             debug!(error_msg);
             return ModuleInfo::Invalid(error_msg);
         }
-        let hash = Hash::compute_from(bytecode);
+        let hash = self.module_hash(bytecode);
         if let Some(lru_module_info) = self.lru_cache.get(hash) {
             debug!("load_module: {} present in lru", hash);
             lru_module_info
@@ -185,3 +214,54 @@ impl ModuleCache {
         Ok(module)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_sc_runtime::GasCosts;
+    use serial_test::serial;
+    use tempfile::TempDir;
+
+    #[test]
+    #[serial]
+    fn test_runtime_versions() {
+        let tmp_dir = TempDir::new().unwrap();
+        let mut cache = ModuleCache::new(ModuleCacheConfig {
+            hd_cache_path: tmp_dir.path().to_path_buf(),
+            gas_costs: GasCosts::default(),
+            lru_cache_size: 10,
+            hd_cache_size: 10,
+            snip_amount: 1,
+            max_module_length: 1000,
+        });
+        let bytecode: Vec<u8> = vec![
+            0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x01, 0x60, 0x01, 0x7f,
+            0x01, 0x7f, 0x03, 0x02, 0x01, 0x00, 0x07, 0x0b, 0x01, 0x07, 0x61, 0x64, 0x64, 0x5f,
+            0x6f, 0x6e, 0x65, 0x00, 0x00, 0x0a, 0x09, 0x01, 0x07, 0x00, 0x20, 0x00, 0x41, 0x01,
+            0x6a, 0x0b, 0x00, 0x1a, 0x04, 0x6e, 0x61, 0x6d, 0x65, 0x01, 0x0a, 0x01, 0x00, 0x07,
+            0x61, 0x64, 0x64, 0x5f, 0x6f, 0x6e, 0x65, 0x02, 0x07, 0x01, 0x00, 0x01, 0x00, 0x02,
+            0x70, 0x30,
+        ];
+
+        // the modules of the initial version are keyed by the hash of their bytecode
+        cache.save_module(&bytecode);
+        let hash_v0 = Hash::compute_from(&bytecode);
+        assert_eq!(cache.module_hash(&bytecode), hash_v0);
+        cache.set_invalid(&bytecode, "invalid in version 0".to_string());
+
+        // a new version does not see the modules of the previous one
+        cache.set_runtime_version(1);
+        let hash_v1 = cache.module_hash(&bytecode);
+        assert_ne!(hash_v1, hash_v0);
+        assert!(cache.lru_cache.get(hash_v1).is_none());
+        assert!(cache.hd_cache.get(hash_v1, GasCosts::default()).is_none());
+        assert!(cache.load_module(&bytecode, u64::MAX).is_ok());
+
+        // the modules of the previous version are kept
+        cache.set_runtime_version(0);
+        assert!(matches!(
+            cache.load_module_info(&bytecode),
+            ModuleInfo::Invalid(_)
+        ));
+    }
+}
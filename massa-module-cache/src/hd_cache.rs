@@ -1,22 +1,28 @@
 use crate::types::{
     ModuleInfo, ModuleMetadata, ModuleMetadataDeserializer, ModuleMetadataSerializer,
 };
-use massa_hash::Hash;
+use massa_hash::{Hash, HASH_SIZE_BYTES};
 use massa_sc_runtime::{GasCosts, RuntimeModule};
 use massa_serialization::{DeserializeError, Deserializer, Serializer};
 use rand::RngCore;
-use rocksdb::{Direction, IteratorMode, WriteBatch, DB};
+use rocksdb::{Direction, IteratorMode, Options, WriteBatch, DB, DEFAULT_COLUMN_FAMILY_NAME};
 use std::path::PathBuf;
-use tracing::debug;
+use tracing::{debug, warn};
 
 const OPEN_ERROR: &str = "critical: rocksdb open operation failed";
 const CRUD_ERROR: &str = "critical: rocksdb crud operation failed";
+const CF_ERROR: &str = "critical: rocksdb column family operation failed";
 const DATA_SER_ERROR: &str = "critical: metadata serialization failed";
-const DATA_DESER_ERROR: &str = "critical: metadata deserialization failed";
 const MOD_SER_ERROR: &str = "critical: module serialization failed";
-const MOD_DESER_ERROR: &str = "critical: module deserialization failed";
 const MODULE_IDENT: u8 = 0u8;
 const DATA_IDENT: u8 = 1u8;
+const VERSION_CF: &str = "version";
+const VERSION_KEY: &[u8] = b"version";
+
+/// Version of the stored modules.
+/// The modules are compiled and serialized by the runtime of the node release,
+/// so the modules stored by another release are discarded when the cache is opened.
+const HD_CACHE_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 /// Module key formatting macro
 #[macro_export]
@@ -52,15 +58,21 @@ pub(crate) struct HDCache {
 }
 
 impl HDCache {
-    /// Create a new HDCache
+    /// Create a new HDCache, or open the one stored at `path`
     ///
     /// # Arguments
     /// * path: where to store the db
     /// * max_entry_count: maximum number of entries we want to keep in the db
     /// * amount_to_remove: how many entries are removed when `entry_count` reaches `max_entry_count`
     pub fn new(path: PathBuf, max_entry_count: usize, snip_amount: usize) -> Self {
-        let db = DB::open_default(path).expect(OPEN_ERROR);
-        let entry_count = db.iterator(IteratorMode::Start).count();
+        let mut db_opts = Options::default();
+        db_opts.create_if_missing(true);
+        db_opts.create_missing_column_families(true);
+        let db = DB::open_cf(&db_opts, path, [DEFAULT_COLUMN_FAMILY_NAME, VERSION_CF])
+            .expect(OPEN_ERROR);
+        clear_other_versions(&db);
+        // each entry is made of a module key and a metadata key
+        let entry_count = db.iterator(IteratorMode::Start).count() / 2;
 
         Self {
             db,
@@ -100,8 +112,13 @@ impl HDCache {
             }
         };
 
+        // the module is prefixed by its checksum, verified when it is read
+        let checksum = Hash::compute_from(&ser_module);
         let mut batch = WriteBatch::default();
-        batch.put(module_key!(hash), ser_module);
+        batch.put(
+            module_key!(hash),
+            [&checksum.to_bytes()[..], &ser_module].concat(),
+        );
         batch.put(metadata_key!(hash), ser_metadata);
         self.db.write(batch).expect(CRUD_ERROR);
 
@@ -136,38 +153,72 @@ impl HDCache {
             .expect(CRUD_ERROR);
     }
 
-    /// Retrieve a module
-    pub fn get(&self, hash: Hash, gas_costs: GasCosts) -> Option<ModuleInfo> {
-        let mut iterator = self
-            .db
-            .iterator(IteratorMode::From(&module_key!(hash), Direction::Forward));
-
-        if let (Some(Ok((key_1, ser_module))), Some(Ok((key_2, ser_metadata)))) =
-            (iterator.next(), iterator.next())
-        {
-            if *key_1 == module_key!(hash) && *key_2 == metadata_key!(hash) {
-                let (_, metadata) = self
-                    .meta_deser
-                    .deserialize::<DeserializeError>(&ser_metadata)
-                    .expect(DATA_DESER_ERROR);
-                if let ModuleMetadata::Invalid(err_msg) = metadata {
-                    return Some(ModuleInfo::Invalid(err_msg));
+    /// Retrieve a module.
+    /// The entries failing the integrity checks are removed, for their module to be compiled again.
+    pub fn get(&mut self, hash: Hash, gas_costs: GasCosts) -> Option<ModuleInfo> {
+        let (stored_module, ser_metadata) = {
+            let mut iterator = self
+                .db
+                .iterator(IteratorMode::From(&module_key!(hash), Direction::Forward));
+            match (iterator.next(), iterator.next()) {
+                (Some(Ok((key_1, stored_module))), Some(Ok((key_2, ser_metadata))))
+                    if *key_1 == module_key!(hash) && *key_2 == metadata_key!(hash) =>
+                {
+                    (stored_module, ser_metadata)
                 }
-                let module =
-                    RuntimeModule::deserialize(&ser_module, gas_costs.max_instance_cost, gas_costs)
-                        .expect(MOD_DESER_ERROR);
-                let result = match metadata {
-                    ModuleMetadata::Invalid(err_msg) => ModuleInfo::Invalid(err_msg),
-                    ModuleMetadata::NotExecuted => ModuleInfo::Module(module),
-                    ModuleMetadata::Delta(delta) => ModuleInfo::ModuleAndDelta((module, delta)),
-                };
-                Some(result)
-            } else {
-                None
+                _ => return None,
             }
-        } else {
-            None
+        };
+
+        let module_info = self.parse_module_info(&stored_module, &ser_metadata, gas_costs);
+        if module_info.is_none() {
+            warn!(
+                "HD cache entry of module {} is corrupted, removing it",
+                hash
+            );
+            self.remove(hash);
+        }
+        module_info
+    }
+
+    /// Check and parse a stored module and its metadata, returning None if they are corrupted
+    fn parse_module_info(
+        &self,
+        stored_module: &[u8],
+        ser_metadata: &[u8],
+        gas_costs: GasCosts,
+    ) -> Option<ModuleInfo> {
+        let (_, metadata) = self
+            .meta_deser
+            .deserialize::<DeserializeError>(ser_metadata)
+            .ok()?;
+        if let ModuleMetadata::Invalid(err_msg) = metadata {
+            return Some(ModuleInfo::Invalid(err_msg));
+        }
+        if stored_module.len() < HASH_SIZE_BYTES {
+            return None;
+        }
+        let (checksum, ser_module) = stored_module.split_at(HASH_SIZE_BYTES);
+        if checksum != &Hash::compute_from(ser_module).to_bytes()[..] {
+            return None;
         }
+        let module =
+            RuntimeModule::deserialize(ser_module, gas_costs.max_instance_cost, gas_costs).ok()?;
+        let result = match metadata {
+            ModuleMetadata::Invalid(err_msg) => ModuleInfo::Invalid(err_msg),
+            ModuleMetadata::NotExecuted => ModuleInfo::Module(module),
+            ModuleMetadata::Delta(delta) => ModuleInfo::ModuleAndDelta((module, delta)),
+        };
+        Some(result)
+    }
+
+    /// Remove a module and its metadata
+    fn remove(&mut self, hash: Hash) {
+        let mut batch = WriteBatch::default();
+        batch.delete(module_key!(hash));
+        batch.delete(metadata_key!(hash));
+        self.db.write(batch).expect(CRUD_ERROR);
+        self.entry_count = self.entry_count.saturating_sub(1);
     }
 
     /// Try to remove as much as `self.amount_to_snip` entries from the db
@@ -214,6 +265,27 @@ impl HDCache {
     }
 }
 
+/// Remove the modules stored by another version, and set the current version
+fn clear_other_versions(db: &DB) {
+    let version_cf = db.cf_handle(VERSION_CF).expect(CF_ERROR);
+    let version = db.get_cf(version_cf, VERSION_KEY).expect(CRUD_ERROR);
+    if version.as_deref() == Some(HD_CACHE_VERSION.as_bytes()) {
+        return;
+    }
+    debug!(
+        "HD cache version changed from {:?} to {}, removing the stored modules",
+        version.map(|version| String::from_utf8_lossy(&version).into_owned()),
+        HD_CACHE_VERSION
+    );
+    let mut batch = WriteBatch::default();
+    for entry in db.iterator(IteratorMode::Start) {
+        let (key, _) = entry.expect(CRUD_ERROR);
+        batch.delete(key);
+    }
+    batch.put_cf(version_cf, VERSION_KEY, HD_CACHE_VERSION.as_bytes());
+    db.write(batch).expect(CRUD_ERROR);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -313,4 +385,52 @@ mod tests {
             assert!(cached_module.is_none());
         }
     }
+
+    #[test]
+    #[serial]
+    fn test_corrupted_module() {
+        let mut cache = setup();
+        let hash = Hash::compute_from(b"test_hash");
+        let gas_costs = GasCosts::default();
+
+        cache.insert(hash, make_default_module_info());
+        assert_eq!(cache.entry_count, 1);
+
+        // overwrite the stored module with data not matching its checksum
+        let mut stored_module = cache.db.get(module_key!(hash)).unwrap().unwrap();
+        let last = stored_module.len() - 1;
+        stored_module[last] ^= 0xff;
+        cache.db.put(module_key!(hash), stored_module).unwrap();
+
+        // the corrupted entry is removed
+        assert!(cache.get(hash, gas_costs.clone()).is_none());
+        assert_eq!(cache.entry_count, 0);
+        assert!(cache.db.get(metadata_key!(hash)).unwrap().is_none());
+        assert!(cache.get(hash, gas_costs).is_none());
+    }
+
+    #[test]
+    #[serial]
+    fn test_persistence_across_versions() {
+        let tmp_dir = TempDir::new().unwrap();
+        let hash = Hash::compute_from(b"test_hash");
+        let gas_costs = GasCosts::default();
+
+        let mut cache = HDCache::new(tmp_dir.path().to_path_buf(), 1000, 10);
+        cache.insert(hash, make_default_module_info());
+        drop(cache);
+
+        // the modules are kept when reopening the cache with the same version
+        let mut cache = HDCache::new(tmp_dir.path().to_path_buf(), 1000, 10);
+        assert_eq!(cache.entry_count, 1);
+        assert!(cache.get(hash, gas_costs.clone()).is_some());
+
+        // and discarded when they were stored by another version
+        let version_cf = cache.db.cf_handle(VERSION_CF).unwrap();
+        cache.db.put_cf(version_cf, VERSION_KEY, b"0.0.0").unwrap();
+        drop(cache);
+        let mut cache = HDCache::new(tmp_dir.path().to_path_buf(), 1000, 10);
+        assert_eq!(cache.entry_count, 0);
+        assert!(cache.get(hash, gas_costs).is_none());
+    }
 }
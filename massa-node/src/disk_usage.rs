@@ -0,0 +1,228 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Periodic accounting of the disk usage of the node storage.
+//!
+//! Only the databases are measured: the consensus graph and the storage of the blocks and
+//! operations are kept in RAM, and the logs are written to the standard output.
+//! The archived final states share their unchanged files with the ledger through hard links,
+//! those files being counted in both.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+use crossbeam_channel::{select, tick, Sender};
+use massa_api_exports::storage::{StorageInfo, SubsystemStorageInfo};
+use massa_execution_exports::ExecutionController;
+use massa_metrics::MassaMetrics;
+use massa_time::MassaTime;
+use parking_lot::RwLock;
+use tracing::{error, info, warn};
+use walkdir::WalkDir;
+
+use crate::settings::DiskUsageSettings;
+
+pub struct DiskUsageMonitorStopper {
+    tx_stopper: Option<Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl DiskUsageMonitorStopper {
+    pub fn stop(&mut self) {
+        if let Some(tx) = self.tx_stopper.take() {
+            if tx.send(()).is_err() {
+                warn!("failed to send stop signal to the disk usage thread");
+            }
+        }
+        if let Some(handle) = self.handle.take() {
+            if handle.join().is_err() {
+                warn!("failed to join the disk usage thread");
+            }
+        }
+    }
+}
+
+/// Start the thread measuring the disk usage of the node subsystems every `check_interval`.
+/// The writes to the operation and event indexes are suspended while the total disk usage
+/// is above `max_size`.
+///
+/// # Arguments
+/// * `subsystems`: name and storage path of the subsystems storing data on the disk
+/// * `storage_info`: updated with each measure, for the API
+pub fn start_disk_usage_monitor(
+    config: DiskUsageSettings,
+    subsystems: Vec<(String, PathBuf)>,
+    storage_info: Arc<RwLock<StorageInfo>>,
+    execution_controller: Box<dyn ExecutionController>,
+    massa_metrics: MassaMetrics,
+) -> DiskUsageMonitorStopper {
+    let (tx_stopper, rx_stopper) = crossbeam_channel::bounded(1);
+    let update_tick = tick(config.check_interval.to_duration());
+    let mut monitor = DiskUsageMonitor {
+        config,
+        subsystems,
+        storage_info,
+        execution_controller,
+        massa_metrics,
+        index_writes_suspended: false,
+    };
+    let handle = std::thread::Builder::new()
+        .name("massa-disk-usage".to_string())
+        .spawn(move || loop {
+            monitor.check();
+            select! {
+                recv(rx_stopper) -> _ => break,
+                recv(update_tick) -> _ => {},
+            }
+        })
+        .expect("failed to spawn the disk usage thread");
+    DiskUsageMonitorStopper {
+        tx_stopper: Some(tx_stopper),
+        handle: Some(handle),
+    }
+}
+
+struct DiskUsageMonitor {
+    config: DiskUsageSettings,
+    subsystems: Vec<(String, PathBuf)>,
+    storage_info: Arc<RwLock<StorageInfo>>,
+    execution_controller: Box<dyn ExecutionController>,
+    massa_metrics: MassaMetrics,
+    index_writes_suspended: bool,
+}
+
+impl DiskUsageMonitor {
+    /// Measure the disk usage of the subsystems and check it against the thresholds
+    fn check(&mut self) {
+        let subsystems: Vec<SubsystemStorageInfo> = self
+            .subsystems
+            .iter()
+            .map(|(name, path)| {
+                let size = directory_size(path);
+                self.massa_metrics.set_disk_usage(name, size);
+                SubsystemStorageInfo {
+                    name: name.clone(),
+                    path: path.clone(),
+                    size,
+                }
+            })
+            .collect();
+        let total_size = subsystems.iter().map(|subsystem| subsystem.size).sum();
+
+        let DiskUsageSettings {
+            warning_threshold,
+            max_size,
+            ..
+        } = self.config;
+        if warning_threshold > 0 && total_size >= warning_threshold {
+            warn!(
+                "node storage uses {} bytes, above the warning threshold of {} bytes",
+                total_size, warning_threshold
+            );
+        }
+        let max_size_exceeded = max_size > 0 && total_size >= max_size;
+        if max_size_exceeded != self.index_writes_suspended {
+            if max_size_exceeded {
+                error!(
+                    "node storage uses {} bytes, above its max size: index writes suspended",
+                    total_size
+                );
+            } else {
+                info!("node storage back below the max size: index writes resumed");
+            }
+            self.execution_controller
+                .set_index_writes_suspended(max_size_exceeded);
+            self.index_writes_suspended = max_size_exceeded;
+        }
+
+        *self.storage_info.write() = StorageInfo {
+            subsystems,
+            total_size,
+            warning_threshold,
+            max_size,
+            index_writes_suspended: self.index_writes_suspended,
+            last_check: Some(MassaTime::now()),
+        };
+    }
+}
+
+/// Total size in bytes of the files under a path, 0 if it does not exist
+fn directory_size(path: &Path) -> u64 {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.metadata().ok())
+        .filter(|metadata| metadata.is_file())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_execution_exports::MockExecutionController;
+    use massa_models::config::THREAD_COUNT;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_index_writes_suspended_above_max_size() {
+        let dir = TempDir::new().unwrap();
+        let ledger_path = dir.path().join("ledger");
+        std::fs::create_dir_all(ledger_path.join("archive")).unwrap();
+        std::fs::write(ledger_path.join("000001.sst"), [0; 600]).unwrap();
+        std::fs::write(ledger_path.join("archive").join("000002.sst"), [0; 400]).unwrap();
+
+        // the writes are suspended once, then resumed once
+        let mut execution_controller = MockExecutionController::new();
+        execution_controller
+            .expect_set_index_writes_suspended()
+            .withf(|suspended| *suspended)
+            .times(1)
+            .return_const(());
+        execution_controller
+            .expect_set_index_writes_suspended()
+            .withf(|suspended| !*suspended)
+            .times(1)
+            .return_const(());
+        let storage_info = Arc::new(RwLock::new(StorageInfo::default()));
+        let mut monitor = DiskUsageMonitor {
+            config: DiskUsageSettings {
+                check_interval: MassaTime::from_millis(1000),
+                warning_threshold: 500,
+                max_size: 1000,
+            },
+            subsystems: vec![
+                ("ledger".to_string(), ledger_path.clone()),
+                ("missing".to_string(), dir.path().join("missing")),
+            ],
+            storage_info: storage_info.clone(),
+            execution_controller: Box::new(execution_controller),
+            massa_metrics: MassaMetrics::new(
+                false,
+                "0.0.0.0:31248".parse().unwrap(),
+                THREAD_COUNT,
+                Duration::from_secs(5),
+            )
+            .0,
+            index_writes_suspended: false,
+        };
+
+        monitor.check();
+        monitor.check();
+        {
+            let storage_info = storage_info.read();
+            assert_eq!(storage_info.total_size, 1000);
+            assert_eq!(storage_info.subsystems[0].size, 1000);
+            // a missing directory uses no space
+            assert_eq!(storage_info.subsystems[1].size, 0);
+            assert!(storage_info.index_writes_suspended);
+        }
+
+        std::fs::remove_dir_all(ledger_path.join("archive")).unwrap();
+        monitor.check();
+        let storage_info = storage_info.read();
+        assert_eq!(storage_info.total_size, 600);
+        assert!(!storage_info.index_writes_suspended);
+    }
+}
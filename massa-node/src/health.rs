@@ -0,0 +1,159 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Readiness and liveness of the node, served over HTTP for the service managers and the
+//! orchestrators such as Kubernetes.
+//!
+//! `/readyz` answers 200 once the node is bootstrapped and serving, and 503 while it
+//! bootstraps or stops. `/livez` answers 200 as long as the main loop of the node runs, and 503
+//! once it did not run for `liveness_timeout`. Bootstrapping blocks the main loop, so the
+//! liveness is only checked while the node is ready.
+
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Request, Response, StatusCode};
+use massa_time::MassaTime;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+/// State of the node reported by the health endpoints
+pub struct NodeHealth {
+    /// whether the node is bootstrapped and serving
+    ready: AtomicBool,
+    /// last run of the main loop, in milliseconds since the epoch
+    last_heartbeat: AtomicU64,
+    /// time after which the node is considered stuck if its main loop did not run
+    liveness_timeout: MassaTime,
+}
+
+impl NodeHealth {
+    pub fn new(liveness_timeout: MassaTime) -> Self {
+        NodeHealth {
+            ready: AtomicBool::new(false),
+            last_heartbeat: AtomicU64::new(MassaTime::now().as_millis()),
+            liveness_timeout,
+        }
+    }
+
+    /// Mark the node as serving, or as bootstrapping or stopping
+    pub fn set_ready(&self, ready: bool) {
+        self.heartbeat();
+        self.ready.store(ready, Ordering::Relaxed);
+    }
+
+    /// Record a run of the main loop
+    pub fn heartbeat(&self) {
+        self.last_heartbeat
+            .store(MassaTime::now().as_millis(), Ordering::Relaxed);
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::Relaxed)
+    }
+
+    pub fn is_live(&self) -> bool {
+        let last_heartbeat = MassaTime::from_millis(self.last_heartbeat.load(Ordering::Relaxed));
+        !self.is_ready() || MassaTime::now().saturating_sub(last_heartbeat) <= self.liveness_timeout
+    }
+}
+
+pub struct HealthServerStopper {
+    tx_stopper: Option<oneshot::Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl HealthServerStopper {
+    pub async fn stop(&mut self) {
+        if let Some(tx) = self.tx_stopper.take() {
+            if tx.send(()).is_err() {
+                warn!("failed to send stop signal to the health server");
+            }
+        }
+        if let Some(handle) = self.handle.take() {
+            if handle.await.is_err() {
+                warn!("failed to join the health server task");
+            }
+        }
+    }
+}
+
+/// Start the HTTP server answering `/readyz` and `/livez` on `bind`
+pub fn start_health_server(
+    bind: SocketAddr,
+    health: Arc<NodeHealth>,
+) -> Result<HealthServerStopper, hyper::Error> {
+    let server = hyper::Server::try_bind(&bind)?.serve(make_service_fn(move |_| {
+        let health = health.clone();
+        async move { Ok::<_, hyper::Error>(service_fn(move |req| serve_req(req, health.clone()))) }
+    }));
+    let (tx_stopper, rx_stopper) = oneshot::channel::<()>();
+    let handle = tokio::spawn(async move {
+        let graceful_server = server.with_graceful_shutdown(async {
+            rx_stopper.await.ok();
+        });
+        if let Err(e) = graceful_server.await {
+            error!("health server error: {}", e);
+        }
+    });
+    info!("HEALTH | listening on http://{}", bind);
+    Ok(HealthServerStopper {
+        tx_stopper: Some(tx_stopper),
+        handle: Some(handle),
+    })
+}
+
+async fn serve_req(
+    req: Request<Body>,
+    health: Arc<NodeHealth>,
+) -> Result<Response<Body>, hyper::Error> {
+    let healthy = match req.uri().path() {
+        "/readyz" => health.is_ready(),
+        "/livez" => health.is_live(),
+        _ => {
+            return Ok(Response::builder()
+                .status(StatusCode::NOT_FOUND)
+                .body(Body::from("Not Found"))
+                .unwrap());
+        }
+    };
+    let (status, body) = if healthy {
+        (StatusCode::OK, "ok")
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, "unavailable")
+    };
+    Ok(Response::builder()
+        .status(status)
+        .body(Body::from(body))
+        .unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_liveness_checked_while_ready() {
+        let health = NodeHealth::new(MassaTime::from_millis(1000));
+        assert!(!health.is_ready());
+        assert!(health.is_live());
+
+        health.set_ready(true);
+        assert!(health.is_ready());
+        assert!(health.is_live());
+
+        // the main loop did not run for longer than the timeout
+        let stale = MassaTime::now().as_millis() - 2000;
+        health.last_heartbeat.store(stale, Ordering::Relaxed);
+        assert!(!health.is_live());
+        health.heartbeat();
+        assert!(health.is_live());
+
+        // the main loop is not expected to run while the node stops
+        health.set_ready(false);
+        health.last_heartbeat.store(stale, Ordering::Relaxed);
+        assert!(health.is_live());
+    }
+}
@@ -0,0 +1,271 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Logs of the node: their format, their levels per module, and the rotation of the log file.
+//!
+//! The logs are written to the standard output and, if configured, to a log file. The log file is
+//! rotated when it exceeds `max_size` or after `rotation_interval`: it is renamed with the time of
+//! its rotation as suffix, compressed with gzip in the background, and the oldest rotated files
+//! beyond `max_files` are deleted.
+
+use std::ffi::OsString;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::thread::JoinHandle;
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use massa_time::MassaTime;
+use tracing::Subscriber;
+use tracing_subscriber::filter::{LevelFilter, Targets};
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{reload, Layer, Registry};
+
+use crate::settings::{LogFileSettings, LogFormat, LoggingSettings};
+
+/// Handle used to change the log levels of the running node
+pub type LogFilterHandle = reload::Handle<Targets, Registry>;
+
+/// Converts a logging level setting into a tracing level filter
+pub fn log_level_filter(level: usize) -> LevelFilter {
+    match level {
+        4 => LevelFilter::TRACE,
+        3 => LevelFilter::DEBUG,
+        2 => LevelFilter::INFO,
+        1 => LevelFilter::WARN,
+        _ => LevelFilter::ERROR,
+    }
+}
+
+/// Log filter applying `logging.level` to the massa modules, overridden by `logging.modules`.
+/// The logs of the other crates are ignored unless they have a level in `logging.modules`.
+pub fn log_filter(settings: &LoggingSettings) -> Targets {
+    settings.modules.iter().fold(
+        Targets::new().with_target("massa", log_level_filter(settings.level)),
+        |targets, (module, level)| targets.with_target(module, log_level_filter(*level)),
+    )
+}
+
+/// Set up the logs of the node, returning the handle used to change their levels at runtime
+pub fn init_logging(settings: &LoggingSettings) -> io::Result<LogFilterHandle> {
+    let (filter, filter_handle) = reload::Layer::new(log_filter(settings));
+    let file_layer = match &settings.file.path {
+        Some(path) => {
+            let file = RotatingFile::open(path.clone(), &settings.file)?;
+            Some(fmt_layer(settings.format, Mutex::new(file), false))
+        }
+        None => None,
+    };
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt_layer(settings.format, io::stdout, true))
+        .with(file_layer)
+        .init();
+    Ok(filter_handle)
+}
+
+/// Layer writing the logs to `writer` in the given format
+fn fmt_layer<S, W>(format: LogFormat, writer: W, ansi: bool) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    let layer = tracing_subscriber::fmt::layer()
+        .with_writer(writer)
+        .with_ansi(ansi);
+    match format {
+        LogFormat::Text => layer.boxed(),
+        LogFormat::Json => layer.json().boxed(),
+    }
+}
+
+/// Log file rotated by size and age
+pub struct RotatingFile {
+    /// path of the current log file
+    path: PathBuf,
+    /// current log file
+    file: File,
+    /// size of the current log file
+    size: u64,
+    /// time at which the current log file was opened
+    opened_at: MassaTime,
+    /// rotation settings
+    settings: LogFileSettings,
+    /// compression and deletion of the previously rotated files
+    archiver: Option<JoinHandle<()>>,
+}
+
+impl RotatingFile {
+    /// Open the log file at `path`, appending to it if it exists
+    pub fn open(path: PathBuf, settings: &LogFileSettings) -> io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        Ok(RotatingFile {
+            size: file.metadata()?.len(),
+            path,
+            file,
+            opened_at: MassaTime::now(),
+            settings: settings.clone(),
+            archiver: None,
+        })
+    }
+
+    /// Whether writing `len` more bytes requires rotating the log file first
+    fn should_rotate(&self, len: usize) -> bool {
+        if self.size == 0 {
+            return false;
+        }
+        let max_size = self.settings.max_size;
+        let interval = self.settings.rotation_interval;
+        (max_size > 0 && self.size + len as u64 > max_size)
+            || (interval > MassaTime::from_millis(0)
+                && MassaTime::now().saturating_sub(self.opened_at) >= interval)
+    }
+
+    /// Rename the log file with the current time as suffix and open a new one. The renamed file
+    /// is compressed and the oldest rotated files are deleted in the background.
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        let mut timestamp = MassaTime::now().as_millis();
+        let rotated_path = loop {
+            let rotated_path = suffixed(&self.path, &timestamp.to_string());
+            if !rotated_path.exists() && !suffixed(&rotated_path, "gz").exists() {
+                break rotated_path;
+            }
+            timestamp += 1;
+        };
+        std::fs::rename(&self.path, &rotated_path)?;
+        self.file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        self.size = 0;
+        self.opened_at = MassaTime::now();
+
+        // the rotations are archived one after the other
+        if let Some(archiver) = self.archiver.take() {
+            let _ = archiver.join();
+        }
+        let path = self.path.clone();
+        let settings = self.settings.clone();
+        self.archiver = Some(std::thread::spawn(move || {
+            // the logs can not be used here, as they are being written
+            if settings.compress {
+                if let Err(err) = compress(&rotated_path) {
+                    eprintln!("could not compress {}: {}", rotated_path.display(), err);
+                }
+            }
+            if let Err(err) = delete_oldest_rotations(&path, settings.max_files) {
+                eprintln!("could not delete the oldest rotated log files: {}", err);
+            }
+        }));
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.should_rotate(buf.len()) {
+            self.rotate()?;
+        }
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// `path` followed by a dot and `suffix`
+fn suffixed(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = OsString::from(path);
+    path.push(".");
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+/// Compress the file at `path` with gzip, replacing it by `<path>.gz`
+fn compress(path: &Path) -> io::Result<()> {
+    let mut input = File::open(path)?;
+    let mut output = GzEncoder::new(File::create(suffixed(path, "gz"))?, Compression::default());
+    io::copy(&mut input, &mut output)?;
+    output.finish()?;
+    std::fs::remove_file(path)
+}
+
+/// Delete the oldest rotated files of the log file at `path`, keeping `max_files` of them
+fn delete_oldest_rotations(path: &Path, max_files: usize) -> io::Result<()> {
+    let (Some(dir), Some(name)) = (path.parent(), path.file_name()) else {
+        return Ok(());
+    };
+    let prefix = format!("{}.", name.to_string_lossy());
+    let dir = if dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        dir
+    };
+    let mut rotations: Vec<(u64, PathBuf)> = std::fs::read_dir(dir)?
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let name = path.file_name()?.to_str()?;
+            let suffix = name.strip_prefix(&prefix)?;
+            let timestamp = suffix.strip_suffix(".gz").unwrap_or(suffix).parse().ok()?;
+            Some((timestamp, path))
+        })
+        .collect();
+    rotations.sort_unstable();
+    let excess = rotations.len().saturating_sub(max_files);
+    for (_, path) in rotations.into_iter().take(excess) {
+        std::fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_rotation_by_size() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("logs").join("node.log");
+        let settings = LogFileSettings {
+            path: Some(path.clone()),
+            max_size: 10,
+            rotation_interval: MassaTime::from_millis(0),
+            max_files: 2,
+            compress: true,
+        };
+        let mut file = RotatingFile::open(path.clone(), &settings).unwrap();
+        for line in ["line 1\n", "line 2\n", "line 3\n", "line 4\n"] {
+            file.write_all(line.as_bytes()).unwrap();
+        }
+        file.archiver.take().unwrap().join().unwrap();
+
+        // each line exceeds the max size of the file with the previous one
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "line 4\n");
+        let mut rotations: Vec<PathBuf> = std::fs::read_dir(path.parent().unwrap())
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|rotation| *rotation != path)
+            .collect();
+        rotations.sort();
+        // the oldest rotated file was deleted
+        assert_eq!(rotations.len(), 2);
+        let mut content = String::new();
+        GzDecoder::new(File::open(&rotations[0]).unwrap())
+            .read_to_string(&mut content)
+            .unwrap();
+        assert_eq!(content, "line 2\n");
+    }
+}
@@ -5,6 +5,9 @@
 #![warn(unused_crate_dependencies)]
 extern crate massa_logging;
 
+use crate::disk_usage::{start_disk_usage_monitor, DiskUsageMonitorStopper};
+use crate::logging::{init_logging, LogFilterHandle};
+use crate::node_config::{read_node_config, NodeConfigControllerImpl};
 #[cfg(feature = "op_spammer")]
 use crate::operation_injector::start_operation_injector;
 use crate::settings::SETTINGS;
@@ -14,12 +17,14 @@ use clap::{crate_version, Parser};
 use crossbeam_channel::TryRecvError;
 use dialoguer::Password;
 use massa_api::{ApiServer, ApiV2, Private, Public, RpcServer, StopHandle, API};
-use massa_api_exports::config::APIConfig;
+use massa_api_exports::config::{APIConfig, ApiLimits};
+use massa_api_exports::node_config::NodeConfigController;
+use massa_api_exports::storage::StorageInfo;
 use massa_async_pool::AsyncPoolConfig;
 use massa_bootstrap::BootstrapError;
 use massa_bootstrap::{
     get_state, start_bootstrap_server, BootstrapConfig, BootstrapManager, BootstrapTcpListener,
-    DefaultConnector,
+    DefaultConnector, GlobalBootstrapState,
 };
 use massa_channel::receiver::MassaReceiver;
 use massa_channel::MassaChannel;
@@ -28,8 +33,14 @@ use massa_consensus_exports::{
     ConsensusBroadcasts, ConsensusChannels, ConsensusConfig, ConsensusManager,
 };
 use massa_consensus_worker::start_consensus_worker;
-use massa_db_exports::{MassaDBConfig, MassaDBController};
-use massa_db_worker::MassaDB;
+use massa_db_exports::{
+    MassaDBConfig, MassaDBController, MassaDBError, ShareableMassaDBController, METADATA_CF,
+    STATE_CF, VERSIONING_CF,
+};
+use massa_db_worker::{
+    check_snapshot_hash, export_snapshot, import_snapshot, start_compaction_scheduler,
+    CompactionSchedulerStopper, MassaDB,
+};
 use massa_executed_ops::{ExecutedDenunciationsConfig, ExecutedOpsConfig};
 use massa_execution_exports::{
     ExecutionChannels, ExecutionConfig, ExecutionManager, GasCosts, StorageCostsConstants,
@@ -40,6 +51,8 @@ use massa_factory_worker::start_factory;
 use massa_final_state::{FinalState, FinalStateConfig, FinalStateController};
 use massa_grpc::config::{GrpcConfig, ServiceName};
 use massa_grpc::server::{MassaPrivateGrpc, MassaPublicGrpc};
+use massa_grpc::stream::journal::EventJournals;
+use massa_grpc::stream::limiter::StreamLimiter;
 use massa_ledger_exports::LedgerConfig;
 use massa_ledger_worker::FinalLedger;
 use massa_logging::massa_trace;
@@ -48,21 +61,21 @@ use massa_models::address::Address;
 use massa_models::amount::Amount;
 use massa_models::config::constants::{
     ASYNC_MSG_CST_GAS_COST, BLOCK_REWARD, BOOTSTRAP_RANDOMNESS_SIZE_BYTES, CHANNEL_SIZE,
-    CONSENSUS_BOOTSTRAP_PART_SIZE, DELTA_F0, DENUNCIATION_EXPIRE_PERIODS, ENDORSEMENT_COUNT,
-    END_TIMESTAMP, GENESIS_KEY, GENESIS_TIMESTAMP, INITIAL_DRAW_SEED, LEDGER_COST_PER_BYTE,
-    LEDGER_ENTRY_BASE_COST, LEDGER_ENTRY_DATASTORE_BASE_SIZE, MAX_ADVERTISE_LENGTH, MAX_ASYNC_GAS,
-    MAX_ASYNC_POOL_LENGTH, MAX_BLOCK_SIZE, MAX_BOOTSTRAP_BLOCKS, MAX_BOOTSTRAP_ERROR_LENGTH,
-    MAX_BYTECODE_LENGTH, MAX_CONSENSUS_BLOCKS_IDS, MAX_DATASTORE_ENTRY_COUNT,
-    MAX_DATASTORE_KEY_LENGTH, MAX_DATASTORE_VALUE_LENGTH, MAX_DEFERRED_CREDITS_LENGTH,
-    MAX_DENUNCIATIONS_PER_BLOCK_HEADER, MAX_DENUNCIATION_CHANGES_LENGTH,
-    MAX_ENDORSEMENTS_PER_MESSAGE, MAX_EXECUTED_OPS_CHANGES_LENGTH, MAX_EXECUTED_OPS_LENGTH,
-    MAX_FUNCTION_NAME_LENGTH, MAX_GAS_PER_BLOCK, MAX_LEDGER_CHANGES_COUNT, MAX_LISTENERS_PER_PEER,
-    MAX_OPERATIONS_PER_BLOCK, MAX_OPERATIONS_PER_MESSAGE, MAX_OPERATION_DATASTORE_ENTRY_COUNT,
-    MAX_OPERATION_DATASTORE_KEY_LENGTH, MAX_OPERATION_DATASTORE_VALUE_LENGTH,
-    MAX_OPERATION_STORAGE_TIME, MAX_PARAMETERS_SIZE, MAX_PEERS_IN_ANNOUNCEMENT_LIST,
-    MAX_PRODUCTION_STATS_LENGTH, MAX_ROLLS_COUNT_LENGTH, MAX_SIZE_CHANNEL_COMMANDS_CONNECTIVITY,
-    MAX_SIZE_CHANNEL_COMMANDS_PEERS, MAX_SIZE_CHANNEL_COMMANDS_PEER_TESTERS,
-    MAX_SIZE_CHANNEL_COMMANDS_PROPAGATION_BLOCKS,
+    CONSENSUS_BOOTSTRAP_PART_SIZE, DELTA_F0, DENUNCIATION_EXPIRE_PERIODS, DUST_ACCOUNT_MAX_BALANCE,
+    DUST_PRUNING_SCAN_LENGTH, ENDORSEMENT_COUNT, END_TIMESTAMP, GENESIS_KEY, GENESIS_TIMESTAMP,
+    INITIAL_DRAW_SEED, LEDGER_COST_PER_BYTE, LEDGER_ENTRY_BASE_COST,
+    LEDGER_ENTRY_DATASTORE_BASE_SIZE, MAX_ADVERTISE_LENGTH, MAX_ASYNC_GAS, MAX_ASYNC_POOL_LENGTH,
+    MAX_BLOCK_SIZE, MAX_BOOTSTRAP_BLOCKS, MAX_BOOTSTRAP_ERROR_LENGTH, MAX_BYTECODE_LENGTH,
+    MAX_CONSENSUS_BLOCKS_IDS, MAX_DATASTORE_ENTRY_COUNT, MAX_DATASTORE_KEY_LENGTH,
+    MAX_DATASTORE_VALUE_LENGTH, MAX_DEFERRED_CREDITS_LENGTH, MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
+    MAX_DENUNCIATION_CHANGES_LENGTH, MAX_ENDORSEMENTS_PER_MESSAGE, MAX_EXECUTED_OPS_CHANGES_LENGTH,
+    MAX_EXECUTED_OPS_LENGTH, MAX_FUNCTION_NAME_LENGTH, MAX_GAS_PER_BLOCK, MAX_LEDGER_CHANGES_COUNT,
+    MAX_LISTENERS_PER_PEER, MAX_OPERATIONS_PER_BLOCK, MAX_OPERATIONS_PER_MESSAGE,
+    MAX_OPERATION_DATASTORE_ENTRY_COUNT, MAX_OPERATION_DATASTORE_KEY_LENGTH,
+    MAX_OPERATION_DATASTORE_VALUE_LENGTH, MAX_OPERATION_STORAGE_TIME, MAX_PARAMETERS_SIZE,
+    MAX_PEERS_IN_ANNOUNCEMENT_LIST, MAX_PRODUCTION_STATS_LENGTH, MAX_ROLLS_COUNT_LENGTH,
+    MAX_SIZE_CHANNEL_COMMANDS_CONNECTIVITY, MAX_SIZE_CHANNEL_COMMANDS_PEERS,
+    MAX_SIZE_CHANNEL_COMMANDS_PEER_TESTERS, MAX_SIZE_CHANNEL_COMMANDS_PROPAGATION_BLOCKS,
     MAX_SIZE_CHANNEL_COMMANDS_PROPAGATION_ENDORSEMENTS,
     MAX_SIZE_CHANNEL_COMMANDS_PROPAGATION_OPERATIONS, MAX_SIZE_CHANNEL_COMMANDS_RETRIEVAL_BLOCKS,
     MAX_SIZE_CHANNEL_COMMANDS_RETRIEVAL_ENDORSEMENTS,
@@ -81,7 +94,7 @@ use massa_models::config::{
     POOL_CONTROLLER_ENDORSEMENTS_CHANNEL_SIZE, POOL_CONTROLLER_OPERATIONS_CHANNEL_SIZE,
 };
 use massa_models::slot::Slot;
-use massa_models::timeslots::get_block_slot_timestamp;
+use massa_models::timeslots::{get_block_slot_timestamp, get_latest_block_slot_at_timestamp};
 use massa_pool_exports::{PoolBroadcasts, PoolChannels, PoolConfig, PoolManager};
 use massa_pool_worker::start_pool_controller;
 use massa_pos_exports::{PoSConfig, SelectorConfig, SelectorManager};
@@ -100,18 +113,25 @@ use parking_lot::RwLock;
 use settings::GrpcSettings;
 use std::collections::HashMap;
 use std::path::PathBuf;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Condvar, Mutex};
 use std::time::Duration;
 use std::{path::Path, process, sync::Arc};
 
+use health::{start_health_server, NodeHealth};
+use repair::open_verified_disk_ledger;
 use survey::MassaSurveyStopper;
 use tokio::sync::broadcast;
 use tracing::{debug, error, info, warn};
-use tracing_subscriber::filter::{filter_fn, LevelFilter};
 
+mod disk_usage;
+mod health;
+mod logging;
+mod node_config;
 #[cfg(feature = "op_spammer")]
 mod operation_injector;
+mod repair;
+mod sd_notify;
 mod settings;
 mod survey;
 
@@ -119,6 +139,9 @@ async fn launch(
     args: &Args,
     node_wallet: Arc<RwLock<Wallet>>,
     sig_int_toggled: Arc<(Mutex<bool>, Condvar)>,
+    node_config: Arc<RwLock<serde_json::Value>>,
+    log_filter_handle: LogFilterHandle,
+    shutdown_grace_period: Arc<RwLock<MassaTime>>,
 ) -> (
     MassaReceiver<ConsensusEvent>,
     Option<BootstrapManager>,
@@ -135,6 +158,10 @@ async fn launch(
     Option<massa_grpc::server::StopHandle>,
     MetricsStopper,
     MassaSurveyStopper,
+    CompactionSchedulerStopper,
+    DiskUsageMonitorStopper,
+    NodeConfigControllerImpl,
+    ShareableMassaDBController,
 ) {
     let now = MassaTime::now();
 
@@ -180,6 +207,17 @@ async fn launch(
         endorsement_count: ENDORSEMENT_COUNT,
         keep_executed_history_extra_periods: KEEP_EXECUTED_HISTORY_EXTRA_PERIODS,
     };
+    // the archived final states are deep history, not kept in pruned mode
+    let archive_interval = match pruned_history_periods() {
+        Some(history_periods) => {
+            info!(
+                "Pruned mode: keeping the final state and the last {} periods of history",
+                history_periods
+            );
+            0
+        }
+        None => SETTINGS.ledger.archive_interval,
+    };
     let final_state_config = FinalStateConfig {
         ledger_config: ledger_config.clone(),
         async_pool_config,
@@ -196,6 +234,8 @@ async fn launch(
         max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
         t0: T0,
         genesis_timestamp: *GENESIS_TIMESTAMP,
+        archive_interval,
+        archive_path: SETTINGS.ledger.archive_path.clone(),
     };
 
     // Start massa metrics
@@ -206,30 +246,40 @@ async fn launch(
         SETTINGS.metrics.tick_delay.to_duration(),
     );
 
-    // Remove current disk ledger if there is one and we don't want to restart from snapshot
-    // NOTE: this is temporary, since we cannot currently handle bootstrap from remaining ledger
+    // Remove current disk ledger if we don't want to restart from snapshot, unless it holds the
+    // state received by an interrupted bootstrap, which is then resumed, the state imported
+    // from a local snapshot, or a recent final state whose changes only are bootstrapped
+    // The hard drive module cache is kept, its modules being valid whatever the ledger
+    let mut disk_ledger = open_verified_disk_ledger().expect("could not open the disk ledger");
+    let resume_bootstrap = disk_ledger.get_bootstrap_cursor().is_some();
+    let differential_bootstrap = !resume_bootstrap
+        && args.bootstrap_snapshot.is_none()
+        && args.restart_from_snapshot_at_period.is_none()
+        && is_final_state_recent(&disk_ledger);
     if args.keep_ledger || args.restart_from_snapshot_at_period.is_some() {
         info!("Loading old ledger for next episode");
+    } else if resume_bootstrap {
+        info!("Loading the state received by the interrupted bootstrap");
+    } else if args.bootstrap_snapshot.is_some() {
+        info!("Loading the state imported from the local snapshot");
+    } else if differential_bootstrap {
+        info!("Loading old ledger to bootstrap its changes only");
     } else {
-        if SETTINGS.ledger.disk_ledger_path.exists() {
-            std::fs::remove_dir_all(SETTINGS.ledger.disk_ledger_path.clone())
-                .expect("disk ledger delete failed");
-        }
-        if SETTINGS.execution.hd_cache_path.exists() {
-            std::fs::remove_dir_all(SETTINGS.execution.hd_cache_path.clone())
-                .expect("disk hd cache delete failed");
-        }
+        drop(disk_ledger);
+        std::fs::remove_dir_all(SETTINGS.ledger.disk_ledger_path.clone())
+            .expect("disk ledger delete failed");
+        disk_ledger = open_disk_ledger().expect("could not open the disk ledger");
     }
 
-    let db_config = MassaDBConfig {
-        path: SETTINGS.ledger.disk_ledger_path.clone(),
-        max_history_length: SETTINGS.ledger.final_history_length,
-        max_final_state_elements_size: MAX_BOOTSTRAP_FINAL_STATE_PARTS_SIZE.try_into().unwrap(),
-        max_versioning_elements_size: MAX_BOOTSTRAP_VERSIONING_ELEMENTS_SIZE.try_into().unwrap(),
-        thread_count: THREAD_COUNT,
-    };
+    log_rocksdb_options(&disk_ledger);
+    let compaction_stopper = start_compaction_scheduler(
+        &disk_ledger,
+        SETTINGS.ledger.compaction.clone(),
+        massa_metrics.clone(),
+    )
+    .expect("could not schedule the disk ledger compactions");
     let db = Arc::new(RwLock::new(
-        Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
+        Box::new(disk_ledger) as Box<(dyn MassaDBController + 'static)>
     ));
 
     // Create final ledger
@@ -296,7 +346,11 @@ async fn launch(
                     Box::new(ledger),
                     selector_controller.clone(),
                     mip_store,
-                    true,
+                    // keep the state received by the interrupted bootstrap, imported from the
+                    // local snapshot or whose changes are bootstrapped
+                    !resume_bootstrap
+                        && args.bootstrap_snapshot.is_none()
+                        && !differential_bootstrap,
                 )
                 .expect("could not init final state")
             }
@@ -324,9 +378,14 @@ async fn launch(
         keep_ledger: args.keep_ledger,
         max_listeners_per_peer: MAX_LISTENERS_PER_PEER as u32,
         max_simultaneous_bootstraps: SETTINGS.bootstrap.max_simultaneous_bootstraps,
+        max_simultaneous_bootstraps_per_ip: SETTINGS.bootstrap.max_simultaneous_bootstraps_per_ip,
+        max_queued_bootstraps: SETTINGS.bootstrap.max_queued_bootstraps,
+        server_rate_limit: SETTINGS.bootstrap.server_rate_limit,
         per_ip_min_interval: SETTINGS.bootstrap.per_ip_min_interval,
         ip_list_max_size: SETTINGS.bootstrap.ip_list_max_size,
         rate_limit: SETTINGS.bootstrap.rate_limit,
+        parallel_bootstrap_servers: SETTINGS.bootstrap.parallel_bootstrap_servers,
+        differential_bootstrap,
         max_datastore_key_length: MAX_DATASTORE_KEY_LENGTH,
         randomness_size_bytes: BOOTSTRAP_RANDOMNESS_SIZE_BYTES,
         thread_count: THREAD_COUNT,
@@ -360,23 +419,34 @@ async fn launch(
         chain_id: *CHAINID,
     };
 
-    let bootstrap_state = match get_state(
-        &bootstrap_config,
-        final_state.clone(),
-        DefaultConnector,
-        *VERSION,
-        *GENESIS_TIMESTAMP,
-        *END_TIMESTAMP,
-        args.restart_from_snapshot_at_period,
-        sig_int_toggled.clone(),
-        massa_metrics.clone(),
-    ) {
-        Ok(vals) => vals,
-        Err(BootstrapError::Interrupted(msg)) => {
-            info!("{}", msg);
-            process::exit(0);
+    let bootstrap_state = if args.bootstrap_snapshot.is_some() {
+        // the final state was imported from the local snapshot, do not bootstrap
+        GlobalBootstrapState {
+            final_state: final_state.clone(),
+            graph: None,
+            peers: None,
+        }
+    } else {
+        match get_state(
+            &bootstrap_config,
+            final_state.clone(),
+            DefaultConnector {
+                proxy: SETTINGS.protocol.proxy.clone(),
+            },
+            *VERSION,
+            *GENESIS_TIMESTAMP,
+            *END_TIMESTAMP,
+            args.restart_from_snapshot_at_period,
+            sig_int_toggled.clone(),
+            massa_metrics.clone(),
+        ) {
+            Ok(vals) => vals,
+            Err(BootstrapError::Interrupted(msg)) => {
+                info!("{}", msg);
+                process::exit(0);
+            }
+            Err(err) => panic!("critical error detected in the bootstrap process: {}", err),
         }
-        Err(err) => panic!("critical error detected in the bootstrap process: {}", err),
     };
 
     if !final_state.read().is_db_valid() {
@@ -452,7 +522,14 @@ async fn launch(
     // launch execution module
     let execution_config = ExecutionConfig {
         max_final_events: SETTINGS.execution.max_final_events,
+        max_events_per_slot: SETTINGS.execution.max_events_per_slot,
+        event_overflow_policy: SETTINGS.execution.event_overflow_policy,
         readonly_queue_length: SETTINGS.execution.readonly_queue_length,
+        max_concurrent_readonly_requests: SETTINGS.execution.max_concurrent_readonly_requests,
+        max_concurrent_readonly_requests_per_caller: SETTINGS
+            .execution
+            .max_concurrent_readonly_requests_per_caller,
+        readonly_cache_size: SETTINGS.execution.readonly_cache_size,
         cursor_delay: SETTINGS.execution.cursor_delay,
         max_async_gas: MAX_ASYNC_GAS,
         async_msg_cst_gas_cost: ASYNC_MSG_CST_GAS_COST,
@@ -473,6 +550,7 @@ async fn launch(
         storage_costs_constants,
         max_read_only_gas: SETTINGS.execution.max_read_only_gas,
         gas_costs: gas_costs.clone(),
+        abi_gas_costs_file: SETTINGS.execution.abi_gas_costs_file.clone(),
         base_operation_gas_cost: BASE_OPERATION_GAS_COST,
         last_start_period: final_state.read().get_last_start_period(),
         hd_cache_path: SETTINGS.execution.hd_cache_path.clone(),
@@ -485,6 +563,7 @@ async fn launch(
         broadcast_slot_execution_output_channel_capacity: SETTINGS
             .execution
             .broadcast_slot_execution_output_channel_capacity,
+        broadcast_serialized_output_format: SETTINGS.execution.broadcast_serialized_output_format,
         max_event_size: MAX_EVENT_DATA_SIZE,
         max_function_length: MAX_FUNCTION_NAME_LENGTH,
         max_parameter_length: MAX_PARAMETERS_SIZE,
@@ -497,6 +576,19 @@ async fn launch(
             .execution
             .broadcast_slot_execution_traces_channel_capacity,
         max_execution_traces_slot_limit: SETTINGS.execution.execution_traces_limit,
+        max_debug_sessions: SETTINGS.execution.max_debug_sessions,
+        state_diff_history_length: SETTINGS.execution.state_diff_history_length,
+        slot_stats_history_length: SETTINGS.execution.slot_stats_history_length,
+        operation_index_enabled: SETTINGS.execution.operation_index_enabled,
+        operation_index_path: SETTINGS.execution.operation_index_path.clone(),
+        operation_index_retention_periods: pruned_history_periods()
+            .unwrap_or(SETTINGS.execution.operation_index_retention_periods),
+        event_index_enabled: SETTINGS.execution.event_index_enabled,
+        event_index_path: SETTINGS.execution.event_index_path.clone(),
+        event_index_retention_periods: pruned_history_periods()
+            .unwrap_or(SETTINGS.execution.event_index_retention_periods),
+        dust_account_max_balance: DUST_ACCOUNT_MAX_BALANCE,
+        dust_pruning_scan_length: DUST_PRUNING_SCAN_LENGTH,
     };
 
     let execution_channels = ExecutionChannels {
@@ -504,6 +596,10 @@ async fn launch(
             execution_config.broadcast_slot_execution_output_channel_capacity,
         )
         .0,
+        slot_execution_serialized_output_sender: broadcast::channel(
+            execution_config.broadcast_slot_execution_output_channel_capacity,
+        )
+        .0,
         #[cfg(feature = "execution-trace")]
         slot_execution_traces_sender: broadcast::channel(
             execution_config.broadcast_slot_execution_traces_channel_capacity,
@@ -534,7 +630,13 @@ async fn launch(
         max_operations_per_block: MAX_OPERATIONS_PER_BLOCK,
         max_operation_pool_size: SETTINGS.pool.max_operation_pool_size,
         max_operation_pool_excess_items: SETTINGS.pool.max_operation_pool_excess_items,
+        operation_eviction_policy: SETTINGS.pool.operation_eviction_policy,
+        max_operations_per_address: SETTINGS.pool.max_operations_per_address,
+        max_sender_operation_count: SETTINGS.pool.max_sender_operation_count,
+        max_sender_operation_size: SETTINGS.pool.max_sender_operation_size,
         operation_pool_refresh_interval: SETTINGS.pool.operation_pool_refresh_interval,
+        operation_rebroadcast_interval: SETTINGS.pool.operation_rebroadcast_interval,
+        operation_rebroadcast_max_attempts: SETTINGS.pool.operation_rebroadcast_max_attempts,
         operation_max_future_start_delay: SETTINGS.pool.operation_max_future_start_delay,
         max_endorsements_pool_size_per_thread: SETTINGS.pool.max_endorsements_pool_size_per_thread,
         operations_channel_size: POOL_CONTROLLER_OPERATIONS_CHANNEL_SIZE,
@@ -551,32 +653,25 @@ async fn launch(
         denunciation_expire_periods: DENUNCIATION_EXPIRE_PERIODS,
         max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
         minimal_fees: SETTINGS.pool.minimal_fees,
+        chain_id: *CHAINID,
+        max_datastore_value_length: MAX_DATASTORE_VALUE_LENGTH,
+        max_function_name_length: MAX_FUNCTION_NAME_LENGTH,
+        max_parameters_size: MAX_PARAMETERS_SIZE,
+        max_op_datastore_entry_count: MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+        max_op_datastore_key_length: MAX_OPERATION_DATASTORE_KEY_LENGTH,
+        max_op_datastore_value_length: MAX_OPERATION_DATASTORE_VALUE_LENGTH,
         last_start_period: final_state.read().get_last_start_period(),
     };
 
-    let pool_channels = PoolChannels {
-        broadcasts: PoolBroadcasts {
-            endorsement_sender: broadcast::channel(
-                pool_config.broadcast_endorsements_channel_capacity,
-            )
-            .0,
-            operation_sender: broadcast::channel(pool_config.broadcast_operations_channel_capacity)
-                .0,
-        },
-        selector: selector_controller.clone(),
-        execution_controller: execution_controller.clone(),
-    };
-
-    let (pool_manager, pool_controller) = start_pool_controller(
-        pool_config,
-        &shared_storage,
-        pool_channels.clone(),
-        node_wallet.clone(),
-    );
-
     // launch protocol controller
     let mut listeners = HashMap::default();
     listeners.insert(SETTINGS.protocol.bind, TransportType::Tcp);
+    if let Some(quic_bind) = SETTINGS.protocol.quic_bind {
+        listeners.insert(quic_bind, TransportType::Quic);
+    }
+    for bind in &SETTINGS.protocol.additional_binds {
+        listeners.insert(*bind, TransportType::Tcp);
+    }
     let protocol_config = ProtocolConfig {
         thread_count: THREAD_COUNT,
         ask_block_timeout: SETTINGS.protocol.ask_block_timeout,
@@ -614,6 +709,8 @@ async fn launch(
         max_endorsements_per_message: MAX_ENDORSEMENTS_PER_MESSAGE as u64,
         max_denunciations_in_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
         initial_peers: SETTINGS.protocol.initial_peers_file.clone(),
+        ip_ban_list_file: SETTINGS.protocol.ip_ban_list_file.clone(),
+        asn_database_file: SETTINGS.protocol.asn_database_file.clone(),
         listeners,
         keypair_file: SETTINGS.protocol.keypair_file.clone(),
         max_blocks_kept_for_propagation: SETTINGS.protocol.max_blocks_kept_for_propagation,
@@ -652,6 +749,9 @@ async fn launch(
             as u128,
         try_connection_timer: SETTINGS.protocol.try_connection_timer,
         unban_everyone_timer: SETTINGS.protocol.unban_everyone_timer,
+        peer_score_half_life: SETTINGS.protocol.peer_score_half_life,
+        peer_score_throttle_threshold: SETTINGS.protocol.peer_score_throttle_threshold,
+        peer_score_ban_threshold: SETTINGS.protocol.peer_score_ban_threshold,
         max_in_connections: SETTINGS.protocol.max_in_connections,
         timeout_connection: SETTINGS.protocol.timeout_connection,
         message_timeout: SETTINGS.protocol.message_timeout,
@@ -660,6 +760,7 @@ async fn launch(
             .protocol
             .routable_ip
             .or(SETTINGS.network.routable_ip),
+        additional_routable_ips: SETTINGS.protocol.additional_routable_ips.clone(),
         debug: false,
         peers_categories: SETTINGS.protocol.peers_categories.clone(),
         default_category_info: SETTINGS.protocol.default_category_info,
@@ -667,12 +768,43 @@ async fn launch(
         try_connection_timer_same_peer: SETTINGS.protocol.try_connection_timer_same_peer,
         test_oldest_peer_cooldown: SETTINGS.protocol.test_oldest_peer_cooldown,
         rate_limit: SETTINGS.protocol.rate_limit,
+        bandwidth_limits: SETTINGS.protocol.bandwidth_limits,
+        port_mapping: SETTINGS.protocol.port_mapping,
+        port_mapping_lease_duration: SETTINGS.protocol.port_mapping_lease_duration,
+        reachability_timeout: SETTINGS.protocol.reachability_timeout,
+        dns_seeds: SETTINGS.protocol.dns_seeds.clone(),
+        dns_seed_refresh_interval: SETTINGS.protocol.dns_seed_refresh_interval,
+        compact_block_relay: SETTINGS.protocol.compact_block_relay,
+        proxy: SETTINGS.protocol.proxy.clone(),
         chain_id: *CHAINID,
     };
 
     let (protocol_controller, protocol_channels) =
         create_protocol_controller(protocol_config.clone());
 
+    let pool_channels = PoolChannels {
+        broadcasts: PoolBroadcasts {
+            endorsement_sender: broadcast::channel(
+                pool_config.broadcast_endorsements_channel_capacity,
+            )
+            .0,
+            operation_sender: broadcast::channel(pool_config.broadcast_operations_channel_capacity)
+                .0,
+        },
+        selector: selector_controller.clone(),
+        execution_controller: execution_controller.clone(),
+        protocol: protocol_controller.clone(),
+    };
+
+    let (pool_manager, pool_controller) = start_pool_controller(
+        pool_config,
+        &shared_storage,
+        pool_channels.clone(),
+        node_wallet.clone(),
+        massa_metrics.clone(),
+        SETTINGS.pool.persistence_path.clone(),
+    );
+
     let consensus_config = ConsensusConfig {
         genesis_timestamp: *GENESIS_TIMESTAMP,
         end_timestamp: *END_TIMESTAMP,
@@ -680,6 +812,8 @@ async fn launch(
         t0: T0,
         genesis_key: GENESIS_KEY.clone(),
         max_discarded_blocks: SETTINGS.consensus.max_discarded_blocks,
+        discarded_blocks_retention_periods: SETTINGS.consensus.discarded_blocks_retention_periods,
+        max_discarded_blocks_size: SETTINGS.consensus.max_discarded_blocks_size,
         max_future_processing_blocks: SETTINGS.consensus.max_future_processing_blocks,
         max_dependency_blocks: SETTINGS.consensus.max_dependency_blocks,
         delta_f0: DELTA_F0,
@@ -700,6 +834,7 @@ async fn launch(
         broadcast_filled_blocks_channel_capacity: SETTINGS
             .consensus
             .broadcast_filled_blocks_channel_capacity,
+        broadcast_reorgs_channel_capacity: SETTINGS.consensus.broadcast_reorgs_channel_capacity,
         last_start_period: final_state.read().get_last_start_period(),
         force_keep_final_periods_without_ops: SETTINGS
             .consensus
@@ -715,6 +850,7 @@ async fn launch(
         pool_controller: pool_controller.clone(),
         controller_event_tx: consensus_event_sender,
         protocol_controller: protocol_controller.clone(),
+        mip_store: mip_store.clone(),
         broadcasts: ConsensusBroadcasts {
             block_header_sender: broadcast::channel(
                 consensus_config.broadcast_blocks_headers_channel_capacity,
@@ -725,6 +861,7 @@ async fn launch(
                 consensus_config.broadcast_filled_blocks_channel_capacity,
             )
             .0,
+            reorg_sender: broadcast::channel(consensus_config.broadcast_reorgs_channel_capacity).0,
         },
     };
 
@@ -808,7 +945,7 @@ async fn launch(
         bind_public: SETTINGS.api.bind_public,
         bind_api: SETTINGS.api.bind_api,
         draw_lookahead_period_count: SETTINGS.api.draw_lookahead_period_count,
-        max_arguments: SETTINGS.api.max_arguments,
+        limits: ApiLimits::new(SETTINGS.api.max_arguments),
         openrpc_spec_path: SETTINGS.api.openrpc_spec_path.clone(),
         bootstrap_whitelist_path: SETTINGS.bootstrap.bootstrap_whitelist_path.clone(),
         bootstrap_blacklist_path: SETTINGS.bootstrap.bootstrap_blacklist_path.clone(),
@@ -818,7 +955,14 @@ async fn launch(
         max_subscriptions_per_connection: SETTINGS.api.max_subscriptions_per_connection,
         max_log_length: SETTINGS.api.max_log_length,
         allow_hosts: SETTINGS.api.allow_hosts.clone(),
+        cors_public: SETTINGS.api.cors_public.clone(),
+        cors_private: SETTINGS.api.cors_private.clone(),
+        cors_api: SETTINGS.api.cors_api.clone(),
+        ipc_public: SETTINGS.api.ipc_public.clone(),
+        ipc_private: SETTINGS.api.ipc_private.clone(),
+        ipc_api: SETTINGS.api.ipc_api.clone(),
         batch_request_limit: SETTINGS.api.batch_request_limit,
+        batch_request_concurrency: SETTINGS.api.batch_request_concurrency,
         ping_interval: SETTINGS.api.ping_interval,
         enable_http: SETTINGS.api.enable_http,
         enable_ws: SETTINGS.api.enable_ws,
@@ -827,6 +971,7 @@ async fn launch(
         max_op_datastore_key_length: MAX_OPERATION_DATASTORE_KEY_LENGTH,
         max_op_datastore_value_length: MAX_OPERATION_DATASTORE_VALUE_LENGTH,
         max_gas_per_block: MAX_GAS_PER_BLOCK,
+        max_operations_per_block: MAX_OPERATIONS_PER_BLOCK,
         base_operation_gas_cost: BASE_OPERATION_GAS_COST,
         sp_compilation_cost: gas_costs.sp_compilation_cost,
         max_function_name_length: MAX_FUNCTION_NAME_LENGTH,
@@ -839,6 +984,7 @@ async fn launch(
         last_start_period: final_state.read().get_last_start_period(),
         chain_id: *CHAINID,
         minimal_fees: SETTINGS.pool.minimal_fees,
+        pruned_history_periods: pruned_history_periods(),
     };
 
     // spawn Massa API
@@ -846,6 +992,7 @@ async fn launch(
         consensus_controller.clone(),
         consensus_channels.broadcasts.clone(),
         execution_controller.clone(),
+        execution_channels.clone(),
         pool_channels.broadcasts.clone(),
         api_config.clone(),
         *VERSION,
@@ -891,6 +1038,12 @@ async fn launch(
             keypair_factory: KeyPairFactory {
                 mip_store: mip_store.clone(),
             },
+            stream_limiter: StreamLimiter::new(grpc_public_config.max_streams_per_ip),
+            event_journals: EventJournals::new(
+                grpc_public_config.event_replay_buffer_size,
+                grpc_public_config.max_channel_size,
+            ),
+            massa_metrics: massa_metrics.clone(),
         };
 
         // Spawn gRPC PUBLIC API
@@ -932,6 +1085,7 @@ async fn launch(
             stop_cv: sig_int_toggled.clone(),
             node_wallet: node_wallet.clone(),
             bs_white_black_list,
+            massa_metrics: massa_metrics.clone(),
         };
 
         // Spawn gRPC PRIVATE API
@@ -960,12 +1114,27 @@ async fn launch(
     );
 
     // spawn private API
+    let node_config_controller = NodeConfigControllerImpl::new(
+        node_config,
+        log_filter_handle,
+        pool_controller.clone(),
+        protocol_controller.clone(),
+        api_config.limits.clone(),
+        bootstrap_manager
+            .as_ref()
+            .map(|manager| manager.rate_limits.clone()),
+    );
+    let storage_info = Arc::new(RwLock::new(StorageInfo::default()));
     let api_private = API::<Private>::new(
         protocol_controller.clone(),
         execution_controller.clone(),
         api_config.clone(),
         sig_int_toggled,
         node_wallet,
+        Box::new(node_config_controller.clone()),
+        storage_info.clone(),
+        consensus_controller.clone(),
+        shutdown_grace_period,
     );
     let api_private_handle = api_private
         .serve(&SETTINGS.api.bind_private, &api_config)
@@ -999,6 +1168,43 @@ async fn launch(
         api_config.bind_public
     );
 
+    // measure the disk usage of the subsystems storing data on the disk
+    let mut storage_subsystems = vec![
+        (
+            "ledger".to_string(),
+            SETTINGS.ledger.disk_ledger_path.clone(),
+        ),
+        (
+            "module_cache".to_string(),
+            SETTINGS.execution.hd_cache_path.clone(),
+        ),
+    ];
+    if archive_interval > 0 {
+        storage_subsystems.push((
+            "ledger_archive".to_string(),
+            SETTINGS.ledger.archive_path.clone(),
+        ));
+    }
+    if SETTINGS.execution.operation_index_enabled {
+        storage_subsystems.push((
+            "operation_index".to_string(),
+            SETTINGS.execution.operation_index_path.clone(),
+        ));
+    }
+    if SETTINGS.execution.event_index_enabled {
+        storage_subsystems.push((
+            "event_index".to_string(),
+            SETTINGS.execution.event_index_path.clone(),
+        ));
+    }
+    let disk_usage_stopper = start_disk_usage_monitor(
+        SETTINGS.disk_usage.clone(),
+        storage_subsystems,
+        storage_info,
+        execution_controller.clone(),
+        massa_metrics.clone(),
+    );
+
     let massa_survey_stopper = MassaSurvey::run(
         SETTINGS.metrics.tick_delay.to_duration(),
         execution_controller,
@@ -1058,6 +1264,10 @@ async fn launch(
         grpc_public_handle,
         metrics_stopper,
         massa_survey_stopper,
+        compaction_stopper,
+        disk_usage_stopper,
+        node_config_controller,
+        db,
     )
 }
 
@@ -1081,16 +1291,27 @@ fn configure_grpc(
         generate_self_signed_certificates: settings.generate_self_signed_certificates,
         subject_alt_names: settings.subject_alt_names.clone(),
         bind: settings.bind,
+        uds_path: settings.uds_path.clone(),
+        uds_permissions: settings.uds_permissions,
         accept_compressed: settings.accept_compressed.clone(),
         send_compressed: settings.send_compressed.clone(),
         max_decoding_message_size: settings.max_decoding_message_size,
         max_encoding_message_size: settings.max_encoding_message_size,
+        max_decoding_message_size_per_method: settings.max_decoding_message_size_per_method.clone(),
+        max_encoding_message_size_per_method: settings.max_encoding_message_size_per_method.clone(),
         concurrency_limit_per_connection: settings.concurrency_limit_per_connection,
         timeout: settings.timeout.to_duration(),
+        method_timeouts: settings
+            .method_timeouts
+            .iter()
+            .map(|(method, timeout)| (method.clone(), timeout.to_duration()))
+            .collect(),
         initial_stream_window_size: settings.initial_stream_window_size,
         initial_connection_window_size: settings.initial_connection_window_size,
         max_concurrent_streams: settings.max_concurrent_streams,
         max_arguments: settings.max_arguments,
+        max_streams_per_ip: settings.max_streams_per_ip,
+        event_replay_buffer_size: settings.event_replay_buffer_size,
         tcp_keepalive: settings.tcp_keepalive.map(|t| t.to_duration()),
         tcp_nodelay: settings.tcp_nodelay,
         http2_keepalive_interval: settings.http2_keepalive_interval.map(|t| t.to_duration()),
@@ -1125,6 +1346,7 @@ fn configure_grpc(
         max_operation_ids_per_request: settings.max_operation_ids_per_request,
         max_filters_per_request: settings.max_filters_per_request,
         max_query_items_per_request: settings.max_query_items_per_request,
+        max_ledger_entries_per_request: settings.max_ledger_entries_per_request,
         certificate_authority_root_path: settings.certificate_authority_root_path.clone(),
         server_certificate_path: settings.server_certificate_path.clone(),
         server_private_key_path: settings.server_private_key_path.clone(),
@@ -1138,6 +1360,32 @@ fn configure_grpc(
     }
 }
 
+/// Stop a gRPC API, draining its connections until `deadline`
+async fn stop_grpc(
+    name: &str,
+    handle: Option<massa_grpc::server::StopHandle>,
+    deadline: tokio::time::Instant,
+) {
+    if let Some(handle) = handle {
+        handle.stop_and_drain(deadline).await;
+        info!("API | {} gRPC | stopped", name);
+    }
+}
+
+/// Stop a JSON-RPC API, waiting for its in-flight requests until `deadline`
+async fn stop_json_rpc(name: &str, handle: StopHandle, deadline: tokio::time::Instant) {
+    if tokio::time::timeout_at(deadline, handle.stop())
+        .await
+        .is_err()
+    {
+        warn!(
+            "API | {} JsonRPC | requests still running after the grace period",
+            name
+        );
+    }
+    info!("API | {} JsonRPC | stopped", name);
+}
+
 struct Managers {
     bootstrap_manager: Option<BootstrapManager>,
     consensus_manager: Box<dyn ConsensusManager>,
@@ -1167,6 +1415,10 @@ async fn stop(
     grpc_public_handle: Option<massa_grpc::server::StopHandle>,
     mut metrics_stopper: MetricsStopper,
     mut massa_survey_stopper: MassaSurveyStopper,
+    mut compaction_stopper: CompactionSchedulerStopper,
+    mut disk_usage_stopper: DiskUsageMonitorStopper,
+    db: ShareableMassaDBController,
+    grace_period: Duration,
 ) {
     // stop bootstrap
     if let Some(bootstrap_manager) = bootstrap_manager {
@@ -1177,29 +1429,16 @@ async fn stop(
 
     info!("Start stopping API's: gRPC(PUBLIC, PRIVATE), EXPERIMENTAL, PUBLIC, PRIVATE");
 
-    // stop Massa gRPC PUBLIC API
-    if let Some(handle) = grpc_public_handle {
-        handle.stop();
-    }
-    info!("API | PUBLIC gRPC | stopped");
-
-    // stop Massa gRPC PRIVATE API
-    if let Some(handle) = grpc_private_handle {
-        handle.stop();
-    }
-    info!("API | PRIVATE gRPC | stopped");
-
-    // stop Massa API
-    api_handle.stop().await;
-    info!("API | EXPERIMENTAL JsonRPC | stopped");
-
-    // stop public API
-    api_public_handle.stop().await;
-    info!("API | PUBLIC JsonRPC | stopped");
-
-    // stop private API
-    api_private_handle.stop().await;
-    info!("API | PRIVATE JsonRPC | stopped");
+    // the APIs stop accepting new requests together, then their in-flight requests and streams
+    // are drained until the end of the grace period
+    let deadline = tokio::time::Instant::now() + grace_period;
+    tokio::join!(
+        stop_grpc("PUBLIC", grpc_public_handle, deadline),
+        stop_grpc("PRIVATE", grpc_private_handle, deadline),
+        stop_json_rpc("EXPERIMENTAL", api_handle, deadline),
+        stop_json_rpc("PUBLIC", api_public_handle, deadline),
+        stop_json_rpc("PRIVATE", api_private_handle, deadline),
+    );
 
     // stop metrics
     metrics_stopper.stop();
@@ -1207,6 +1446,12 @@ async fn stop(
     // stop massa survey thread
     massa_survey_stopper.stop();
 
+    // stop the scheduled db compactions
+    compaction_stopper.stop();
+
+    // stop the disk usage accounting
+    disk_usage_stopper.stop();
+
     // stop factory
     factory_manager.stop();
 
@@ -1225,6 +1470,13 @@ async fn stop(
     // stop selector controller
     selector_manager.stop();
 
+    // write the final state to disk, so that the next start does not replay the write-ahead log
+    if let Err(err) = db.read().flush() {
+        warn!("could not flush the final state to disk: {}", err);
+    } else {
+        info!("final state flushed to disk");
+    }
+
     // stop pool controller
     // TODO
     //let protocol_pool_event_receiver = pool_manager.stop().await.expect("pool shutdown failed");
@@ -1246,6 +1498,22 @@ struct Args {
     #[arg(long = "restart-from-snapshot-at-period")]
     restart_from_snapshot_at_period: Option<u64>,
 
+    /// Write the final state of the disk ledger to a snapshot file, and exit
+    #[arg(long = "export-snapshot")]
+    export_snapshot: Option<PathBuf>,
+
+    /// Replace the disk ledger by the final state of a snapshot file, and exit
+    #[arg(long = "import-snapshot")]
+    import_snapshot: Option<PathBuf>,
+
+    /// Start from the final state of a snapshot file instead of bootstrapping from the network
+    #[arg(long = "bootstrap-snapshot", requires = "bootstrap_snapshot_hash")]
+    bootstrap_snapshot: Option<PathBuf>,
+
+    /// Trusted hash of the final state of the snapshot given by `--bootstrap-snapshot`
+    #[arg(long = "bootstrap-snapshot-hash")]
+    bootstrap_snapshot_hash: Option<String>,
+
     #[cfg(feature = "op_spammer")]
     /// number of operations
     #[arg(
@@ -1268,6 +1536,72 @@ struct Args {
     dl_interval: u64,
 }
 
+/// Number of periods of history kept in pruned mode, none when the node keeps its whole history
+fn pruned_history_periods() -> Option<u64> {
+    SETTINGS
+        .pruning
+        .enabled
+        .then_some(SETTINGS.pruning.history_periods)
+}
+
+/// Configuration of the database of the final state
+fn disk_ledger_config() -> MassaDBConfig {
+    MassaDBConfig {
+        path: SETTINGS.ledger.disk_ledger_path.clone(),
+        max_history_length: SETTINGS.ledger.final_history_length,
+        max_final_state_elements_size: MAX_BOOTSTRAP_FINAL_STATE_PARTS_SIZE.try_into().unwrap(),
+        max_versioning_elements_size: MAX_BOOTSTRAP_VERSIONING_ELEMENTS_SIZE.try_into().unwrap(),
+        thread_count: THREAD_COUNT,
+    }
+}
+
+/// Open the disk ledger with the configured RocksDB options
+fn open_disk_ledger() -> Result<MassaDB, MassaDBError> {
+    MassaDB::new_with_rocksdb_config(disk_ledger_config(), &SETTINGS.ledger.rocksdb)
+}
+
+/// Whether the final state of the disk ledger was left less than
+/// `max_differential_bootstrap_cycles` cycles ago, so that only its changes are bootstrapped
+fn is_final_state_recent(disk_ledger: &MassaDB) -> bool {
+    let Ok(slot) = disk_ledger.get_change_id() else {
+        return false;
+    };
+    let current_slot =
+        get_latest_block_slot_at_timestamp(THREAD_COUNT, T0, *GENESIS_TIMESTAMP, MassaTime::now());
+    match current_slot {
+        Ok(Some(current_slot)) => {
+            current_slot
+                .get_cycle(PERIODS_PER_CYCLE)
+                .saturating_sub(slot.get_cycle(PERIODS_PER_CYCLE))
+                < SETTINGS.bootstrap.max_differential_bootstrap_cycles
+        }
+        _ => false,
+    }
+}
+
+/// Log the effective RocksDB options of the disk ledger
+fn log_rocksdb_options(db: &MassaDB) {
+    let rocksdb_config = &SETTINGS.ledger.rocksdb;
+    if let Some((usage, capacity)) = db.get_block_cache_usage() {
+        info!(
+            "Disk ledger block cache: {} bytes used out of {}",
+            usage, capacity
+        );
+    }
+    info!(
+        "Disk ledger max open files: {}",
+        rocksdb_config.max_open_files
+    );
+    for cf in [STATE_CF, METADATA_CF, VERSIONING_CF] {
+        info!(
+            "Disk ledger column family {}: write buffer size {} bytes, compression {:?}",
+            cf,
+            rocksdb_config.get_write_buffer_size(cf),
+            rocksdb_config.get_compression(cf)
+        );
+    }
+}
+
 /// Load wallet, asking for passwords if necessary
 fn load_wallet(
     password: Option<String>,
@@ -1315,24 +1649,8 @@ fn main() -> anyhow::Result<()> {
 
 async fn run(args: Args) -> anyhow::Result<()> {
     let mut cur_args = args;
-    use tracing_subscriber::prelude::*;
-    // spawn the console server in the background, returning a `Layer`:
-    let tracing_layer = tracing_subscriber::fmt::layer()
-        .with_filter(match SETTINGS.logging.level {
-            4 => LevelFilter::TRACE,
-            3 => LevelFilter::DEBUG,
-            2 => LevelFilter::INFO,
-            1 => LevelFilter::WARN,
-            _ => LevelFilter::ERROR,
-        })
-        .with_filter(filter_fn(|metadata| {
-            metadata.target().starts_with("massa") // ignore non-massa logs
-        }));
-    // build a `Subscriber` by combining layers with a `tracing_subscriber::Registry`:
-    tracing_subscriber::registry()
-        // add the console layer to the subscriber or default layers...
-        .with(tracing_layer)
-        .init();
+    // the log levels can be changed at runtime by reloading the configuration
+    let log_filter_handle = init_logging(&SETTINGS.logging)?;
 
     // Setup panic handlers,
     // and when a panic occurs,
@@ -1346,6 +1664,50 @@ async fn run(args: Args) -> anyhow::Result<()> {
 
     info!("Node version : {}", *VERSION);
 
+    // export or import a snapshot of the final state, without starting the node
+    if let Some(path) = &cur_args.export_snapshot {
+        let db = open_disk_ledger()?;
+        let (slot, hash) = export_snapshot(&db, path)?;
+        info!(
+            "Final state of slot {} exported to {}, with hash {}",
+            slot,
+            path.display(),
+            hash
+        );
+        return Ok(());
+    }
+    if let Some(path) = &cur_args.import_snapshot {
+        if SETTINGS.ledger.disk_ledger_path.exists() {
+            std::fs::remove_dir_all(&SETTINGS.ledger.disk_ledger_path)?;
+        }
+        let mut db = open_disk_ledger()?;
+        let slot = import_snapshot(&mut db, path)?;
+        info!(
+            "Final state of slot {} imported from {}, the node can now be restarted from it",
+            slot,
+            path.display()
+        );
+        return Ok(());
+    }
+    if let Some(path) = &cur_args.bootstrap_snapshot {
+        // check the hash of the snapshot before replacing the disk ledger
+        let trusted_hash = cur_args
+            .bootstrap_snapshot_hash
+            .as_deref()
+            .unwrap_or_default();
+        let slot = check_snapshot_hash(path, trusted_hash)?;
+        if SETTINGS.ledger.disk_ledger_path.exists() {
+            std::fs::remove_dir_all(&SETTINGS.ledger.disk_ledger_path)?;
+        }
+        let mut db = open_disk_ledger()?;
+        import_snapshot(&mut db, path)?;
+        info!(
+            "Final state of slot {} imported from {}, starting from it without bootstrap",
+            slot,
+            path.display()
+        );
+    }
+
     // load or create wallet, asking for password if necessary
     let node_wallet = load_wallet(
         cur_args.password.clone(),
@@ -1353,6 +1715,12 @@ async fn run(args: Args) -> anyhow::Result<()> {
         *CHAINID,
     )?;
 
+    // effective configuration, updated when the configuration is reloaded
+    let node_config = Arc::new(RwLock::new(read_node_config()?));
+
+    // time given to the API requests to finish when stopping, can be set by the `shutdown` method
+    let shutdown_grace_period = Arc::new(RwLock::new(SETTINGS.api.shutdown_grace_period));
+
     // interrupt signal listener
     let sig_int_toggled = Arc::new((Mutex::new(false), Condvar::new()));
 
@@ -1366,6 +1734,36 @@ async fn run(args: Args) -> anyhow::Result<()> {
     })
     .expect("Error setting Ctrl-C handler");
 
+    // configuration reload signal listener
+    let reload_requested = Arc::new(AtomicBool::new(false));
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut hangup = signal(SignalKind::hangup()).expect("Error setting SIGHUP handler");
+        let reload_requested = Arc::clone(&reload_requested);
+        tokio::spawn(async move {
+            while hangup.recv().await.is_some() {
+                reload_requested.store(true, Ordering::Relaxed);
+            }
+        });
+    }
+
+    // readiness and liveness of the node, for the service managers
+    let node_health = Arc::new(NodeHealth::new(SETTINGS.health.liveness_timeout));
+    let health_server_stopper = match SETTINGS.health.enabled {
+        true => Some(start_health_server(
+            SETTINGS.health.bind,
+            node_health.clone(),
+        )?),
+        false => None,
+    };
+    // notify systemd that the node is alive, including while it bootstraps again
+    if let Some(watchdog_period) = sd_notify::watchdog_period() {
+        let node_health = node_health.clone();
+        sd_notify::start_watchdog(watchdog_period, move || node_health.is_live());
+    }
+
     #[cfg(feature = "resync_check")]
     let mut resync_check = Some(std::time::Instant::now() + std::time::Duration::from_secs(10));
 
@@ -1386,11 +1784,27 @@ async fn run(args: Args) -> anyhow::Result<()> {
             grpc_public_handle,
             metrics_stopper,
             massa_survey_stopper,
-        ) = launch(&cur_args, node_wallet.clone(), Arc::clone(&sig_int_toggled)).await;
+            compaction_stopper,
+            disk_usage_stopper,
+            node_config_controller,
+            db,
+        ) = launch(
+            &cur_args,
+            node_wallet.clone(),
+            Arc::clone(&sig_int_toggled),
+            node_config.clone(),
+            log_filter_handle.clone(),
+            shutdown_grace_period.clone(),
+        )
+        .await;
+        node_health.set_ready(true);
+        sd_notify::notify(sd_notify::READY);
 
         // loop over messages
         let restart = loop {
             massa_trace!("massa-node.main.run.select", {});
+            node_health.heartbeat();
+
             match consensus_event_receiver.try_recv() {
                 Ok(evt) => match evt {
                     ConsensusEvent::NeedSync => {
@@ -1422,6 +1836,15 @@ async fn run(args: Args) -> anyhow::Result<()> {
                 info!("interrupt signal received");
                 break false;
             }
+            drop(wake);
+
+            // apply the reloadable settings of the configuration on SIGHUP
+            if reload_requested.swap(false, Ordering::Relaxed) {
+                info!("configuration reload signal received");
+                if let Err(err) = node_config_controller.reload_node_config() {
+                    warn!("could not reload the node configuration: {}", err);
+                }
+            }
 
             // Elements of the system that involve stopping and restarting should be checked by forcing a relaunch.
             // This check allows the system to start up as normal, wait 10s, then force a relaunch. If Things take too long
@@ -1435,6 +1858,10 @@ async fn run(args: Args) -> anyhow::Result<()> {
                 }
             }
         };
+        node_health.set_ready(false);
+        if !restart {
+            sd_notify::notify(sd_notify::STOPPING);
+        }
         stop(
             consensus_event_receiver,
             Managers {
@@ -1453,6 +1880,10 @@ async fn run(args: Args) -> anyhow::Result<()> {
             grpc_public_handle,
             metrics_stopper,
             massa_survey_stopper,
+            compaction_stopper,
+            disk_usage_stopper,
+            db,
+            shutdown_grace_period.read().to_duration(),
         )
         .await;
 
@@ -1461,6 +1892,10 @@ async fn run(args: Args) -> anyhow::Result<()> {
         }
         // If we restart because of a desync, then we do not want to restart from a snapshot
         cur_args.restart_from_snapshot_at_period = None;
+        cur_args.bootstrap_snapshot = None;
+    }
+    if let Some(mut stopper) = health_server_stopper {
+        stopper.stop().await;
     }
     Ok(())
 }
@@ -0,0 +1,344 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Runtime inspection and reload of the node configuration
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use massa_api_exports::{
+    config::ApiLimits,
+    error::ApiError,
+    node_config::{NodeConfigController, NodeConfigReload},
+};
+use massa_bootstrap::BootstrapRateLimits;
+use massa_models::config::try_build_massa_settings;
+use massa_pool_exports::PoolController;
+use massa_protocol_exports::ProtocolController;
+use parking_lot::RwLock;
+use serde_json::Value;
+use tracing::info;
+
+use crate::logging::{log_filter, LogFilterHandle};
+use crate::settings::Settings;
+
+/// Settings that are applied to the running node when the configuration is reloaded.
+/// The peer ban and allow lists are reloaded when their file (`protocol.ip_ban_list_file`) changes.
+const RELOADABLE_SETTINGS: [&str; 13] = [
+    "logging.level",
+    "api.max_arguments",
+    "pool.max_operation_pool_size",
+    "pool.max_operation_pool_excess_items",
+    "pool.max_endorsements_pool_size_per_thread",
+    "pool.operation_eviction_policy",
+    "pool.max_operations_per_address",
+    "protocol.bandwidth_limits.peer_upload",
+    "protocol.bandwidth_limits.peer_download",
+    "protocol.bandwidth_limits.global_upload",
+    "protocol.bandwidth_limits.global_download",
+    "bootstrap.server_rate_limit",
+    "bootstrap.rate_limit",
+];
+
+/// Tables whose settings are all applied to the running node when the configuration is reloaded
+const RELOADABLE_TABLES: [&str; 1] = ["logging.modules."];
+
+/// Whether a setting is applied to the running node when the configuration is reloaded
+fn is_reloadable(setting: &str) -> bool {
+    RELOADABLE_SETTINGS.contains(&setting)
+        || RELOADABLE_TABLES
+            .iter()
+            .any(|table| setting.starts_with(table))
+}
+
+/// Reads the merged configuration sources of the node
+pub fn read_node_config() -> Result<Value, ApiError> {
+    Ok(try_build_massa_settings("massa-node", "MASSA_NODE")?)
+}
+
+#[derive(Clone)]
+pub struct NodeConfigControllerImpl {
+    /// last configuration loaded, shared across node restarts
+    current_config: Arc<RwLock<Value>>,
+    /// handle to the log filter
+    log_filter_handle: LogFilterHandle,
+    /// link to the pool component
+    pool_controller: Box<dyn PoolController>,
+    /// link to the protocol component
+    protocol_controller: Box<dyn ProtocolController>,
+    /// limits shared by the API servers
+    api_limits: ApiLimits,
+    /// rate limits of the bootstrap server, if it runs
+    bootstrap_rate_limits: Option<Arc<BootstrapRateLimits>>,
+}
+
+impl NodeConfigControllerImpl {
+    pub fn new(
+        current_config: Arc<RwLock<Value>>,
+        log_filter_handle: LogFilterHandle,
+        pool_controller: Box<dyn PoolController>,
+        protocol_controller: Box<dyn ProtocolController>,
+        api_limits: ApiLimits,
+        bootstrap_rate_limits: Option<Arc<BootstrapRateLimits>>,
+    ) -> Self {
+        NodeConfigControllerImpl {
+            current_config,
+            log_filter_handle,
+            pool_controller,
+            protocol_controller,
+            api_limits,
+            bootstrap_rate_limits,
+        }
+    }
+
+    /// Apply the reloadable settings of `new_config` that differ from the current configuration
+    fn apply_node_config(&self, new_config: Value) -> Result<NodeConfigReload, ApiError> {
+        // make sure the new configuration is valid before applying anything
+        let new_settings: Settings = serde_json::from_value(new_config.clone())
+            .map_err(|err| ApiError::BadRequest(format!("invalid configuration: {}", err)))?;
+
+        let mut current_config = self.current_config.write();
+        let mut reload = NodeConfigReload::default();
+        for setting in changed_settings(&current_config, &new_config) {
+            if is_reloadable(&setting) {
+                reload.applied.push(setting);
+            } else {
+                reload.requires_restart.push(setting);
+            }
+        }
+
+        if reload
+            .applied
+            .iter()
+            .any(|setting| setting.starts_with("logging."))
+        {
+            self.log_filter_handle
+                .reload(log_filter(&new_settings.logging))
+                .map_err(|err| ApiError::InternalServerError(err.to_string()))?;
+        }
+        if reload
+            .applied
+            .iter()
+            .any(|setting| setting == "api.max_arguments")
+        {
+            self.api_limits
+                .set_max_arguments(new_settings.api.max_arguments);
+        }
+        if reload
+            .applied
+            .iter()
+            .any(|setting| setting.starts_with("pool."))
+        {
+            self.pool_controller.set_pool_size_limits(
+                new_settings.pool.max_operation_pool_size,
+                new_settings.pool.max_operation_pool_excess_items,
+                new_settings.pool.max_endorsements_pool_size_per_thread,
+            );
+            self.pool_controller.set_operation_eviction_policy(
+                new_settings.pool.operation_eviction_policy,
+                new_settings.pool.max_operations_per_address,
+            );
+        }
+        if reload
+            .applied
+            .iter()
+            .any(|setting| setting.starts_with("protocol.bandwidth_limits."))
+        {
+            self.protocol_controller
+                .set_bandwidth_limits(new_settings.protocol.bandwidth_limits)
+                .map_err(|err| ApiError::InternalServerError(err.to_string()))?;
+        }
+        if let Some(bootstrap_rate_limits) = &self.bootstrap_rate_limits {
+            if reload
+                .applied
+                .iter()
+                .any(|setting| setting.starts_with("bootstrap."))
+            {
+                bootstrap_rate_limits.set(
+                    new_settings.bootstrap.server_rate_limit,
+                    new_settings.bootstrap.rate_limit,
+                );
+            }
+        }
+
+        info!(
+            "node configuration reloaded, applied: {:?}, requires restart: {:?}",
+            reload.applied, reload.requires_restart
+        );
+        *current_config = new_config;
+        Ok(reload)
+    }
+}
+
+impl NodeConfigController for NodeConfigControllerImpl {
+    fn get_node_config(&self) -> Result<Value, ApiError> {
+        Ok(self.current_config.read().clone())
+    }
+
+    fn reload_node_config(&self) -> Result<NodeConfigReload, ApiError> {
+        self.apply_node_config(read_node_config()?)
+    }
+
+    fn clone_box(&self) -> Box<dyn NodeConfigController> {
+        Box::new(self.clone())
+    }
+}
+
+/// Returns the dotted paths of the settings that differ between two configurations
+fn changed_settings(old: &Value, new: &Value) -> Vec<String> {
+    let mut old_settings = BTreeMap::new();
+    flatten_settings("", old, &mut old_settings);
+    let mut new_settings = BTreeMap::new();
+    flatten_settings("", new, &mut new_settings);
+
+    let mut changed: Vec<String> = new_settings
+        .iter()
+        .filter(|(path, value)| old_settings.get(*path) != Some(*value))
+        .map(|(path, _)| path.clone())
+        .collect();
+    changed.extend(
+        old_settings
+            .keys()
+            .filter(|path| !new_settings.contains_key(*path))
+            .cloned(),
+    );
+    changed.sort();
+    changed
+}
+
+fn flatten_settings(prefix: &str, value: &Value, out: &mut BTreeMap<String, Value>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten_settings(&path, value, out);
+            }
+        }
+        _ => {
+            out.insert(prefix.to_string(), value.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_pool_exports::MockPoolControllerWrapper;
+    use massa_protocol_exports::MockProtocolControllerWrapper;
+    use serde_json::json;
+    use tracing_subscriber::{reload, Registry};
+
+    /// Controller of the node configuration read from `base_config`, with the bootstrap rate
+    /// limits it updates
+    fn node_config_controller(
+        pool_controller: MockPoolControllerWrapper,
+        protocol_controller: MockProtocolControllerWrapper,
+    ) -> (NodeConfigControllerImpl, Arc<BootstrapRateLimits>) {
+        let current_config = read_node_config().unwrap();
+        let settings: Settings = serde_json::from_value(current_config.clone()).unwrap();
+        // the logging settings are not changed by the tests so the filter is never reloaded
+        let (_, log_filter_handle) =
+            reload::Layer::<_, Registry>::new(log_filter(&settings.logging));
+        let bootstrap_rate_limits = Arc::new(BootstrapRateLimits::default());
+        let controller = NodeConfigControllerImpl::new(
+            Arc::new(RwLock::new(current_config)),
+            log_filter_handle,
+            Box::new(pool_controller),
+            Box::new(protocol_controller),
+            ApiLimits::new(settings.api.max_arguments),
+            Some(bootstrap_rate_limits.clone()),
+        );
+        (controller, bootstrap_rate_limits)
+    }
+
+    #[test]
+    fn test_reload_changed_config() {
+        let mut pool_controller = MockPoolControllerWrapper::new();
+        pool_controller.set_expectations(|pool_controller| {
+            pool_controller
+                .expect_set_pool_size_limits()
+                .withf(|max_operation_pool_size, _, _| *max_operation_pool_size == 1000)
+                .times(1)
+                .return_const(());
+            pool_controller
+                .expect_set_operation_eviction_policy()
+                .times(1)
+                .return_const(());
+        });
+        let (controller, bootstrap_rate_limits) =
+            node_config_controller(pool_controller, MockProtocolControllerWrapper::new());
+
+        let mut new_config = controller.get_node_config().unwrap();
+        new_config["pool"]["max_operation_pool_size"] = json!(1000);
+        new_config["bootstrap"]["server_rate_limit"] = json!(1_048_576);
+        new_config["bootstrap"]["rate_limit"] = json!(65_536);
+        new_config["api"]["bind_public"] = json!("0.0.0.0:33336");
+        let reload = controller.apply_node_config(new_config.clone()).unwrap();
+
+        assert_eq!(
+            reload.applied,
+            vec![
+                "bootstrap.rate_limit",
+                "bootstrap.server_rate_limit",
+                "pool.max_operation_pool_size",
+            ]
+        );
+        assert_eq!(reload.requires_restart, vec!["api.bind_public"]);
+        assert_eq!(bootstrap_rate_limits.server_rate_limit(), 1_048_576);
+        assert_eq!(bootstrap_rate_limits.connection_rate_limit(), 65_536);
+        // the new configuration is the current one, even for the settings requiring a restart
+        assert_eq!(controller.get_node_config().unwrap(), new_config);
+    }
+
+    #[test]
+    fn test_reload_api_and_bandwidth_limits() {
+        let mut protocol_controller = MockProtocolControllerWrapper::new();
+        protocol_controller.set_expectations(|protocol_controller| {
+            protocol_controller
+                .expect_set_bandwidth_limits()
+                .withf(|limits| limits.peer_upload == 65_536 && limits.global_download == 0)
+                .times(1)
+                .returning(|_| Ok(()));
+        });
+        let (controller, _) =
+            node_config_controller(MockPoolControllerWrapper::new(), protocol_controller);
+        // the API servers share the limits of the controller
+        let api_limits = controller.api_limits.clone();
+
+        let mut new_config = controller.get_node_config().unwrap();
+        new_config["api"]["max_arguments"] = json!(7);
+        new_config["protocol"]["bandwidth_limits"]["peer_upload"] = json!(65_536);
+        let reload = controller.apply_node_config(new_config).unwrap();
+
+        assert_eq!(
+            reload.applied,
+            vec!["api.max_arguments", "protocol.bandwidth_limits.peer_upload"]
+        );
+        assert!(reload.requires_restart.is_empty());
+        assert_eq!(api_limits.max_arguments(), 7);
+    }
+
+    #[test]
+    fn test_reload_invalid_config() {
+        // nothing is applied to the pool
+        let (controller, bootstrap_rate_limits) = node_config_controller(
+            MockPoolControllerWrapper::new(),
+            MockProtocolControllerWrapper::new(),
+        );
+        let current_config = controller.get_node_config().unwrap();
+
+        let mut new_config = current_config.clone();
+        new_config["bootstrap"]["server_rate_limit"] = json!(1_048_576);
+        new_config["pool"]["max_operation_pool_size"] = json!("not a size");
+        assert!(matches!(
+            controller.apply_node_config(new_config),
+            Err(ApiError::BadRequest(_))
+        ));
+
+        assert_eq!(bootstrap_rate_limits.server_rate_limit(), 0);
+        assert_eq!(controller.get_node_config().unwrap(), current_config);
+    }
+}
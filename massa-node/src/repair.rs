@@ -0,0 +1,246 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Verification of the disk ledger at startup, and its repair after a crash.
+//!
+//! A disk ledger that can not be opened, or whose final state hash does not match its entries,
+//! is replaced by the most recent consistent copy of its final state, among the backups kept in
+//! its directory and the archived final states. Only the changes since that copy are then
+//! bootstrapped, or the whole final state if the copy is too old.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context};
+use massa_db_exports::MassaDBConfig;
+use massa_db_worker::{verify_state_integrity, MassaDB};
+use massa_final_state::ARCHIVE_DIR_PREFIX;
+use massa_models::slot::Slot;
+use tracing::{info, warn};
+
+use crate::settings::SETTINGS;
+use crate::{disk_ledger_config, open_disk_ledger};
+
+/// prefix of the names of the directories of the backups kept in the disk ledger directory
+const BACKUP_DIR_PREFIX: &str = "backup_";
+
+/// Open the disk ledger, verifying the integrity of its final state and repairing it if needed
+pub fn open_verified_disk_ledger() -> anyhow::Result<MassaDB> {
+    let error = match open_disk_ledger() {
+        // the partial state of an interrupted bootstrap is completed by resuming it
+        Ok(db) if !SETTINGS.ledger.startup_verification || db.get_bootstrap_cursor().is_some() => {
+            return Ok(db);
+        }
+        Ok(db) => match verify_state_integrity(&db) {
+            Ok(slot) => {
+                info!("Disk ledger final state at slot {} verified", slot);
+                return Ok(db);
+            }
+            Err(err) => err,
+        },
+        Err(err) => err,
+    };
+    if !SETTINGS.ledger.auto_repair {
+        return Err(anyhow!(
+            "the disk ledger is corrupted: {}, enable `ledger.auto_repair` or delete {} to \
+             bootstrap again",
+            error,
+            SETTINGS.ledger.disk_ledger_path.display()
+        ));
+    }
+    warn!("the disk ledger is corrupted: {}, repairing it", error);
+    repair_disk_ledger(&disk_ledger_config(), &SETTINGS.ledger.archive_path)?;
+    Ok(open_disk_ledger()?)
+}
+
+/// Replace the disk ledger of `config` by the most recent consistent copy of its final state
+/// among its backups and the archives of `archive_path`, or remove it if there is none.
+/// The backups are kept in both cases.
+fn repair_disk_ledger(config: &MassaDBConfig, archive_path: &Path) -> anyhow::Result<()> {
+    let ledger_path = &config.path;
+    let mut copies = list_state_copies(ledger_path, BACKUP_DIR_PREFIX);
+    copies.extend(list_state_copies(archive_path, ARCHIVE_DIR_PREFIX));
+    copies.sort_by(|(slot_a, _), (slot_b, _)| slot_b.cmp(slot_a));
+
+    for (slot, path) in copies {
+        if let Err(err) = verify_state_copy(&path, config) {
+            warn!(
+                "skipping the final state copied in {}: {}",
+                path.display(),
+                err
+            );
+            continue;
+        }
+        // if the copy is interrupted, the disk ledger can not be opened at the next startup and
+        // is repaired again from the backups, which are kept
+        remove_db_files(ledger_path)?;
+        copy_db_files(&path, ledger_path)
+            .with_context(|| format!("could not copy the final state of {}", path.display()))?;
+        warn!(
+            "disk ledger restored to the final state at slot {} copied in {}",
+            slot,
+            path.display()
+        );
+        return Ok(());
+    }
+
+    warn!("no consistent copy of the final state found, bootstrapping the whole final state");
+    remove_db_files(ledger_path)?;
+    Ok(())
+}
+
+/// Remove the database of the disk ledger at `ledger_path`, keeping the directories of its backups
+fn remove_db_files(ledger_path: &Path) -> std::io::Result<()> {
+    if !ledger_path.exists() {
+        return Ok(());
+    }
+    for entry in std::fs::read_dir(ledger_path)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            std::fs::remove_file(entry.path())?;
+        } else if !entry
+            .file_name()
+            .to_str()
+            .is_some_and(|name| name.starts_with(BACKUP_DIR_PREFIX))
+        {
+            std::fs::remove_dir_all(entry.path())?;
+        }
+    }
+    Ok(())
+}
+
+/// Copies of the final state in the directories of `dir` named after `prefix` and their slot
+fn list_state_copies(dir: &Path, prefix: &str) -> Vec<(Slot, PathBuf)> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let name = path.file_name()?.to_str()?;
+            let (period, thread) = name.strip_prefix(prefix)?.split_once('_')?;
+            let slot = Slot::new(period.parse().ok()?, thread.parse().ok()?);
+            Some((slot, path))
+        })
+        .collect()
+}
+
+/// Check the integrity of the copy of the final state at `path`, without modifying it
+fn verify_state_copy(path: &Path, config: &MassaDBConfig) -> anyhow::Result<()> {
+    let db = MassaDB::new_read_only(MassaDBConfig {
+        path: path.to_path_buf(),
+        ..config.clone()
+    })?;
+    verify_state_integrity(&db)?;
+    Ok(())
+}
+
+/// Copy the files of the database at `from` to `to`. The table files are never modified by
+/// RocksDB, so they are hard linked when possible, the other files are copied.
+fn copy_db_files(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_file() {
+            continue;
+        }
+        let target = to.join(entry.file_name());
+        let is_table = entry.path().extension().is_some_and(|ext| ext == "sst");
+        if !is_table || std::fs::hard_link(entry.path(), &target).is_err() {
+            std::fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_db_exports::{DBBatch, MassaDBController};
+    use massa_models::config::THREAD_COUNT;
+    use tempfile::TempDir;
+
+    fn db_config(path: &Path) -> MassaDBConfig {
+        MassaDBConfig {
+            path: path.to_path_buf(),
+            max_history_length: 100,
+            max_final_state_elements_size: 100,
+            max_versioning_elements_size: 100,
+            thread_count: THREAD_COUNT,
+        }
+    }
+
+    /// Database at `path` with an entry written at each of `slots`, backed up after each of them
+    /// when `backup` is set
+    fn write_db(path: &Path, slots: &[Slot], backup: bool) {
+        std::fs::create_dir_all(path).unwrap();
+        let mut db = MassaDB::new(db_config(path));
+        for slot in slots {
+            let batch = DBBatch::from([(vec![slot.period as u8], Some(vec![slot.thread; 4]))]);
+            db.write_batch(batch, DBBatch::new(), Some(*slot));
+            if backup {
+                db.backup_db(*slot);
+            }
+        }
+    }
+
+    /// Slot of the final state of the disk ledger at `path`, once verified
+    fn verified_slot(path: &Path) -> Slot {
+        verify_state_integrity(&MassaDB::new(db_config(path))).unwrap()
+    }
+
+    /// Make the copy of the final state at `path` impossible to open
+    fn corrupt_copy(path: &Path) {
+        std::fs::remove_file(path.join("CURRENT")).unwrap();
+    }
+
+    #[test]
+    fn test_repair_from_backup() {
+        let dir = TempDir::new().unwrap();
+        let ledger_path = dir.path().join("ledger");
+        write_db(&ledger_path, &[Slot::new(1, 0), Slot::new(2, 0)], true);
+        corrupt_copy(&ledger_path.join("backup_2_0"));
+
+        repair_disk_ledger(&db_config(&ledger_path), &dir.path().join("archives")).unwrap();
+        assert_eq!(verified_slot(&ledger_path), Slot::new(1, 0));
+        // the backups were kept, including the one used and the corrupted one
+        assert!(ledger_path.join("backup_1_0").exists());
+        assert!(ledger_path.join("backup_2_0").exists());
+    }
+
+    #[test]
+    fn test_repair_from_archive() {
+        let dir = TempDir::new().unwrap();
+        let ledger_path = dir.path().join("ledger");
+        let archive_path = dir.path().join("archives");
+        write_db(&ledger_path, &[Slot::new(1, 0), Slot::new(3, 0)], true);
+        corrupt_copy(&ledger_path.join("backup_1_0"));
+        corrupt_copy(&ledger_path.join("backup_3_0"));
+        write_db(
+            &archive_path.join("archive_2_0"),
+            &[Slot::new(1, 0), Slot::new(2, 0)],
+            false,
+        );
+
+        repair_disk_ledger(&db_config(&ledger_path), &archive_path).unwrap();
+        assert_eq!(verified_slot(&ledger_path), Slot::new(2, 0));
+        assert!(ledger_path.join("backup_1_0").exists());
+        assert!(ledger_path.join("backup_3_0").exists());
+        // the archive is copied, not moved
+        assert!(archive_path.join("archive_2_0").join("CURRENT").exists());
+    }
+
+    #[test]
+    fn test_repair_without_consistent_copy() {
+        let dir = TempDir::new().unwrap();
+        let ledger_path = dir.path().join("ledger");
+        write_db(&ledger_path, &[Slot::new(1, 0)], true);
+        corrupt_copy(&ledger_path.join("backup_1_0"));
+
+        repair_disk_ledger(&db_config(&ledger_path), &dir.path().join("archives")).unwrap();
+        // only the backups are left, and the final state is bootstrapped from scratch
+        let entries: Vec<PathBuf> = std::fs::read_dir(&ledger_path)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        assert_eq!(entries, vec![ledger_path.join("backup_1_0")]);
+    }
+}
@@ -0,0 +1,196 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Notifications of the state of the node to systemd, following the `sd_notify` protocol.
+//!
+//! Each state is sent as a datagram to the Unix socket given by the `NOTIFY_SOCKET`
+//! environment variable. Without it, when the node is not started by systemd with
+//! `Type=notify`, the notifications are ignored.
+
+use std::time::Duration;
+
+use tracing::debug;
+
+/// The node is started and serving
+pub const READY: &str = "READY=1";
+/// The node is stopping
+pub const STOPPING: &str = "STOPPING=1";
+/// The node is alive, to be sent at least every watchdog period
+pub const WATCHDOG: &str = "WATCHDOG=1";
+
+/// Notify systemd of the state of the node, if the node is supervised by systemd
+pub fn notify(state: &str) {
+    #[cfg(unix)]
+    if let Some(path) = std::env::var_os("NOTIFY_SOCKET") {
+        if let Err(err) = send(&path, state) {
+            debug!("could not notify systemd of {}: {}", state, err);
+        }
+    }
+    #[cfg(not(unix))]
+    debug!(
+        "systemd notifications are not supported, ignoring {}",
+        state
+    );
+}
+
+/// Interval at which the node must notify systemd that it is alive, if the watchdog is enabled
+/// for the node with `WatchdogSec`
+pub fn watchdog_period() -> Option<Duration> {
+    if let Ok(pid) = std::env::var("WATCHDOG_PID") {
+        if pid.parse::<u32>().ok() != Some(std::process::id()) {
+            return None;
+        }
+    }
+    match std::env::var("WATCHDOG_USEC").ok()?.parse::<u64>() {
+        Ok(usec) if usec > 0 => Some(Duration::from_micros(usec)),
+        _ => None,
+    }
+}
+
+/// Start a thread notifying systemd that the node is alive twice per `watchdog_period`,
+/// as long as `is_live` holds.
+///
+/// The notifications do not depend on the main loop of the node, that does not run while the
+/// node stops and bootstraps again after a desynchronization.
+pub fn start_watchdog<F>(watchdog_period: Duration, is_live: F)
+where
+    F: Fn() -> bool + Send + 'static,
+{
+    std::thread::Builder::new()
+        .name("massa-sd-watchdog".to_string())
+        .spawn(move || loop {
+            if is_live() {
+                notify(WATCHDOG);
+            }
+            std::thread::sleep(watchdog_period / 2);
+        })
+        .expect("failed to spawn the systemd watchdog thread");
+}
+
+#[cfg(unix)]
+fn send(path: &std::ffi::OsStr, state: &str) -> std::io::Result<()> {
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::net::UnixDatagram;
+
+    let socket = UnixDatagram::unbound()?;
+    match path.as_bytes().strip_prefix(b"@") {
+        // abstract socket, only available on Linux
+        Some(name) => {
+            #[cfg(target_os = "linux")]
+            {
+                use std::os::linux::net::SocketAddrExt;
+
+                let address = std::os::unix::net::SocketAddr::from_abstract_name(name)?;
+                socket.send_to_addr(state.as_bytes(), &address)?;
+            }
+            #[cfg(not(target_os = "linux"))]
+            {
+                let _ = name;
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::Unsupported,
+                    "abstract sockets are only supported on Linux",
+                ));
+            }
+        }
+        None => {
+            socket.send_to(state.as_bytes(), path)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_send_to_socket_path() {
+        use std::os::unix::net::UnixDatagram;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("notify.sock");
+        let receiver = UnixDatagram::bind(&path).unwrap();
+        send(path.as_os_str(), READY).unwrap();
+        send(path.as_os_str(), WATCHDOG).unwrap();
+
+        let mut buf = [0u8; 64];
+        let len = receiver.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..len], READY.as_bytes());
+        let len = receiver.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..len], WATCHDOG.as_bytes());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_send_to_abstract_socket() {
+        use std::os::linux::net::SocketAddrExt;
+        use std::os::unix::net::{SocketAddr, UnixDatagram};
+
+        let name = format!("massa-node-test-notify-{}", std::process::id());
+        let address = SocketAddr::from_abstract_name(name.as_bytes()).unwrap();
+        let receiver = UnixDatagram::bind_addr(&address).unwrap();
+        send(std::ffi::OsStr::new(&format!("@{}", name)), STOPPING).unwrap();
+
+        let mut buf = [0u8; 64];
+        let len = receiver.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..len], STOPPING.as_bytes());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_watchdog_follows_liveness() {
+        use std::os::unix::net::UnixDatagram;
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("notify.sock");
+        let receiver = UnixDatagram::bind(&path).unwrap();
+        receiver
+            .set_read_timeout(Some(Duration::from_millis(500)))
+            .unwrap();
+        std::env::set_var("NOTIFY_SOCKET", &path);
+
+        let live = Arc::new(AtomicBool::new(true));
+        let is_live = live.clone();
+        start_watchdog(Duration::from_millis(20), move || {
+            is_live.load(Ordering::Relaxed)
+        });
+
+        let mut buf = [0u8; 64];
+        for _ in 0..3 {
+            let len = receiver.recv(&mut buf).unwrap();
+            assert_eq!(&buf[..len], WATCHDOG.as_bytes());
+        }
+
+        // no more notifications once the node is not live
+        live.store(false, Ordering::Relaxed);
+        std::thread::sleep(Duration::from_millis(50));
+        receiver.set_nonblocking(true).unwrap();
+        while receiver.recv(&mut buf).is_ok() {}
+        receiver.set_nonblocking(false).unwrap();
+        assert!(receiver.recv(&mut buf).is_err());
+        std::env::remove_var("NOTIFY_SOCKET");
+    }
+
+    #[test]
+    fn test_watchdog_period() {
+        std::env::remove_var("WATCHDOG_PID");
+        std::env::remove_var("WATCHDOG_USEC");
+        assert_eq!(watchdog_period(), None);
+
+        std::env::set_var("WATCHDOG_USEC", "30000000");
+        assert_eq!(watchdog_period(), Some(Duration::from_secs(30)));
+        // the watchdog is enabled for this process
+        std::env::set_var("WATCHDOG_PID", std::process::id().to_string());
+        assert_eq!(watchdog_period(), Some(Duration::from_secs(30)));
+        // the watchdog is enabled for another process
+        std::env::set_var("WATCHDOG_PID", (std::process::id() + 1).to_string());
+        assert_eq!(watchdog_period(), None);
+
+        std::env::remove_var("WATCHDOG_PID");
+        std::env::set_var("WATCHDOG_USEC", "0");
+        assert_eq!(watchdog_period(), None);
+        std::env::remove_var("WATCHDOG_USEC");
+    }
+}
@@ -3,9 +3,13 @@
 //! Build here the default node settings from the configuration file toml
 use std::{collections::HashMap, path::PathBuf};
 
+use massa_api_exports::config::{CorsConfig, IpcConfig};
 use massa_bootstrap::IpType;
+use massa_db_exports::{MassaDBCompactionConfig, RocksDBConfig};
+use massa_execution_exports::{EventOverflowPolicy, ExportFormat};
 use massa_models::{amount::Amount, config::build_massa_settings, node::NodeId};
-use massa_protocol_exports::PeerCategoryInfo;
+use massa_pool_exports::OperationEvictionPolicy;
+use massa_protocol_exports::{BandwidthLimits, PeerCategoryInfo, Socks5Proxy};
 use massa_time::MassaTime;
 use serde::Deserialize;
 use std::net::{IpAddr, SocketAddr};
@@ -17,12 +21,47 @@ lazy_static::lazy_static! {
 #[derive(Debug, Deserialize, Clone)]
 pub struct LoggingSettings {
     pub level: usize,
+    pub format: LogFormat,
+    /// levels overriding `level` for the modules whose path starts with the keys
+    #[serde(default)]
+    pub modules: HashMap<String, usize>,
+    pub file: LogFileSettings,
+}
+
+/// Format of the logs
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// human readable lines
+    Text,
+    /// one JSON object per line
+    Json,
+}
+
+/// Log file written in addition to the standard output, and its rotation
+#[derive(Debug, Deserialize, Clone)]
+pub struct LogFileSettings {
+    /// path of the log file, none to disable it
+    pub path: Option<PathBuf>,
+    /// size in bytes above which the log file is rotated (0 disables it)
+    pub max_size: u64,
+    /// time after which the log file is rotated (0 disables it)
+    pub rotation_interval: MassaTime,
+    /// number of rotated log files kept
+    pub max_files: usize,
+    /// whether the rotated log files are compressed with gzip
+    pub compress: bool,
 }
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct ExecutionSettings {
     pub max_final_events: usize,
+    pub max_events_per_slot: usize,
+    pub event_overflow_policy: EventOverflowPolicy,
     pub readonly_queue_length: usize,
+    pub max_concurrent_readonly_requests: usize,
+    pub max_concurrent_readonly_requests_per_caller: usize,
+    pub readonly_cache_size: usize,
     pub cursor_delay: MassaTime,
     pub stats_time_window_duration: MassaTime,
     pub max_read_only_gas: u64,
@@ -34,9 +73,22 @@ pub struct ExecutionSettings {
     pub snip_amount: usize,
     /// slot execution outputs channel capacity
     pub broadcast_slot_execution_output_channel_capacity: usize,
+    /// format in which the slot execution outputs are also broadcast, for external indexers
+    pub broadcast_serialized_output_format: Option<ExportFormat>,
     /// slot execution traces channel capacity
     pub broadcast_slot_execution_traces_channel_capacity: usize,
     pub execution_traces_limit: usize,
+    pub max_debug_sessions: usize,
+    pub state_diff_history_length: usize,
+    pub slot_stats_history_length: usize,
+    /// whether the final operations are indexed by emitter and recipient address
+    pub operation_index_enabled: bool,
+    pub operation_index_path: PathBuf,
+    pub operation_index_retention_periods: u64,
+    /// whether the final events are stored in a persistent index instead of in RAM
+    pub event_index_enabled: bool,
+    pub event_index_path: PathBuf,
+    pub event_index_retention_periods: u64,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -50,6 +102,12 @@ pub struct LedgerSettings {
     pub disk_ledger_path: PathBuf,
     pub final_history_length: usize,
     pub initial_deferred_credits_path: Option<PathBuf>,
+    pub archive_interval: u64,
+    pub archive_path: PathBuf,
+    pub startup_verification: bool,
+    pub auto_repair: bool,
+    pub rocksdb: RocksDBConfig,
+    pub compaction: MassaDBCompactionConfig,
 }
 
 /// Bootstrap configuration.
@@ -70,9 +128,16 @@ pub struct BootstrapSettings {
     pub max_clock_delta: MassaTime,
     pub cache_duration: MassaTime,
     pub max_simultaneous_bootstraps: u32,
+    pub max_simultaneous_bootstraps_per_ip: usize,
+    pub max_queued_bootstraps: usize,
+    pub server_rate_limit: u64,
     pub per_ip_min_interval: MassaTime,
     pub ip_list_max_size: usize,
     pub rate_limit: u64,
+    /// Number of servers from which the final state is downloaded in parallel
+    pub parallel_bootstrap_servers: usize,
+    /// Number of cycles offline under which only the changes of the final state are bootstrapped
+    pub max_differential_bootstrap_cycles: u64,
     /// Allocated time with which to manage the bootstrap process
     pub bootstrap_timeout: MassaTime,
 }
@@ -93,8 +158,20 @@ pub struct FactorySettings {
 pub struct PoolSettings {
     pub max_operation_pool_size: usize,
     pub max_operation_pool_excess_items: usize,
+    /// order in which the operations are evicted when the pool is full
+    pub operation_eviction_policy: OperationEvictionPolicy,
+    /// max number of operations kept in the pool per sender address (0 for no limit)
+    pub max_operations_per_address: usize,
+    /// max number of operations of a sender in the pool, beyond which its new ones are rejected
+    pub max_sender_operation_count: usize,
+    /// max total size of the operations of a sender in the pool (bytes)
+    pub max_sender_operation_size: usize,
     pub operation_max_future_start_delay: MassaTime,
     pub operation_pool_refresh_interval: MassaTime,
+    /// interval between two propagations of an operation submitted through the API
+    pub operation_rebroadcast_interval: MassaTime,
+    /// max number of propagations of an operation submitted through the API after the first one
+    pub operation_rebroadcast_max_attempts: u32,
     pub max_endorsements_pool_size_per_thread: usize,
     pub max_item_return_count: usize,
     /// endorsements channel capacity
@@ -103,6 +180,8 @@ pub struct PoolSettings {
     pub broadcast_operations_channel_capacity: usize,
     /// operations minimum fees for block creator
     pub minimal_fees: Amount,
+    /// file where the pool is written on shutdown and reloaded from on startup (none to disable)
+    pub persistence_path: Option<PathBuf>,
 }
 
 /// API and server configuration, read from a file configuration.
@@ -120,12 +199,20 @@ pub struct APISettings {
     pub max_subscriptions_per_connection: u32,
     pub max_log_length: u32,
     pub allow_hosts: Vec<String>,
+    pub cors_public: CorsConfig,
+    pub cors_private: CorsConfig,
+    pub cors_api: CorsConfig,
+    pub ipc_public: Option<IpcConfig>,
+    pub ipc_private: Option<IpcConfig>,
+    pub ipc_api: Option<IpcConfig>,
     pub batch_request_limit: u32,
+    pub batch_request_concurrency: usize,
     pub ping_interval: MassaTime,
     pub enable_http: bool,
     pub enable_ws: bool,
     // whether to broadcast for blocks, endorsement and operations
     pub enable_broadcast: bool,
+    pub shutdown_grace_period: MassaTime,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -144,6 +231,9 @@ pub struct Settings {
     pub grpc: GrpcApiSettings,
     pub metrics: MetricsSettings,
     pub versioning: VersioningSettings,
+    pub disk_usage: DiskUsageSettings,
+    pub health: HealthSettings,
+    pub pruning: PruningSettings,
 }
 
 /// Consensus configuration
@@ -152,6 +242,11 @@ pub struct Settings {
 pub struct ConsensusSettings {
     /// Maximum number of blocks allowed in discarded blocks.
     pub max_discarded_blocks: usize,
+    /// Number of periods before the latest final block of their thread during which
+    /// the discarded blocks are retained (0 for no limit).
+    pub discarded_blocks_retention_periods: u64,
+    /// Maximum estimated size in bytes of the retained discarded blocks (0 for no limit).
+    pub max_discarded_blocks_size: u64,
     /// Maximum number of blocks allowed in `FutureIncomingBlocks`.
     pub max_future_processing_blocks: usize,
     /// Maximum number of blocks allowed in `DependencyWaitingBlocks`.
@@ -170,6 +265,8 @@ pub struct ConsensusSettings {
     pub broadcast_blocks_channel_capacity: usize,
     /// filled blocks channel capacity
     pub broadcast_filled_blocks_channel_capacity: usize,
+    /// blockclique reorganizations channel capacity
+    pub broadcast_reorgs_channel_capacity: usize,
 }
 
 // TODO: Remove one date. Kept for retro compatibility.
@@ -240,12 +337,24 @@ pub struct ProtocolSettings {
     pub max_endorsements_propagation_time: MassaTime,
     /// Path for initial peers
     pub initial_peers_file: PathBuf,
+    /// Path of the file where banned IP ranges and ASNs are persisted
+    pub ip_ban_list_file: PathBuf,
+    /// Optional path of the database mapping ASNs to their IP ranges
+    pub asn_database_file: Option<PathBuf>,
     /// Keypair
     pub keypair_file: PathBuf,
     /// Ip we are bind to listen to
     pub bind: SocketAddr,
+    /// Optional address to listen to with the QUIC transport, alongside TCP on `bind`.
+    /// Peers announcing a QUIC listener are then reached through QUIC.
+    pub quic_bind: Option<SocketAddr>,
+    /// Other addresses to listen to with the TCP transport, for instance on other interfaces
+    /// or for the other IP family than `bind`
+    pub additional_binds: Vec<SocketAddr>,
     /// Ip seen by others. If none the bind ip is used
     pub routable_ip: Option<IpAddr>,
+    /// Other ips seen by others, typically the one of the other IP family than `routable_ip`
+    pub additional_routable_ips: Vec<IpAddr>,
     /// Time threshold to have a connection to a node
     pub connect_timeout: MassaTime,
     /// Number of tester threads
@@ -258,6 +367,12 @@ pub struct ProtocolSettings {
     pub try_connection_timer_same_peer: MassaTime,
     /// periodically unban every peer
     pub unban_everyone_timer: MassaTime,
+    /// time after which the score of a peer is halved
+    pub peer_score_half_life: MassaTime,
+    /// peers with a lower score are neither connected to nor advertised to the others
+    pub peer_score_throttle_threshold: f64,
+    /// peers with a lower score are banned
+    pub peer_score_ban_threshold: f64,
     /// Timeout connection
     pub timeout_connection: MassaTime,
     /// Message timeout
@@ -274,6 +389,22 @@ pub struct ProtocolSettings {
     pub test_oldest_peer_cooldown: MassaTime,
     /// Rate limitation to apply to the data stream (per second)
     pub rate_limit: u64,
+    /// Initial bandwidth limits, that can be changed through the private API
+    pub bandwidth_limits: BandwidthLimits,
+    /// Map the ports of the listeners on the router through UPnP or NAT-PMP
+    pub port_mapping: bool,
+    /// Duration of the port mappings
+    pub port_mapping_lease_duration: MassaTime,
+    /// Time without any accepted connection after which the node is considered unreachable
+    pub reachability_timeout: MassaTime,
+    /// DNS names resolved to the addresses of peers to test
+    pub dns_seeds: Vec<String>,
+    /// Interval between two resolutions of the DNS seeds
+    pub dns_seed_refresh_interval: MassaTime,
+    /// Rebuild the blocks from the operations we know using the prefixes of their operation ids
+    pub compact_block_relay: bool,
+    /// SOCKS5 proxy for the outbound connections to the peers and to the bootstrap servers
+    pub proxy: Option<Socks5Proxy>,
 }
 
 /// gRPC settings
@@ -300,6 +431,10 @@ pub struct GrpcSettings {
     pub subject_alt_names: Vec<String>,
     /// bind for the Massa gRPC API
     pub bind: SocketAddr,
+    /// path of an additional Unix socket listener for the Massa gRPC API (Unix only)
+    pub uds_path: Option<PathBuf>,
+    /// file permissions of the Unix socket, used to restrict the local users allowed to connect
+    pub uds_permissions: u32,
     /// which compression encodings does the server accept for requests
     pub accept_compressed: Option<String>,
     /// which compression encodings might the server use for responses
@@ -308,12 +443,18 @@ pub struct GrpcSettings {
     pub max_decoding_message_size: usize,
     /// limits the maximum size of an encoded message. Defaults to 4MB
     pub max_encoding_message_size: usize,
+    /// maximum size of a decoded message for specific methods, by method name (overrides `max_decoding_message_size`)
+    pub max_decoding_message_size_per_method: HashMap<String, usize>,
+    /// maximum size of an encoded message for specific methods, by method name (overrides `max_encoding_message_size`)
+    pub max_encoding_message_size_per_method: HashMap<String, usize>,
     /// limits the maximum size of streaming channel
     pub max_channel_size: usize,
     /// set the concurrency limit applied to on requests inbound per connection. Defaults to 32
     pub concurrency_limit_per_connection: usize,
     /// set a timeout on for all request handlers
     pub timeout: MassaTime,
+    /// max duration of the handlers of specific methods, by method name (ex: `ExecuteReadOnlyCall`)
+    pub method_timeouts: HashMap<String, MassaTime>,
     /// sets the SETTINGS_INITIAL_WINDOW_SIZE spec option for HTTP2 stream-level flow control. Default is 65,535
     pub initial_stream_window_size: Option<u32>,
     /// sets the max connection-level flow control for HTTP2. Default is 65,535
@@ -322,6 +463,10 @@ pub struct GrpcSettings {
     pub max_concurrent_streams: Option<u32>,
     /// max number of arguments per gRPC request
     pub max_arguments: u64,
+    /// max number of streaming requests simultaneously opened by a client IP (0 means unlimited)
+    pub max_streams_per_ip: usize,
+    /// number of events of each kind kept to be replayed to the streams resuming from a cursor
+    pub event_replay_buffer_size: usize,
     /// set whether TCP keepalive messages are enabled on accepted connections
     pub tcp_keepalive: Option<MassaTime>,
     /// set the value of `TCP_NODELAY` option for accepted connections. Enabled by default
@@ -352,6 +497,8 @@ pub struct GrpcSettings {
     pub max_filters_per_request: u32,
     /// max number of query items that can be included in a single request
     pub max_query_items_per_request: u32,
+    /// max number of ledger entries that can be returned by a single request
+    pub max_ledger_entries_per_request: u32,
     /// certificate authority root path
     pub certificate_authority_root_path: PathBuf,
     /// server certificate path
@@ -381,6 +528,38 @@ pub struct VersioningSettings {
     pub(crate) mip_stats_warn_announced_version: u32,
 }
 
+/// Disk usage accounting of the node storage
+#[derive(Debug, Deserialize, Clone)]
+pub struct DiskUsageSettings {
+    /// interval between two measures of the disk usage
+    pub check_interval: MassaTime,
+    /// total disk usage in bytes above which warnings are logged (0 disables them)
+    pub warning_threshold: u64,
+    /// total disk usage in bytes above which the writes to the operation and event indexes
+    /// are suspended (0 disables it)
+    pub max_size: u64,
+}
+
+/// Readiness and liveness endpoints of the node
+#[derive(Debug, Deserialize, Clone)]
+pub struct HealthSettings {
+    /// enable the `/readyz` and `/livez` HTTP endpoints
+    pub enabled: bool,
+    /// address on which to listen for the health checks
+    pub bind: SocketAddr,
+    /// time after which the node is reported as not live if its main loop did not run
+    pub liveness_timeout: MassaTime,
+}
+
+/// Pruned mode of the node, keeping only the final state and its recent history
+#[derive(Debug, Deserialize, Clone)]
+pub struct PruningSettings {
+    /// keep only the recent history, disabling the archived final states
+    pub enabled: bool,
+    /// number of periods of final operations and events kept in pruned mode
+    pub history_periods: u64,
+}
+
 #[cfg(test)]
 #[test]
 fn test_load_node_config() {
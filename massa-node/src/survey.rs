@@ -121,9 +121,22 @@ impl MassaSurvey {
                                 }
 
                                 {
-                                    massa_metrics.set_operations_pool(pool_controller.get_operation_count());
-                                    massa_metrics.set_endorsements_pool(pool_controller.get_endorsement_count());
-                                    massa_metrics.set_denunciations_pool(pool_controller.get_denunciation_count());
+                                    let pool_stats = pool_controller.get_pool_stats();
+                                    massa_metrics.set_operations_pool(pool_stats.operation_count as usize);
+                                    massa_metrics.set_endorsements_pool(pool_stats.endorsement_count as usize);
+                                    massa_metrics.set_denunciations_pool(pool_stats.denunciation_count as usize);
+                                    massa_metrics.set_pool_oldest_ages(
+                                        pool_stats.oldest_endorsement_age.map_or(0, |age| age.as_millis()),
+                                        pool_stats.oldest_denunciation_age.map_or(0, |age| age.as_millis()),
+                                    );
+                                    let slot_count = pool_stats.endorsement_slots.len();
+                                    let coverage = if slot_count > 0 && pool_stats.max_block_endorsement_count > 0 {
+                                        let covered_count: usize = pool_stats.endorsement_slots.iter().map(|slot| slot.indexes.len()).sum();
+                                        covered_count as f64 / (slot_count as f64 * pool_stats.max_block_endorsement_count as f64)
+                                    } else {
+                                        0.0
+                                    };
+                                    massa_metrics.set_endorsement_pool_coverage(coverage);
 
                                     let count = std::thread::available_parallelism()
                                     .unwrap_or(std::num::NonZeroUsize::MIN)
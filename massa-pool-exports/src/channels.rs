@@ -1,6 +1,7 @@
 use massa_execution_exports::ExecutionController;
 use massa_models::{endorsement::SecureShareEndorsement, operation::SecureShareOperation};
 use massa_pos_exports::SelectorController;
+use massa_protocol_exports::ProtocolController;
 
 /// channels used by the pool worker
 #[derive(Clone)]
@@ -9,6 +10,8 @@ pub struct PoolChannels {
     pub execution_controller: Box<dyn ExecutionController>,
     /// Selector to get draws
     pub selector: Box<dyn SelectorController>,
+    /// Protocol, to re-propagate the operations submitted through the API of this node
+    pub protocol: Box<dyn ProtocolController>,
     /// Broadcasts used by the pool worker to send new operations and endorsements
     pub broadcasts: PoolBroadcasts,
 }
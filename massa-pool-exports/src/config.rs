@@ -4,6 +4,18 @@ use massa_models::amount::Amount;
 use massa_time::MassaTime;
 use serde::{Deserialize, Serialize};
 
+/// Order in which the operations are evicted when the operation pool is full
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationEvictionPolicy {
+    /// evict the operations of lowest score for block production first
+    Score,
+    /// evict the operations paying the lowest fee for the block space and gas they use first
+    FeeDensity,
+    /// evict the operations that entered the pool first
+    OldestFirst,
+}
+
 /// Pool configuration
 #[derive(Debug, Deserialize, Serialize, Clone, Copy)]
 pub struct PoolConfig {
@@ -23,6 +35,12 @@ pub struct PoolConfig {
     pub operation_validity_periods: u64,
     /// operation pool refresh interval
     pub operation_pool_refresh_interval: MassaTime,
+    /// interval between two propagations of an operation submitted through the API of this node,
+    /// checked at each refresh of the operation pool
+    pub operation_rebroadcast_interval: MassaTime,
+    /// max number of propagations of an operation submitted through the API of this node after
+    /// the first one (0 to disable the rebroadcast)
+    pub operation_rebroadcast_max_attempts: u32,
     /// max delay in the future for operation validity start
     pub operation_max_future_start_delay: MassaTime,
     /// max operations per block
@@ -31,6 +49,16 @@ pub struct PoolConfig {
     pub max_operation_pool_size: usize,
     /// max excess on pool size (in-between refreshes)
     pub max_operation_pool_excess_items: usize,
+    /// order in which the operations are evicted when the pool is full
+    pub operation_eviction_policy: OperationEvictionPolicy,
+    /// max number of operations kept in the pool per sender address (0 for no limit)
+    pub max_operations_per_address: usize,
+    /// max number of operations of a sender in the pool, the new operations exceeding it
+    /// being rejected (0 for no limit)
+    pub max_sender_operation_count: usize,
+    /// max total size in bytes of the operations of a sender in the pool, the new operations
+    /// exceeding it being rejected (0 for no limit)
+    pub max_sender_operation_size: usize,
     /// max endorsement pool size per thread (in number of endorsements)
     pub max_endorsements_pool_size_per_thread: usize,
     /// max number of endorsements per block
@@ -59,6 +87,20 @@ pub struct PoolConfig {
     pub max_denunciations_per_block_header: u32,
     /// Minimum acceptable fees to include an operation in a block
     pub minimal_fees: Amount,
+    /// chain id
+    pub chain_id: u64,
+    /// max datastore value length, for the deserialization of persisted operations
+    pub max_datastore_value_length: u64,
+    /// max function name length, for the deserialization of persisted operations
+    pub max_function_name_length: u16,
+    /// max parameters size, for the deserialization of persisted operations
+    pub max_parameters_size: u32,
+    /// max operation datastore entry count, for the deserialization of persisted operations
+    pub max_op_datastore_entry_count: u64,
+    /// max operation datastore key length, for the deserialization of persisted operations
+    pub max_op_datastore_key_length: u8,
+    /// max operation datastore value length, for the deserialization of persisted operations
+    pub max_op_datastore_value_length: u64,
     /// last_start_period
     /// * If start all new network: set to 0
     /// * If from snapshot: retrieve from args
@@ -4,11 +4,14 @@ use massa_models::{
     block_id::BlockId,
     denunciation::{Denunciation, DenunciationPrecursor},
     endorsement::EndorsementId,
-    operation::OperationId,
+    operation::{OperationId, PoolOperationSort, PoolOperationSummary, SecureShareOperation},
     slot::Slot,
+    stats::{OperationPoolStats, PoolStats},
 };
 use massa_storage::Storage;
 
+use crate::{OperationEvictionPolicy, PoolError};
+
 #[cfg(feature = "test-exports")]
 use std::sync::{Arc, RwLock};
 
@@ -18,6 +21,16 @@ pub trait PoolController: Send + Sync {
     /// Asynchronously add operations to pool. Simply print a warning on failure.
     fn add_operations(&mut self, ops: Storage);
 
+    /// Asynchronously add operations submitted through the API of this node to pool.
+    /// They are then propagated again periodically until they are executed or expire.
+    /// Simply print a warning on failure.
+    fn add_local_operations(&mut self, ops: Storage);
+
+    /// Check that operations can be added to the pool without exceeding the limits
+    /// on the number and the size of the operations of each sender.
+    /// The operations exceeding them are otherwise dropped by `add_operations`.
+    fn check_sender_limits(&self, operations: &[SecureShareOperation]) -> Result<(), PoolError>;
+
     /// Asynchronously add endorsements to pool. Simply print a warning on failure.
     fn add_endorsements(&mut self, endorsements: Storage);
 
@@ -55,6 +68,49 @@ pub trait PoolController: Send + Sync {
     /// Get the number of denunciations in the pool
     fn get_denunciation_count(&self) -> usize;
 
+    /// Get the number of items of the pools, the age of the oldest endorsement and denunciation,
+    /// and the endorsement indexes covered at each slot of the endorsement pool
+    fn get_pool_stats(&self) -> PoolStats;
+
+    /// Get a page of summaries of the operations of the pool, with the occupancy of the pool
+    ///
+    /// # Arguments
+    /// * `sort`: order of the operations
+    /// * `offset`: number of operations to skip
+    /// * `limit`: max number of operations returned
+    fn get_pool_operations(
+        &self,
+        sort: PoolOperationSort,
+        offset: usize,
+        limit: usize,
+    ) -> (Vec<PoolOperationSummary>, OperationPoolStats);
+
+    /// Update the size limits of the operation and endorsement pools at runtime.
+    /// Pools exceeding the new limits are truncated at their next refresh.
+    ///
+    /// # Arguments
+    /// * `max_operation_pool_size`: max operation pool size (in number of operations)
+    /// * `max_operation_pool_excess_items`: max excess on operation pool size (in-between refreshes)
+    /// * `max_endorsements_pool_size_per_thread`: max endorsement pool size per thread
+    fn set_pool_size_limits(
+        &self,
+        max_operation_pool_size: usize,
+        max_operation_pool_excess_items: usize,
+        max_endorsements_pool_size_per_thread: usize,
+    );
+
+    /// Update the eviction policy of the operation pool at runtime.
+    /// It is applied at the next refresh of the pool.
+    ///
+    /// # Arguments
+    /// * `policy`: order in which the operations are evicted when the pool is full
+    /// * `max_operations_per_address`: max number of operations per sender address (0 for no limit)
+    fn set_operation_eviction_policy(
+        &self,
+        policy: OperationEvictionPolicy,
+        max_operations_per_address: usize,
+    );
+
     /// Returns a boxed clone of self.
     /// Useful to allow cloning `Box<dyn PoolController>`.
     fn clone_box(&self) -> Box<dyn PoolController>;
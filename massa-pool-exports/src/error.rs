@@ -0,0 +1,18 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! this file defines all possible pool error categories
+
+use displaydoc::Display;
+use massa_models::address::Address;
+use thiserror::Error;
+
+/// Errors of the pool component.
+#[non_exhaustive]
+#[derive(Clone, Display, Error, Debug)]
+pub enum PoolError {
+    /// sender {0} would exceed the limit of {1} operations in the pool
+    SenderOperationCountLimit(Address, usize),
+
+    /// sender {0} would exceed the limit of {1} bytes of operations in the pool
+    SenderOperationSizeLimit(Address, usize),
+}
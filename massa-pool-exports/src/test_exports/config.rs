@@ -3,14 +3,17 @@
 use massa_models::{
     amount::Amount,
     config::{
-        BASE_OPERATION_GAS_COST, DENUNCIATION_EXPIRE_PERIODS, ENDORSEMENT_COUNT, MAX_BLOCK_SIZE,
-        MAX_DENUNCIATIONS_PER_BLOCK_HEADER, MAX_GAS_PER_BLOCK, MAX_OPERATIONS_PER_BLOCK,
-        OPERATION_VALIDITY_PERIODS, PERIODS_PER_CYCLE, ROLL_PRICE, T0, THREAD_COUNT,
+        BASE_OPERATION_GAS_COST, CHAINID, DENUNCIATION_EXPIRE_PERIODS, ENDORSEMENT_COUNT,
+        MAX_BLOCK_SIZE, MAX_DATASTORE_VALUE_LENGTH, MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
+        MAX_FUNCTION_NAME_LENGTH, MAX_GAS_PER_BLOCK, MAX_OPERATIONS_PER_BLOCK,
+        MAX_OPERATION_DATASTORE_ENTRY_COUNT, MAX_OPERATION_DATASTORE_KEY_LENGTH,
+        MAX_OPERATION_DATASTORE_VALUE_LENGTH, MAX_PARAMETERS_SIZE, OPERATION_VALIDITY_PERIODS,
+        PERIODS_PER_CYCLE, ROLL_PRICE, T0, THREAD_COUNT,
     },
 };
 use massa_time::MassaTime;
 
-use crate::PoolConfig;
+use crate::{OperationEvictionPolicy, PoolConfig};
 
 impl Default for PoolConfig {
     fn default() -> Self {
@@ -24,6 +27,10 @@ impl Default for PoolConfig {
             base_operation_gas_cost: BASE_OPERATION_GAS_COST,
             max_operation_pool_size: 32000,
             max_operation_pool_excess_items: 10000,
+            operation_eviction_policy: OperationEvictionPolicy::Score,
+            max_operations_per_address: 0,
+            max_sender_operation_count: 0,
+            max_sender_operation_size: 0,
             max_endorsements_pool_size_per_thread: 1000,
             max_operations_per_block: MAX_OPERATIONS_PER_BLOCK,
             max_block_endorsement_count: ENDORSEMENT_COUNT,
@@ -40,8 +47,17 @@ impl Default for PoolConfig {
             max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
             last_start_period: 0,
             operation_pool_refresh_interval: MassaTime::from_millis(2000),
+            operation_rebroadcast_interval: MassaTime::from_millis(2000),
+            operation_rebroadcast_max_attempts: 0,
             operation_max_future_start_delay: T0.saturating_mul(5),
             minimal_fees: Amount::zero(),
+            chain_id: *CHAINID,
+            max_datastore_value_length: MAX_DATASTORE_VALUE_LENGTH,
+            max_function_name_length: MAX_FUNCTION_NAME_LENGTH,
+            max_parameters_size: MAX_PARAMETERS_SIZE,
+            max_op_datastore_entry_count: MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+            max_op_datastore_key_length: MAX_OPERATION_DATASTORE_KEY_LENGTH,
+            max_op_datastore_value_length: MAX_OPERATION_DATASTORE_VALUE_LENGTH,
         }
     }
 }
@@ -3,19 +3,29 @@
 //! Pool controller implementation
 
 use massa_models::{
-    block_id::BlockId, denunciation::Denunciation, denunciation::DenunciationPrecursor,
-    endorsement::EndorsementId, operation::OperationId, slot::Slot,
+    block_id::BlockId,
+    denunciation::Denunciation,
+    denunciation::DenunciationPrecursor,
+    endorsement::EndorsementId,
+    operation::{OperationId, PoolOperationSort, PoolOperationSummary, SecureShareOperation},
+    slot::Slot,
+    stats::{OperationPoolStats, PoolStats},
+    timeslots::get_block_slot_timestamp,
+};
+use massa_pool_exports::{
+    OperationEvictionPolicy, PoolConfig, PoolController, PoolError, PoolManager,
 };
-use massa_pool_exports::{PoolConfig, PoolController, PoolManager};
 use massa_storage::Storage;
+use massa_time::MassaTime;
 use parking_lot::RwLock;
+use std::path::PathBuf;
 use std::sync::mpsc::TrySendError;
 use std::sync::{mpsc::SyncSender, Arc};
 use tracing::{info, warn};
 
 use crate::{
     denunciation_pool::DenunciationPool, endorsement_pool::EndorsementPool,
-    operation_pool::OperationPool,
+    operation_pool::OperationPool, persistence::save_pool,
 };
 
 /// A generic command to send commands to a pool
@@ -23,6 +33,8 @@ use crate::{
 pub enum Command {
     /// Add items to the pool
     AddItems(Storage),
+    /// Add operations submitted through the API of this node to the pool
+    AddLocalOperations(Storage),
     /// Add denunciation precursor to the pool
     AddDenunciationPrecursor(DenunciationPrecursor),
     /// Notify of new final consensus periods
@@ -35,7 +47,7 @@ pub enum Command {
 #[derive(Clone)]
 pub struct PoolControllerImpl {
     /// Config
-    pub(crate) _config: PoolConfig,
+    pub(crate) config: PoolConfig,
     /// Shared reference to the operation pool
     pub(crate) operation_pool: Arc<RwLock<OperationPool>>,
     /// Shared reference to the endorsement pool
@@ -69,6 +81,29 @@ impl PoolController for PoolControllerImpl {
         }
     }
 
+    /// Asynchronously add operations submitted through the API of this node to the pool,
+    /// to propagate them again until they are executed or expire.
+    /// Simply print a warning on failure.
+    fn add_local_operations(&mut self, ops: Storage) {
+        match self
+            .operations_input_sender
+            .try_send(Command::AddLocalOperations(ops))
+        {
+            Err(TrySendError::Disconnected(_)) => {
+                warn!("Could not add local operations to pool: worker is unreachable.");
+            }
+            Err(TrySendError::Full(_)) => {
+                warn!("Could not add local operations to pool: worker channel is full.");
+            }
+            Ok(_) => {}
+        }
+    }
+
+    /// Check that operations can be added to the pool without exceeding the per-sender limits
+    fn check_sender_limits(&self, operations: &[SecureShareOperation]) -> Result<(), PoolError> {
+        self.operation_pool.read().check_sender_limits(operations)
+    }
+
     /// Asynchronously add endorsements to pool. Simply print a warning on failure.
     fn add_endorsements(&mut self, endorsements: Storage) {
         // Send endorsements to the denunciation pool - so we got unfiltered endorsements
@@ -221,6 +256,80 @@ impl PoolController for PoolControllerImpl {
         self.denunciation_pool.read().len()
     }
 
+    /// Get the counts, ages and per-slot endorsement coverage of the pools
+    fn get_pool_stats(&self) -> PoolStats {
+        let now = MassaTime::now();
+        let slot_age = |slot: Slot| {
+            get_block_slot_timestamp(
+                self.config.thread_count,
+                self.config.t0,
+                self.config.genesis_timestamp,
+                slot,
+            )
+            .ok()
+            .map(|timestamp| now.saturating_sub(timestamp))
+        };
+        let (endorsement_count, oldest_endorsement_slot, endorsement_slots) = {
+            let endorsement_pool = self.endorsement_pool.read();
+            (
+                endorsement_pool.len(),
+                endorsement_pool.get_oldest_slot(),
+                endorsement_pool.get_slot_coverage(),
+            )
+        };
+        let (denunciation_count, oldest_denunciation_slot) = {
+            let denunciation_pool = self.denunciation_pool.read();
+            (denunciation_pool.len(), denunciation_pool.get_oldest_slot())
+        };
+        PoolStats {
+            operation_count: self.operation_pool.read().len() as u64,
+            endorsement_count: endorsement_count as u64,
+            denunciation_count: denunciation_count as u64,
+            oldest_endorsement_age: oldest_endorsement_slot.and_then(slot_age),
+            oldest_denunciation_age: oldest_denunciation_slot.and_then(slot_age),
+            max_block_endorsement_count: self.config.max_block_endorsement_count,
+            endorsement_slots,
+        }
+    }
+
+    /// Get a page of summaries of the operations of the pool, with the occupancy of the pool
+    fn get_pool_operations(
+        &self,
+        sort: PoolOperationSort,
+        offset: usize,
+        limit: usize,
+    ) -> (Vec<PoolOperationSummary>, OperationPoolStats) {
+        self.operation_pool
+            .read()
+            .get_operation_summaries(sort, offset, limit)
+    }
+
+    /// Update the size limits of the operation and endorsement pools at runtime.
+    fn set_pool_size_limits(
+        &self,
+        max_operation_pool_size: usize,
+        max_operation_pool_excess_items: usize,
+        max_endorsements_pool_size_per_thread: usize,
+    ) {
+        self.operation_pool
+            .write()
+            .set_size_limits(max_operation_pool_size, max_operation_pool_excess_items);
+        self.endorsement_pool
+            .write()
+            .set_size_limit(max_endorsements_pool_size_per_thread);
+    }
+
+    /// Update the eviction policy of the operation pool at runtime.
+    fn set_operation_eviction_policy(
+        &self,
+        policy: OperationEvictionPolicy,
+        max_operations_per_address: usize,
+    ) {
+        self.operation_pool
+            .write()
+            .set_eviction_policy(policy, max_operations_per_address);
+    }
+
     /// Returns a boxed clone of self.
     /// Allows cloning `Box<dyn PoolController>`,
     fn clone_box(&self) -> Box<dyn PoolController> {
@@ -249,6 +358,12 @@ pub struct PoolManagerImpl {
     pub(crate) endorsements_input_sender: SyncSender<Command>,
     /// Denunciations input data mpsc (used to stop the pool thread)
     pub(crate) denunciations_input_sender: SyncSender<Command>,
+    /// Shared reference to the operation pool, persisted when stopping
+    pub(crate) operation_pool: Arc<RwLock<OperationPool>>,
+    /// Shared reference to the endorsement pool, persisted when stopping
+    pub(crate) endorsement_pool: Arc<RwLock<EndorsementPool>>,
+    /// File where the pool is persisted when stopping, if enabled
+    pub(crate) persistence_path: Option<PathBuf>,
 }
 
 impl PoolManager for PoolManagerImpl {
@@ -273,6 +388,20 @@ impl PoolManager for PoolManagerImpl {
                 .join()
                 .expect("denunciations pool thread panicked on try to join");
         }
+        if let Some(path) = &self.persistence_path {
+            let operations = self.operation_pool.read().get_operations();
+            let endorsements = self.endorsement_pool.read().get_endorsements();
+            let (operation_count, endorsement_count) = (operations.len(), endorsements.len());
+            match save_pool(path, operations, endorsements) {
+                Ok(()) => info!(
+                    "{} operations and {} endorsements of the pool written to {}",
+                    operation_count,
+                    endorsement_count,
+                    path.display()
+                ),
+                Err(err) => warn!("failed to write the pool to {}: {}", path.display(), err),
+            }
+        }
         info!("pool workers stopped");
     }
 }
@@ -43,6 +43,17 @@ impl DenunciationPool {
             .count()
     }
 
+    /// Get the slot of the items denounced by the oldest denunciation of the pool
+    pub(crate) fn get_oldest_slot(&self) -> Option<Slot> {
+        self.denunciations_cache
+            .values()
+            .filter_map(|de_st| match de_st {
+                DenunciationStatus::Accumulating(_) => None,
+                DenunciationStatus::DenunciationEmitted(de) => Some(*de.get_slot()),
+            })
+            .min()
+    }
+
     /// Checks whether an element is stored in the pool - only used in unit tests for now
     #[cfg(feature = "test-exports")]
     pub fn _contains(&self, denunciation: &Denunciation) -> bool {
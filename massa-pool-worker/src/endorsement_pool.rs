@@ -2,9 +2,10 @@
 
 use massa_models::{
     block_id::BlockId,
-    endorsement::EndorsementId,
+    endorsement::{EndorsementId, SecureShareEndorsement},
     prehash::{CapacityAllocator, PreHashSet},
     slot::Slot,
+    stats::SlotEndorsementCoverage,
 };
 use massa_pool_exports::{PoolChannels, PoolConfig};
 use massa_storage::Storage;
@@ -63,11 +64,60 @@ impl EndorsementPool {
         self.storage.get_endorsement_refs().len()
     }
 
+    /// Update the per-thread size limit of the pool. It is enforced when new endorsements are added.
+    pub(crate) fn set_size_limit(&mut self, max_pool_size_per_thread: usize) {
+        self.config.max_endorsements_pool_size_per_thread = max_pool_size_per_thread;
+    }
+
     /// Checks whether an element is stored in the pool.
     pub fn contains(&self, id: &EndorsementId) -> bool {
         self.storage.get_endorsement_refs().contains(id)
     }
 
+    /// Get the endorsements of the pool
+    pub(crate) fn get_endorsements(&self) -> Vec<SecureShareEndorsement> {
+        let endorsements = self.storage.read_endorsements();
+        self.storage
+            .get_endorsement_refs()
+            .iter()
+            .filter_map(|id| endorsements.get(id).cloned())
+            .collect()
+    }
+
+    /// Get the number of endorsements and the endorsement indexes covered at each slot,
+    /// by increasing slot
+    pub(crate) fn get_slot_coverage(&self) -> Vec<SlotEndorsementCoverage> {
+        let mut slots: BTreeMap<Slot, SlotEndorsementCoverage> = BTreeMap::new();
+        for (slot, index, _block_id) in self.endorsements_indexed.keys() {
+            let coverage = slots
+                .entry(*slot)
+                .or_insert_with(|| SlotEndorsementCoverage {
+                    slot: *slot,
+                    endorsement_count: 0,
+                    indexes: Vec::new(),
+                });
+            coverage.endorsement_count += 1;
+            coverage.indexes.push(*index);
+        }
+        slots
+            .into_values()
+            .map(|mut coverage| {
+                coverage.indexes.sort_unstable();
+                coverage.indexes.dedup();
+                coverage
+            })
+            .collect()
+    }
+
+    /// Get the slot of the oldest endorsement of the pool
+    pub(crate) fn get_oldest_slot(&self) -> Option<Slot> {
+        self.endorsements_sorted
+            .iter()
+            .filter_map(|endorsements| endorsements.first_key_value())
+            .map(|((slot, _index, _block_id), _id)| *slot)
+            .min()
+    }
+
     /// notify of new final CS periods
     pub(crate) fn notify_final_cs_periods(&mut self, final_cs_periods: &[u64]) {
         // update internal final CS period counter
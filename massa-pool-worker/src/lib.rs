@@ -9,6 +9,7 @@ mod controller_impl;
 mod denunciation_pool;
 mod endorsement_pool;
 mod operation_pool;
+mod persistence;
 mod types;
 mod worker;
 
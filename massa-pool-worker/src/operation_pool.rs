@@ -1,23 +1,40 @@
 //! Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use massa_metrics::MassaMetrics;
 use massa_models::{
     address::Address,
     amount::Amount,
-    operation::OperationId,
+    operation::{OperationId, PoolOperationSort, PoolOperationSummary, SecureShareOperation},
     prehash::{CapacityAllocator, PreHashMap, PreHashSet},
     slot::Slot,
+    stats::OperationPoolStats,
     timeslots::get_latest_block_slot_at_timestamp,
 };
-use massa_pool_exports::{PoolChannels, PoolConfig};
+use massa_pool_exports::{OperationEvictionPolicy, PoolChannels, PoolConfig, PoolError};
 use massa_storage::Storage;
 use massa_time::MassaTime;
 use massa_wallet::Wallet;
 use parking_lot::RwLock;
-use std::{cmp::max, cmp::Ordering, cmp::PartialOrd, collections::BTreeSet, sync::Arc};
+use std::{
+    cmp::max, cmp::Ordering, cmp::PartialOrd, cmp::Reverse, collections::BTreeSet, sync::Arc,
+};
 use tracing::{debug, trace, warn};
 
 use crate::types::OperationInfo;
 
+/// Operation submitted through the API of this node, propagated again until it is executed
+/// or expires
+struct LocalOperation {
+    /// thread of the operation
+    thread: u8,
+    /// expire period of the operation
+    expire_period: u64,
+    /// number of propagations after the first one
+    attempts: u32,
+    /// time after which the operation is propagated again
+    next_rebroadcast: MassaTime,
+}
+
 pub struct OperationPool {
     /// configuration
     config: PoolConfig,
@@ -28,6 +45,12 @@ pub struct OperationPool {
     /// storage instance
     pub(crate) storage: Storage,
 
+    /// operations submitted through the API of this node, to propagate again
+    local_ops: PreHashMap<OperationId, LocalOperation>,
+
+    /// storage instance holding the operations submitted through the API of this node
+    local_ops_storage: Storage,
+
     /// last consensus final periods, per thread
     last_cs_final_periods: Vec<u64>,
 
@@ -36,6 +59,12 @@ pub struct OperationPool {
 
     /// staking wallet, to know which addresses we are using to stake
     wallet: Arc<RwLock<Wallet>>,
+
+    /// entry index of the next operation added to the pool
+    next_entry_index: u64,
+
+    /// metrics
+    massa_metrics: MassaMetrics,
 }
 
 impl OperationPool {
@@ -44,6 +73,7 @@ impl OperationPool {
         storage: &Storage,
         channels: PoolChannels,
         wallet: Arc<RwLock<Wallet>>,
+        massa_metrics: MassaMetrics,
     ) -> Self {
         OperationPool {
             sorted_ops: Vec::with_capacity(
@@ -54,8 +84,12 @@ impl OperationPool {
             last_cs_final_periods: vec![0u64; config.thread_count as usize],
             config,
             storage: storage.clone_without_refs(),
+            local_ops: Default::default(),
+            local_ops_storage: storage.clone_without_refs(),
             channels,
             wallet,
+            next_entry_index: 0,
+            massa_metrics,
         }
     }
 
@@ -223,22 +257,109 @@ impl OperationPool {
         self.storage.drop_operation_refs(&removed);
     }
 
-    /// Truncates the container to the max allowed size
-    fn truncate_container(&mut self) {
-        if self.sorted_ops.len() > self.config.max_operation_pool_size {
-            let mut removed = PreHashSet::default();
-            for op_info in self
-                .sorted_ops
-                .iter()
-                .skip(self.config.max_operation_pool_size)
-            {
-                removed.insert(op_info.id);
+    /// Fee paid by an operation per share of the block resources it uses,
+    /// the share being the largest of its block size and block gas ones
+    fn fee_density(&self, op_info: &OperationInfo) -> f32 {
+        let size_share = (op_info.size as f32) / (self.config.max_block_size as f32);
+        let gas_share = (op_info.max_gas_usage as f32) / (self.config.max_block_gas as f32);
+        (op_info.fee.to_raw() as f32) / size_share.max(gas_share)
+    }
+
+    /// Number and total size of the operations of each sender in the pool
+    fn get_sender_usage(&self) -> PreHashMap<Address, (usize, usize)> {
+        let mut sender_usage: PreHashMap<Address, (usize, usize)> = PreHashMap::default();
+        for op_info in &self.sorted_ops {
+            let (count, size) = sender_usage.entry(op_info.creator_address).or_default();
+            *count += 1;
+            *size += op_info.size;
+        }
+        sender_usage
+    }
+
+    /// Account for a new operation of `size` bytes in the `usage` (count, size) of its sender,
+    /// failing if it would exceed the per-sender limits
+    fn use_sender_limits(
+        &self,
+        sender: Address,
+        size: usize,
+        usage: &mut (usize, usize),
+    ) -> Result<(), PoolError> {
+        let (count, total_size) = usage;
+        let max_count = self.config.max_sender_operation_count;
+        if max_count > 0 && *count >= max_count {
+            return Err(PoolError::SenderOperationCountLimit(sender, max_count));
+        }
+        let max_size = self.config.max_sender_operation_size;
+        if max_size > 0 && total_size.saturating_add(size) > max_size {
+            return Err(PoolError::SenderOperationSizeLimit(sender, max_size));
+        }
+        *count += 1;
+        *total_size += size;
+        Ok(())
+    }
+
+    /// Evicts the operations exceeding the max number of operations per address
+    /// and the max pool size, in the order given by the eviction policy.
+    /// Assumes that the ops are sorted by descending score: the kept ones stay in that order.
+    fn evict_operations(&mut self) {
+        let max_operations_per_address = self.config.max_operations_per_address;
+        if max_operations_per_address == 0
+            && self.sorted_ops.len() <= self.config.max_operation_pool_size
+        {
+            return;
+        }
+
+        // indices of the ops, from the first to keep to the first to evict
+        let mut keep_order: Vec<usize> = (0..self.sorted_ops.len()).collect();
+        match self.config.operation_eviction_policy {
+            OperationEvictionPolicy::Score => {}
+            OperationEvictionPolicy::FeeDensity => {
+                let densities: Vec<f32> = self
+                    .sorted_ops
+                    .iter()
+                    .map(|op_info| self.fee_density(op_info))
+                    .collect();
+                keep_order.sort_by(|i1, i2| {
+                    densities[*i2]
+                        .partial_cmp(&densities[*i1])
+                        .unwrap_or(Ordering::Equal)
+                });
+            }
+            OperationEvictionPolicy::OldestFirst => {
+                keep_order.sort_by_key(|i| Reverse(self.sorted_ops[*i].entry_index));
             }
-            self.sorted_ops
-                .truncate(self.config.max_operation_pool_size);
-            // drop from storage
-            self.storage.drop_operation_refs(&removed);
         }
+
+        let mut address_counts: PreHashMap<Address, usize> = PreHashMap::default();
+        let mut kept_count = 0;
+        let mut address_cap_evictions = PreHashSet::default();
+        let mut pool_size_evictions = PreHashSet::default();
+        for index in keep_order {
+            let op_info = &self.sorted_ops[index];
+            let address_count = address_counts.entry(op_info.creator_address).or_default();
+            if max_operations_per_address > 0 && *address_count >= max_operations_per_address {
+                address_cap_evictions.insert(op_info.id);
+            } else if kept_count >= self.config.max_operation_pool_size {
+                pool_size_evictions.insert(op_info.id);
+            } else {
+                *address_count += 1;
+                kept_count += 1;
+            }
+        }
+        if address_cap_evictions.is_empty() && pool_size_evictions.is_empty() {
+            return;
+        }
+        self.massa_metrics
+            .inc_operation_pool_evictions("address_cap", address_cap_evictions.len() as u64);
+        self.massa_metrics
+            .inc_operation_pool_evictions("pool_size", pool_size_evictions.len() as u64);
+
+        let mut removed = address_cap_evictions;
+        removed.extend(pool_size_evictions);
+        self.sorted_ops
+            .retain(|op_info| !removed.contains(&op_info.id));
+        // drop from storage
+        self.storage.drop_operation_refs(&removed);
     }
 
     /// Score the operations
@@ -361,8 +482,8 @@ impl OperationPool {
         // eliminate balance overflows in sorted ops
         self.eliminate_balance_overflows(&sender_balances);
 
-        // eliminate container size overflows
-        self.truncate_container();
+        // eliminate container size and per-address overflows
+        self.evict_operations();
     }
 
     /// Get the number of stored elements
@@ -370,11 +491,102 @@ impl OperationPool {
         self.sorted_ops.len()
     }
 
+    /// Update the size limits of the pool. They are enforced at the next refresh.
+    pub(crate) fn set_size_limits(&mut self, max_pool_size: usize, max_excess_items: usize) {
+        self.config.max_operation_pool_size = max_pool_size;
+        self.config.max_operation_pool_excess_items = max_excess_items;
+    }
+
+    /// Update the eviction policy of the pool. It is applied at the next refresh.
+    pub(crate) fn set_eviction_policy(
+        &mut self,
+        policy: OperationEvictionPolicy,
+        max_operations_per_address: usize,
+    ) {
+        self.config.operation_eviction_policy = policy;
+        self.config.max_operations_per_address = max_operations_per_address;
+    }
+
     /// Checks whether an element is stored in the pool.
     pub fn contains(&self, id: &OperationId) -> bool {
         self.storage.get_op_refs().contains(id)
     }
 
+    /// Get the operations of the pool
+    pub(crate) fn get_operations(&self) -> Vec<SecureShareOperation> {
+        let ops = self.storage.read_operations();
+        self.storage
+            .get_op_refs()
+            .iter()
+            .filter_map(|id| ops.get(id).cloned())
+            .collect()
+    }
+
+    /// Get a page of summaries of the operations of the pool, with the occupancy of the pool
+    ///
+    /// # Arguments
+    /// * `sort`: order of the operations
+    /// * `offset`: number of operations to skip
+    /// * `limit`: max number of operations returned
+    pub(crate) fn get_operation_summaries(
+        &self,
+        sort: PoolOperationSort,
+        offset: usize,
+        limit: usize,
+    ) -> (Vec<PoolOperationSummary>, OperationPoolStats) {
+        let mut op_infos: Vec<&OperationInfo> = self.sorted_ops.iter().collect();
+        match sort {
+            PoolOperationSort::Fee => op_infos.sort_by(|op1, op2| {
+                op2.fee
+                    .cmp(&op1.fee)
+                    .then(op1.entry_index.cmp(&op2.entry_index))
+            }),
+            PoolOperationSort::Age => op_infos.sort_by_key(|op_info| op_info.entry_index),
+        }
+        let summaries = op_infos
+            .into_iter()
+            .skip(offset)
+            .take(limit)
+            .map(|op_info| PoolOperationSummary {
+                id: op_info.id,
+                sender: op_info.creator_address,
+                fee: op_info.fee,
+                kind: op_info.kind,
+                expire_period: *op_info.validity_period_range.end(),
+                added_at: op_info.added_at,
+            })
+            .collect();
+        let stats = OperationPoolStats {
+            operation_count: self.sorted_ops.len(),
+            max_operation_count: self.config.max_operation_pool_size,
+            total_size: self.sorted_ops.iter().map(|op_info| op_info.size).sum(),
+            sender_count: self
+                .sorted_ops
+                .iter()
+                .map(|op_info| op_info.creator_address)
+                .collect::<PreHashSet<Address>>()
+                .len(),
+        };
+        (summaries, stats)
+    }
+
+    /// Check that operations can be added to the pool without exceeding the per-sender limits.
+    /// The operations already in the pool are not counted.
+    pub(crate) fn check_sender_limits(
+        &self,
+        operations: &[SecureShareOperation],
+    ) -> Result<(), PoolError> {
+        let mut sender_usage = self.get_sender_usage();
+        for op in operations {
+            if self.storage.get_op_refs().contains(&op.id) {
+                continue;
+            }
+            let usage = sender_usage.entry(op.content_creator_address).or_default();
+            self.use_sender_limits(op.content_creator_address, op.serialized_size(), usage)?;
+        }
+        Ok(())
+    }
+
     /// notify of new final slot
     pub(crate) fn notify_final_cs_periods(&mut self, final_cs_periods: &[u64]) {
         // update internal final slot counter
@@ -383,10 +595,107 @@ impl OperationPool {
             "notified of new final consensus periods: {:?}",
             self.last_cs_final_periods
         );
+
+        // forget the local operations that expired
+        let mut expired = PreHashSet::default();
+        self.local_ops.retain(|id, local_op| {
+            if local_op.expire_period > final_cs_periods[local_op.thread as usize] {
+                return true;
+            }
+            expired.insert(*id);
+            false
+        });
+        self.local_ops_storage.drop_operation_refs(&expired);
+    }
+
+    /// Add operations submitted through the API of this node to the pool.
+    /// They are propagated again every `operation_rebroadcast_interval`, up to
+    /// `operation_rebroadcast_max_attempts` times, until they are executed as final or expire.
+    /// The caller is in charge of their first propagation.
+    pub(crate) fn add_local_operations(&mut self, ops_storage: Storage) {
+        if self.config.operation_rebroadcast_max_attempts > 0 {
+            let next_rebroadcast =
+                MassaTime::now().saturating_add(self.config.operation_rebroadcast_interval);
+            {
+                let ops = ops_storage.read_operations();
+                for op_id in ops_storage.get_op_refs() {
+                    if self.local_ops.contains_key(op_id) {
+                        continue;
+                    }
+                    let op = ops
+                        .get(op_id)
+                        .expect("operation not found in storage but listed as owned");
+                    self.local_ops.insert(
+                        *op_id,
+                        LocalOperation {
+                            thread: op
+                                .content_creator_address
+                                .get_thread(self.config.thread_count),
+                            expire_period: op.content.expire_period,
+                            attempts: 0,
+                            next_rebroadcast,
+                        },
+                    );
+                }
+            }
+            self.local_ops_storage.extend(ops_storage.clone());
+        }
+        self.add_operations(ops_storage);
+    }
+
+    /// Propagate again the operations submitted through the API of this node that are due.
+    /// The ones executed as final or propagated the max number of times are forgotten,
+    /// and the ones executed in a block that is not final yet are not propagated.
+    pub(crate) fn rebroadcast_local_operations(&mut self) {
+        if self.local_ops.is_empty() {
+            return;
+        }
+        let op_ids: Vec<OperationId> = self.local_ops.keys().copied().collect();
+        let exec_statuses = self
+            .channels
+            .execution_controller
+            .get_ops_exec_status(&op_ids);
+        let now = MassaTime::now();
+        let mut due = PreHashSet::default();
+        let mut removed = PreHashSet::default();
+        for (op_id, (spec_status, final_status)) in op_ids.into_iter().zip(exec_statuses) {
+            let local_op = self
+                .local_ops
+                .get_mut(&op_id)
+                .expect("local operation listed but not found");
+            if final_status.is_some()
+                || local_op.attempts >= self.config.operation_rebroadcast_max_attempts
+            {
+                removed.insert(op_id);
+            } else if spec_status.is_none() && local_op.next_rebroadcast <= now {
+                local_op.attempts += 1;
+                local_op.next_rebroadcast =
+                    now.saturating_add(self.config.operation_rebroadcast_interval);
+                due.insert(op_id);
+            }
+        }
+        self.local_ops.retain(|id, _| !removed.contains(id));
+        self.local_ops_storage.drop_operation_refs(&removed);
+
+        if due.is_empty() {
+            return;
+        }
+        debug!("propagating again {} local operations", due.len());
+        let mut rebroadcast_storage = self.local_ops_storage.clone_without_refs();
+        rebroadcast_storage.claim_operation_refs(&due);
+        if let Err(err) = self
+            .channels
+            .protocol
+            .propagate_operations(rebroadcast_storage)
+        {
+            warn!("failed to propagate again the local operations: {}", err);
+        }
     }
 
     /// Add a list of operations to the end of the pool.
     /// They will be cleaned up at the next refresh.
+    ///
+    /// The operations exceeding the per-sender limits are rejected.
     pub(crate) fn add_operations(&mut self, mut ops_storage: Storage) {
         // List all the new operations
         let mut new_op_ids = ops_storage.get_op_refs() - self.storage.get_op_refs();
@@ -408,6 +717,8 @@ impl OperationPool {
             }
         }
         if dropped_items > 0 {
+            self.massa_metrics
+                .inc_operation_pool_evictions("excess", dropped_items as u64);
             warn!(
                 "Operation pool excess limit reached. Dropping {} non-scored operations.",
                 dropped_items
@@ -418,13 +729,23 @@ impl OperationPool {
         // Note that the added items are put at the end of the sorted ops
         // so that they can still be picked for block production before refresh but with low priority
         // because in that case we don't know anything about their quality.
+        let mut rejected: PreHashSet<OperationId> = PreHashSet::default();
         {
+            let mut sender_usage = self.get_sender_usage();
             let ops = ops_storage.read_operations();
             for new_op_id in &new_op_ids {
                 let op = ops
                     .get(new_op_id)
                     .expect("operation not found in storage but listed as owned");
 
+                let sender = op.content_creator_address;
+                let usage = sender_usage.entry(sender).or_default();
+                if let Err(err) = self.use_sender_limits(sender, op.serialized_size(), usage) {
+                    debug!("operation {} rejected: {}", op.id, err);
+                    rejected.insert(*new_op_id);
+                    continue;
+                }
+
                 // Broadcast operations to active channel subscribers.
                 if self.config.broadcast_enabled {
                     if let Err(err) = self.channels.broadcasts.operation_sender.send(op.clone()) {
@@ -439,10 +760,14 @@ impl OperationPool {
                     self.config.thread_count,
                     self.config.base_operation_gas_cost,
                     self.config.sp_compilation_cost,
+                    self.next_entry_index,
                 ));
+                self.next_entry_index += 1;
             }
         }
 
+        new_op_ids.retain(|id| !rejected.contains(id));
+
         // This will add the new ops to the storage without taking locks.
         // It just take the local references from `ops_storage` if they are not in `self.storage` yet.
         // If the objects are already in `self.storage` the references in ops_storage it will not add them to `self.storage` and
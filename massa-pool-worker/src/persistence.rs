@@ -0,0 +1,117 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Persistence of the pool across restarts.
+//!
+//! The operations and endorsements of the pool are written to a file when the pool stops,
+//! and read back when it starts. They are then added to the pool like the ones received
+//! from the network, so that the ones that expired, were executed or became invalid
+//! in-between are filtered out.
+
+use massa_models::{
+    endorsement::{EndorsementDeserializer, SecureShareEndorsement},
+    operation::{OperationsDeserializer, OperationsSerializer, SecureShareOperation},
+    secure_share::{SecureShareDeserializer, SecureShareSerializer},
+};
+use massa_pool_exports::PoolConfig;
+use massa_serialization::{
+    DeserializeError, Deserializer, SerializeError, Serializer, U32VarIntDeserializer,
+    U32VarIntSerializer,
+};
+use std::io::{Error, ErrorKind};
+use std::ops::Bound::Included;
+use std::path::Path;
+use tracing::warn;
+
+/// Write the operations and endorsements of the pool to a file, replacing it if it exists
+pub(crate) fn save_pool(
+    path: &Path,
+    operations: Vec<SecureShareOperation>,
+    endorsements: Vec<SecureShareEndorsement>,
+) -> Result<(), Error> {
+    let invalid_data = |err: SerializeError| Error::new(ErrorKind::InvalidData, err.to_string());
+    let mut buffer = Vec::new();
+    OperationsSerializer::new()
+        .serialize(&operations, &mut buffer)
+        .map_err(invalid_data)?;
+    U32VarIntSerializer::new()
+        .serialize(&(endorsements.len() as u32), &mut buffer)
+        .map_err(invalid_data)?;
+    for endorsement in endorsements.iter() {
+        SecureShareSerializer::new()
+            .serialize(endorsement, &mut buffer)
+            .map_err(invalid_data)?;
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    // write a temporary file first, so that an interrupted write does not leave a truncated file
+    let tmp_path = path.with_extension("tmp");
+    std::fs::write(&tmp_path, buffer)?;
+    std::fs::rename(tmp_path, path)
+}
+
+/// Read the operations and endorsements written to a file by `save_pool`.
+/// The items with an invalid signature are skipped.
+pub(crate) fn load_pool(
+    path: &Path,
+    config: &PoolConfig,
+) -> Result<(Vec<SecureShareOperation>, Vec<SecureShareEndorsement>), Error> {
+    let invalid_data = |msg: String| Error::new(ErrorKind::InvalidData, msg);
+    let buffer = std::fs::read(path)?;
+
+    let max_operations = config
+        .max_operation_pool_size
+        .saturating_add(config.max_operation_pool_excess_items);
+    let (rest, mut operations) = OperationsDeserializer::new(
+        config.thread_count,
+        max_operations.try_into().unwrap_or(u32::MAX),
+        config.max_datastore_value_length,
+        config.max_function_name_length,
+        config.max_parameters_size,
+        config.max_op_datastore_entry_count,
+        config.max_op_datastore_key_length,
+        config.max_op_datastore_value_length,
+        config.chain_id,
+    )
+    .deserialize::<DeserializeError>(&buffer)
+    .map_err(|err| invalid_data(err.to_string()))?;
+
+    let max_endorsements = config
+        .max_endorsements_pool_size_per_thread
+        .saturating_mul(config.thread_count as usize);
+    let (mut rest, endorsement_count) = U32VarIntDeserializer::new(
+        Included(0),
+        Included(max_endorsements.try_into().unwrap_or(u32::MAX)),
+    )
+    .deserialize::<DeserializeError>(rest)
+    .map_err(|err| invalid_data(err.to_string()))?;
+    let endorsement_deserializer = SecureShareDeserializer::new(
+        EndorsementDeserializer::new(config.thread_count, config.max_block_endorsement_count),
+        config.chain_id,
+    );
+    let mut endorsements = Vec::with_capacity(endorsement_count as usize);
+    for _ in 0..endorsement_count {
+        let (new_rest, endorsement) = endorsement_deserializer
+            .deserialize::<DeserializeError>(rest)
+            .map_err(|err| invalid_data(err.to_string()))?;
+        rest = new_rest;
+        endorsements.push(endorsement);
+    }
+    if !rest.is_empty() {
+        return Err(invalid_data("data left after the pool items".to_string()));
+    }
+
+    // re-validate the signatures, the file may have been altered
+    let count = operations.len() + endorsements.len();
+    operations.retain(|operation| operation.verify_signature().is_ok());
+    endorsements.retain(|endorsement| endorsement.verify_signature().is_ok());
+    let invalid_count = count - operations.len() - endorsements.len();
+    if invalid_count > 0 {
+        warn!(
+            "{} persisted pool items have an invalid signature and were skipped",
+            invalid_count
+        );
+    }
+    Ok((operations, endorsements))
+}
@@ -1,6 +1,8 @@
 use std::{collections::BTreeMap, time::Duration};
 
-use massa_models::{address::Address, config::THREAD_COUNT, slot::Slot};
+use massa_models::{
+    address::Address, config::THREAD_COUNT, slot::Slot, stats::SlotEndorsementCoverage,
+};
 use massa_pool_exports::PoolConfig;
 use massa_pos_exports::{MockSelectorController, Selection};
 use massa_signature::KeyPair;
@@ -255,3 +257,49 @@ fn test_get_block_endorsements_works() {
         },
     );
 }
+
+#[test]
+fn test_pool_stats() {
+    let sender_keypair = KeyPair::generate(0).unwrap();
+    let address = Address::from_public_key(&sender_keypair.get_public_key());
+    let execution_controller = default_mock_execution_controller();
+    let selector_controller = default_mock_selector(address);
+    pool_test(
+        PoolConfig::default(),
+        execution_controller,
+        selector_controller,
+        Some((address, sender_keypair.clone())),
+        |mut pool, mut storage| {
+            let endorsements = vec![
+                create_endorsement(&sender_keypair, 0, Slot::new(1, 2)),
+                create_endorsement(&sender_keypair, 1, Slot::new(1, 2)),
+                create_endorsement(&sender_keypair, 0, Slot::new(1, 3)),
+            ];
+            storage.store_endorsements(endorsements);
+            pool.add_endorsements(storage.clone());
+            // Allow some time for the pool to add the endorsements
+            std::thread::sleep(Duration::from_secs(2));
+
+            let stats = pool.get_pool_stats();
+            assert_eq!(stats.endorsement_count, 3);
+            assert!(stats.oldest_endorsement_age.is_some());
+            assert_eq!(
+                stats.endorsement_slots,
+                vec![
+                    SlotEndorsementCoverage {
+                        slot: Slot::new(1, 2),
+                        endorsement_count: 2,
+                        indexes: vec![0, 1],
+                    },
+                    SlotEndorsementCoverage {
+                        slot: Slot::new(1, 3),
+                        endorsement_count: 1,
+                        indexes: vec![0],
+                    },
+                ]
+            );
+            assert_eq!(stats.denunciation_count, 0);
+            assert!(stats.oldest_denunciation_age.is_none());
+        },
+    );
+}
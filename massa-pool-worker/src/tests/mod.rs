@@ -2,5 +2,6 @@
 
 mod endorsement_pool_tests;
 mod operation_pool_tests;
+mod persistence_tests;
 mod scenario;
 pub(crate) mod tools;
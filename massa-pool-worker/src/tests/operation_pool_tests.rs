@@ -12,6 +12,26 @@
 //! Same as classic but we try to add irrelevant operation. (See the definition
 //! chapter below)
 //!
+//! # Eviction policies
+//! Function: [`test_eviction_policies`]
+//! The operations exceeding the pool size and the per-address cap are evicted
+//! in the order of the eviction policy, which can be changed at runtime.
+//!
+//! # Sender limits
+//! Function: [`test_sender_limits`]
+//! The new operations of a sender exceeding the per-sender limits on the number
+//! and the size of its operations are rejected.
+//!
+//! # Pool inspection
+//! Function: [`test_get_pool_operations`]
+//! The summaries of the operations of the pool are sorted by fee or by age,
+//! and paginated.
+//!
+//! # Local operations rebroadcast
+//! Function: [`test_rebroadcast_local_operations`]
+//! The operations submitted through the API of the node are propagated again
+//! at the refreshes of the pool, up to the max number of attempts.
+//!
 //! # Definition
 //! Relevant operation: Operation with a validity range corresponding to the
 //! latest period given his own thread. All operation which doesn't fit these
@@ -20,11 +40,20 @@
 use crate::tests::tools::OpGenerator;
 
 use super::tools::{
-    create_some_operations, default_mock_execution_controller, pool_test, PoolTestBoilerPlate,
+    create_some_operations, default_mock_execution_controller, default_mock_protocol_controller,
+    pool_test, PoolTestBoilerPlate,
 };
-use massa_models::{amount::Amount, config::ENDORSEMENT_COUNT, operation::OperationId, slot::Slot};
-use massa_pool_exports::PoolConfig;
+use massa_models::{
+    amount::Amount,
+    config::ENDORSEMENT_COUNT,
+    operation::{OperationId, PoolOperationSort},
+    slot::Slot,
+};
+use massa_pool_exports::{OperationEvictionPolicy, PoolConfig, PoolController};
 use massa_pos_exports::{MockSelectorController, Selection};
+use massa_protocol_exports::MockProtocolController;
+use massa_signature::KeyPair;
+use massa_time::MassaTime;
 use std::{collections::BTreeMap, time::Duration};
 
 #[test]
@@ -180,7 +209,12 @@ fn test_pool() {
         mut pool_manager,
         mut pool_controller,
         storage: storage_base,
-    } = PoolTestBoilerPlate::pool_test(pool_config, execution_controller, selector_controller);
+    } = PoolTestBoilerPlate::pool_test(
+        pool_config,
+        execution_controller,
+        selector_controller,
+        default_mock_protocol_controller(),
+    );
 
     // // generate (id, transactions, range of validity) by threads
     let mut thread_tx_lists = vec![Vec::new(); pool_config.thread_count as usize];
@@ -237,3 +271,330 @@ fn test_pool() {
     }
     pool_manager.stop();
 }
+
+#[test]
+fn test_eviction_policies() {
+    let pool_config = PoolConfig {
+        max_operation_pool_size: 2,
+        max_operations_per_address: 1,
+        ..Default::default()
+    };
+    let execution_controller = default_mock_execution_controller();
+    let selector_controller = {
+        let mut res = Box::new(MockSelectorController::new());
+        res.expect_clone_box().times(2).returning(|| {
+            let mut story = MockSelectorController::new();
+            story
+                .expect_get_available_selections_in_range()
+                .returning(|slot_range, opt_addrs| {
+                    let mut all_slots = BTreeMap::new();
+                    let addr = *opt_addrs
+                        .expect("No addresses filter given")
+                        .iter()
+                        .next()
+                        .expect("No addresses given");
+                    for i in 0..15 {
+                        for j in 0..32 {
+                            let s = Slot::new(i, j);
+                            if slot_range.contains(&s) {
+                                all_slots.insert(
+                                    s,
+                                    Selection {
+                                        producer: addr,
+                                        endorsements: vec![addr; ENDORSEMENT_COUNT as usize],
+                                    },
+                                );
+                            }
+                        }
+                    }
+                    Ok(all_slots)
+                });
+            Box::new(story)
+        });
+        res
+    };
+    pool_test(
+        pool_config,
+        execution_controller,
+        selector_controller,
+        None,
+        |mut operation_pool, storage| {
+            let op_gen = OpGenerator::default().expirery(2);
+            let creator = KeyPair::generate(0).unwrap();
+            let op_old = op_gen
+                .clone()
+                .creator(creator.clone())
+                .fee(Amount::const_init(5, 0))
+                .generate();
+            let op_other = op_gen.clone().fee(Amount::const_init(1, 0)).generate();
+            let op_new = op_gen
+                .clone()
+                .creator(creator)
+                .fee(Amount::const_init(1, 0))
+                .generate();
+            let op_new_other = op_gen.clone().fee(Amount::const_init(2, 0)).generate();
+            let op_high_fee = op_gen.clone().fee(Amount::const_init(10, 0)).generate();
+            let add_operations = |operation_pool: &mut Box<dyn PoolController>, ops| {
+                let mut ops_storage = storage.clone_without_refs();
+                ops_storage.store_operations(ops);
+                operation_pool.add_operations(ops_storage);
+                // Allow some time for the pool to add the operations
+                std::thread::sleep(Duration::from_millis(100));
+            };
+
+            // the oldest operations are evicted, the one of highest fee included
+            operation_pool.set_operation_eviction_policy(OperationEvictionPolicy::OldestFirst, 1);
+            add_operations(&mut operation_pool, vec![op_old.clone()]);
+            add_operations(&mut operation_pool, vec![op_other.clone()]);
+            add_operations(
+                &mut operation_pool,
+                vec![op_new.clone(), op_new_other.clone()],
+            );
+            // Allow some time for the pool to be refreshed
+            std::thread::sleep(Duration::from_secs(3));
+            assert_eq!(
+                operation_pool.contains_operations(&[
+                    op_old.id,
+                    op_other.id,
+                    op_new.id,
+                    op_new_other.id
+                ]),
+                vec![false, false, true, true]
+            );
+
+            // the operations paying the lowest fee for their size and gas are evicted
+            operation_pool.set_operation_eviction_policy(OperationEvictionPolicy::FeeDensity, 1);
+            add_operations(&mut operation_pool, vec![op_high_fee.clone()]);
+            std::thread::sleep(Duration::from_secs(3));
+            assert_eq!(
+                operation_pool.contains_operations(&[op_new.id, op_new_other.id, op_high_fee.id]),
+                vec![false, true, true]
+            );
+        },
+    );
+}
+
+#[test]
+fn test_get_pool_operations() {
+    let execution_controller = default_mock_execution_controller();
+    let selector_controller = {
+        let mut res = Box::new(MockSelectorController::new());
+        res.expect_clone_box().times(2).returning(|| {
+            let mut story = MockSelectorController::new();
+            story
+                .expect_get_available_selections_in_range()
+                .returning(|slot_range, opt_addrs| {
+                    let mut all_slots = BTreeMap::new();
+                    let addr = *opt_addrs
+                        .expect("No addresses filter given")
+                        .iter()
+                        .next()
+                        .expect("No addresses given");
+                    for i in 0..15 {
+                        for j in 0..32 {
+                            let s = Slot::new(i, j);
+                            if slot_range.contains(&s) {
+                                all_slots.insert(
+                                    s,
+                                    Selection {
+                                        producer: addr,
+                                        endorsements: vec![addr; ENDORSEMENT_COUNT as usize],
+                                    },
+                                );
+                            }
+                        }
+                    }
+                    Ok(all_slots)
+                });
+            Box::new(story)
+        });
+        res
+    };
+    pool_test(
+        PoolConfig::default(),
+        execution_controller,
+        selector_controller,
+        None,
+        |mut operation_pool, storage| {
+            let op_gen = OpGenerator::default().expirery(2);
+            let op_low_fee = op_gen.clone().fee(Amount::const_init(1, 0)).generate();
+            let op_high_fee = op_gen.clone().fee(Amount::const_init(5, 0)).generate();
+            let op_mid_fee = op_gen.clone().fee(Amount::const_init(2, 0)).generate();
+            for op in [&op_low_fee, &op_high_fee, &op_mid_fee] {
+                let mut ops_storage = storage.clone_without_refs();
+                ops_storage.store_operations(vec![op.clone()]);
+                operation_pool.add_operations(ops_storage);
+                // Allow some time for the pool to add the operation
+                std::thread::sleep(Duration::from_millis(100));
+            }
+
+            let (operations, stats) =
+                operation_pool.get_pool_operations(PoolOperationSort::Fee, 0, 10);
+            let ids: Vec<OperationId> = operations.iter().map(|op| op.id).collect();
+            assert_eq!(ids, vec![op_high_fee.id, op_mid_fee.id, op_low_fee.id]);
+            assert_eq!(operations[0].fee, Amount::const_init(5, 0));
+            assert_eq!(stats.operation_count, 3);
+            assert_eq!(stats.sender_count, 3);
+
+            let (operations, _) = operation_pool.get_pool_operations(PoolOperationSort::Age, 1, 1);
+            let ids: Vec<OperationId> = operations.iter().map(|op| op.id).collect();
+            assert_eq!(ids, vec![op_high_fee.id]);
+        },
+    );
+}
+
+#[test]
+fn test_sender_limits() {
+    let pool_config = PoolConfig {
+        max_sender_operation_count: 2,
+        ..Default::default()
+    };
+    let execution_controller = default_mock_execution_controller();
+    let selector_controller = {
+        let mut res = Box::new(MockSelectorController::new());
+        res.expect_clone_box().times(2).returning(|| {
+            let mut story = MockSelectorController::new();
+            story
+                .expect_get_available_selections_in_range()
+                .returning(|slot_range, opt_addrs| {
+                    let mut all_slots = BTreeMap::new();
+                    let addr = *opt_addrs
+                        .expect("No addresses filter given")
+                        .iter()
+                        .next()
+                        .expect("No addresses given");
+                    for i in 0..15 {
+                        for j in 0..32 {
+                            let s = Slot::new(i, j);
+                            if slot_range.contains(&s) {
+                                all_slots.insert(
+                                    s,
+                                    Selection {
+                                        producer: addr,
+                                        endorsements: vec![addr; ENDORSEMENT_COUNT as usize],
+                                    },
+                                );
+                            }
+                        }
+                    }
+                    Ok(all_slots)
+                });
+            Box::new(story)
+        });
+        res
+    };
+    pool_test(
+        pool_config,
+        execution_controller,
+        selector_controller,
+        None,
+        |mut operation_pool, storage| {
+            let op_gen = OpGenerator::default()
+                .expirery(2)
+                .creator(KeyPair::generate(0).unwrap());
+            let ops = create_some_operations(3, &op_gen);
+            let other_op = OpGenerator::default().expirery(2).generate();
+
+            // the operations beyond the limit are reported before being added
+            assert!(operation_pool.check_sender_limits(&ops[..2]).is_ok());
+            assert!(operation_pool.check_sender_limits(&ops).is_err());
+
+            let mut ops_storage = storage.clone_without_refs();
+            ops_storage.store_operations(ops.clone());
+            operation_pool.add_operations(ops_storage);
+            // Allow some time for the pool to add the operations
+            std::thread::sleep(Duration::from_millis(100));
+            assert_eq!(operation_pool.get_operation_count(), 2);
+
+            // the limit is per sender
+            assert!(operation_pool.check_sender_limits(&ops[2..]).is_err());
+            assert!(operation_pool.check_sender_limits(&[other_op]).is_ok());
+        },
+    );
+}
+
+#[test]
+fn test_rebroadcast_local_operations() {
+    let pool_config = PoolConfig {
+        operation_pool_refresh_interval: MassaTime::from_millis(200),
+        operation_rebroadcast_interval: MassaTime::from_millis(100),
+        operation_rebroadcast_max_attempts: 2,
+        ..Default::default()
+    };
+    let execution_controller = default_mock_execution_controller();
+    let selector_controller = {
+        let mut res = Box::new(MockSelectorController::new());
+        res.expect_clone_box().times(2).returning(|| {
+            let mut story = MockSelectorController::new();
+            story
+                .expect_get_available_selections_in_range()
+                .returning(|slot_range, opt_addrs| {
+                    let mut all_slots = BTreeMap::new();
+                    let addr = *opt_addrs
+                        .expect("No addresses filter given")
+                        .iter()
+                        .next()
+                        .expect("No addresses given");
+                    for i in 0..15 {
+                        for j in 0..32 {
+                            let s = Slot::new(i, j);
+                            if slot_range.contains(&s) {
+                                all_slots.insert(
+                                    s,
+                                    Selection {
+                                        producer: addr,
+                                        endorsements: vec![addr; ENDORSEMENT_COUNT as usize],
+                                    },
+                                );
+                            }
+                        }
+                    }
+                    Ok(all_slots)
+                });
+            Box::new(story)
+        });
+        res
+    };
+    let (propagated_tx, propagated_rx) = crossbeam_channel::unbounded();
+    let protocol_controller = {
+        let mut res = Box::new(MockProtocolController::new());
+        res.expect_clone_box().returning(move || {
+            let propagated_tx = propagated_tx.clone();
+            let mut story = MockProtocolController::new();
+            story
+                .expect_propagate_operations()
+                .returning(move |operations| {
+                    propagated_tx
+                        .send(operations.get_op_refs().clone())
+                        .unwrap();
+                    Ok(())
+                });
+            Box::new(story)
+        });
+        res
+    };
+    let PoolTestBoilerPlate {
+        mut pool_manager,
+        mut pool_controller,
+        storage,
+    } = PoolTestBoilerPlate::pool_test(
+        pool_config,
+        execution_controller,
+        selector_controller,
+        protocol_controller,
+    );
+
+    let op = OpGenerator::default().expirery(2).generate();
+    let mut ops_storage = storage.clone_without_refs();
+    ops_storage.store_operations(vec![op.clone()]);
+    pool_controller.add_local_operations(ops_storage);
+
+    // the operation is propagated again at the refreshes of the pool, up to the max attempts
+    for _ in 0..pool_config.operation_rebroadcast_max_attempts {
+        let propagated = propagated_rx.recv_timeout(Duration::from_secs(2)).unwrap();
+        assert!(propagated.contains(&op.id));
+    }
+    assert!(propagated_rx.recv_timeout(Duration::from_secs(1)).is_err());
+
+    pool_manager.stop();
+}
@@ -0,0 +1,46 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+//! # Pool persistence tests
+//! The operations and endorsements written to a file when the pool stops are read back
+//! when it starts, skipping the ones whose signature is no longer valid.
+
+use super::tools::{create_endorsement, create_some_operations, OpGenerator};
+use crate::persistence::{load_pool, save_pool};
+use massa_models::slot::Slot;
+use massa_pool_exports::PoolConfig;
+use massa_signature::KeyPair;
+use tempfile::tempdir;
+
+#[test]
+fn test_pool_persistence() {
+    let pool_config = PoolConfig::default();
+    let temp_dir = tempdir().expect("Unable to create a temp folder");
+    let path = temp_dir.path().join("pool").join("pool_items.bin");
+
+    let mut operations = create_some_operations(3, &OpGenerator::default().expirery(2));
+    let keypair = KeyPair::generate(0).unwrap();
+    let endorsements = vec![
+        create_endorsement(&keypair, 0, Slot::new(1, 0)),
+        create_endorsement(&keypair, 1, Slot::new(1, 0)),
+    ];
+    // the signature of another operation is not valid for this one
+    operations[2].signature = operations[0].signature;
+
+    save_pool(&path, operations.clone(), endorsements.clone()).unwrap();
+    let (loaded_operations, loaded_endorsements) = load_pool(&path, &pool_config).unwrap();
+    assert_eq!(
+        loaded_operations.iter().map(|op| op.id).collect::<Vec<_>>(),
+        vec![operations[0].id, operations[1].id]
+    );
+    assert_eq!(
+        loaded_endorsements
+            .iter()
+            .map(|endorsement| endorsement.id)
+            .collect::<Vec<_>>(),
+        vec![endorsements[0].id, endorsements[1].id]
+    );
+
+    // a truncated file is rejected
+    let data = std::fs::read(&path).unwrap();
+    std::fs::write(&path, &data[..data.len() - 1]).unwrap();
+    assert!(load_pool(&path, &pool_config).is_err());
+}
@@ -29,6 +29,7 @@ use massa_pos_exports::MockSelectorController;
 use massa_pos_exports::Selection;
 use massa_signature::KeyPair;
 
+use super::tools::default_mock_protocol_controller;
 use super::tools::PoolTestBoilerPlate;
 
 /// # Test simple get operation
@@ -108,7 +109,12 @@ fn test_simple_get_operations() {
         mut pool_manager,
         mut pool_controller,
         mut storage,
-    } = PoolTestBoilerPlate::pool_test(config, execution_controller, selector_controller);
+    } = PoolTestBoilerPlate::pool_test(
+        config,
+        execution_controller,
+        selector_controller,
+        default_mock_protocol_controller(),
+    );
 
     // setup storage
     storage.store_operations(ops);
@@ -238,7 +244,12 @@ fn test_get_operations_overflow() {
         mut pool_manager,
         mut pool_controller,
         mut storage,
-    } = PoolTestBoilerPlate::pool_test(config, execution_controller, selector_controller);
+    } = PoolTestBoilerPlate::pool_test(
+        config,
+        execution_controller,
+        selector_controller,
+        default_mock_protocol_controller(),
+    );
 
     storage.store_operations(create_some_operations(10, &op_gen));
     pool_controller.add_operations(storage);
@@ -1,11 +1,13 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use crate::start_pool_controller;
 use crossbeam_channel as _;
 use massa_execution_exports::MockExecutionController;
 use massa_hash::Hash;
+use massa_metrics::MassaMetrics;
 use massa_models::config::CHAINID;
 use massa_models::{
     address::Address,
@@ -19,13 +21,14 @@ use massa_models::{
 };
 use massa_pool_exports::{PoolBroadcasts, PoolChannels, PoolConfig, PoolController, PoolManager};
 use massa_pos_exports::MockSelectorController as AutoMockSelectorController;
+use massa_protocol_exports::MockProtocolController;
 use massa_signature::KeyPair;
 use massa_storage::Storage;
 use massa_wallet::test_exports::create_test_wallet;
 use parking_lot::RwLock;
 use tokio::sync::broadcast;
 
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub(crate) struct OpGenerator {
     creator: Option<KeyPair>,
     receiver: Option<KeyPair>,
@@ -104,6 +107,7 @@ impl PoolTestBoilerPlate {
         cfg: PoolConfig,
         execution_story: Box<MockExecutionController>,
         selector_story: Box<AutoMockSelectorController>,
+        protocol_story: Box<MockProtocolController>,
     ) -> Self {
         let storage: Storage = Storage::create_root();
         let keypair = KeyPair::generate(0).unwrap();
@@ -123,8 +127,17 @@ impl PoolTestBoilerPlate {
                     operation_sender,
                 },
                 selector: selector_story,
+                protocol: protocol_story,
             },
             wallet,
+            MassaMetrics::new(
+                false,
+                "0.0.0.0:9898".parse().unwrap(),
+                cfg.thread_count,
+                Duration::from_secs(1),
+            )
+            .0,
+            None,
         );
 
         Self {
@@ -165,8 +178,17 @@ pub fn pool_test<F>(
                 operation_sender,
             },
             selector,
+            protocol: default_mock_protocol_controller(),
         },
         wallet,
+        MassaMetrics::new(
+            false,
+            "0.0.0.0:9898".parse().unwrap(),
+            cfg.thread_count,
+            Duration::from_secs(1),
+        )
+        .0,
+        None,
     );
     test(pool_controller, storage);
     pool_manager.stop();
@@ -218,3 +240,13 @@ pub fn default_mock_execution_controller() -> Box<MockExecutionController> {
     });
     res
 }
+
+pub fn default_mock_protocol_controller() -> Box<MockProtocolController> {
+    let mut res = Box::new(MockProtocolController::new());
+    res.expect_clone_box().returning(|| {
+        let mut story = MockProtocolController::new();
+        story.expect_propagate_operations().returning(|_| Ok(()));
+        Box::new(story)
+    });
+    res
+}
@@ -1,8 +1,9 @@
 use massa_models::{
     address::Address,
     amount::Amount,
-    operation::{OperationId, SecureShareOperation},
+    operation::{OperationId, OperationKind, SecureShareOperation},
 };
+use massa_time::MassaTime;
 use std::ops::RangeInclusive;
 
 #[derive(Debug, Clone)]
@@ -17,6 +18,12 @@ pub struct OperationInfo {
     /// max amount that the op might spend from the sender's balance
     pub max_spending: Amount,
     pub validity_period_range: RangeInclusive<u64>,
+    /// order of entry in the pool, increasing with the time the op was added
+    pub entry_index: u64,
+    /// time at which the op was added to the pool
+    pub added_at: MassaTime,
+    /// kind of the op
+    pub kind: OperationKind,
 }
 
 impl OperationInfo {
@@ -27,6 +34,7 @@ impl OperationInfo {
         thread_count: u8,
         base_operation_gas_cost: u64,
         sp_compilation_cost: u64,
+        entry_index: u64,
     ) -> Self {
         OperationInfo {
             id: op.id,
@@ -37,6 +45,9 @@ impl OperationInfo {
             thread: op.content_creator_address.get_thread(thread_count),
             validity_period_range: op.get_validity_range(operation_validity_periods),
             max_spending: op.get_max_spending(roll_price),
+            entry_index,
+            added_at: MassaTime::now(),
+            kind: OperationKind::from(&op.content.op),
         }
     }
 }
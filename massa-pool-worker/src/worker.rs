@@ -5,12 +5,15 @@
 use crate::controller_impl::{Command, PoolManagerImpl};
 use crate::denunciation_pool::DenunciationPool;
 use crate::operation_pool::OperationPool;
+use crate::persistence::load_pool;
 use crate::{controller_impl::PoolControllerImpl, endorsement_pool::EndorsementPool};
+use massa_metrics::MassaMetrics;
 use massa_pool_exports::PoolConfig;
 use massa_pool_exports::{PoolChannels, PoolController, PoolManager};
 use massa_storage::Storage;
 use massa_wallet::Wallet;
 use parking_lot::RwLock;
+use std::path::PathBuf;
 use std::time::Instant;
 use std::{
     sync::mpsc::{sync_channel, Receiver, RecvError, RecvTimeoutError},
@@ -18,7 +21,7 @@ use std::{
     thread,
     thread::JoinHandle,
 };
-use tracing::warn;
+use tracing::{info, warn};
 
 /// Endorsement pool write thread instance
 pub(crate) struct EndorsementPoolThread {
@@ -109,6 +112,9 @@ impl OperationPoolThread {
                     Ok(Command::AddItems(operations)) => {
                         self.operation_pool.write().add_operations(operations)
                     }
+                    Ok(Command::AddLocalOperations(operations)) => {
+                        self.operation_pool.write().add_local_operations(operations)
+                    }
                     Ok(Command::NotifyFinalCsPeriods(final_cs_periods)) => self
                         .operation_pool
                         .write()
@@ -120,7 +126,9 @@ impl OperationPoolThread {
                     Err(RecvTimeoutError::Timeout) => {}
                 };
             } else {
-                self.operation_pool.write().refresh();
+                let mut operation_pool = self.operation_pool.write();
+                operation_pool.refresh();
+                operation_pool.rebroadcast_local_operations();
                 start_time = Instant::now();
             }
         }
@@ -187,6 +195,8 @@ pub fn start_pool_controller(
     storage: &Storage,
     channels: PoolChannels,
     wallet: Arc<RwLock<Wallet>>,
+    massa_metrics: MassaMetrics,
+    persistence_path: Option<PathBuf>,
 ) -> (Box<dyn PoolManager>, Box<dyn PoolController>) {
     let (operations_input_sender, operations_input_receiver) =
         sync_channel(config.operations_channel_size);
@@ -199,6 +209,7 @@ pub fn start_pool_controller(
         storage,
         channels.clone(),
         wallet.clone(),
+        massa_metrics,
     )));
     let endorsement_pool = Arc::new(RwLock::new(EndorsementPool::init(
         config,
@@ -207,8 +218,35 @@ pub fn start_pool_controller(
         wallet,
     )));
     let denunciation_pool = Arc::new(RwLock::new(DenunciationPool::init(config, channels)));
+
+    // reload the operations and endorsements persisted when the node last stopped
+    if let Some(path) = persistence_path.as_ref().filter(|path| path.exists()) {
+        match load_pool(path, &config) {
+            Ok((operations, endorsements)) => {
+                info!(
+                    "reloading {} operations and {} endorsements into the pool",
+                    operations.len(),
+                    endorsements.len()
+                );
+                let mut ops_storage = storage.clone_without_refs();
+                ops_storage.store_operations(operations);
+                operation_pool.write().add_operations(ops_storage);
+                let mut endorsements_storage = storage.clone_without_refs();
+                endorsements_storage.store_endorsements(endorsements);
+                endorsement_pool
+                    .write()
+                    .add_endorsements(endorsements_storage);
+            }
+            Err(err) => warn!("failed to reload the pool from {}: {}", path.display(), err),
+        }
+        // the file is consumed, so that a crash does not reload outdated items
+        if let Err(err) = std::fs::remove_file(path) {
+            warn!("failed to remove {}: {}", path.display(), err);
+        }
+    }
+
     let controller = PoolControllerImpl {
-        _config: config,
+        config,
         operation_pool: operation_pool.clone(),
         endorsement_pool: endorsement_pool.clone(),
         denunciation_pool: denunciation_pool.clone(),
@@ -219,9 +257,9 @@ pub fn start_pool_controller(
     };
 
     let operations_thread_handle =
-        OperationPoolThread::spawn(operations_input_receiver, operation_pool, config);
+        OperationPoolThread::spawn(operations_input_receiver, operation_pool.clone(), config);
     let endorsements_thread_handle =
-        EndorsementPoolThread::spawn(endorsements_input_receiver, endorsement_pool);
+        EndorsementPoolThread::spawn(endorsements_input_receiver, endorsement_pool.clone());
     let denunciations_thread_handle =
         DenunciationPoolThread::spawn(denunciations_input_receiver, denunciation_pool);
 
@@ -232,6 +270,9 @@ pub fn start_pool_controller(
         operations_input_sender,
         endorsements_input_sender,
         denunciations_input_sender,
+        operation_pool,
+        endorsement_pool,
+        persistence_path,
     };
     (Box::new(manager), Box::new(controller))
 }
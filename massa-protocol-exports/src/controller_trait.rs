@@ -4,9 +4,12 @@ use std::collections::HashMap;
 use std::net::SocketAddr;
 
 use crate::error::ProtocolError;
+use crate::BandwidthLimits;
 use crate::BootstrapPeers;
+use crate::IpCidr;
 
 use crate::PeerId;
+use crate::PeerReputation;
 use massa_models::prehash::{PreHashMap, PreHashSet};
 use massa_models::stats::NetworkStats;
 use massa_models::{block_header::SecuredHeader, block_id::BlockId};
@@ -81,6 +84,41 @@ pub trait ProtocolController: Send + Sync {
     /// Unban a list of Peer Id
     fn unban_peers(&self, peer_ids: Vec<PeerId>) -> Result<(), ProtocolError>;
 
+    /// Get the peers known by the node, with their reputation
+    fn get_peers(&self) -> Result<HashMap<PeerId, PeerReputation>, ProtocolError>;
+
+    /// Ban IP ranges and autonomous systems.
+    /// Connected peers matching the bans are disconnected and no connection is accepted from or attempted to them.
+    ///
+    /// # Arguments
+    /// * `ranges`: IP ranges to ban
+    /// * `asns`: autonomous system numbers to ban, resolved using the configured ASN database
+    fn ban_ips(&self, ranges: Vec<IpCidr>, asns: Vec<u32>) -> Result<(), ProtocolError>;
+
+    /// Unban IP ranges and autonomous systems
+    ///
+    /// # Arguments
+    /// * `ranges`: IP ranges to unban
+    /// * `asns`: autonomous system numbers to unban
+    fn unban_ips(&self, ranges: Vec<IpCidr>, asns: Vec<u32>) -> Result<(), ProtocolError>;
+
+    /// Get the allowed IP ranges. When there are some, no connection is accepted from or attempted
+    /// to the peers outside of them.
+    fn get_allowed_ips(&self) -> Result<Vec<IpCidr>, ProtocolError>;
+
+    /// Add IP ranges to the allowed ones.
+    /// Once a range is allowed, connected peers outside of the allowed ranges are disconnected.
+    fn allow_ips(&self, ranges: Vec<IpCidr>) -> Result<(), ProtocolError>;
+
+    /// Remove IP ranges from the allowed ones
+    fn disallow_ips(&self, ranges: Vec<IpCidr>) -> Result<(), ProtocolError>;
+
+    /// Get the current bandwidth limits of the node
+    fn get_bandwidth_limits(&self) -> Result<BandwidthLimits, ProtocolError>;
+
+    /// Change the bandwidth limits of the node, applied to the traffic from now on
+    fn set_bandwidth_limits(&self, limits: BandwidthLimits) -> Result<(), ProtocolError>;
+
     /// Returns a boxed clone of self.
     /// Useful to allow cloning `Box<dyn ProtocolController>`.
     fn clone_box(&self) -> Box<dyn ProtocolController>;
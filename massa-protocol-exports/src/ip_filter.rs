@@ -0,0 +1,332 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! IP range (CIDR) and autonomous system (ASN) based filtering of peers.
+//!
+//! The filter is persisted to a JSON file that is watched by the protocol, so that it can be edited
+//! while the node runs:
+//! `{ "ranges": ["10.0.0.0/8"], "asns": [64512], "allowed_ranges": ["192.168.0.0/16"] }`
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::Display,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    path::Path,
+    str::FromStr,
+    time::SystemTime,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::ProtocolError;
+
+/// A range of IP addresses expressed in CIDR notation (ex: `192.168.0.0/16`, `2001:db8::/32`).
+/// A single IP address without prefix length is a range containing only this address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct IpCidr {
+    /// network address, with all the bits outside of the prefix set to zero
+    network: IpAddr,
+    /// number of leading bits of the prefix
+    prefix_len: u8,
+}
+
+impl IpCidr {
+    /// Creates a new range, masking the bits of `addr` that are outside of the prefix
+    pub fn new(addr: IpAddr, prefix_len: u8) -> Result<Self, ProtocolError> {
+        let addr = to_canonical(addr);
+        let max_len = match addr {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        };
+        if prefix_len > max_len {
+            return Err(ProtocolError::GeneralProtocolError(format!(
+                "invalid prefix length {} for address {}",
+                prefix_len, addr
+            )));
+        }
+        let network = match addr {
+            IpAddr::V4(v4) => {
+                let mask = u32::MAX.checked_shl(32 - prefix_len as u32).unwrap_or(0);
+                IpAddr::V4(Ipv4Addr::from(u32::from(v4) & mask))
+            }
+            IpAddr::V6(v6) => {
+                let mask = u128::MAX.checked_shl(128 - prefix_len as u32).unwrap_or(0);
+                IpAddr::V6(Ipv6Addr::from(u128::from(v6) & mask))
+            }
+        };
+        Ok(IpCidr {
+            network,
+            prefix_len,
+        })
+    }
+
+    /// Network address of the range
+    pub fn network(&self) -> IpAddr {
+        self.network
+    }
+
+    /// Prefix length of the range
+    pub fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+
+    /// Returns true if `ip` belongs to the range.
+    /// IPv4-mapped IPv6 addresses are matched against IPv4 ranges.
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+        match IpCidr::new(*ip, self.prefix_len) {
+            Ok(other) => other.network == self.network,
+            // `ip` is an IPv4 address and `self` an IPv6 range with a longer prefix
+            Err(_) => false,
+        }
+    }
+}
+
+impl FromStr for IpCidr {
+    type Err = ProtocolError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ProtocolError::GeneralProtocolError(format!("invalid IP range: {}", s));
+        match s.split_once('/') {
+            Some((addr, prefix_len)) => IpCidr::new(
+                addr.trim().parse().map_err(|_| invalid())?,
+                prefix_len.trim().parse().map_err(|_| invalid())?,
+            ),
+            None => {
+                let addr = to_canonical(s.trim().parse().map_err(|_| invalid())?);
+                let prefix_len = if addr.is_ipv4() { 32 } else { 128 };
+                IpCidr::new(addr, prefix_len)
+            }
+        }
+    }
+}
+
+impl Display for IpCidr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.network, self.prefix_len)
+    }
+}
+
+impl TryFrom<String> for IpCidr {
+    type Error = ProtocolError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        IpCidr::from_str(&value)
+    }
+}
+
+impl From<IpCidr> for String {
+    fn from(value: IpCidr) -> Self {
+        value.to_string()
+    }
+}
+
+/// List of banned IP ranges and ASNs, and of allowed IP ranges, persisted to disk by the protocol.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct IpBanList {
+    /// banned IP ranges
+    pub ranges: BTreeSet<IpCidr>,
+    /// banned autonomous system numbers
+    pub asns: BTreeSet<u32>,
+    /// allowed IP ranges: when not empty, the IPs outside of these ranges are banned
+    #[serde(default)]
+    pub allowed_ranges: BTreeSet<IpCidr>,
+    /// IP ranges announced by each ASN, loaded from the ASN database
+    #[serde(skip)]
+    asn_ranges: HashMap<u32, Vec<IpCidr>>,
+    /// modification time of the ban list file when it was last loaded
+    #[serde(skip)]
+    file_modified: Option<SystemTime>,
+}
+
+impl IpBanList {
+    /// Loads the ban list from `ban_list_file` (an empty list is used if the file does not exist)
+    /// and the ASN to IP ranges mapping from `asn_database_file`, if any.
+    ///
+    /// The ASN database is a JSON object mapping each ASN to the list of ranges it announces:
+    /// `{ "13335": ["104.16.0.0/13", "2606:4700::/32"] }`
+    pub fn load(
+        ban_list_file: &Path,
+        asn_database_file: Option<&Path>,
+    ) -> Result<Self, ProtocolError> {
+        let mut ban_list = if ban_list_file.is_file() {
+            serde_json::from_str::<IpBanList>(&std::fs::read_to_string(ban_list_file)?)?
+        } else {
+            IpBanList::default()
+        };
+        ban_list.file_modified = file_modified(ban_list_file);
+        if let Some(asn_database_file) = asn_database_file {
+            ban_list.asn_ranges =
+                serde_json::from_str(&std::fs::read_to_string(asn_database_file)?)?;
+        }
+        Ok(ban_list)
+    }
+
+    /// Writes the ban list (without the ASN database) to `ban_list_file`
+    pub fn save(&self, ban_list_file: &Path) -> Result<(), ProtocolError> {
+        std::fs::write(ban_list_file, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Reloads the ban list from `ban_list_file` if the file was modified since it was last loaded,
+    /// keeping the ASN database. Returns true if the list changed.
+    pub fn reload_if_modified(&mut self, ban_list_file: &Path) -> Result<bool, ProtocolError> {
+        let modified = file_modified(ban_list_file);
+        if modified == self.file_modified {
+            return Ok(false);
+        }
+        let loaded = IpBanList::load(ban_list_file, None)?;
+        self.file_modified = modified;
+        // the file is also modified when the node saves it
+        if loaded.ranges == self.ranges
+            && loaded.asns == self.asns
+            && loaded.allowed_ranges == self.allowed_ranges
+        {
+            return Ok(false);
+        }
+        self.ranges = loaded.ranges;
+        self.asns = loaded.asns;
+        self.allowed_ranges = loaded.allowed_ranges;
+        Ok(true)
+    }
+
+    /// Returns true if the ASN database knows the given ASN
+    pub fn is_asn_known(&self, asn: u32) -> bool {
+        self.asn_ranges.contains_key(&asn)
+    }
+
+    /// Adds ranges and ASNs to the list
+    pub fn ban(&mut self, ranges: &[IpCidr], asns: &[u32]) {
+        self.ranges.extend(ranges.iter().copied());
+        self.asns.extend(asns.iter().copied());
+    }
+
+    /// Removes ranges and ASNs from the list
+    pub fn unban(&mut self, ranges: &[IpCidr], asns: &[u32]) {
+        for range in ranges {
+            self.ranges.remove(range);
+        }
+        for asn in asns {
+            self.asns.remove(asn);
+        }
+    }
+
+    /// Adds ranges to the allowed ones
+    pub fn allow(&mut self, ranges: &[IpCidr]) {
+        self.allowed_ranges.extend(ranges.iter().copied());
+    }
+
+    /// Removes ranges from the allowed ones
+    pub fn disallow(&mut self, ranges: &[IpCidr]) {
+        for range in ranges {
+            self.allowed_ranges.remove(range);
+        }
+    }
+
+    /// Returns true if `ip` belongs to a banned range or to a range of a banned ASN,
+    /// or if there are allowed ranges and `ip` belongs to none of them
+    pub fn is_banned(&self, ip: &IpAddr) -> bool {
+        let allowed = self.allowed_ranges.is_empty()
+            || self.allowed_ranges.iter().any(|range| range.contains(ip));
+        !allowed
+            || self.ranges.iter().any(|range| range.contains(ip))
+            || self.asns.iter().any(|asn| {
+                self.asn_ranges
+                    .get(asn)
+                    .map(|ranges| ranges.iter().any(|range| range.contains(ip)))
+                    .unwrap_or(false)
+            })
+    }
+}
+
+fn file_modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+fn to_canonical(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+            Some(v4) => IpAddr::V4(v4),
+            None => IpAddr::V6(v6),
+        },
+        v4 => v4,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ip_cidr_parse_and_contains() {
+        let range = IpCidr::from_str("192.168.12.34/16").unwrap();
+        assert_eq!(range.to_string(), "192.168.0.0/16");
+        assert!(range.contains(&"192.168.255.1".parse().unwrap()));
+        assert!(range.contains(&"::ffff:192.168.1.1".parse().unwrap()));
+        assert!(!range.contains(&"192.169.0.1".parse().unwrap()));
+        assert!(!range.contains(&"2001:db8::1".parse().unwrap()));
+
+        let single = IpCidr::from_str("2001:db8::1").unwrap();
+        assert_eq!(single.prefix_len(), 128);
+        assert!(single.contains(&"2001:db8::1".parse().unwrap()));
+        assert!(!single.contains(&"2001:db8::2".parse().unwrap()));
+
+        let everything = IpCidr::from_str("0.0.0.0/0").unwrap();
+        assert!(everything.contains(&"8.8.8.8".parse().unwrap()));
+
+        assert!(IpCidr::from_str("10.0.0.0/33").is_err());
+        assert!(IpCidr::from_str("not an ip").is_err());
+    }
+
+    #[test]
+    fn test_ip_ban_list_asn() {
+        let mut ban_list = IpBanList::default();
+        ban_list
+            .asn_ranges
+            .insert(64512, vec![IpCidr::from_str("10.0.0.0/8").unwrap()]);
+        let ip = "10.1.2.3".parse().unwrap();
+        assert!(!ban_list.is_banned(&ip));
+        ban_list.ban(&[], &[64512]);
+        assert!(ban_list.is_banned(&ip));
+        ban_list.unban(&[], &[64512]);
+        assert!(!ban_list.is_banned(&ip));
+
+        ban_list.ban(&[IpCidr::from_str("10.1.0.0/16").unwrap()], &[]);
+        let serialized = serde_json::to_string(&ban_list).unwrap();
+        let deserialized: IpBanList = serde_json::from_str(&serialized).unwrap();
+        assert!(deserialized.is_banned(&ip));
+    }
+
+    #[test]
+    fn test_ip_ban_list_allowed_ranges() {
+        let mut ban_list = IpBanList::default();
+        let ip = "10.1.2.3".parse().unwrap();
+        ban_list.allow(&[IpCidr::from_str("192.168.0.0/16").unwrap()]);
+        assert!(ban_list.is_banned(&ip));
+        assert!(!ban_list.is_banned(&"192.168.1.1".parse().unwrap()));
+        ban_list.allow(&[IpCidr::from_str("10.0.0.0/8").unwrap()]);
+        assert!(!ban_list.is_banned(&ip));
+        // the banned ranges take precedence over the allowed ones
+        ban_list.ban(&[IpCidr::from_str("10.1.0.0/16").unwrap()], &[]);
+        assert!(ban_list.is_banned(&ip));
+        ban_list.unban(&[IpCidr::from_str("10.1.0.0/16").unwrap()], &[]);
+        ban_list.disallow(&[IpCidr::from_str("10.0.0.0/8").unwrap()]);
+        assert!(ban_list.is_banned(&ip));
+    }
+
+    #[test]
+    fn test_ip_ban_list_reload() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let mut ban_list = IpBanList::default();
+        let ip = "10.1.2.3".parse().unwrap();
+        assert!(!ban_list.is_banned(&ip));
+
+        let mut edited = IpBanList::default();
+        edited.ban(&[IpCidr::from_str("10.0.0.0/8").unwrap()], &[]);
+        edited.save(file.path()).unwrap();
+        assert!(ban_list.reload_if_modified(file.path()).unwrap());
+        assert!(ban_list.is_banned(&ip));
+        assert!(!ban_list.reload_if_modified(file.path()).unwrap());
+    }
+}
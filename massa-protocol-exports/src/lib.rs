@@ -1,18 +1,24 @@
 mod bootstrap_peers;
 mod controller_trait;
 mod error;
+mod ip_filter;
 mod peer_id;
+mod peer_score;
 mod settings;
+mod socks5;
 
 pub use bootstrap_peers::{
     BootstrapPeers, BootstrapPeersDeserializer, BootstrapPeersSerializer, PeerData,
 };
 pub use controller_trait::{ProtocolController, ProtocolManager};
 pub use error::ProtocolError;
+pub use ip_filter::{IpBanList, IpCidr};
 pub use peer_id::{PeerId, PeerIdDeserializer, PeerIdSerializer};
+pub use peer_score::{PeerReputation, PeerScore, PeerScoreEvent, MAX_PEER_SCORE};
 pub use peernet::peer::PeerConnectionType;
 pub use peernet::transports::TransportType;
-pub use settings::{PeerCategoryInfo, ProtocolConfig};
+pub use settings::{BandwidthLimits, PeerCategoryInfo, ProtocolConfig};
+pub use socks5::Socks5Proxy;
 
 #[cfg(any(test, feature = "test-exports"))]
 pub mod test_exports;
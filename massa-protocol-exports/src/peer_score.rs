@@ -0,0 +1,165 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Reputation of the peers.
+//!
+//! Every peer has a score, lowered when it misbehaves and raised when it is the first to send
+//! us new blocks or operations. The score decays exponentially towards 0, so that the old
+//! behaviors of a peer are progressively forgotten. The peers with a score below the throttle
+//! threshold are neither connected to nor advertised to the others, and the ones with a score
+//! below the ban threshold are banned.
+
+use std::net::SocketAddr;
+
+use massa_time::MassaTime;
+
+/// Highest score of a peer, so that a useful peer cannot build up credit for misbehaving later
+pub const MAX_PEER_SCORE: f64 = 100.0;
+
+/// Behavior of a peer changing its score
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeerScoreEvent {
+    /// the peer sent us a message that is invalid
+    InvalidMessage,
+    /// the peer did not answer one of our requests in time
+    SlowResponse,
+    /// the peer sent us data we did not ask for
+    Spam,
+    /// the peer was the first to send us a block header
+    NewBlock,
+    /// the peer was the first to send us some operations
+    NewOperations,
+}
+
+impl PeerScoreEvent {
+    /// Change of the score of the peer caused by the event
+    pub fn score_change(&self) -> f64 {
+        match self {
+            PeerScoreEvent::InvalidMessage => -20.0,
+            PeerScoreEvent::SlowResponse => -2.0,
+            PeerScoreEvent::Spam => -5.0,
+            PeerScoreEvent::NewBlock => 2.0,
+            PeerScoreEvent::NewOperations => 0.5,
+        }
+    }
+}
+
+/// Score of a peer, with the count of the events that changed it
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PeerScore {
+    /// score at the last update
+    pub score: f64,
+    /// time of the last update, `None` if the score was never updated
+    pub last_update: Option<MassaTime>,
+    /// number of invalid messages received from the peer
+    pub invalid_message_count: u64,
+    /// number of our requests the peer did not answer in time
+    pub slow_response_count: u64,
+    /// number of unrequested messages received from the peer
+    pub spam_count: u64,
+    /// number of block headers first received from the peer
+    pub new_block_count: u64,
+    /// number of batches of new operations first received from the peer
+    pub new_operations_count: u64,
+}
+
+impl PeerScore {
+    /// Score at `now`, after its decay since the last update
+    ///
+    /// # Arguments
+    /// * `now`: current time
+    /// * `half_life`: time after which a score is halved
+    pub fn get_score(&self, now: MassaTime, half_life: MassaTime) -> f64 {
+        match self.last_update {
+            Some(last_update) => {
+                let elapsed = now.saturating_sub(last_update).as_millis() as f64;
+                let half_lives = elapsed / half_life.as_millis().max(1) as f64;
+                self.score * 0.5f64.powf(half_lives)
+            }
+            None => self.score,
+        }
+    }
+
+    /// Apply the change caused by an event to the score
+    ///
+    /// # Arguments
+    /// * `event`: behavior of the peer
+    /// * `now`: time of the event
+    /// * `half_life`: time after which a score is halved
+    ///
+    /// # Returns
+    /// The new score
+    pub fn apply_event(
+        &mut self,
+        event: PeerScoreEvent,
+        now: MassaTime,
+        half_life: MassaTime,
+    ) -> f64 {
+        let count = match event {
+            PeerScoreEvent::InvalidMessage => &mut self.invalid_message_count,
+            PeerScoreEvent::SlowResponse => &mut self.slow_response_count,
+            PeerScoreEvent::Spam => &mut self.spam_count,
+            PeerScoreEvent::NewBlock => &mut self.new_block_count,
+            PeerScoreEvent::NewOperations => &mut self.new_operations_count,
+        };
+        *count = count.saturating_add(1);
+        let score = self.get_score(now, half_life) + event.score_change();
+        self.score = score.min(MAX_PEER_SCORE);
+        self.last_update = Some(now);
+        self.score
+    }
+}
+
+/// Reputation of a peer known by the node
+#[derive(Debug, Clone, PartialEq)]
+pub struct PeerReputation {
+    /// score of the peer, as of its last update
+    pub score: PeerScore,
+    /// score of the peer, decayed up to the time of the request
+    pub current_score: f64,
+    /// whether the peer is throttled because of its low score
+    pub throttled: bool,
+    /// whether the peer is banned
+    pub banned: bool,
+    /// addresses of the listeners announced by the peer
+    pub listeners: Vec<SocketAddr>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_peer_score() {
+        let half_life = MassaTime::from_millis(1000);
+        let start = MassaTime::from_millis(10_000);
+        let mut score = PeerScore::default();
+        assert_eq!(score.get_score(start, half_life), 0.0);
+
+        assert_eq!(
+            score.apply_event(PeerScoreEvent::InvalidMessage, start, half_life),
+            -20.0
+        );
+        assert_eq!(
+            score.apply_event(PeerScoreEvent::Spam, start, half_life),
+            -25.0
+        );
+        assert_eq!(score.invalid_message_count, 1);
+        assert_eq!(score.spam_count, 1);
+
+        // the score is halved after each half-life
+        let later = start.saturating_add(MassaTime::from_millis(2000));
+        assert_eq!(score.get_score(later, half_life), -6.25);
+        assert_eq!(
+            score.apply_event(PeerScoreEvent::NewBlock, later, half_life),
+            -4.25
+        );
+        assert_eq!(score.last_update, Some(later));
+
+        // the score is capped
+        for _ in 0..100 {
+            score.apply_event(PeerScoreEvent::NewBlock, later, half_life);
+        }
+        assert_eq!(score.get_score(later, half_life), MAX_PEER_SCORE);
+        assert_eq!(score.new_block_count, 101);
+    }
+}
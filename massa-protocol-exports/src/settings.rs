@@ -6,10 +6,11 @@ use std::{
     path::PathBuf,
 };
 
+use crate::Socks5Proxy;
 use massa_models::version::Version;
 use massa_time::MassaTime;
 use peernet::transports::TransportType;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize, Clone, Copy)]
 pub struct PeerCategoryInfo {
@@ -19,6 +20,19 @@ pub struct PeerCategoryInfo {
     pub max_in_connections_per_ip: usize,
 }
 
+/// Bandwidth limits of the node, in bytes per second. A limit of 0 disables it.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub struct BandwidthLimits {
+    /// max upload rate to each peer
+    pub peer_upload: u64,
+    /// max download rate from each peer
+    pub peer_download: u64,
+    /// max upload rate to all the peers
+    pub global_upload: u64,
+    /// max download rate from all the peers
+    pub global_download: u64,
+}
+
 /// Dynamic protocol configuration mix in static settings and constants configurations.
 #[derive(Debug, Deserialize, Clone)]
 pub struct ProtocolConfig {
@@ -28,6 +42,11 @@ pub struct ProtocolConfig {
     pub listeners: HashMap<SocketAddr, TransportType>,
     /// initial peers path
     pub initial_peers: PathBuf,
+    /// path of the file where banned IP ranges and ASNs, and allowed IP ranges, are persisted.
+    /// It is reloaded when modified.
+    pub ip_ban_list_file: PathBuf,
+    /// optional path of the database mapping ASNs to the IP ranges they announce
+    pub asn_database_file: Option<PathBuf>,
     /// after `ask_block_timeout` milliseconds we try to ask a block to another node
     pub ask_block_timeout: MassaTime,
     /// Max known blocks we keep during their propagation
@@ -150,6 +169,12 @@ pub struct ProtocolConfig {
     pub try_connection_timer_same_peer: MassaTime,
     /// periodically unban every peer
     pub unban_everyone_timer: MassaTime,
+    /// time after which the score of a peer is halved
+    pub peer_score_half_life: MassaTime,
+    /// peers with a lower score are neither connected to nor advertised to the others
+    pub peer_score_throttle_threshold: f64,
+    /// peers with a lower score are banned
+    pub peer_score_ban_threshold: f64,
     /// Max in connections
     pub max_in_connections: usize,
     /// Timeout connection
@@ -162,6 +187,8 @@ pub struct ProtocolConfig {
     pub read_write_limit_bytes_per_second: u128,
     /// Optional routable ip
     pub routable_ip: Option<IpAddr>,
+    /// other routable ips, typically the one of the other IP family than `routable_ip`
+    pub additional_routable_ips: Vec<IpAddr>,
     /// debug prints
     pub debug: bool,
     /// Peers categories infos
@@ -174,6 +201,36 @@ pub struct ProtocolConfig {
     pub test_oldest_peer_cooldown: MassaTime,
     /// Rate limit to apply on the data stream
     pub rate_limit: u64,
+    /// Initial bandwidth limits, that can be changed at runtime
+    pub bandwidth_limits: BandwidthLimits,
+    /// map the ports of the listeners on the router through UPnP or NAT-PMP,
+    /// and announce the external IP when `routable_ip` is not set
+    pub port_mapping: bool,
+    /// duration of the port mappings, renewed at half of it
+    pub port_mapping_lease_duration: MassaTime,
+    /// the node is considered unreachable when it did not accept any connection for this long
+    pub reachability_timeout: MassaTime,
+    /// DNS names resolved to the addresses of peers to test, with an optional port
+    pub dns_seeds: Vec<String>,
+    /// interval between two resolutions of the DNS seeds
+    pub dns_seed_refresh_interval: MassaTime,
+    /// ask the peers for the prefixes of the operation ids of the blocks, and rebuild the blocks
+    /// from the operations we know, rather than asking for the full operation ids
+    pub compact_block_relay: bool,
+    /// SOCKS5 proxy through which the outbound connections to the peers are made.
+    /// Our listeners are then only announced with the explicitly configured routable IPs.
+    pub proxy: Option<Socks5Proxy>,
     /// Chain id
     pub chain_id: u64,
 }
+
+impl ProtocolConfig {
+    /// All the IPs at which the other peers can reach us
+    pub fn routable_ips(&self) -> Vec<IpAddr> {
+        self.routable_ip
+            .iter()
+            .chain(self.additional_routable_ips.iter())
+            .copied()
+            .collect()
+    }
+}
@@ -0,0 +1,268 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Minimal SOCKS5 client (RFC 1928) used to route the outbound connections of the node through a
+//! proxy, typically a Tor daemon. Only the `CONNECT` command is supported, either without
+//! authentication or with a username and a password (RFC 1929).
+
+use std::{
+    io::{self, Read, Write},
+    net::{IpAddr, SocketAddr, TcpStream},
+    time::Duration,
+};
+
+use serde::Deserialize;
+
+const SOCKS_VERSION: u8 = 0x05;
+const METHOD_NO_AUTH: u8 = 0x00;
+const METHOD_PASSWORD: u8 = 0x02;
+const PASSWORD_AUTH_VERSION: u8 = 0x01;
+const COMMAND_CONNECT: u8 = 0x01;
+const ADDRESS_IPV4: u8 = 0x01;
+const ADDRESS_DOMAIN: u8 = 0x03;
+const ADDRESS_IPV6: u8 = 0x04;
+
+/// SOCKS5 proxy through which the outbound connections are made
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct Socks5Proxy {
+    /// address of the proxy
+    pub address: SocketAddr,
+    /// username to authenticate with, if the proxy requires it
+    #[serde(default)]
+    pub username: Option<String>,
+    /// password to authenticate with, used along with `username`
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+impl Socks5Proxy {
+    /// Open a TCP connection to `target` through the proxy
+    ///
+    /// # Arguments
+    /// * `target`: address to connect to
+    /// * `timeout`: bounds the connection to the proxy and each step of the SOCKS5 handshake
+    pub fn connect(&self, target: SocketAddr, timeout: Option<Duration>) -> io::Result<TcpStream> {
+        let mut stream = match timeout {
+            Some(timeout) => TcpStream::connect_timeout(&self.address, timeout)?,
+            None => TcpStream::connect(self.address)?,
+        };
+        stream.set_read_timeout(timeout)?;
+        stream.set_write_timeout(timeout)?;
+        self.handshake(&mut stream, target)?;
+        stream.set_read_timeout(None)?;
+        stream.set_write_timeout(None)?;
+        Ok(stream)
+    }
+
+    /// Negotiate the authentication with the proxy, then ask it to connect to `target`
+    fn handshake<S: Read + Write>(&self, stream: &mut S, target: SocketAddr) -> io::Result<()> {
+        let method = if self.username.is_some() {
+            METHOD_PASSWORD
+        } else {
+            METHOD_NO_AUTH
+        };
+        stream.write_all(&[SOCKS_VERSION, 1, method])?;
+        let mut reply = [0u8; 2];
+        stream.read_exact(&mut reply)?;
+        if reply[0] != SOCKS_VERSION {
+            return Err(socks_error(format!("unexpected version {}", reply[0])));
+        }
+        if reply[1] != method {
+            return Err(socks_error(
+                "no acceptable authentication method".to_string(),
+            ));
+        }
+        if method == METHOD_PASSWORD {
+            self.authenticate(stream)?;
+        }
+
+        stream.write_all(&connect_request(target))?;
+        let mut reply = [0u8; 4];
+        stream.read_exact(&mut reply)?;
+        if reply[0] != SOCKS_VERSION {
+            return Err(socks_error(format!("unexpected version {}", reply[0])));
+        }
+        if reply[1] != 0 {
+            return Err(socks_error(format!(
+                "connection to {} failed: {}",
+                target,
+                reply_message(reply[1])
+            )));
+        }
+        // skip the address bound by the proxy, followed by its port
+        let bound_address_len = match reply[3] {
+            ADDRESS_IPV4 => 4,
+            ADDRESS_IPV6 => 16,
+            ADDRESS_DOMAIN => {
+                let mut len = [0u8; 1];
+                stream.read_exact(&mut len)?;
+                len[0] as usize
+            }
+            address_type => {
+                return Err(socks_error(format!(
+                    "unknown address type {}",
+                    address_type
+                )))
+            }
+        };
+        let mut bound_address = vec![0u8; bound_address_len + 2];
+        stream.read_exact(&mut bound_address)
+    }
+
+    /// Username and password authentication (RFC 1929)
+    fn authenticate<S: Read + Write>(&self, stream: &mut S) -> io::Result<()> {
+        let username = self.username.as_deref().unwrap_or_default();
+        let password = self.password.as_deref().unwrap_or_default();
+        if username.len() > u8::MAX as usize || password.len() > u8::MAX as usize {
+            return Err(socks_error(
+                "username and password must be at most 255 bytes long".to_string(),
+            ));
+        }
+        let mut request = vec![PASSWORD_AUTH_VERSION, username.len() as u8];
+        request.extend(username.as_bytes());
+        request.push(password.len() as u8);
+        request.extend(password.as_bytes());
+        stream.write_all(&request)?;
+        let mut reply = [0u8; 2];
+        stream.read_exact(&mut reply)?;
+        if reply[1] != 0 {
+            return Err(socks_error("authentication failed".to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// `CONNECT` request to `target`
+fn connect_request(target: SocketAddr) -> Vec<u8> {
+    let mut request = vec![SOCKS_VERSION, COMMAND_CONNECT, 0];
+    match target.ip() {
+        IpAddr::V4(ip) => {
+            request.push(ADDRESS_IPV4);
+            request.extend(ip.octets());
+        }
+        IpAddr::V6(ip) => {
+            request.push(ADDRESS_IPV6);
+            request.extend(ip.octets());
+        }
+    }
+    request.extend(target.port().to_be_bytes());
+    request
+}
+
+fn reply_message(reply: u8) -> &'static str {
+    match reply {
+        0x01 => "general SOCKS server failure",
+        0x02 => "connection not allowed by ruleset",
+        0x03 => "network unreachable",
+        0x04 => "host unreachable",
+        0x05 => "connection refused",
+        0x06 => "TTL expired",
+        0x07 => "command not supported",
+        0x08 => "address type not supported",
+        _ => "unknown error",
+    }
+}
+
+fn socks_error(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, format!("SOCKS5 proxy: {}", message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// Stream replaying the replies of a proxy and recording the requests sent to it
+    struct FakeProxyStream {
+        replies: Cursor<Vec<u8>>,
+        requests: Vec<u8>,
+    }
+
+    impl Read for FakeProxyStream {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.replies.read(buf)
+        }
+    }
+
+    impl Write for FakeProxyStream {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.requests.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn proxy(username: Option<&str>, password: Option<&str>) -> Socks5Proxy {
+        Socks5Proxy {
+            address: "127.0.0.1:9050".parse().unwrap(),
+            username: username.map(str::to_string),
+            password: password.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_connect_request() {
+        assert_eq!(
+            connect_request("1.2.3.4:31244".parse().unwrap()),
+            vec![5, 1, 0, 1, 1, 2, 3, 4, 0x7a, 0x0c]
+        );
+        let mut expected = vec![5, 1, 0, 4];
+        expected.extend([0; 15]);
+        expected.extend([1, 0x7a, 0x0c]);
+        assert_eq!(connect_request("[::1]:31244".parse().unwrap()), expected);
+    }
+
+    #[test]
+    fn test_handshake_without_authentication() {
+        let mut stream = FakeProxyStream {
+            replies: Cursor::new(vec![5, 0, 5, 0, 0, 1, 127, 0, 0, 1, 0x23, 0x82]),
+            requests: Vec::new(),
+        };
+        proxy(None, None)
+            .handshake(&mut stream, "1.2.3.4:31244".parse().unwrap())
+            .unwrap();
+        assert_eq!(
+            stream.requests,
+            vec![5, 1, 0, 5, 1, 0, 1, 1, 2, 3, 4, 0x7a, 0x0c]
+        );
+    }
+
+    #[test]
+    fn test_handshake_with_password() {
+        let mut stream = FakeProxyStream {
+            replies: Cursor::new(vec![5, 2, 1, 0, 5, 0, 0, 3, 2, b'a', b'b', 0, 0]),
+            requests: Vec::new(),
+        };
+        proxy(Some("user"), Some("pw"))
+            .handshake(&mut stream, "1.2.3.4:31244".parse().unwrap())
+            .unwrap();
+        let mut expected = vec![5, 1, 2, 1, 4];
+        expected.extend(b"user");
+        expected.push(2);
+        expected.extend(b"pw");
+        expected.extend([5, 1, 0, 1, 1, 2, 3, 4, 0x7a, 0x0c]);
+        assert_eq!(stream.requests, expected);
+    }
+
+    #[test]
+    fn test_handshake_refused() {
+        let mut stream = FakeProxyStream {
+            replies: Cursor::new(vec![5, 0, 5, 5, 0, 1, 0, 0, 0, 0, 0, 0]),
+            requests: Vec::new(),
+        };
+        let err = proxy(None, None)
+            .handshake(&mut stream, "1.2.3.4:31244".parse().unwrap())
+            .unwrap_err();
+        assert!(err.to_string().contains("connection refused"));
+
+        // the proxy requires an authentication that we cannot provide
+        let mut stream = FakeProxyStream {
+            replies: Cursor::new(vec![5, 0xff]),
+            requests: Vec::new(),
+        };
+        assert!(proxy(None, None)
+            .handshake(&mut stream, "1.2.3.4:31244".parse().unwrap())
+            .is_err());
+    }
+}
@@ -1,6 +1,9 @@
 use std::collections::HashMap;
 
-use crate::{settings::PeerCategoryInfo, ProtocolConfig};
+use crate::{
+    settings::{BandwidthLimits, PeerCategoryInfo},
+    ProtocolConfig,
+};
 use massa_models::config::{CHAINID, ENDORSEMENT_COUNT, MAX_MESSAGE_SIZE};
 use massa_time::MassaTime;
 use tempfile::NamedTempFile;
@@ -48,6 +51,11 @@ impl Default for ProtocolConfig {
                 .expect("cannot create temp file")
                 .path()
                 .to_path_buf(),
+            ip_ban_list_file: NamedTempFile::new()
+                .expect("cannot create temp file")
+                .path()
+                .to_path_buf(),
+            asn_database_file: None,
             listeners: HashMap::default(),
             thread_tester_count: 2,
             max_size_channel_commands_connectivity: 1000,
@@ -82,7 +90,11 @@ impl Default for ProtocolConfig {
             timeout_connection: MassaTime::from_millis(1000),
             try_connection_timer: MassaTime::from_millis(5000),
             unban_everyone_timer: MassaTime::from_millis(ONE_DAY_MS),
+            peer_score_half_life: MassaTime::from_millis(3600000),
+            peer_score_throttle_threshold: -20.0,
+            peer_score_ban_threshold: -50.0,
             routable_ip: None,
+            additional_routable_ips: Vec::new(),
             max_in_connections: 10,
             debug: true,
             peers_categories: HashMap::default(),
@@ -96,6 +108,14 @@ impl Default for ProtocolConfig {
             try_connection_timer_same_peer: MassaTime::from_millis(1000),
             test_oldest_peer_cooldown: MassaTime::from_millis(720000),
             rate_limit: 1024 * 1024 * 2,
+            bandwidth_limits: BandwidthLimits::default(),
+            port_mapping: false,
+            port_mapping_lease_duration: MassaTime::from_millis(3600000),
+            reachability_timeout: MassaTime::from_millis(600000),
+            dns_seeds: Vec::new(),
+            dns_seed_refresh_interval: MassaTime::from_millis(3600000),
+            compact_block_relay: false,
+            proxy: None,
             chain_id: *CHAINID,
         }
     }
@@ -0,0 +1,201 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Bandwidth limits of the traffic with the peers.
+//!
+//! The traffic is measured with token buckets, refilled at the rate of each limit and holding
+//! up to one second of traffic. The download is limited by pausing the reading of the messages
+//! of a peer until the buckets are refilled, which slows the peer down through the flow control
+//! of its connection. The upload is measured from the bytes sent on each connection: while a
+//! bucket is in debt, the low-priority messages are dropped and the high-priority ones are
+//! still sent.
+
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use massa_protocol_exports::{BandwidthLimits, PeerId};
+use parking_lot::Mutex;
+
+/// Token bucket refilled at the rate of a limit, holding up to one second of traffic
+#[derive(Debug, Default)]
+struct TokenBucket {
+    /// available bytes, negative when more bytes than allowed were used
+    tokens: f64,
+    /// time of the last refill, `None` before the first use
+    last_refill: Option<Instant>,
+}
+
+impl TokenBucket {
+    /// Refill the bucket at `rate` bytes per second, then remove `bytes` from it
+    ///
+    /// # Returns
+    /// The time to wait for the bucket to be out of debt
+    fn consume(&mut self, bytes: u64, rate: u64, now: Instant) -> Duration {
+        let rate = rate as f64;
+        self.tokens = match self.last_refill {
+            Some(last_refill) => {
+                let elapsed = now.saturating_duration_since(last_refill).as_secs_f64();
+                (self.tokens + elapsed * rate).min(rate)
+            }
+            None => rate,
+        };
+        self.last_refill = Some(now);
+        self.tokens -= bytes as f64;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / rate)
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct PeerBandwidth {
+    upload: TokenBucket,
+    download: TokenBucket,
+    /// total bytes sent on the connection with the peer at the last measure
+    bytes_sent: u64,
+}
+
+#[derive(Debug, Default)]
+struct BandwidthState {
+    limits: BandwidthLimits,
+    global_upload: TokenBucket,
+    global_download: TokenBucket,
+    peers: HashMap<PeerId, PeerBandwidth>,
+}
+
+/// Bandwidth limits shared by the threads reading from and writing to the peers
+#[derive(Debug, Default)]
+pub struct BandwidthLimiter {
+    state: Mutex<BandwidthState>,
+}
+
+impl BandwidthLimiter {
+    pub fn new(limits: BandwidthLimits) -> Self {
+        BandwidthLimiter {
+            state: Mutex::new(BandwidthState {
+                limits,
+                ..Default::default()
+            }),
+        }
+    }
+
+    pub fn get_limits(&self) -> BandwidthLimits {
+        self.state.lock().limits
+    }
+
+    pub fn set_limits(&self, limits: BandwidthLimits) {
+        self.state.lock().limits = limits;
+    }
+
+    /// Forget the peers that are not connected anymore
+    pub fn retain_peers(&self, peer_ids: &HashSet<PeerId>) {
+        self.state
+            .lock()
+            .peers
+            .retain(|peer_id, _| peer_ids.contains(peer_id));
+    }
+
+    /// Account for a message received from a peer
+    ///
+    /// # Returns
+    /// The time to wait before reading the next messages for the download limits to be respected
+    pub fn on_download(&self, peer_id: &PeerId, bytes: u64, now: Instant) -> Duration {
+        let mut guard = self.state.lock();
+        let state = &mut *guard;
+        let BandwidthLimits {
+            peer_download,
+            global_download,
+            ..
+        } = state.limits;
+        let mut wait = Duration::ZERO;
+        if peer_download > 0 {
+            let peer = state.peers.entry(*peer_id).or_default();
+            wait = wait.max(peer.download.consume(bytes, peer_download, now));
+        }
+        if global_download > 0 {
+            let global_wait = state.global_download.consume(bytes, global_download, now);
+            wait = wait.max(global_wait);
+        }
+        wait
+    }
+
+    /// Account for the bytes sent to a peer since the last call, and check whether a message
+    /// can be sent to it
+    ///
+    /// # Arguments
+    /// * `bytes_sent`: total bytes sent on the connection with the peer
+    /// * `high_priority`: the high-priority messages are always allowed
+    pub fn allow_upload(
+        &self,
+        peer_id: &PeerId,
+        bytes_sent: u64,
+        high_priority: bool,
+        now: Instant,
+    ) -> bool {
+        let mut guard = self.state.lock();
+        let state = &mut *guard;
+        let BandwidthLimits {
+            peer_upload,
+            global_upload,
+            ..
+        } = state.limits;
+        // the bytes are measured even without limits, so that enabling one later
+        // does not account for the whole past traffic
+        let peer = state.peers.entry(*peer_id).or_default();
+        let new_bytes = bytes_sent.saturating_sub(peer.bytes_sent);
+        peer.bytes_sent = bytes_sent;
+        let mut allowed = true;
+        if peer_upload > 0 {
+            allowed &= peer.upload.consume(new_bytes, peer_upload, now).is_zero();
+        }
+        if global_upload > 0 {
+            let global_wait = state.global_upload.consume(new_bytes, global_upload, now);
+            allowed &= global_wait.is_zero();
+        }
+        allowed || high_priority
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_signature::KeyPair;
+
+    #[test]
+    fn test_bandwidth_limiter() {
+        let peer_id = PeerId::from_public_key(KeyPair::generate(0).unwrap().get_public_key());
+        let limiter = BandwidthLimiter::new(BandwidthLimits {
+            peer_upload: 1000,
+            peer_download: 1000,
+            global_upload: 0,
+            global_download: 2000,
+        });
+        let start = Instant::now();
+
+        // one second of traffic is allowed at once, then the peer has to wait
+        assert_eq!(limiter.on_download(&peer_id, 1000, start), Duration::ZERO);
+        assert_eq!(
+            limiter.on_download(&peer_id, 500, start),
+            Duration::from_millis(500)
+        );
+        let later = start + Duration::from_millis(500);
+        assert_eq!(limiter.on_download(&peer_id, 0, later), Duration::ZERO);
+
+        // the low-priority messages are dropped while the upload budget is exhausted
+        assert!(limiter.allow_upload(&peer_id, 1000, false, start));
+        assert!(!limiter.allow_upload(&peer_id, 1500, false, start));
+        assert!(limiter.allow_upload(&peer_id, 1500, true, start));
+        assert!(limiter.allow_upload(&peer_id, 1500, false, later));
+
+        limiter.set_limits(BandwidthLimits::default());
+        assert!(limiter.allow_upload(&peer_id, 1_000_000, false, later));
+        assert_eq!(
+            limiter.on_download(&peer_id, 1_000_000, later),
+            Duration::ZERO
+        );
+
+        limiter.retain_peers(&HashSet::new());
+        assert!(limiter.state.lock().peers.is_empty());
+    }
+}
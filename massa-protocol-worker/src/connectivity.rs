@@ -7,8 +7,11 @@ use massa_metrics::MassaMetrics;
 use massa_models::stats::NetworkStats;
 use massa_pool_exports::PoolController;
 use massa_pos_exports::SelectorController;
-use massa_protocol_exports::{PeerCategoryInfo, PeerId, ProtocolConfig, ProtocolError};
+use massa_protocol_exports::{
+    BandwidthLimits, PeerCategoryInfo, PeerId, ProtocolConfig, ProtocolError, TransportType,
+};
 use massa_storage::Storage;
+use massa_time::MassaTime;
 use massa_versioning::versioning::MipStore;
 use parking_lot::RwLock;
 use peernet::peer::PeerConnectionType;
@@ -16,7 +19,7 @@ use std::net::SocketAddr;
 use std::sync::Arc;
 use std::{collections::HashMap, net::IpAddr};
 use std::{thread::JoinHandle, time::Duration};
-use tracing::{debug, warn};
+use tracing::{debug, info, warn};
 
 use crate::handlers::peer_handler::models::ConnectionMetadata;
 use crate::{
@@ -24,7 +27,11 @@ use crate::{
     ip::to_canonical,
     worker::ProtocolChannels,
 };
-use crate::{handlers::peer_handler::PeerManagementHandler, messages::MessagesHandler};
+use crate::{
+    handlers::peer_handler::{is_peer_throttled, PeerManagementHandler},
+    messages::MessagesHandler,
+    nat::ReachabilityTracker,
+};
 use crate::{
     handlers::{
         block_handler::{cache::BlockCache, BlockHandler},
@@ -49,6 +56,10 @@ pub enum ConnectivityCommand {
             HashMap<PeerId, (SocketAddr, PeerConnectionType)>,
         )>,
     },
+    GetBandwidthLimits {
+        responder: MassaSender<BandwidthLimits>,
+    },
+    SetBandwidthLimits(BandwidthLimits),
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -76,6 +87,7 @@ pub(crate) fn start_connectivity_thread(
     ),
     initial_peers: InitialPeers,
     peer_db: SharedPeerDB,
+    mapped_address: Option<SocketAddr>,
     storage: Storage,
     protocol_channels: ProtocolChannels,
     messages_handler: MessagesHandler,
@@ -92,6 +104,7 @@ pub(crate) fn start_connectivity_thread(
         let sender_blocks_retrieval_ext = protocol_channels.block_handler_retrieval.0.clone();
         let sender_blocks_propagation_ext = protocol_channels.block_handler_propagation.0.clone();
         let sender_operations_propagation_ext = protocol_channels.operation_handler_propagation.0.clone();
+        let bandwidth_limiter = messages_handler.bandwidth_limiter.clone();
         move || {
             for (addr, transport) in &config.listeners {
                 network_controller
@@ -105,6 +118,13 @@ pub(crate) fn start_connectivity_thread(
             // Little hack to be sure that listeners are started before trying to connect to peers
             std::thread::sleep(Duration::from_millis(100));
 
+            // The peers are reached through QUIC when we listen to it, TCP otherwise.
+            // Only TCP goes through the proxy. Whatever the transport, the messages of all the
+            // handlers share the single ordered stream that peernet opens for each connection.
+            let quic_enabled = config.proxy.is_none() && config.listeners.values().any(|transport| *transport == TransportType::Quic);
+            // The IPv6 listeners of the peers are only reached when we listen to IPv6 ourselves
+            let ipv6_enabled = config.listeners.keys().any(|addr| addr.is_ipv6());
+
             // Create cache outside of the op handler because it could be used by other handlers
             let total_in_slots = config.peers_categories.values().map(|v| v.max_in_connections).sum::<usize>() + config.default_category_info.max_in_connections + 1;
             let total_out_slots = config.peers_categories.values().map(| v| v.target_out_connections).sum::<usize>() + config.default_category_info.target_out_connections + 1;
@@ -140,6 +160,7 @@ pub(crate) fn start_connectivity_thread(
             let mut operation_handler = OperationHandler::new(
                 pool_controller.clone(),
                 storage.clone_without_refs(),
+                mip_store.clone(),
                 config.clone(),
                 operation_cache.clone(),
                 network_controller.get_active_connections(),
@@ -191,6 +212,7 @@ pub(crate) fn start_connectivity_thread(
             let tick_metrics = tick(massa_metrics.tick_delay);
             let tick_try_connect = tick(config.try_connection_timer.to_duration());
             let tick_unban_everyone = tick(config.unban_everyone_timer.to_duration());
+            let mut reachability_tracker = ReachabilityTracker::new(config.reachability_timeout, MassaTime::now());
 
             //Try to connect to peers
             loop {
@@ -221,18 +243,29 @@ pub(crate) fn start_connectivity_thread(
                                     let peer_db_read = peer_db.read();
                                     (peer_db_read.get_banned_peer_count(), peer_db_read.get_known_peer_count())
                                 };
+                                let now = MassaTime::now();
+                                reachability_tracker.on_in_connections(in_connection_count as usize, now);
                                 let stats = NetworkStats {
                                     active_node_count,
                                     in_connection_count,
                                     out_connection_count,
                                     banned_peer_count,
                                     known_peer_count,
+                                    reachability: reachability_tracker.get_reachability(now),
+                                    mapped_address,
                                 };
                                 let peers: HashMap<PeerId, (SocketAddr, PeerConnectionType)> = network_controller.get_active_connections().get_peers_connected().into_iter().map(|(peer_id, peer)| {
                                     (peer_id, (peer.0, peer.1))
                                 }).collect();
                                 responder.try_send((stats, peers)).unwrap_or_else(|_| warn!("Failed to send stats to responder"));
                             }
+                            Ok(ConnectivityCommand::GetBandwidthLimits { responder }) => {
+                                responder.try_send(bandwidth_limiter.get_limits()).unwrap_or_else(|_| warn!("Failed to send bandwidth limits to responder"));
+                            }
+                            Ok(ConnectivityCommand::SetBandwidthLimits(limits)) => {
+                                info!("Bandwidth limits set to {:?}", limits);
+                                bandwidth_limiter.set_limits(limits);
+                            }
                             Err(_) => {
                                 warn!("Channel to connectivity thread is closed. Stopping the protocol");
                                 break;
@@ -245,6 +278,7 @@ pub(crate) fn start_connectivity_thread(
                         let active_conn = network_controller.get_active_connections();
                         massa_metrics.set_active_connections(active_conn.get_nb_in_connections(), active_conn.get_nb_out_connections());
                         let peers_map = active_conn.get_peers_connections_bandwidth();
+                        bandwidth_limiter.retain_peers(&active_conn.get_peer_ids_connected());
                         massa_metrics.update_peers_tx_rx(peers_map);
                         let peer_db_read = peer_db.read();
                         massa_metrics.set_known_peers(peer_db_read.get_known_peer_count() as usize);
@@ -252,6 +286,7 @@ pub(crate) fn start_connectivity_thread(
                     },
                     recv(tick_try_connect) -> _ => {
                         let active_conn = network_controller.get_active_connections();
+                        reachability_tracker.on_in_connections(active_conn.get_nb_in_connections(), MassaTime::now());
                         let peers_connected = active_conn.get_peers_connected();
                         let peers_connection_queue = active_conn.get_peer_ids_out_connection_queue();
 
@@ -284,13 +319,18 @@ pub(crate) fn start_connectivity_thread(
                                     continue;
                                 }
 
+                                // do not connect to the peers with a low score
+                                if is_peer_throttled(&*peer_db_read, peer_id, &config) {
+                                    continue;
+                                }
+
                                 if peer_info.state == PeerState::Trusted {
                                     if let Some(ref last_announce) = peer_info.last_announce {
                                         if last_announce.listeners.is_empty() {
                                             continue;
                                         }
 
-                                        if let Some((addr, _)) = last_announce.listeners.iter().next() {
+                                        if let Some((addr, transport)) = select_listener(&last_announce.listeners, quic_enabled, ipv6_enabled) {
                                             let canonical_ip = to_canonical(addr.ip());
                                             let mut allowed_local_ips = false;
                                             // Check if the peer is in a category and we didn't reached out target yet
@@ -331,7 +371,11 @@ pub(crate) fn start_connectivity_thread(
                                                 continue;
                                             }
 
-                                            addresses_can_connect.push((*addr, connection_metadata, category_found));
+                                            if peer_db_read.is_ip_banned(&canonical_ip) {
+                                                continue;
+                                            }
+
+                                            addresses_can_connect.push((*addr, *transport, connection_metadata, category_found));
                                         } else {
                                             tracing::warn!("No listeners for the peer {peer_id}");
                                         }
@@ -341,11 +385,11 @@ pub(crate) fn start_connectivity_thread(
                         }
 
                         // Sort addresses using the metadata
-                        addresses_can_connect.sort_by(|a, b| a.1.cmp(&b.1));
+                        addresses_can_connect.sort_by(|a, b| a.2.cmp(&b.2));
 
                         // Connect to the given addresses, trying to fill all the slots available
                         let mut addresses_connected = vec![];
-                        for (addr, _, category) in addresses_can_connect.iter() {
+                        for (addr, transport, _, category) in addresses_can_connect.iter() {
                             if addresses_connected.contains(addr) {
                                 continue;
                             }
@@ -357,7 +401,7 @@ pub(crate) fn start_connectivity_thread(
                                     for (name, slots) in connection_slots.iter_mut() {
                                         if name == *cat && *slots > 0 {
                                             // In case the connection succeeds, we take a place in a slot
-                                            if try_connect_peer(*addr, &mut network_controller, &peer_db, &config).is_ok() {
+                                            if try_connect_peer(*addr, *transport, &mut network_controller, &peer_db, &config).is_ok() {
                                                 *slots = slots.saturating_sub(1);
                                                 addresses_connected.push(*addr);
                                             }
@@ -368,7 +412,7 @@ pub(crate) fn start_connectivity_thread(
                                 // Default category
                                 None if connection_slots["default"] > 0 => {
                                     // In case the connection succeeds, we take a place in a slot
-                                    if try_connect_peer(*addr, &mut network_controller, &peer_db, &config).is_err() {
+                                    if try_connect_peer(*addr, *transport, &mut network_controller, &peer_db, &config).is_err() {
                                         if let Some(v) = connection_slots.get_mut("default") {
                                             *v = v.saturating_sub(1);
                                         }
@@ -402,16 +446,36 @@ pub(crate) fn start_connectivity_thread(
     Ok((protocol_channels.connectivity_thread.0, handle))
 }
 
+/// Select the listener of a peer to connect to: its QUIC one if `quic_enabled`,
+/// falling back to its TCP one. Its IPv6 listeners are skipped unless `ipv6_enabled`.
+fn select_listener(
+    listeners: &HashMap<SocketAddr, TransportType>,
+    quic_enabled: bool,
+    ipv6_enabled: bool,
+) -> Option<(&SocketAddr, &TransportType)> {
+    let find_listener = |transport_type: TransportType| {
+        listeners.iter().find(|(addr, transport)| {
+            **transport == transport_type && (ipv6_enabled || addr.is_ipv4())
+        })
+    };
+    quic_enabled
+        .then(|| find_listener(TransportType::Quic))
+        .flatten()
+        .or_else(|| find_listener(TransportType::Tcp))
+}
+
 // Attempt to connect to peer
 fn try_connect_peer(
     addr: SocketAddr,
+    transport: TransportType,
     network_controller: &mut Box<dyn NetworkController>,
     peer_db: &SharedPeerDB,
     config: &ProtocolConfig,
 ) -> Result<(), ProtocolError> {
-    debug!("Trying to connect to addr {}", addr);
+    debug!("Trying to connect to addr {} with {:?}", addr, transport);
 
-    let conn_res = network_controller.try_connect(addr, config.timeout_connection.to_duration());
+    let conn_res =
+        network_controller.try_connect(transport, addr, config.timeout_connection.to_duration());
     {
         let mut peer_db_write = peer_db.write();
         peer_db_write.set_try_connect_success_or_insert(&addr);
@@ -422,3 +486,98 @@ fn try_connect_peer(
     }
     conn_res
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{select_listener, try_connect_peer};
+    use crate::handlers::peer_handler::models::{PeerDB, SharedPeerDB};
+    use crate::wrap_network::{MockNetworkController, NetworkController};
+    use crate::wrap_peer_db::PeerDBTrait;
+    use massa_protocol_exports::{ProtocolConfig, ProtocolError, TransportType};
+    use mockall::predicate;
+    use parking_lot::RwLock;
+    use std::collections::HashMap;
+    use std::net::SocketAddr;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_select_listener() {
+        let tcp_addr: SocketAddr = "127.0.0.1:8081".parse().unwrap();
+        let quic_addr: SocketAddr = "127.0.0.1:8082".parse().unwrap();
+        let mut listeners = HashMap::new();
+        listeners.insert(tcp_addr, TransportType::Tcp);
+        assert_eq!(
+            select_listener(&listeners, true, true),
+            Some((&tcp_addr, &TransportType::Tcp))
+        );
+
+        listeners.insert(quic_addr, TransportType::Quic);
+        assert_eq!(
+            select_listener(&listeners, true, true),
+            Some((&quic_addr, &TransportType::Quic))
+        );
+        assert_eq!(
+            select_listener(&listeners, false, true),
+            Some((&tcp_addr, &TransportType::Tcp))
+        );
+
+        listeners.remove(&tcp_addr);
+        assert_eq!(select_listener(&listeners, false, true), None);
+
+        let ipv6_addr: SocketAddr = "[2001:db8::1]:8081".parse().unwrap();
+        listeners.insert(ipv6_addr, TransportType::Tcp);
+        assert_eq!(
+            select_listener(&listeners, false, true),
+            Some((&ipv6_addr, &TransportType::Tcp))
+        );
+        assert_eq!(select_listener(&listeners, false, false), None);
+    }
+
+    #[test]
+    fn test_try_connect_peer_with_quic() {
+        let tcp_addr: SocketAddr = "127.0.0.1:8081".parse().unwrap();
+        let quic_addr: SocketAddr = "127.0.0.1:8082".parse().unwrap();
+        let listeners = HashMap::from([
+            (tcp_addr, TransportType::Tcp),
+            (quic_addr, TransportType::Quic),
+        ]);
+        let (addr, transport) = select_listener(&listeners, true, true).unwrap();
+
+        // the QUIC listener of the peer is dialed with QUIC
+        let mut network_controller = MockNetworkController::new();
+        network_controller
+            .expect_try_connect()
+            .with(
+                predicate::eq(TransportType::Quic),
+                predicate::eq(quic_addr),
+                predicate::always(),
+            )
+            .times(1)
+            .returning(|_, _, _| {
+                Err(ProtocolError::GeneralProtocolError(
+                    "connection refused".to_string(),
+                ))
+            });
+        let mut network_controller: Box<dyn NetworkController> = Box::new(network_controller);
+        let peer_db: SharedPeerDB = Arc::new(RwLock::new(PeerDB::default()));
+        assert!(try_connect_peer(
+            *addr,
+            *transport,
+            &mut network_controller,
+            &peer_db,
+            &ProtocolConfig::default()
+        )
+        .is_err());
+
+        // the failure is recorded for the QUIC listener
+        let peer_db_read = peer_db.read();
+        assert!(peer_db_read
+            .get_connection_metadata_or_default(&quic_addr)
+            .last_failure
+            .is_some());
+        assert!(peer_db_read
+            .get_connection_metadata_or_default(&tcp_addr)
+            .last_failure
+            .is_none());
+    }
+}
@@ -7,7 +7,10 @@ use massa_models::{
     prehash::{PreHashMap, PreHashSet},
     stats::NetworkStats,
 };
-use massa_protocol_exports::{BootstrapPeers, PeerId, ProtocolController, ProtocolError};
+use massa_protocol_exports::{
+    BandwidthLimits, BootstrapPeers, IpCidr, PeerId, PeerReputation, ProtocolController,
+    ProtocolError,
+};
 use massa_storage::Storage;
 use peernet::peer::PeerConnectionType;
 
@@ -172,6 +175,88 @@ impl ProtocolController for ProtocolControllerImpl {
             .map_err(|_| ProtocolError::ChannelError("unban_peers command send error".into()))
     }
 
+    fn get_peers(&self) -> Result<HashMap<PeerId, PeerReputation>, ProtocolError> {
+        let (sender, receiver) = MassaChannel::new("get_peers".to_string(), Some(1));
+        self.sender_peer_management_thread
+            .as_ref()
+            .unwrap()
+            .try_send(PeerManagementCmd::GetPeers { responder: sender })
+            .map_err(|_| ProtocolError::ChannelError("get_peers command send error".into()))?;
+        receiver
+            .recv_timeout(Duration::from_secs(10))
+            .map_err(|_| ProtocolError::ChannelError("get_peers command receive error".into()))
+    }
+
+    fn ban_ips(&self, ranges: Vec<IpCidr>, asns: Vec<u32>) -> Result<(), ProtocolError> {
+        self.sender_peer_management_thread
+            .as_ref()
+            .unwrap()
+            .try_send(PeerManagementCmd::BanIps { ranges, asns })
+            .map_err(|_| ProtocolError::ChannelError("ban_ips command send error".into()))
+    }
+
+    fn unban_ips(&self, ranges: Vec<IpCidr>, asns: Vec<u32>) -> Result<(), ProtocolError> {
+        self.sender_peer_management_thread
+            .as_ref()
+            .unwrap()
+            .try_send(PeerManagementCmd::UnbanIps { ranges, asns })
+            .map_err(|_| ProtocolError::ChannelError("unban_ips command send error".into()))
+    }
+
+    fn get_allowed_ips(&self) -> Result<Vec<IpCidr>, ProtocolError> {
+        let (sender, receiver) = MassaChannel::new("get_allowed_ips".to_string(), Some(1));
+        self.sender_peer_management_thread
+            .as_ref()
+            .unwrap()
+            .try_send(PeerManagementCmd::GetAllowedIps { responder: sender })
+            .map_err(|_| {
+                ProtocolError::ChannelError("get_allowed_ips command send error".into())
+            })?;
+        receiver.recv_timeout(Duration::from_secs(10)).map_err(|_| {
+            ProtocolError::ChannelError("get_allowed_ips command receive error".into())
+        })
+    }
+
+    fn allow_ips(&self, ranges: Vec<IpCidr>) -> Result<(), ProtocolError> {
+        self.sender_peer_management_thread
+            .as_ref()
+            .unwrap()
+            .try_send(PeerManagementCmd::AllowIps(ranges))
+            .map_err(|_| ProtocolError::ChannelError("allow_ips command send error".into()))
+    }
+
+    fn disallow_ips(&self, ranges: Vec<IpCidr>) -> Result<(), ProtocolError> {
+        self.sender_peer_management_thread
+            .as_ref()
+            .unwrap()
+            .try_send(PeerManagementCmd::DisallowIps(ranges))
+            .map_err(|_| ProtocolError::ChannelError("disallow_ips command send error".into()))
+    }
+
+    fn get_bandwidth_limits(&self) -> Result<BandwidthLimits, ProtocolError> {
+        let (sender, receiver) = MassaChannel::new("get_bandwidth_limits".to_string(), Some(1));
+        self.sender_connectivity_thread
+            .as_ref()
+            .unwrap()
+            .try_send(ConnectivityCommand::GetBandwidthLimits { responder: sender })
+            .map_err(|_| {
+                ProtocolError::ChannelError("get_bandwidth_limits command send error".into())
+            })?;
+        receiver.recv_timeout(Duration::from_secs(10)).map_err(|_| {
+            ProtocolError::ChannelError("get_bandwidth_limits command receive error".into())
+        })
+    }
+
+    fn set_bandwidth_limits(&self, limits: BandwidthLimits) -> Result<(), ProtocolError> {
+        self.sender_connectivity_thread
+            .as_ref()
+            .unwrap()
+            .try_send(ConnectivityCommand::SetBandwidthLimits(limits))
+            .map_err(|_| {
+                ProtocolError::ChannelError("set_bandwidth_limits command send error".into())
+            })
+    }
+
     fn get_bootstrap_peers(&self) -> Result<BootstrapPeers, ProtocolError> {
         let (sender, receiver) = MassaChannel::new("get_bootstrap_peers".to_string(), Some(1));
         self.sender_peer_management_thread
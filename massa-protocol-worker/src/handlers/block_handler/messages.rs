@@ -2,8 +2,8 @@ use massa_models::{
     block_header::{BlockHeader, BlockHeaderDeserializer, SecuredHeader},
     block_id::{BlockId, BlockIdDeserializer, BlockIdSerializer},
     operation::{
-        OperationId, OperationIdSerializer, OperationIdsDeserializer, OperationsDeserializer,
-        SecureShareOperation,
+        OperationId, OperationIdSerializer, OperationIdsDeserializer, OperationPrefixId,
+        OperationPrefixIdDeserializer, OperationsDeserializer, SecureShareOperation,
     },
     secure_share::{SecureShareDeserializer, SecureShareSerializer},
 };
@@ -12,6 +12,7 @@ use massa_serialization::{
 };
 use nom::{
     error::{context, ContextError, ParseError},
+    multi::length_count,
     sequence::tuple,
     IResult, Parser,
 };
@@ -28,6 +29,8 @@ pub enum AskForBlockInfo {
     OperationIds,
     /// Ask for a subset of operations of the block
     Operations(Vec<OperationId>),
+    /// Ask for the list of operation ID prefixes of the block, to rebuild it from known operations
+    OperationPrefixIds,
 }
 
 /// Reply to a block data request
@@ -42,6 +45,8 @@ pub enum BlockInfoReply {
     Operations(Vec<SecureShareOperation>),
     /// Block not found
     NotFound,
+    /// List of the prefixes of the operation IDs within the block, in the block order
+    OperationPrefixIds(Vec<OperationPrefixId>),
 }
 
 #[derive(Debug)]
@@ -84,6 +89,17 @@ impl From<&BlockMessage> for MessageTypeId {
     }
 }
 
+impl MessageTypeId {
+    /// Name of the message type in the network metrics
+    pub(crate) fn metrics_name(&self) -> &'static str {
+        match self {
+            MessageTypeId::Header => "block_header",
+            MessageTypeId::DataRequest => "block_data_request",
+            MessageTypeId::DataResponse => "block_data_response",
+        }
+    }
+}
+
 #[derive(IntoPrimitive, Debug, Eq, PartialEq, TryFromPrimitive)]
 #[repr(u64)]
 pub enum BlockInfoType {
@@ -91,6 +107,7 @@ pub enum BlockInfoType {
     OperationIds = 1,
     Operations = 2,
     NotFound = 3,
+    OperationPrefixIds = 4,
 }
 
 #[derive(Default, Clone)]
@@ -154,6 +171,10 @@ impl Serializer<BlockMessage> for BlockMessageSerializer {
                                 .serialize(operation_id, buffer)?;
                         }
                     }
+                    AskForBlockInfo::OperationPrefixIds => {
+                        self.id_serializer
+                            .serialize(&(BlockInfoType::OperationPrefixIds as u64), buffer)?;
+                    }
                 }
             }
             BlockMessage::DataResponse {
@@ -190,6 +211,15 @@ impl Serializer<BlockMessage> for BlockMessageSerializer {
                         self.id_serializer
                             .serialize(&(BlockInfoType::NotFound as u64), buffer)?;
                     }
+                    BlockInfoReply::OperationPrefixIds(operation_prefix_ids) => {
+                        self.id_serializer
+                            .serialize(&(BlockInfoType::OperationPrefixIds as u64), buffer)?;
+                        self.length_serializer
+                            .serialize(&(operation_prefix_ids.len() as u64), buffer)?;
+                        for operation_prefix_id in operation_prefix_ids {
+                            buffer.extend(Vec::<u8>::from(operation_prefix_id));
+                        }
+                    }
                 }
             }
         }
@@ -203,6 +233,8 @@ pub struct BlockMessageDeserializer {
     block_id_deserializer: BlockIdDeserializer,
     operation_ids_deserializer: OperationIdsDeserializer,
     operations_deserializer: OperationsDeserializer,
+    operation_prefix_ids_length_deserializer: U64VarIntDeserializer,
+    operation_prefix_id_deserializer: OperationPrefixIdDeserializer,
 }
 
 pub struct BlockMessageDeserializerArgs {
@@ -239,6 +271,7 @@ impl BlockMessageDeserializer {
                 args.max_operations_per_block,
             ),
             operations_deserializer: OperationsDeserializer::new(
+                args.thread_count,
                 args.max_operations_per_block,
                 args.max_datastore_value_length,
                 args.max_function_name_length,
@@ -248,6 +281,11 @@ impl BlockMessageDeserializer {
                 args.max_op_datastore_value_length,
                 args.chain_id,
             ),
+            operation_prefix_ids_length_deserializer: U64VarIntDeserializer::new(
+                Included(0),
+                Included(args.max_operations_per_block as u64),
+            ),
+            operation_prefix_id_deserializer: OperationPrefixIdDeserializer::new(),
         }
     }
 }
@@ -298,6 +336,9 @@ impl Deserializer<BlockMessage> for BlockMessageDeserializer {
                                     .map(|(rest, operation_ids)| {
                                         (rest, AskForBlockInfo::Operations(operation_ids))
                                     }),
+                                BlockInfoType::OperationPrefixIds => {
+                                    Ok((rest, AskForBlockInfo::OperationPrefixIds))
+                                }
                                 BlockInfoType::NotFound => {
                                     Err(nom::Err::Error(ParseError::from_error_kind(
                                         buffer,
@@ -347,6 +388,17 @@ impl Deserializer<BlockMessage> for BlockMessageDeserializer {
                                         (rest, BlockInfoReply::Operations(operations))
                                     }),
                                 BlockInfoType::NotFound => Ok((rest, BlockInfoReply::NotFound)),
+                                BlockInfoType::OperationPrefixIds => length_count(
+                                    |input| {
+                                        self.operation_prefix_ids_length_deserializer
+                                            .deserialize(input)
+                                    },
+                                    |input| {
+                                        self.operation_prefix_id_deserializer.deserialize(input)
+                                    },
+                                )
+                                .map(BlockInfoReply::OperationPrefixIds)
+                                .parse(rest),
                             }
                         }),
                     )),
@@ -521,4 +573,51 @@ mod tests {
             _ => panic!("Wrong message type"),
         }
     }
+
+    #[test]
+    fn test_operation_prefix_ids_message() {
+        let block_id =
+            BlockId::from_str("B12DvrcQkzF1Wi8BVoNfc4n93CD3E2qhCNe7nVhnEQGWHZ24fEmg").unwrap();
+        let op_id =
+            OperationId::from_str("O1yrsTtyyhDJtPD7jZHkodstNCjUSsfGbVZ5xdG6bVZWABeze8y").unwrap();
+        let message = super::BlockMessage::DataResponse {
+            block_id,
+            block_info: super::BlockInfoReply::OperationPrefixIds(vec![
+                op_id.prefix(),
+                op_id.prefix(),
+            ]),
+        };
+        let mut buffer = Vec::new();
+        let serializer = super::BlockMessageSerializer::new();
+        serializer.serialize(&message, &mut buffer).unwrap();
+        let deserializer =
+            super::BlockMessageDeserializer::new(super::BlockMessageDeserializerArgs {
+                thread_count: 1,
+                endorsement_count: 1,
+                max_operations_per_block: 2,
+                max_datastore_value_length: 1,
+                max_function_name_length: 1,
+                max_parameters_size: 1,
+                max_op_datastore_entry_count: 1,
+                max_op_datastore_key_length: 1,
+                max_op_datastore_value_length: 1,
+                max_denunciations_in_block_header: 1,
+                last_start_period: None,
+                chain_id: *CHAINID,
+            });
+        let (rest, deserialized_message) = deserializer
+            .deserialize::<DeserializeError>(&buffer)
+            .unwrap();
+        assert!(rest.is_empty());
+        match deserialized_message {
+            super::BlockMessage::DataResponse {
+                block_id: deserialized_block_id,
+                block_info: super::BlockInfoReply::OperationPrefixIds(prefixes),
+            } => {
+                assert_eq!(deserialized_block_id, block_id);
+                assert_eq!(prefixes, vec![op_id.prefix(), op_id.prefix()]);
+            }
+            _ => panic!("Wrong message type"),
+        }
+    }
 }
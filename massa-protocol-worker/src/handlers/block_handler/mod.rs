@@ -24,7 +24,9 @@ pub mod messages;
 mod propagation;
 mod retrieval;
 
-pub(crate) use messages::{BlockMessage, BlockMessageSerializer};
+pub(crate) use messages::{
+    BlockMessage, BlockMessageSerializer, MessageTypeId as BlockMessageTypeId,
+};
 
 #[cfg(test)]
 pub use messages::{
@@ -87,7 +89,7 @@ impl BlockHandler {
             cache.clone(),
             storage.clone_without_refs(),
             mip_store,
-            massa_metrics,
+            massa_metrics.clone(),
         );
         let block_propagation_thread = start_propagation_thread(
             active_connections,
@@ -95,6 +97,7 @@ impl BlockHandler {
             peer_cmd_sender,
             config,
             cache,
+            massa_metrics,
         );
         Self {
             block_retrieval_thread: Some((sender_ext, block_retrieval_thread)),
@@ -22,6 +22,7 @@ use crate::{
 };
 use crossbeam::channel::RecvTimeoutError;
 use massa_channel::{receiver::MassaReceiver, sender::MassaSender};
+use massa_metrics::MassaMetrics;
 use massa_models::block_header::SecuredHeader;
 use massa_models::block_id::BlockId;
 use massa_protocol_exports::PeerId;
@@ -224,12 +225,14 @@ pub fn start_propagation_thread(
     peer_cmd_sender: MassaSender<PeerManagementCmd>,
     config: ProtocolConfig,
     cache: SharedBlockCache,
+    massa_metrics: MassaMetrics,
 ) -> JoinHandle<()> {
     std::thread::Builder::new()
         .name(THREAD_NAME.to_string())
         .spawn(move || {
             let block_serializer = MessagesSerializer::new()
-                .with_block_message_serializer(BlockMessageSerializer::new());
+                .with_block_message_serializer(BlockMessageSerializer::new())
+                .with_massa_metrics(massa_metrics);
             let mut propagation_thread = PropagationThread {
                 stored_for_propagation: LruMap::new(ByLength::new(
                     config
@@ -33,20 +33,22 @@ use massa_models::{
     block_id::BlockId,
     endorsement::EndorsementId,
     operation::{
-        compute_operations_hash, OperationId, OperationIdSerializer, SecureShareOperation,
+        compute_operations_hash, OperationId, OperationIdSerializer, OperationPrefixId,
+        SecureShareOperation,
     },
     prehash::{PreHashMap, PreHashSet},
     secure_share::SecureShare,
+    slot::Slot,
     timeslots::get_block_slot_timestamp,
 };
 use massa_pool_exports::PoolController;
 use massa_pos_exports::SelectorController;
 use massa_protocol_exports::PeerId;
-use massa_protocol_exports::{ProtocolConfig, ProtocolError};
+use massa_protocol_exports::{PeerScoreEvent, ProtocolConfig, ProtocolError};
 use massa_serialization::{DeserializeError, Deserializer, Serializer};
 use massa_storage::Storage;
 use massa_time::TimeError;
-use massa_versioning::versioning::MipStore;
+use massa_versioning::versioning::{MipComponent, MipStore};
 use rand::thread_rng;
 use rand::{seq::SliceRandom, Rng};
 use tracing::{debug, info, warn};
@@ -58,7 +60,7 @@ use super::{
     commands_retrieval::BlockHandlerRetrievalCommand,
     messages::{
         AskForBlockInfo, BlockInfoReply, BlockMessage, BlockMessageDeserializer,
-        BlockMessageDeserializerArgs,
+        BlockMessageDeserializerArgs, MessageTypeId,
     },
     BlockMessageSerializer,
 };
@@ -74,6 +76,9 @@ pub(crate) struct BlockInfo {
     pub(crate) header: Option<SecuredHeader>,
     /// Operations ids. None if not received yet
     pub(crate) operation_ids: Option<Vec<OperationId>>,
+    /// Whether the full operation ids have to be asked because the block
+    /// could not be rebuilt from their prefixes
+    pub(crate) ask_full_operation_ids: bool,
     /// Operations and endorsements contained in the block,
     /// if we've received them already, and none otherwise.
     pub(crate) storage: Storage,
@@ -84,6 +89,7 @@ impl BlockInfo {
         BlockInfo {
             header,
             operation_ids: None,
+            ask_full_operation_ids: false,
             storage,
         }
     }
@@ -139,11 +145,13 @@ impl RetrievalThread {
                     self.receiver_network.update_metrics();
                     match msg {
                         Ok((peer_id, message)) => {
+                            let start = Instant::now();
                             let (rest, message) = match block_message_deserializer
                                 .deserialize::<DeserializeError>(&message) {
                                 Ok((rest, message)) => (rest, message),
                                 Err(err) => {
                                     warn!("Error in deserializing block message: {:?}", err);
+                                    self.report_peer(&peer_id, PeerScoreEvent::InvalidMessage);
                                     continue;
                                 }
                             };
@@ -151,6 +159,7 @@ impl RetrievalThread {
                                 println!("Error: message not fully consumed");
                                 return;
                             }
+                            let message_type = MessageTypeId::from(&message).metrics_name();
                             match message {
                                 BlockMessage::DataRequest{block_id, block_info} => {
                                     self.on_ask_for_block_info_received(peer_id, block_id, block_info);
@@ -164,6 +173,7 @@ impl RetrievalThread {
                                     self.update_block_retrieval();
                                 }
                             }
+                            self.massa_metrics.record_network_message_processing(message_type, start.elapsed());
                         },
                         Err(_) => {
                             info!("Stop block retrieval thread");
@@ -301,6 +311,17 @@ impl RetrievalThread {
 
                 BlockInfoReply::OperationIds(block_op_ids)
             }
+            (Some((_, block_op_ids)), AskForBlockInfo::OperationPrefixIds) => {
+                // the peer asked for the prefixes of the operation IDs of the block
+
+                // once sent, the peer will know about those operations,
+                // no need to announce their IDs to that peer anymore
+                operation_knowledge_updates.extend(block_op_ids.iter().cloned());
+
+                BlockInfoReply::OperationPrefixIds(
+                    block_op_ids.iter().map(|op_id| op_id.prefix()).collect(),
+                )
+            }
             (Some((_, block_op_ids)), AskForBlockInfo::Operations(mut asked_ops)) => {
                 // the peer asked for a list of full operations from the block
 
@@ -406,6 +427,15 @@ impl RetrievalThread {
                 // the block_header.
                 self.on_block_operation_list_received(from_peer_id, block_id, operation_list);
             }
+            BlockInfoReply::OperationPrefixIds(operation_prefix_list) => {
+                // Rebuild the list of operation ids from the operations we know,
+                // or fall back to asking for the full list.
+                self.on_block_operation_prefix_list_received(
+                    from_peer_id,
+                    block_id,
+                    operation_prefix_list,
+                );
+            }
             BlockInfoReply::Operations(operations) => {
                 // Send operations to pool,
                 // before performing the below checks,
@@ -441,6 +471,9 @@ impl RetrievalThread {
                 return;
             }
         };
+        if is_new {
+            self.report_peer(&from_peer_id, PeerScoreEvent::NewBlock);
+        }
 
         if let Some(info) = self.block_wishlist.get_mut(&block_id) {
             // We are actively trying to get this block
@@ -665,6 +698,17 @@ impl RetrievalThread {
             .map_err(|err| ProtocolError::SendError(err.to_string()))
     }
 
+    /// send a score event about a peer to the peer handler
+    fn report_peer(&self, peer_id: &PeerId, event: PeerScoreEvent) {
+        let cmd = PeerManagementCmd::ScoreEvent {
+            peer_id: *peer_id,
+            event,
+        };
+        if let Err(err) = self.peer_cmd_sender.try_send(cmd) {
+            debug!("Error when reporting peer {}: {}", peer_id, err);
+        }
+    }
+
     /// Remove the given blocks from the local wishlist
     pub(crate) fn remove_asked_blocks(&mut self, remove_hashes: &PreHashSet<BlockId>) {
         for asked_blocks in self.asked_blocks.values_mut() {
@@ -785,6 +829,93 @@ impl RetrievalThread {
         self.remove_asked_blocks(&[block_id].into_iter().collect());
     }
 
+    /// We received the list of the operation ID prefixes of a block.
+    ///
+    /// The list of operation IDs is rebuilt from the operations we know that match the prefixes.
+    /// If some of them are unknown or ambiguous, or if the rebuilt list does not match the header,
+    /// we fall back to asking for the full list of operation IDs.
+    fn on_block_operation_prefix_list_received(
+        &mut self,
+        from_peer_id: PeerId,
+        block_id: BlockId,
+        operation_prefix_ids: Vec<OperationPrefixId>,
+    ) {
+        debug!(
+            "received operation prefix list for block {} from {}",
+            block_id, &from_peer_id
+        );
+
+        // mark the sender node as knowing those ops
+        self.operation_cache
+            .write()
+            .insert_peer_known_ops(&from_peer_id, &operation_prefix_ids);
+
+        // check if we were looking to retrieve the list of ops for that block
+        let wishlist_info = if let Some(info) = self
+            .block_wishlist
+            .get_mut(&block_id)
+            .filter(|i| i.header.is_some() && i.operation_ids.is_none())
+        {
+            info
+        } else {
+            // we were not actively looking for that data, but mark the remote node as knowing the block
+            debug!("peer {} sent us a list of operation prefixes for block id {} but we were not looking for it", from_peer_id, block_id);
+            self.cache
+                .write()
+                .insert_peer_known_block(&from_peer_id, &[block_id], true);
+            return;
+        };
+
+        // find the only operation we know for each prefix
+        let operation_ids: Option<Vec<OperationId>> = {
+            let op_storage_lock = self.storage.read_operations();
+            operation_prefix_ids
+                .iter()
+                .map(|prefix| {
+                    op_storage_lock
+                        .get_operations_by_prefix(prefix)
+                        .filter(|op_ids| op_ids.len() == 1)
+                        .and_then(|op_ids| op_ids.iter().next().copied())
+                })
+                .collect()
+        };
+
+        // check that the rebuilt list matches the hash in the header. Two operations can share
+        // a prefix, so a mismatch is not a proof that the peer misbehaved.
+        let operation_merkle_root = wishlist_info
+            .header
+            .as_ref()
+            .expect("header presence in wishlist should have been checked above")
+            .content
+            .operation_merkle_root;
+        let operation_ids = operation_ids.filter(|operation_ids| {
+            compute_operations_hash(operation_ids, &self.operation_id_serializer)
+                == operation_merkle_root
+        });
+
+        // Mark the sender as knowing this block
+        self.cache
+            .write()
+            .insert_peer_known_block(&from_peer_id, &[block_id], true);
+
+        match operation_ids {
+            Some(operation_ids) => {
+                // Save the rebuilt operation ID list to the wishlist
+                wishlist_info.operation_ids = Some(operation_ids);
+            }
+            None => {
+                debug!(
+                    "could not rebuild the operation list of block {} from its prefixes",
+                    block_id
+                );
+                wishlist_info.ask_full_operation_ids = true;
+            }
+        }
+
+        // free up all the nodes that we asked for that operation list
+        self.remove_asked_blocks(&[block_id].into_iter().collect());
+    }
+
     /// Return the sum of all operation's serialized sizes in the id list
     fn get_total_operations_size(storage: &Storage, operation_ids: &[OperationId]) -> usize {
         let op_read_lock = storage.read_operations();
@@ -795,6 +926,32 @@ impl RetrievalThread {
             .sum()
     }
 
+    /// First of the given operations whose type is not active yet at `slot`.
+    /// A block of `slot` containing such an operation is invalid.
+    fn find_inactive_operation(
+        mip_store: &MipStore,
+        config: &ProtocolConfig,
+        storage: &Storage,
+        operation_ids: &PreHashSet<OperationId>,
+        slot: Slot,
+    ) -> Option<OperationId> {
+        let timestamp = get_block_slot_timestamp(
+            config.thread_count,
+            config.t0,
+            config.genesis_timestamp,
+            slot,
+        )
+        .ok()?;
+        let execution_version =
+            mip_store.get_latest_component_version_at(&MipComponent::Execution, timestamp);
+        let op_read_lock = storage.read_operations();
+        operation_ids.iter().copied().find(|id| {
+            op_read_lock
+                .get(id)
+                .is_some_and(|op| op.content.op.required_execution_version() > execution_version)
+        })
+    }
+
     /// We received the full operations of a block.
     fn on_block_full_operations_received(
         &mut self,
@@ -883,6 +1040,7 @@ impl RetrievalThread {
             &self.storage,
             &mut self.operation_cache,
             &self.config,
+            &self.mip_store,
             operations.values().cloned().collect(),
             &from_peer_id,
             &mut self.sender_propagation_ops,
@@ -962,6 +1120,8 @@ impl RetrievalThread {
         let mut to_ask: PreHashSet<BlockId> = self.block_wishlist.keys().copied().collect();
         // the number of things already being asked to those peers
         let mut peer_loads: HashMap<PeerId, usize> = Default::default();
+        // the peers that did not answer one of our asks in time
+        let mut slow_peers = Vec::new();
         for (peer_id, asked_blocks) in &mut self.asked_blocks {
             // init the list of items to remove from asked_blocks
             let mut to_remove_from_asked_blocks = Vec::new();
@@ -980,6 +1140,7 @@ impl RetrievalThread {
                     // We mark the block for removal from the asked_blocks list.
                     // This prevents us from re-detecting the timeout many times.
                     to_remove_from_asked_blocks.push(*block_id);
+                    slow_peers.push(*peer_id);
                 } else {
                     // this block was recently asked to this peer: no need to ask for the block for now
 
@@ -1000,6 +1161,9 @@ impl RetrievalThread {
                 asked_blocks.remove(&remove_id);
             }
         }
+        for peer_id in slow_peers {
+            self.report_peer(&peer_id, PeerScoreEvent::SlowResponse);
+        }
 
         // for each block to ask, choose a peer to ask it from and perform the ask
         let mut to_ask = to_ask.into_iter().collect::<Vec<_>>();
@@ -1071,8 +1235,15 @@ impl RetrievalThread {
             ) {
                 // ask for header
                 (false, false) => AskForBlockInfo::Header,
-                // ask for the list of operation IDs in the block
-                (true, false) => AskForBlockInfo::OperationIds,
+                // ask for the list of operation IDs in the block, or only for their prefixes
+                // to rebuild it from the operations we know
+                (true, false) => {
+                    if self.config.compact_block_relay && !wishlist_info.ask_full_operation_ids {
+                        AskForBlockInfo::OperationPrefixIds
+                    } else {
+                        AskForBlockInfo::OperationIds
+                    }
+                }
                 // ask for missing operations in the block
                 (true, true) => {
                     // gather missing block operations and perform necessary followups
@@ -1190,6 +1361,33 @@ impl RetrievalThread {
             return None;
         }
 
+        // Check that the operations we know about are all active at the slot of the block.
+        // Otherwise the block is invalid, and we should stop trying to retrieve it.
+        let slot = wishlist_info
+            .header
+            .as_ref()
+            .expect("header presence in wishlist should have been checked above")
+            .content
+            .slot;
+        if let Some(op_id) = Self::find_inactive_operation(
+            &self.mip_store,
+            &self.config,
+            &wishlist_info.storage,
+            &claimed_ops,
+            slot,
+        ) {
+            warn!(
+                "Block {} contains the operation {} whose type is not active yet at slot {}.",
+                block_id, op_id, slot
+            );
+
+            // stop retrieving the block
+            self.mark_block_as_invalid(block_id);
+
+            // quit
+            return None;
+        }
+
         // if there are missing blocks, return them
         if claimed_ops.len() < op_id_set.len() {
             return Some((&op_id_set - &claimed_ops).into_iter().collect());
@@ -1279,8 +1477,9 @@ pub fn start_retrieval_thread(
     mip_store: MipStore,
     massa_metrics: MassaMetrics,
 ) -> JoinHandle<()> {
-    let block_message_serializer =
-        MessagesSerializer::new().with_block_message_serializer(BlockMessageSerializer::new());
+    let block_message_serializer = MessagesSerializer::new()
+        .with_block_message_serializer(BlockMessageSerializer::new())
+        .with_massa_metrics(massa_metrics.clone());
     std::thread::Builder::new()
         .name(THREAD_NAME.to_string())
         .spawn(move || {
@@ -33,6 +33,15 @@ impl From<&EndorsementMessage> for MessageTypeId {
     }
 }
 
+impl MessageTypeId {
+    /// Name of the message type in the network metrics
+    pub(crate) fn metrics_name(&self) -> &'static str {
+        match self {
+            MessageTypeId::Endorsements => "endorsements",
+        }
+    }
+}
+
 #[derive(Default, Clone)]
 pub struct EndorsementMessageSerializer {
     id_serializer: U64VarIntSerializer,
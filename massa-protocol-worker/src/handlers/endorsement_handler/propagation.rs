@@ -4,6 +4,7 @@ use super::{
 };
 use crate::{messages::MessagesSerializer, wrap_network::ActiveConnectionsTrait};
 use massa_channel::receiver::MassaReceiver;
+use massa_metrics::MassaMetrics;
 use massa_protocol_exports::ProtocolConfig;
 use massa_storage::Storage;
 use std::thread::JoinHandle;
@@ -144,12 +145,14 @@ pub fn start_propagation_thread(
     cache: SharedEndorsementCache,
     config: ProtocolConfig,
     active_connections: Box<dyn ActiveConnectionsTrait>,
+    massa_metrics: MassaMetrics,
 ) -> JoinHandle<()> {
     std::thread::Builder::new()
         .name(THREAD_NAME.to_string())
         .spawn(move || {
             let endorsement_serializer = MessagesSerializer::new()
-                .with_endorsement_message_serializer(EndorsementMessageSerializer::new());
+                .with_endorsement_message_serializer(EndorsementMessageSerializer::new())
+                .with_massa_metrics(massa_metrics);
             let mut propagation_thread = PropagationThread {
                 receiver,
                 config,
@@ -1,4 +1,4 @@
-use std::thread::JoinHandle;
+use std::{thread::JoinHandle, time::Instant};
 
 use crossbeam::{channel::tick, select};
 use massa_channel::{receiver::MassaReceiver, sender::MassaSender};
@@ -12,7 +12,7 @@ use massa_models::{
 use massa_pool_exports::PoolController;
 use massa_pos_exports::SelectorController;
 use massa_protocol_exports::PeerId;
-use massa_protocol_exports::{ProtocolConfig, ProtocolError};
+use massa_protocol_exports::{PeerScoreEvent, ProtocolConfig, ProtocolError};
 use massa_serialization::{DeserializeError, Deserializer};
 use massa_storage::Storage;
 use massa_time::MassaTime;
@@ -30,7 +30,7 @@ use super::{
     cache::SharedEndorsementCache,
     commands_propagation::EndorsementHandlerPropagationCommand,
     commands_retrieval::EndorsementHandlerRetrievalCommand,
-    messages::{EndorsementMessageDeserializer, EndorsementMessageDeserializerArgs},
+    messages::{EndorsementMessageDeserializer, EndorsementMessageDeserializerArgs, MessageTypeId},
 };
 
 // protocol-endorsement-handler-retrieval
@@ -102,6 +102,7 @@ impl RetrievalThread {
 
     /// Process incoming message
     fn process_message(&mut self, peer_id: PeerId, message: Vec<u8>) {
+        let start = Instant::now();
         let (rest, message) = match self
             .endorsement_message_deserializer
             .deserialize::<DeserializeError>(&message)
@@ -112,13 +113,16 @@ impl RetrievalThread {
                     "Error while deserializing message from peer {} err: {:?}",
                     peer_id, err
                 );
+                self.report_peer(&peer_id, PeerScoreEvent::InvalidMessage);
                 return;
             }
         };
         if !rest.is_empty() {
             debug!("Message not fully consumed");
+            self.report_peer(&peer_id, PeerScoreEvent::InvalidMessage);
             return;
         }
+        let message_type = MessageTypeId::from(&message).metrics_name();
         match message {
             EndorsementMessage::Endorsements(endorsements) => {
                 debug!("Received endorsement message: Endorsement from {}", peer_id);
@@ -144,6 +148,8 @@ impl RetrievalThread {
                 }
             }
         }
+        self.metrics
+            .record_network_message_processing(message_type, start.elapsed());
     }
 
     /// send a ban peer command to the peer handler
@@ -153,6 +159,17 @@ impl RetrievalThread {
             .try_send(PeerManagementCmd::Ban(vec![*peer_id]))
             .map_err(|err| ProtocolError::SendError(err.to_string()))
     }
+
+    /// send a score event about a peer to the peer handler
+    fn report_peer(&self, peer_id: &PeerId, event: PeerScoreEvent) {
+        let cmd = PeerManagementCmd::ScoreEvent {
+            peer_id: *peer_id,
+            event,
+        };
+        if let Err(err) = self.peer_cmd_sender.try_send(cmd) {
+            debug!("Error when reporting peer {}: {}", peer_id, err);
+        }
+    }
 }
 
 /// Note endorsements coming from a given node,
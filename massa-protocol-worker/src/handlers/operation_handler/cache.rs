@@ -1,5 +1,7 @@
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{hash_map::RandomState, HashMap, HashSet},
+    hash::BuildHasher,
+    mem,
     sync::Arc,
 };
 
@@ -8,14 +10,114 @@ use massa_protocol_exports::PeerId;
 use parking_lot::RwLock;
 use schnellru::{ByLength, LruMap};
 
+/// False positive rate of each generation of the filters of the operations known by a peer
+const KNOWN_OPS_FALSE_POSITIVE_RATE: f64 = 0.005;
+
+/// Bloom filter of operation ID prefixes
+struct BloomFilter {
+    bits: Vec<u64>,
+    bit_count: u64,
+    hash_count: u64,
+    /// random keys, so that the false positives differ from one filter to another
+    hasher: RandomState,
+    /// number of operations inserted
+    len: usize,
+}
+
+impl BloomFilter {
+    /// Create a filter sized for `capacity` operations at the given false positive rate
+    fn new(capacity: usize, false_positive_rate: f64) -> Self {
+        let capacity = capacity.max(1) as f64;
+        let ln2 = std::f64::consts::LN_2;
+        let bit_count = (-capacity * false_positive_rate.ln() / (ln2 * ln2))
+            .ceil()
+            .max(64.0);
+        let hash_count = (bit_count / capacity * ln2).round().max(1.0);
+        let bit_count = bit_count as u64;
+        BloomFilter {
+            bits: vec![0; bit_count.div_ceil(64) as usize],
+            bit_count,
+            hash_count: hash_count as u64,
+            hasher: RandomState::new(),
+            len: 0,
+        }
+    }
+
+    /// Indices of the bits of an operation, derived from a single hash by double hashing
+    fn bit_indices(&self, op: &OperationPrefixId) -> impl Iterator<Item = u64> {
+        let hash = self.hasher.hash_one(op);
+        let (h1, h2) = (hash & u32::MAX as u64, (hash >> 32) | 1);
+        let bit_count = self.bit_count;
+        (0..self.hash_count).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % bit_count)
+    }
+
+    fn insert(&mut self, op: &OperationPrefixId) {
+        for index in self.bit_indices(op) {
+            self.bits[(index / 64) as usize] |= 1 << (index % 64);
+        }
+        self.len += 1;
+    }
+
+    fn contains(&self, op: &OperationPrefixId) -> bool {
+        self.bit_indices(op)
+            .all(|index| (self.bits[(index / 64) as usize] & (1 << (index % 64))) != 0)
+    }
+}
+
+/// Operations known by a peer, kept in two generations of bloom filters:
+/// once the current generation holds `capacity` operations, it replaces the previous one.
+///
+/// Unlike a map of the prefixes, the memory used does not depend on the size of the prefixes.
+/// In exchange, a few operations that the peer does not know are considered known,
+/// and are not announced to it.
+pub struct KnownOperations {
+    current: BloomFilter,
+    previous: BloomFilter,
+    capacity: usize,
+}
+
+impl KnownOperations {
+    pub fn new(capacity: usize) -> Self {
+        KnownOperations {
+            current: BloomFilter::new(capacity, KNOWN_OPS_FALSE_POSITIVE_RATE),
+            previous: BloomFilter::new(capacity, KNOWN_OPS_FALSE_POSITIVE_RATE),
+            capacity,
+        }
+    }
+
+    pub fn insert(&mut self, op: &OperationPrefixId) {
+        if self.current.contains(op) {
+            return;
+        }
+        if self.current.len >= self.capacity {
+            let next = BloomFilter::new(self.capacity, KNOWN_OPS_FALSE_POSITIVE_RATE);
+            self.previous = mem::replace(&mut self.current, next);
+        }
+        self.current.insert(op);
+    }
+
+    pub fn contains(&self, op: &OperationPrefixId) -> bool {
+        self.current.contains(op) || self.previous.contains(op)
+    }
+
+    /// Number of operations remembered, including the ones inserted more than once
+    pub fn len(&self) -> usize {
+        self.current.len + self.previous.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
 /// Cache for operations
 pub struct OperationCache {
     /// List of operations we checked recently
     pub checked_operations: LruMap<OperationId, ()>,
     /// List of operation ID prefixes we checked recently
     pub checked_operations_prefix: LruMap<OperationPrefixId, ()>,
-    /// List of operations known by peers
-    pub ops_known_by_peer: HashMap<PeerId, LruMap<OperationPrefixId, ()>>,
+    /// Operations known by peers
+    pub ops_known_by_peer: HashMap<PeerId, KnownOperations>,
     /// Maximum number of operations known by a peer
     pub max_known_ops_by_peer: u32,
 }
@@ -36,9 +138,9 @@ impl OperationCache {
         let known_ops = self
             .ops_known_by_peer
             .entry(*peer_id)
-            .or_insert_with(|| LruMap::new(ByLength::new(self.max_known_ops_by_peer)));
+            .or_insert_with(|| KnownOperations::new(self.max_known_ops_by_peer as usize));
         for op in ops {
-            known_ops.insert(*op, ());
+            known_ops.insert(op);
         }
     }
 
@@ -60,7 +162,7 @@ impl OperationCache {
             match self.ops_known_by_peer.entry(*peer_id) {
                 std::collections::hash_map::Entry::Occupied(_) => {}
                 std::collections::hash_map::Entry::Vacant(entry) => {
-                    entry.insert(LruMap::new(ByLength::new(self.max_known_ops_by_peer)));
+                    entry.insert(KnownOperations::new(self.max_known_ops_by_peer as usize));
                 }
             }
         }
@@ -68,3 +170,39 @@ impl OperationCache {
 }
 
 pub type SharedOperationCache = Arc<RwLock<OperationCache>>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_models::operation::OPERATION_ID_PREFIX_SIZE_BYTES;
+
+    fn prefix(i: u32) -> OperationPrefixId {
+        let mut bytes = [0; OPERATION_ID_PREFIX_SIZE_BYTES];
+        bytes[..4].copy_from_slice(&i.to_be_bytes());
+        OperationPrefixId::from(&bytes)
+    }
+
+    #[test]
+    fn test_known_operations() {
+        let mut known_ops = KnownOperations::new(1000);
+        for i in 0..1500 {
+            known_ops.insert(&prefix(i));
+        }
+        // no false negatives among the last operations
+        assert!((500..1500).all(|i| known_ops.contains(&prefix(i))));
+        let false_positives = (10_000..20_000)
+            .filter(|i| known_ops.contains(&prefix(*i)))
+            .count();
+        assert!(false_positives < 300, "{} false positives", false_positives);
+
+        // the oldest generation is forgotten after two rotations
+        for i in 1500..3500 {
+            known_ops.insert(&prefix(i));
+        }
+        let remembered = (0..1000)
+            .filter(|i| known_ops.contains(&prefix(*i)))
+            .count();
+        assert!(remembered < 30, "{} old operations remembered", remembered);
+        assert!((2500..3500).all(|i| known_ops.contains(&prefix(i))));
+    }
+}
@@ -40,6 +40,17 @@ impl From<&OperationMessage> for MessageTypeId {
     }
 }
 
+impl MessageTypeId {
+    /// Name of the message type in the network metrics
+    pub(crate) fn metrics_name(&self) -> &'static str {
+        match self {
+            MessageTypeId::OperationsAnnouncement => "operations_announcement",
+            MessageTypeId::AskForOperations => "ask_for_operations",
+            MessageTypeId::Operations => "operations",
+        }
+    }
+}
+
 #[derive(Default, Clone)]
 pub struct OperationMessageSerializer {
     id_serializer: U64VarIntSerializer,
@@ -94,6 +105,8 @@ pub struct OperationMessageDeserializer {
 
 /// Limits used in the deserialization of `OperationMessage`
 pub struct OperationMessageDeserializerArgs {
+    /// Number of threads
+    pub thread_count: u8,
     /// Maximum number of prefix ids that can be asked to propagate or sent
     pub max_operations_prefix_ids: u32,
     /// Maximum of full operations sent in one message
@@ -123,6 +136,7 @@ impl OperationMessageDeserializer {
                 args.max_operations_prefix_ids,
             ),
             operations_deserializer: OperationsDeserializer::new(
+                args.thread_count,
                 args.max_operations,
                 args.max_datastore_value_length,
                 args.max_function_name_length,
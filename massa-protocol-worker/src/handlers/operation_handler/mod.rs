@@ -5,6 +5,7 @@ use massa_metrics::MassaMetrics;
 use massa_pool_exports::PoolController;
 use massa_protocol_exports::ProtocolConfig;
 use massa_storage::Storage;
+use massa_versioning::versioning::MipStore;
 
 use crate::wrap_network::ActiveConnectionsTrait;
 
@@ -21,7 +22,9 @@ mod messages;
 mod propagation;
 mod retrieval;
 
-pub(crate) use messages::{OperationMessage, OperationMessageSerializer};
+pub(crate) use messages::{
+    MessageTypeId as OperationMessageTypeId, OperationMessage, OperationMessageSerializer,
+};
 pub(crate) use retrieval::note_operations_from_peer;
 
 use super::peer_handler::models::{PeerManagementCmd, PeerMessageTuple};
@@ -42,6 +45,7 @@ impl OperationHandler {
     pub fn new(
         pool_controller: Box<dyn PoolController>,
         storage: Storage,
+        mip_store: MipStore,
         config: ProtocolConfig,
         cache: SharedOperationCache,
         active_connections: Box<dyn ActiveConnectionsTrait>,
@@ -57,6 +61,7 @@ impl OperationHandler {
             receiver_network,
             pool_controller,
             storage.clone_without_refs(),
+            mip_store,
             config.clone(),
             cache.clone(),
             active_connections.clone(),
@@ -161,12 +161,12 @@ impl PropagationThread {
                 let ops = cache_write.ops_known_by_peer.get_mut(&peer_id).unwrap();
                 let new_ops: Vec<OperationId> = operation_ids
                     .iter()
-                    .filter(|id| ops.peek(&id.prefix()).is_none())
+                    .filter(|id| !ops.contains(&id.prefix()))
                     .copied()
                     .collect();
                 if !new_ops.is_empty() {
                     for id in &new_ops {
-                        ops.insert(id.prefix(), ());
+                        ops.insert(&id.prefix());
                     }
                     debug!(
                         "Send operations announcement of len {} to {}",
@@ -226,9 +226,10 @@ pub fn start_propagation_thread(
                 ),
                 config,
                 cache,
-                _massa_metrics: massa_metrics,
                 operation_message_serializer: MessagesSerializer::new()
-                    .with_operation_message_serializer(OperationMessageSerializer::new()),
+                    .with_operation_message_serializer(OperationMessageSerializer::new())
+                    .with_massa_metrics(massa_metrics.clone()),
+                _massa_metrics: massa_metrics,
             };
             propagation_thread.run();
         })
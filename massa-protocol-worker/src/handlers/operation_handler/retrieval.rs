@@ -13,10 +13,11 @@ use massa_models::{
 };
 use massa_pool_exports::PoolController;
 use massa_protocol_exports::PeerId;
-use massa_protocol_exports::{ProtocolConfig, ProtocolError};
+use massa_protocol_exports::{PeerScoreEvent, ProtocolConfig, ProtocolError};
 use massa_serialization::{DeserializeError, Deserializer};
 use massa_storage::Storage;
 use massa_time::{MassaTime, TimeError};
+use massa_versioning::versioning::{MipComponent, MipStore};
 use schnellru::{ByLength, LruMap};
 
 use crate::{
@@ -31,7 +32,10 @@ use super::{
     cache::SharedOperationCache,
     commands_propagation::OperationHandlerPropagationCommand,
     commands_retrieval::OperationHandlerRetrievalCommand,
-    messages::{OperationMessage, OperationMessageDeserializer, OperationMessageDeserializerArgs},
+    messages::{
+        MessageTypeId, OperationMessage, OperationMessageDeserializer,
+        OperationMessageDeserializerArgs,
+    },
     OperationMessageSerializer,
 };
 
@@ -59,18 +63,20 @@ pub struct RetrievalThread {
     active_connections: Box<dyn ActiveConnectionsTrait>,
     op_batch_buffer: VecDeque<OperationBatchItem>,
     storage: Storage,
+    mip_store: MipStore,
     config: ProtocolConfig,
     internal_sender: MassaSender<OperationHandlerPropagationCommand>,
     receiver_ext: MassaReceiver<OperationHandlerRetrievalCommand>,
     operation_message_serializer: MessagesSerializer,
     peer_cmd_sender: MassaSender<PeerManagementCmd>,
-    _massa_metrics: MassaMetrics,
+    massa_metrics: MassaMetrics,
 }
 
 impl RetrievalThread {
     fn run(&mut self) {
         let operation_message_deserializer =
             OperationMessageDeserializer::new(OperationMessageDeserializerArgs {
+                thread_count: self.config.thread_count,
                 max_operations_prefix_ids: self.config.max_operations_per_message as u32,
                 max_operations: self.config.max_operations_per_message as u32,
                 max_datastore_value_length: self.config.max_op_datastore_value_length,
@@ -89,11 +95,13 @@ impl RetrievalThread {
                     self.receiver.update_metrics();
                     match msg {
                         Ok((peer_id, message)) => {
+                            let start = Instant::now();
                             let (rest, message) = match operation_message_deserializer
                                 .deserialize::<DeserializeError>(&message) {
                                     Ok((rest, message)) => (rest, message),
                                     Err(err) => {
                                         warn!("Error when deserializing message from peer {}: Err = {}", peer_id, err);
+                                        self.report_peer(&peer_id, PeerScoreEvent::InvalidMessage);
                                         continue;
                                     }
                                 };
@@ -101,22 +109,37 @@ impl RetrievalThread {
                                 println!("Error: message not fully consumed");
                                 return;
                             }
+                            let message_type = MessageTypeId::from(&message).metrics_name();
                             match message {
                                 OperationMessage::Operations(ops) => {
                                     debug!("Received operation message: Operations from {}", peer_id);
-                                    if let Err(err) = note_operations_from_peer(
+                                    // full operations are only sent in response to our requests
+                                    let unrequested = ops.iter().any(|op| {
+                                        self.asked_operations
+                                            .peek(&op.id.prefix())
+                                            .map_or(true, |(_, asked_peers)| !asked_peers.contains(&peer_id))
+                                    });
+                                    if unrequested {
+                                        self.report_peer(&peer_id, PeerScoreEvent::Spam);
+                                    }
+                                    match note_operations_from_peer(
                                         &self.storage,
                                         &mut self.cache,
                                         &self.config,
+                                        &self.mip_store,
                                         ops,
                                         &peer_id,
                                         &mut self.internal_sender,
                                         &mut self.pool_controller
                                     ) {
-                                        warn!("peer {} sent us critically incorrect operation, which may be an attack attempt by the remote peer or a loss of sync between us and the remote peer. Err = {}", peer_id, err);
-
-                                        if let Err(e) = self.ban_node(&peer_id) {
-                                            warn!("Error when banning node: {}", e);
+                                        Ok(true) => self.report_peer(&peer_id, PeerScoreEvent::NewOperations),
+                                        Ok(false) => {}
+                                        Err(err) => {
+                                            warn!("peer {} sent us critically incorrect operation, which may be an attack attempt by the remote peer or a loss of sync between us and the remote peer. Err = {}", peer_id, err);
+
+                                            if let Err(e) = self.ban_node(&peer_id) {
+                                                warn!("Error when banning node: {}", e);
+                                            }
                                         }
                                     }
                                 }
@@ -135,6 +158,7 @@ impl RetrievalThread {
                                     }
                                 }
                             }
+                            self.massa_metrics.record_network_message_processing(message_type, start.elapsed());
                         }
                         Err(_) => {
                             info!("Stop operation retrieval thread");
@@ -362,22 +386,46 @@ impl RetrievalThread {
             .try_send(PeerManagementCmd::Ban(vec![*peer_id]))
             .map_err(|err| ProtocolError::SendError(err.to_string()))
     }
+
+    /// send a score event about a peer to the peer handler
+    fn report_peer(&self, peer_id: &PeerId, event: PeerScoreEvent) {
+        let cmd = PeerManagementCmd::ScoreEvent {
+            peer_id: *peer_id,
+            event,
+        };
+        if let Err(err) = self.peer_cmd_sender.try_send(cmd) {
+            debug!("Error when reporting peer {}: {}", peer_id, err);
+        }
+    }
 }
 
+/// Check the operations received from a peer, and add the new ones to the pool and propagate them.
+///
+/// Returns whether some of the operations were new
+
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn note_operations_from_peer(
     base_storage: &Storage,
     operations_cache: &mut SharedOperationCache,
     config: &ProtocolConfig,
+    mip_store: &MipStore,
     operations: Vec<SecureShareOperation>,
     source_peer_id: &PeerId,
     ops_propagation_sender: &mut MassaSender<OperationHandlerPropagationCommand>,
     pool_controller: &mut Box<dyn PoolController>,
-) -> Result<(), ProtocolError> {
+) -> Result<bool, ProtocolError> {
     massa_trace!("protocol.protocol_worker.note_operations_from_peer", { "peer": source_peer_id, "operations": operations });
     let now = MassaTime::now();
+    let execution_version =
+        mip_store.get_latest_component_version_at(&MipComponent::Execution, now);
 
     let mut new_operations = PreHashMap::with_capacity(operations.len());
     for operation in operations {
+        // ignore if the type of op is not active yet
+        if operation.content.op.required_execution_version() > execution_version {
+            continue;
+        }
+
         // ignore if op is too old
         let expire_period_timestamp = get_block_slot_timestamp(
             config.thread_count,
@@ -449,7 +497,8 @@ pub(crate) fn note_operations_from_peer(
         );
     }
 
-    if !new_operations.is_empty() {
+    let has_new_operations = !new_operations.is_empty();
+    if has_new_operations {
         // Store new operations, claim locally
         let mut ops = base_storage.clone_without_refs();
         ops.store_operations(new_operations.into_values().collect());
@@ -465,7 +514,7 @@ pub(crate) fn note_operations_from_peer(
         pool_controller.add_operations(ops);
     }
 
-    Ok(())
+    Ok(has_new_operations)
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -473,6 +522,7 @@ pub fn start_retrieval_thread(
     receiver: MassaReceiver<PeerMessageTuple>,
     pool_controller: Box<dyn PoolController>,
     storage: Storage,
+    mip_store: MipStore,
     config: ProtocolConfig,
     cache: SharedOperationCache,
     active_connections: Box<dyn ActiveConnectionsTrait>,
@@ -488,6 +538,7 @@ pub fn start_retrieval_thread(
                 receiver,
                 pool_controller,
                 storage,
+                mip_store,
                 internal_sender,
                 receiver_ext,
                 cache,
@@ -500,10 +551,11 @@ pub fn start_retrieval_thread(
                 )),
                 config,
                 operation_message_serializer: MessagesSerializer::new()
-                    .with_operation_message_serializer(OperationMessageSerializer::new()),
+                    .with_operation_message_serializer(OperationMessageSerializer::new())
+                    .with_massa_metrics(massa_metrics.clone()),
                 op_batch_buffer: VecDeque::new(),
                 peer_cmd_sender,
-                _massa_metrics: massa_metrics,
+                massa_metrics,
             };
             retrieval_thread.run();
         })
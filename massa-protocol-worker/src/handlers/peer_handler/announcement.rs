@@ -1,6 +1,6 @@
 use std::{
     collections::HashMap,
-    net::{IpAddr, SocketAddr},
+    net::{IpAddr, Ipv6Addr, SocketAddr},
     ops::Bound::Included,
 };
 
@@ -169,18 +169,35 @@ impl Deserializer<Announcement> for AnnouncementDeserializer {
     }
 }
 
+/// Addresses at which the other peers can reach our listeners
+///
+/// Each listener is announced with the routable IPs of its IP family. A listener bound to the
+/// IPv6 unspecified address also accepts the IPv4 connections, so it is announced for both.
+pub(crate) fn announced_listeners(
+    listeners: &HashMap<SocketAddr, TransportType>,
+    routable_ips: &[IpAddr],
+) -> HashMap<SocketAddr, TransportType> {
+    let mut announced = HashMap::new();
+    for (addr, transport) in listeners {
+        let dual_stack = addr.ip() == IpAddr::V6(Ipv6Addr::UNSPECIFIED);
+        for ip in routable_ips {
+            if dual_stack || addr.is_ipv4() == ip.is_ipv4() {
+                announced.insert(SocketAddr::new(*ip, addr.port()), *transport);
+            }
+        }
+    }
+    announced
+}
+
 impl Announcement {
     pub fn new(
-        mut listeners: HashMap<SocketAddr, TransportType>,
-        routable_ip: Option<IpAddr>,
+        listeners: &HashMap<SocketAddr, TransportType>,
+        routable_ips: &[IpAddr],
         keypair: &KeyPair,
     ) -> PeerNetResult<Self> {
         let mut buf: Vec<u8> = vec![];
         let length_serializer = U64VarIntSerializer::new();
-        //TODO: Hacky to fix and adapt to support multiple ip/listeners
-        if routable_ip.is_none() {
-            listeners = HashMap::default()
-        }
+        let listeners = announced_listeners(listeners, routable_ips);
         length_serializer
             .serialize(&(listeners.len() as u64), &mut buf)
             .map_err(|err| {
@@ -188,8 +205,7 @@ impl Announcement {
                     .error("Announcement serialization", Some(err.to_string()))
             })?;
         for listener in &listeners {
-            let ip = routable_ip.unwrap_or_else(|| listener.0.ip());
-            let ip_bytes = match ip {
+            let ip_bytes = match listener.0.ip() {
                 IpAddr::V4(ip) => {
                     buf.push(4);
                     ip.octets().to_vec()
@@ -222,12 +238,13 @@ impl Announcement {
 #[cfg(test)]
 mod tests {
     use crate::handlers::peer_handler::announcement::{
-        Announcement, AnnouncementDeserializer, AnnouncementDeserializerArgs,
+        announced_listeners, Announcement, AnnouncementDeserializer, AnnouncementDeserializerArgs,
     };
     use massa_serialization::{DeserializeError, Deserializer, Serializer};
     use massa_signature::KeyPair;
     use peernet::transports::TransportType;
     use std::collections::HashMap;
+    use std::net::{IpAddr, SocketAddr};
 
     use super::AnnouncementSerializer;
 
@@ -237,7 +254,7 @@ mod tests {
         listeners.insert("127.0.0.1:8081".parse().unwrap(), TransportType::Tcp);
         listeners.insert("127.0.0.1:8082".parse().unwrap(), TransportType::Quic);
         let announcement =
-            Announcement::new(listeners, None, &KeyPair::generate(0).unwrap()).unwrap();
+            Announcement::new(&listeners, &[], &KeyPair::generate(0).unwrap()).unwrap();
         let announcement_serializer = AnnouncementSerializer::new();
         let announcement_deserializer =
             AnnouncementDeserializer::new(AnnouncementDeserializerArgs { max_listeners: 100 });
@@ -250,4 +267,32 @@ mod tests {
             .unwrap();
         assert_eq!(announcement, announcement_deserialized);
     }
+
+    #[test]
+    fn test_announced_listeners() {
+        let v4: IpAddr = "1.2.3.4".parse().unwrap();
+        let v6: IpAddr = "2001:db8::1".parse().unwrap();
+        let mut listeners = HashMap::new();
+        listeners.insert("0.0.0.0:8081".parse().unwrap(), TransportType::Tcp);
+        listeners.insert("[2001:db8::2]:8082".parse().unwrap(), TransportType::Tcp);
+        listeners.insert("[::]:8083".parse().unwrap(), TransportType::Quic);
+
+        assert!(announced_listeners(&listeners, &[]).is_empty());
+        assert_eq!(
+            announced_listeners(&listeners, &[v4, v6]),
+            HashMap::from([
+                (SocketAddr::new(v4, 8081), TransportType::Tcp),
+                (SocketAddr::new(v6, 8082), TransportType::Tcp),
+                (SocketAddr::new(v4, 8083), TransportType::Quic),
+                (SocketAddr::new(v6, 8083), TransportType::Quic),
+            ])
+        );
+        assert_eq!(
+            announced_listeners(&listeners, &[v6]),
+            HashMap::from([
+                (SocketAddr::new(v6, 8082), TransportType::Tcp),
+                (SocketAddr::new(v6, 8083), TransportType::Quic),
+            ])
+        );
+    }
 }
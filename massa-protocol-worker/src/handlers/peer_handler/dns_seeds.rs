@@ -0,0 +1,128 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Discovery of peers through DNS seeds.
+//!
+//! A DNS seed is a name resolving to the addresses of nodes of the network. The addresses are
+//! given to the testers, whose handshake discovers the peer ids and adds the peers to the peer
+//! database, the same way as for the peers announced by the other nodes.
+
+use std::{
+    collections::HashMap,
+    net::{SocketAddr, ToSocketAddrs},
+    sync::mpsc::{self, RecvTimeoutError, Sender},
+    thread::JoinHandle,
+    time::Duration,
+};
+
+use massa_channel::sender::MassaSender;
+use massa_protocol_exports::PeerId;
+use peernet::transports::TransportType;
+use tracing::{debug, info, warn};
+
+// protocol-peer-handler-dns-seeds
+const THREAD_NAME: &str = "pph-dns-seeds";
+static_assertions::const_assert!(THREAD_NAME.len() < 16);
+
+/// Resolve the DNS seeds to the TCP listeners of the nodes they point to
+///
+/// # Arguments
+/// * `seeds`: DNS names, each one optionally followed by a port
+/// * `default_port`: port used for the seeds without one
+pub(crate) fn resolve_dns_seeds(
+    seeds: &[String],
+    default_port: u16,
+) -> HashMap<SocketAddr, TransportType> {
+    let mut listeners = HashMap::new();
+    for seed in seeds {
+        // a seed without a port is not a valid socket address and is resolved with the default one
+        let addrs = seed
+            .to_socket_addrs()
+            .or_else(|_| (seed.as_str(), default_port).to_socket_addrs());
+        match addrs {
+            Ok(addrs) => {
+                for addr in addrs {
+                    listeners.insert(addr, TransportType::Tcp);
+                }
+            }
+            Err(err) => warn!("failed to resolve DNS seed {}: {}", seed, err),
+        }
+    }
+    listeners
+}
+
+/// Stops the thread resolving the DNS seeds
+pub(crate) struct DnsSeedStopper {
+    tx_stopper: Option<Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl DnsSeedStopper {
+    pub(crate) fn stop(&mut self) {
+        if let Some(tx) = self.tx_stopper.take() {
+            if tx.send(()).is_err() {
+                warn!("failed to send stop signal to the DNS seed thread");
+            }
+        }
+        if let Some(handle) = self.handle.take() {
+            if handle.join().is_err() {
+                warn!("failed to join the DNS seed thread");
+            }
+        }
+    }
+}
+
+/// Start the thread resolving the DNS seeds at startup and then at each `refresh_interval`,
+/// and sending the resolved addresses to the testers
+pub(crate) fn start_dns_seed_thread(
+    seeds: Vec<String>,
+    default_port: u16,
+    refresh_interval: Duration,
+    test_sender: MassaSender<(Option<PeerId>, HashMap<SocketAddr, TransportType>)>,
+) -> DnsSeedStopper {
+    let (tx_stopper, rx_stopper) = mpsc::channel();
+    let handle = std::thread::Builder::new()
+        .name(THREAD_NAME.to_string())
+        .spawn(move || loop {
+            let listeners = resolve_dns_seeds(&seeds, default_port);
+            info!(
+                "{} peer addresses resolved from the DNS seeds",
+                listeners.len()
+            );
+            // one message per address so that they are tested in parallel
+            for listener in listeners {
+                if let Err(err) = test_sender.try_send((None, HashMap::from([listener]))) {
+                    debug!(
+                        "error when sending DNS seed address to peer tester: {}",
+                        err
+                    );
+                }
+            }
+            // stop signal received, or stopper dropped
+            if rx_stopper.recv_timeout(refresh_interval) != Err(RecvTimeoutError::Timeout) {
+                break;
+            }
+        })
+        .expect("OS failed to start DNS seed thread");
+    DnsSeedStopper {
+        tx_stopper: Some(tx_stopper),
+        handle: Some(handle),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_dns_seeds() {
+        let seeds = vec!["127.0.0.1".to_string(), "[::1]:31245".to_string()];
+        let listeners = resolve_dns_seeds(&seeds, 31244);
+        assert_eq!(
+            listeners,
+            HashMap::from([
+                ("127.0.0.1:31244".parse().unwrap(), TransportType::Tcp),
+                ("[::1]:31245".parse().unwrap(), TransportType::Tcp),
+            ])
+        );
+    }
+}
@@ -40,6 +40,16 @@ impl From<&PeerManagementMessage> for MessageTypeId {
     }
 }
 
+impl MessageTypeId {
+    /// Name of the message type in the network metrics
+    pub(crate) fn metrics_name(&self) -> &'static str {
+        match self {
+            MessageTypeId::NewPeerConnected => "new_peer_connected",
+            MessageTypeId::ListPeers => "list_peers",
+        }
+    }
+}
+
 #[derive(Default, Clone)]
 pub struct PeerManagementMessageSerializer {
     id_serializer: U64VarIntSerializer,
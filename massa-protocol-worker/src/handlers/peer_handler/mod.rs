@@ -1,5 +1,10 @@
 use std::net::IpAddr;
-use std::{collections::HashMap, net::SocketAddr, thread::JoinHandle, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    net::SocketAddr,
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
 
 use crossbeam::channel::tick;
 use crossbeam::select;
@@ -9,10 +14,12 @@ use massa_metrics::MassaMetrics;
 use massa_models::config::SIGNATURE_DESER_SIZE;
 use massa_models::version::{VersionDeserializer, VersionSerializer};
 use massa_protocol_exports::{
-    BootstrapPeers, PeerId, PeerIdDeserializer, PeerIdSerializer, ProtocolConfig,
+    BootstrapPeers, PeerId, PeerIdDeserializer, PeerIdSerializer, PeerReputation, PeerScoreEvent,
+    ProtocolConfig,
 };
 use massa_serialization::{DeserializeError, Deserializer, Serializer};
 use massa_signature::Signature;
+use massa_time::MassaTime;
 use peernet::context::Context as _;
 use peernet::messages::MessagesSerializer as _;
 use rand::{rngs::StdRng, RngCore, SeedableRng};
@@ -28,8 +35,11 @@ use tracing::{debug, error, info, warn};
 use crate::context::Context;
 use crate::handlers::peer_handler::models::PeerState;
 use crate::messages::{Message, MessagesHandler, MessagesSerializer};
+use crate::proxy::ProxyRelays;
 use crate::wrap_network::ActiveConnectionsTrait;
+use crate::wrap_peer_db::PeerDBTrait;
 
+use self::dns_seeds::{start_dns_seed_thread, DnsSeedStopper};
 use self::models::PeerInfo;
 use self::{
     models::{
@@ -40,7 +50,7 @@ use self::{
 
 use self::{
     announcement::{
-        Announcement, AnnouncementDeserializer, AnnouncementDeserializerArgs,
+        announced_listeners, Announcement, AnnouncementDeserializer, AnnouncementDeserializerArgs,
         AnnouncementSerializer,
     },
     messages::{PeerManagementMessageDeserializer, PeerManagementMessageDeserializerArgs},
@@ -50,17 +60,22 @@ use self::{
 /// This handler is here to check that announcements we receive are valid and
 /// that all the endpoints we received are active.
 mod announcement;
+mod dns_seeds;
 mod messages;
 pub mod models;
 mod tester;
 
-pub(crate) use messages::{PeerManagementMessage, PeerManagementMessageSerializer};
+pub(crate) use messages::{
+    MessageTypeId as PeerManagementMessageTypeId, PeerManagementMessage,
+    PeerManagementMessageSerializer,
+};
 
 pub struct PeerManagementHandler {
     pub peer_db: SharedPeerDB,
     pub thread_join: Option<JoinHandle<()>>,
     pub sender: PeerManagementChannel,
     testers: Vec<Tester>,
+    dns_seeds: Option<DnsSeedStopper>,
 }
 
 // protocol-peer-handler
@@ -97,9 +112,25 @@ impl PeerManagementHandler {
             messages_handler,
             target_out_connections,
             default_target_out_connections,
-            massa_metrics,
+            massa_metrics.clone(),
         );
 
+        // the DNS seeds are resolved with the port of our TCP listener when they have none
+        let dns_seed_port = config
+            .listeners
+            .iter()
+            .find(|(_, transport)| **transport == TransportType::Tcp)
+            .map(|(addr, _)| addr.port());
+        let dns_seeds = match dns_seed_port {
+            Some(port) if !config.dns_seeds.is_empty() => Some(start_dns_seed_thread(
+                config.dns_seeds.clone(),
+                port,
+                config.dns_seed_refresh_interval.into(),
+                test_sender.clone(),
+            )),
+            _ => None,
+        };
+
         let thread_join = std::thread::Builder::new()
         .name(THREAD_NAME.to_string())
         .spawn({
@@ -107,7 +138,8 @@ impl PeerManagementHandler {
             let ticker = tick(Duration::from_secs(10));
             let config = config.clone();
             let message_serializer = MessagesSerializer::new()
-                .with_peer_management_message_serializer(PeerManagementMessageSerializer::new());
+                .with_peer_management_message_serializer(PeerManagementMessageSerializer::new())
+                .with_massa_metrics(massa_metrics.clone());
             let message_deserializer =
                 PeerManagementMessageDeserializer::new(PeerManagementMessageDeserializerArgs {
                     max_peers_per_announcement: config.max_size_peers_announcement,
@@ -118,7 +150,23 @@ impl PeerManagementHandler {
                 loop {
                     select! {
                         recv(ticker) -> _ => {
-                            let peers_to_send = peer_db.read().get_rand_peers_to_send(100);
+                            // apply the edits made to the ban list file while the node runs
+                            let reloaded = peer_db.write().reload_ip_ban_list(&config.ip_ban_list_file);
+                            match reloaded {
+                                Ok(true) => {
+                                    info!("IP ban list reloaded from {}", config.ip_ban_list_file.display());
+                                    disconnect_banned_ips(&peer_db, active_connections.as_mut());
+                                }
+                                Ok(false) => {}
+                                Err(err) => warn!("error reloading ip ban list: {}", err),
+                            }
+                            let peers_to_send = {
+                                let peer_db_read = peer_db.read();
+                                let mut peers = peer_db_read.get_rand_peers_to_send(100);
+                                // do not advertise the peers with a low score
+                                peers.retain(|(id, _)| !is_peer_throttled(&*peer_db_read, id, &config));
+                                peers
+                            };
                             if peers_to_send.is_empty() {
                                 continue;
                             }
@@ -150,19 +198,93 @@ impl PeerManagementHandler {
                                     peer_db.write().unban_peer(&peer_id);
                                 }
                             },
+                             Ok(PeerManagementCmd::BanIps { ranges, asns }) => {
+                                for asn in &asns {
+                                    if !peer_db.read().get_ip_ban_list().is_asn_known(*asn) {
+                                        warn!("Banned ASN {} is not in the ASN database, it will have no effect", asn);
+                                    }
+                                }
+                                peer_db.write().ban_ips(&ranges, &asns);
+                                disconnect_banned_ips(&peer_db, active_connections.as_mut());
+                                if let Err(err) = peer_db.read().get_ip_ban_list().save(&config.ip_ban_list_file) {
+                                    error!("error saving ip ban list: {}", err);
+                                }
+                            },
+                             Ok(PeerManagementCmd::UnbanIps { ranges, asns }) => {
+                                peer_db.write().unban_ips(&ranges, &asns);
+                                if let Err(err) = peer_db.read().get_ip_ban_list().save(&config.ip_ban_list_file) {
+                                    error!("error saving ip ban list: {}", err);
+                                }
+                            },
+                             Ok(PeerManagementCmd::AllowIps(ranges)) => {
+                                peer_db.write().allow_ips(&ranges);
+                                disconnect_banned_ips(&peer_db, active_connections.as_mut());
+                                if let Err(err) = peer_db.read().get_ip_ban_list().save(&config.ip_ban_list_file) {
+                                    error!("error saving ip ban list: {}", err);
+                                }
+                            },
+                             Ok(PeerManagementCmd::DisallowIps(ranges)) => {
+                                peer_db.write().disallow_ips(&ranges);
+                                // the peers of the removed range are banned if other ranges remain allowed
+                                disconnect_banned_ips(&peer_db, active_connections.as_mut());
+                                if let Err(err) = peer_db.read().get_ip_ban_list().save(&config.ip_ban_list_file) {
+                                    error!("error saving ip ban list: {}", err);
+                                }
+                            },
+                             Ok(PeerManagementCmd::GetAllowedIps { responder }) => {
+                                let ranges = peer_db.read().get_ip_ban_list().allowed_ranges.iter().copied().collect();
+                                if let Err(err) = responder.try_send(ranges) {
+                                    warn!("error sending allowed ips: {:?}", err);
+                                }
+                             },
                              Ok(PeerManagementCmd::GetBootstrapPeers { responder }) => {
-                                let mut peers = peer_db.read().get_rand_peers_to_send(100);
+                                let mut peers = {
+                                    let peer_db_read = peer_db.read();
+                                    let mut peers = peer_db_read.get_rand_peers_to_send(100);
+                                    peers.retain(|(id, _)| !is_peer_throttled(&*peer_db_read, id, &config));
+                                    peers
+                                };
                                 // Add myself
-                                if let Some(routable_ip) = config.routable_ip {
-                                    let listeners = config.listeners.iter().map(|(addr, ty)| {
-                                        (SocketAddr::new(routable_ip, addr.port()), *ty)
-                                    }).collect();
+                                let listeners = announced_listeners(&config.listeners, &config.routable_ips());
+                                if !listeners.is_empty() {
                                     peers.push((peer_id, listeners));
                                 }
                                 if let Err(err) = responder.try_send(BootstrapPeers(peers)) {
                                     warn!("error sending bootstrap peers: {:?}", err);
                                 }
                              },
+                             Ok(PeerManagementCmd::ScoreEvent { peer_id, event }) => {
+                                on_peer_score_event(&peer_db, active_connections.as_mut(), &config, &peer_id, event);
+                             },
+                             Ok(PeerManagementCmd::GetPeers { responder }) => {
+                                let peer_db_read = peer_db.read();
+                                let now = MassaTime::now();
+                                let peer_ids: HashSet<PeerId> = peer_db_read.get_peers().keys()
+                                    .chain(peer_db_read.get_peer_scores().keys())
+                                    .copied()
+                                    .collect();
+                                let peers = peer_ids.into_iter().map(|id| {
+                                    let score = peer_db_read.get_peer_scores().get(&id).cloned().unwrap_or_default();
+                                    let current_score = score.get_score(now, config.peer_score_half_life);
+                                    let peer_info = peer_db_read.get_peers().get(&id);
+                                    let mut listeners: Vec<SocketAddr> = peer_info
+                                        .and_then(|peer| peer.last_announce.as_ref())
+                                        .map(|announce| announce.listeners.keys().copied().collect())
+                                        .unwrap_or_default();
+                                    listeners.sort();
+                                    let reputation = PeerReputation {
+                                        score,
+                                        current_score,
+                                        throttled: current_score < config.peer_score_throttle_threshold,
+                                        banned: peer_info.map_or(false, |peer| peer.state == PeerState::Banned),
+                                        listeners,
+                                    };
+                                    (id, reputation)
+                                }).collect();
+                                if let Err(err) = responder.try_send(peers) {
+                                    warn!("error sending peers: {:?}", err);
+                                }
+                             },
                              Ok(PeerManagementCmd::Stop) => {
                                 while let Ok(_msg) = test_receiver.try_recv() {
                                     // nothing to do just clean the channel
@@ -182,6 +304,7 @@ impl PeerManagementHandler {
                                     return;
                                 }
                             };
+                            let start = Instant::now();
                             // check if peer is banned
                             if let Some(peer) = peer_db.read().get_peers().get(&peer_id) {
                                 if peer.state == PeerState::Banned {
@@ -194,29 +317,33 @@ impl PeerManagementHandler {
                                 Ok((rest, message)) => (rest, message),
                                 Err(e) => {
                                     warn!("error when deserializing message: {:?}", e);
+                                    on_peer_score_event(&peer_db, active_connections.as_mut(), &config, &peer_id, PeerScoreEvent::InvalidMessage);
                                     continue;
                                 }
                             };
                             if !rest.is_empty() {
                                 warn!("message not fully deserialized");
+                                on_peer_score_event(&peer_db, active_connections.as_mut(), &config, &peer_id, PeerScoreEvent::InvalidMessage);
                                 continue;
                             }
+                            let message_type = PeerManagementMessageTypeId::from(&message).metrics_name();
                             match message {
                                 PeerManagementMessage::NewPeerConnected((peer_id, listeners)) => {
                                     debug!("Received peer message: NewPeerConnected from {}", peer_id);
-                                        if let Err(e) = test_sender.try_send((peer_id, listeners)) {
+                                        if let Err(e) = test_sender.try_send((Some(peer_id), listeners)) {
                                             debug!("error when sending msg to peer connect : {}", e);
                                         }
                                 }
                                 PeerManagementMessage::ListPeers(peers) => {
                                     debug!("Received peer message: List peers from {}", peer_id);
                                     for (peer_id, listeners) in peers.into_iter() {
-                                        if let Err(e) = test_sender.try_send((peer_id, listeners)) {
+                                        if let Err(e) = test_sender.try_send((Some(peer_id), listeners)) {
                                             debug!("error when sending msg to peer tester : {}", e);
                                         }
                                     }
                                 }
                             }
+                            massa_metrics.record_network_message_processing(message_type, start.elapsed());
                         }
                     }
                 }
@@ -242,6 +369,7 @@ impl PeerManagementHandler {
                 command_sender: sender_cmd,
             },
             testers,
+            dns_seeds,
         }
     }
 
@@ -251,6 +379,11 @@ impl PeerManagementHandler {
             .send(PeerManagementCmd::Stop)
             .unwrap();
 
+        // the testers stop once all the senders of their channel are dropped
+        if let Some(mut dns_seeds) = self.dns_seeds.take() {
+            dns_seeds.stop();
+        }
+
         // waiting for all threads to finish
         self.testers.iter_mut().for_each(|tester| {
             if let Some(join_handle) = tester.handler.take() {
@@ -260,6 +393,58 @@ impl PeerManagementHandler {
     }
 }
 
+/// Whether a peer is throttled because of its low score:
+/// we neither connect to it nor advertise it to the other peers
+pub(crate) fn is_peer_throttled(
+    peer_db: &dyn PeerDBTrait,
+    peer_id: &PeerId,
+    config: &ProtocolConfig,
+) -> bool {
+    peer_db.get_peer_score(peer_id, config.peer_score_half_life)
+        < config.peer_score_throttle_threshold
+}
+
+/// Apply a score event to a peer, and ban it if its score falls below the ban threshold
+fn on_peer_score_event(
+    peer_db: &SharedPeerDB,
+    active_connections: &mut dyn ActiveConnectionsTrait,
+    config: &ProtocolConfig,
+    peer_id: &PeerId,
+    event: PeerScoreEvent,
+) {
+    let score = peer_db
+        .write()
+        .apply_peer_score_event(peer_id, event, config.peer_score_half_life);
+    if score >= config.peer_score_ban_threshold {
+        return;
+    }
+    let banned = peer_db
+        .read()
+        .get_peers()
+        .get(peer_id)
+        .map_or(false, |peer| peer.state == PeerState::Banned);
+    if !banned {
+        info!(
+            "Banning peer {} because of its low score: {}",
+            peer_id, score
+        );
+        active_connections.shutdown_connection(peer_id);
+        peer_db.write().ban_peer(peer_id);
+    }
+}
+
+/// Close the connections to the peers whose IP is banned
+fn disconnect_banned_ips(
+    peer_db: &SharedPeerDB,
+    active_connections: &mut dyn ActiveConnectionsTrait,
+) {
+    for (peer_id, (addr, _, _)) in active_connections.get_peers_connected() {
+        if peer_db.read().is_ip_banned(&addr.ip()) {
+            active_connections.shutdown_connection(&peer_id);
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct MassaHandshake {
     pub announcement_serializer: AnnouncementSerializer,
@@ -268,15 +453,22 @@ pub struct MassaHandshake {
     pub version_deserializer: VersionDeserializer,
     pub config: ProtocolConfig,
     pub peer_db: SharedPeerDB,
+    /// relays of the outbound connections when they go through a proxy
+    proxy_relays: Option<ProxyRelays>,
     peer_mngt_msg_serializer: MessagesSerializer,
     peer_id_serializer: PeerIdSerializer,
     peer_id_deserializer: PeerIdDeserializer,
 }
 
 impl MassaHandshake {
-    pub fn new(peer_db: SharedPeerDB, config: ProtocolConfig) -> Self {
+    pub(crate) fn new(
+        peer_db: SharedPeerDB,
+        config: ProtocolConfig,
+        proxy_relays: Option<ProxyRelays>,
+    ) -> Self {
         Self {
             peer_db,
+            proxy_relays,
             announcement_serializer: AnnouncementSerializer::new(),
             announcement_deserializer: AnnouncementDeserializer::new(
                 AnnouncementDeserializerArgs {
@@ -307,7 +499,16 @@ impl InitConnectionHandler<PeerId, Context, MessagesHandler> for MassaHandshake
         listeners: &HashMap<SocketAddr, TransportType>,
         messages_handler: MessagesHandler,
     ) -> PeerNetResult<PeerId> {
-        let addr = *endpoint.get_target_addr();
+        let mut addr = *endpoint.get_target_addr();
+        if let Some(proxy_relays) = &self.proxy_relays {
+            addr = proxy_relays.peer_addr(&addr);
+        }
+        if self.peer_db.read().is_ip_banned(&addr.ip()) {
+            return Err(PeerNetError::HandshakeError.error(
+                "Massa Handshake",
+                Some(format!("IP {} is banned", addr.ip())),
+            ));
+        }
         let mut bytes = vec![];
         self.peer_id_serializer
             .serialize(&context.get_peer_id(), &mut bytes)
@@ -328,12 +529,9 @@ impl InitConnectionHandler<PeerId, Context, MessagesHandler> for MassaHandshake
                 )
             })?;
         bytes.push(0);
-        let listeners_announcement = Announcement::new(
-            listeners.clone(),
-            self.config.routable_ip,
-            &context.our_keypair,
-        )
-        .unwrap();
+        let listeners_announcement =
+            Announcement::new(listeners, &self.config.routable_ips(), &context.our_keypair)
+                .unwrap();
         self.announcement_serializer
             .serialize(&listeners_announcement, &mut bytes)
             .map_err(|err| {
@@ -624,15 +822,21 @@ mod tests {
     use std::{collections::HashMap, ops::Deref, sync::Arc};
 
     use massa_channel::MassaChannel;
-    use massa_protocol_exports::ProtocolConfig;
+    use massa_metrics::MassaMetrics;
+    use massa_protocol_exports::{PeerId, PeerScoreEvent, ProtocolConfig};
     use massa_serialization::U64VarIntDeserializer;
     use massa_signature::KeyPair;
+    use mockall::predicate;
     use parking_lot::RwLock;
     use peernet::{peer::InitConnectionHandler, transports::endpoint::Endpoint};
 
-    use crate::{context::Context, messages::MessagesHandler};
+    use crate::{
+        bandwidth::BandwidthLimiter, context::Context, messages::MessagesHandler,
+        wrap_network::MockActiveConnectionsTrait,
+    };
 
-    use super::models::PeerDB;
+    use super::models::{PeerDB, PeerInfo, PeerState, SharedPeerDB};
+    use super::{is_peer_throttled, on_peer_score_event};
 
     #[test]
     fn test_handshake_working_behaviour() {
@@ -641,7 +845,8 @@ mod tests {
         let (sender_operations, _) = MassaChannel::new(String::from("test_operations"), None);
         let (sender_peers, _) = MassaChannel::new(String::from("test_peers"), None);
         let shared_peer_db = Arc::new(RwLock::new(PeerDB::default()));
-        let mut handshake = super::MassaHandshake::new(shared_peer_db, ProtocolConfig::default());
+        let mut handshake =
+            super::MassaHandshake::new(shared_peer_db, ProtocolConfig::default(), None);
         let our_keypair = KeyPair::generate(0).unwrap();
         let messages_handlers = MessagesHandler {
             id_deserializer: U64VarIntDeserializer::new(
@@ -652,6 +857,14 @@ mod tests {
             sender_endorsements,
             sender_operations,
             sender_peers,
+            bandwidth_limiter: Arc::new(BandwidthLimiter::default()),
+            massa_metrics: MassaMetrics::new(
+                false,
+                "0.0.0.0:9898".parse().unwrap(),
+                32,
+                std::time::Duration::from_secs(5),
+            )
+            .0,
         };
         let (local_sender, remote_receiver) =
             MassaChannel::new(String::from("Test_transport_local_to_remote"), None);
@@ -702,7 +915,8 @@ mod tests {
         let (sender_operations, _) = MassaChannel::new(String::from("test_operations"), None);
         let (sender_peers, _) = MassaChannel::new(String::from("test_peers"), None);
         let shared_peer_db = Arc::new(RwLock::new(PeerDB::default()));
-        let mut handshake = super::MassaHandshake::new(shared_peer_db, ProtocolConfig::default());
+        let mut handshake =
+            super::MassaHandshake::new(shared_peer_db, ProtocolConfig::default(), None);
         let our_keypair = KeyPair::generate(0).unwrap();
         let messages_handlers = MessagesHandler {
             id_deserializer: U64VarIntDeserializer::new(
@@ -713,6 +927,14 @@ mod tests {
             sender_endorsements,
             sender_operations,
             sender_peers,
+            bandwidth_limiter: Arc::new(BandwidthLimiter::default()),
+            massa_metrics: MassaMetrics::new(
+                false,
+                "0.0.0.0:9898".parse().unwrap(),
+                32,
+                std::time::Duration::from_secs(5),
+            )
+            .0,
         };
         let (local_sender, _) =
             MassaChannel::new(String::from("Test_transport_local_to_remote"), None);
@@ -747,7 +969,8 @@ mod tests {
         let (sender_operations, _) = MassaChannel::new(String::from("test_operations"), None);
         let (sender_peers, _) = MassaChannel::new(String::from("test_peers"), None);
         let shared_peer_db = Arc::new(RwLock::new(PeerDB::default()));
-        let mut handshake = super::MassaHandshake::new(shared_peer_db, ProtocolConfig::default());
+        let mut handshake =
+            super::MassaHandshake::new(shared_peer_db, ProtocolConfig::default(), None);
         let our_keypair = KeyPair::generate(0).unwrap();
         let messages_handlers = MessagesHandler {
             id_deserializer: U64VarIntDeserializer::new(
@@ -758,6 +981,14 @@ mod tests {
             sender_endorsements,
             sender_operations,
             sender_peers,
+            bandwidth_limiter: Arc::new(BandwidthLimiter::default()),
+            massa_metrics: MassaMetrics::new(
+                false,
+                "0.0.0.0:9898".parse().unwrap(),
+                32,
+                std::time::Duration::from_secs(5),
+            )
+            .0,
         };
         let (local_sender, _) =
             MassaChannel::new(String::from("Test_transport_local_to_remote"), None);
@@ -778,4 +1009,59 @@ mod tests {
         );
         assert!(res.is_err());
     }
+
+    #[test]
+    fn test_peer_score_throttle_and_ban() {
+        let config = ProtocolConfig::default();
+        let peer_id = PeerId::from_public_key(KeyPair::generate(0).unwrap().get_public_key());
+        let peer_db: SharedPeerDB = Arc::new(RwLock::new(PeerDB::default()));
+        peer_db.write().get_peers_mut().insert(
+            peer_id,
+            PeerInfo {
+                last_announce: None,
+                state: PeerState::Trusted,
+            },
+        );
+        let mut active_connections = MockActiveConnectionsTrait::new();
+        active_connections
+            .expect_shutdown_connection()
+            .times(1)
+            .with(predicate::eq(peer_id))
+            .return_const(());
+
+        // each invalid message costs 20 points: the peer is throttled below -20,
+        // and banned below -50
+        let report_invalid_message = |active_connections: &mut MockActiveConnectionsTrait| {
+            on_peer_score_event(
+                &peer_db,
+                active_connections,
+                &config,
+                &peer_id,
+                PeerScoreEvent::InvalidMessage,
+            )
+        };
+        report_invalid_message(&mut active_connections);
+        assert!(!is_peer_throttled(&*peer_db.read(), &peer_id, &config));
+        report_invalid_message(&mut active_connections);
+        assert!(is_peer_throttled(&*peer_db.read(), &peer_id, &config));
+        assert_eq!(
+            peer_db.read().get_peers()[&peer_id].state,
+            PeerState::Trusted
+        );
+        report_invalid_message(&mut active_connections);
+        assert_eq!(
+            peer_db.read().get_peers()[&peer_id].state,
+            PeerState::Banned
+        );
+
+        // the peer gets a fresh start when unbanned
+        peer_db.write().unban_peer(&peer_id);
+        assert!(!is_peer_throttled(&*peer_db.read(), &peer_id, &config));
+        assert_eq!(
+            peer_db
+                .read()
+                .get_peer_score(&peer_id, config.peer_score_half_life),
+            0.0
+        );
+    }
 }
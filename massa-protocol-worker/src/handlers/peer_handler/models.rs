@@ -1,5 +1,8 @@
 use massa_channel::sender::MassaSender;
-use massa_protocol_exports::{BootstrapPeers, PeerId};
+use massa_protocol_exports::{
+    BootstrapPeers, IpBanList, IpCidr, PeerId, PeerReputation, PeerScore, PeerScoreEvent,
+    ProtocolError,
+};
 use massa_time::MassaTime;
 use parking_lot::RwLock;
 use peernet::transports::TransportType;
@@ -8,7 +11,12 @@ use rand::{thread_rng, Rng};
 use std::cmp::Ordering;
 use std::collections::HashSet;
 use std::time::Duration;
-use std::{collections::HashMap, net::SocketAddr, sync::Arc};
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+    path::Path,
+    sync::Arc,
+};
 use tracing::info;
 
 use crate::wrap_peer_db::PeerDBTrait;
@@ -155,6 +163,10 @@ pub struct PeerDB {
     pub try_connect_history: HashMap<SocketAddr, ConnectionMetadata>,
     /// peers currently tested
     pub peers_in_test: HashSet<SocketAddr>,
+    /// banned IP ranges and ASNs
+    pub ip_ban_list: IpBanList,
+    /// scores of the peers, the ones without an entry have a score of 0
+    pub peer_scores: HashMap<PeerId, PeerScore>,
 }
 
 pub type SharedPeerDB = Arc<RwLock<dyn PeerDBTrait>>;
@@ -180,9 +192,29 @@ pub enum PeerState {
 pub enum PeerManagementCmd {
     Ban(Vec<PeerId>),
     Unban(Vec<PeerId>),
+    BanIps {
+        ranges: Vec<IpCidr>,
+        asns: Vec<u32>,
+    },
+    UnbanIps {
+        ranges: Vec<IpCidr>,
+        asns: Vec<u32>,
+    },
+    AllowIps(Vec<IpCidr>),
+    DisallowIps(Vec<IpCidr>),
+    GetAllowedIps {
+        responder: MassaSender<Vec<IpCidr>>,
+    },
     GetBootstrapPeers {
         responder: MassaSender<BootstrapPeers>,
     },
+    ScoreEvent {
+        peer_id: PeerId,
+        event: PeerScoreEvent,
+    },
+    GetPeers {
+        responder: MassaSender<HashMap<PeerId, PeerReputation>>,
+    },
     Stop,
 }
 
@@ -202,6 +234,8 @@ impl PeerDBTrait for PeerDB {
     }
 
     fn unban_peer(&mut self, peer_id: &PeerId) {
+        // give the peer a fresh start
+        self.peer_scores.remove(peer_id);
         if let Some(peer) = self.peers.get_mut(peer_id) {
             // We set the state to HandshakeFailed to force the peer to be tested again
             peer.state = PeerState::HandshakeFailed;
@@ -211,6 +245,61 @@ impl PeerDBTrait for PeerDB {
         };
     }
 
+    fn ban_ips(&mut self, ranges: &[IpCidr], asns: &[u32]) {
+        self.ip_ban_list.ban(ranges, asns);
+        info!("Banned ip ranges: {:?}, asns: {:?}", ranges, asns);
+    }
+
+    fn unban_ips(&mut self, ranges: &[IpCidr], asns: &[u32]) {
+        self.ip_ban_list.unban(ranges, asns);
+        info!("Unbanned ip ranges: {:?}, asns: {:?}", ranges, asns);
+    }
+
+    fn allow_ips(&mut self, ranges: &[IpCidr]) {
+        self.ip_ban_list.allow(ranges);
+        info!("Allowed ip ranges: {:?}", ranges);
+    }
+
+    fn disallow_ips(&mut self, ranges: &[IpCidr]) {
+        self.ip_ban_list.disallow(ranges);
+        info!("Disallowed ip ranges: {:?}", ranges);
+    }
+
+    fn reload_ip_ban_list(&mut self, ban_list_file: &Path) -> Result<bool, ProtocolError> {
+        self.ip_ban_list.reload_if_modified(ban_list_file)
+    }
+
+    fn is_ip_banned(&self, ip: &IpAddr) -> bool {
+        self.ip_ban_list.is_banned(ip)
+    }
+
+    fn get_ip_ban_list(&self) -> &IpBanList {
+        &self.ip_ban_list
+    }
+
+    fn apply_peer_score_event(
+        &mut self,
+        peer_id: &PeerId,
+        event: PeerScoreEvent,
+        half_life: MassaTime,
+    ) -> f64 {
+        self.peer_scores.entry(*peer_id).or_default().apply_event(
+            event,
+            MassaTime::now(),
+            half_life,
+        )
+    }
+
+    fn get_peer_score(&self, peer_id: &PeerId, half_life: MassaTime) -> f64 {
+        self.peer_scores
+            .get(peer_id)
+            .map_or(0.0, |score| score.get_score(MassaTime::now(), half_life))
+    }
+
+    fn get_peer_scores(&self) -> &HashMap<PeerId, PeerScore> {
+        &self.peer_scores
+    }
+
     /// Retrieve the peer with the oldest test date.
     fn get_oldest_peer(
         &self,
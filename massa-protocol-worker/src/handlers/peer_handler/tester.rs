@@ -46,8 +46,8 @@ impl Tester {
         massa_metrics: MassaMetrics,
     ) -> (
         (
-            MassaSender<(PeerId, HashMap<SocketAddr, TransportType>)>,
-            MassaReceiver<(PeerId, HashMap<SocketAddr, TransportType>)>,
+            MassaSender<(Option<PeerId>, HashMap<SocketAddr, TransportType>)>,
+            MassaReceiver<(Option<PeerId>, HashMap<SocketAddr, TransportType>)>,
         ),
         Vec<Tester>,
     ) {
@@ -89,9 +89,15 @@ impl Tester {
         let our_version = config.version;
 
         let exec_handshake = || {
-            let mut socket =
-                std::net::TcpStream::connect_timeout(&addr, config.tester_timeout.into())
-                    .map_err(|e| PeerNetError::PeerConnectionError.new("connect", e, None))?;
+            if peer_db.read().is_ip_banned(&addr.ip()) {
+                return Err(PeerNetError::PeerConnectionError
+                    .error("connect", Some(format!("IP {} is banned", addr.ip()))));
+            }
+            let mut socket = match &config.proxy {
+                Some(proxy) => proxy.connect(addr, Some(config.tester_timeout.into())),
+                None => std::net::TcpStream::connect_timeout(&addr, config.tester_timeout.into()),
+            }
+            .map_err(|e| PeerNetError::PeerConnectionError.new("connect", e, None))?;
             socket
                 .set_read_timeout(Some(config.tester_timeout.into()))
                 .map_err(|err| PeerNetError::PeerConnectionError.new("read timeout", err, None))?;
@@ -272,7 +278,7 @@ impl Tester {
         peer_db: SharedPeerDB,
         active_connections: Box<dyn ActiveConnectionsTrait>,
         protocol_config: ProtocolConfig,
-        receiver: MassaReceiver<(PeerId, HashMap<SocketAddr, TransportType>)>,
+        receiver: MassaReceiver<(Option<PeerId>, HashMap<SocketAddr, TransportType>)>,
         messages_handler: MessagesHandler,
         target_out_connections: HashMap<String, (Vec<IpAddr>, usize)>,
         default_target_out_connections: usize,
@@ -293,6 +299,7 @@ impl Tester {
 
             //let mut network_manager = PeerNetManager::new(config);
             let protocol_config = protocol_config.clone();
+            let routable_ips = protocol_config.routable_ips();
             'main_loop: loop {
                 crossbeam::select! {
                     recv(receiver) -> res => {
@@ -333,8 +340,8 @@ impl Tester {
                                 {
                                     let now = MassaTime::now();
                                     let db = db.clone();
-                                    // receive new listener to test
-                                    for (addr, _) in listener.1.iter() {
+                                    // receive new listener to test, the tester only speaking TCP
+                                    for (addr, _) in listener.1.iter().filter(|(_, transport)| **transport == TransportType::Tcp) {
                                         if !db.write().insert_peer_in_test(addr) {
                                             // if the peer is already in test, we skip it
                                             continue;
@@ -380,13 +387,11 @@ impl Tester {
                                             }
 
                                             //Don't test our proper ip
-                                            if let Some(ip) = protocol_config.routable_ip {
-                                                if to_canonical(ip) == ip_canonical {
-                                                    db.write().remove_peer_in_test(addr);
-                                                    continue 'main_loop;
-                                                }
+                                            if routable_ips.iter().any(|ip| to_canonical(*ip) == ip_canonical) {
+                                                db.write().remove_peer_in_test(addr);
+                                                continue 'main_loop;
                                             }
-                                            debug!("testing peer {} listener addr: {}", &listener.0, &addr);
+                                            debug!("testing peer {:?} listener addr: {}", &listener.0, &addr);
 
                                             let res = Tester::tcp_handshake(
                                                 messages_handler.clone(),
@@ -446,11 +451,9 @@ impl Tester {
                             }
                         }
                         //Don't test our proper ip
-                        if let Some(ip) = protocol_config.routable_ip {
-                            if to_canonical(ip) == ip_canonical {
-                                db.write().remove_peer_in_test(&listener);
-                                continue;
-                            }
+                        if routable_ips.iter().any(|ip| to_canonical(*ip) == ip_canonical) {
+                            db.write().remove_peer_in_test(&listener);
+                            continue;
                         }
                         debug!("testing listener addr: {}", &listener);
 
@@ -1,3 +1,4 @@
+mod bandwidth;
 mod connectivity;
 mod context;
 mod controller;
@@ -5,6 +6,8 @@ mod handlers;
 mod ip;
 mod manager;
 mod messages;
+mod nat;
+mod proxy;
 mod sig_verifier;
 mod worker;
 mod wrap_network;
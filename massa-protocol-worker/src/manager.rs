@@ -5,16 +5,22 @@ use massa_protocol_exports::ProtocolManager;
 use tracing::info;
 
 use crate::connectivity::ConnectivityCommand;
+use crate::nat::PortMappingStopper;
 
 /// protocol manager used to stop the protocol
 pub struct ProtocolManagerImpl {
     connectivity_thread: Option<(MassaSender<ConnectivityCommand>, JoinHandle<()>)>,
+    port_mapping: Option<PortMappingStopper>,
 }
 
 impl ProtocolManagerImpl {
-    pub fn new(connectivity_thread: (MassaSender<ConnectivityCommand>, JoinHandle<()>)) -> Self {
+    pub(crate) fn new(
+        connectivity_thread: (MassaSender<ConnectivityCommand>, JoinHandle<()>),
+        port_mapping: Option<PortMappingStopper>,
+    ) -> Self {
         Self {
             connectivity_thread: Some(connectivity_thread),
+            port_mapping,
         }
     }
 }
@@ -31,5 +37,8 @@ impl ProtocolManager for ProtocolManagerImpl {
                 .join()
                 .expect("connectivity thread panicked on try to join");
         }
+        if let Some(mut port_mapping) = self.port_mapping.take() {
+            port_mapping.stop();
+        }
     }
 }
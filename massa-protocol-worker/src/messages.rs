@@ -1,4 +1,5 @@
 use massa_channel::sender::MassaSender;
+use massa_metrics::MassaMetrics;
 use massa_protocol_exports::PeerId;
 use massa_serialization::{
     DeserializeError, Deserializer, Serializer, U64VarIntDeserializer, U64VarIntSerializer,
@@ -10,14 +11,21 @@ use peernet::{
         MessagesHandler as PeerNetMessagesHandler, MessagesSerializer as PeerNetMessagesSerializer,
     },
 };
+use std::{sync::Arc, time::Instant};
 use tracing::debug;
 
-use crate::handlers::{
-    block_handler::{BlockMessage, BlockMessageSerializer},
-    endorsement_handler::{EndorsementMessage, EndorsementMessageSerializer},
-    operation_handler::{OperationMessage, OperationMessageSerializer},
-    peer_handler::{
-        models::PeerMessageTuple, PeerManagementMessage, PeerManagementMessageSerializer,
+use crate::{
+    bandwidth::BandwidthLimiter,
+    handlers::{
+        block_handler::{BlockMessage, BlockMessageSerializer, BlockMessageTypeId},
+        endorsement_handler::{
+            EndorsementMessage, EndorsementMessageSerializer, EndorsementMessageTypeId,
+        },
+        operation_handler::{OperationMessage, OperationMessageSerializer, OperationMessageTypeId},
+        peer_handler::{
+            models::PeerMessageTuple, PeerManagementMessage, PeerManagementMessageSerializer,
+            PeerManagementMessageTypeId,
+        },
     },
 };
 
@@ -50,6 +58,42 @@ impl From<&Message> for MessageTypeId {
 }
 
 //TODO: Macroize this
+impl Message {
+    /// Name of the message type in the network metrics
+    pub(crate) fn metrics_name(&self) -> &'static str {
+        match self {
+            Message::Block(message) => BlockMessageTypeId::from(message.as_ref()).metrics_name(),
+            Message::Endorsement(message) => EndorsementMessageTypeId::from(message).metrics_name(),
+            Message::Operation(message) => OperationMessageTypeId::from(message).metrics_name(),
+            Message::PeerManagement(message) => {
+                PeerManagementMessageTypeId::from(message.as_ref()).metrics_name()
+            }
+        }
+    }
+}
+
+impl MessageTypeId {
+    /// Name in the network metrics of a message of this type, given the type id of the message
+    /// in its handler
+    fn metrics_name(&self, handler_id: u64) -> &'static str {
+        let name = match self {
+            MessageTypeId::Block => BlockMessageTypeId::try_from(handler_id)
+                .ok()
+                .map(|id| id.metrics_name()),
+            MessageTypeId::Endorsement => EndorsementMessageTypeId::try_from(handler_id)
+                .ok()
+                .map(|id| id.metrics_name()),
+            MessageTypeId::Operation => OperationMessageTypeId::try_from(handler_id)
+                .ok()
+                .map(|id| id.metrics_name()),
+            MessageTypeId::PeerManagement => PeerManagementMessageTypeId::try_from(handler_id)
+                .ok()
+                .map(|id| id.metrics_name()),
+        };
+        name.unwrap_or("unknown")
+    }
+}
+
 impl From<BlockMessage> for Message {
     fn from(message: BlockMessage) -> Self {
         Self::Block(Box::from(message))
@@ -81,6 +125,7 @@ pub struct MessagesSerializer {
     operation_message_serializer: Option<OperationMessageSerializer>,
     endorsement_message_serializer: Option<EndorsementMessageSerializer>,
     peer_management_message_serializer: Option<PeerManagementMessageSerializer>,
+    massa_metrics: Option<MassaMetrics>,
 }
 
 impl Default for MessagesSerializer {
@@ -97,6 +142,7 @@ impl MessagesSerializer {
             operation_message_serializer: None,
             endorsement_message_serializer: None,
             peer_management_message_serializer: None,
+            massa_metrics: None,
         }
     }
 
@@ -131,11 +177,31 @@ impl MessagesSerializer {
         self.peer_management_message_serializer = Some(peer_management_message_serializer);
         self
     }
+
+    /// Record the serialized messages in the network metrics, as messages sent to the peers
+    pub fn with_massa_metrics(mut self, massa_metrics: MassaMetrics) -> Self {
+        self.massa_metrics = Some(massa_metrics);
+        self
+    }
 }
 
 impl PeerNetMessagesSerializer<Message> for MessagesSerializer {
     /// Serialize the message
     fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> PeerNetResult<()> {
+        let initial_len = buffer.len();
+        self.serialize_message(message, buffer)?;
+        if let Some(massa_metrics) = &self.massa_metrics {
+            massa_metrics.record_network_message_sent(
+                message.metrics_name(),
+                (buffer.len() - initial_len) as u64,
+            );
+        }
+        Ok(())
+    }
+}
+
+impl MessagesSerializer {
+    fn serialize_message(&self, message: &Message, buffer: &mut Vec<u8>) -> PeerNetResult<()> {
         self.id_serializer
             .serialize(
                 &MessageTypeId::from(message).try_into().map_err(|_| {
@@ -224,10 +290,20 @@ pub struct MessagesHandler {
     pub sender_endorsements: MassaSender<PeerMessageTuple>,
     pub sender_operations: MassaSender<PeerMessageTuple>,
     pub sender_peers: MassaSender<PeerMessageTuple>,
+    pub bandwidth_limiter: Arc<BandwidthLimiter>,
+    pub massa_metrics: MassaMetrics,
 }
 
 impl PeerNetMessagesHandler<PeerId> for MessagesHandler {
     fn handle(&self, data: &[u8], peer_id: &PeerId) -> PeerNetResult<()> {
+        // Pausing the reading of the messages of the peer slows it down to the download limits
+        let wait = self
+            .bandwidth_limiter
+            .on_download(peer_id, data.len() as u64, Instant::now());
+        if !wait.is_zero() {
+            std::thread::sleep(wait);
+        }
+        let size = data.len() as u64;
         let (data, raw_id) = self
             .id_deserializer
             .deserialize::<DeserializeError>(data)
@@ -243,6 +319,13 @@ impl PeerNetMessagesHandler<PeerId> for MessagesHandler {
                 Some(String::from("Invalid message type id")),
             )
         })?;
+        // the message starts with its type id in its handler
+        let handler_id = self
+            .id_deserializer
+            .deserialize::<DeserializeError>(data)
+            .map_or(u64::MAX, |(_, handler_id)| handler_id);
+        self.massa_metrics
+            .record_network_message_received(id.metrics_name(handler_id), size);
         match id {
             // Blocks are high-priority: we block if the channel is full.
             // This means that the sender will be blocked until the message is sent.
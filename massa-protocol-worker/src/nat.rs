@@ -0,0 +1,304 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Reachability of the node by the other peers.
+//!
+//! A node behind a NAT cannot be reached by the other peers unless its router forwards the ports
+//! of its listeners. When enabled, the ports are mapped on the router through UPnP, or through
+//! NAT-PMP if no UPnP gateway answers, and the mappings are renewed until the node stops.
+//! Whether the mapping works or not, the node is considered reachable as long as it accepts
+//! connections from the other peers.
+
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr, UdpSocket};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use igd_next::{Gateway, PortMappingProtocol, SearchOptions};
+use massa_models::stats::Reachability;
+use massa_protocol_exports::{ProtocolError, TransportType};
+use massa_time::MassaTime;
+use natpmp::{Natpmp, Protocol, Response};
+use tracing::{debug, info, warn};
+
+// protocol-port-mapping
+const THREAD_NAME: &str = "p-port-mapping";
+static_assertions::const_assert!(THREAD_NAME.len() < 16);
+
+/// Time to wait for the answers of the router
+const GATEWAY_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Description of the port mappings on the router
+const MAPPING_DESCRIPTION: &str = "massa node";
+
+enum PortMappingMethod {
+    Upnp {
+        gateway: Gateway,
+        /// address of the node on the local network of the gateway
+        local_ip: IpAddr,
+    },
+    NatPmp,
+}
+
+/// Ports of the listeners of the node mapped on its router
+pub(crate) struct PortMapper {
+    method: PortMappingMethod,
+    /// port of each listener, and whether it uses UDP rather than TCP
+    ports: Vec<(u16, bool)>,
+    lease_duration: Duration,
+    external_ip: IpAddr,
+}
+
+impl PortMapper {
+    /// Find the router of the node and map the ports of the listeners on it
+    pub(crate) fn new(
+        listeners: &HashMap<SocketAddr, TransportType>,
+        lease_duration: Duration,
+    ) -> Result<Self, ProtocolError> {
+        let ports = listeners
+            .iter()
+            .map(|(addr, transport)| (addr.port(), *transport == TransportType::Quic))
+            .collect();
+        let search_options = SearchOptions {
+            timeout: Some(GATEWAY_TIMEOUT),
+            ..Default::default()
+        };
+        let mapper = match igd_next::search_gateway(search_options) {
+            Ok(gateway) => {
+                let external_ip = gateway.get_external_ip().map_err(|err| {
+                    ProtocolError::GeneralProtocolError(format!(
+                        "failed to get the external IP from the UPnP gateway: {}",
+                        err
+                    ))
+                })?;
+                // the address of the node as seen by the gateway
+                let local_ip = UdpSocket::bind(("0.0.0.0", 0))
+                    .and_then(|socket| {
+                        socket.connect(gateway.addr)?;
+                        socket.local_addr()
+                    })?
+                    .ip();
+                PortMapper {
+                    method: PortMappingMethod::Upnp { gateway, local_ip },
+                    ports,
+                    lease_duration,
+                    external_ip,
+                }
+            }
+            Err(err) => {
+                debug!("no UPnP gateway found, trying NAT-PMP: {}", err);
+                let response = natpmp_request(|natpmp| natpmp.send_public_address_request())?;
+                let Response::Gateway(response) = response else {
+                    return Err(ProtocolError::GeneralProtocolError(
+                        "unexpected NAT-PMP response".to_string(),
+                    ));
+                };
+                PortMapper {
+                    method: PortMappingMethod::NatPmp,
+                    ports,
+                    lease_duration,
+                    external_ip: IpAddr::V4(*response.public_address()),
+                }
+            }
+        };
+        mapper.map_ports()?;
+        Ok(mapper)
+    }
+
+    /// IP address of the node on the internet, as given by the router
+    pub(crate) fn external_ip(&self) -> IpAddr {
+        self.external_ip
+    }
+
+    /// Create or renew the mappings of the ports
+    fn map_ports(&self) -> Result<(), ProtocolError> {
+        let lease_secs = self.lease_duration.as_secs().clamp(1, u32::MAX as u64) as u32;
+        for (port, udp) in self.ports.iter().copied() {
+            match &self.method {
+                PortMappingMethod::Upnp { gateway, local_ip } => gateway
+                    .add_port(
+                        upnp_protocol(udp),
+                        port,
+                        SocketAddr::new(*local_ip, port),
+                        lease_secs,
+                        MAPPING_DESCRIPTION,
+                    )
+                    .map_err(|err| {
+                        ProtocolError::GeneralProtocolError(format!(
+                            "failed to map port {} through UPnP: {}",
+                            port, err
+                        ))
+                    })?,
+                PortMappingMethod::NatPmp => {
+                    natpmp_request(|natpmp| {
+                        natpmp.send_port_mapping_request(
+                            natpmp_protocol(udp),
+                            port,
+                            port,
+                            lease_secs,
+                        )
+                    })?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove the mappings of the ports
+    fn unmap_ports(&self) {
+        for (port, udp) in self.ports.iter().copied() {
+            let result = match &self.method {
+                PortMappingMethod::Upnp { gateway, .. } => gateway
+                    .remove_port(upnp_protocol(udp), port)
+                    .map_err(|err| ProtocolError::GeneralProtocolError(err.to_string())),
+                // a mapping with a zero lifetime is removed
+                PortMappingMethod::NatPmp => natpmp_request(|natpmp| {
+                    natpmp.send_port_mapping_request(natpmp_protocol(udp), port, 0, 0)
+                })
+                .map(|_| ()),
+            };
+            if let Err(err) = result {
+                warn!("failed to remove the mapping of port {}: {}", port, err);
+            }
+        }
+    }
+}
+
+fn upnp_protocol(udp: bool) -> PortMappingProtocol {
+    if udp {
+        PortMappingProtocol::UDP
+    } else {
+        PortMappingProtocol::TCP
+    }
+}
+
+fn natpmp_protocol(udp: bool) -> Protocol {
+    if udp {
+        Protocol::UDP
+    } else {
+        Protocol::TCP
+    }
+}
+
+/// Send a request to the NAT-PMP gateway of the node and wait for its response
+fn natpmp_request(
+    send: impl FnOnce(&mut Natpmp) -> Result<(), natpmp::Error>,
+) -> Result<Response, ProtocolError> {
+    let natpmp_error = |err: natpmp::Error| {
+        ProtocolError::GeneralProtocolError(format!("NAT-PMP request failed: {:?}", err))
+    };
+    let mut natpmp = Natpmp::new().map_err(natpmp_error)?;
+    send(&mut natpmp).map_err(natpmp_error)?;
+    let start = Instant::now();
+    loop {
+        match natpmp.read_response_or_retry() {
+            Ok(response) => return Ok(response),
+            Err(natpmp::Error::NATPMP_TRYAGAIN) if start.elapsed() < GATEWAY_TIMEOUT => {
+                std::thread::sleep(Duration::from_millis(50));
+            }
+            Err(err) => return Err(natpmp_error(err)),
+        }
+    }
+}
+
+/// Stops the thread renewing the port mappings
+pub(crate) struct PortMappingStopper {
+    tx_stopper: Option<Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl PortMappingStopper {
+    /// Stop the thread and remove the port mappings
+    pub(crate) fn stop(&mut self) {
+        if let Some(tx) = self.tx_stopper.take() {
+            if tx.send(()).is_err() {
+                warn!("failed to send stop signal to the port mapping thread");
+            }
+        }
+        if let Some(handle) = self.handle.take() {
+            if handle.join().is_err() {
+                warn!("failed to join the port mapping thread");
+            }
+        }
+    }
+}
+
+/// Start the thread renewing the port mappings at half of their lease
+pub(crate) fn start_port_mapping_thread(mapper: PortMapper) -> PortMappingStopper {
+    let (tx_stopper, rx_stopper) = mpsc::channel();
+    let handle = std::thread::Builder::new()
+        .name(THREAD_NAME.to_string())
+        .spawn(move || {
+            let renewal_interval = mapper.lease_duration / 2;
+            // stop signal received, or stopper dropped
+            while rx_stopper.recv_timeout(renewal_interval) == Err(RecvTimeoutError::Timeout) {
+                if let Err(err) = mapper.map_ports() {
+                    warn!("failed to renew the port mappings: {}", err);
+                }
+            }
+            mapper.unmap_ports();
+            info!("port mappings removed");
+        })
+        .expect("failed to spawn the port mapping thread");
+    PortMappingStopper {
+        tx_stopper: Some(tx_stopper),
+        handle: Some(handle),
+    }
+}
+
+/// Tracks whether the node accepts connections from the other peers
+pub(crate) struct ReachabilityTracker {
+    start: MassaTime,
+    last_in_connection: Option<MassaTime>,
+    timeout: MassaTime,
+}
+
+impl ReachabilityTracker {
+    pub(crate) fn new(timeout: MassaTime, now: MassaTime) -> Self {
+        ReachabilityTracker {
+            start: now,
+            last_in_connection: None,
+            timeout,
+        }
+    }
+
+    /// Record the number of connections currently accepted from the other peers
+    pub(crate) fn on_in_connections(&mut self, in_connection_count: usize, now: MassaTime) {
+        if in_connection_count > 0 {
+            self.last_in_connection = Some(now);
+        }
+    }
+
+    pub(crate) fn get_reachability(&self, now: MassaTime) -> Reachability {
+        match self.last_in_connection {
+            Some(last) if now.saturating_sub(last) < self.timeout => Reachability::Reachable,
+            None if now.saturating_sub(self.start) < self.timeout => Reachability::Unknown,
+            _ => Reachability::Unreachable,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reachability_tracker() {
+        let at = MassaTime::from_millis;
+        let mut tracker = ReachabilityTracker::new(at(1000), at(0));
+        assert_eq!(tracker.get_reachability(at(500)), Reachability::Unknown);
+        tracker.on_in_connections(0, at(500));
+        assert_eq!(
+            tracker.get_reachability(at(1000)),
+            Reachability::Unreachable
+        );
+
+        tracker.on_in_connections(2, at(1500));
+        assert_eq!(tracker.get_reachability(at(2000)), Reachability::Reachable);
+        tracker.on_in_connections(0, at(2000));
+        assert_eq!(
+            tracker.get_reachability(at(2500)),
+            Reachability::Unreachable
+        );
+    }
+}
@@ -0,0 +1,166 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Outbound connections to the peers through a SOCKS5 proxy.
+//!
+//! Peernet opens the sockets of the outbound connections itself, so it cannot go through a proxy.
+//! Instead, each outbound connection is made to a relay: a listener on the loopback interface
+//! whose single connection is forwarded to the peer through the proxy. The relays keep track of
+//! the peer behind each of their addresses, so that the connections are known by the address of
+//! the peer rather than by the one of the relay.
+
+use std::collections::HashMap;
+use std::io;
+use std::net::{Ipv4Addr, Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use massa_protocol_exports::Socks5Proxy;
+use parking_lot::RwLock;
+use tracing::debug;
+
+// protocol-proxy-relay
+const RELAY_THREAD_NAME: &str = "p-proxy-relay";
+static_assertions::const_assert!(RELAY_THREAD_NAME.len() < 16);
+// protocol-proxy-upload
+const UPLOAD_THREAD_NAME: &str = "p-proxy-upload";
+static_assertions::const_assert!(UPLOAD_THREAD_NAME.len() < 16);
+
+/// Interval between two checks for the connection of peernet to a relay
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Relays of the outbound connections through the proxy
+#[derive(Clone)]
+pub(crate) struct ProxyRelays {
+    proxy: Socks5Proxy,
+    /// address of the peer behind the address of each open relay
+    targets: Arc<RwLock<HashMap<SocketAddr, SocketAddr>>>,
+}
+
+impl ProxyRelays {
+    pub(crate) fn new(proxy: Socks5Proxy) -> Self {
+        ProxyRelays {
+            proxy,
+            targets: Default::default(),
+        }
+    }
+
+    /// Connect to `target` through the proxy, and open a relay forwarding its first connection
+    /// to it. Returns the address of the relay.
+    ///
+    /// The relay is closed if nothing connects to it within `timeout`.
+    pub(crate) fn open(&self, target: SocketAddr, timeout: Duration) -> io::Result<SocketAddr> {
+        let remote = self.proxy.connect(target, Some(timeout))?;
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+        let relay_addr = listener.local_addr()?;
+        self.targets.write().insert(relay_addr, target);
+        let targets = self.targets.clone();
+        let spawned = std::thread::Builder::new()
+            .name(RELAY_THREAD_NAME.to_string())
+            .spawn(move || {
+                let relayed =
+                    accept_timeout(&listener, timeout).and_then(|local| forward(local, remote));
+                if let Err(err) = relayed {
+                    debug!("proxy relay to {} closed: {}", target, err);
+                }
+                targets.write().remove(&relay_addr);
+            });
+        if let Err(err) = spawned {
+            self.targets.write().remove(&relay_addr);
+            return Err(err);
+        }
+        Ok(relay_addr)
+    }
+
+    /// Address of the peer behind `addr` if it is the address of a relay, `addr` otherwise
+    pub(crate) fn peer_addr(&self, addr: &SocketAddr) -> SocketAddr {
+        self.targets.read().get(addr).copied().unwrap_or(*addr)
+    }
+}
+
+/// Wait at most `timeout` for a connection to `listener`
+fn accept_timeout(listener: &TcpListener, timeout: Duration) -> io::Result<TcpStream> {
+    listener.set_nonblocking(true)?;
+    let start = Instant::now();
+    loop {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                stream.set_nonblocking(false)?;
+                return Ok(stream);
+            }
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                if start.elapsed() >= timeout {
+                    return Err(io::Error::new(
+                        io::ErrorKind::TimedOut,
+                        "no connection to the relay",
+                    ));
+                }
+                std::thread::sleep(ACCEPT_POLL_INTERVAL);
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Forward the data between the two streams until one of them is closed
+fn forward(local: TcpStream, remote: TcpStream) -> io::Result<()> {
+    let mut local_read = local.try_clone()?;
+    let mut remote_write = remote.try_clone()?;
+    let upload = std::thread::Builder::new()
+        .name(UPLOAD_THREAD_NAME.to_string())
+        .spawn(move || {
+            let _ = io::copy(&mut local_read, &mut remote_write);
+            // also stops the download
+            let _ = remote_write.shutdown(Shutdown::Both);
+        })?;
+    let (mut remote_read, mut local_write) = (remote, local);
+    let downloaded = io::copy(&mut remote_read, &mut local_write);
+    // also stops the upload
+    let _ = local_write.shutdown(Shutdown::Both);
+    let _ = upload.join();
+    downloaded.map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+
+    #[test]
+    fn test_relay_through_proxy() {
+        // fake proxy accepting a single connection without authentication
+        let proxy_listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let proxy = Socks5Proxy {
+            address: proxy_listener.local_addr().unwrap(),
+            username: None,
+            password: None,
+        };
+        let target: SocketAddr = "1.2.3.4:31244".parse().unwrap();
+        let proxy_thread = std::thread::spawn(move || {
+            let (mut stream, _) = proxy_listener.accept().unwrap();
+            let mut request = [0u8; 3];
+            stream.read_exact(&mut request).unwrap();
+            stream.write_all(&[5, 0]).unwrap();
+            let mut request = [0u8; 10];
+            stream.read_exact(&mut request).unwrap();
+            assert_eq!(request, [5, 1, 0, 1, 1, 2, 3, 4, 0x7a, 0x0c]);
+            stream.write_all(&[5, 0, 0, 1, 0, 0, 0, 0, 0, 0]).unwrap();
+            // echo the data of the peer
+            let mut data = [0u8; 4];
+            stream.read_exact(&mut data).unwrap();
+            stream.write_all(&data).unwrap();
+        });
+
+        let relays = ProxyRelays::new(proxy);
+        let relay_addr = relays.open(target, Duration::from_secs(5)).unwrap();
+        assert_eq!(relays.peer_addr(&relay_addr), target);
+        let unknown_addr: SocketAddr = "5.6.7.8:31244".parse().unwrap();
+        assert_eq!(relays.peer_addr(&unknown_addr), unknown_addr);
+
+        let mut stream = TcpStream::connect(relay_addr).unwrap();
+        stream.write_all(b"ping").unwrap();
+        let mut data = [0u8; 4];
+        stream.read_exact(&mut data).unwrap();
+        assert_eq!(&data, b"ping");
+        proxy_thread.join().unwrap();
+    }
+}
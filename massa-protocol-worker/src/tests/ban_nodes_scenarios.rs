@@ -35,6 +35,14 @@ fn peer_db_boilerplate(mock_peer_db: &mut RwLockWriteGuard<MockPeerDBTrait>) {
     mock_peer_db
         .expect_get_rand_peers_to_send()
         .return_const(vec![]);
+    mock_peer_db.expect_is_ip_banned().return_const(false);
+    mock_peer_db
+        .expect_reload_ip_ban_list()
+        .returning(|_| Ok(false));
+    mock_peer_db.expect_get_peer_score().return_const(0.0);
+    mock_peer_db
+        .expect_apply_peer_score_event()
+        .return_const(0.0);
 }
 
 #[test]
@@ -8,10 +8,14 @@ use crate::messages::Message;
 use crate::wrap_network::MockActiveConnectionsTraitWrapper;
 
 use super::universe::{ProtocolForeignControllers, ProtocolTestUniverse};
+use massa_models::amount::Amount;
 use massa_models::block_header::SecuredHeader;
 use massa_models::config::CHAINID;
-use massa_models::operation::{OperationId, OperationPrefixId};
+use massa_models::operation::{
+    Operation, OperationId, OperationPrefixId, OperationSerializer, OperationType,
+};
 use massa_models::prehash::PreHashSet;
+use massa_models::secure_share::SecureShareContent;
 use massa_models::{block_id::BlockId, slot::Slot};
 use massa_protocol_exports::PeerId;
 use massa_protocol_exports::ProtocolConfig;
@@ -318,6 +322,126 @@ fn test_full_ask_block_workflow() {
     waitpoint.wait();
 }
 
+#[test]
+fn test_block_with_inactive_operation_is_invalid() {
+    let protocol_config = ProtocolConfig {
+        thread_count: 2,
+        ask_block_timeout: MassaTime::from_millis(100),
+        ..Default::default()
+    };
+
+    // the execution version of the MIP store of the universe does not activate the cancellation
+    // of the async messages
+    let block_creator = KeyPair::generate(0).unwrap();
+    let op_1 = Operation::new_verifiable(
+        Operation {
+            fee: Amount::default(),
+            op: OperationType::CancelAsyncMessage {
+                emission_slot: Slot::new(1, 0),
+                emission_index: 0,
+            },
+            expire_period: 5,
+        },
+        OperationSerializer::new(),
+        &block_creator,
+        *CHAINID,
+    )
+    .unwrap();
+    let op_thread = op_1
+        .content_creator_address
+        .get_thread(protocol_config.thread_count);
+    let block = ProtocolTestUniverse::create_block(
+        &block_creator,
+        Slot::new(1, op_thread),
+        vec![op_1.clone()],
+        vec![],
+        vec![],
+    );
+    let node_a_keypair = KeyPair::generate(0).unwrap();
+    let node_a_peer_id = PeerId::from_public_key(node_a_keypair.get_public_key());
+    let node_b_keypair = KeyPair::generate(0).unwrap();
+    let node_b_peer_id = PeerId::from_public_key(node_b_keypair.get_public_key());
+
+    let waitpoint = WaitPoint::new();
+    let mut foreign_controllers = ProtocolForeignControllers::new_with_mocks();
+    ProtocolTestUniverse::peer_db_boilerplate(&mut foreign_controllers.peer_db.write());
+    foreign_controllers
+        .consensus_controller
+        .expect_register_block_header()
+        .return_once(move |block_id, header| {
+            assert_eq!(block_id, block.id);
+            assert_eq!(header.id, block.content.header.id);
+        });
+    // the block is not registered, but marked as invalid once its operations are received
+    let waitpoint_trigger_handle = waitpoint.get_trigger_handle();
+    foreign_controllers
+        .consensus_controller
+        .expect_mark_invalid_block()
+        .times(1)
+        .return_once(move |block_id, _| {
+            assert_eq!(block_id, block.id);
+            waitpoint_trigger_handle.trigger();
+        });
+    block_retrieval_mock(
+        vec![
+            TestsStepMatch::AskData((
+                PeerIdMatchers::PeerId(node_a_peer_id),
+                block.id,
+                AskForBlockInfo::OperationIds,
+            )),
+            TestsStepMatch::AskData((
+                PeerIdMatchers::PeerId(node_b_peer_id),
+                block.id,
+                AskForBlockInfo::OperationIds,
+            )),
+            TestsStepMatch::AskData((
+                PeerIdMatchers::PeerId(node_b_peer_id),
+                block.id,
+                AskForBlockInfo::Operations(vec![op_1.id]),
+            )),
+        ],
+        &mut foreign_controllers,
+        waitpoint.get_trigger_handle(),
+    );
+
+    let universe = ProtocolTestUniverse::new(foreign_controllers, protocol_config);
+
+    universe.mock_message_receive(
+        &node_a_peer_id,
+        Message::Block(Box::new(BlockMessage::Header(block.content.header.clone()))),
+    );
+
+    universe
+        .module_controller
+        .send_wishlist_delta(
+            vec![(block.id, Some(block.content.header.clone()))]
+                .into_iter()
+                .collect(),
+            PreHashSet::<BlockId>::default(),
+        )
+        .unwrap();
+    waitpoint.wait();
+    waitpoint.wait();
+
+    universe.mock_message_receive(
+        &node_b_peer_id,
+        Message::Block(Box::new(BlockMessage::DataResponse {
+            block_id: block.id,
+            block_info: BlockInfoReply::OperationIds(vec![op_1.id]),
+        })),
+    );
+    waitpoint.wait();
+
+    universe.mock_message_receive(
+        &node_b_peer_id,
+        Message::Block(Box::new(BlockMessage::DataResponse {
+            block_id: block.id,
+            block_info: BlockInfoReply::Operations(vec![op_1]),
+        })),
+    );
+    waitpoint.wait();
+}
+
 #[test]
 fn test_empty_block() {
     let protocol_config = ProtocolConfig {
@@ -391,6 +515,176 @@ fn test_empty_block() {
     waitpoint.wait();
 }
 
+#[test]
+fn test_compact_block_relay_rebuilds_block() {
+    let protocol_config = ProtocolConfig {
+        thread_count: 2,
+        ask_block_timeout: MassaTime::from_millis(10000),
+        compact_block_relay: true,
+        ..Default::default()
+    };
+
+    let block_creator = KeyPair::generate(0).unwrap();
+    let block =
+        ProtocolTestUniverse::create_block(&block_creator, Slot::new(1, 1), vec![], vec![], vec![]);
+    let node_a_keypair = KeyPair::generate(0).unwrap();
+    let node_a_peer_id = PeerId::from_public_key(node_a_keypair.get_public_key());
+
+    let waitpoint = WaitPoint::new();
+
+    let mut foreign_controllers = ProtocolForeignControllers::new_with_mocks();
+    ProtocolTestUniverse::peer_db_boilerplate(&mut foreign_controllers.peer_db.write());
+    foreign_controllers
+        .consensus_controller
+        .expect_register_block_header()
+        .return_once(move |block_id, header| {
+            assert_eq!(block_id, block.id);
+            assert_eq!(header.id, block.content.header.id);
+        });
+    block_retrieval_mock(
+        vec![
+            TestsStepMatch::AskData((
+                PeerIdMatchers::PeerId(node_a_peer_id),
+                block.id,
+                AskForBlockInfo::OperationPrefixIds,
+            )),
+            TestsStepMatch::BlockManaged((block.id, false)),
+        ],
+        &mut foreign_controllers,
+        waitpoint.get_trigger_handle(),
+    );
+    let universe = ProtocolTestUniverse::new(foreign_controllers, protocol_config);
+
+    universe.mock_message_receive(
+        &node_a_peer_id,
+        Message::Block(Box::new(BlockMessage::Header(block.content.header.clone()))),
+    );
+
+    universe
+        .module_controller
+        .send_wishlist_delta(
+            vec![(block.id, Some(block.content.header.clone()))]
+                .into_iter()
+                .collect(),
+            PreHashSet::<BlockId>::default(),
+        )
+        .unwrap();
+    waitpoint.wait();
+
+    universe.mock_message_receive(
+        &node_a_peer_id,
+        Message::Block(Box::new(BlockMessage::DataResponse {
+            block_id: block.id,
+            block_info: BlockInfoReply::OperationPrefixIds(vec![]),
+        })),
+    );
+    waitpoint.wait();
+}
+
+#[test]
+fn test_compact_block_relay_falls_back_to_operation_ids() {
+    let protocol_config = ProtocolConfig {
+        thread_count: 2,
+        ask_block_timeout: MassaTime::from_millis(10000),
+        compact_block_relay: true,
+        ..Default::default()
+    };
+
+    let block_creator = KeyPair::generate(0).unwrap();
+    let op_1 = ProtocolTestUniverse::create_operation(&block_creator, 5, *CHAINID);
+    let op_thread = op_1
+        .content_creator_address
+        .get_thread(protocol_config.thread_count);
+    let block = ProtocolTestUniverse::create_block(
+        &block_creator,
+        Slot::new(1, op_thread),
+        vec![op_1.clone()],
+        vec![],
+        vec![],
+    );
+    let node_a_keypair = KeyPair::generate(0).unwrap();
+    let node_a_peer_id = PeerId::from_public_key(node_a_keypair.get_public_key());
+
+    let waitpoint = WaitPoint::new();
+    let mut foreign_controllers = ProtocolForeignControllers::new_with_mocks();
+    ProtocolTestUniverse::peer_db_boilerplate(&mut foreign_controllers.peer_db.write());
+    foreign_controllers
+        .consensus_controller
+        .expect_register_block_header()
+        .return_once(move |block_id, header| {
+            assert_eq!(block_id, block.id);
+            assert_eq!(header.id, block.content.header.id);
+        });
+    block_retrieval_mock(
+        vec![
+            TestsStepMatch::AskData((
+                PeerIdMatchers::PeerId(node_a_peer_id),
+                block.id,
+                AskForBlockInfo::OperationPrefixIds,
+            )),
+            // the operation is unknown, so the block cannot be rebuilt from the prefixes
+            TestsStepMatch::AskData((
+                PeerIdMatchers::PeerId(node_a_peer_id),
+                block.id,
+                AskForBlockInfo::OperationIds,
+            )),
+            TestsStepMatch::AskData((
+                PeerIdMatchers::PeerId(node_a_peer_id),
+                block.id,
+                AskForBlockInfo::Operations(vec![op_1.id]),
+            )),
+            TestsStepMatch::BlockManaged((block.id, true)),
+        ],
+        &mut foreign_controllers,
+        waitpoint.get_trigger_handle(),
+    );
+
+    let universe = ProtocolTestUniverse::new(foreign_controllers, protocol_config);
+
+    universe.mock_message_receive(
+        &node_a_peer_id,
+        Message::Block(Box::new(BlockMessage::Header(block.content.header.clone()))),
+    );
+
+    universe
+        .module_controller
+        .send_wishlist_delta(
+            vec![(block.id, Some(block.content.header.clone()))]
+                .into_iter()
+                .collect(),
+            PreHashSet::<BlockId>::default(),
+        )
+        .unwrap();
+    waitpoint.wait();
+
+    universe.mock_message_receive(
+        &node_a_peer_id,
+        Message::Block(Box::new(BlockMessage::DataResponse {
+            block_id: block.id,
+            block_info: BlockInfoReply::OperationPrefixIds(vec![op_1.id.prefix()]),
+        })),
+    );
+    waitpoint.wait();
+
+    universe.mock_message_receive(
+        &node_a_peer_id,
+        Message::Block(Box::new(BlockMessage::DataResponse {
+            block_id: block.id,
+            block_info: BlockInfoReply::OperationIds(vec![op_1.id]),
+        })),
+    );
+    waitpoint.wait();
+
+    universe.mock_message_receive(
+        &node_a_peer_id,
+        Message::Block(Box::new(BlockMessage::DataResponse {
+            block_id: block.id,
+            block_info: BlockInfoReply::Operations(vec![op_1]),
+        })),
+    );
+    waitpoint.wait();
+}
+
 #[test]
 fn test_dont_want_it_anymore() {
     let protocol_config = ProtocolConfig {
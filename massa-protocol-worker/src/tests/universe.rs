@@ -20,6 +20,7 @@ use std::{
 };
 
 use crate::{
+    bandwidth::BandwidthLimiter,
     connectivity::start_connectivity_thread,
     create_protocol_controller,
     handlers::{
@@ -129,6 +130,14 @@ impl ProtocolTestUniverse {
         mock_peer_db
             .expect_get_rand_peers_to_send()
             .return_const(vec![]);
+        mock_peer_db.expect_is_ip_banned().return_const(false);
+        mock_peer_db
+            .expect_reload_ip_ban_list()
+            .returning(|_| Ok(false));
+        mock_peer_db.expect_get_peer_score().return_const(0.0);
+        mock_peer_db
+            .expect_apply_peer_score_event()
+            .return_const(0.0);
     }
 
     pub fn active_connections_boilerplate(
@@ -215,6 +224,14 @@ pub fn start_protocol_controller_with_mock_network(
         Some(config.max_size_channel_network_to_peer_handler),
     );
 
+    let massa_metrics = MassaMetrics::new(
+        false,
+        "0.0.0.0:9898".parse().unwrap(),
+        32,
+        std::time::Duration::from_secs(5),
+    )
+    .0;
+
     // Register channels for handlers
     let message_handlers: MessagesHandler = MessagesHandler {
         sender_blocks: sender_blocks.clone(),
@@ -222,6 +239,8 @@ pub fn start_protocol_controller_with_mock_network(
         sender_operations: sender_operations.clone(),
         sender_peers: sender_peers.clone(),
         id_deserializer: U64VarIntDeserializer::new(Included(0), Included(u64::MAX)),
+        bandwidth_limiter: Arc::new(BandwidthLimiter::new(config.bandwidth_limits)),
+        massa_metrics: massa_metrics.clone(),
     };
 
     let (controller, channels) = create_protocol_controller(config.clone());
@@ -244,6 +263,7 @@ pub fn start_protocol_controller_with_mock_network(
         (sender_peers, receiver_peers),
         HashMap::default(),
         peer_db,
+        None,
         storage,
         channels,
         message_handlers.clone(),
@@ -256,16 +276,10 @@ pub fn start_protocol_controller_with_mock_network(
         },
         config,
         mip_store,
-        MassaMetrics::new(
-            false,
-            "0.0.0.0:9898".parse().unwrap(),
-            32,
-            std::time::Duration::from_secs(5),
-        )
-        .0,
+        massa_metrics,
     )?;
 
-    let manager = ProtocolManagerImpl::new(connectivity_thread_handle);
+    let manager = ProtocolManagerImpl::new(connectivity_thread_handle, None);
 
     Ok((message_handlers, controller, Box::new(manager)))
 }
@@ -5,8 +5,8 @@ use massa_models::node::NodeId;
 use massa_pool_exports::PoolController;
 use massa_pos_exports::SelectorController;
 use massa_protocol_exports::{
-    BootstrapPeers, PeerData, PeerId, ProtocolConfig, ProtocolController, ProtocolError,
-    ProtocolManager,
+    BootstrapPeers, IpBanList, PeerData, PeerId, ProtocolConfig, ProtocolController, ProtocolError,
+    ProtocolManager, TransportType,
 };
 use massa_serialization::U64VarIntDeserializer;
 use massa_signature::KeyPair;
@@ -22,10 +22,13 @@ use peernet::{
     config::{PeerNetCategoryInfo, PeerNetConfiguration},
     network_manager::PeerNetManager,
 };
-use std::{collections::HashMap, fs::read_to_string, ops::Bound::Included, sync::Arc};
-use tracing::{debug, log::warn};
+use std::{
+    collections::HashMap, fs::read_to_string, net::SocketAddr, ops::Bound::Included, sync::Arc,
+};
+use tracing::{debug, info, log::warn};
 
 use crate::{
+    bandwidth::BandwidthLimiter,
     connectivity::{start_connectivity_thread, ConnectivityCommand},
     context::Context,
     controller::ProtocolControllerImpl,
@@ -50,6 +53,8 @@ use crate::{
     ip::to_canonical,
     manager::ProtocolManagerImpl,
     messages::MessagesHandler,
+    nat::{start_port_mapping_thread, PortMapper},
+    proxy::ProxyRelays,
     wrap_network::NetworkControllerImpl,
 };
 
@@ -177,7 +182,7 @@ pub fn create_protocol_controller(
 /// * `storage`: Shared storage to fetch data that are fetch across all modules
 #[allow(clippy::too_many_arguments)]
 pub fn start_protocol_controller(
-    config: ProtocolConfig,
+    mut config: ProtocolConfig,
     selector_controller: Box<dyn SelectorController>,
     consensus_controller: Box<dyn ConsensusController>,
     bootstrap_peers: Option<BootstrapPeers>,
@@ -188,7 +193,13 @@ pub fn start_protocol_controller(
     massa_metrics: MassaMetrics,
 ) -> Result<(Box<dyn ProtocolManager>, KeyPair, NodeId), ProtocolError> {
     debug!("starting protocol controller");
-    let peer_db = Arc::new(RwLock::new(PeerDB::default()));
+    let peer_db = Arc::new(RwLock::new(PeerDB {
+        ip_ban_list: IpBanList::load(
+            &config.ip_ban_list_file,
+            config.asn_database_file.as_deref(),
+        )?,
+        ..Default::default()
+    }));
 
     let (sender_operations, receiver_operations) = MassaChannel::new(
         "sender_operations".to_string(),
@@ -207,6 +218,8 @@ pub fn start_protocol_controller(
         Some(config.max_size_channel_network_to_peer_handler),
     );
 
+    let bandwidth_limiter = Arc::new(BandwidthLimiter::new(config.bandwidth_limits));
+
     // Register channels for handlers
     let message_handlers: MessagesHandler = MessagesHandler {
         sender_blocks: sender_blocks.clone(),
@@ -214,6 +227,8 @@ pub fn start_protocol_controller(
         sender_operations: sender_operations.clone(),
         sender_peers: sender_peers.clone(),
         id_deserializer: U64VarIntDeserializer::new(Included(0), Included(u64::MAX)),
+        bandwidth_limiter: bandwidth_limiter.clone(),
+        massa_metrics: massa_metrics.clone(),
     };
 
     // try to read node keypair from file, otherwise generate it & write to file. Then derive nodeId
@@ -237,8 +252,44 @@ pub fn start_protocol_controller(
         keypair
     };
 
+    // map the ports of the listeners on the router, and announce the external IP it gives us
+    let mut mapped_address = None;
+    // behind a proxy, mapping the ports would announce the external IP of the node
+    if config.port_mapping && config.proxy.is_some() {
+        warn!("port mapping is disabled when the connections go through a proxy");
+    }
+    let port_mapping = if config.port_mapping && config.proxy.is_none() {
+        let lease_duration = config.port_mapping_lease_duration.to_duration();
+        match PortMapper::new(&config.listeners, lease_duration) {
+            Ok(mapper) => {
+                let external_ip = mapper.external_ip();
+                info!(
+                    "listener ports mapped on the router, external IP: {}",
+                    external_ip
+                );
+                if config.routable_ip.is_none() {
+                    config.routable_ip = Some(external_ip);
+                }
+                mapped_address = config
+                    .listeners
+                    .iter()
+                    .find(|(_, transport)| **transport == TransportType::Tcp)
+                    .map(|(addr, _)| SocketAddr::new(external_ip, addr.port()));
+                Some(start_port_mapping_thread(mapper))
+            }
+            Err(err) => {
+                warn!("could not map the listener ports on the router: {}", err);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    let proxy_relays = config.proxy.clone().map(ProxyRelays::new);
+
     let mut peernet_config = PeerNetConfiguration::default(
-        MassaHandshake::new(peer_db.clone(), config.clone()),
+        MassaHandshake::new(peer_db.clone(), config.clone(), proxy_relays.clone()),
         message_handlers.clone(),
         Context {
             our_keypair: keypair.clone(),
@@ -314,9 +365,11 @@ pub fn start_protocol_controller(
     };
     peernet_config.max_in_connections = config.max_in_connections;
 
-    let network_controller = Box::new(NetworkControllerImpl::new(PeerNetManager::new(
-        peernet_config,
-    )));
+    let network_controller = Box::new(NetworkControllerImpl::new(
+        PeerNetManager::new(peernet_config),
+        bandwidth_limiter,
+        proxy_relays,
+    ));
 
     let connectivity_thread_handle = start_connectivity_thread(
         PeerId::from_public_key(keypair.get_public_key()),
@@ -330,6 +383,7 @@ pub fn start_protocol_controller(
         (sender_peers, receiver_peers),
         initial_peers,
         peer_db,
+        mapped_address,
         storage,
         protocol_channels,
         message_handlers,
@@ -369,7 +423,7 @@ pub fn start_protocol_controller(
         massa_metrics,
     )?;
 
-    let manager = ProtocolManagerImpl::new(connectivity_thread_handle);
+    let manager = ProtocolManagerImpl::new(connectivity_thread_handle, port_mapping);
 
     Ok((
         Box::new(manager),
@@ -1,6 +1,8 @@
 use std::{
     collections::{HashMap, HashSet},
     net::SocketAddr,
+    sync::Arc,
+    time::Instant,
 };
 
 use massa_protocol_exports::{PeerId, ProtocolError};
@@ -11,13 +13,15 @@ use peernet::{
 };
 
 use crate::{
+    bandwidth::BandwidthLimiter,
     context::Context,
     handlers::peer_handler::MassaHandshake,
     messages::{Message, MessagesHandler, MessagesSerializer},
+    proxy::ProxyRelays,
 };
 
 #[cfg(test)]
-use std::sync::{Arc, RwLock};
+use std::sync::RwLock;
 
 #[cfg_attr(test, mockall_wrap::wrap, mockall::automock)]
 pub trait ActiveConnectionsTrait: Send + Sync {
@@ -46,7 +50,25 @@ impl Clone for Box<dyn ActiveConnectionsTrait> {
     }
 }
 
-impl ActiveConnectionsTrait for SharedActiveConnections<PeerId> {
+/// Active connections of peernet, with the messages sent to the peers limited in bandwidth
+#[derive(Clone)]
+pub struct ThrottledActiveConnections {
+    connections: SharedActiveConnections<PeerId>,
+    bandwidth_limiter: Arc<BandwidthLimiter>,
+    proxy_relays: Option<ProxyRelays>,
+}
+
+impl ThrottledActiveConnections {
+    /// Address of the peer, rather than the one of the relay for the connections through the proxy
+    fn peer_addr(&self, addr: &SocketAddr) -> SocketAddr {
+        match &self.proxy_relays {
+            Some(proxy_relays) => proxy_relays.peer_addr(addr),
+            None => *addr,
+        }
+    }
+}
+
+impl ActiveConnectionsTrait for ThrottledActiveConnections {
     fn send_to_peer(
         &self,
         peer_id: &PeerId,
@@ -54,7 +76,18 @@ impl ActiveConnectionsTrait for SharedActiveConnections<PeerId> {
         message: Message,
         high_priority: bool,
     ) -> Result<(), ProtocolError> {
-        if let Some(connection) = self.read().connections.get(peer_id) {
+        if let Some(connection) = self.connections.read().connections.get(peer_id) {
+            let (bytes_sent, _) = connection.endpoint.get_bandwidth();
+            let now = Instant::now();
+            if !self
+                .bandwidth_limiter
+                .allow_upload(peer_id, bytes_sent, high_priority, now)
+            {
+                return Err(ProtocolError::SendError(format!(
+                    "upload bandwidth limit reached for peer {}",
+                    peer_id
+                )));
+            }
             connection
                 .send_channels
                 .try_send(message_serializer, message, high_priority)
@@ -69,20 +102,26 @@ impl ActiveConnectionsTrait for SharedActiveConnections<PeerId> {
     }
 
     fn get_peer_ids_connected(&self) -> HashSet<PeerId> {
-        self.read().connections.keys().cloned().collect()
+        self.connections
+            .read()
+            .connections
+            .keys()
+            .cloned()
+            .collect()
     }
 
     fn get_peers_connected(
         &self,
     ) -> HashMap<PeerId, (SocketAddr, PeerConnectionType, Option<String>)> {
-        self.read()
+        self.connections
+            .read()
             .connections
             .iter()
             .map(|(peer_id, connection)| {
                 (
                     *peer_id,
                     (
-                        *connection.endpoint.get_target_addr(),
+                        self.peer_addr(connection.endpoint.get_target_addr()),
                         connection.connection_type,
                         connection.category_name.clone(),
                     ),
@@ -92,29 +131,34 @@ impl ActiveConnectionsTrait for SharedActiveConnections<PeerId> {
     }
 
     fn get_nb_out_connections(&self) -> usize {
-        self.read().nb_out_connections
+        self.connections.read().nb_out_connections
     }
 
     fn get_nb_in_connections(&self) -> usize {
-        self.read().nb_in_connections
+        self.connections.read().nb_in_connections
     }
 
     fn shutdown_connection(&mut self, peer_id: &PeerId) {
-        if let Some(connection) = self.write().connections.get_mut(peer_id) {
+        if let Some(connection) = self.connections.write().connections.get_mut(peer_id) {
             connection.shutdown();
         }
     }
 
     fn get_peers_connections_bandwidth(&self) -> HashMap<String, (u64, u64)> {
         let mut map = HashMap::new();
-        for (peerid, conn) in self.read().connections.iter() {
+        for (peerid, conn) in self.connections.read().connections.iter() {
             map.insert(peerid.to_string(), conn.endpoint.get_bandwidth());
         }
         map
     }
 
     fn get_peer_ids_out_connection_queue(&self) -> HashSet<SocketAddr> {
-        self.read().out_connection_queue.clone()
+        self.connections
+            .read()
+            .out_connection_queue
+            .iter()
+            .map(|addr| self.peer_addr(addr))
+            .collect()
     }
 }
 
@@ -133,6 +177,7 @@ pub trait NetworkController: Send + Sync {
     ) -> Result<(), ProtocolError>;
     fn try_connect(
         &mut self,
+        transport_type: TransportType,
         addr: SocketAddr,
         timeout: std::time::Duration,
     ) -> Result<(), ProtocolError>;
@@ -142,19 +187,32 @@ pub trait NetworkController: Send + Sync {
 
 pub struct NetworkControllerImpl {
     peernet_manager: PeerNetManager<PeerId, Context, MassaHandshake, MessagesHandler>,
+    bandwidth_limiter: Arc<BandwidthLimiter>,
+    /// relays of the outbound connections when they go through a proxy
+    proxy_relays: Option<ProxyRelays>,
 }
 
 impl NetworkControllerImpl {
-    pub fn new(
+    pub(crate) fn new(
         peernet_manager: PeerNetManager<PeerId, Context, MassaHandshake, MessagesHandler>,
+        bandwidth_limiter: Arc<BandwidthLimiter>,
+        proxy_relays: Option<ProxyRelays>,
     ) -> Self {
-        Self { peernet_manager }
+        Self {
+            peernet_manager,
+            bandwidth_limiter,
+            proxy_relays,
+        }
     }
 }
 
 impl NetworkController for NetworkControllerImpl {
     fn get_active_connections(&self) -> Box<dyn ActiveConnectionsTrait> {
-        Box::new(self.peernet_manager.active_connections.clone())
+        Box::new(ThrottledActiveConnections {
+            connections: self.peernet_manager.active_connections.clone(),
+            bandwidth_limiter: self.bandwidth_limiter.clone(),
+            proxy_relays: self.proxy_relays.clone(),
+        })
     }
 
     fn start_listener(
@@ -179,12 +237,28 @@ impl NetworkController for NetworkControllerImpl {
 
     fn try_connect(
         &mut self,
+        transport_type: TransportType,
         addr: SocketAddr,
         timeout: std::time::Duration,
     ) -> Result<(), ProtocolError> {
-        //TODO: Change when we support multiple transports
+        // through the proxy, peernet connects to a relay forwarding the connection to the peer
+        let addr = match &self.proxy_relays {
+            Some(_) if transport_type != TransportType::Tcp => {
+                return Err(ProtocolError::GeneralProtocolError(format!(
+                    "cannot connect to {} with {:?} through the proxy",
+                    addr, transport_type
+                )));
+            }
+            Some(proxy_relays) => proxy_relays.open(addr, timeout).map_err(|err| {
+                ProtocolError::GeneralProtocolError(format!(
+                    "failed to connect to {} through the proxy: {}",
+                    addr, err
+                ))
+            })?,
+            None => addr,
+        };
         self.peernet_manager
-            .try_connect(TransportType::Tcp, addr, timeout)
+            .try_connect(transport_type, addr, timeout)
             .map_err(|err| ProtocolError::GeneralProtocolError(err.to_string()))?;
         Ok(())
     }
@@ -1,16 +1,34 @@
 use crate::handlers::peer_handler::models::{ConnectionMetadata, PeerInfo};
 use std::{
     collections::{HashMap, HashSet},
-    net::SocketAddr,
+    net::{IpAddr, SocketAddr},
+    path::Path,
     time::Duration,
 };
 
-use massa_protocol_exports::{PeerId, TransportType};
+use massa_protocol_exports::{
+    IpBanList, IpCidr, PeerId, PeerScore, PeerScoreEvent, ProtocolError, TransportType,
+};
 
 #[cfg_attr(test, mockall::automock)]
 pub trait PeerDBTrait: Send + Sync {
     fn ban_peer(&mut self, peer_id: &PeerId);
     fn unban_peer(&mut self, peer_id: &PeerId);
+    fn ban_ips(&mut self, ranges: &[IpCidr], asns: &[u32]);
+    fn unban_ips(&mut self, ranges: &[IpCidr], asns: &[u32]);
+    fn allow_ips(&mut self, ranges: &[IpCidr]);
+    fn disallow_ips(&mut self, ranges: &[IpCidr]);
+    fn reload_ip_ban_list(&mut self, ban_list_file: &Path) -> Result<bool, ProtocolError>;
+    fn is_ip_banned(&self, ip: &IpAddr) -> bool;
+    fn get_ip_ban_list(&self) -> &IpBanList;
+    fn apply_peer_score_event(
+        &mut self,
+        peer_id: &PeerId,
+        event: PeerScoreEvent,
+        half_life: massa_time::MassaTime,
+    ) -> f64;
+    fn get_peer_score(&self, peer_id: &PeerId, half_life: massa_time::MassaTime) -> f64;
+    fn get_peer_scores(&self) -> &HashMap<PeerId, PeerScore>;
     fn clone_box(&self) -> Box<dyn PeerDBTrait>;
     fn get_oldest_peer(
         &self,
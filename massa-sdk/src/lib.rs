@@ -18,39 +18,45 @@ use massa_api_exports::page::PagedVecV2;
 use massa_api_exports::ApiRequest;
 use massa_api_exports::{
     address::AddressInfo,
-    block::{BlockInfo, BlockSummary},
+    block::{BlockGraphExportRequest, BlockInfo, BlockSummary, FinalityProof},
     datastore::{DatastoreEntryInput, DatastoreEntryOutput},
     endorsement::EndorsementInfo,
     execution::{ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall, Transfer},
-    node::NodeStatus,
-    operation::{OperationInfo, OperationInput},
+    node::{BandwidthLimits, NodeStatus, PeerInfo},
+    node_config::NodeConfigReload,
+    operation::{OperationInfo, OperationInput, PoolOperations},
+    page::PageRequest,
+    storage::StorageInfo,
     TimeInterval,
 };
 use massa_models::secure_share::SecureShare;
 use massa_models::slot::Slot;
 use massa_models::{
     address::Address,
-    block::FilledBlock,
+    block::{DiscardedBlock, FilledBlock},
     block_header::BlockHeader,
     block_id::BlockId,
-    clique::Clique,
+    clique::{Clique, CliqueDetails},
     composite::PubkeySig,
     endorsement::EndorsementId,
-    execution::EventFilter,
+    execution::{AsyncMessageFeeSuggestion, AsyncMessageFilter, EventFilter, PendingAsyncMessage},
     node::NodeId,
-    operation::{Operation, OperationId},
+    operation::{Operation, OperationId, PoolOperationSort},
     output_event::SCOutputEvent,
     prehash::{PreHashMap, PreHashSet},
+    stats::{CycleStaleBlockStats, PoolStats},
     version::Version,
 };
 use massa_proto_rs::massa::api::v1::private_service_client::PrivateServiceClient;
 use massa_proto_rs::massa::api::v1::public_service_client::PublicServiceClient;
+use massa_time::MassaTime;
 use std::net::{IpAddr, SocketAddr};
 use std::str::FromStr;
 use thiserror::Error;
 
 pub mod cert_manager;
 mod config;
+pub mod multisig;
 pub use config::ClientConfig;
 pub use config::HttpConfig;
 pub use config::WsConfig;
@@ -155,6 +161,15 @@ impl RpcClient {
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
+    /// Gracefully stop the node, giving the in-flight API requests `grace_period` to finish
+    /// instead of the configured one.
+    pub async fn shutdown(&self, grace_period: Option<MassaTime>) -> RpcResult<()> {
+        self.http_client
+            .request("shutdown", rpc_params![grace_period])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
     /// Sign message with node's key.
     /// Returns the public key that signed the message and the signature.
     pub async fn node_sign_message(&self, message: Vec<u8>) -> RpcResult<PubkeySig> {
@@ -226,26 +241,85 @@ impl RpcClient {
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// Returns node peers whitelist IP address(es).
-    pub async fn node_peers_whitelist(&self) -> RpcResult<Vec<IpAddr>> {
+    /// Returns the effective node configuration
+    pub async fn get_node_config(&self) -> RpcResult<serde_json::Value> {
+        self.http_client
+            .request("get_node_config", rpc_params![])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Reloads the node configuration, applying the settings that can be changed without a restart
+    pub async fn reload_node_config(&self) -> RpcResult<NodeConfigReload> {
+        self.http_client
+            .request("reload_node_config", rpc_params![])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Returns the disk usage of the node subsystems, as last measured
+    pub async fn get_storage_info(&self) -> RpcResult<StorageInfo> {
+        self.http_client
+            .request("get_storage_info", rpc_params![])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Returns the peers known by the node, with their reputation score
+    pub async fn get_peers(&self) -> RpcResult<Vec<PeerInfo>> {
+        self.http_client
+            .request("get_peers", rpc_params![])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Returns the bandwidth limits of the node
+    pub async fn get_bandwidth_limits(&self) -> RpcResult<BandwidthLimits> {
+        self.http_client
+            .request("get_bandwidth_limits", rpc_params![])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Changes the bandwidth limits of the node
+    pub async fn set_bandwidth_limits(&self, limits: BandwidthLimits) -> RpcResult<()> {
+        self.http_client
+            .request("set_bandwidth_limits", rpc_params![limits])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Renders the block graph over a slot range in DOT or GraphML
+    pub async fn node_export_block_graph(
+        &self,
+        request: BlockGraphExportRequest,
+    ) -> RpcResult<String> {
+        self.http_client
+            .request("node_export_block_graph", rpc_params![request])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Returns node peers whitelist IP ranges, in CIDR notation.
+    pub async fn node_peers_whitelist(&self) -> RpcResult<Vec<String>> {
         self.http_client
             .request("node_peers_whitelist", rpc_params![])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// Add IP address(es) to node peers whitelist.
-    pub async fn node_add_to_peers_whitelist(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
+    /// Add IP address(es) or ranges (CIDR notation) to node peers whitelist.
+    pub async fn node_add_to_peers_whitelist(&self, ranges: Vec<String>) -> RpcResult<()> {
         self.http_client
-            .request("node_add_to_peers_whitelist", rpc_params![ips])
+            .request("node_add_to_peers_whitelist", rpc_params![ranges])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// Remove IP address(es) to node peers whitelist.
-    pub async fn node_remove_from_peers_whitelist(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
+    /// Remove IP address(es) or ranges (CIDR notation) from node peers whitelist.
+    pub async fn node_remove_from_peers_whitelist(&self, ranges: Vec<String>) -> RpcResult<()> {
         self.http_client
-            .request("node_remove_from_peers_whitelist", rpc_params![ips])
+            .request("node_remove_from_peers_whitelist", rpc_params![ranges])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
@@ -336,6 +410,38 @@ impl RpcClient {
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
+    /// Get the details of the cliques and why each of them is the blockclique or not
+    pub async fn get_cliques_details(&self) -> RpcResult<Vec<CliqueDetails>> {
+        self.http_client
+            .request("get_cliques_details", rpc_params![])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Get the stale blocks of the stats history with their creators and slots, grouped by cycle
+    pub async fn get_stale_block_stats(&self) -> RpcResult<Vec<CycleStaleBlockStats>> {
+        self.http_client
+            .request("get_stale_block_stats", rpc_params![])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Get the discarded blocks retained by the node, with the reason of their discard
+    pub async fn get_discarded_blocks(&self) -> RpcResult<Vec<DiscardedBlock>> {
+        self.http_client
+            .request("get_discarded_blocks", rpc_params![])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Get the data proving to an external verifier that a block is final
+    pub async fn get_finality_proof(&self, block_id: BlockId) -> RpcResult<FinalityProof> {
+        self.http_client
+            .request("get_finality_proof", rpc_params![block_id])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
     // Debug (specific information)
 
     /// Returns the active stakers and their roll counts for the current cycle.
@@ -357,6 +463,27 @@ impl RpcClient {
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
+    /// Returns a page of summaries of the operations of the pool, with the occupancy of the pool
+    pub async fn get_pool_operations(
+        &self,
+        sort: PoolOperationSort,
+        page_request: Option<PageRequest>,
+    ) -> RpcResult<PoolOperations> {
+        self.http_client
+            .request("get_pool_operations", rpc_params![sort, page_request])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Returns the number of items of the pools, the age of their oldest endorsement
+    /// and denunciation, and the endorsement indexes collected at each slot
+    pub async fn get_pool_stats(&self) -> RpcResult<PoolStats> {
+        self.http_client
+            .request("get_pool_stats", rpc_params![])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
     /// Returns endorsement(s) information associated to a given list of endorsement(s) ID(s)
     pub async fn get_endorsements(
         &self,
@@ -407,6 +534,29 @@ impl RpcClient {
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
+    /// Get the asynchronous messages of the pool with various filters
+    pub async fn get_async_messages(
+        &self,
+        filter: AsyncMessageFilter,
+    ) -> RpcResult<Vec<PendingAsyncMessage>> {
+        self.http_client
+            .request("get_async_messages", rpc_params![filter])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Get the eviction threshold of the asynchronous pool,
+    /// and the minimum fee for a message using `max_gas` to be retained in it
+    pub async fn get_async_message_fee_suggestion(
+        &self,
+        max_gas: u64,
+    ) -> RpcResult<AsyncMessageFeeSuggestion> {
+        self.http_client
+            .request("get_async_message_fee_suggestion", rpc_params![max_gas])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
     /// Get datastore entries
     pub async fn get_datastore_entries(
         &self,
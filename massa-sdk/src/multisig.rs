@@ -0,0 +1,177 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Proposals of multisig vaults: smart contracts making a call once enough of their owners
+//! approved it.
+//!
+//! A proposal describes a call to be made by the vault. Each owner signs the message of the
+//! proposal with the key of their address, off-chain, and the signatures are collected in the
+//! proposal, which the owners share as a JSON file. Once enough owners signed it, the proposal is
+//! submitted by calling the [`EXECUTE_FUNCTION`] of the vault with the parameter built by
+//! [`MultisigProposal::execute_parameter`]. The vault rebuilds the message of the proposal, checks
+//! the signatures against its owners and threshold, and makes the call.
+//!
+//! The message is text so that the vault can check the signatures with the signature
+//! verification of the smart contract runtime, which takes strings.
+
+use std::fmt::Write as _;
+
+use massa_hash::Hash;
+use massa_models::{address::Address, amount::Amount, composite::PubkeySig};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Function of the vault executing a proposal
+pub const EXECUTE_FUNCTION: &str = "execute";
+
+/// Error on a multisig proposal
+#[derive(Error, Debug)]
+pub enum MultisigError {
+    /// Invalid signature
+    #[error("invalid signature of {0} for this proposal")]
+    InvalidSignature(Address),
+}
+
+/// Call to be made by a multisig vault, with the signatures of its owners collected so far
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MultisigProposal {
+    /// chain on which the proposal is valid
+    pub chain_id: u64,
+    /// address of the vault
+    pub vault: Address,
+    /// nonce of the proposal, accepted only once by the vault
+    pub nonce: u64,
+    /// address called by the vault
+    pub target_address: Address,
+    /// function called by the vault
+    pub target_function: String,
+    /// parameter of the call
+    pub parameter: Vec<u8>,
+    /// coins sent with the call
+    pub coins: Amount,
+    /// signatures of the owners
+    pub signatures: Vec<PubkeySig>,
+}
+
+impl MultisigProposal {
+    /// Message signed by the owners
+    pub fn message(&self) -> String {
+        let mut parameter = String::with_capacity(2 * self.parameter.len());
+        for byte in &self.parameter {
+            let _ = write!(parameter, "{:02x}", byte);
+        }
+        format!(
+            "massa-multisig:{}:{}:{}:{}:{}:{}:{}",
+            self.chain_id,
+            self.vault,
+            self.nonce,
+            self.target_address,
+            self.target_function,
+            parameter,
+            self.coins.to_raw()
+        )
+    }
+
+    /// Add the signature of an owner, replacing their previous one
+    pub fn add_signature(&mut self, signature: PubkeySig) -> Result<(), MultisigError> {
+        let hash = Hash::compute_from(self.message().as_bytes());
+        if signature
+            .public_key
+            .verify_signature(&hash, &signature.signature)
+            .is_err()
+        {
+            return Err(MultisigError::InvalidSignature(Address::from_public_key(
+                &signature.public_key,
+            )));
+        }
+        self.signatures
+            .retain(|existing| existing.public_key != signature.public_key);
+        self.signatures.push(signature);
+        Ok(())
+    }
+
+    /// Addresses of the owners who signed the proposal
+    pub fn signers(&self) -> Vec<Address> {
+        self.signatures
+            .iter()
+            .map(|signature| Address::from_public_key(&signature.public_key))
+            .collect()
+    }
+
+    /// Parameter of the call to the [`EXECUTE_FUNCTION`] of the vault, serialized as the
+    /// arguments of the smart contracts: the nonce, the target address and function, the
+    /// parameter, the coins, and the public key and signature of each owner who signed
+    pub fn execute_parameter(&self) -> Vec<u8> {
+        let mut args = Vec::new();
+        args.extend(self.nonce.to_le_bytes());
+        push_arg(&mut args, self.target_address.to_string().as_bytes());
+        push_arg(&mut args, self.target_function.as_bytes());
+        push_arg(&mut args, &self.parameter);
+        args.extend(self.coins.to_raw().to_le_bytes());
+        args.extend((self.signatures.len() as u32).to_le_bytes());
+        for signature in &self.signatures {
+            push_arg(&mut args, signature.public_key.to_string().as_bytes());
+            push_arg(&mut args, signature.signature.to_string().as_bytes());
+        }
+        args
+    }
+}
+
+/// Append a variable-length argument, prefixed with its length
+fn push_arg(args: &mut Vec<u8>, arg: &[u8]) {
+    args.extend((arg.len() as u32).to_le_bytes());
+    args.extend(arg);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_signature::KeyPair;
+
+    #[test]
+    fn test_multisig_proposal_signatures() {
+        let owner = KeyPair::generate(0).unwrap();
+        let other = KeyPair::generate(0).unwrap();
+        let mut proposal = MultisigProposal {
+            chain_id: 77,
+            vault: Address::from_public_key(&other.get_public_key()),
+            nonce: 1,
+            target_address: Address::from_public_key(&owner.get_public_key()),
+            target_function: "transfer".to_string(),
+            parameter: vec![0, 255],
+            coins: Amount::from_raw(5),
+            signatures: Vec::new(),
+        };
+        assert!(proposal.message().ends_with(":transfer:00ff:5"));
+
+        let hash = Hash::compute_from(proposal.message().as_bytes());
+        let signature = PubkeySig {
+            public_key: owner.get_public_key(),
+            signature: owner.sign(&hash).unwrap(),
+        };
+        proposal.add_signature(signature.clone()).unwrap();
+        // signing again replaces the previous signature
+        proposal.add_signature(signature).unwrap();
+        assert_eq!(proposal.signers(), vec![proposal.target_address]);
+
+        // a signature of another proposal is refused
+        let wrong_signature = PubkeySig {
+            public_key: other.get_public_key(),
+            signature: other.sign(&Hash::compute_from(b"other")).unwrap(),
+        };
+        assert!(proposal.add_signature(wrong_signature).is_err());
+
+        let parameter = proposal.execute_parameter();
+        assert_eq!(&parameter[..8], &1u64.to_le_bytes());
+        let target_len = proposal.target_address.to_string().len();
+        assert_eq!(&parameter[8..12], &(target_len as u32).to_le_bytes());
+        let signatures_start = 12 + target_len + 12 + 6 + 8;
+        assert_eq!(
+            &parameter[signatures_start - 8..signatures_start],
+            &5u64.to_le_bytes()
+        );
+        assert_eq!(
+            &parameter[signatures_start..signatures_start + 4],
+            &1u32.to_le_bytes()
+        );
+    }
+}
@@ -26,4 +26,9 @@ pub enum WalletError {
     MassaCipherError(#[from] massa_cipher::CipherError),
     /// Version error: {0}
     VersionError(String),
+    /// Ledger error: {0}
+    #[cfg(feature = "ledger")]
+    LedgerError(String),
+    /// Mnemonic error: {0}
+    MnemonicError(String),
 }
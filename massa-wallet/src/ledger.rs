@@ -0,0 +1,266 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Signing with a Ledger device running the Massa application.
+//!
+//! The secret keys never leave the device: the wallet only gets the public keys of the accounts,
+//! derived along `m/44'/632'/account'/0'/0'`, and sends the operations to sign, whose details the
+//! device displays for the user to approve.
+//!
+//! The device is reached through the Linux `hidraw` interface. The APDUs exchanged with the
+//! application are split into 64-byte HID reports, each one starting with the channel, the tag
+//! and the sequence number of the report, as for any Ledger application.
+//!
+//! The instructions below are not taken from a published specification of the APDUs of the Massa
+//! application, and the transport only exists on Linux: this module is only built with the
+//! `ledger` feature, off by default, until that specification exists and is followed here.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::PathBuf;
+
+use massa_models::address::Address;
+use massa_models::error::ModelsError;
+use massa_models::operation::{Operation, OperationId, OperationSerializer, SecureShareOperation};
+use massa_models::secure_share::{Id, SecureShare, SecureShareContent};
+use massa_serialization::Serializer;
+use massa_signature::{PublicKey, Signature};
+
+use crate::mnemonic::{HARDENED, MASSA_COIN_TYPE};
+use crate::WalletError;
+
+/// USB vendor id of the Ledger devices
+const LEDGER_VENDOR_ID: &str = "00002C97";
+const HID_REPORT_SIZE: usize = 64;
+const HID_CHANNEL: u16 = 0x0101;
+const HID_TAG_APDU: u8 = 0x05;
+
+const CLA: u8 = 0x57;
+const INS_GET_PUBLIC_KEY: u8 = 0x02;
+const INS_SIGN_OPERATION: u8 = 0x03;
+/// display the address on the device and wait for the user to confirm it
+const P1_CONFIRM: u8 = 0x01;
+/// the first chunk of a signing request, carrying the derivation path
+const P1_FIRST_CHUNK: u8 = 0x00;
+const P1_NEXT_CHUNK: u8 = 0x01;
+const P1_LAST_CHUNK: u8 = 0x02;
+/// maximum data size of an APDU
+const MAX_CHUNK_SIZE: usize = 250;
+
+const STATUS_OK: u16 = 0x9000;
+const STATUS_DENIED: u16 = 0x6985;
+
+/// Ledger device connected to the machine
+pub struct LedgerDevice {
+    hidraw: File,
+}
+
+impl LedgerDevice {
+    /// Open the first Ledger device found
+    pub fn open() -> Result<Self, WalletError> {
+        let path = find_hidraw()?
+            .ok_or_else(|| WalletError::LedgerError("no Ledger device found".to_string()))?;
+        let hidraw = OpenOptions::new().read(true).write(true).open(path)?;
+        Ok(LedgerDevice { hidraw })
+    }
+
+    /// Public key of the account of index `account`.
+    /// If `confirm` is set, the address is displayed on the device for the user to check it.
+    pub fn get_public_key(
+        &mut self,
+        account: u32,
+        confirm: bool,
+    ) -> Result<PublicKey, WalletError> {
+        let p1 = if confirm { P1_CONFIRM } else { 0 };
+        let response = self.exchange(&apdu(INS_GET_PUBLIC_KEY, p1, &derivation_path(account)))?;
+        // the device sends the raw Ed25519 key, without the version of the key
+        Ok(PublicKey::from_bytes(
+            &[&[0u8], response.as_slice()].concat(),
+        )?)
+    }
+
+    /// Sign `content` with the key of the account of index `account`, once the user approved it
+    /// on the device
+    pub fn sign_operation(
+        &mut self,
+        account: u32,
+        content: Operation,
+        chain_id: u64,
+    ) -> Result<SecureShareOperation, WalletError> {
+        let public_key = self.get_public_key(account, false)?;
+        let mut serialized_data = Vec::new();
+        OperationSerializer::new()
+            .serialize(&content, &mut serialized_data)
+            .map_err(ModelsError::from)?;
+
+        self.exchange(&apdu(
+            INS_SIGN_OPERATION,
+            P1_FIRST_CHUNK,
+            &derivation_path(account),
+        ))?;
+        let payload = [chain_id.to_be_bytes().as_slice(), &serialized_data].concat();
+        let chunk_count = payload.chunks(MAX_CHUNK_SIZE).count();
+        let mut response = Vec::new();
+        for (index, chunk) in payload.chunks(MAX_CHUNK_SIZE).enumerate() {
+            let p1 = if index + 1 == chunk_count {
+                P1_LAST_CHUNK
+            } else {
+                P1_NEXT_CHUNK
+            };
+            response = self.exchange(&apdu(INS_SIGN_OPERATION, p1, chunk))?;
+        }
+        // the device sends the raw Ed25519 signature, without the version of the signature
+        let signature = Signature::from_bytes(&[&[0u8], response.as_slice()].concat())?;
+
+        let hash = content.compute_hash(&serialized_data, &public_key, chain_id);
+        content.verify_signature(&public_key, &hash, &signature)?;
+        Ok(SecureShare {
+            signature,
+            content_creator_pub_key: public_key,
+            content_creator_address: Address::from_public_key(&public_key),
+            content,
+            serialized_data,
+            id: OperationId::new(hash),
+        })
+    }
+
+    /// Send an APDU to the application, and return the data of its response
+    fn exchange(&mut self, apdu: &[u8]) -> Result<Vec<u8>, WalletError> {
+        for report in wrap_apdu(apdu) {
+            // the report id of the device is 0
+            self.hidraw
+                .write_all(&[&[0u8], report.as_slice()].concat())?;
+        }
+        let mut reports = Vec::new();
+        let response = loop {
+            let mut report = [0u8; HID_REPORT_SIZE];
+            self.hidraw.read_exact(&mut report)?;
+            reports.push(report);
+            if let Some(response) = unwrap_response(&reports)? {
+                break response;
+            }
+        };
+
+        let Some(split) = response.len().checked_sub(2) else {
+            return Err(WalletError::LedgerError("response too short".to_string()));
+        };
+        let (data, status) = response.split_at(split);
+        match u16::from_be_bytes([status[0], status[1]]) {
+            STATUS_OK => Ok(data.to_vec()),
+            STATUS_DENIED => Err(WalletError::LedgerError(
+                "rejected on the device".to_string(),
+            )),
+            status => Err(WalletError::LedgerError(format!(
+                "error 0x{:04x}, check that the Massa application is open on the device",
+                status
+            ))),
+        }
+    }
+}
+
+/// Path of the `hidraw` interface of the first Ledger device found
+fn find_hidraw() -> Result<Option<PathBuf>, WalletError> {
+    let Ok(entries) = fs::read_dir("/sys/class/hidraw") else {
+        return Ok(None);
+    };
+    for entry in entries {
+        let entry = entry?;
+        let Ok(uevent) = fs::read_to_string(entry.path().join("device/uevent")) else {
+            continue;
+        };
+        // the first USB interface of the device is the one of the applications
+        let is_ledger = uevent.lines().any(|line| {
+            line.starts_with("HID_ID=") && line.to_uppercase().contains(LEDGER_VENDOR_ID)
+        });
+        let is_first_interface = uevent
+            .lines()
+            .any(|line| line.starts_with("HID_PHYS=") && line.ends_with("input0"));
+        if is_ledger && is_first_interface {
+            return Ok(Some(PathBuf::from("/dev").join(entry.file_name())));
+        }
+    }
+    Ok(None)
+}
+
+/// BIP-32 path of the account of index `account`, prefixed with the number of its components
+fn derivation_path(account: u32) -> Vec<u8> {
+    let components = [44, MASSA_COIN_TYPE, account, 0, 0];
+    let mut path = vec![components.len() as u8];
+    for component in components {
+        path.extend((component | HARDENED).to_be_bytes());
+    }
+    path
+}
+
+/// APDU of the Massa application
+fn apdu(ins: u8, p1: u8, data: &[u8]) -> Vec<u8> {
+    let mut apdu = vec![CLA, ins, p1, 0, data.len() as u8];
+    apdu.extend(data);
+    apdu
+}
+
+/// Split an APDU into HID reports. The first report also carries the length of the APDU.
+fn wrap_apdu(apdu: &[u8]) -> Vec<[u8; HID_REPORT_SIZE]> {
+    let data = [(apdu.len() as u16).to_be_bytes().as_slice(), apdu].concat();
+    data.chunks(HID_REPORT_SIZE - 5)
+        .enumerate()
+        .map(|(sequence, chunk)| {
+            let mut report = [0u8; HID_REPORT_SIZE];
+            report[..2].copy_from_slice(&HID_CHANNEL.to_be_bytes());
+            report[2] = HID_TAG_APDU;
+            report[3..5].copy_from_slice(&(sequence as u16).to_be_bytes());
+            report[5..5 + chunk.len()].copy_from_slice(chunk);
+            report
+        })
+        .collect()
+}
+
+/// Reassemble the response carried by the HID reports received so far, if they are complete
+fn unwrap_response(reports: &[[u8; HID_REPORT_SIZE]]) -> Result<Option<Vec<u8>>, WalletError> {
+    let mut data = Vec::new();
+    for (sequence, report) in reports.iter().enumerate() {
+        if report[..2] != HID_CHANNEL.to_be_bytes()
+            || report[2] != HID_TAG_APDU
+            || report[3..5] != (sequence as u16).to_be_bytes()
+        {
+            return Err(WalletError::LedgerError("invalid HID report".to_string()));
+        }
+        data.extend(&report[5..]);
+    }
+    let len = u16::from_be_bytes([data[0], data[1]]) as usize;
+    if data.len() < len + 2 {
+        return Ok(None);
+    }
+    Ok(Some(data[2..len + 2].to_vec()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hid_framing() {
+        let apdu = apdu(INS_SIGN_OPERATION, P1_NEXT_CHUNK, &[7u8; 100]);
+        let reports = wrap_apdu(&apdu);
+        assert_eq!(reports.len(), 2);
+        assert_eq!(&reports[0][..7], &[0x01, 0x01, 0x05, 0, 0, 0, 105]);
+        assert_eq!(
+            &reports[0][7..12],
+            &[CLA, INS_SIGN_OPERATION, P1_NEXT_CHUNK, 0, 100]
+        );
+        assert_eq!(&reports[1][..5], &[0x01, 0x01, 0x05, 0, 1]);
+
+        assert_eq!(unwrap_response(&reports[..1]).unwrap(), None);
+        assert_eq!(unwrap_response(&reports).unwrap(), Some(apdu));
+        assert!(unwrap_response(&reports[1..]).is_err());
+    }
+
+    #[test]
+    fn test_derivation_path() {
+        let path = derivation_path(1);
+        assert_eq!(path.len(), 21);
+        assert_eq!(path[0], 5);
+        assert_eq!(&path[1..5], &[0x80, 0, 0, 44]);
+        assert_eq!(&path[5..9], &[0x80, 0, 0x02, 0x78]);
+        assert_eq!(&path[9..13], &[0x80, 0, 0, 1]);
+    }
+}
@@ -6,7 +6,7 @@
 
 pub use error::WalletError;
 
-use massa_cipher::{decrypt, encrypt, CipherData, Salt};
+use massa_cipher::{decrypt, encrypt, Argon2Params, CipherData, Kdf, Salt};
 use massa_hash::Hash;
 use massa_models::address::Address;
 use massa_models::composite::PubkeySig;
@@ -16,14 +16,27 @@ use massa_models::secure_share::SecureShareContent;
 use massa_signature::{KeyPair, PublicKey};
 use serde::{Deserialize, Serialize};
 use std::collections::hash_map::Entry;
-use std::collections::HashSet;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
 mod error;
+#[cfg(feature = "ledger")]
+mod ledger;
+mod migration;
+mod mnemonic;
 
-const WALLET_VERSION: u64 = 1;
+#[cfg(feature = "ledger")]
+pub use ledger::LedgerDevice;
+pub use mnemonic::{Mnemonic, MNEMONIC_WORD_COUNT};
+
+/// Version of the format of the wallet files, see the `migration` module for the previous ones
+const WALLET_VERSION: u64 = 2;
+/// Prefix of the files of the watch-only addresses
+const WATCH_ONLY_FILE_PREFIX: &str = "watch_";
+/// File of the staking key changes waiting to be applied
+const SCHEDULED_KEY_CHANGES_FILE: &str = "scheduled_key_changes.yaml";
 
 /// Contains the keypairs created in the wallet.
 #[derive(Clone, Debug, Deserialize, Serialize, Zeroize, ZeroizeOnDrop)]
@@ -31,6 +44,9 @@ pub struct Wallet {
     /// Keypairs and addresses
     #[zeroize(skip)]
     pub keys: PreHashMap<Address, KeyPair>,
+    /// Public keys of the addresses watched without their secret key
+    #[zeroize(skip)]
+    pub watch_only: PreHashMap<Address, PublicKey>,
     /// Path to the file containing the keypairs (encrypted)
     #[zeroize(skip)]
     wallet_path: PathBuf,
@@ -38,6 +54,48 @@ pub struct Wallet {
     password: String,
     /// chain id
     chain_id: u64,
+    /// Staking key changes waiting to be applied
+    #[serde(skip)]
+    scheduled_key_changes: ScheduledKeyChanges,
+    /// Original content of the files of a previous version, written back until their migration
+    #[zeroize(skip)]
+    #[serde(skip)]
+    outdated_files: PreHashMap<Address, Vec<u8>>,
+}
+
+/// A change of the staking key set, applied atomically at the beginning of a cycle
+#[derive(Clone, Default)]
+pub struct ScheduledKeyChange {
+    /// Keypairs to start staking with
+    pub keys_to_add: Vec<KeyPair>,
+    /// Addresses to stop staking with
+    pub addresses_to_remove: Vec<Address>,
+}
+
+impl std::fmt::Debug for ScheduledKeyChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // the keypairs are shown by their address, to keep their secret key out of the logs
+        let addresses_to_add: Vec<Address> = self
+            .keys_to_add
+            .iter()
+            .map(|key| Address::from_public_key(&key.get_public_key()))
+            .collect();
+        f.debug_struct("ScheduledKeyChange")
+            .field("addresses_to_add", &addresses_to_add)
+            .field("addresses_to_remove", &self.addresses_to_remove)
+            .finish()
+    }
+}
+
+/// Staking key changes indexed by the cycle at which they take effect.
+/// Zeroizing them drops their keypairs, whose secret keys are zeroized on drop.
+#[derive(Clone, Debug, Default)]
+struct ScheduledKeyChanges(BTreeMap<u64, ScheduledKeyChange>);
+
+impl Zeroize for ScheduledKeyChanges {
+    fn zeroize(&mut self) {
+        self.0.clear();
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -51,6 +109,68 @@ struct WalletFileFormat {
     nonce: [u8; 12],
     ciphered_data: Vec<u8>,
     public_key: Vec<u8>,
+    /// parameters of the key derivation, from version 2
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    kdf_params: Option<KdfParams>,
+}
+
+/// Staking key changes waiting to be applied, whose keypairs are encrypted as in the wallet files
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct ScheduledKeyChangesFileFormat {
+    version: u64,
+    changes: Vec<ScheduledKeyChangeFileFormat>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct ScheduledKeyChangeFileFormat {
+    cycle: u64,
+    keys_to_add: Vec<WalletFileFormat>,
+    addresses_to_remove: Vec<String>,
+}
+
+/// `Argon2id` parameters of a wallet file
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct KdfParams {
+    memory_cost: u32,
+    time_cost: u32,
+    parallelism: u32,
+}
+
+impl KdfParams {
+    /// Parameters recorded for the key derivation function `kdf`, `None` for `PBKDF2`
+    fn from_kdf(kdf: Kdf) -> Option<Self> {
+        match kdf {
+            Kdf::Argon2id(params) => Some(KdfParams {
+                memory_cost: params.memory_cost,
+                time_cost: params.time_cost,
+                parallelism: params.parallelism,
+            }),
+            Kdf::Pbkdf2 => None,
+        }
+    }
+
+    /// Key derivation function using these parameters
+    fn to_kdf(self) -> Kdf {
+        Kdf::Argon2id(Argon2Params {
+            memory_cost: self.memory_cost,
+            time_cost: self.time_cost,
+            parallelism: self.parallelism,
+        })
+    }
+}
+
+/// Address watched without its secret key, to follow its balance, rolls and deferred credits.
+/// The file does not need the password of the wallet.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct WatchOnlyFileFormat {
+    version: u64,
+    nickname: String,
+    address: String,
+    public_key: Vec<u8>,
 }
 
 //TODO: Use exports and mock it
@@ -59,66 +179,65 @@ impl Wallet {
     pub fn new(path: PathBuf, password: String, chain_id: u64) -> Result<Wallet, WalletError> {
         if path.is_dir() {
             let mut keys = PreHashMap::default();
+            let mut watch_only = PreHashMap::default();
+            let mut outdated_files = PreHashMap::default();
+            let mut scheduled_key_changes = ScheduledKeyChanges::default();
             for entry in std::fs::read_dir(&path)? {
                 let entry = entry?;
                 let path = entry.path();
                 if path.is_file() {
                     let content = &std::fs::read(&path)?[..];
-                    let mut wallet = serde_yaml::from_slice::<WalletFileFormat>(content)?;
-                    if wallet.version == 0 {
-                        // fix bug in handling version 0
-                        wallet.version = 1;
-                    }
-                    // check version
-                    if wallet.version != WALLET_VERSION {
-                        return Err(WalletError::VersionError(format!(
-                            "Unsupported wallet version {}",
-                            wallet.version
-                        )));
+                    if entry.file_name() == SCHEDULED_KEY_CHANGES_FILE {
+                        scheduled_key_changes = read_scheduled_key_changes(content, &password)?;
+                        continue;
                     }
-                    let mut secret_key = decrypt(
-                        &password,
-                        CipherData {
-                            salt: wallet.salt,
-                            nonce: wallet.nonce,
-                            encrypted_bytes: wallet.ciphered_data,
-                        },
-                    )?;
-                    // check secret key length
-                    match secret_key.len() {
-                        33 => {
-                            // standard compliant: version(1B) + privkey(32B)
-                        },
-                        65 => {
-                            // version(1B) + privkey(32B) + pubkey(32B)
-                            // truncate to standard compliant: version(1B) + privkey(32B)
-                            secret_key.truncate(33);
-                        },
-                        32 | 64 if wallet.version == 0 => {
-                            return Err(WalletError::VersionError("Your wallet is from an old version that does not follow the standard. Please create a new wallet.".to_string()))
-                        }
-                        _ => {
-                            return Err(WalletError::VersionError("Invalid wallet/version matching: your wallet does not follow its version's secret key encoding format.".to_string()))
+                    if entry
+                        .file_name()
+                        .to_string_lossy()
+                        .starts_with(WATCH_ONLY_FILE_PREFIX)
+                    {
+                        let watched = serde_yaml::from_slice::<WatchOnlyFileFormat>(content)?;
+                        if !(1..=WALLET_VERSION).contains(&watched.version) {
+                            return Err(WalletError::VersionError(format!(
+                                "Unsupported wallet version {}",
+                                watched.version
+                            )));
                         }
+                        watch_only.insert(
+                            Address::from_str(&watched.address)?,
+                            PublicKey::from_bytes(&watched.public_key)?,
+                        );
+                        continue;
                     }
-                    keys.insert(
-                        Address::from_str(&wallet.address)?,
-                        KeyPair::from_bytes(&secret_key)?,
-                    );
+                    // check version, and upgrade the files of a previous version
+                    let version = migration::read_version(content)?;
+                    let wallet =
+                        migration::upgrade(serde_yaml::from_slice::<WalletFileFormat>(content)?)?;
+                    let address = Address::from_str(&wallet.address)?;
+                    if version < WALLET_VERSION {
+                        outdated_files.insert(address, content.to_vec());
+                    }
+                    keys.insert(address, decrypt_keypair(wallet, &password, version)?);
                 }
             }
             Ok(Wallet {
                 keys,
+                watch_only,
                 wallet_path: path,
                 password,
                 chain_id,
+                scheduled_key_changes,
+                outdated_files,
             })
         } else {
             let wallet = Wallet {
                 keys: PreHashMap::default(),
+                watch_only: PreHashMap::default(),
                 wallet_path: path,
                 password,
                 chain_id,
+                scheduled_key_changes: ScheduledKeyChanges::default(),
+                outdated_files: PreHashMap::default(),
             };
             wallet.save()?;
             Ok(wallet)
@@ -144,6 +263,7 @@ impl Wallet {
     }
 
     /// Adds a list of keypairs to the wallet, returns their addresses.
+    /// The addresses are not watch-only anymore.
     /// The wallet file is updated.
     pub fn add_keypairs(&mut self, keys: Vec<KeyPair>) -> Result<Vec<Address>, WalletError> {
         let mut changed = false;
@@ -152,6 +272,7 @@ impl Wallet {
             let addr = Address::from_public_key(&key.get_public_key());
             if let Entry::Vacant(e) = self.keys.entry(addr) {
                 e.insert(key);
+                self.watch_only.remove(&addr);
                 changed = true;
             }
             addrs.push(addr);
@@ -162,7 +283,33 @@ impl Wallet {
         Ok(addrs)
     }
 
-    /// Removes wallet entries given a list of addresses. Missing entries are ignored.
+    /// Adds a list of public keys to the wallet, to watch their addresses without their secret
+    /// key, returns their addresses. The addresses whose keypair is in the wallet are ignored.
+    /// The wallet file is updated.
+    pub fn add_watch_only(
+        &mut self,
+        public_keys: Vec<PublicKey>,
+    ) -> Result<Vec<Address>, WalletError> {
+        let mut changed = false;
+        let mut addrs = Vec::with_capacity(public_keys.len());
+        for public_key in public_keys {
+            let addr = Address::from_public_key(&public_key);
+            if !self.keys.contains_key(&addr) {
+                if let Entry::Vacant(e) = self.watch_only.entry(addr) {
+                    e.insert(public_key);
+                    changed = true;
+                }
+            }
+            addrs.push(addr);
+        }
+        if changed {
+            self.save()?;
+        }
+        Ok(addrs)
+    }
+
+    /// Removes wallet entries given a list of addresses, including the watch-only ones.
+    /// Missing entries are ignored.
     /// call save() to persist the changes on disk.
     pub fn remove_addresses(&mut self, addresses: &Vec<Address>) -> Result<bool, WalletError> {
         let mut changed = false;
@@ -170,34 +317,118 @@ impl Wallet {
             if self.keys.remove(address).is_some() {
                 changed = true;
             }
+            if self.watch_only.remove(address).is_some() {
+                changed = true;
+            }
+            self.outdated_files.remove(address);
         }
         Ok(changed)
     }
 
+    /// Schedules keypairs to be added and addresses to be removed at the beginning of `cycle`.
+    /// Changes scheduled for the same cycle are merged.
+    /// The wallet file is updated, so that the changes are kept across restarts.
+    /// Returns the addresses of the keypairs that will be added.
+    pub fn schedule_key_change(
+        &mut self,
+        cycle: u64,
+        keys_to_add: Vec<KeyPair>,
+        addresses_to_remove: Vec<Address>,
+    ) -> Result<Vec<Address>, WalletError> {
+        let added = keys_to_add
+            .iter()
+            .map(|key| Address::from_public_key(&key.get_public_key()))
+            .collect();
+        let change = self.scheduled_key_changes.0.entry(cycle).or_default();
+        change.keys_to_add.extend(keys_to_add);
+        change.addresses_to_remove.extend(addresses_to_remove);
+        self.save()?;
+        Ok(added)
+    }
+
+    /// Get the staking key changes that are not applied yet, indexed by cycle
+    pub fn get_scheduled_key_changes(&self) -> &BTreeMap<u64, ScheduledKeyChange> {
+        &self.scheduled_key_changes.0
+    }
+
+    /// Returns true if some key changes must be applied at or before `cycle`
+    pub fn has_key_changes_due(&self, cycle: u64) -> bool {
+        self.scheduled_key_changes
+            .0
+            .first_key_value()
+            .map_or(false, |(first_cycle, _)| *first_cycle <= cycle)
+    }
+
+    /// Applies all the key changes scheduled at or before `cycle`, in cycle order.
+    /// Within a change, removals are applied before additions so that a key can be swapped.
+    /// The wallet file is updated if some changes were due.
+    /// Returns true if the key set changed.
+    pub fn apply_scheduled_key_changes(&mut self, cycle: u64) -> Result<bool, WalletError> {
+        let remaining = self
+            .scheduled_key_changes
+            .0
+            .split_off(&cycle.saturating_add(1));
+        let due = std::mem::replace(&mut self.scheduled_key_changes.0, remaining);
+        if due.is_empty() {
+            return Ok(false);
+        }
+        let mut changed = false;
+        for (_, change) in due {
+            changed |= self.remove_addresses(&change.addresses_to_remove)?;
+            for key in change.keys_to_add {
+                let addr = Address::from_public_key(&key.get_public_key());
+                if let Entry::Vacant(e) = self.keys.entry(addr) {
+                    e.insert(key);
+                    changed = true;
+                }
+            }
+        }
+        self.save()?;
+        Ok(changed)
+    }
+
     /// Finds the keypair associated with given address
     pub fn find_associated_keypair(&self, address: &Address) -> Option<&KeyPair> {
         self.keys.get(address)
     }
 
-    /// Finds the public key associated with given address
+    /// Finds the public key associated with given address, including the watch-only ones
     pub fn find_associated_public_key(&self, address: &Address) -> Option<PublicKey> {
         self.keys
             .get(address)
             .map(|keypair| keypair.get_public_key())
+            .or_else(|| self.watch_only.get(address).copied())
     }
 
-    /// Get all addresses in the wallet
+    /// Get all addresses in the wallet, except the watch-only ones
     pub fn get_wallet_address_list(&self) -> PreHashSet<Address> {
         self.keys.keys().copied().collect()
     }
 
+    /// Get the watch-only addresses of the wallet
+    pub fn get_watch_only_address_list(&self) -> PreHashSet<Address> {
+        self.watch_only.keys().copied().collect()
+    }
+
     /// Save the wallets in a directory, each wallet in a yaml file.
+    /// The files of a previous version are written back as they are, until `migrate` is called.
     pub fn save(&self) -> Result<(), WalletError> {
+        self.save_to(&self.wallet_path, &self.password, false)
+    }
+
+    /// Save the wallets in the directory `wallet_path`, encrypted with `password`.
+    /// If `migrate` is false, the files of a previous version are written back as they are.
+    fn save_to(
+        &self,
+        wallet_path: &Path,
+        password: &str,
+        migrate: bool,
+    ) -> Result<(), WalletError> {
         let mut existing_keys: HashSet<PathBuf> = HashSet::new();
-        if !self.wallet_path.exists() {
-            std::fs::create_dir_all(&self.wallet_path)?;
+        if !wallet_path.exists() {
+            std::fs::create_dir_all(wallet_path)?;
         } else {
-            let read_dir = std::fs::read_dir(&self.wallet_path)?;
+            let read_dir = std::fs::read_dir(wallet_path)?;
             for path in read_dir {
                 existing_keys.insert(path?.path());
             }
@@ -205,22 +436,60 @@ impl Wallet {
         let mut persisted_keys: HashSet<PathBuf> = HashSet::new();
         // write the keys in the directory
         for (addr, keypair) in &self.keys {
-            let encrypted_secret = encrypt(&self.password, &keypair.to_bytes())?;
-            let file_formatted = WalletFileFormat {
+            let file_path = wallet_path.join(format!("wallet_{}.yaml", addr));
+            if let (false, Some(content)) = (migrate, self.outdated_files.get(addr)) {
+                std::fs::write(&file_path, content)?;
+                persisted_keys.insert(file_path);
+                continue;
+            }
+            let file_formatted = encrypt_keypair(keypair, password)?;
+            let ser_keys = serde_yaml::to_string(&file_formatted)?;
+
+            std::fs::write(&file_path, ser_keys)?;
+            persisted_keys.insert(file_path);
+        }
+        // write the watch-only addresses in the directory
+        for (addr, public_key) in &self.watch_only {
+            let file_formatted = WatchOnlyFileFormat {
                 version: WALLET_VERSION,
                 nickname: addr.to_string(),
                 address: addr.to_string(),
-                salt: encrypted_secret.salt,
-                nonce: encrypted_secret.nonce,
-                ciphered_data: encrypted_secret.encrypted_bytes,
-                public_key: keypair.get_public_key().to_bytes().to_vec(),
+                public_key: public_key.to_bytes(),
             };
             let ser_keys = serde_yaml::to_string(&file_formatted)?;
-            let file_path = self.wallet_path.join(format!("wallet_{}.yaml", addr));
+            let file_path = wallet_path.join(format!("{}{}.yaml", WATCH_ONLY_FILE_PREFIX, addr));
 
             std::fs::write(&file_path, ser_keys)?;
             persisted_keys.insert(file_path);
         }
+        // write the staking key changes waiting to be applied
+        if !self.scheduled_key_changes.0.is_empty() {
+            let mut changes = Vec::with_capacity(self.scheduled_key_changes.0.len());
+            for (cycle, change) in &self.scheduled_key_changes.0 {
+                changes.push(ScheduledKeyChangeFileFormat {
+                    cycle: *cycle,
+                    keys_to_add: change
+                        .keys_to_add
+                        .iter()
+                        .map(|keypair| encrypt_keypair(keypair, password))
+                        .collect::<Result<_, _>>()?,
+                    addresses_to_remove: change
+                        .addresses_to_remove
+                        .iter()
+                        .map(|address| address.to_string())
+                        .collect(),
+                });
+            }
+            let file_formatted = ScheduledKeyChangesFileFormat {
+                version: WALLET_VERSION,
+                changes,
+            };
+            let ser_changes = serde_yaml::to_string(&file_formatted)?;
+            let file_path = wallet_path.join(SCHEDULED_KEY_CHANGES_FILE);
+
+            std::fs::write(&file_path, ser_changes)?;
+            persisted_keys.insert(file_path);
+        }
 
         let to_remove = existing_keys.difference(&persisted_keys);
         for path in to_remove {
@@ -230,6 +499,54 @@ impl Wallet {
         Ok(())
     }
 
+    /// Addresses whose file is of a previous version of the format
+    pub fn get_outdated_address_list(&self) -> PreHashSet<Address> {
+        self.outdated_files.keys().copied().collect()
+    }
+
+    /// Write the files of a previous version in the current version of the format, returning the
+    /// path of the backup of the previous wallet directory, as `change_password`.
+    pub fn migrate(&mut self) -> Result<PathBuf, WalletError> {
+        let mut password = self.password.clone();
+        let backup_path = self.rewrite(&password);
+        password.zeroize();
+        backup_path
+    }
+
+    /// Re-encrypt the wallet with `new_password`, returning the path of the backup of the previous
+    /// wallet directory. The files of a previous version are migrated.
+    pub fn change_password(&mut self, new_password: String) -> Result<PathBuf, WalletError> {
+        let backup_path = self.rewrite(&new_password)?;
+        self.password.zeroize();
+        self.password = new_password;
+        Ok(backup_path)
+    }
+
+    /// Write all the files of the wallet in the current version, encrypted with `password`.
+    ///
+    /// The wallet is written in a new directory next to the wallet directory, which then replaces
+    /// it by renaming: an interruption leaves either the previous or the new wallet in place.
+    /// The previous directory is kept with the `.bak` suffix, replacing the previous backup.
+    fn rewrite(&mut self, password: &str) -> Result<PathBuf, WalletError> {
+        let new_path = sibling_path(&self.wallet_path, ".new");
+        let backup_path = sibling_path(&self.wallet_path, ".bak");
+        if new_path.exists() {
+            std::fs::remove_dir_all(&new_path)?;
+        }
+        self.save_to(&new_path, password, true)?;
+
+        if backup_path.exists() {
+            std::fs::remove_dir_all(&backup_path)?;
+        }
+        std::fs::rename(&self.wallet_path, &backup_path)?;
+        if let Err(e) = std::fs::rename(&new_path, &self.wallet_path) {
+            std::fs::rename(&backup_path, &self.wallet_path)?;
+            return Err(e.into());
+        }
+        self.outdated_files.clear();
+        Ok(backup_path)
+    }
+
     /// Export keys and addresses
     pub fn get_full_wallet(&self) -> &PreHashMap<Address, KeyPair> {
         &self.keys
@@ -254,6 +571,110 @@ impl Wallet {
     }
 }
 
+/// Encrypt a keypair with `password` in a wallet file of the current version of the format
+fn encrypt_keypair(keypair: &KeyPair, password: &str) -> Result<WalletFileFormat, WalletError> {
+    let address = Address::from_public_key(&keypair.get_public_key());
+    let encrypted_secret = encrypt(password, &keypair.to_bytes())?;
+    Ok(WalletFileFormat {
+        version: WALLET_VERSION,
+        nickname: address.to_string(),
+        address: address.to_string(),
+        salt: encrypted_secret.salt,
+        nonce: encrypted_secret.nonce,
+        ciphered_data: encrypted_secret.encrypted_bytes,
+        public_key: keypair.get_public_key().to_bytes().to_vec(),
+        kdf_params: KdfParams::from_kdf(encrypted_secret.kdf),
+    })
+}
+
+/// Decrypt the keypair of a wallet file upgraded to the current version of the format,
+/// `version` being the version the file was written in
+fn decrypt_keypair(
+    wallet: WalletFileFormat,
+    password: &str,
+    version: u64,
+) -> Result<KeyPair, WalletError> {
+    let kdf = match wallet.kdf_params {
+        Some(kdf_params) => kdf_params.to_kdf(),
+        // the files written before version 2 derive the key with PBKDF2
+        None if version < 2 => Kdf::Pbkdf2,
+        None => {
+            return Err(WalletError::VersionError(
+                "Missing key derivation parameters in the wallet".to_string(),
+            ))
+        }
+    };
+    let mut secret_key = decrypt(
+        password,
+        CipherData {
+            kdf,
+            salt: wallet.salt,
+            nonce: wallet.nonce,
+            encrypted_bytes: wallet.ciphered_data,
+        },
+    )?;
+    // check secret key length
+    match secret_key.len() {
+        33 => {
+            // standard compliant: version(1B) + privkey(32B)
+        },
+        65 => {
+            // version(1B) + privkey(32B) + pubkey(32B)
+            // truncate to standard compliant: version(1B) + privkey(32B)
+            secret_key.truncate(33);
+        },
+        32 | 64 if version == 0 => {
+            return Err(WalletError::VersionError("Your wallet is from an old version that does not follow the standard. Please create a new wallet.".to_string()))
+        }
+        _ => {
+            return Err(WalletError::VersionError("Invalid wallet/version matching: your wallet does not follow its version's secret key encoding format.".to_string()))
+        }
+    }
+    Ok(KeyPair::from_bytes(&secret_key)?)
+}
+
+/// Read the staking key changes waiting to be applied, decrypting their keypairs with `password`
+fn read_scheduled_key_changes(
+    content: &[u8],
+    password: &str,
+) -> Result<ScheduledKeyChanges, WalletError> {
+    let file_formatted = serde_yaml::from_slice::<ScheduledKeyChangesFileFormat>(content)?;
+    if file_formatted.version != WALLET_VERSION {
+        return Err(WalletError::VersionError(format!(
+            "Unsupported scheduled key changes version {}",
+            file_formatted.version
+        )));
+    }
+    let mut changes = BTreeMap::new();
+    for change in file_formatted.changes {
+        let keys_to_add = change
+            .keys_to_add
+            .into_iter()
+            .map(|key| decrypt_keypair(key, password, WALLET_VERSION))
+            .collect::<Result<_, _>>()?;
+        let addresses_to_remove = change
+            .addresses_to_remove
+            .iter()
+            .map(|address| Address::from_str(address))
+            .collect::<Result<_, _>>()?;
+        changes.insert(
+            change.cycle,
+            ScheduledKeyChange {
+                keys_to_add,
+                addresses_to_remove,
+            },
+        );
+    }
+    Ok(ScheduledKeyChanges(changes))
+}
+
+/// Path next to `path`, whose file name is the one of `path` followed by `suffix`
+fn sibling_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(suffix);
+    path.with_file_name(file_name)
+}
+
 impl std::fmt::Display for Wallet {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         writeln!(f)?;
@@ -262,6 +683,10 @@ impl std::fmt::Display for Wallet {
             writeln!(f, "Public key: {}", keypair.get_public_key())?;
             writeln!(f, "Address: {}", addr)?;
         }
+        for (addr, public_key) in &self.watch_only {
+            writeln!(f, "Watch-only public key: {}", public_key)?;
+            writeln!(f, "Address: {}", addr)?;
+        }
         Ok(())
     }
 }
@@ -269,3 +694,239 @@ impl std::fmt::Display for Wallet {
 /// Test utils
 #[cfg(feature = "test-exports")]
 pub mod test_exports;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_cipher::encrypt_with_kdf;
+    use massa_models::config::{PERIODS_PER_CYCLE, THREAD_COUNT};
+    use massa_models::slot::Slot;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_change_password() {
+        let folder = TempDir::new().unwrap();
+        let path = folder.path().join("wallet");
+        let mut wallet = Wallet::new(path.clone(), "old".to_string(), 77).unwrap();
+        let address = wallet
+            .add_keypairs(vec![KeyPair::generate(0).unwrap()])
+            .unwrap()[0];
+
+        let backup_path = wallet.change_password("new".to_string()).unwrap();
+        assert_eq!(backup_path, folder.path().join("wallet.bak"));
+        assert!(!folder.path().join("wallet.new").exists());
+        assert!(Wallet::new(path.clone(), "old".to_string(), 77).is_err());
+        let reloaded = Wallet::new(path, "new".to_string(), 77).unwrap();
+        assert!(reloaded.keys.contains_key(&address));
+        let backup = Wallet::new(backup_path, "old".to_string(), 77).unwrap();
+        assert!(backup.keys.contains_key(&address));
+    }
+
+    #[test]
+    fn test_watch_only() {
+        let folder = TempDir::new().unwrap();
+        let path = folder.path().join("wallet");
+        let mut wallet = Wallet::new(path.clone(), "password".to_string(), 77).unwrap();
+        let owned_keypair = KeyPair::generate(0).unwrap();
+        let owned_address = wallet.add_keypairs(vec![owned_keypair.clone()]).unwrap()[0];
+        let watched_keypair = KeyPair::generate(0).unwrap();
+        let watched_public_key = watched_keypair.get_public_key();
+
+        // the address whose keypair is in the wallet is not watch-only
+        let addresses = wallet
+            .add_watch_only(vec![owned_keypair.get_public_key(), watched_public_key])
+            .unwrap();
+        let watched_address = addresses[1];
+        assert_eq!(addresses[0], owned_address);
+        assert_eq!(
+            wallet.get_watch_only_address_list(),
+            PreHashSet::from_iter([watched_address])
+        );
+        assert_eq!(
+            wallet.get_wallet_address_list(),
+            PreHashSet::from_iter([owned_address])
+        );
+        assert_eq!(
+            wallet.find_associated_public_key(&watched_address),
+            Some(watched_public_key)
+        );
+        assert!(wallet.find_associated_keypair(&watched_address).is_none());
+
+        // the watch-only addresses are saved in their own file
+        assert!(path
+            .join(format!(
+                "{}{}.yaml",
+                WATCH_ONLY_FILE_PREFIX, watched_address
+            ))
+            .is_file());
+        let reloaded = Wallet::new(path.clone(), "password".to_string(), 77).unwrap();
+        assert_eq!(
+            reloaded.get_watch_only_address_list(),
+            PreHashSet::from_iter([watched_address])
+        );
+
+        // importing the keypair of a watched address turns it into a regular entry
+        wallet.add_keypairs(vec![watched_keypair]).unwrap();
+        assert!(wallet.get_watch_only_address_list().is_empty());
+        let reloaded = Wallet::new(path.clone(), "password".to_string(), 77).unwrap();
+        assert!(reloaded.get_watch_only_address_list().is_empty());
+        assert!(reloaded.keys.contains_key(&watched_address));
+
+        // watch-only addresses can be removed
+        let other_address = wallet
+            .add_watch_only(vec![KeyPair::generate(0).unwrap().get_public_key()])
+            .unwrap()[0];
+        assert!(wallet.remove_addresses(&vec![other_address]).unwrap());
+        wallet.save().unwrap();
+        let reloaded = Wallet::new(path, "password".to_string(), 77).unwrap();
+        assert!(reloaded.get_watch_only_address_list().is_empty());
+    }
+
+    #[test]
+    fn test_load_pbkdf2_wallet() {
+        let folder = TempDir::new().unwrap();
+        let path = folder.path().join("wallet");
+        std::fs::create_dir(&path).unwrap();
+        let keypair = KeyPair::generate(0).unwrap();
+        let address = Address::from_public_key(&keypair.get_public_key());
+        let file_path = path.join(format!("wallet_{}.yaml", address));
+        let encrypted_secret =
+            encrypt_with_kdf("password", &keypair.to_bytes(), Kdf::Pbkdf2).unwrap();
+        let file_formatted = WalletFileFormat {
+            version: 1,
+            nickname: address.to_string(),
+            address: address.to_string(),
+            salt: encrypted_secret.salt,
+            nonce: encrypted_secret.nonce,
+            ciphered_data: encrypted_secret.encrypted_bytes,
+            public_key: keypair.get_public_key().to_bytes().to_vec(),
+            kdf_params: None,
+        };
+        std::fs::write(&file_path, serde_yaml::to_string(&file_formatted).unwrap()).unwrap();
+
+        // the upgrade does not re-encrypt the secret key
+        let upgraded = migration::upgrade(file_formatted.clone()).unwrap();
+        assert_eq!(upgraded.version, WALLET_VERSION);
+        assert_eq!(upgraded.ciphered_data, file_formatted.ciphered_data);
+        assert!(upgraded.kdf_params.is_none());
+
+        let mut wallet = Wallet::new(path.clone(), "password".to_string(), 77).unwrap();
+        assert!(wallet.keys.contains_key(&address));
+        assert!(wallet.get_outdated_address_list().contains(&address));
+
+        // the file is written back as it is until the migration
+        wallet
+            .add_keypairs(vec![KeyPair::generate(0).unwrap()])
+            .unwrap();
+        let content = std::fs::read(&file_path).unwrap();
+        assert_eq!(migration::read_version(&content).unwrap(), 1);
+
+        wallet.migrate().unwrap();
+        assert!(wallet.get_outdated_address_list().is_empty());
+        let content = std::fs::read(&file_path).unwrap();
+        let file_formatted = serde_yaml::from_slice::<WalletFileFormat>(&content).unwrap();
+        assert_eq!(file_formatted.version, WALLET_VERSION);
+        assert!(file_formatted.kdf_params.is_some());
+        let reloaded = Wallet::new(path, "password".to_string(), 77).unwrap();
+        assert!(reloaded.keys.contains_key(&address));
+        assert!(reloaded.get_outdated_address_list().is_empty());
+    }
+
+    #[test]
+    fn test_schedule_key_change() {
+        let folder = TempDir::new().unwrap();
+        let path = folder.path().join("wallet");
+        let mut wallet = Wallet::new(path.clone(), "password".to_string(), 77).unwrap();
+        let old_address = wallet
+            .add_keypairs(vec![KeyPair::generate(0).unwrap()])
+            .unwrap()[0];
+        let new_keypair = KeyPair::generate(0).unwrap();
+        let new_address = Address::from_public_key(&new_keypair.get_public_key());
+
+        let added = wallet
+            .schedule_key_change(5, vec![new_keypair.clone()], Vec::new())
+            .unwrap();
+        assert_eq!(added, vec![new_address]);
+        // the changes of the same cycle are merged, and none is applied yet
+        wallet
+            .schedule_key_change(5, Vec::new(), vec![old_address])
+            .unwrap();
+        assert_eq!(wallet.get_scheduled_key_changes().len(), 1);
+        assert!(wallet.keys.contains_key(&old_address));
+        assert!(!wallet.keys.contains_key(&new_address));
+
+        // the schedule is kept across restarts, with its keypairs encrypted
+        let content = std::fs::read_to_string(path.join(SCHEDULED_KEY_CHANGES_FILE)).unwrap();
+        assert!(!content.contains(&new_keypair.to_string()));
+        let reloaded = Wallet::new(path, "password".to_string(), 77).unwrap();
+        let change = &reloaded.get_scheduled_key_changes()[&5];
+        assert_eq!(change.keys_to_add[0].to_bytes(), new_keypair.to_bytes());
+        assert_eq!(change.addresses_to_remove, vec![old_address]);
+        assert!(!reloaded.keys.contains_key(&new_address));
+
+        // the secret keys are not shown in the debug output
+        assert!(!format!("{:?}", change).contains(&new_keypair.to_string()));
+        assert!(format!("{:?}", change).contains(&new_address.to_string()));
+    }
+
+    #[test]
+    fn test_apply_scheduled_key_changes() {
+        let folder = TempDir::new().unwrap();
+        let path = folder.path().join("wallet");
+        let mut wallet = Wallet::new(path.clone(), "password".to_string(), 77).unwrap();
+        let old_address = wallet
+            .add_keypairs(vec![KeyPair::generate(0).unwrap()])
+            .unwrap()[0];
+        let new_keypair = KeyPair::generate(0).unwrap();
+        let new_address = Address::from_public_key(&new_keypair.get_public_key());
+        let later_keypair = KeyPair::generate(0).unwrap();
+        let later_address = Address::from_public_key(&later_keypair.get_public_key());
+        wallet
+            .schedule_key_change(3, vec![new_keypair], vec![old_address])
+            .unwrap();
+        wallet
+            .schedule_key_change(4, vec![later_keypair], Vec::new())
+            .unwrap();
+
+        // the factories apply the changes of the cycle of each slot they process:
+        // nothing is due at the last slot of the previous cycle
+        let last_slot = Slot::new(3 * PERIODS_PER_CYCLE - 1, THREAD_COUNT - 1);
+        let cycle = last_slot.get_cycle(PERIODS_PER_CYCLE);
+        assert!(!wallet.has_key_changes_due(cycle));
+        assert!(!wallet.apply_scheduled_key_changes(cycle).unwrap());
+        assert!(wallet.keys.contains_key(&old_address));
+
+        // the swap is applied at the first slot of its cycle, the later change is kept
+        let first_slot = Slot::new(3 * PERIODS_PER_CYCLE, 0);
+        let cycle = first_slot.get_cycle(PERIODS_PER_CYCLE);
+        assert!(wallet.has_key_changes_due(cycle));
+        assert!(wallet.apply_scheduled_key_changes(cycle).unwrap());
+        assert!(!wallet.keys.contains_key(&old_address));
+        assert!(wallet.keys.contains_key(&new_address));
+        assert!(!wallet.keys.contains_key(&later_address));
+        assert!(!wallet.has_key_changes_due(cycle));
+
+        // the applied changes are removed from the wallet files
+        let reloaded = Wallet::new(path.clone(), "password".to_string(), 77).unwrap();
+        assert!(reloaded.keys.contains_key(&new_address));
+        assert_eq!(
+            reloaded
+                .get_scheduled_key_changes()
+                .keys()
+                .collect::<Vec<_>>(),
+            vec![&4]
+        );
+
+        // a late factory applies all the changes that are due
+        assert!(wallet.apply_scheduled_key_changes(10).unwrap());
+        assert!(wallet.keys.contains_key(&later_address));
+        assert!(wallet.get_scheduled_key_changes().is_empty());
+        assert!(!path.join(SCHEDULED_KEY_CHANGES_FILE).exists());
+    }
+
+    #[test]
+    fn test_future_wallet_version() {
+        let content = format!("Version: {}\nNickname: future\n", WALLET_VERSION + 1);
+        assert!(migration::read_version(content.as_bytes()).is_err());
+    }
+}
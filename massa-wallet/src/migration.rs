@@ -0,0 +1,68 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Migration of the wallet files written in a previous version of their format.
+//!
+//! The `Version` header of a file is read before the rest of its content. A file of a previous
+//! version is upgraded in memory by the steps of [`MIGRATIONS`], one version at a time, so that
+//! the wallet only decodes files of the current version. The steps do not re-encrypt the secret
+//! keys: an upgraded file keeps the encryption of its original version, and its key is only
+//! encrypted in the current version when the file is written. The upgraded files are written only
+//! once the user confirms the migration: until then, the original files are written back as they
+//! are.
+//!
+//! A change of the format increments `WALLET_VERSION` and adds the step upgrading the files of the
+//! previous version.
+
+use serde::Deserialize;
+
+use crate::{WalletError, WalletFileFormat, WALLET_VERSION};
+
+/// Header of a wallet file, read before its content
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct VersionHeader {
+    version: u64,
+}
+
+/// Step upgrading a wallet file to the next version
+type Migration = fn(WalletFileFormat) -> Result<WalletFileFormat, WalletError>;
+
+/// Steps upgrading the wallet files, the step of index `i` upgrading the files of version `i`
+const MIGRATIONS: [Migration; WALLET_VERSION as usize] = [migrate_v0, migrate_v1];
+
+/// Read the version of a wallet file, failing if it is more recent than the current version
+pub(crate) fn read_version(content: &[u8]) -> Result<u64, WalletError> {
+    let version = serde_yaml::from_slice::<VersionHeader>(content)?.version;
+    if version > WALLET_VERSION {
+        return Err(WalletError::VersionError(format!(
+            "Unsupported wallet version {}, please update your client",
+            version
+        )));
+    }
+    Ok(version)
+}
+
+/// Upgrade a wallet file to the current version
+pub(crate) fn upgrade(mut file: WalletFileFormat) -> Result<WalletFileFormat, WalletError> {
+    while file.version < WALLET_VERSION {
+        file = MIGRATIONS[file.version as usize](file)?;
+    }
+    Ok(file)
+}
+
+/// Files of version 0 have the format of version 1, they were written with a wrong version
+fn migrate_v0(mut file: WalletFileFormat) -> Result<WalletFileFormat, WalletError> {
+    file.version = 1;
+    Ok(file)
+}
+
+/// Files of version 2 encrypt the secret key with a key derived by `Argon2id` instead of `PBKDF2`,
+/// and record the parameters of the derivation.
+///
+/// The secret key of an upgraded file stays encrypted with `PBKDF2`, as told by its missing
+/// derivation parameters: deriving an `Argon2id` key at each load would slow down the loading of
+/// the wallets that are not migrated yet, for a key that is only needed when the file is written.
+fn migrate_v1(mut file: WalletFileFormat) -> Result<WalletFileFormat, WalletError> {
+    file.version = 2;
+    Ok(file)
+}
@@ -0,0 +1,272 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! BIP-39 mnemonic phrases, to back up the keys of a wallet with 24 words.
+//!
+//! The seed of a phrase is derived into Ed25519 keys with SLIP-10, along the path
+//! `m/44'/632'/account'/0'/0'` of the Ledger application, so that a phrase restores the same
+//! accounts in the wallet and on a Ledger device.
+
+use hmac::{Hmac, Mac};
+use massa_signature::KeyPair;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha2::{Digest, Sha256, Sha512};
+use unicode_normalization::UnicodeNormalization;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::WalletError;
+
+/// BIP-39 English word list, one word per line
+const WORDLIST: &str = include_str!("bip39_english.txt");
+/// Number of words of a phrase
+pub const MNEMONIC_WORD_COUNT: usize = 24;
+/// Size of the entropy encoded by a phrase, in bytes
+const ENTROPY_SIZE: usize = 32;
+/// Number of bits encoded by a word
+const BITS_PER_WORD: usize = 11;
+/// PBKDF2 rounds of the derivation of the seed
+const PBKDF2_ROUNDS: u32 = 2048;
+/// SLIP-44 coin type of Massa
+pub(crate) const MASSA_COIN_TYPE: u32 = 632;
+/// Flag of the hardened derivation indexes
+pub(crate) const HARDENED: u32 = 0x8000_0000;
+
+/// BIP-39 mnemonic phrase
+#[derive(Zeroize, ZeroizeOnDrop)]
+pub struct Mnemonic {
+    phrase: String,
+}
+
+impl Mnemonic {
+    /// Generate a random phrase
+    pub fn generate() -> Self {
+        let mut entropy = [0u8; ENTROPY_SIZE];
+        OsRng.fill_bytes(&mut entropy);
+        let mnemonic = Mnemonic::from_entropy(&entropy);
+        entropy.zeroize();
+        mnemonic
+    }
+
+    /// Phrase encoding `entropy`, followed by the first byte of its SHA-256 as checksum
+    fn from_entropy(entropy: &[u8; ENTROPY_SIZE]) -> Self {
+        let words: Vec<&str> = WORDLIST.lines().collect();
+        let checksum = Sha256::digest(entropy)[0];
+        let mut bits = entropy
+            .iter()
+            .chain(std::iter::once(&checksum))
+            .flat_map(|byte| (0..8).rev().map(move |shift| (byte >> shift) & 1));
+        let phrase = (0..MNEMONIC_WORD_COUNT)
+            .map(|_| {
+                let index = (0..BITS_PER_WORD).fold(0usize, |index, _| {
+                    (index << 1) | bits.next().unwrap_or_default() as usize
+                });
+                words[index]
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        Mnemonic { phrase }
+    }
+
+    /// Parse a phrase, checking its words and its checksum
+    pub fn from_phrase(phrase: &str) -> Result<Self, WalletError> {
+        let words: Vec<&str> = WORDLIST.lines().collect();
+        let mut indices = Vec::with_capacity(MNEMONIC_WORD_COUNT);
+        for word in phrase.split_whitespace() {
+            let word = word.to_lowercase();
+            let Ok(index) = words.binary_search(&word.as_str()) else {
+                return Err(WalletError::MnemonicError(format!("unknown word {}", word)));
+            };
+            indices.push(index);
+        }
+        if indices.len() != MNEMONIC_WORD_COUNT {
+            return Err(WalletError::MnemonicError(format!(
+                "expected {} words, got {}",
+                MNEMONIC_WORD_COUNT,
+                indices.len()
+            )));
+        }
+
+        let mut entropy = [0u8; ENTROPY_SIZE + 1];
+        for (position, index) in indices.iter().enumerate() {
+            for bit in 0..BITS_PER_WORD {
+                if (index >> (BITS_PER_WORD - 1 - bit)) & 1 == 1 {
+                    let bit_position = position * BITS_PER_WORD + bit;
+                    entropy[bit_position / 8] |= 0x80 >> (bit_position % 8);
+                }
+            }
+        }
+        let valid = Sha256::digest(&entropy[..ENTROPY_SIZE])[0] == entropy[ENTROPY_SIZE];
+        entropy.zeroize();
+        indices.zeroize();
+        if !valid {
+            return Err(WalletError::MnemonicError("invalid checksum".to_string()));
+        }
+        Ok(Mnemonic {
+            phrase: phrase
+                .split_whitespace()
+                .map(str::to_lowercase)
+                .collect::<Vec<_>>()
+                .join(" "),
+        })
+    }
+
+    /// Words of the phrase, separated by spaces
+    pub fn phrase(&self) -> &str {
+        &self.phrase
+    }
+
+    /// BIP-39 seed of the phrase with an optional `passphrase`, both normalized to NFKD so that
+    /// a passphrase typed with composed or decomposed characters gives the same seed
+    fn seed(&self, passphrase: &str) -> [u8; 64] {
+        let mut phrase: String = self.phrase.nfkd().collect();
+        let mut salt: String = "mnemonic".chars().chain(passphrase.nfkd()).collect();
+        let mut seed = [0u8; 64];
+        pbkdf2::pbkdf2_hmac::<Sha512>(phrase.as_bytes(), salt.as_bytes(), PBKDF2_ROUNDS, &mut seed);
+        phrase.zeroize();
+        salt.zeroize();
+        seed
+    }
+
+    /// Keypair of the account of index `account`, derived from the seed of the phrase along
+    /// `m/44'/632'/account'/0'/0'`
+    pub fn derive_keypair(&self, passphrase: &str, account: u32) -> Result<KeyPair, WalletError> {
+        let mut seed = self.seed(passphrase);
+        let mut secret_key = slip10_derive(&seed, &[44, MASSA_COIN_TYPE, account, 0, 0]);
+        seed.zeroize();
+        // version byte of the keypair, followed by the secret key
+        let mut bytes = Vec::with_capacity(1 + secret_key.len());
+        bytes.push(0u8);
+        bytes.extend_from_slice(&secret_key);
+        secret_key.zeroize();
+        let keypair = KeyPair::from_bytes(&bytes);
+        bytes.zeroize();
+        Ok(keypair?)
+    }
+}
+
+/// SLIP-10 Ed25519 secret key of `seed` at `path`, whose indexes are all hardened
+fn slip10_derive(seed: &[u8], path: &[u32]) -> [u8; 32] {
+    let mut node = hmac_sha512(b"ed25519 seed", &[seed]);
+    for index in path {
+        let (key, chain_code) = node.split_at(32);
+        let mut child = hmac_sha512(
+            chain_code,
+            &[&[0u8], key, &(index | HARDENED).to_be_bytes()],
+        );
+        node.zeroize();
+        node = child;
+        child.zeroize();
+    }
+    let mut secret_key = [0u8; 32];
+    secret_key.copy_from_slice(&node[..32]);
+    node.zeroize();
+    secret_key
+}
+
+/// HMAC-SHA512 of the concatenation of `data`
+fn hmac_sha512(key: &[u8], data: &[&[u8]]) -> [u8; 64] {
+    let mut mac = Hmac::<Sha512>::new_from_slice(key).expect("HMAC accepts keys of any size");
+    for part in data {
+        mac.update(part);
+    }
+    let mut digest = [0u8; 64];
+    digest.copy_from_slice(&mac.finalize().into_bytes());
+    digest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    #[test]
+    fn test_bip39_vectors() {
+        // vectors of the reference implementation, with the passphrase "TREZOR"
+        let mnemonic = Mnemonic::from_entropy(&[0x00; ENTROPY_SIZE]);
+        assert_eq!(mnemonic.phrase(), format!("{}art", "abandon ".repeat(23)));
+        assert_eq!(
+            to_hex(&mnemonic.seed("TREZOR")),
+            "bda85446c68413707090a52022edd26a1c9462295029f2e60cd7c4f2bbd3097170af7a4d73245cafa9c3cc\
+             a8d561a7c3de6f5d4a10be8ed2a5e608d68f92fcc8"
+        );
+
+        let mnemonic = Mnemonic::from_entropy(&[0x7f; ENTROPY_SIZE]);
+        assert_eq!(
+            mnemonic.phrase(),
+            "legal winner thank year wave sausage worth useful legal winner thank year wave \
+             sausage worth useful legal winner thank year wave sausage worth title"
+        );
+        assert_eq!(
+            to_hex(&mnemonic.seed("TREZOR")),
+            "bc09fca1804f7e69da93c2f2028eb238c227f2e9dda30cd63699232578480a4021b146ad717fbb7e451ce9\
+             eb835f43620bf5c514db0f8add49f5d121449d3e87"
+        );
+
+        // the passphrase is normalized to NFKD, whether its characters are composed or not
+        let mnemonic = Mnemonic::from_entropy(&[0x00; ENTROPY_SIZE]);
+        for passphrase in ["n\u{f6}de\u{2460}", "no\u{308}de1"] {
+            assert_eq!(
+                to_hex(&mnemonic.seed(passphrase)),
+                "fa1f0b59872f67035cb8f3ee11068ac3c626ae5cb6077497fe3e29a5fcbb21ab59c45dabef14fd915dd5ab\
+                 a312f4843d4a3596e8c49ebbe00548721951696f0f"
+            );
+        }
+
+        let mnemonic = Mnemonic::from_entropy(&[0x7f; ENTROPY_SIZE]);
+        let parsed = Mnemonic::from_phrase(&mnemonic.phrase().to_uppercase()).unwrap();
+        assert_eq!(parsed.phrase(), mnemonic.phrase());
+        let generated = Mnemonic::generate();
+        assert!(Mnemonic::from_phrase(generated.phrase()).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_phrases() {
+        let checksum_error = format!("{}abandon", "abandon ".repeat(23));
+        assert!(Mnemonic::from_phrase(&checksum_error).is_err());
+        let unknown_word = format!("{}massa", "abandon ".repeat(23));
+        assert!(Mnemonic::from_phrase(&unknown_word).is_err());
+        assert!(Mnemonic::from_phrase("abandon abandon art").is_err());
+    }
+
+    #[test]
+    fn test_slip10_vectors() {
+        // test vector 1 of SLIP-10 for ed25519
+        let seed = [
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d,
+            0x0e, 0x0f,
+        ];
+        assert_eq!(
+            to_hex(&slip10_derive(&seed, &[])),
+            "2b4be7f19ee27bbf30c667b642d5f4aa69fd169872f8fc3059c08ebae2eb19e7"
+        );
+        assert_eq!(
+            to_hex(&slip10_derive(&seed, &[0])),
+            "68e0fe46dfb67e368c75379acec591dad19df3cde26e63b93a8e704f1dade7a3"
+        );
+        assert_eq!(
+            to_hex(&slip10_derive(&seed, &[0, 1, 2, 2, 1_000_000_000])),
+            "8f94d394a8e8fd6b1bc2f3f49f5c47e385281d5c17e65324b0f62483e37e8793"
+        );
+    }
+
+    #[test]
+    fn test_derive_accounts() {
+        let mnemonic = Mnemonic::from_phrase(&format!("{}art", "abandon ".repeat(23))).unwrap();
+        let first = mnemonic.derive_keypair("", 0).unwrap();
+        let second = mnemonic.derive_keypair("", 1).unwrap();
+        assert_eq!(
+            to_hex(&first.to_bytes()[1..]),
+            "176ac957d586f39f4f766793a9f992523355cdc1d79d83ad08bad5d82fd5966d"
+        );
+        assert_eq!(
+            to_hex(&second.to_bytes()[1..]),
+            "7f8182a30a833a450c9a8cdc87cf4a7673c0f131ac8b9118d436b8be18ff117d"
+        );
+        // the passphrase changes the accounts
+        let protected = mnemonic.derive_keypair("passphrase", 0).unwrap();
+        assert_ne!(protected.get_public_key(), first.get_public_key());
+    }
+}